@@ -217,9 +217,17 @@ consumerRouter.post(
 
       // Create consumer account and initialize ATAs
       const consumerKeypair = Keypair.generate();
-      await initializeOrFetchATAs(consumerKeypair.publicKey, req.tokens!);
-
-      // Register consumer
+      const ATAs = await initializeOrFetchATAs(
+        consumerKeypair.publicKey,
+        req.tokens!
+      );
+
+      // Register consumer, delegating its WTK and WST to the billing authority so
+      // payments can later be settled without its key
+      const [billingAuthority] = PublicKey.findProgramAddressSync(
+        [Buffer.from("billing_authority"), wallet.publicKey.toBuffer()],
+        program.programId
+      );
       await program.methods
         .registerConsumer(
           new PublicKey(tariff_key),
@@ -231,6 +239,9 @@ consumerRouter.post(
           consumer: consumerKeypair.publicKey,
           agency: wallet.publicKey,
           watcMint: req.tokens!.WATC,
+          billingAuthority,
+          consumerWtk: ATAs.WTK,
+          consumerWst: ATAs.WST,
         })
         .signers([consumerKeypair])
         .rpc();
@@ -630,6 +641,7 @@ consumerRouter.put(
         .accounts({
           consumer: consumerKey,
           agency: wallet.publicKey,
+          authority: wallet.publicKey,
         })
         .rpc();
 
@@ -744,6 +756,7 @@ consumerRouter.put(
         .accounts({
           consumer: consumerKey,
           agency: wallet.publicKey,
+          authority: wallet.publicKey,
         })
         .rpc();
 
@@ -357,34 +357,45 @@ reservoirRouter.put(
         });
       }
 
+      // If `capacity` is provided but not a number, exit
+      if (capacity && typeof capacity !== "number") {
+        return res.status(400).json({
+          error: "Capacity of reservoir must be a number",
+        });
+      }
+
+      const updateLevel = program.methods
+        .updateReservoirLevel(reservoirKey, new anchor.BN(current_level))
+        .accounts({
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        });
+
       // If `capacity` is not provided, do not change the capacity
       if (!capacity) {
+        await updateLevel.rpc();
+      } else {
         const reservoirPDA = await getReservoirPDA(reservoirKey);
         const reservoirAccount = await program.account.reservoir.fetch(
           reservoirPDA
         );
-        capacity = reservoirAccount.capacity.toNumber();
-      }
+        const updateConfig = await program.methods
+          .updateReservoirConfig(reservoirKey, new anchor.BN(capacity))
+          .accounts({
+            agency: wallet.publicKey,
+            authority: wallet.publicKey,
+          })
+          .instruction();
 
-      // If `capacity` is not a number, exit
-      if (typeof capacity !== "number") {
-        return res.status(400).json({
-          error: "Capacity of reservoir must be a number",
-        });
+        // The level must fit the capacity at each step, so a capacity shrinking
+        // below the recorded level is only applied after the new level
+        if (capacity >= reservoirAccount.currentLevel.toNumber()) {
+          await updateLevel.preInstructions([updateConfig]).rpc();
+        } else {
+          await updateLevel.postInstructions([updateConfig]).rpc();
+        }
       }
 
-      // Execute the relevant instructions based on provided fields
-      await program.methods
-        .updateReservoir(
-          reservoirKey,
-          new anchor.BN(current_level),
-          new anchor.BN(capacity)
-        )
-        .accounts({
-          agency: wallet.publicKey,
-        })
-        .rpc();
-
       // Send a success response
       res.status(200).json({
         message: "Reservoir updated successfully",
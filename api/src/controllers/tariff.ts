@@ -428,6 +428,7 @@ tariffRouter.put(
           )
           .accounts({
             agency: wallet.publicKey,
+            authority: wallet.publicKey,
           })
           .rpc();
       }
@@ -438,6 +439,7 @@ tariffRouter.put(
           .updateTariffType(tariffKey, convertedTariffType)
           .accounts({
             agency: wallet.publicKey,
+            authority: wallet.publicKey,
           })
           .rpc();
       }
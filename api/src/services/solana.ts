@@ -14,6 +14,10 @@ dotenv.config();
 
 const TOKEN_SEED = "tokens";
 
+// Billing units registered alongside the token mints
+const CURRENCY_DECIMALS = 9;
+const VOLUME_SCALE = 1000; // raw volume units per billed unit
+
 // Parse the wallet keypair from the environment variable
 const walletKeypair = anchor.web3.Keypair.fromSecretKey(
   Uint8Array.from(JSON.parse(process.env.WALLET_KEYPAIR!))
@@ -45,7 +49,13 @@ const getPDA = async (seed: String, key: PublicKey): Promise<PublicKey> => {
 
 // Helper function to create a new mint
 const createNewMint = async (): Promise<PublicKey> => {
-  return await createMint(connection, wallet.payer, wallet.publicKey, null, 9);
+  return await createMint(
+    connection,
+    wallet.payer,
+    wallet.publicKey,
+    null,
+    CURRENCY_DECIMALS
+  );
 };
 
 // Function to initialize or fetch tokens and return mint addresses
@@ -71,7 +81,15 @@ const InitOrFetchTokens = async (): Promise<TokenAccounts> => {
     const wasteTokenMint = await createNewMint();
 
     await program.methods
-      .initializeTokens(waterTokenMint, waterCapacityTokenMint, wasteTokenMint)
+      .initializeTokens(
+        waterTokenMint,
+        waterCapacityTokenMint,
+        wasteTokenMint,
+        {
+          volumeScale: new anchor.BN(VOLUME_SCALE),
+          currencyDecimals: CURRENCY_DECIMALS,
+        }
+      )
       .accounts({
         authority: wallet.publicKey,
       })
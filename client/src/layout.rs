@@ -0,0 +1,539 @@
+//! Account discriminators and field offsets for `getProgramAccounts` filters.
+//!
+//! Every account type of the program is re-exported here along with the [`Discriminator`]
+//! trait, so an indexer can match accounts of a type with `Consumer::DISCRIMINATOR` at
+//! offset zero. Each account type then has a module of the byte offsets of the fields
+//! it can be filtered on with a memcmp, such as [`consumer::ASSIGNED_TARIFF`].
+//!
+//! Offsets count the 8-byte discriminator and are only given for fields laid out before
+//! the first variable-size field (an `Option`, `Vec` or `String`), since the position of
+//! anything after it depends on the account's contents. New fields are appended to the
+//! end of an account, as pinned by the field order tests of the program's `grow_account`,
+//! so the offsets stay valid as the program grows.
+//!
+//! # Example
+//! ```ignore
+//! let filters = vec![
+//!     RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &Consumer::DISCRIMINATOR)),
+//!     RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+//!         consumer::ASSIGNED_TARIFF,
+//!         tariff_key.as_ref(),
+//!     )),
+//! ];
+//! ```
+
+pub use anchor_lang::Discriminator;
+pub use aquachain::state::{
+    AgencyBond, AgencyStats, AllocationClaim, AllocationTree, AuditRecord, CarbonOffsets,
+    CommunalPoint, Complaint, ComplaintPolicy, ConservationCertificate, Consumer,
+    ConsumerHydration, ConsumerTree, CreditNote, DrBid, DrEvent, DroughtCover, DroughtInsurance,
+    EnergyOracle, ForwardContract, FxOracle, HydroArchiveShard, InterruptiblePolicy,
+    IrrigationSchedule, LevelForecast, OffchainPayment, PaymentGateway, PaymentReceipt,
+    RedemptionOffer, RedemptionVoucher, RegulatorAuthority, Reservoir, ReservoirDailyStats, Role,
+    Sensor, SessionKey, SharedReservoir, SourceMix, SpillRecord, Tariff, TariffHistory, Tokens,
+    WatcMarket, WatcOrder, WeatherDerivative, WorkOrder,
+};
+
+/// Size of the discriminator every account starts with
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+const PUBKEY: usize = 32;
+const U64: usize = 8;
+const ENUM: usize = 1;
+
+/// Offsets of the `AgencyBond` fields
+pub mod agency_bond {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const SETTLEMENT_MINT: usize = AGENCY + PUBKEY;
+}
+
+/// Offsets of the `AllocationTree` fields
+pub mod allocation_tree {
+    use super::*;
+
+    pub const MERKLE_ROOT: usize = DISCRIMINATOR_LEN;
+    pub const TARIFF_KEY: usize = MERKLE_ROOT + 32;
+    pub const RESERVOIR_KEY: usize = TARIFF_KEY + PUBKEY;
+    pub const TREE_KEY: usize = RESERVOIR_KEY + PUBKEY + 3 * U64;
+}
+
+/// Offsets of the `AllocationClaim` fields
+pub mod allocation_claim {
+    use super::*;
+
+    pub const CONSUMER: usize = DISCRIMINATOR_LEN;
+}
+
+/// Offsets of the `AuditRecord` fields
+pub mod audit_record {
+    use super::*;
+
+    pub const DOCUMENT_HASH: usize = DISCRIMINATOR_LEN;
+    pub const CATEGORY: usize = DOCUMENT_HASH + 32;
+    pub const SUBJECT: usize = CATEGORY + ENUM;
+}
+
+/// Offsets of the `CarbonOffsets` fields
+pub mod carbon_offsets {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const CREDIT_MINT: usize = AGENCY + PUBKEY;
+}
+
+/// Offsets of the `CommunalPoint` fields
+pub mod communal_point {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const CONSUMER: usize = AGENCY + PUBKEY;
+}
+
+/// Offsets of the `ComplaintPolicy` fields
+pub mod complaint_policy {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const AQC_MINT: usize = AGENCY + PUBKEY;
+}
+
+/// Offsets of the `Complaint` fields
+pub mod complaint {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const COMPLAINT_KEY: usize = AGENCY + PUBKEY;
+    pub const CONSUMER: usize = COMPLAINT_KEY + PUBKEY;
+    pub const CATEGORY: usize = CONSUMER + PUBKEY;
+}
+
+/// Offsets of the `ConservationCertificate` fields
+pub mod conservation_certificate {
+    use super::*;
+
+    pub const CONSUMER: usize = DISCRIMINATOR_LEN;
+    pub const MINT: usize = CONSUMER + PUBKEY;
+}
+
+/// Offsets of the `Consumer` fields
+///
+/// The optional `guarantor` follows `assigned_reservoir`, so later fields have no fixed
+/// offset.
+pub mod consumer {
+    use super::*;
+
+    pub const BLOCK_RATE: usize = DISCRIMINATOR_LEN;
+    pub const CONTRACTED_CAPACITY: usize = BLOCK_RATE + U64;
+    pub const ASSIGNED_TARIFF: usize = CONTRACTED_CAPACITY + U64;
+    pub const ASSIGNED_RESERVOIR: usize = ASSIGNED_TARIFF + PUBKEY;
+}
+
+/// Offsets of the `ConsumerTree` fields
+pub mod consumer_tree {
+    use super::*;
+    use aquachain::state::{CONSUMER_TREE_DEPTH, CONSUMER_TREE_ROOT_HISTORY};
+
+    pub const TREE_KEY: usize =
+        DISCRIMINATOR_LEN + 32 * CONSUMER_TREE_DEPTH + 32 * CONSUMER_TREE_ROOT_HISTORY + 1 + U64;
+}
+
+/// Offsets of the `ConsumerHydration` fields
+pub mod consumer_hydration {
+    use super::*;
+
+    pub const CONSUMER: usize = DISCRIMINATOR_LEN;
+}
+
+/// Offsets of the `CreditNote` fields
+pub mod credit_note {
+    use super::*;
+
+    pub const CONSUMER: usize = DISCRIMINATOR_LEN;
+    pub const KIND: usize = CONSUMER + PUBKEY;
+    pub const REASON: usize = KIND + ENUM + U64;
+    pub const NOTE_KEY: usize = REASON + ENUM + U64;
+}
+
+/// Offsets of the `DrEvent` fields
+pub mod dr_event {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const EVENT_KEY: usize = AGENCY + PUBKEY;
+    pub const RESERVOIR_KEY: usize = EVENT_KEY + PUBKEY;
+    pub const SETTLEMENT_MINT: usize = RESERVOIR_KEY + PUBKEY;
+}
+
+/// Offsets of the `DrBid` fields
+pub mod dr_bid {
+    use super::*;
+
+    pub const DR_EVENT: usize = DISCRIMINATOR_LEN;
+    pub const CONSUMER: usize = DR_EVENT + PUBKEY;
+}
+
+/// Offsets of the `DroughtInsurance` fields
+pub mod drought_insurance {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const RESERVOIR_KEY: usize = AGENCY + PUBKEY;
+}
+
+/// Offsets of the `DroughtCover` fields
+pub mod drought_cover {
+    use super::*;
+
+    pub const CONSUMER: usize = DISCRIMINATOR_LEN;
+    pub const RESERVOIR_KEY: usize = CONSUMER + PUBKEY;
+}
+
+/// Offsets of the `EnergyOracle` fields
+pub mod energy_oracle {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const AUTHORITY: usize = AGENCY + PUBKEY;
+}
+
+/// Offsets of the `ForwardContract` fields
+pub mod forward_contract {
+    use super::*;
+
+    pub const CONSUMER: usize = DISCRIMINATOR_LEN;
+    pub const TARIFF_KEY: usize = CONSUMER + PUBKEY;
+}
+
+/// Offsets of the `FxOracle` fields
+pub mod fx_oracle {
+    use super::*;
+
+    pub const CURRENCY_CODE: usize = DISCRIMINATOR_LEN;
+    pub const SETTLEMENT_MINT: usize = CURRENCY_CODE + 3;
+    pub const AUTHORITY: usize = SETTLEMENT_MINT + PUBKEY;
+}
+
+/// Offsets of the `HydroArchiveShard` fields
+pub mod hydro_archive_shard {
+    use super::*;
+
+    pub const RESERVOIR_KEY: usize = DISCRIMINATOR_LEN;
+    pub const SHARD: usize = RESERVOIR_KEY + PUBKEY;
+    pub const PAYER: usize = SHARD + U64;
+}
+
+/// Offsets of the `InterruptiblePolicy` fields
+pub mod interruptible_policy {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const AQC_MINT: usize = AGENCY + PUBKEY;
+}
+
+/// Offsets of the `IrrigationSchedule` fields
+pub mod irrigation_schedule {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const CONSUMER: usize = AGENCY + PUBKEY;
+}
+
+/// Offsets of the `LevelForecast` fields
+pub mod level_forecast {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const RESERVOIR_KEY: usize = AGENCY + PUBKEY;
+}
+
+/// Offsets of the `PaymentGateway` fields
+pub mod payment_gateway {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const GATEWAY_KEY: usize = AGENCY + PUBKEY;
+    pub const ATTESTOR: usize = GATEWAY_KEY + PUBKEY;
+}
+
+/// Offsets of the `OffchainPayment` fields
+pub mod offchain_payment {
+    use super::*;
+
+    pub const GATEWAY: usize = DISCRIMINATOR_LEN;
+    pub const CONSUMER: usize = GATEWAY + PUBKEY;
+    pub const PAYMENT_ID: usize = CONSUMER + PUBKEY;
+}
+
+/// Offsets of the `PaymentReceipt` fields
+pub mod payment_receipt {
+    use super::*;
+
+    pub const CONSUMER: usize = DISCRIMINATOR_LEN;
+    pub const RECEIPT_NUMBER: usize = CONSUMER + PUBKEY;
+    pub const INVOICE_HASH: usize = RECEIPT_NUMBER + U64;
+    pub const PAYER: usize = INVOICE_HASH + 32;
+}
+
+/// Offsets of the `RedemptionOffer` fields
+pub mod redemption_offer {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const OFFER_KEY: usize = AGENCY + PUBKEY;
+    pub const AQC_MINT: usize = OFFER_KEY + PUBKEY;
+}
+
+/// Offsets of the `RedemptionVoucher` fields
+pub mod redemption_voucher {
+    use super::*;
+
+    pub const OFFER: usize = DISCRIMINATOR_LEN;
+    pub const CONSUMER: usize = OFFER + PUBKEY;
+}
+
+/// Offsets of the `RegulatorAuthority` fields
+pub mod regulator_authority {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const AUTHORITY: usize = AGENCY + PUBKEY;
+}
+
+/// Offsets of the `Reservoir` fields
+pub mod reservoir {
+    use super::*;
+
+    pub const CURRENT_LEVEL: usize = DISCRIMINATOR_LEN;
+    pub const CAPACITY: usize = CURRENT_LEVEL + U64;
+    pub const RESERVOIR_KEY: usize = CAPACITY + U64;
+}
+
+/// Offsets of the `ReservoirDailyStats` fields
+pub mod reservoir_daily_stats {
+    use super::*;
+
+    pub const RESERVOIR_KEY: usize = DISCRIMINATOR_LEN;
+}
+
+/// Offsets of the `Role` fields
+pub mod role {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const MEMBER: usize = AGENCY + PUBKEY;
+    pub const KIND: usize = MEMBER + PUBKEY;
+}
+
+/// Offsets of the `Sensor` fields
+pub mod sensor {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const RESERVOIR_KEY: usize = AGENCY + PUBKEY;
+    pub const SENSOR_KEY: usize = RESERVOIR_KEY + PUBKEY;
+    pub const AUTHORITY: usize = SENSOR_KEY + PUBKEY;
+}
+
+/// Offsets of the `SessionKey` fields
+pub mod session_key {
+    use super::*;
+
+    pub const CONSUMER: usize = DISCRIMINATOR_LEN;
+    pub const SESSION_KEY: usize = CONSUMER + PUBKEY;
+    pub const SCOPE: usize = SESSION_KEY + PUBKEY;
+    pub const REGISTERED_BY: usize = SCOPE + ENUM + U64;
+}
+
+/// Offsets of the `SharedReservoir` fields
+pub mod shared_reservoir {
+    use super::*;
+
+    pub const OPERATOR: usize = DISCRIMINATOR_LEN;
+    pub const RESERVOIR_KEY: usize = OPERATOR + PUBKEY;
+}
+
+/// Offsets of the `SourceMix` fields
+pub mod source_mix {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+}
+
+/// Offsets of the `SpillRecord` fields
+pub mod spill_record {
+    use super::*;
+
+    pub const RESERVOIR_KEY: usize = DISCRIMINATOR_LEN;
+}
+
+/// Offsets of the `Tariff` fields
+pub mod tariff {
+    use super::*;
+
+    pub const WATER_RATE: usize = DISCRIMINATOR_LEN;
+    pub const WASTE_RATE: usize = WATER_RATE + U64;
+    pub const TARIFF_TYPE: usize = WASTE_RATE + U64;
+    pub const TARIFF_KEY: usize = TARIFF_TYPE + ENUM;
+    pub const CURRENCY_CODE: usize = TARIFF_KEY + PUBKEY;
+}
+
+/// Offsets of the `TariffHistory` fields
+pub mod tariff_history {
+    use super::*;
+
+    pub const TARIFF_KEY: usize = DISCRIMINATOR_LEN;
+}
+
+/// Offsets of the `Tokens` fields
+pub mod tokens {
+    use super::*;
+
+    pub const WTK: usize = DISCRIMINATOR_LEN;
+    pub const WST: usize = WTK + PUBKEY;
+    pub const WATC: usize = WST + PUBKEY;
+}
+
+/// Offsets of the `WatcMarket` fields
+pub mod watc_market {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const SETTLEMENT_MINT: usize = AGENCY + PUBKEY;
+}
+
+/// Offsets of the `WatcOrder` fields
+pub mod watc_order {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const ORDER_ID: usize = AGENCY + PUBKEY;
+    pub const CONSUMER: usize = ORDER_ID + U64;
+    pub const SIDE: usize = CONSUMER + PUBKEY;
+}
+
+/// Offsets of the `WeatherDerivative` fields
+pub mod weather_derivative {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const COUNTERPARTY: usize = AGENCY + PUBKEY;
+    pub const DERIVATIVE_KEY: usize = COUNTERPARTY + PUBKEY;
+    pub const SETTLEMENT_MINT: usize = DERIVATIVE_KEY + PUBKEY;
+    pub const ORACLE_AUTHORITY: usize = SETTLEMENT_MINT + PUBKEY;
+}
+
+/// Offsets of the `WorkOrder` fields
+pub mod work_order {
+    use super::*;
+
+    pub const AGENCY: usize = DISCRIMINATOR_LEN;
+    pub const ORDER_KEY: usize = AGENCY + PUBKEY;
+    pub const CONSUMER: usize = ORDER_KEY + PUBKEY;
+    pub const KIND: usize = CONSUMER + PUBKEY;
+    pub const WORKER: usize = KIND + ENUM;
+    pub const STATUS: usize = WORKER + PUBKEY;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AccountSerialize;
+    use aquachain::state::{RoleKind, TariffType, WorkOrderKind, WorkOrderStatus};
+    use solana_sdk::pubkey::Pubkey;
+
+    fn serialize(account: &impl AccountSerialize) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    fn key_at(data: &[u8], offset: usize) -> Pubkey {
+        Pubkey::try_from(&data[offset..offset + PUBKEY]).unwrap()
+    }
+
+    #[test]
+    fn test_role_offsets() {
+        let role = Role {
+            agency: Pubkey::new_unique(),
+            member: Pubkey::new_unique(),
+            kind: RoleKind::Regulator,
+            granted_slot: 0,
+        };
+        let data = serialize(&role);
+
+        assert_eq!(data[..DISCRIMINATOR_LEN], Role::DISCRIMINATOR);
+        assert_eq!(key_at(&data, role::AGENCY), role.agency);
+        assert_eq!(key_at(&data, role::MEMBER), role.member);
+        assert_eq!(data[role::KIND], RoleKind::Regulator as u8);
+    }
+
+    #[test]
+    fn test_tariff_offsets() {
+        let tariff = Tariff {
+            water_rate: 500,
+            waste_rate: 200,
+            tariff_type: TariffType::SeasonalDBT,
+            tariff_key: Pubkey::new_unique(),
+            currency_code: *b"TTD",
+            activated_slot: 0,
+            grace_period_slots: 0,
+            billing_period_slots: 0,
+            capacity_rollover_bps: 0,
+            block_threshold: 0,
+            per_capita_allowance: 0,
+            assigned_consumer_count: 0,
+            conservation_baseline_bps: 0,
+            conservation_periods: 0,
+            bulk_rate: 0,
+            forecast_pricing: false,
+            max_multiplier: 0,
+            min_charge: 0,
+        };
+        let data = serialize(&tariff);
+
+        assert_eq!(data[..DISCRIMINATOR_LEN], Tariff::DISCRIMINATOR);
+        assert_eq!(data[tariff::TARIFF_TYPE], TariffType::SeasonalDBT as u8);
+        assert_eq!(key_at(&data, tariff::TARIFF_KEY), tariff.tariff_key);
+        assert_eq!(&data[tariff::CURRENCY_CODE..][..3], b"TTD");
+    }
+
+    #[test]
+    fn test_work_order_offsets() {
+        let order = WorkOrder {
+            agency: Pubkey::new_unique(),
+            order_key: Pubkey::new_unique(),
+            consumer: Pubkey::new_unique(),
+            kind: WorkOrderKind::MeterSwap,
+            worker: Pubkey::new_unique(),
+            status: WorkOrderStatus::Completed,
+            fee: 0,
+            depends_on: None,
+            created_slot: 0,
+            completed_slot: 0,
+        };
+        let data = serialize(&order);
+
+        assert_eq!(key_at(&data, work_order::CONSUMER), order.consumer);
+        assert_eq!(data[work_order::KIND], WorkOrderKind::MeterSwap as u8);
+        assert_eq!(key_at(&data, work_order::WORKER), order.worker);
+        assert_eq!(data[work_order::STATUS], WorkOrderStatus::Completed as u8);
+    }
+
+    #[test]
+    fn test_discriminators_are_distinct() {
+        let discriminators = [
+            Consumer::DISCRIMINATOR,
+            Tariff::DISCRIMINATOR,
+            Reservoir::DISCRIMINATOR,
+            PaymentReceipt::DISCRIMINATOR,
+            CreditNote::DISCRIMINATOR,
+        ];
+        for (i, a) in discriminators.iter().enumerate() {
+            for b in &discriminators[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+}
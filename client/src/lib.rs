@@ -0,0 +1,243 @@
+//! Transaction builders for integrating with the AquaChain program.
+//!
+//! [`TransactionBuilder`] assembles program instructions into v0 transactions, with
+//! address lookup tables to fit more accounts per transaction and compute-budget
+//! instructions to set a priority fee, so billing cranks can land during congestion.
+//!
+//! # Example
+//! ```ignore
+//! let instruction = program_instruction(
+//!     aquachain::accounts::CollectAutopay { /* ... */ },
+//!     aquachain::instruction::CollectAutopay { tariff_key },
+//! );
+//! let transaction = TransactionBuilder::new()
+//!     .instruction(instruction)
+//!     .compute_unit_limit(200_000)
+//!     .priority_fee(10_000)
+//!     .lookup_table(fetch_lookup_table(&rpc, &table_address)?)
+//!     .build(&[&cranker], rpc.get_latest_blockhash()?)?;
+//! ```
+//!
+//! The [`solana_pay`] module builds Solana Pay transaction requests for prepaid top-ups
+//! taken at kiosks and by field agents, and the [`layout`] module exports the account
+//! discriminators and field offsets indexers filter program accounts on. The [`query`]
+//! module builds on them to find the consumers on a tariff or reservoir and the invoices
+//! past their grace period, and the [`subscribe`] module streams a consumer's balances
+//! to wallets and portals as they change.
+
+pub mod layout;
+pub mod query;
+pub mod solana_pay;
+pub mod subscribe;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    address_lookup_table::{state::AddressLookupTable, AddressLookupTableAccount},
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signer::Signer,
+    transaction::VersionedTransaction,
+};
+
+/// Builds an instruction for the AquaChain program from its Anchor accounts and arguments
+///
+/// # Arguments
+/// * `accounts` - The instruction's accounts, e.g. `aquachain::accounts::UseWater`
+/// * `data` - The instruction's arguments, e.g. `aquachain::instruction::UseWater`
+pub fn program_instruction(
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id: aquachain::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Fetches an address lookup table so it can be used when compiling transactions
+///
+/// # Arguments
+/// * `rpc` - RPC client to fetch the table with
+/// * `address` - Address of the lookup table account
+pub fn fetch_lookup_table(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> Result<AddressLookupTableAccount, String> {
+    let account = rpc.get_account(address).map_err(|err| err.to_string())?;
+    let table = AddressLookupTable::deserialize(&account.data).map_err(|err| err.to_string())?;
+    Ok(AddressLookupTableAccount {
+        key: *address,
+        addresses: table.addresses.to_vec(),
+    })
+}
+
+/// Assembles instructions into a signed v0 transaction
+///
+/// # Fields
+/// * `instructions` - Instructions to include, in order
+/// * `compute_unit_limit` - Compute unit limit requested for the transaction, if any
+/// * `compute_unit_price` - Priority fee in micro-lamports per compute unit, if any
+/// * `lookup_tables` - Address lookup tables used to compress account keys
+#[derive(Default)]
+pub struct TransactionBuilder {
+    instructions: Vec<Instruction>,
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl TransactionBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an instruction
+    pub fn instruction(mut self, instruction: Instruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Appends several instructions, in order
+    pub fn instructions(mut self, instructions: impl IntoIterator<Item = Instruction>) -> Self {
+        self.instructions.extend(instructions);
+        self
+    }
+
+    /// Requests a compute unit limit for the transaction
+    pub fn compute_unit_limit(mut self, units: u32) -> Self {
+        self.compute_unit_limit = Some(units);
+        self
+    }
+
+    /// Sets the priority fee, in micro-lamports per compute unit
+    pub fn priority_fee(mut self, micro_lamports: u64) -> Self {
+        self.compute_unit_price = Some(micro_lamports);
+        self
+    }
+
+    /// Adds an address lookup table the transaction can load account keys from
+    pub fn lookup_table(mut self, table: AddressLookupTableAccount) -> Self {
+        self.lookup_tables.push(table);
+        self
+    }
+
+    /// Returns the instructions of the transaction, with the compute-budget
+    /// instructions placed before the program instructions
+    pub fn build_instructions(&self) -> Vec<Instruction> {
+        let budget = [
+            self.compute_unit_limit
+                .map(ComputeBudgetInstruction::set_compute_unit_limit),
+            self.compute_unit_price
+                .map(ComputeBudgetInstruction::set_compute_unit_price),
+        ];
+        budget
+            .into_iter()
+            .flatten()
+            .chain(self.instructions.iter().cloned())
+            .collect()
+    }
+
+    /// Compiles and signs a v0 transaction, with the first signer paying the fees
+    ///
+    /// # Arguments
+    /// * `signers` - Every signer of the transaction, fee payer first
+    /// * `blockhash` - A recent blockhash
+    ///
+    /// # Errors
+    /// * If there are no signers, the message cannot be compiled or a signer is missing
+    pub fn build(
+        &self,
+        signers: &[&dyn Signer],
+        blockhash: Hash,
+    ) -> Result<VersionedTransaction, String> {
+        let payer = signers
+            .first()
+            .ok_or_else(|| "a fee payer is required".to_string())?
+            .pubkey();
+        let message = v0::Message::try_compile(
+            &payer,
+            &self.build_instructions(),
+            &self.lookup_tables,
+            blockhash,
+        )
+        .map_err(|err| err.to_string())?;
+        VersionedTransaction::try_new(VersionedMessage::V0(message), signers)
+            .map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{compute_budget, instruction::AccountMeta, signature::Keypair};
+
+    fn instruction(accounts: &[Pubkey]) -> Instruction {
+        Instruction {
+            program_id: aquachain::ID,
+            accounts: accounts
+                .iter()
+                .map(|key| AccountMeta::new(*key, false))
+                .collect(),
+            data: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn test_budget_instructions_come_first() {
+        let instructions = TransactionBuilder::new()
+            .instruction(instruction(&[Pubkey::new_unique()]))
+            .compute_unit_limit(200_000)
+            .priority_fee(10_000)
+            .build_instructions();
+
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[0].program_id, compute_budget::id());
+        assert_eq!(instructions[1].program_id, compute_budget::id());
+        assert_eq!(instructions[2].program_id, aquachain::ID);
+    }
+
+    #[test]
+    fn test_no_budget_instructions_by_default() {
+        let instructions = TransactionBuilder::new()
+            .instruction(instruction(&[Pubkey::new_unique()]))
+            .build_instructions();
+
+        assert_eq!(instructions.len(), 1);
+    }
+
+    #[test]
+    fn test_build_with_lookup_table() {
+        let payer = Keypair::new();
+        let accounts: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: accounts.clone(),
+        };
+
+        let transaction = TransactionBuilder::new()
+            .instruction(instruction(&accounts))
+            .priority_fee(10_000)
+            .lookup_table(table)
+            .build(&[&payer], Hash::default())
+            .unwrap();
+
+        let VersionedMessage::V0(message) = &transaction.message else {
+            panic!("expected a v0 message");
+        };
+        assert_eq!(message.address_table_lookups.len(), 1);
+        assert_eq!(message.address_table_lookups[0].writable_indexes.len(), 4);
+        assert_eq!(transaction.signatures.len(), 1);
+    }
+
+    #[test]
+    fn test_build_requires_payer() {
+        assert!(TransactionBuilder::new()
+            .build(&[], Hash::default())
+            .is_err());
+    }
+}
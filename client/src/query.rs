@@ -0,0 +1,378 @@
+//! Program account queries every agency backend runs, such as listing the consumers on a
+//! tariff or the invoices past their grace period.
+//!
+//! Queries list matching accounts with `getProgramAccounts` and memcmp filters, asking
+//! for no account data, then fetch the accounts in pages of [`PAGE_SIZE`] with
+//! `getMultipleAccounts`, so large agencies stay within RPC response limits.
+//!
+//! # Example
+//! ```ignore
+//! for (consumer_key, consumer) in find_consumers_by_tariff(&rpc, &tariff_key)? {
+//!     println!("{} owes {}", consumer_key, consumer.outstanding_water_debt);
+//! }
+//! let overdue = find_overdue_invoices(&rpc, &agency, rpc.get_slot()?)?;
+//! ```
+
+use crate::layout::{self, Consumer, Discriminator, Reservoir, Tariff};
+use anchor_lang::AccountDeserialize;
+use aquachain::state::invoice_hash;
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+
+/// Number of accounts fetched per `getMultipleAccounts` call, the most RPC nodes accept
+pub const PAGE_SIZE: usize = 100;
+
+/// A consumer's water debt left unpaid past its tariff's grace period
+///
+/// # Fields
+/// * `consumer` - The consumer account owing the debt
+/// * `owner` - Wallet of the occupant responsible for the account
+/// * `invoice_hash` - Hash identifying the invoice, as recorded on payment receipts
+/// * `outstanding` - Water debt outstanding, in WTK base units
+/// * `debt_since_slot` - Slot since which the debt has been outstanding
+/// * `overdue_slots` - Slots elapsed since the grace period ended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OverdueInvoice {
+    pub consumer: Pubkey,
+    pub owner: Pubkey,
+    pub invoice_hash: [u8; 32],
+    pub outstanding: u64,
+    pub debt_since_slot: u64,
+    pub overdue_slots: u64,
+}
+
+/// Matches a public key stored at the given offset
+pub fn key_filter(offset: usize, key: &Pubkey) -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, key.as_ref()))
+}
+
+/// Matches accounts of type `T` by their discriminator
+pub fn discriminator_filter<T: Discriminator>() -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &T::DISCRIMINATOR))
+}
+
+/// Fetches and decodes program accounts, in pages of [`PAGE_SIZE`]
+///
+/// # Arguments
+/// * `rpc` - RPC client to fetch the accounts with
+/// * `addresses` - Addresses of the accounts
+///
+/// # Returns
+/// The decoded accounts, in the order of `addresses`, with `None` for accounts that do
+/// not exist or are not of type `T`
+pub fn fetch_accounts<T: AccountDeserialize>(
+    rpc: &RpcClient,
+    addresses: &[Pubkey],
+) -> Result<Vec<Option<T>>, String> {
+    let mut accounts = Vec::with_capacity(addresses.len());
+    for page in addresses.chunks(PAGE_SIZE) {
+        let fetched = rpc
+            .get_multiple_accounts(page)
+            .map_err(|err| err.to_string())?;
+        accounts.extend(fetched.into_iter().map(|account| {
+            account
+                .filter(|account| account.owner == aquachain::ID)
+                .and_then(|account| T::try_deserialize(&mut account.data.as_slice()).ok())
+        }));
+    }
+    Ok(accounts)
+}
+
+/// Finds every program account of type `T` matching the memcmp filters
+///
+/// # Arguments
+/// * `rpc` - RPC client to query
+/// * `filters` - Filters on the account data, in addition to the discriminator of `T`
+pub fn find_accounts<T: AccountDeserialize + Discriminator>(
+    rpc: &RpcClient,
+    mut filters: Vec<RpcFilterType>,
+) -> Result<Vec<(Pubkey, T)>, String> {
+    filters.insert(0, discriminator_filter::<T>());
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: Some(UiDataSliceConfig {
+                offset: 0,
+                length: 0,
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let addresses: Vec<Pubkey> = rpc
+        .get_program_accounts_with_config(&aquachain::ID, config)
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .map(|(address, _)| address)
+        .collect();
+
+    // Accounts closed between the two calls are skipped
+    let accounts = fetch_accounts::<T>(rpc, &addresses)?;
+    Ok(addresses
+        .into_iter()
+        .zip(accounts)
+        .filter_map(|(address, account)| Some((address, account?)))
+        .collect())
+}
+
+/// Finds the consumers assigned to a tariff
+///
+/// # Arguments
+/// * `rpc` - RPC client to query
+/// * `tariff_key` - Unique identifier of the tariff
+pub fn find_consumers_by_tariff(
+    rpc: &RpcClient,
+    tariff_key: &Pubkey,
+) -> Result<Vec<(Pubkey, Consumer)>, String> {
+    find_accounts(
+        rpc,
+        vec![key_filter(layout::consumer::ASSIGNED_TARIFF, tariff_key)],
+    )
+}
+
+/// Finds the consumers assigned to a reservoir
+///
+/// # Arguments
+/// * `rpc` - RPC client to query
+/// * `reservoir_key` - Unique identifier of the reservoir
+pub fn find_consumers_by_reservoir(
+    rpc: &RpcClient,
+    reservoir_key: &Pubkey,
+) -> Result<Vec<(Pubkey, Consumer)>, String> {
+    find_accounts(
+        rpc,
+        vec![key_filter(
+            layout::consumer::ASSIGNED_RESERVOIR,
+            reservoir_key,
+        )],
+    )
+}
+
+/// Lists the accounts of type `T` an agency derives from a key stored in each account
+///
+/// Tariffs and reservoirs hold no agency field, so the key of every account of the type
+/// is listed and only the accounts at the address the agency derives for their key kept.
+///
+/// # Arguments
+/// * `rpc` - RPC client to query
+/// * `agency` - The agency whose accounts are searched
+/// * `seed` - Constant seed of the account PDAs, followed by the agency and the key
+/// * `key_offset` - Offset of the key in the account data
+///
+/// # Returns
+/// The address and key of each of the agency's accounts
+fn find_agency_keys<T: Discriminator>(
+    rpc: &RpcClient,
+    agency: &Pubkey,
+    seed: &[u8],
+    key_offset: usize,
+) -> Result<Vec<(Pubkey, Pubkey)>, String> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![discriminator_filter::<T>()]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: Some(UiDataSliceConfig {
+                offset: key_offset,
+                length: 32,
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    Ok(rpc
+        .get_program_accounts_with_config(&aquachain::ID, config)
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .filter_map(|(address, account)| {
+            let key = Pubkey::try_from(account.data.as_slice()).ok()?;
+            (address == agency_address(agency, seed, &key)).then_some((address, key))
+        })
+        .collect())
+}
+
+/// Finds the tariffs of an agency
+///
+/// # Arguments
+/// * `rpc` - RPC client to query
+/// * `agency` - The agency whose tariffs are searched
+pub fn find_tariffs_by_agency(
+    rpc: &RpcClient,
+    agency: &Pubkey,
+) -> Result<Vec<(Pubkey, Tariff)>, String> {
+    let addresses: Vec<Pubkey> =
+        find_agency_keys::<Tariff>(rpc, agency, b"tariff", layout::tariff::TARIFF_KEY)?
+            .into_iter()
+            .map(|(address, _)| address)
+            .collect();
+
+    // Tariffs closed between the two calls are skipped
+    let tariffs = fetch_accounts::<Tariff>(rpc, &addresses)?;
+    Ok(addresses
+        .into_iter()
+        .zip(tariffs)
+        .filter_map(|(address, tariff)| Some((address, tariff?)))
+        .collect())
+}
+
+/// Finds the agency's consumers with water debt unpaid past their tariff's grace period
+///
+/// Consumers are queried per tariff of the agency with a memcmp filter. Tariff and
+/// reservoir keys are only unique within an agency, so consumers whose reservoir is not
+/// one of the agency's belong to another agency and are left out.
+///
+/// # Arguments
+/// * `rpc` - RPC client to query
+/// * `agency` - The agency whose consumers are searched
+/// * `slot` - The current slot
+///
+/// # Returns
+/// The overdue invoices, longest overdue first
+pub fn find_overdue_invoices(
+    rpc: &RpcClient,
+    agency: &Pubkey,
+    slot: u64,
+) -> Result<Vec<OverdueInvoice>, String> {
+    let reservoir_keys: HashSet<Pubkey> =
+        find_agency_keys::<Reservoir>(rpc, agency, b"reservoir", layout::reservoir::RESERVOIR_KEY)?
+            .into_iter()
+            .map(|(_, reservoir_key)| reservoir_key)
+            .collect();
+
+    let mut invoices = Vec::new();
+    for (_, tariff) in find_tariffs_by_agency(rpc, agency)? {
+        for (consumer_key, consumer) in find_consumers_by_tariff(rpc, &tariff.tariff_key)? {
+            if !reservoir_keys.contains(&consumer.assigned_reservoir) {
+                continue;
+            }
+            invoices.extend(overdue_invoice(&consumer_key, &consumer, &tariff, slot));
+        }
+    }
+    invoices.sort_by(|a, b| b.overdue_slots.cmp(&a.overdue_slots));
+    Ok(invoices)
+}
+
+/// Returns the address of the agency's account derived from a constant seed and a key
+fn agency_address(agency: &Pubkey, seed: &[u8], key: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seed, agency.as_ref(), key.as_ref()], &aquachain::ID).0
+}
+
+/// Returns the consumer's invoice if its debt is past the tariff's grace period
+fn overdue_invoice(
+    consumer_key: &Pubkey,
+    consumer: &Consumer,
+    tariff: &Tariff,
+    slot: u64,
+) -> Option<OverdueInvoice> {
+    let overdue_slots = overdue_slots(
+        tariff,
+        consumer.outstanding_water_debt,
+        consumer.debt_since_slot,
+        slot,
+    )?;
+    Some(OverdueInvoice {
+        consumer: *consumer_key,
+        owner: consumer.owner,
+        invoice_hash: invoice_hash(
+            consumer_key,
+            consumer.capacity_period_start,
+            &consumer.last_bill,
+        ),
+        outstanding: consumer.outstanding_water_debt,
+        debt_since_slot: consumer.debt_since_slot,
+        overdue_slots,
+    })
+}
+
+/// Returns the slots elapsed since the grace period of an outstanding debt ended
+fn overdue_slots(
+    tariff: &Tariff,
+    outstanding: u64,
+    debt_since_slot: u64,
+    slot: u64,
+) -> Option<u64> {
+    if outstanding == 0 || !tariff.grace_period_elapsed(debt_since_slot, slot) {
+        return None;
+    }
+    Some(slot - debt_since_slot.saturating_add(tariff.grace_period_slots))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aquachain::state::TariffType;
+
+    fn tariff(grace_period_slots: u64) -> Tariff {
+        Tariff {
+            water_rate: 500,
+            waste_rate: 200,
+            tariff_type: TariffType::UniformIBT,
+            tariff_key: Pubkey::new_unique(),
+            currency_code: *b"TTD",
+            activated_slot: 0,
+            grace_period_slots,
+            billing_period_slots: 0,
+            capacity_rollover_bps: 0,
+            block_threshold: 0,
+            per_capita_allowance: 0,
+            assigned_consumer_count: 0,
+            conservation_baseline_bps: 0,
+            conservation_periods: 0,
+            bulk_rate: 0,
+            forecast_pricing: false,
+            max_multiplier: 0,
+            min_charge: 0,
+        }
+    }
+
+    #[test]
+    fn test_key_filter() {
+        let key = Pubkey::new_unique();
+        let offset = layout::consumer::ASSIGNED_TARIFF;
+        let RpcFilterType::Memcmp(memcmp) = key_filter(offset, &key) else {
+            panic!("expected a memcmp filter");
+        };
+
+        let mut data = vec![0; offset + 64];
+        data[offset..][..32].copy_from_slice(key.as_ref());
+        assert!(memcmp.bytes_match(&data));
+
+        // The same key one field over does not match
+        data.copy_within(offset..offset + 32, offset + 32);
+        data[offset..][..32].fill(0);
+        assert!(!memcmp.bytes_match(&data));
+    }
+
+    #[test]
+    fn test_agency_address() {
+        let agency = Pubkey::new_unique();
+        let tariff_key = Pubkey::new_unique();
+        let (address, _) = Pubkey::find_program_address(
+            &[b"tariff", agency.as_ref(), tariff_key.as_ref()],
+            &aquachain::ID,
+        );
+        assert_eq!(agency_address(&agency, b"tariff", &tariff_key), address);
+
+        // The same tariff key under another agency is another account
+        let other = Pubkey::new_unique();
+        assert_ne!(agency_address(&other, b"tariff", &tariff_key), address);
+    }
+
+    #[test]
+    fn test_overdue_slots() {
+        let tariff = tariff(1_000);
+
+        // Within the grace period, or with nothing owed, nothing is overdue
+        assert_eq!(overdue_slots(&tariff, 500, 100, 1_099), None);
+        assert_eq!(overdue_slots(&tariff, 0, 100, 5_000), None);
+
+        assert_eq!(overdue_slots(&tariff, 500, 100, 1_100), Some(0));
+        assert_eq!(overdue_slots(&tariff, 500, 100, 1_600), Some(500));
+    }
+}
@@ -0,0 +1,192 @@
+//! Solana Pay support for pay-as-you-go prepaid top-ups.
+//!
+//! A kiosk or field agent shows a QR code encoding a Solana Pay transaction request for
+//! a consumer. The customer's wallet fetches a `top_up_prepaid` transaction from the
+//! kiosk's endpoint, which pays the agency and credits the consumer's prepaid balance
+//! atomically. Each top-up carries a reference derived from the consumer, so the kiosk
+//! can find the payment with `getSignaturesForAddress` once the wallet submits it.
+//!
+//! # Example
+//! ```ignore
+//! let reference = payment_reference(&consumer, receipt_number);
+//! let qr = transaction_request_url("https://kiosk.example/pay", &consumer, 20_000, &reference);
+//! // ... then, when the wallet posts its account to the endpoint:
+//! let instruction = PrepaidTopUp {
+//!     agency,
+//!     consumer,
+//!     assigned_tariff,
+//!     currency_code: *b"TTD",
+//!     settlement_mint: usdc_mint,
+//! }
+//! .instruction(&wallet, 20_000, &reference);
+//! ```
+
+use crate::program_instruction;
+use anchor_spl::{associated_token, associated_token::get_associated_token_address, token};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+/// Seed of the Solana Pay references derived for a consumer's top-ups
+pub const PAYMENT_REFERENCE_SEED: &[u8] = b"payment_reference";
+
+/// Derives the Solana Pay reference of a consumer's top-up
+///
+/// References are program-derived addresses, so no one holds their key, and the same
+/// consumer and nonce always give the same reference. Use a fresh nonce, such as a
+/// receipt number, for every top-up.
+///
+/// # Arguments
+/// * `consumer` - The consumer topped up
+/// * `nonce` - Number distinguishing the consumer's top-ups
+pub fn payment_reference(consumer: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            PAYMENT_REFERENCE_SEED,
+            consumer.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        &aquachain::ID,
+    )
+    .0
+}
+
+/// Builds the Solana Pay transaction request URL to encode in a top-up QR code
+///
+/// # Arguments
+/// * `endpoint` - HTTPS endpoint serving `top_up_prepaid` transactions
+/// * `consumer` - The consumer topped up
+/// * `amount` - Amount to credit to the prepaid balance, in WTK base units
+/// * `reference` - The top-up's reference, from [`payment_reference`]
+pub fn transaction_request_url(
+    endpoint: &str,
+    consumer: &Pubkey,
+    amount: u64,
+    reference: &Pubkey,
+) -> String {
+    let link = format!("{endpoint}?consumer={consumer}&amount={amount}&reference={reference}");
+    format!("solana:{}", percent_encode(&link))
+}
+
+/// Percent-encodes every character but the URL-unreserved ones
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// The accounts a consumer's prepaid top-ups are paid through
+///
+/// # Fields
+/// * `agency` - The agency serving the consumer and receiving the payment
+/// * `consumer` - The consumer topped up
+/// * `assigned_tariff` - Key of the consumer's assigned tariff
+/// * `currency_code` - Currency code of the tariff
+/// * `settlement_mint` - The stablecoin mint of the tariff's FX oracle
+pub struct PrepaidTopUp {
+    pub agency: Pubkey,
+    pub consumer: Pubkey,
+    pub assigned_tariff: Pubkey,
+    pub currency_code: [u8; 3],
+    pub settlement_mint: Pubkey,
+}
+
+impl PrepaidTopUp {
+    /// Builds the `top_up_prepaid` instruction for a payer, tagged with the reference
+    ///
+    /// # Arguments
+    /// * `payer` - The wallet paying for the top-up
+    /// * `amount` - Amount to credit to the prepaid balance, in WTK base units
+    /// * `reference` - The top-up's reference, appended as a read-only account
+    pub fn instruction(&self, payer: &Pubkey, amount: u64, reference: &Pubkey) -> Instruction {
+        let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &aquachain::ID).0;
+        let agency = self.agency.as_ref();
+
+        let mut instruction = program_instruction(
+            aquachain::accounts::TopUpPrepaid {
+                consumer: self.consumer,
+                tariff: pda(&[b"tariff", agency, self.assigned_tariff.as_ref()]),
+                fx_oracle: pda(&[b"fx_oracle", agency, &self.currency_code]),
+                tokens: pda(&[b"tokens", agency]),
+                agency: self.agency,
+                payer: *payer,
+                payer_settlement: get_associated_token_address(payer, &self.settlement_mint),
+                agency_settlement: get_associated_token_address(
+                    &self.agency,
+                    &self.settlement_mint,
+                ),
+                settlement_mint: self.settlement_mint,
+                token_program: token::ID,
+                associated_token_program: associated_token::ID,
+            },
+            aquachain::instruction::TopUpPrepaid { amount },
+        );
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(*reference, false));
+        instruction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payment_reference() {
+        let consumer = Pubkey::new_unique();
+        assert_eq!(
+            payment_reference(&consumer, 1),
+            payment_reference(&consumer, 1)
+        );
+        assert_ne!(
+            payment_reference(&consumer, 1),
+            payment_reference(&consumer, 2)
+        );
+        assert_ne!(
+            payment_reference(&consumer, 1),
+            payment_reference(&Pubkey::new_unique(), 1)
+        );
+    }
+
+    #[test]
+    fn test_transaction_request_url() {
+        let (consumer, reference) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let url =
+            transaction_request_url("https://kiosk.example/pay", &consumer, 20_000, &reference);
+        assert_eq!(
+            url,
+            format!(
+                "solana:https%3A%2F%2Fkiosk.example%2Fpay%3Fconsumer%3D{consumer}%26amount%3D20000%26reference%3D{reference}"
+            )
+        );
+    }
+
+    #[test]
+    fn test_reference_is_appended_read_only() {
+        let top_up = PrepaidTopUp {
+            agency: Pubkey::new_unique(),
+            consumer: Pubkey::new_unique(),
+            assigned_tariff: Pubkey::new_unique(),
+            currency_code: *b"TTD",
+            settlement_mint: Pubkey::new_unique(),
+        };
+        let (payer, reference) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instruction = top_up.instruction(&payer, 20_000, &reference);
+
+        let last = instruction.accounts.last().unwrap();
+        assert_eq!(last.pubkey, reference);
+        assert!(!last.is_signer && !last.is_writable);
+        assert!(instruction
+            .accounts
+            .iter()
+            .any(|meta| meta.pubkey == payer && meta.is_signer));
+    }
+}
@@ -0,0 +1,334 @@
+//! Live consumer balances for wallets and customer portals.
+//!
+//! [`ConsumerSubscription`] watches a consumer account and its five token accounts over
+//! the RPC node's websocket and yields a [`ConsumerSnapshot`] whenever any of them
+//! changes, so a portal can show debt and balances without polling.
+//!
+//! # Example
+//! ```ignore
+//! let mints = ConsumerMints { wtk, wst, watc, aqc, settlement: usdc_mint };
+//! let mut snapshots =
+//!     ConsumerSubscription::new(&rpc, "wss://api.devnet.solana.com", consumer, &mints).await?;
+//! while let Some(snapshot) = snapshots.next().await {
+//!     println!("{} WTK owed, {} WATC left", snapshot.wtk, snapshot.watc);
+//! }
+//! ```
+
+use crate::layout::Consumer;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::{
+    associated_token::get_associated_token_address,
+    token::{self, TokenAccount},
+};
+use futures_util::{
+    stream::{select_all, StreamExt},
+    Stream,
+};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonblocking::{pubsub_client::PubsubClient, rpc_client::RpcClient},
+    rpc_config::RpcAccountInfoConfig,
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+
+/// Number of accounts watched per consumer, the consumer account and its token accounts
+const WATCHED_ACCOUNTS: usize = 6;
+
+/// The mints of the tokens a consumer holds
+///
+/// # Fields
+/// * `wtk` - The Water Token (WTK) mint
+/// * `wst` - The Waste Token (WST) mint
+/// * `watc` - The Water Capacity Token (WATC) mint
+/// * `aqc` - The AquaCoin (AQC) mint
+/// * `settlement` - The stablecoin mint debts are settled in
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsumerMints {
+    pub wtk: Pubkey,
+    pub wst: Pubkey,
+    pub watc: Pubkey,
+    pub aqc: Pubkey,
+    pub settlement: Pubkey,
+}
+
+impl ConsumerMints {
+    /// Returns the consumer's associated token accounts, in the order of the mints
+    pub fn token_accounts(&self, consumer: &Pubkey) -> [Pubkey; 5] {
+        [self.wtk, self.wst, self.watc, self.aqc, self.settlement]
+            .map(|mint| get_associated_token_address(consumer, &mint))
+    }
+}
+
+/// A consumer's account and token balances as of a slot
+///
+/// Balances of token accounts that do not exist are zero.
+///
+/// # Fields
+/// * `slot` - The latest slot any of the watched accounts was observed at
+/// * `consumer` - The consumer account, `None` if it does not exist
+/// * `wtk` - WTK balance, the water debt billed and not yet paid
+/// * `wst` - WST balance, the waste treatment debt not yet paid
+/// * `watc` - WATC balance, the contracted capacity left this period
+/// * `aqc` - AquaCoin balance
+/// * `settlement` - Stablecoin balance
+#[derive(Clone, Default)]
+pub struct ConsumerSnapshot {
+    pub slot: u64,
+    pub consumer: Option<Consumer>,
+    pub wtk: u64,
+    pub wst: u64,
+    pub watc: u64,
+    pub aqc: u64,
+    pub settlement: u64,
+}
+
+/// The latest state of the watched accounts, and the slot each was observed at
+#[derive(Default)]
+struct Tracker {
+    snapshot: ConsumerSnapshot,
+    slots: [u64; WATCHED_ACCOUNTS],
+}
+
+impl Tracker {
+    /// Applies the state of a watched account observed at a slot
+    ///
+    /// # Arguments
+    /// * `index` - Index of the account, the consumer first and then its token accounts
+    /// * `slot` - The slot the account was observed at
+    /// * `account` - The account, `None` if it does not exist
+    ///
+    /// # Returns
+    /// Whether the snapshot changed, `false` for states older than the one applied
+    fn apply(&mut self, index: usize, slot: u64, account: Option<Account>) -> bool {
+        if slot < self.slots[index] {
+            return false;
+        }
+        self.slots[index] = slot;
+        self.snapshot.slot = self.snapshot.slot.max(slot);
+
+        if index == 0 {
+            self.snapshot.consumer = account
+                .filter(|account| account.owner == aquachain::ID)
+                .and_then(|account| Consumer::try_deserialize(&mut account.data.as_slice()).ok());
+            return true;
+        }
+        let amount = account
+            .filter(|account| account.owner == token::ID)
+            .and_then(|account| TokenAccount::try_deserialize(&mut account.data.as_slice()).ok())
+            .map_or(0, |token_account| token_account.amount);
+        let balance = match index {
+            1 => &mut self.snapshot.wtk,
+            2 => &mut self.snapshot.wst,
+            3 => &mut self.snapshot.watc,
+            4 => &mut self.snapshot.aqc,
+            _ => &mut self.snapshot.settlement,
+        };
+        *balance = amount;
+        true
+    }
+}
+
+/// A stream of [`ConsumerSnapshot`]s, one whenever a watched account changes
+///
+/// The first snapshot is the consumer's state when subscribing. Dropping the subscription
+/// closes its websocket connection.
+pub struct ConsumerSubscription {
+    tracker: Tracker,
+    initial: bool,
+    updates: mpsc::UnboundedReceiver<(usize, u64, Option<Account>)>,
+    task: JoinHandle<()>,
+}
+
+impl ConsumerSubscription {
+    /// Subscribes to a consumer's account and token accounts
+    ///
+    /// Accounts are subscribed to before their initial state is fetched, so no change in
+    /// between is missed. Notifications older than the initial state are dropped.
+    ///
+    /// # Arguments
+    /// * `rpc` - RPC client fetching the initial state, whose commitment is also used for
+    ///   the subscriptions
+    /// * `ws_url` - Websocket URL of the RPC node
+    /// * `consumer` - The consumer account to watch
+    /// * `mints` - The mints of the consumer's token accounts
+    pub async fn new(
+        rpc: &RpcClient,
+        ws_url: &str,
+        consumer: Pubkey,
+        mints: &ConsumerMints,
+    ) -> Result<Self, String> {
+        let mut addresses = [consumer; WATCHED_ACCOUNTS];
+        addresses[1..].copy_from_slice(&mints.token_accounts(&consumer));
+
+        let pubsub = PubsubClient::new(ws_url)
+            .await
+            .map_err(|err| err.to_string())?;
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(rpc.commitment()),
+            ..Default::default()
+        };
+        let (sender, updates) = mpsc::unbounded_channel();
+        let (subscribed, subscription) = oneshot::channel();
+
+        // The notification streams borrow the pubsub client, so both live in the task
+        let task = tokio::spawn(async move {
+            let mut streams = Vec::with_capacity(WATCHED_ACCOUNTS);
+            for (index, address) in addresses.iter().enumerate() {
+                match pubsub
+                    .account_subscribe(address, Some(config.clone()))
+                    .await
+                {
+                    Ok((stream, _unsubscribe)) => {
+                        streams.push(stream.map(move |response| (index, response)))
+                    }
+                    Err(err) => {
+                        let _ = subscribed.send(Err(err.to_string()));
+                        return;
+                    }
+                }
+            }
+            let _ = subscribed.send(Ok(()));
+
+            let mut notifications = select_all(streams);
+            while let Some((index, response)) = notifications.next().await {
+                let account = response.value.decode::<Account>();
+                if sender
+                    .send((index, response.context.slot, account))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        subscription
+            .await
+            .map_err(|_| "Subscription task stopped".to_string())??;
+
+        let mut tracker = Tracker::default();
+        let initial = rpc
+            .get_multiple_accounts_with_commitment(&addresses, rpc.commitment())
+            .await
+            .map_err(|err| err.to_string())?;
+        for (index, account) in initial.value.into_iter().enumerate() {
+            tracker.apply(index, initial.context.slot, account);
+        }
+
+        Ok(Self {
+            tracker,
+            initial: true,
+            updates,
+            task,
+        })
+    }
+}
+
+impl Stream for ConsumerSubscription {
+    type Item = ConsumerSnapshot;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if std::mem::take(&mut self.initial) {
+            return Poll::Ready(Some(self.tracker.snapshot.clone()));
+        }
+        loop {
+            let Some((index, slot, account)) = ready!(self.updates.poll_recv(cx)) else {
+                return Poll::Ready(None);
+            };
+            if self.tracker.apply(index, slot, account) {
+                return Poll::Ready(Some(self.tracker.snapshot.clone()));
+            }
+        }
+    }
+}
+
+impl Drop for ConsumerSubscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token::spl_token::state::{Account as SplAccount, AccountState};
+
+    fn token_account(amount: u64) -> Option<Account> {
+        let mut data = vec![0; SplAccount::LEN];
+        SplAccount {
+            mint: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            amount,
+            state: AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        Some(Account {
+            lamports: 2_039_280,
+            data,
+            owner: token::ID,
+            executable: false,
+            rent_epoch: 0,
+        })
+    }
+
+    #[test]
+    fn test_token_accounts_follow_mint_order() {
+        let consumer = Pubkey::new_unique();
+        let mints = ConsumerMints {
+            wtk: Pubkey::new_unique(),
+            wst: Pubkey::new_unique(),
+            watc: Pubkey::new_unique(),
+            aqc: Pubkey::new_unique(),
+            settlement: Pubkey::new_unique(),
+        };
+        let accounts = mints.token_accounts(&consumer);
+
+        assert_eq!(
+            accounts[0],
+            get_associated_token_address(&consumer, &mints.wtk)
+        );
+        assert_eq!(
+            accounts[4],
+            get_associated_token_address(&consumer, &mints.settlement)
+        );
+    }
+
+    #[test]
+    fn test_tracker_applies_balances() {
+        let mut tracker = Tracker::default();
+
+        assert!(tracker.apply(1, 10, token_account(500)));
+        assert!(tracker.apply(3, 12, token_account(2_000)));
+        assert_eq!(tracker.snapshot.wtk, 500);
+        assert_eq!(tracker.snapshot.watc, 2_000);
+        assert_eq!(tracker.snapshot.slot, 12);
+
+        // A closed or foreign account reads as an empty balance
+        assert!(tracker.apply(1, 13, None));
+        assert_eq!(tracker.snapshot.wtk, 0);
+        let mut foreign = token_account(700);
+        foreign.as_mut().unwrap().owner = Pubkey::new_unique();
+        assert!(tracker.apply(2, 13, foreign));
+        assert_eq!(tracker.snapshot.wst, 0);
+    }
+
+    #[test]
+    fn test_tracker_drops_stale_states() {
+        let mut tracker = Tracker::default();
+        assert!(tracker.apply(1, 20, token_account(500)));
+
+        // A notification sent before the initial fetch must not overwrite it
+        assert!(!tracker.apply(1, 15, token_account(900)));
+        assert_eq!(tracker.snapshot.wtk, 500);
+        assert_eq!(tracker.snapshot.slot, 20);
+    }
+}
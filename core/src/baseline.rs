@@ -0,0 +1,88 @@
+/// Number of closed billing periods a consumer's usage baseline is averaged over
+pub const BASELINE_PERIODS: usize = 4;
+
+/// Weather adjustment that leaves a baseline unchanged, in basis points
+pub const NEUTRAL_WEATHER_BPS: u16 = 10_000;
+
+/// Records the usage of a billing period that just closed in a consumer's history
+///
+/// The history holds the usage of the most recent periods, newest first, and the oldest
+/// period is dropped once `BASELINE_PERIODS` are held.
+///
+/// # Arguments
+/// * `history` - Usage of the most recently closed periods, newest first
+/// * `recorded` - Number of periods held in the history, updated in place
+/// * `usage` - Raw volume used over the period that closed
+pub fn record_period_usage(history: &mut [u64; BASELINE_PERIODS], recorded: &mut u8, usage: u64) {
+    history.copy_within(..BASELINE_PERIODS - 1, 1);
+    history[0] = usage;
+    *recorded = (*recorded).saturating_add(1).min(BASELINE_PERIODS as u8);
+}
+
+/// Compute the volume a consumer is expected to use over a window of slots
+///
+/// The baseline is the consumer's average usage over its recorded billing periods, spread
+/// evenly over the slots of a period, extended to the window and scaled by the weather
+/// adjustment. Only closed periods count, so usage in the current period cannot inflate
+/// the baseline a reduction is measured against.
+///
+/// # Arguments
+/// * `history` - Usage of the recorded periods; periods not yet recorded must be left out
+/// * `period_slots` - Number of slots in a billing period
+/// * `window_slots` - Number of slots in the window
+/// * `weather_adjustment_bps` - Weather adjustment in basis points, `NEUTRAL_WEATHER_BPS`
+///   for none, above it for hot or dry weather that raises demand
+///
+/// # Returns
+/// The raw volume of the baseline, saturating at `u64::MAX`, or 0 if no period is
+/// recorded or the billing period is empty
+pub fn usage_baseline(
+    history: &[u64],
+    period_slots: u64,
+    window_slots: u64,
+    weather_adjustment_bps: u16,
+) -> u64 {
+    if history.is_empty() || period_slots == 0 {
+        return 0;
+    }
+    let total: u128 = history.iter().map(|usage| *usage as u128).sum();
+    let baseline = total * window_slots as u128 * weather_adjustment_bps as u128
+        / (history.len() as u128 * period_slots as u128 * NEUTRAL_WEATHER_BPS as u128);
+    u64::try_from(baseline).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_period_usage() {
+        let (mut history, mut recorded) = ([0; BASELINE_PERIODS], 0);
+        for usage in [10, 20, 30, 40, 50] {
+            record_period_usage(&mut history, &mut recorded, usage);
+        }
+        // The oldest period is dropped
+        assert_eq!(history, [50, 40, 30, 20]);
+        assert_eq!(recorded, BASELINE_PERIODS as u8);
+    }
+
+    #[test]
+    fn test_usage_baseline() {
+        // 40,000 a period on average, over a quarter of a period
+        assert_eq!(
+            usage_baseline(&[30_000, 50_000], 1_000, 250, NEUTRAL_WEATHER_BPS),
+            10_000
+        );
+        // A heatwave raises the baseline by 20%
+        assert_eq!(
+            usage_baseline(&[30_000, 50_000], 1_000, 250, 12_000),
+            12_000
+        );
+        assert_eq!(usage_baseline(&[], 1_000, 250, NEUTRAL_WEATHER_BPS), 0);
+        assert_eq!(usage_baseline(&[30_000], 0, 250, NEUTRAL_WEATHER_BPS), 0);
+        assert_eq!(
+            usage_baseline(&[u64::MAX], 1, 2, NEUTRAL_WEATHER_BPS),
+            u64::MAX
+        );
+    }
+}
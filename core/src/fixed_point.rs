@@ -2,7 +2,6 @@ use std::{
     cmp::{Ordering, PartialOrd},
     fmt::Display,
     ops::{Add, Div, Mul, Sub},
-    u128,
 };
 
 /// Scale factor representing 3 decimal places of precision (1000)
@@ -28,7 +27,7 @@ impl FixedPoint {
     ///
     /// # Returns
     /// The fixed-point number as a u64, maintaining the scale factor
-    fn to_u64(&self) -> u64 {
+    fn to_u64(self) -> u64 {
         self.0 as u64
     }
 
@@ -39,6 +38,14 @@ impl FixedPoint {
     pub fn one() -> Self {
         FixedPoint(SCALE)
     }
+
+    /// Returns the underlying scaled representation
+    ///
+    /// # Returns
+    /// The raw u128 value, i.e. the number multiplied by the scale factor
+    pub fn raw(&self) -> u128 {
+        self.0
+    }
 }
 
 impl Display for FixedPoint {
@@ -52,7 +59,7 @@ impl Display for FixedPoint {
 // Implement PartialOrd for FixedPoint to support comparison operators
 impl PartialOrd for FixedPoint {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 
@@ -101,9 +108,9 @@ impl Div for FixedPoint {
 }
 
 // Implement conversion to u64
-impl Into<u64> for FixedPoint {
-    fn into(self) -> u64 {
-        self.to_u64()
+impl From<FixedPoint> for u64 {
+    fn from(value: FixedPoint) -> Self {
+        value.to_u64()
     }
 }
 
@@ -117,7 +124,6 @@ impl From<u64> for FixedPoint {
 #[cfg(test)]
 mod tests {
     use super::FixedPoint;
-    use std::u64;
 
     const SCALE: u128 = 1_000;
 
@@ -0,0 +1,17 @@
+//! AquaChain pricing core.
+//!
+//! The fixed-point arithmetic, tariff pricing, unit conversions and usage baselines the
+//! program bills and rewards with, free of any Solana dependency so the same code runs
+//! on-chain, in off-chain tools and, with the `wasm` feature, in the browser.
+
+mod baseline;
+mod fixed_point;
+mod pricing;
+mod units;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use baseline::*;
+pub use fixed_point::*;
+pub use pricing::*;
+pub use units::*;
@@ -0,0 +1,401 @@
+use crate::{
+    fixed_point::FixedPoint,
+    units::{to_currency, to_volume},
+};
+use std::{fmt, str::FromStr};
+
+/// Tariff structures the pricing supports, mirroring the program's `TariffType`
+///
+/// # Variants
+/// * `UniformIBT` - Uniform Increasing Block Tariff
+/// * `SeasonalIBT` - Seasonal Increasing Block Tariff, rising as the reservoir empties
+/// * `SeasonalDBT` - Seasonal Decreasing Block Tariff, falling as the reservoir fills
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TariffType {
+    UniformIBT,
+    SeasonalIBT,
+    SeasonalDBT,
+}
+
+impl FromStr for TariffType {
+    type Err = PricingError;
+
+    /// Parses a tariff type from its snake_case name, e.g. `uniform_ibt`
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "uniform_ibt" => Ok(TariffType::UniformIBT),
+            "seasonal_ibt" => Ok(TariffType::SeasonalIBT),
+            "seasonal_dbt" => Ok(TariffType::SeasonalDBT),
+            _ => Err(PricingError::UnknownTariffType),
+        }
+    }
+}
+
+/// Reasons a reading cannot be billed
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PricingError {
+    UnknownTariffType,
+    InvalidUnits,
+    Overflow,
+}
+
+impl fmt::Display for PricingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            PricingError::UnknownTariffType => "unknown tariff type",
+            PricingError::InvalidUnits => "invalid unit configuration",
+            PricingError::Overflow => "amount does not fit in a u64",
+        };
+        f.write_str(message)
+    }
+}
+
+/// Regulatory bounds on the charge of a reading, as set on a tariff
+///
+/// # Fields
+/// * `max_multiplier` - Highest scarcity multiplier applied to the block rate, with three
+///   implied decimals; zero leaves the multiplier uncapped
+/// * `min_charge` - Lowest cost of a reading, with three implied decimals; zero means there
+///   is no floor
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ChargeBounds {
+    pub max_multiplier: u64,
+    pub min_charge: u64,
+}
+
+impl ChargeBounds {
+    /// Caps a scarcity multiplier at the maximum multiplier, when one is set
+    pub fn cap_multiplier(&self, multiplier: FixedPoint) -> FixedPoint {
+        if self.max_multiplier == 0 {
+            multiplier
+        } else {
+            multiplier.min(FixedPoint::from(self.max_multiplier))
+        }
+    }
+
+    /// Raises the cost of a reading to the minimum charge
+    pub fn floor_cost(&self, cost: FixedPoint) -> FixedPoint {
+        cost.max(FixedPoint::from(self.min_charge))
+    }
+}
+
+/// Compute the cost of a water reading under a tariff
+///
+/// Usage within the volume remaining below the block threshold is charged at the water
+/// rate, and any excess at the block rate, scaled by the reservoir level for seasonal
+/// tariffs. The scarcity multiplier is capped and the cost floored by the tariff's bounds.
+///
+/// # Arguments
+/// * `block_remaining` - Billed volume still charged at the water rate this billing period
+/// * `amount_fp` - Billed volume of the reading
+/// * `water_rate_fp` - Tariff water rate
+/// * `tariff_type` - Tariff structure applied to usage above the block threshold
+/// * `block_rate_fp` - Consumer's block rate
+/// * `level_max` - Reservoir capacity
+/// * `level` - Current reservoir level
+/// * `bounds` - Regulatory bounds of the tariff
+///
+/// # Returns
+/// The cost as a fixed-point amount of the billing currency
+#[allow(clippy::too_many_arguments)]
+pub fn calculate_total_cost(
+    block_remaining: FixedPoint,
+    amount_fp: FixedPoint,
+    water_rate_fp: FixedPoint,
+    tariff_type: TariffType,
+    block_rate_fp: FixedPoint,
+    level_max: FixedPoint,
+    level: FixedPoint,
+    bounds: &ChargeBounds,
+) -> FixedPoint {
+    let multiplier = bounds.cap_multiplier(scarcity_multiplier(tariff_type, level_max, level));
+    bounds.floor_cost(calculate_scaled_cost(
+        block_remaining,
+        amount_fp,
+        water_rate_fp,
+        block_rate_fp,
+        multiplier,
+    ))
+}
+
+/// Compute the default scarcity multiplier applied to the block rate
+///
+/// Seasonal tariffs scale the block rate linearly with the reservoir level: the seasonal
+/// IBT by the volume missing from the reservoir, and the seasonal DBT from two when the
+/// reservoir is empty down to one when it is full. The uniform IBT is not scaled.
+///
+/// # Arguments
+/// * `tariff_type` - Tariff structure applied to usage above the block threshold
+/// * `level_max` - Reservoir capacity
+/// * `level` - Current reservoir level
+///
+/// # Returns
+/// The multiplier as a fixed-point factor
+pub fn scarcity_multiplier(
+    tariff_type: TariffType,
+    level_max: FixedPoint,
+    level: FixedPoint,
+) -> FixedPoint {
+    match tariff_type {
+        TariffType::UniformIBT => FixedPoint::one(),
+        TariffType::SeasonalIBT => level_max - level,
+        TariffType::SeasonalDBT => FixedPoint::one() + FixedPoint::one() - (level / level_max),
+    }
+}
+
+/// Compute the cost of a water reading with a given scarcity multiplier
+///
+/// Usage within the volume remaining below the block threshold is charged at the water
+/// rate, and any excess at the block rate scaled by `multiplier`.
+///
+/// # Arguments
+/// * `block_remaining` - Billed volume still charged at the water rate this billing period
+/// * `amount_fp` - Billed volume of the reading
+/// * `water_rate_fp` - Tariff water rate
+/// * `block_rate_fp` - Consumer's block rate
+/// * `multiplier` - Scarcity multiplier applied to the block rate
+///
+/// # Returns
+/// The cost as a fixed-point amount of the billing currency
+pub fn calculate_scaled_cost(
+    block_remaining: FixedPoint,
+    amount_fp: FixedPoint,
+    water_rate_fp: FixedPoint,
+    block_rate_fp: FixedPoint,
+    multiplier: FixedPoint,
+) -> FixedPoint {
+    let (base, excess) = calculate_cost_breakdown(
+        block_remaining,
+        amount_fp,
+        water_rate_fp,
+        block_rate_fp,
+        multiplier,
+    );
+    base + excess
+}
+
+/// Split the cost of a water reading into its base and excess parts
+///
+/// Takes the same arguments as [`calculate_scaled_cost`].
+///
+/// # Returns
+/// The cost of the usage within the block at the water rate, and the cost of the excess
+/// at the scaled block rate, as fixed-point amounts of the billing currency
+pub fn calculate_cost_breakdown(
+    block_remaining: FixedPoint,
+    amount_fp: FixedPoint,
+    water_rate_fp: FixedPoint,
+    block_rate_fp: FixedPoint,
+    multiplier: FixedPoint,
+) -> (FixedPoint, FixedPoint) {
+    if block_remaining >= amount_fp {
+        (amount_fp * water_rate_fp, FixedPoint::from(0))
+    } else {
+        let excess = amount_fp - block_remaining;
+        (
+            block_remaining * water_rate_fp,
+            excess * block_rate_fp * multiplier,
+        )
+    }
+}
+
+/// Compute a household's lifeline allocation for a billing period
+///
+/// Social tariffs commonly size the first block per person, e.g. 50 L per person per
+/// day, so larger households are not pushed into higher blocks for essential use.
+///
+/// # Arguments
+/// * `per_capita_allowance` - Raw volume allowed per person per billing period
+/// * `household_size` - Number of people in the household
+///
+/// # Returns
+/// The raw volume of the allocation, saturating at `u64::MAX`
+pub fn lifeline_allocation(per_capita_allowance: u64, household_size: u16) -> u64 {
+    per_capita_allowance.saturating_mul(household_size as u64)
+}
+
+/// A water reading to bill, in raw on-chain amounts
+///
+/// The scarcity multiplier is an input rather than derived from the reservoir level: the
+/// program prices seasonal tariffs on the reservoir's scarcity curve, at its smoothed level
+/// or a posted forecast, none of which the reading carries. Pass the multiplier the program
+/// prices at, such as the `multiplier` recorded in the consumer's `last_pricing`, or one
+/// (1000) for uniform tariffs.
+///
+/// # Fields
+/// * `block_remaining` - Raw volume left below the block threshold before the reading
+/// * `usage` - Raw volume of the reading
+/// * `water_rate` - Tariff water rate, with three implied decimals
+/// * `block_rate` - Consumer's block rate, with three implied decimals
+/// * `multiplier` - Scarcity multiplier of the reservoir before the tariff's cap, with
+///   three implied decimals
+/// * `volume_scale` - Number of raw volume units per billed unit of volume
+/// * `currency_decimals` - Number of decimals of the billing currency
+/// * `bounds` - Regulatory bounds of the tariff
+#[derive(Clone, Copy, Debug)]
+pub struct Reading {
+    pub block_remaining: u64,
+    pub usage: u64,
+    pub water_rate: u64,
+    pub block_rate: u64,
+    pub multiplier: u64,
+    pub volume_scale: u64,
+    pub currency_decimals: u8,
+    pub bounds: ChargeBounds,
+}
+
+/// Largest number of currency decimals whose scale still fits in a u64
+pub const MAX_CURRENCY_DECIMALS: u8 = 18;
+
+impl Reading {
+    /// Bills the reading as the program's `use_water` does at the given multiplier
+    ///
+    /// # Returns
+    /// The charge in base units of the billing currency
+    pub fn bill(&self) -> Result<u64, PricingError> {
+        if self.volume_scale == 0 || self.currency_decimals > MAX_CURRENCY_DECIMALS {
+            return Err(PricingError::InvalidUnits);
+        }
+        let volume = |raw| to_volume(raw, self.volume_scale).ok_or(PricingError::Overflow);
+
+        let multiplier = self
+            .bounds
+            .cap_multiplier(FixedPoint::from(self.multiplier));
+        let cost = self.bounds.floor_cost(calculate_scaled_cost(
+            volume(self.block_remaining)?,
+            volume(self.usage)?,
+            FixedPoint::from(self.water_rate),
+            FixedPoint::from(self.block_rate),
+            multiplier,
+        ));
+        to_currency(cost, self.currency_decimals).ok_or(PricingError::Overflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(multiplier: u64, usage: u64) -> Reading {
+        Reading {
+            block_remaining: 100000,
+            usage,
+            water_rate: 500,
+            block_rate: 800,
+            multiplier,
+            volume_scale: 1000,
+            currency_decimals: 3,
+            bounds: ChargeBounds::default(),
+        }
+    }
+
+    #[test]
+    fn test_bill_under_cap() {
+        assert_eq!(reading(1000, 100000).bill(), Ok(50000));
+    }
+
+    #[test]
+    fn test_bill_above_cap() {
+        assert_eq!(reading(1000, 120000).bill(), Ok(66000));
+        // The default curves of the seasonal IBT and DBT with the reservoir at 95%
+        assert_eq!(reading(50000, 120000).bill(), Ok(850000));
+        assert_eq!(reading(1050, 120000).bill(), Ok(66800));
+        // A scarcity curve band is billed as given
+        assert_eq!(reading(3000, 120000).bill(), Ok(98000));
+    }
+
+    #[test]
+    fn test_scaled_cost_matches_default_curve() {
+        let (block_remaining, amount) = (FixedPoint::from(100u64), FixedPoint::from(120u64));
+        let (water_rate, block_rate) = (FixedPoint::from(500u64), FixedPoint::from(800u64));
+        let (level_max, level) = (FixedPoint::from(1000u64), FixedPoint::from(950u64));
+
+        for tariff_type in [
+            TariffType::UniformIBT,
+            TariffType::SeasonalIBT,
+            TariffType::SeasonalDBT,
+        ] {
+            assert_eq!(
+                calculate_scaled_cost(
+                    block_remaining,
+                    amount,
+                    water_rate,
+                    block_rate,
+                    scarcity_multiplier(tariff_type, level_max, level),
+                ),
+                calculate_total_cost(
+                    block_remaining,
+                    amount,
+                    water_rate,
+                    tariff_type,
+                    block_rate,
+                    level_max,
+                    level,
+                    &ChargeBounds::default(),
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_bill_within_bounds() {
+        let mut capped = reading(50000, 120000);
+        capped.bounds.max_multiplier = 2000;
+        assert_eq!(capped.bill(), Ok(82000));
+
+        // A small reading is topped up to the minimum charge
+        let mut floored = reading(1000, 1000);
+        floored.bounds.min_charge = 5000;
+        assert_eq!(floored.bill(), Ok(5000));
+        floored.usage = 120000;
+        assert_eq!(floored.bill(), Ok(66000));
+    }
+
+    #[test]
+    fn test_cap_multiplier() {
+        let mut bounds = ChargeBounds::default();
+        // Unbounded by default
+        assert_eq!(
+            bounds.cap_multiplier(FixedPoint::from(50000)),
+            FixedPoint::from(50000)
+        );
+
+        bounds.max_multiplier = 2000;
+        assert_eq!(
+            bounds.cap_multiplier(FixedPoint::from(50000)),
+            FixedPoint::from(2000)
+        );
+        assert_eq!(
+            bounds.cap_multiplier(FixedPoint::from(1500)),
+            FixedPoint::from(1500)
+        );
+    }
+
+    #[test]
+    fn test_bill_rejects_invalid_readings() {
+        let mut invalid = reading(1000, 1000);
+        invalid.volume_scale = 0;
+        assert_eq!(invalid.bill(), Err(PricingError::InvalidUnits));
+
+        invalid = reading(1000, 1000);
+        invalid.currency_decimals = MAX_CURRENCY_DECIMALS + 1;
+        assert_eq!(invalid.bill(), Err(PricingError::InvalidUnits));
+    }
+
+    #[test]
+    fn test_lifeline_allocation() {
+        // 50 L per person per day over a 30 day period, in millilitres
+        assert_eq!(lifeline_allocation(1_500_000, 4), 6_000_000);
+        assert_eq!(lifeline_allocation(1_500_000, 0), 0);
+        assert_eq!(lifeline_allocation(u64::MAX, 2), u64::MAX);
+    }
+
+    #[test]
+    fn test_parse_tariff_type() {
+        assert_eq!("seasonal_dbt".parse(), Ok(TariffType::SeasonalDBT));
+        assert_eq!(
+            "flat".parse::<TariffType>(),
+            Err(PricingError::UnknownTariffType)
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use crate::fixed_point::{FixedPoint, SCALE};
+
+/// Converts a raw volume amount into a fixed-point billed volume
+///
+/// # Arguments
+/// * `raw` - The raw metered amount
+/// * `volume_scale` - Number of raw volume units per billed unit of volume
+///
+/// # Returns
+/// The billed volume, or `None` if it does not fit in a u64
+pub fn to_volume(raw: u64, volume_scale: u64) -> Option<FixedPoint> {
+    let scaled = (raw as u128) * SCALE / (volume_scale as u128);
+    u64::try_from(scaled).ok().map(FixedPoint::from)
+}
+
+/// Converts a fixed-point cost into base units of the billing currency
+///
+/// # Arguments
+/// * `cost` - The fixed-point cost
+/// * `currency_decimals` - Number of decimals of the billing currency
+///
+/// # Returns
+/// The cost in base units, or `None` if it does not fit in a u64
+pub fn to_currency(cost: FixedPoint, currency_decimals: u8) -> Option<u64> {
+    let base_units = cost
+        .raw()
+        .checked_mul(10u128.checked_pow(currency_decimals as u32)?)?
+        / SCALE;
+    u64::try_from(base_units).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_volume() {
+        assert_eq!(
+            to_volume(2_500_000, 1_000_000),
+            Some(FixedPoint::from(2500))
+        );
+        assert_eq!(to_volume(u64::MAX, 1), None);
+    }
+
+    #[test]
+    fn test_to_currency() {
+        assert_eq!(to_currency(FixedPoint::from(1250), 6), Some(1_250_000));
+        assert_eq!(to_currency(FixedPoint::from(u64::MAX), 18), None);
+    }
+}
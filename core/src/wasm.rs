@@ -0,0 +1,83 @@
+//! JavaScript bindings, built with `wasm-pack build core --features wasm --target web`
+
+use crate::{calculate_total_cost, ChargeBounds, FixedPoint, Reading, TariffType};
+use wasm_bindgen::prelude::*;
+
+/// Parses a tariff type, surfacing unknown names as JavaScript errors
+fn tariff_type(name: &str) -> Result<TariffType, JsError> {
+    name.parse()
+        .map_err(|err: crate::PricingError| JsError::new(&err.to_string()))
+}
+
+/// Bills a reading in currency base units, as the program would at the given multiplier.
+///
+/// Rates, the multiplier and bounds carry three implied decimals, volumes are raw metered
+/// amounts. The multiplier is the reservoir's scarcity multiplier the program prices at,
+/// before the tariff's cap. A zero `max_multiplier` or `min_charge` leaves the charge
+/// unbounded.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn bill(
+    block_remaining: u64,
+    usage: u64,
+    water_rate: u64,
+    block_rate: u64,
+    multiplier: u64,
+    volume_scale: u64,
+    currency_decimals: u8,
+    max_multiplier: u64,
+    min_charge: u64,
+) -> Result<u64, JsError> {
+    Reading {
+        block_remaining,
+        usage,
+        water_rate,
+        block_rate,
+        multiplier,
+        volume_scale,
+        currency_decimals,
+        bounds: ChargeBounds {
+            max_multiplier,
+            min_charge,
+        },
+    }
+    .bill()
+    .map_err(|err| JsError::new(&err.to_string()))
+}
+
+/// Computes the fixed-point cost of a billed volume, without unit conversions.
+///
+/// Seasonal tariffs are scaled by the default linear curve of reservoirs without a
+/// scarcity curve. Every argument and the result are fixed-point values scaled by 1000. A zero
+/// `max_multiplier` or `min_charge` leaves the cost unbounded.
+#[wasm_bindgen(js_name = totalCost)]
+#[allow(clippy::too_many_arguments)]
+pub fn total_cost(
+    block_remaining: u64,
+    amount: u64,
+    water_rate: u64,
+    tariff: &str,
+    block_rate: u64,
+    level_max: u64,
+    level: u64,
+    max_multiplier: u64,
+    min_charge: u64,
+) -> Result<u64, JsError> {
+    if level > level_max {
+        return Err(JsError::new("level exceeds level_max"));
+    }
+    Ok(calculate_total_cost(
+        FixedPoint::from(block_remaining),
+        FixedPoint::from(amount),
+        FixedPoint::from(water_rate),
+        tariff_type(tariff)?,
+        FixedPoint::from(block_rate),
+        FixedPoint::from(level_max),
+        FixedPoint::from(level),
+        &ChargeBounds {
+            max_multiplier,
+            min_charge,
+        },
+    )
+    .into())
+}
@@ -0,0 +1,342 @@
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anchor_spl::{associated_token::get_associated_token_address, token};
+use aquachain::state::{Consumer, FxOracle, InterruptiblePolicy, Tariff, Tokens};
+use aquachain_client::program_instruction;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use std::collections::HashMap;
+
+/// A periodic operation fired by the crank
+pub trait Job {
+    /// Name of the job, used in logs and metrics
+    fn name(&self) -> &'static str;
+
+    /// Number of slots between two runs of the job
+    fn interval_slots(&self) -> u64;
+
+    /// Builds the instructions due at `slot`, each sent in its own transaction
+    ///
+    /// # Arguments
+    /// * `rpc` - RPC client to read program accounts with
+    /// * `agency` - The agency whose accounts are cranked
+    /// * `cranker` - The wallet signing and paying for the cranks
+    /// * `slot` - The current slot
+    fn instructions(
+        &self,
+        rpc: &RpcClient,
+        agency: &Pubkey,
+        cranker: &Pubkey,
+        slot: u64,
+    ) -> Result<Vec<Instruction>, String>;
+}
+
+/// Fetches and decodes a program account, returning `None` if it does not exist
+fn fetch<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Option<T> {
+    let account = rpc.get_account(address).ok()?;
+    T::try_deserialize(&mut account.data.as_slice()).ok()
+}
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &aquachain::ID).0
+}
+
+/// Collects outstanding water debt from every consumer with auto-pay enabled
+pub struct AutopayJob {
+    pub interval_slots: u64,
+}
+
+impl Job for AutopayJob {
+    fn name(&self) -> &'static str {
+        "autopay"
+    }
+
+    fn interval_slots(&self) -> u64 {
+        self.interval_slots
+    }
+
+    fn instructions(
+        &self,
+        rpc: &RpcClient,
+        agency: &Pubkey,
+        cranker: &Pubkey,
+        slot: u64,
+    ) -> Result<Vec<Instruction>, String> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                0,
+                &Consumer::DISCRIMINATOR,
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let consumers = rpc
+            .get_program_accounts_with_config(&aquachain::ID, config)
+            .map_err(|err| err.to_string())?;
+
+        let tokens_key = pda(&[b"tokens", agency.as_ref()]);
+        let tokens: Tokens = fetch(rpc, &tokens_key).ok_or("agency tokens are not initialized")?;
+        let agency_stats = pda(&[b"agency_stats", agency.as_ref()]);
+        let billing_authority = pda(&[b"billing_authority", agency.as_ref()]);
+
+        // Tariffs and oracles are shared by many consumers, fetch each only once
+        let mut tariffs: HashMap<Pubkey, Option<(Pubkey, Tariff)>> = HashMap::new();
+        let mut oracles: HashMap<[u8; 3], Option<(Pubkey, FxOracle)>> = HashMap::new();
+        let mut instructions = Vec::new();
+
+        for (consumer_key, account) in consumers {
+            let Ok(consumer) = Consumer::try_deserialize(&mut account.data.as_slice()) else {
+                continue;
+            };
+            if !consumer.autopay_enabled || consumer.outstanding_water_debt == 0 {
+                continue;
+            }
+
+            // Consumers of other agencies have no tariff under this agency's seeds
+            let tariff_key = consumer.assigned_tariff;
+            let Some((tariff_pda, tariff)) = tariffs
+                .entry(tariff_key)
+                .or_insert_with(|| {
+                    let address = pda(&[b"tariff", agency.as_ref(), tariff_key.as_ref()]);
+                    fetch(rpc, &address).map(|tariff| (address, tariff))
+                })
+                .clone()
+            else {
+                continue;
+            };
+
+            // Debt is only collected once past the tariff's grace period
+            if !tariff.grace_period_elapsed(consumer.debt_since_slot, slot) {
+                continue;
+            }
+
+            // Collection fails on-chain while the rate is stale, so wait for a fresh one
+            let Some((fx_oracle, oracle)) = oracles
+                .entry(tariff.currency_code)
+                .or_insert_with(|| {
+                    let address = pda(&[b"fx_oracle", agency.as_ref(), &tariff.currency_code]);
+                    fetch(rpc, &address).map(|oracle| (address, oracle))
+                })
+                .clone()
+            else {
+                continue;
+            };
+            if oracle.is_stale(slot) {
+                continue;
+            }
+
+            instructions.push(program_instruction(
+                aquachain::accounts::CollectAutopay {
+                    cranker: *cranker,
+                    consumer: consumer_key,
+                    tariff: tariff_pda,
+                    fx_oracle,
+                    agency: *agency,
+                    tokens: tokens_key,
+                    agency_stats,
+                    billing_authority,
+                    receipt: pda(&[
+                        b"receipt",
+                        consumer_key.as_ref(),
+                        &consumer.receipts_issued.to_le_bytes(),
+                    ]),
+                    consumer_wtk: get_associated_token_address(&consumer_key, &tokens.wtk),
+                    consumer_settlement: get_associated_token_address(
+                        &consumer_key,
+                        &oracle.settlement_mint,
+                    ),
+                    agency_settlement: get_associated_token_address(
+                        agency,
+                        &oracle.settlement_mint,
+                    ),
+                    wtk_mint: tokens.wtk,
+                    settlement_mint: oracle.settlement_mint,
+                    token_program: token::ID,
+                    associated_token_program: anchor_spl::associated_token::ID,
+                    system_program: solana_sdk::system_program::ID,
+                },
+                aquachain::instruction::CollectAutopay { tariff_key },
+            ));
+        }
+        Ok(instructions)
+    }
+}
+
+/// Tops up the WATC of every consumer whose billing period has ended
+///
+/// Minting capacity requires the agency's signature, so this job only runs when the
+/// crank signs with the agency's own key.
+pub struct RefreshCapacityJob {
+    pub interval_slots: u64,
+}
+
+impl Job for RefreshCapacityJob {
+    fn name(&self) -> &'static str {
+        "refresh_capacity"
+    }
+
+    fn interval_slots(&self) -> u64 {
+        self.interval_slots
+    }
+
+    fn instructions(
+        &self,
+        rpc: &RpcClient,
+        agency: &Pubkey,
+        _cranker: &Pubkey,
+        slot: u64,
+    ) -> Result<Vec<Instruction>, String> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                0,
+                &Consumer::DISCRIMINATOR,
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let consumers = rpc
+            .get_program_accounts_with_config(&aquachain::ID, config)
+            .map_err(|err| err.to_string())?;
+
+        let tokens_key = pda(&[b"tokens", agency.as_ref()]);
+        let tokens: Tokens = fetch(rpc, &tokens_key).ok_or("agency tokens are not initialized")?;
+        let mut tariffs: HashMap<Pubkey, Option<(Pubkey, Tariff)>> = HashMap::new();
+        let mut instructions = Vec::new();
+
+        for (consumer_key, account) in consumers {
+            let Ok(consumer) = Consumer::try_deserialize(&mut account.data.as_slice()) else {
+                continue;
+            };
+
+            // Consumers of other agencies have no tariff under this agency's seeds
+            let tariff_key = consumer.assigned_tariff;
+            let Some((tariff_pda, tariff)) = tariffs
+                .entry(tariff_key)
+                .or_insert_with(|| {
+                    let address = pda(&[b"tariff", agency.as_ref(), tariff_key.as_ref()]);
+                    fetch(rpc, &address).map(|tariff| (address, tariff))
+                })
+                .clone()
+            else {
+                continue;
+            };
+            if consumer
+                .capacity_refresh_due(tariff.billing_period_slots, slot)
+                .is_none()
+            {
+                continue;
+            }
+
+            instructions.push(program_instruction(
+                aquachain::accounts::RefreshCapacity {
+                    consumer: consumer_key,
+                    tariff: tariff_pda,
+                    agency: *agency,
+                    tokens: tokens_key,
+                    consumer_watc: get_associated_token_address(&consumer_key, &tokens.watc),
+                    watc_mint: tokens.watc,
+                    token_program: token::ID,
+                    associated_token_program: anchor_spl::associated_token::ID,
+                },
+                aquachain::instruction::RefreshCapacity { tariff_key },
+            ));
+        }
+        Ok(instructions)
+    }
+}
+
+/// Compensates every interruptible consumer for the periods its supply was curtailed
+///
+/// Minting AquaCoin requires the agency's signature, so this job only runs when the
+/// crank signs with the agency's own key.
+pub struct CompensateCurtailmentJob {
+    pub interval_slots: u64,
+}
+
+impl Job for CompensateCurtailmentJob {
+    fn name(&self) -> &'static str {
+        "compensate_curtailment"
+    }
+
+    fn interval_slots(&self) -> u64 {
+        self.interval_slots
+    }
+
+    fn instructions(
+        &self,
+        rpc: &RpcClient,
+        agency: &Pubkey,
+        _cranker: &Pubkey,
+        _slot: u64,
+    ) -> Result<Vec<Instruction>, String> {
+        // Agencies not offering interruptible supply have nothing to compensate
+        let policy_key = pda(&[b"interruptible_policy", agency.as_ref()]);
+        let Some(policy) = fetch::<InterruptiblePolicy>(rpc, &policy_key) else {
+            return Ok(Vec::new());
+        };
+
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                0,
+                &Consumer::DISCRIMINATOR,
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let consumers = rpc
+            .get_program_accounts_with_config(&aquachain::ID, config)
+            .map_err(|err| err.to_string())?;
+
+        let mut tariffs: HashMap<Pubkey, Option<Pubkey>> = HashMap::new();
+        let mut instructions = Vec::new();
+
+        for (consumer_key, account) in consumers {
+            let Ok(consumer) = Consumer::try_deserialize(&mut account.data.as_slice()) else {
+                continue;
+            };
+            if !consumer.interruptible
+                || policy.uncompensated_periods(consumer.curtailed_periods_compensated) == 0
+            {
+                continue;
+            }
+
+            // Consumers of other agencies have no tariff under this agency's seeds
+            let tariff_key = consumer.assigned_tariff;
+            let Some(tariff_pda) = *tariffs.entry(tariff_key).or_insert_with(|| {
+                let address = pda(&[b"tariff", agency.as_ref(), tariff_key.as_ref()]);
+                fetch::<Tariff>(rpc, &address).map(|_| address)
+            }) else {
+                continue;
+            };
+
+            instructions.push(program_instruction(
+                aquachain::accounts::CompensateCurtailment {
+                    consumer: consumer_key,
+                    tariff: tariff_pda,
+                    interruptible_policy: policy_key,
+                    consumer_aqc: get_associated_token_address(&consumer_key, &policy.aqc_mint),
+                    aqc_mint: policy.aqc_mint,
+                    agency: *agency,
+                    system_program: solana_sdk::system_program::ID,
+                    token_program: token::ID,
+                    associated_token_program: anchor_spl::associated_token::ID,
+                },
+                aquachain::instruction::CompensateCurtailment {},
+            ));
+        }
+        Ok(instructions)
+    }
+}
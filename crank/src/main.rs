@@ -0,0 +1,189 @@
+//! Crank daemon firing AquaChain's periodic permissionless operations.
+//!
+//! Polls the chain clock and runs every job whose interval has elapsed since its last
+//! run. Each crank is sent in its own transaction with a priority fee and retried with
+//! exponential backoff. Counters of fired and failed cranks are exported to Prometheus,
+//! and `/healthz` fails once the chain clock has not been read for a few polls.
+//!
+//! # Jobs
+//! * `autopay` - Collects overdue water debt from consumers with auto-pay enabled
+//! * `refresh_capacity` - Tops up WATC at billing period rollover, only when the crank
+//!   keypair is the agency's own key since minting requires its signature
+//! * `compensate_curtailment` - Pays interruptible consumers AQC for curtailed periods, also
+//!   only when the crank keypair is the agency's own key
+//!
+//! # Environment
+//! * `AQUACHAIN_RPC_URL` - RPC endpoint (default `http://127.0.0.1:8899`)
+//! * `AQUACHAIN_AGENCY` - Public key of the agency whose accounts are cranked
+//! * `AQUACHAIN_CRANK_KEYPAIR` - Keypair file of the cranker paying the fees
+//! * `AQUACHAIN_PRIORITY_FEE` - Priority fee in micro-lamports per compute unit (default `0`)
+//! * `AQUACHAIN_AUTOPAY_INTERVAL_SLOTS` - Slots between auto-pay runs (default `216000`, about a day)
+//! * `AQUACHAIN_REFRESH_INTERVAL_SLOTS` - Slots between capacity refresh runs (default `9000`, about an hour)
+//! * `AQUACHAIN_COMPENSATION_INTERVAL_SLOTS` - Slots between curtailment compensation runs (default `9000`, about an hour)
+//! * `AQUACHAIN_METRICS_ADDR` - Address serving `/metrics` and `/healthz` (default `0.0.0.0:9100`)
+
+mod jobs;
+mod scheduler;
+
+use aquachain_client::TransactionBuilder;
+use aquachain_telemetry::{serve, Telemetry};
+use jobs::{AutopayJob, CompensateCurtailmentJob, Job, RefreshCapacityJob};
+use scheduler::{is_due, with_retry};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair},
+    signer::Signer,
+};
+use std::{env, process, str::FromStr, sync::Arc, thread, time::Duration};
+
+const DEFAULT_RPC_URL: &str = "http://127.0.0.1:8899";
+const DEFAULT_AUTOPAY_INTERVAL_SLOTS: u64 = 216_000;
+const DEFAULT_REFRESH_INTERVAL_SLOTS: u64 = 9_000;
+const DEFAULT_COMPENSATION_INTERVAL_SLOTS: u64 = 9_000;
+const DEFAULT_METRICS_ADDR: &str = "0.0.0.0:9100";
+/// Delay between two polls of the chain clock
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// Attempts made for each RPC call or crank before giving up until the next run
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Reads a required environment variable, exiting with an error if it is missing or invalid
+fn required_var<T>(name: &str, parse: impl FnOnce(&str) -> Option<T>) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|value| parse(&value))
+        .unwrap_or_else(|| {
+            eprintln!("{} must be set to a valid value", name);
+            process::exit(1);
+        })
+}
+
+/// Reads an optional environment variable, falling back to a default
+fn optional_var<T: FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Sends a single crank in its own transaction, retrying with backoff
+fn send_crank(
+    rpc: &RpcClient,
+    cranker: &Keypair,
+    priority_fee: u64,
+    instruction: Instruction,
+) -> Result<(), String> {
+    with_retry(MAX_ATTEMPTS, || {
+        let blockhash = rpc.get_latest_blockhash().map_err(|err| err.to_string())?;
+        let transaction = TransactionBuilder::new()
+            .instruction(instruction.clone())
+            .priority_fee(priority_fee)
+            .build(&[cranker], blockhash)?;
+        rpc.send_and_confirm_transaction(&transaction)
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    })
+}
+
+fn main() {
+    let rpc_url = env::var("AQUACHAIN_RPC_URL").unwrap_or_else(|_| DEFAULT_RPC_URL.to_string());
+    let agency: Pubkey = required_var("AQUACHAIN_AGENCY", |value| Pubkey::from_str(value).ok());
+    let cranker = required_var("AQUACHAIN_CRANK_KEYPAIR", |path| {
+        read_keypair_file(path).ok()
+    });
+    let priority_fee = optional_var("AQUACHAIN_PRIORITY_FEE", 0);
+
+    let rpc = RpcClient::new(rpc_url);
+    let mut jobs: Vec<Box<dyn Job>> = vec![Box::new(AutopayJob {
+        interval_slots: optional_var(
+            "AQUACHAIN_AUTOPAY_INTERVAL_SLOTS",
+            DEFAULT_AUTOPAY_INTERVAL_SLOTS,
+        ),
+    })];
+    if cranker.pubkey() == agency {
+        jobs.push(Box::new(RefreshCapacityJob {
+            interval_slots: optional_var(
+                "AQUACHAIN_REFRESH_INTERVAL_SLOTS",
+                DEFAULT_REFRESH_INTERVAL_SLOTS,
+            ),
+        }));
+        jobs.push(Box::new(CompensateCurtailmentJob {
+            interval_slots: optional_var(
+                "AQUACHAIN_COMPENSATION_INTERVAL_SLOTS",
+                DEFAULT_COMPENSATION_INTERVAL_SLOTS,
+            ),
+        }));
+    } else {
+        println!(
+            "Capacity refreshes and curtailment compensation disabled: the crank keypair is not the agency's"
+        );
+    }
+    let mut last_runs: Vec<Option<u64>> = vec![None; jobs.len()];
+
+    let telemetry = Arc::new(Telemetry::new().with_max_heartbeat_age(POLL_INTERVAL * 6));
+    telemetry.describe("aquachain_cranks_fired_total", "Cranks sent successfully");
+    telemetry.describe(
+        "aquachain_cranks_failed_total",
+        "Cranks or crank listings that failed after every retry",
+    );
+    let metrics_addr =
+        env::var("AQUACHAIN_METRICS_ADDR").unwrap_or_else(|_| DEFAULT_METRICS_ADDR.to_string());
+    if let Err(err) = serve(telemetry.clone(), &metrics_addr) {
+        eprintln!("Failed to serve metrics on {}: {}", metrics_addr, err);
+        process::exit(1);
+    }
+
+    println!("Cranking agency {} as {}", agency, cranker.pubkey());
+    loop {
+        let slot = match with_retry(MAX_ATTEMPTS, || {
+            rpc.get_slot().map_err(|err| err.to_string())
+        }) {
+            Ok(slot) => slot,
+            Err(err) => {
+                eprintln!("Failed to read the chain clock: {}", err);
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+        };
+        telemetry.heartbeat();
+
+        for (job, last_run) in jobs.iter().zip(last_runs.iter_mut()) {
+            if !is_due(*last_run, slot, job.interval_slots()) {
+                continue;
+            }
+
+            // A job whose cranks cannot be listed is retried on the next poll
+            let instructions = match with_retry(MAX_ATTEMPTS, || {
+                job.instructions(&rpc, &agency, &cranker.pubkey(), slot)
+            }) {
+                Ok(instructions) => instructions,
+                Err(err) => {
+                    eprintln!("Failed to list {} cranks: {}", job.name(), err);
+                    telemetry.inc("aquachain_cranks_failed_total", &[("job", job.name())]);
+                    continue;
+                }
+            };
+
+            for instruction in instructions {
+                match send_crank(&rpc, &cranker, priority_fee, instruction) {
+                    Ok(()) => telemetry.inc("aquachain_cranks_fired_total", &[("job", job.name())]),
+                    Err(err) => {
+                        eprintln!("Failed to send {} crank: {}", job.name(), err);
+                        telemetry.inc("aquachain_cranks_failed_total", &[("job", job.name())]);
+                    }
+                }
+            }
+            *last_run = Some(slot);
+            println!(
+                "Ran {} at slot {}: {} cranks fired, {} failed in total",
+                job.name(),
+                slot,
+                telemetry.get("aquachain_cranks_fired_total", &[("job", job.name())]),
+                telemetry.get("aquachain_cranks_failed_total", &[("job", job.name())]),
+            );
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
@@ -0,0 +1,88 @@
+use std::{thread, time::Duration};
+
+/// Delay before the first retry of a failed crank
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+/// Longest delay between two retries
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Returns true if a job last run at `last_run` is due again at `slot`
+///
+/// # Arguments
+/// * `last_run` - Slot of the job's last run, `None` if it never ran
+/// * `slot` - The current slot
+/// * `interval_slots` - Number of slots between two runs
+pub fn is_due(last_run: Option<u64>, slot: u64, interval_slots: u64) -> bool {
+    match last_run {
+        Some(last_run) => slot.saturating_sub(last_run) >= interval_slots,
+        None => true,
+    }
+}
+
+/// Returns the delay before a retry, doubling with each attempt up to `MAX_BACKOFF`
+///
+/// # Arguments
+/// * `attempt` - Number of attempts already made, starting at 1
+pub fn backoff(attempt: u32) -> Duration {
+    BASE_BACKOFF
+        .saturating_mul(
+            1u32.checked_shl(attempt.saturating_sub(1))
+                .unwrap_or(u32::MAX),
+        )
+        .min(MAX_BACKOFF)
+}
+
+/// Runs an operation until it succeeds, sleeping with exponential backoff in between
+///
+/// # Arguments
+/// * `max_attempts` - Number of attempts before giving up
+/// * `operation` - The operation to run
+///
+/// # Returns
+/// The first success, or the error of the last attempt
+pub fn with_retry<T>(
+    max_attempts: u32,
+    mut operation: impl FnMut() -> Result<T, String>,
+) -> Result<T, String> {
+    let mut attempt = 1;
+    loop {
+        match operation() {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt >= max_attempts => return Err(err),
+            Err(_) => {
+                thread::sleep(backoff(attempt));
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_due() {
+        assert!(is_due(None, 0, 100));
+        assert!(!is_due(Some(1000), 1099, 100));
+        assert!(is_due(Some(1000), 1100, 100));
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        assert_eq!(backoff(1), Duration::from_millis(500));
+        assert_eq!(backoff(2), Duration::from_millis(1000));
+        assert_eq!(backoff(3), Duration::from_millis(2000));
+        assert_eq!(backoff(40), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_with_retry_returns_last_error() {
+        let mut calls = 0;
+        let result: Result<(), String> = with_retry(1, || {
+            calls += 1;
+            Err(format!("attempt {}", calls))
+        });
+        assert_eq!(result, Err("attempt 1".to_string()));
+        assert_eq!(calls, 1);
+    }
+}
@@ -0,0 +1,70 @@
+use solana_client::rpc_client::RpcClient;
+use std::{
+    path::Path,
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Time the validator is given to start answering RPC requests
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A `solana-test-validator` process with the program preloaded at its declared id
+///
+/// The ledger is reset on every start, so the fixtures are always created on an empty
+/// chain. The validator is killed when the value is dropped.
+pub struct Localnet {
+    process: Child,
+}
+
+impl Localnet {
+    /// Starts the validator and waits until it answers RPC requests
+    ///
+    /// # Arguments
+    /// * `program` - Path to the compiled program, e.g. `target/deploy/aquachain.so`
+    /// * `ledger` - Directory the ledger is written to
+    /// * `rpc` - Client for the validator's RPC endpoint
+    pub fn start(program: &Path, ledger: &Path, rpc: &RpcClient) -> Result<Self, String> {
+        if !program.exists() {
+            return Err(format!(
+                "{} not found, run `anchor build` first",
+                program.display()
+            ));
+        }
+        let process = Command::new("solana-test-validator")
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(ledger)
+            .arg("--bpf-program")
+            .arg(aquachain::ID.to_string())
+            .arg(program)
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(|err| format!("failed to start solana-test-validator: {}", err))?;
+        let localnet = Self { process };
+
+        let started = Instant::now();
+        while rpc.get_health().is_err() {
+            if started.elapsed() > STARTUP_TIMEOUT {
+                return Err("solana-test-validator did not become healthy".to_string());
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+        Ok(localnet)
+    }
+
+    /// Blocks until the validator exits, e.g. on Ctrl-C
+    pub fn wait(mut self) -> Result<(), String> {
+        self.process
+            .wait()
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Drop for Localnet {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+    }
+}
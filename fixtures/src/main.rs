@@ -0,0 +1,423 @@
+//! Localnet fixture generator for frontend and integration development.
+//!
+//! ```text
+//! dev-fixtures            # start a localnet and populate it
+//! dev-fixtures snapshot   # write the same accounts as a snapshot, without a validator
+//! ```
+//!
+//! The fixtures are the agency's WTK, WATC and WST mints, three tariffs (one per tariff
+//! type), two reservoirs (one full, one low) and fifty demo consumers, each billed a few
+//! randomized meter readings. Every keypair and reading is derived from a seed, so the
+//! same seed always reproduces the same addresses and balances.
+//!
+//! By default a fresh `solana-test-validator` is started with the program preloaded and
+//! the fixtures are created by sending transactions. It keeps running until the process
+//! is interrupted. With `snapshot`, every account is instead written to `accounts/` in
+//! the format of `solana account --output json`, ready for `solana-test-validator
+//! --account-dir` or for bankrun, so tests can load an initialized agency instantly.
+//!
+//! The agency and consumer keypairs, and a `manifest.json` listing every address, are
+//! written to the output directory in both cases.
+//!
+//! # Environment
+//! * `AQUACHAIN_RPC_URL` - RPC endpoint of the validator (default `http://127.0.0.1:8899`)
+//! * `AQUACHAIN_PROGRAM_SO` - Compiled program to load (default `target/deploy/aquachain.so`)
+//! * `AQUACHAIN_FIXTURES_SEED` - Seed the fixtures are derived from (default `42`)
+//! * `AQUACHAIN_FIXTURES_DIR` - Output directory, also holding the ledger (default `target/dev-fixtures`)
+
+mod localnet;
+mod plan;
+mod snapshot;
+
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address, spl_associated_token_account::instruction as ata,
+    },
+    token::{self, spl_token},
+};
+use aquachain_client::{program_instruction, TransactionBuilder};
+use localnet::Localnet;
+use plan::{Plan, UNITS};
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{write_keypair_file, Keypair},
+    signer::Signer,
+    system_instruction, system_program,
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+    thread,
+    time::Duration,
+};
+
+const DEFAULT_RPC_URL: &str = "http://127.0.0.1:8899";
+const DEFAULT_PROGRAM_SO: &str = "target/deploy/aquachain.so";
+const DEFAULT_SEED: u64 = 42;
+const DEFAULT_FIXTURES_DIR: &str = "target/dev-fixtures";
+const USAGE: &str = "Usage: dev-fixtures [snapshot]";
+/// SOL airdropped to the agency, which pays for every account
+const AGENCY_AIRDROP_SOL: u64 = 100;
+
+/// Reads an optional environment variable, falling back to a default
+fn optional_var<T: FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &aquachain::ID).0
+}
+
+/// Sends instructions in a single transaction, with the first signer paying the fees
+fn send(
+    rpc: &RpcClient,
+    instructions: Vec<Instruction>,
+    signers: &[&Keypair],
+) -> Result<(), String> {
+    let signers: Vec<&dyn Signer> = signers
+        .iter()
+        .map(|signer| *signer as &dyn Signer)
+        .collect();
+    let blockhash = rpc.get_latest_blockhash().map_err(|err| err.to_string())?;
+    let transaction = TransactionBuilder::new()
+        .instructions(instructions)
+        .build(&signers, blockhash)?;
+    rpc.send_and_confirm_transaction(&transaction)
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+/// Funds the agency and waits until the airdrop has landed
+fn airdrop(rpc: &RpcClient, agency: &Pubkey) -> Result<(), String> {
+    rpc.request_airdrop(agency, AGENCY_AIRDROP_SOL * LAMPORTS_PER_SOL)
+        .map_err(|err| err.to_string())?;
+    for _ in 0..60 {
+        if rpc.get_balance(agency).map_err(|err| err.to_string())? > 0 {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_millis(500));
+    }
+    Err("the agency airdrop did not land".to_string())
+}
+
+/// Creates a mint with the agency as its authority
+fn create_mint(rpc: &RpcClient, agency: &Keypair, mint: &Keypair) -> Result<(), String> {
+    let rent = rpc
+        .get_minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN)
+        .map_err(|err| err.to_string())?;
+    let instructions = vec![
+        system_instruction::create_account(
+            &agency.pubkey(),
+            &mint.pubkey(),
+            rent,
+            spl_token::state::Mint::LEN as u64,
+            &token::ID,
+        ),
+        spl_token::instruction::initialize_mint2(
+            &token::ID,
+            &mint.pubkey(),
+            &agency.pubkey(),
+            None,
+            UNITS.currency_decimals,
+        )
+        .map_err(|err| err.to_string())?,
+    ];
+    send(rpc, instructions, &[agency, mint])
+}
+
+/// Creates every fixture account on the validator
+fn populate(rpc: &RpcClient, plan: &Plan) -> Result<(), String> {
+    let agency = &plan.agency;
+    let agency_key = agency.pubkey();
+    airdrop(rpc, &agency_key)?;
+
+    for mint in [&plan.wtk_mint, &plan.watc_mint, &plan.wst_mint] {
+        create_mint(rpc, agency, mint)?;
+    }
+    let tokens = pda(&[b"tokens", agency_key.as_ref()]);
+    let agency_stats = pda(&[b"agency_stats", agency_key.as_ref()]);
+    send(
+        rpc,
+        vec![program_instruction(
+            aquachain::accounts::InitializeTokens {
+                tokens,
+                agency_stats,
+                authority: agency_key,
+                system_program: system_program::ID,
+            },
+            aquachain::instruction::InitializeTokens {
+                water_token: plan.wtk_mint.pubkey(),
+                water_capacity_token: plan.watc_mint.pubkey(),
+                waste_token: plan.wst_mint.pubkey(),
+                units: UNITS,
+            },
+        )],
+        &[agency],
+    )?;
+
+    for tariff in &plan.tariffs {
+        send(
+            rpc,
+            vec![program_instruction(
+                aquachain::accounts::InitializeTariff {
+                    tariff: tariff.address(&agency_key),
+                    agency: agency_key,
+                    system_program: system_program::ID,
+                },
+                aquachain::instruction::InitializeTariff {
+                    tariff_key: tariff.tariff_key,
+                    water_rate: tariff.water_rate,
+                    waste_rate: tariff.waste_rate,
+                    tariff_type: tariff.tariff_type,
+                },
+            )],
+            &[agency],
+        )?;
+    }
+    for reservoir in &plan.reservoirs {
+        send(
+            rpc,
+            vec![program_instruction(
+                aquachain::accounts::InitializeReservoir {
+                    reservoir: reservoir.address(&agency_key),
+                    agency: agency_key,
+                    system_program: system_program::ID,
+                },
+                aquachain::instruction::InitializeReservoir {
+                    reservoir_key: reservoir.reservoir_key,
+                    current_level: reservoir.current_level,
+                    capacity: reservoir.capacity,
+                },
+            )],
+            &[agency],
+        )?;
+    }
+
+    let (wtk_mint, watc_mint) = (plan.wtk_mint.pubkey(), plan.watc_mint.pubkey());
+    for consumer in &plan.consumers {
+        let consumer_key = consumer.keypair.pubkey();
+        let (tariff_fixture, reservoir_fixture) = (
+            &plan.tariffs[consumer.tariff],
+            &plan.reservoirs[consumer.reservoir],
+        );
+        let (tariff_key, reservoir_key) =
+            (tariff_fixture.tariff_key, reservoir_fixture.reservoir_key);
+        let tariff = tariff_fixture.address(&agency_key);
+        let reservoir = reservoir_fixture.address(&agency_key);
+        let reservoir_daily_stats = pda(&[
+            b"reservoir_daily_stats",
+            agency_key.as_ref(),
+            reservoir_key.as_ref(),
+        ]);
+        let consumer_wtk = get_associated_token_address(&consumer_key, &wtk_mint);
+        let consumer_watc = get_associated_token_address(&consumer_key, &watc_mint);
+
+        send(
+            rpc,
+            vec![
+                ata::create_associated_token_account(
+                    &agency_key,
+                    &consumer_key,
+                    &wtk_mint,
+                    &token::ID,
+                ),
+                ata::create_associated_token_account(
+                    &agency_key,
+                    &consumer_key,
+                    &watc_mint,
+                    &token::ID,
+                ),
+                program_instruction(
+                    aquachain::accounts::RegisterConsumer {
+                        consumer: consumer_key,
+                        tariff,
+                        reservoir,
+                        agency: agency_key,
+                        tokens,
+                        agency_stats,
+                        consumer_watc,
+                        watc_mint,
+                        billing_authority: None,
+                        consumer_wtk: None,
+                        consumer_wst: None,
+                        system_program: system_program::ID,
+                        token_program: token::ID,
+                        associated_token_program: anchor_spl::associated_token::ID,
+                    },
+                    aquachain::instruction::RegisterConsumer {
+                        tariff_key,
+                        reservoir_key,
+                        contracted_capacity: consumer.contracted_capacity,
+                        block_rate: consumer.block_rate,
+                    },
+                ),
+            ],
+            &[agency, &consumer.keypair],
+        )?;
+
+        for &amount in &consumer.readings {
+            send(
+                rpc,
+                vec![program_instruction(
+                    aquachain::accounts::UseWater {
+                        consumer: consumer_key,
+                        tariff,
+                        reservoir,
+                        agency: agency_key,
+                        tokens,
+                        agency_stats,
+                        reservoir_daily_stats,
+                        consumer_wtk,
+                        consumer_watc,
+                        wtk_mint,
+                        watc_mint,
+                        token_program: token::ID,
+                        associated_token_program: anchor_spl::associated_token::ID,
+                        system_program: system_program::ID,
+                        drought_cover: None,
+                        drought_insurance: None,
+                        communal_point: None,
+                        irrigation_schedule: None,
+                        energy_oracle: None,
+                        level_forecast: None,
+                        shared_reservoir: None,
+                        source_mix: None,
+                        interruptible_policy: None,
+                        dr_bid: None,
+                    },
+                    aquachain::instruction::UseWater {
+                        tariff_key,
+                        reservoir_key,
+                        amount,
+                    },
+                )],
+                &[agency, &consumer.keypair],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the keypairs and a manifest of every fixture address to the output directory
+fn write_manifest(plan: &Plan, seed: u64, rpc_url: &str, dir: &Path) -> Result<(), String> {
+    let consumers_dir = dir.join("consumers");
+    fs::create_dir_all(&consumers_dir).map_err(|err| err.to_string())?;
+    let agency_path = dir.join("agency.json");
+    write_keypair_file(&plan.agency, &agency_path).map_err(|err| err.to_string())?;
+
+    let agency_key = plan.agency.pubkey();
+    let consumers = plan
+        .consumers
+        .iter()
+        .map(|consumer| {
+            let address = consumer.keypair.pubkey();
+            let path = consumers_dir.join(format!("{}.json", address));
+            write_keypair_file(&consumer.keypair, &path).map_err(|err| err.to_string())?;
+            Ok(json!({
+                "address": address.to_string(),
+                "keypair": path.display().to_string(),
+                "tariff": plan.tariffs[consumer.tariff].name,
+                "reservoir": plan.reservoirs[consumer.reservoir].name,
+                "contracted_capacity": consumer.contracted_capacity,
+                "block_rate": consumer.block_rate,
+                "readings": consumer.readings,
+            }))
+        })
+        .collect::<Result<Vec<Value>, String>>()?;
+
+    let manifest = json!({
+        "seed": seed,
+        "rpc_url": rpc_url,
+        "program_id": aquachain::ID.to_string(),
+        "agency": agency_key.to_string(),
+        "agency_keypair": agency_path.display().to_string(),
+        "mints": {
+            "wtk": plan.wtk_mint.pubkey().to_string(),
+            "watc": plan.watc_mint.pubkey().to_string(),
+            "wst": plan.wst_mint.pubkey().to_string(),
+        },
+        "tariffs": plan.tariffs.iter().map(|tariff| json!({
+            "name": tariff.name,
+            "tariff_key": tariff.tariff_key.to_string(),
+            "address": tariff.address(&agency_key).to_string(),
+            "tariff_type": format!("{:?}", tariff.tariff_type),
+        })).collect::<Vec<_>>(),
+        "reservoirs": plan.reservoirs.iter().map(|reservoir| json!({
+            "name": reservoir.name,
+            "reservoir_key": reservoir.reservoir_key.to_string(),
+            "address": reservoir.address(&agency_key).to_string(),
+        })).collect::<Vec<_>>(),
+        "consumers": consumers,
+    });
+    let contents = serde_json::to_string_pretty(&manifest).map_err(|err| err.to_string())?;
+    fs::write(dir.join("manifest.json"), contents).map_err(|err| err.to_string())
+}
+
+/// Starts a localnet and creates the fixtures on it
+fn run_localnet(plan: &Plan, seed: u64, dir: &Path) -> Result<(), String> {
+    let rpc_url = env::var("AQUACHAIN_RPC_URL").unwrap_or_else(|_| DEFAULT_RPC_URL.to_string());
+    let program = PathBuf::from(optional_var(
+        "AQUACHAIN_PROGRAM_SO",
+        DEFAULT_PROGRAM_SO.to_string(),
+    ));
+
+    let rpc = RpcClient::new(rpc_url.clone());
+    let localnet = Localnet::start(&program, &dir.join("ledger"), &rpc)?;
+    populate(&rpc, plan)?;
+    write_manifest(plan, seed, &rpc_url, dir)?;
+
+    println!(
+        "Created {} tariffs, {} reservoirs and {} consumers for agency {} (seed {})",
+        plan.tariffs.len(),
+        plan.reservoirs.len(),
+        plan.consumers.len(),
+        plan.agency.pubkey(),
+        seed
+    );
+    println!("Wrote {}", dir.join("manifest.json").display());
+    println!("Localnet running at {}, press Ctrl-C to stop", rpc_url);
+    localnet.wait()
+}
+
+/// Writes the fixtures as serialized accounts, without a validator
+fn run_snapshot(plan: &Plan, seed: u64, dir: &Path) -> Result<(), String> {
+    let count = snapshot::write(plan, &dir.join("accounts"))?;
+    write_manifest(plan, seed, DEFAULT_RPC_URL, dir)?;
+    println!(
+        "Wrote {} accounts for agency {} (seed {}) to {}",
+        count,
+        plan.agency.pubkey(),
+        seed,
+        dir.join("accounts").display()
+    );
+    Ok(())
+}
+
+fn main() {
+    let seed = optional_var("AQUACHAIN_FIXTURES_SEED", DEFAULT_SEED);
+    let dir = PathBuf::from(optional_var(
+        "AQUACHAIN_FIXTURES_DIR",
+        DEFAULT_FIXTURES_DIR.to_string(),
+    ));
+    let plan = Plan::new(seed);
+
+    let result = match env::args().nth(1).as_deref() {
+        None => run_localnet(&plan, seed, &dir),
+        Some("snapshot") => run_snapshot(&plan, seed, &dir),
+        Some(_) => Err(USAGE.to_string()),
+    };
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,212 @@
+use aquachain::state::{TariffType, UnitConfig};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{keypair_from_seed, Keypair},
+    signer::Signer,
+};
+
+/// Unit configuration of the fixture agency: litres billed per m³, three currency decimals
+pub const UNITS: UnitConfig = UnitConfig {
+    volume_scale: 1000,
+    currency_decimals: 3,
+};
+pub const CONSUMER_COUNT: usize = 50;
+/// Meter readings billed for each consumer
+pub const READINGS_PER_CONSUMER: usize = 3;
+
+/// A tariff created by the fixtures
+///
+/// # Fields
+/// * `name` - Human-readable name, written to the manifest
+/// * `tariff_key` - Unique identifier of the tariff
+/// * `water_rate` - Water rate, with three implied decimals
+/// * `waste_rate` - Waste rate, with three implied decimals
+/// * `tariff_type` - Tariff structure
+pub struct TariffFixture {
+    pub name: &'static str,
+    pub tariff_key: Pubkey,
+    pub water_rate: u64,
+    pub waste_rate: u64,
+    pub tariff_type: TariffType,
+}
+
+/// A reservoir created by the fixtures
+///
+/// # Fields
+/// * `name` - Human-readable name, written to the manifest
+/// * `reservoir_key` - Unique identifier of the reservoir
+/// * `current_level` - Raw current level
+/// * `capacity` - Raw capacity
+pub struct ReservoirFixture {
+    pub name: &'static str,
+    pub reservoir_key: Pubkey,
+    pub current_level: u64,
+    pub capacity: u64,
+}
+
+/// A demo consumer and the readings billed to it
+///
+/// # Fields
+/// * `keypair` - Keypair of the consumer account
+/// * `tariff` - Index of the assigned tariff
+/// * `reservoir` - Index of the assigned reservoir
+/// * `contracted_capacity` - Raw contracted capacity
+/// * `block_rate` - Block rate, with three implied decimals
+/// * `readings` - Raw volumes billed with `use_water`, in order
+pub struct ConsumerFixture {
+    pub keypair: Keypair,
+    pub tariff: usize,
+    pub reservoir: usize,
+    pub contracted_capacity: u64,
+    pub block_rate: u64,
+    pub readings: Vec<u64>,
+}
+
+/// Every account the fixtures create, derived from a single seed
+///
+/// # Fields
+/// * `agency` - Keypair of the agency, also the mint authority
+/// * `wtk_mint` - Keypair of the WTK mint
+/// * `watc_mint` - Keypair of the WATC mint
+/// * `wst_mint` - Keypair of the WST mint
+/// * `tariffs` - The three tariffs, one per tariff type
+/// * `reservoirs` - The two reservoirs, one full and one low
+/// * `consumers` - The demo consumers
+pub struct Plan {
+    pub agency: Keypair,
+    pub wtk_mint: Keypair,
+    pub watc_mint: Keypair,
+    pub wst_mint: Keypair,
+    pub tariffs: Vec<TariffFixture>,
+    pub reservoirs: Vec<ReservoirFixture>,
+    pub consumers: Vec<ConsumerFixture>,
+}
+
+impl TariffFixture {
+    /// Address of the tariff PDA under the given agency
+    pub fn address(&self, agency: &Pubkey) -> Pubkey {
+        let seeds: &[&[u8]] = &[b"tariff", agency.as_ref(), self.tariff_key.as_ref()];
+        Pubkey::find_program_address(seeds, &aquachain::ID).0
+    }
+}
+
+impl ReservoirFixture {
+    /// Address of the reservoir PDA under the given agency
+    pub fn address(&self, agency: &Pubkey) -> Pubkey {
+        let seeds: &[&[u8]] = &[b"reservoir", agency.as_ref(), self.reservoir_key.as_ref()];
+        Pubkey::find_program_address(seeds, &aquachain::ID).0
+    }
+}
+
+fn keypair(rng: &mut StdRng) -> Keypair {
+    keypair_from_seed(&rng.gen::<[u8; 32]>()).expect("32-byte seeds are valid")
+}
+
+impl Plan {
+    /// Derives the fixtures from a seed, so the same seed always yields the same
+    /// addresses, consumers and readings
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let agency = keypair(&mut rng);
+        let wtk_mint = keypair(&mut rng);
+        let watc_mint = keypair(&mut rng);
+        let wst_mint = keypair(&mut rng);
+
+        let tariffs = vec![
+            TariffFixture {
+                name: "residential",
+                tariff_key: keypair(&mut rng).pubkey(),
+                water_rate: 500,
+                waste_rate: 200,
+                tariff_type: TariffType::UniformIBT,
+            },
+            TariffFixture {
+                name: "irrigation",
+                tariff_key: keypair(&mut rng).pubkey(),
+                water_rate: 300,
+                waste_rate: 100,
+                tariff_type: TariffType::SeasonalIBT,
+            },
+            TariffFixture {
+                name: "industrial",
+                tariff_key: keypair(&mut rng).pubkey(),
+                water_rate: 800,
+                waste_rate: 400,
+                tariff_type: TariffType::SeasonalDBT,
+            },
+        ];
+        let reservoirs = vec![
+            ReservoirFixture {
+                name: "north",
+                reservoir_key: keypair(&mut rng).pubkey(),
+                current_level: 950_000,
+                capacity: 1_000_000,
+            },
+            ReservoirFixture {
+                name: "south",
+                reservoir_key: keypair(&mut rng).pubkey(),
+                current_level: 300_000,
+                capacity: 1_000_000,
+            },
+        ];
+
+        let consumers = (0..CONSUMER_COUNT)
+            .map(|_| {
+                let contracted_capacity = rng.gen_range(20..=100) * 1_000;
+                ConsumerFixture {
+                    keypair: keypair(&mut rng),
+                    tariff: rng.gen_range(0..tariffs.len()),
+                    reservoir: rng.gen_range(0..reservoirs.len()),
+                    contracted_capacity,
+                    block_rate: rng.gen_range(6..=12) * 100,
+                    // Readings add up to between half and one and a half times the
+                    // contracted capacity, so some consumers are billed above it
+                    readings: (0..READINGS_PER_CONSUMER)
+                        .map(|_| rng.gen_range(contracted_capacity / 6..=contracted_capacity / 2))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            agency,
+            wtk_mint,
+            watc_mint,
+            wst_mint,
+            tariffs,
+            reservoirs,
+            consumers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_is_reproducible() {
+        let (a, b) = (Plan::new(7), Plan::new(7));
+        assert_eq!(a.agency.pubkey(), b.agency.pubkey());
+        assert_eq!(
+            a.consumers[49].keypair.pubkey(),
+            b.consumers[49].keypair.pubkey()
+        );
+        assert_eq!(a.consumers[49].readings, b.consumers[49].readings);
+        assert_ne!(a.agency.pubkey(), Plan::new(8).agency.pubkey());
+    }
+
+    #[test]
+    fn test_plan_shape() {
+        let plan = Plan::new(42);
+        assert_eq!(plan.tariffs.len(), 3);
+        assert_eq!(plan.reservoirs.len(), 2);
+        assert_eq!(plan.consumers.len(), CONSUMER_COUNT);
+        for consumer in &plan.consumers {
+            assert!(consumer.tariff < 3 && consumer.reservoir < 2);
+            assert_eq!(consumer.readings.len(), READINGS_PER_CONSUMER);
+            assert!(consumer.readings.iter().all(|&reading| reading > 0));
+        }
+    }
+}
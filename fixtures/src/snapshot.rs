@@ -0,0 +1,339 @@
+use crate::plan::{Plan, UNITS};
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{program_option::COption, program_pack::Pack},
+    AccountSerialize, Space,
+};
+use anchor_spl::{associated_token::get_associated_token_address, token::spl_token};
+use aquachain::{
+    pricing::{calculate_total_cost, FixedPoint},
+    state::{
+        AgencyStats, BillBreakdown, Consumer, PricingSnapshot, Reservoir, ScarcityInputs, Tariff,
+        Tokens,
+    },
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_sdk::{
+    account::Account, native_token::LAMPORTS_PER_SOL, rent::Rent, signer::Signer, system_program,
+};
+use std::{fs, path::Path};
+
+/// Slot the snapshot is taken at, recorded as the start of tariffs, debts and capacity periods
+pub const SNAPSHOT_SLOT: u64 = 1;
+/// SOL held by the agency in the snapshot, to pay for accounts created by tests
+const AGENCY_SOL: u64 = 100;
+
+/// Serializes an Anchor account into a rent-exempt account owned by the program
+fn program_account<T: AccountSerialize + Space>(account: &T) -> Result<Account, String> {
+    let mut data = Vec::with_capacity(8 + T::INIT_SPACE);
+    account
+        .try_serialize(&mut data)
+        .map_err(|err| err.to_string())?;
+    data.resize(8 + T::INIT_SPACE, 0);
+    Ok(packed_account(data, aquachain::ID))
+}
+
+/// Wraps raw account data into a rent-exempt account
+fn packed_account(data: Vec<u8>, owner: Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Packs a mint with the agency as its authority
+fn mint_account(agency: &Pubkey, supply: u64) -> Account {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::Some(*agency),
+        supply,
+        decimals: UNITS.currency_decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    packed_account(data, spl_token::ID)
+}
+
+/// Packs a token account holding `amount` of `mint`
+fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        delegate: COption::None,
+        state: spl_token::state::AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    packed_account(data, spl_token::ID)
+}
+
+/// Builds every account of the fixture agency as the program would leave them
+///
+/// Consumers are registered and billed their readings exactly as `register_consumer`
+/// and `use_water` do, without running a validator.
+///
+/// # Returns
+/// The address and contents of every account, program excluded
+pub fn accounts(plan: &Plan) -> Result<Vec<(Pubkey, Account)>, String> {
+    let agency = plan.agency.pubkey();
+    let (wtk_mint, watc_mint, wst_mint) = (
+        plan.wtk_mint.pubkey(),
+        plan.watc_mint.pubkey(),
+        plan.wst_mint.pubkey(),
+    );
+    let mut accounts = vec![(
+        agency,
+        Account::new(AGENCY_SOL * LAMPORTS_PER_SOL, 0, &system_program::ID),
+    )];
+
+    accounts.push((
+        Pubkey::find_program_address(&[b"tokens", agency.as_ref()], &aquachain::ID).0,
+        program_account(&Tokens {
+            wtk: wtk_mint,
+            wst: wst_mint,
+            watc: watc_mint,
+            units: UNITS,
+        })?,
+    ));
+    for (index, tariff) in plan.tariffs.iter().enumerate() {
+        let assigned_consumers = plan.consumers.iter().filter(|c| c.tariff == index);
+        accounts.push((
+            tariff.address(&agency),
+            program_account(&Tariff {
+                water_rate: tariff.water_rate,
+                waste_rate: tariff.waste_rate,
+                tariff_type: tariff.tariff_type,
+                tariff_key: tariff.tariff_key,
+                currency_code: [0; 3],
+                activated_slot: SNAPSHOT_SLOT,
+                grace_period_slots: 0,
+                billing_period_slots: 0,
+                capacity_rollover_bps: 0,
+                block_threshold: 0,
+                per_capita_allowance: 0,
+                assigned_consumer_count: assigned_consumers.count() as u64,
+                conservation_baseline_bps: 0,
+                conservation_periods: 0,
+                bulk_rate: 0,
+                forecast_pricing: false,
+                max_multiplier: 0,
+                min_charge: 0,
+            })?,
+        ));
+    }
+    for (index, reservoir) in plan.reservoirs.iter().enumerate() {
+        let assigned_consumers = plan.consumers.iter().filter(|c| c.reservoir == index);
+        accounts.push((
+            reservoir.address(&agency),
+            program_account(&Reservoir {
+                current_level: reservoir.current_level,
+                capacity: reservoir.capacity,
+                reservoir_key: reservoir.reservoir_key,
+                assigned_consumer_count: assigned_consumers.count() as u64,
+                zone_geohash: [0; 12],
+                basin_id: 0,
+                pumping_intensity: 0,
+                emissions_factor: 0,
+                scarcity_curve: vec![],
+                smoothing_bps: 0,
+                smoothed_level: reservoir.current_level,
+                telemetry_authority: None,
+                max_level_rate: 0,
+                level_updated_slot: 0,
+                max_change_bps_per_hour: 0,
+                level_updated_at: 0,
+                alert_level_high: 0,
+                alert_level_low: 0,
+            })?,
+        ));
+    }
+
+    let (mut wtk_supply, mut watc_supply) = (0u64, 0u64);
+    let mut agency_stats = AgencyStats {
+        total_consumers: 0,
+        total_usage: 0,
+        total_waste: 0,
+        revenue_collected: 0,
+        co2e_grams: 0,
+    };
+    for fixture in &plan.consumers {
+        let address = fixture.keypair.pubkey();
+        let tariff = &plan.tariffs[fixture.tariff];
+        let reservoir = &plan.reservoirs[fixture.reservoir];
+        let mut consumer = Consumer {
+            block_rate: fixture.block_rate,
+            contracted_capacity: fixture.contracted_capacity,
+            assigned_tariff: tariff.tariff_key,
+            assigned_reservoir: reservoir.reservoir_key,
+            guarantor: None,
+            master_consumer: None,
+            tenant_share_bps: 0,
+            sub_consumer_count: 0,
+            owner: address,
+            autopay_enabled: false,
+            outstanding_water_debt: 0,
+            average_usage: 0,
+            estimated_usage: 0,
+            estimated_charge: 0,
+            budget_billing: false,
+            budget_amount: 0,
+            budget_paid: 0,
+            debt_since_slot: 0,
+            capacity_period_start: SNAPSHOT_SLOT,
+            period_usage: 0,
+            period_waste: 0,
+            period_watc_bought: 0,
+            conservation_streak: 0,
+            certificates_awarded: 0,
+            household_size: 0,
+            contract_expiry_slot: 0,
+            meter_reading: 0,
+            meter_read_at: 0,
+            geohash: [0; 12],
+            identity_hash: [0; 32],
+            identity_uri: None,
+            co2e_grams: 0,
+            last_bill: BillBreakdown::default(),
+            last_pricing: PricingSnapshot::default(),
+            flagged_charge: None,
+            interruptible: false,
+            curtailed_periods_compensated: 0,
+            usage_history: Default::default(),
+            usage_history_len: 0,
+            prepaid_balance: 0,
+            receipts_issued: 0,
+            capacity_approval_bps: 0,
+            capacity_approval_base: 0,
+            capacity_approval_period: 0,
+            last_scarcity_inputs: ScarcityInputs::default(),
+        };
+
+        let (mut wtk, mut watc) = (0u64, fixture.contracted_capacity);
+        for &amount in &fixture.readings {
+            let volume = |raw| UNITS.to_volume(raw).map_err(|err| err.to_string());
+            // Fixture tariffs have no block threshold, so the contracted capacity applies
+            let block_remaining = fixture
+                .contracted_capacity
+                .saturating_sub(consumer.period_usage);
+            let charge = UNITS
+                .to_currency(calculate_total_cost(
+                    volume(block_remaining)?,
+                    volume(amount)?,
+                    FixedPoint::from(tariff.water_rate),
+                    tariff.tariff_type,
+                    FixedPoint::from(fixture.block_rate),
+                    volume(reservoir.capacity)?,
+                    volume(reservoir.current_level)?,
+                    &tariff.charge_bounds(),
+                ))
+                .map_err(|err| err.to_string())?;
+            wtk += charge;
+            watc -= amount.min(watc);
+            consumer
+                .bill_water(charge, SNAPSHOT_SLOT)
+                .map_err(|err| err.to_string())?;
+            consumer.record_actual_usage(amount);
+            agency_stats.record_usage(amount);
+        }
+        agency_stats.record_consumer();
+        wtk_supply += wtk;
+        watc_supply += watc;
+
+        accounts.push((address, program_account(&consumer)?));
+        accounts.push((
+            get_associated_token_address(&address, &wtk_mint),
+            token_account(&wtk_mint, &address, wtk),
+        ));
+        accounts.push((
+            get_associated_token_address(&address, &watc_mint),
+            token_account(&watc_mint, &address, watc),
+        ));
+    }
+
+    accounts.push((
+        Pubkey::find_program_address(&[b"agency_stats", agency.as_ref()], &aquachain::ID).0,
+        program_account(&agency_stats)?,
+    ));
+    accounts.push((wtk_mint, mint_account(&agency, wtk_supply)));
+    accounts.push((watc_mint, mint_account(&agency, watc_supply)));
+    accounts.push((wst_mint, mint_account(&agency, 0)));
+    Ok(accounts)
+}
+
+/// Formats an account like `solana account --output json`, the format loaded by
+/// `solana-test-validator --account-dir`
+pub fn to_json(address: &Pubkey, account: &Account) -> Value {
+    json!({
+        "pubkey": address.to_string(),
+        "account": {
+            "lamports": account.lamports,
+            "data": [STANDARD.encode(&account.data), "base64"],
+            "owner": account.owner.to_string(),
+            "executable": account.executable,
+            "rentEpoch": account.rent_epoch,
+            "space": account.data.len(),
+        },
+    })
+}
+
+/// Writes every account of the snapshot to its own JSON file in `dir`
+pub fn write(plan: &Plan, dir: &Path) -> Result<usize, String> {
+    let accounts = accounts(plan)?;
+    fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    for (address, account) in &accounts {
+        let contents = serde_json::to_string_pretty(&to_json(address, account))
+            .map_err(|err| err.to_string())?;
+        fs::write(dir.join(format!("{}.json", address)), contents)
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(accounts.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AccountDeserialize;
+
+    #[test]
+    fn test_snapshot_matches_billing() {
+        let plan = Plan::new(42);
+        let accounts = accounts(&plan).unwrap();
+        // Agency, tokens, 3 tariffs, 2 reservoirs, stats, 3 mints and 3 accounts per consumer
+        assert_eq!(accounts.len(), 11 + 3 * plan.consumers.len());
+
+        let fixture = &plan.consumers[0];
+        let (_, account) = accounts
+            .iter()
+            .find(|(address, _)| *address == fixture.keypair.pubkey())
+            .unwrap();
+        let consumer = Consumer::try_deserialize(&mut account.data.as_slice()).unwrap();
+        assert_eq!(consumer.contracted_capacity, fixture.contracted_capacity);
+        assert!(consumer.outstanding_water_debt > 0);
+        assert_eq!(consumer.debt_since_slot, SNAPSHOT_SLOT);
+
+        let wtk = get_associated_token_address(&fixture.keypair.pubkey(), &plan.wtk_mint.pubkey());
+        let (_, account) = accounts
+            .iter()
+            .find(|(address, _)| *address == wtk)
+            .unwrap();
+        let balance = spl_token::state::Account::unpack(&account.data).unwrap();
+        assert_eq!(balance.amount, consumer.outstanding_water_debt);
+    }
+
+    #[test]
+    fn test_account_json() {
+        let account = Account::new(1, 2, &system_program::ID);
+        let value = to_json(&Pubkey::default(), &account);
+        assert_eq!(value["account"]["data"][0], "AAA=");
+        assert_eq!(value["account"]["space"], 2);
+    }
+}
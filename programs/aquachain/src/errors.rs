@@ -0,0 +1,225 @@
+//! Errors returned by every AquaChain instruction.
+//!
+//! All instructions share this single enum so clients can decode any error code
+//! unambiguously from the IDL. New variants are only ever appended, keeping the codes
+//! of existing errors stable.
+
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum CustomError {
+    #[msg("Invalid contracted capacity: must be greater than zero.")]
+    InvalidCapacity,
+    #[msg("Invalid rate: must be greater than zero.")]
+    InvalidRate,
+    #[msg("Invalid reservoir level: must be greater than zero and not exceed capacity.")]
+    InvalidReservoirLevel,
+    #[msg("Invalid reservoir capacity: must be greater than zero.")]
+    InvalidReservoirCapacity,
+    #[msg("Invalid amount: must be greater than zero.")]
+    InvalidAmount,
+    #[msg("Unauthorized: only the owner can perform this action.")]
+    Unauthorized,
+    #[msg("Overpaid: payment exceeds the necessary amount.")]
+    OverPayment,
+    #[msg("Invalid unit configuration: volume scale must be non-zero and currency decimals at most 18.")]
+    InvalidUnitConfig,
+    #[msg("Math overflow: the computed amount does not fit in a token balance.")]
+    MathOverflow,
+    #[msg("Invalid currency code: must be three uppercase ISO 4217 letters.")]
+    InvalidCurrencyCode,
+    #[msg("Invalid exchange rate: must be greater than zero.")]
+    InvalidExchangeRate,
+    #[msg("Stale oracle: the posted rate or price is unset or too old to be used.")]
+    StaleOracle,
+    #[msg("Insufficient delegation: the approved delegation does not cover this amount.")]
+    InsufficientDelegation,
+    #[msg("Invalid share: basis points must not exceed 10,000.")]
+    InvalidShare,
+    #[msg("Invalid consumer hierarchy: sub-consumers cannot be nested or linked twice.")]
+    InvalidConsumerHierarchy,
+    #[msg("Outstanding debt: all water and waste debt must be settled first.")]
+    OutstandingDebt,
+    #[msg("Auto-pay is not enabled for this consumer.")]
+    AutopayDisabled,
+    #[msg("Insufficient capacity: adjustment exceeds the WATC balance.")]
+    InsufficientCapacity,
+    #[msg("Tariff version unavailable: no stored rates for that slot.")]
+    TariffVersionUnavailable,
+    #[msg("No usage history: no actual readings to estimate from.")]
+    NoUsageHistory,
+    #[msg("No estimate pending: there is no estimated usage to true up.")]
+    NoEstimatePending,
+    #[msg("Budget billing is not enabled for this consumer.")]
+    BudgetBillingDisabled,
+    #[msg("Grace period active: the debt is not yet overdue.")]
+    GracePeriodActive,
+    #[msg("Invalid Merkle proof: the allocation is not part of the tree.")]
+    InvalidMerkleProof,
+    #[msg("Allocation exhausted: not enough unclaimed capacity in the tree.")]
+    AllocationExhausted,
+    #[msg("Consumer tree full: no leaves left to append to.")]
+    ConsumerTreeFull,
+    #[msg("Unknown Merkle root: the proof was not built against a recent root.")]
+    UnknownMerkleRoot,
+    #[msg("Program paused: the agency has suspended this operation.")]
+    ProgramPaused,
+    #[msg("Consumer suspended: the account is suspended and cannot be billed or served.")]
+    ConsumerSuspended,
+    #[msg("Capacity refresh not due: the billing period has not ended or is not configured.")]
+    CapacityRefreshNotDue,
+    #[msg("Invalid contract expiry: the new expiry slot must be in the future.")]
+    InvalidContractExpiry,
+    #[msg("Empty batch: at least one consumer account must be provided.")]
+    EmptyBatch,
+    #[msg("Invalid batch index: a reading refers to an account pair that was not passed.")]
+    InvalidBatchIndex,
+    #[msg(
+        "Reservoir in use: consumers must be reassigned before the reservoir is decommissioned."
+    )]
+    ReservoirInUse,
+    #[msg("Tariff in use: consumers must be reassigned before the tariff is closed.")]
+    TariffInUse,
+    #[msg("Invalid document hash: the SHA-256 hash of the document must not be empty.")]
+    InvalidDocumentHash,
+    #[msg("Invalid identity URI: the URI must not be empty or exceed the maximum length.")]
+    InvalidIdentityUri,
+    #[msg("Missing role: the signer holds no role permitting this operation.")]
+    MissingRole,
+    #[msg("Invalid role member: the agency key holds every role and cannot be granted one.")]
+    InvalidRoleMember,
+    #[msg("Invalid session expiry: the session must expire in the future and within the maximum duration.")]
+    InvalidSessionExpiry,
+    #[msg("Session expired: the session key has expired or is not scoped for this operation.")]
+    SessionExpired,
+    #[msg("Invalid meter reading: the reading is below the previous register value.")]
+    InvalidMeterReading,
+    #[msg("Invalid meter signature: the payload was not verified as signed by the meter device.")]
+    InvalidMeterSignature,
+    #[msg("Stale meter reading: the payload is not newer than the last reading or is dated in the future.")]
+    StaleMeterReading,
+    #[msg("Invalid geohash: the geohash must use the base32 alphabet and be padded with zeroes.")]
+    InvalidGeohash,
+    #[msg("Outside zone: the consumer is not located within the reservoir's zone.")]
+    OutsideZone,
+    #[msg("Premium already paid: the drought premium for this period has been collected.")]
+    PremiumAlreadyPaid,
+    #[msg("Invalid drought cover: the cover or pool belongs to another consumer or reservoir.")]
+    InvalidDroughtCover,
+    #[msg("Invalid weather window: the window must be non-empty and not yet started.")]
+    InvalidWeatherWindow,
+    #[msg("Invalid derivative status: the derivative is not in a state allowing this operation.")]
+    InvalidDerivativeStatus,
+    #[msg("Invalid rainfall observation: observations must fall within the window and be newer than the last.")]
+    InvalidRainfallObservation,
+    #[msg("Weather window open: the derivative cannot settle before its window ends.")]
+    WeatherWindowOpen,
+    #[msg("Forward not due: the capacity bought forward cannot be delivered before its period starts.")]
+    ForwardNotDue,
+    #[msg("Invalid order side: the orders must be an ask and a bid.")]
+    InvalidOrderSide,
+    #[msg("Self trade: a consumer cannot trade with its own order.")]
+    SelfTrade,
+    #[msg("Orders do not cross: the bid price is below the ask price.")]
+    OrdersDoNotCross,
+    #[msg("Order expired: orders only trade within the capacity period they were placed in.")]
+    OrderExpired,
+    #[msg("Trade cap exceeded: the consumer cannot buy more WATC this period.")]
+    TradeCapExceeded,
+    #[msg("Offer sold out: every listed redemption of the offer has been made.")]
+    OfferSoldOut,
+    #[msg("Invalid perk name: the name must be non-empty and at most 32 bytes.")]
+    InvalidPerkName,
+    #[msg("Conservation streak incomplete: the consumer has not conserved for enough periods.")]
+    ConservationStreakIncomplete,
+    #[msg("Communal point full: the standpipe has the maximum number of members.")]
+    CommunalPointFull,
+    #[msg("Member already registered: the wallet is already a member of the standpipe.")]
+    MemberAlreadyRegistered,
+    #[msg("Not a communal member: the wallet is not a member of the standpipe.")]
+    NotCommunalMember,
+    #[msg("Invalid communal point: the communal point belongs to another consumer.")]
+    InvalidCommunalPoint,
+    #[msg("Invalid work order status: the work order does not allow this operation.")]
+    InvalidWorkOrderStatus,
+    #[msg("Invalid work order: the work order is not the one this order depends on.")]
+    InvalidWorkOrder,
+    #[msg("Complaint already resolved: a complaint can only be resolved once.")]
+    ComplaintAlreadyResolved,
+    #[msg("Bulk delivery unavailable: the consumer's tariff does not offer bulk delivery.")]
+    BulkDeliveryUnavailable,
+    #[msg("Insufficient reservoir level: the reservoir holds less water than requested.")]
+    InsufficientReservoirLevel,
+    #[msg("Invalid irrigation schedule: the seasons overlap or the schedule belongs to another consumer.")]
+    InvalidIrrigationSchedule,
+    #[msg("Invalid conveyance: water must be conveyed between two different reservoirs.")]
+    InvalidConveyance,
+    #[msg("Missing regulator approval: the operation must be approved by a regulator other than the agency.")]
+    MissingRegulatorApproval,
+    #[msg("Invalid energy oracle: the oracle belongs to another agency.")]
+    InvalidEnergyOracle,
+    #[msg("Offset exceeds emissions: more CO2e would be retired than the agency has emitted.")]
+    OffsetExceedsEmissions,
+    #[msg("Invalid scarcity curve: bands must rise to a full reservoir with non-zero multipliers.")]
+    InvalidScarcityCurve,
+    #[msg("Invalid level forecast: the forecast is malformed or projects another reservoir.")]
+    InvalidLevelForecast,
+    #[msg("Forecast unavailable: the tariff prices on a level forecast that does not cover the current slot.")]
+    ForecastUnavailable,
+    #[msg("No charge to verify: no metered reading of the consumer has been charged.")]
+    NoChargeToVerify,
+    #[msg("Charge already flagged: a flagged charge of the consumer is still unresolved.")]
+    ChargeAlreadyFlagged,
+    #[msg("No flagged charge: the consumer has no charge awaiting resolution.")]
+    NoFlaggedCharge,
+    #[msg("Invalid agency bond: the bond accounts do not match the agency's bond.")]
+    InvalidAgencyBond,
+    #[msg("Implausible level change: the level changed faster than the reservoir's plausible rate.")]
+    ImplausibleLevelChange,
+    #[msg("Invalid sensor: the sensor belongs to another reservoir or was passed twice.")]
+    InvalidSensor,
+    #[msg("No recent sensor readings: none of the reservoir's sensors has reported recently.")]
+    NoRecentSensorReadings,
+    #[msg("Invalid alert levels: the high alert level must be within capacity and above the low alert level.")]
+    InvalidAlertLevels,
+    #[msg("Invalid archive shard: the shard does not cover the current day.")]
+    InvalidArchiveShard,
+    #[msg("Archive shard retained: the shard holds days within the retention window.")]
+    ArchiveShardRetained,
+    #[msg("Invalid draw rights: shares must name distinct agencies and total 10,000 basis points.")]
+    InvalidDrawRights,
+    #[msg("Invalid shared reservoir: the shared reservoir covers another reservoir or grants the agency no draw right.")]
+    InvalidSharedReservoir,
+    #[msg("Outstanding settlement: the agency still owes volume drawn beyond its share.")]
+    OutstandingSettlement,
+    #[msg("Invalid source mix: every source needs a share and the shares must total 10,000 basis points.")]
+    InvalidSourceMix,
+    #[msg("Invalid interruptible policy: the policy belongs to another agency or its terms are out of range.")]
+    InvalidInterruptiblePolicy,
+    #[msg("Supply curtailed: interruptible supply is curtailed by an emergency.")]
+    SupplyCurtailed,
+    #[msg("Curtailment uncompensated: the consumer is still owed compensation for a curtailment.")]
+    CurtailmentUncompensated,
+    #[msg("Invalid demand-response event: the window must start in the future and end after it starts.")]
+    InvalidDrEvent,
+    #[msg("Demand-response event closed: bids close when the window starts and settle once it has ended.")]
+    DrEventClosed,
+    #[msg("Invalid curtailment bid: the bid exceeds the consumer's baseline or the volume left to bid.")]
+    InvalidCurtailmentBid,
+    #[msg("Curtailment already settled: the bid was already paid.")]
+    CurtailmentAlreadySettled,
+    #[msg("Gateway suspended: the agency has suspended the payment gateway's licence.")]
+    GatewaySuspended,
+    #[msg("Capacity change not approved: the change exceeds the consumer's standing approval and needs its signature.")]
+    CapacityChangeNotApproved,
+    #[msg("Missing consumer signature: burning the consumer's tokens needs its signature or a delegation to the billing authority.")]
+    MissingConsumerSignature,
+    #[msg("Invalid delegated account: the token account is not the consumer's account for the agency's mint.")]
+    InvalidDelegatedAccount,
+    #[msg("Missing billing authority: token accounts can only be delegated to the agency's billing authority.")]
+    MissingBillingAuthority,
+    #[msg("Invalid regulator: the regulator must be a key other than the agency.")]
+    InvalidRegulator,
+    #[msg("Reserved role: the Regulator role is granted and revoked only by the agency's regulator authority.")]
+    ReservedRole,
+}
@@ -0,0 +1,562 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{AdjustmentReason, BillBreakdown, ComplaintCategory, ReservoirAlertKind};
+
+/// Emitted when a consumer account is reassigned to a new account holder
+///
+/// # Fields
+/// * `consumer` - The consumer account whose holder changed
+/// * `previous_owner` - The account holder before the reassignment
+/// * `new_owner` - The account holder after the reassignment
+/// * `slot` - The slot at which the reassignment took place
+#[event]
+pub struct AccountHolderReassigned {
+    pub consumer: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub slot: u64,
+}
+
+/// Emitted when the agency mints or burns a delta of a consumer's WATC balance
+///
+/// # Fields
+/// * `consumer` - The consumer account whose capacity was adjusted
+/// * `delta` - Signed amount of WATC minted (positive) or burned (negative)
+/// * `reason` - Reason code for the adjustment
+/// * `balance` - The consumer's WATC balance after the adjustment
+/// * `slot` - The slot at which the adjustment took place
+#[event]
+pub struct CapacityAdjusted {
+    pub consumer: Pubkey,
+    pub delta: i64,
+    pub reason: AdjustmentReason,
+    pub balance: u64,
+    pub slot: u64,
+}
+
+/// Emitted when a consumer's capacity or block rate is updated with its consent
+///
+/// # Fields
+/// * `consumer` - The consumer account that was updated
+/// * `contracted_capacity` - The consumer's contracted capacity after the update
+/// * `block_rate` - The consumer's block rate after the update
+/// * `pre_approved` - Whether the change fell within the consumer's standing approval
+///   instead of being signed by it
+/// * `slot` - The slot at which the update took place
+#[event]
+pub struct ConsumerUpdateConsented {
+    pub consumer: Pubkey,
+    pub contracted_capacity: u64,
+    pub block_rate: u64,
+    pub pre_approved: bool,
+    pub slot: u64,
+}
+
+/// Emitted when the agency reduces a consumer's capacity without its consent
+///
+/// # Fields
+/// * `consumer` - The consumer account whose capacity was reduced
+/// * `previous_capacity` - The consumer's contracted capacity before the reduction
+/// * `contracted_capacity` - The consumer's contracted capacity after the reduction
+/// * `emergency` - Whether the consumer's reservoir was low at the time
+/// * `regulator` - The regulator that co-signed the reduction, if any
+/// * `slot` - The slot at which the reduction took place
+#[event]
+pub struct ConsumerCapacityMandated {
+    pub consumer: Pubkey,
+    pub previous_capacity: u64,
+    pub contracted_capacity: u64,
+    pub emergency: bool,
+    pub regulator: Option<Pubkey>,
+    pub slot: u64,
+}
+
+/// Emitted when the charge for a past billing period is recomputed
+///
+/// # Fields
+/// * `consumer` - The consumer account that was rebilled
+/// * `period_slot` - A slot within the billing period that was corrected
+/// * `billed_amount` - The WTK originally charged for the period
+/// * `corrected_amount` - The WTK the period should have been charged
+/// * `slot` - The slot at which the correction took place
+#[event]
+pub struct PeriodRebilled {
+    pub consumer: Pubkey,
+    pub period_slot: u64,
+    pub billed_amount: u64,
+    pub corrected_amount: u64,
+    pub slot: u64,
+}
+
+/// Emitted when a consumer's budget billing year is reconciled
+///
+/// # Fields
+/// * `consumer` - The consumer account that was reconciled
+/// * `levelized_paid` - Levelized payments collected over the year
+/// * `difference` - Remaining true cost settled at reconciliation
+/// * `budget_amount` - Levelized amount for the next year
+/// * `slot` - The slot at which the reconciliation took place
+#[event]
+pub struct BudgetBillingReconciled {
+    pub consumer: Pubkey,
+    pub levelized_paid: u64,
+    pub difference: u64,
+    pub budget_amount: u64,
+    pub slot: u64,
+}
+
+/// Emitted when a consumer is billed for metered water usage
+///
+/// # Fields
+/// * `consumer` - The consumer account that was billed
+/// * `volume` - Raw volume of water reported by the meter
+/// * `charge` - Amount of WTK charged for the usage
+/// * `outstanding` - The consumer's outstanding water debt after billing
+/// * `breakdown` - How the charge was composed, before any communal balance drawn
+/// * `slot` - The slot at which the usage was billed
+#[event]
+pub struct WaterBilled {
+    pub consumer: Pubkey,
+    pub volume: u64,
+    pub charge: u64,
+    pub outstanding: u64,
+    pub breakdown: BillBreakdown,
+    pub slot: u64,
+}
+
+/// Emitted when a consumer pays water debt
+///
+/// # Fields
+/// * `consumer` - The consumer account that paid
+/// * `amount` - Amount of WTK paid
+/// * `outstanding` - The consumer's outstanding water debt after the payment
+/// * `slot` - The slot at which the payment was made
+#[event]
+pub struct PaymentReceived {
+    pub consumer: Pubkey,
+    pub amount: u64,
+    pub outstanding: u64,
+    pub slot: u64,
+}
+
+/// Emitted when a new level is recorded for a reservoir
+///
+/// # Fields
+/// * `reservoir_key` - Unique identifier of the reservoir
+/// * `current_level` - The reservoir's new current level
+/// * `smoothed_level` - The reservoir's smoothed level after the update
+/// * `slot` - The slot at which the level was reported
+#[event]
+pub struct ReservoirLevelUpdated {
+    pub reservoir_key: Pubkey,
+    pub current_level: u64,
+    pub smoothed_level: u64,
+    pub slot: u64,
+}
+
+/// Emitted when the agency changes the configuration of a reservoir
+///
+/// # Fields
+/// * `reservoir_key` - Unique identifier of the reservoir
+/// * `capacity` - The reservoir's new capacity
+/// * `slot` - The slot at which the configuration changed
+#[event]
+pub struct ReservoirConfigUpdated {
+    pub reservoir_key: Pubkey,
+    pub capacity: u64,
+    pub slot: u64,
+}
+
+/// Emitted when a reservoir level update leaves it below its low-level threshold
+///
+/// # Fields
+/// * `reservoir_key` - Unique identifier of the reservoir
+/// * `current_level` - The reservoir's new current level
+/// * `capacity` - The reservoir's capacity
+/// * `slot` - The slot at which the level was reported
+#[event]
+pub struct ReservoirLow {
+    pub reservoir_key: Pubkey,
+    pub current_level: u64,
+    pub capacity: u64,
+    pub slot: u64,
+}
+
+/// Emitted when a level update crosses one of a reservoir's dam safety thresholds
+///
+/// # Fields
+/// * `reservoir_key` - Unique identifier of the reservoir
+/// * `kind` - Whether the high or the low alert level was crossed
+/// * `threshold` - The alert level crossed
+/// * `current_level` - The reservoir's new current level
+/// * `slot` - The slot at which the level was reported
+#[event]
+pub struct ReservoirAlert {
+    pub reservoir_key: Pubkey,
+    pub kind: ReservoirAlertKind,
+    pub threshold: u64,
+    pub current_level: u64,
+    pub slot: u64,
+}
+
+/// Emitted when an inflow fills a reservoir past its capacity
+///
+/// # Fields
+/// * `reservoir_key` - Unique identifier of the reservoir
+/// * `spilled` - Volume spilled over the reservoir's capacity
+/// * `total_spilled` - Total volume the reservoir has spilled
+/// * `capacity` - The reservoir's capacity, at which its level is capped
+/// * `slot` - The slot at which the inflow was recorded
+#[event]
+pub struct ReservoirSpilled {
+    pub reservoir_key: Pubkey,
+    pub spilled: u64,
+    pub total_spilled: u64,
+    pub capacity: u64,
+    pub slot: u64,
+}
+
+/// Emitted when an agency's consumers draw beyond its share of a shared reservoir
+///
+/// # Fields
+/// * `reservoir_key` - Unique identifier of the shared reservoir
+/// * `agency` - The agency that drew beyond its share
+/// * `exceeded` - Volume of the draw beyond the agency's share
+/// * `owed` - Total volume the agency owes the other agencies
+/// * `slot` - The slot at which the draw was recorded
+#[event]
+pub struct SharedDrawExceeded {
+    pub reservoir_key: Pubkey,
+    pub agency: Pubkey,
+    pub exceeded: u64,
+    pub owed: u64,
+    pub slot: u64,
+}
+
+/// Emitted when the operator of a shared reservoir settles volume an agency owed
+///
+/// # Fields
+/// * `reservoir_key` - Unique identifier of the shared reservoir
+/// * `agency` - The agency whose obligation was settled
+/// * `settled` - Volume cleared from the obligation
+/// * `owed` - Volume the agency still owes
+/// * `slot` - The slot at which the settlement was recorded
+#[event]
+pub struct SharedDrawSettled {
+    pub reservoir_key: Pubkey,
+    pub agency: Pubkey,
+    pub settled: u64,
+    pub owed: u64,
+    pub slot: u64,
+}
+
+/// Emitted when a consumer account is closed into a leaf of a consumer tree
+///
+/// # Fields
+/// * `consumer` - The consumer account that was compressed
+/// * `tree_key` - Unique identifier of the consumer tree
+/// * `leaf_index` - Position of the consumer's leaf in the tree
+/// * `data` - The consumer's serialized account data, hashed into the leaf
+/// * `slot` - The slot at which the consumer was compressed
+#[event]
+pub struct ConsumerCompressed {
+    pub consumer: Pubkey,
+    pub tree_key: Pubkey,
+    pub leaf_index: u64,
+    pub data: Vec<u8>,
+    pub slot: u64,
+}
+
+/// Emitted when water is billed to a consumer whose fixed-term contract has expired
+///
+/// # Fields
+/// * `consumer` - The consumer account whose contract expired
+/// * `expiry_slot` - The slot at which the contract expired
+/// * `slot` - The slot at which the usage was billed
+#[event]
+pub struct ContractExpired {
+    pub consumer: Pubkey,
+    pub expiry_slot: u64,
+    pub slot: u64,
+}
+
+/// Emitted when an ask and a bid are matched on the WATC marketplace
+///
+/// # Fields
+/// * `ask_order` - The order the WATC was sold from
+/// * `bid_order` - The order the WATC was bought by
+/// * `amount` - Raw volume of WATC traded
+/// * `price` - Settlement base units paid per raw unit of WATC
+/// * `fee` - Settlement base units paid to the agency
+/// * `slot` - The slot at which the trade took place
+#[event]
+pub struct WatcTraded {
+    pub ask_order: Pubkey,
+    pub bid_order: Pubkey,
+    pub amount: u64,
+    pub price: u64,
+    pub fee: u64,
+    pub slot: u64,
+}
+
+/// Emitted when a member tops up the shared balance of a communal point
+///
+/// # Fields
+/// * `communal_point` - The communal point topped up
+/// * `member` - The member wallet that paid for the top-up
+/// * `amount` - Amount credited to the shared balance, in WTK base units
+/// * `settlement_amount` - Settlement base units paid to the agency for the top-up
+/// * `prepaid_balance` - The shared balance after the top-up
+/// * `slot` - The slot at which the top-up was made
+#[event]
+pub struct CommunalTopUp {
+    pub communal_point: Pubkey,
+    pub member: Pubkey,
+    pub amount: u64,
+    pub settlement_amount: u64,
+    pub prepaid_balance: u64,
+    pub slot: u64,
+}
+
+/// Emitted when a wallet tops up a consumer's prepaid balance
+///
+/// # Fields
+/// * `consumer` - The consumer topped up
+/// * `payer` - The wallet that paid for the top-up
+/// * `amount` - Amount credited to the prepaid balance, in WTK base units
+/// * `settlement_amount` - Settlement base units paid to the agency for the top-up
+/// * `prepaid_balance` - The prepaid balance after the top-up
+/// * `slot` - The slot at which the top-up was credited
+#[event]
+pub struct PrepaidToppedUp {
+    pub consumer: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+    pub settlement_amount: u64,
+    pub prepaid_balance: u64,
+    pub slot: u64,
+}
+
+/// Emitted when a gateway's attested mobile-money payment is credited to a consumer
+///
+/// # Fields
+/// * `gateway` - The gateway that attested the payment
+/// * `consumer` - The consumer credited
+/// * `payment_id` - The gateway's identifier of the payment
+/// * `amount` - Amount credited to the prepaid balance
+/// * `prepaid_balance` - The prepaid balance after the payment
+/// * `slot` - The slot at which the payment was recorded
+#[event]
+pub struct OffchainPaymentRecorded {
+    pub gateway: Pubkey,
+    pub consumer: Pubkey,
+    pub payment_id: [u8; 32],
+    pub amount: u64,
+    pub prepaid_balance: u64,
+    pub slot: u64,
+}
+
+/// Emitted when the agency resolves a consumer complaint
+///
+/// # Fields
+/// * `complaint` - The complaint account that was resolved
+/// * `consumer` - The consumer that filed the complaint
+/// * `category` - Subject of the complaint
+/// * `opened_slot` - The slot at which the complaint was filed
+/// * `resolved_slot` - The slot at which the complaint was resolved
+/// * `goodwill` - Amount of AquaCoin credited for breaching the SLA
+#[event]
+pub struct ComplaintResolved {
+    pub complaint: Pubkey,
+    pub consumer: Pubkey,
+    pub category: ComplaintCategory,
+    pub opened_slot: u64,
+    pub resolved_slot: u64,
+    pub goodwill: u64,
+}
+
+/// Emitted when a tanker driver dispenses a bulk delivery to a consumer
+///
+/// # Fields
+/// * `consumer` - The consumer billed for the delivery
+/// * `driver` - The driver key that dispensed the water
+/// * `reservoir` - The reservoir the water was drawn from
+/// * `volume` - The volume dispensed
+/// * `charge` - Amount of WTK billed at the tariff's bulk rate
+/// * `slot` - The slot at which the water was dispensed
+#[event]
+pub struct BulkDispensed {
+    pub consumer: Pubkey,
+    pub driver: Pubkey,
+    pub reservoir: Pubkey,
+    pub volume: u64,
+    pub charge: u64,
+    pub slot: u64,
+}
+
+/// Emitted when water is conveyed from one reservoir to another
+///
+/// # Fields
+/// * `source` - Key of the reservoir the water was drawn from
+/// * `destination` - Key of the reservoir the water was delivered to
+/// * `volume` - The volume conveyed
+/// * `regulator` - The regulator that approved an inter-basin transfer, if any
+/// * `permit_hash` - SHA-256 hash of the transfer permit, all zeros within a basin
+/// * `slot` - The slot at which the water was conveyed
+#[event]
+pub struct WaterConveyed {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub volume: u64,
+    pub regulator: Option<Pubkey>,
+    pub permit_hash: [u8; 32],
+    pub slot: u64,
+}
+
+/// Emitted when the agency retires carbon credits against its emissions
+///
+/// # Fields
+/// * `agency` - The agency retiring the credits
+/// * `credit_mint` - The carbon-credit token mint
+/// * `amount` - Credits burned, in base units of the credit mint
+/// * `co2e_grams` - Emissions offset by the credits burned, in grams CO2e
+/// * `co2e_retired` - Emissions offset by all of the agency's retirements, in grams CO2e
+/// * `slot` - The slot at which the credits were retired
+#[event]
+pub struct CarbonOffsetRetired {
+    pub agency: Pubkey,
+    pub credit_mint: Pubkey,
+    pub amount: u64,
+    pub co2e_grams: u64,
+    pub co2e_retired: u64,
+    pub slot: u64,
+}
+
+/// Emitted when projected levels of a reservoir are posted
+///
+/// # Fields
+/// * `reservoir_key` - Unique identifier of the reservoir the forecast projects
+/// * `start_slot` - Slot at which the first projected period begins
+/// * `period_slots` - Length of each projected period in slots
+/// * `levels` - Projected reservoir level for each period, in order
+#[event]
+pub struct LevelForecastPosted {
+    pub reservoir_key: Pubkey,
+    pub start_slot: u64,
+    pub period_slots: u64,
+    pub levels: Vec<u64>,
+}
+
+/// Emitted when a consumer's last charge differs from its recomputation
+///
+/// # Fields
+/// * `consumer` - The consumer whose charge was flagged
+/// * `verifier` - The key that submitted the check
+/// * `billed` - Usage charge that was billed
+/// * `recomputed` - Usage charge recomputed from the pricing snapshot
+/// * `charge_slot` - The slot at which the flagged reading was charged
+/// * `slot` - The slot at which the charge was flagged
+#[event]
+pub struct ChargeDiscrepancyFlagged {
+    pub consumer: Pubkey,
+    pub verifier: Pubkey,
+    pub billed: u64,
+    pub recomputed: u64,
+    pub charge_slot: u64,
+    pub slot: u64,
+}
+
+/// Emitted when a regulator confirms or dismisses a flagged charge
+///
+/// # Fields
+/// * `consumer` - The consumer whose charge was flagged
+/// * `regulator` - The regulator that resolved the flag
+/// * `billed` - Usage charge that was billed
+/// * `recomputed` - Usage charge recomputed from the pricing snapshot
+/// * `confirmed` - Whether the mischarge was confirmed
+/// * `compensation` - Amount paid to the consumer from the agency's bond
+/// * `slot` - The slot at which the flag was resolved
+#[event]
+pub struct ChargeDiscrepancyResolved {
+    pub consumer: Pubkey,
+    pub regulator: Pubkey,
+    pub billed: u64,
+    pub recomputed: u64,
+    pub confirmed: bool,
+    pub compensation: u64,
+    pub slot: u64,
+}
+
+/// Emitted when an agency curtails the supply of its interruptible consumers
+///
+/// # Fields
+/// * `agency` - The agency curtailing supply
+/// * `periods` - Number of periods supply is curtailed for
+/// * `curtailed_until_slot` - The slot until which supply is curtailed
+/// * `slot` - The slot at which supply was curtailed
+#[event]
+pub struct InterruptibleSupplyCurtailed {
+    pub agency: Pubkey,
+    pub periods: u64,
+    pub curtailed_until_slot: u64,
+    pub slot: u64,
+}
+
+/// Emitted when an interruptible consumer is compensated for curtailed periods
+///
+/// # Fields
+/// * `consumer` - The consumer compensated
+/// * `periods` - Number of curtailed periods compensated
+/// * `compensation` - Amount of AquaCoin minted to the consumer
+/// * `slot` - The slot at which the consumer was compensated
+#[event]
+pub struct CurtailmentCompensated {
+    pub consumer: Pubkey,
+    pub periods: u64,
+    pub compensation: u64,
+    pub slot: u64,
+}
+
+/// Emitted when an agency calls for reductions in usage during a shortage window
+///
+/// # Fields
+/// * `agency` - The agency calling the event
+/// * `event_key` - Unique identifier for the event
+/// * `reservoir_key` - The reservoir the event relieves
+/// * `start_slot` - The first slot of the window
+/// * `end_slot` - The slot at which the window closes
+/// * `target_volume` - Total reduction sought, in raw volume units
+/// * `payment_rate` - Stablecoin base units paid per raw volume unit of verified reduction
+/// * `weather_adjustment_bps` - Scaling of consumers' baselines for the forecast weather
+#[event]
+pub struct DrEventAnnounced {
+    pub agency: Pubkey,
+    pub event_key: Pubkey,
+    pub reservoir_key: Pubkey,
+    pub start_slot: u64,
+    pub end_slot: u64,
+    pub target_volume: u64,
+    pub payment_rate: u64,
+    pub weather_adjustment_bps: u16,
+}
+
+/// Emitted when a consumer's curtailment bid is settled
+///
+/// # Fields
+/// * `event_key` - Unique identifier for the event
+/// * `consumer` - The consumer that bid
+/// * `baseline` - Volume the consumer was expected to use during the window
+/// * `usage` - Volume the consumer used during the window
+/// * `reduction` - Verified reduction paid for
+/// * `payment` - Amount paid from the agency's treasury
+/// * `slot` - The slot at which the bid was settled
+#[event]
+pub struct CurtailmentBidSettled {
+    pub event_key: Pubkey,
+    pub consumer: Pubkey,
+    pub baseline: u64,
+    pub usage: u64,
+    pub reduction: u64,
+    pub payment: u64,
+    pub slot: u64,
+}
@@ -0,0 +1,118 @@
+use crate::{
+    events::CapacityAdjusted,
+    state::{AdjustmentReason, Consumer, Tokens},
+    utils::burn_signed_by_consumer,
+    CustomError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Adjust capacity instruction context
+///
+/// The **AdjustCapacity** context is used by the agency to correct a consumer's WATC balance
+/// without resetting it. The consumer co-signs so clawed back tokens can be burned.
+///
+/// # Fields
+/// * `consumer` - The consumer account being adjusted (must be signer)
+/// * `agency` - The authority that can mint WATC tokens
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `consumer_watc` - The consumer's WaterCapacityToken account
+/// * `watc_mint` - The mint for WaterCapacityTokens
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token operations
+///
+/// # Seeds for Tokens PDA
+/// * `"tokens"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct AdjustCapacity<'info> {
+    #[account(signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, associated_token::mint = watc_mint, associated_token::authority = consumer)]
+    pub consumer_watc: Account<'info, TokenAccount>, // Consumer's WaterCapacityToken account
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub watc_mint: Account<'info, Mint>, // Mint for the WaterCapacityToken
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Mint or burn a delta of a consumer's WATC balance
+///
+/// Unlike `update_consumer`, which burns the whole balance and mints the contracted
+/// capacity again, this only applies the difference, so capacity already consumed in
+/// the current period is preserved. A `CapacityAdjusted` event is emitted for audit.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, agency and token accounts
+/// * `delta` - Amount of WATC to mint (positive) or burn (negative)
+/// * `reason` - Reason code for the adjustment
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If delta is zero
+/// * `CustomError::InsufficientCapacity` - If a burn exceeds the consumer's WATC balance
+///
+/// # Returns
+/// * `Ok(())` on successful adjustment
+pub fn adjust_capacity(
+    ctx: Context<AdjustCapacity>,
+    delta: i64,
+    reason: AdjustmentReason,
+) -> Result<()> {
+    require!(delta != 0, CustomError::InvalidAmount);
+
+    let amount = delta.unsigned_abs();
+    let balance = ctx.accounts.consumer_watc.amount;
+
+    let new_balance = if delta > 0 {
+        // Top up the consumer's capacity
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    to: ctx.accounts.consumer_watc.to_account_info(),
+                    authority: ctx.accounts.agency.to_account_info(),
+                    mint: ctx.accounts.watc_mint.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        balance
+            .checked_add(amount)
+            .ok_or(error!(CustomError::MathOverflow))?
+    } else {
+        require!(balance >= amount, CustomError::InsufficientCapacity);
+
+        // Claw back erroneously minted capacity
+        burn_signed_by_consumer(
+            &ctx.accounts.token_program,
+            ctx.accounts.watc_mint.to_account_info(),
+            ctx.accounts.consumer_watc.to_account_info(),
+            ctx.accounts.consumer.to_account_info(),
+            amount,
+        )?;
+        balance - amount
+    };
+
+    emit!(CapacityAdjusted {
+        consumer: ctx.accounts.consumer.key(),
+        delta,
+        reason,
+        balance: new_balance,
+        slot: Clock::get()?.slot,
+    });
+
+    msg!(
+        "Consumer capacity adjusted by {} for {:?}, new balance: {}.",
+        delta,
+        reason,
+        new_balance
+    );
+    Ok(())
+}
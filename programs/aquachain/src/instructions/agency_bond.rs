@@ -0,0 +1,262 @@
+use crate::{
+    events::ChargeDiscrepancyResolved,
+    state::{AgencyBond, Consumer, RegulatorAuthority, Role, Tariff},
+    utils::{clear_stale_bytes, is_valid_bps},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Post agency bond instruction context
+///
+/// The **PostAgencyBond** context is used by the agency to escrow a stablecoin bond against
+/// mischarges, usually when it registers. Further calls top the bond up.
+///
+/// # Fields
+/// * `agency_bond` - The PDA account recording the agency's bond
+/// * `escrow` - The bond's stablecoin account holding the posted amount
+/// * `agency` - The agency posting the bond (must be signer)
+/// * `agency_settlement` - The agency's stablecoin treasury account
+/// * `settlement_mint` - The stablecoin mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for AgencyBond PDA
+/// * `"agency_bond"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct PostAgencyBond<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"agency_bond", agency.key().as_ref()],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + AgencyBond::INIT_SPACE
+    )]
+    pub agency_bond: Account<'info, AgencyBond>,
+    #[account(
+        init_if_needed,
+        payer = agency,
+        associated_token::mint = settlement_mint,
+        associated_token::authority = agency_bond
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = agency)]
+    pub agency_settlement: Account<'info, TokenAccount>,
+    pub settlement_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Resolve flagged charge instruction context
+///
+/// The **ResolveFlaggedCharge** context is used by the agency's regulator to confirm or
+/// dismiss a charge flagged by `verify_charge`. The bond accounts are only needed when the agency
+/// has posted a bond.
+///
+/// # Fields
+/// * `consumer` - The consumer whose charge was flagged
+/// * `tariff` - The PDA tariff account assigned to the consumer
+/// * `agency` - The agency that billed the consumer
+/// * `regulator_authority` - The PDA account recording the agency's regulator
+/// * `authority` - The regulator authority, or a key it granted the Regulator role
+/// * `role` - Role account of the authority, when it is not the regulator authority
+/// * `agency_bond` - The PDA account recording the agency's bond, if posted
+/// * `escrow` - The bond's stablecoin account, if a bond was posted
+/// * `consumer_settlement` - The consumer's stablecoin account, if a bond was posted
+/// * `token_program` - Required for token operations
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `consumer.assigned_tariff` - Tariff assigned to the consumer
+///
+/// # Seeds for RegulatorAuthority PDA
+/// * `"regulator_authority"` - Constant string
+/// * `agency` - Agency's public key
+///
+/// # Seeds for AgencyBond PDA
+/// * `"agency_bond"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct ResolveFlaggedCharge<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            consumer.assigned_tariff.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    /// CHECK: only used to derive the tariff, regulator authority and bond PDAs
+    pub agency: UncheckedAccount<'info>,
+    #[account(seeds = [b"regulator_authority", agency.key().as_ref()], bump)]
+    pub regulator_authority: Account<'info, RegulatorAuthority>,
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
+    #[account(mut, seeds = [b"agency_bond", agency.key().as_ref()], bump)]
+    pub agency_bond: Option<Account<'info, AgencyBond>>,
+    #[account(mut)]
+    pub escrow: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub consumer_settlement: Option<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Escrow a stablecoin bond against mischarges
+///
+/// The amount is moved from the agency's treasury into the bond's escrow. A bond is kept
+/// in a single stablecoin, so top-ups must use the mint it was first posted in.
+///
+/// # Arguments
+/// * `ctx` - Context containing the bond, escrow, agency and token accounts
+/// * `amount` - Amount moved into the escrow, in settlement base units
+/// * `slash_bps` - Share of the escrowed balance paid out per confirmed mischarge
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If amount is zero
+/// * `CustomError::InvalidShare` - If slash_bps exceeds 10,000
+/// * `CustomError::InvalidAgencyBond` - If the bond was posted in another mint
+///
+/// # Returns
+/// * `Ok(())` on successful posting
+pub fn post_agency_bond(ctx: Context<PostAgencyBond>, amount: u64, slash_bps: u16) -> Result<()> {
+    require!(amount > 0, CustomError::InvalidAmount);
+    require!(is_valid_bps(slash_bps), CustomError::InvalidShare);
+
+    let agency_bond = &mut ctx.accounts.agency_bond;
+    require!(
+        agency_bond.total_posted == 0
+            || agency_bond.settlement_mint == ctx.accounts.settlement_mint.key(),
+        CustomError::InvalidAgencyBond
+    );
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.agency_settlement.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.agency.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    agency_bond.agency = ctx.accounts.agency.key();
+    agency_bond.settlement_mint = ctx.accounts.settlement_mint.key();
+    agency_bond.slash_bps = slash_bps;
+    agency_bond.post(amount);
+
+    msg!(
+        "Agency bond of {} posted, {} bps slashed per mischarge.",
+        amount,
+        slash_bps
+    );
+    Ok(())
+}
+
+/// Confirm or dismiss a charge flagged by `verify_charge`
+///
+/// Only the agency's regulator, or a key it granted the Regulator role, resolves flags,
+/// so the agency never decides whether its own bond is slashed. The flag is cleared
+/// either way. When the regulator confirms the mischarge and the agency has posted a bond, the bond's share of its escrowed balance is paid to the
+/// consumer's stablecoin account.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, tariff, agency, regulator and bond accounts
+/// * `confirmed` - Whether the regulator confirms the charge was mischarged
+///
+/// # Errors
+/// * `CustomError::MissingRegulatorApproval` - If the authority is the agency itself
+/// * `CustomError::MissingRole` - If the authority is neither the regulator authority nor
+///   holds the Regulator role
+/// * `CustomError::NoFlaggedCharge` - If the consumer has no flagged charge
+/// * `CustomError::InvalidAgencyBond` - If the escrow or consumer account does not match
+///   the bond
+///
+/// # Returns
+/// * `Ok(())` on successful resolution
+pub fn resolve_flagged_charge(ctx: Context<ResolveFlaggedCharge>, confirmed: bool) -> Result<()> {
+    ctx.accounts
+        .regulator_authority
+        .authorize(&ctx.accounts.authority.key(), ctx.accounts.role.as_deref())?;
+
+    let flagged = ctx
+        .accounts
+        .consumer
+        .flagged_charge
+        .take()
+        .ok_or(CustomError::NoFlaggedCharge)?;
+    clear_stale_bytes(&ctx.accounts.consumer)?;
+
+    // Compensate the consumer out of the agency's bond, when one was posted
+    let compensation = match (
+        confirmed,
+        ctx.accounts.agency_bond.as_mut(),
+        ctx.accounts.escrow.as_ref(),
+        ctx.accounts.consumer_settlement.as_ref(),
+    ) {
+        (true, Some(agency_bond), Some(escrow), Some(consumer_settlement)) => {
+            require!(
+                escrow.owner == agency_bond.key()
+                    && escrow.mint == agency_bond.settlement_mint
+                    && consumer_settlement.owner == ctx.accounts.consumer.key()
+                    && consumer_settlement.mint == agency_bond.settlement_mint,
+                CustomError::InvalidAgencyBond
+            );
+
+            let compensation = agency_bond.compensation_for(escrow.amount);
+            let agency_key = ctx.accounts.agency.key();
+            let bump = ctx
+                .bumps
+                .agency_bond
+                .ok_or(CustomError::InvalidAgencyBond)?;
+            let signer_seeds: &[&[&[u8]]] = &[&[b"agency_bond", agency_key.as_ref(), &[bump]]];
+
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: escrow.to_account_info(),
+                        to: consumer_settlement.to_account_info(),
+                        authority: agency_bond.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                compensation,
+            )?;
+            agency_bond.slash(compensation);
+            compensation
+        }
+        (true, Some(_), _, _) => return err!(CustomError::InvalidAgencyBond),
+        _ => 0,
+    };
+
+    emit!(ChargeDiscrepancyResolved {
+        consumer: ctx.accounts.consumer.key(),
+        regulator: ctx.accounts.authority.key(),
+        billed: flagged.billed,
+        recomputed: flagged.recomputed,
+        confirmed,
+        compensation,
+        slot: Clock::get()?.slot,
+    });
+
+    msg!(
+        "Flagged charge {}, {} paid from the agency bond.",
+        if confirmed { "confirmed" } else { "dismissed" },
+        compensation
+    );
+    Ok(())
+}
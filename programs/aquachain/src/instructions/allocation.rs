@@ -0,0 +1,302 @@
+use crate::{
+    state::{AgencyStats, AllocationClaim, AllocationTree, Consumer, Reservoir, Tariff, Tokens},
+    utils::verify_merkle_proof,
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Create allocation tree instruction context
+///
+/// The **CreateAllocationTree** context is used by the agency to post the Merkle root of a
+/// batch of initial WATC allocations and escrow their total in a vault owned by the tree.
+///
+/// # Fields
+/// * `allocation_tree` - The PDA account storing the Merkle root
+/// * `tariff` - The PDA tariff account assigned to the claimants
+/// * `reservoir` - The PDA reservoir account assigned to the claimants
+/// * `agency` - The authority creating the allocations
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `allocation_vault` - The tree's WATC token account holding the escrowed allocations
+/// * `watc_mint` - The WATC token mint
+/// * `system_program` - Required for account creation
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for AllocationTree PDA
+/// * `"allocation_tree"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tree_key` - Unique identifier for the allocation tree
+#[derive(Accounts)]
+#[instruction(tree_key: Pubkey, merkle_root: [u8; 32], tariff_key: Pubkey, reservoir_key: Pubkey)]
+pub struct CreateAllocationTree<'info> {
+    #[account(
+        init,
+        seeds = [b"allocation_tree", agency.key().as_ref(), &tree_key.as_ref()],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + AllocationTree::INIT_SPACE
+    )]
+    pub allocation_tree: Account<'info, AllocationTree>,
+    #[account(seeds = [b"tariff", agency.key().as_ref(), &tariff_key.as_ref()], bump)]
+    pub tariff: Account<'info, Tariff>,
+    #[account(seeds = [b"reservoir", agency.key().as_ref(), &reservoir_key.as_ref()], bump)]
+    pub reservoir: Account<'info, Reservoir>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>,
+    #[account(
+        init,
+        payer = agency,
+        associated_token::mint = watc_mint,
+        associated_token::authority = allocation_tree
+    )]
+    pub allocation_vault: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub watc_mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Claim allocation instruction context
+///
+/// The **ClaimAllocation** context is used by a claimant to register a consumer from an
+/// allocation tree, without a signature from the agency.
+///
+/// # Fields
+/// * `consumer` - The new consumer account to be initialized (must be signer)
+/// * `claimant` - The wallet named in the tree leaf, which pays for the new accounts
+/// * `allocation_tree` - The PDA account storing the Merkle root
+/// * `allocation_claim` - The PDA account marking the leaf as claimed
+/// * `agency` - The agency that created the allocation tree
+/// * `tariff` - The PDA tariff account assigned to the claimants
+/// * `reservoir` - The PDA reservoir account assigned to the claimants
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
+/// * `allocation_vault` - The tree's WATC token account holding the escrowed allocations
+/// * `consumer_watc` - The consumer's WATC token account
+/// * `watc_mint` - The WATC token mint
+/// * `system_program` - Required for account creation
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for AllocationTree PDA
+/// * `"allocation_tree"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tree_key` - Unique identifier for the allocation tree
+///
+/// # Seeds for AllocationClaim PDA
+/// * `"allocation_claim"` - Constant string
+/// * `allocation_tree` - Allocation tree's public key
+/// * `claimant` - Claimant's public key
+#[derive(Accounts)]
+#[instruction(tree_key: Pubkey)]
+pub struct ClaimAllocation<'info> {
+    #[account(init, payer = claimant, space = DISCRIMINATOR + Consumer::INIT_SPACE)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"allocation_tree", agency.key().as_ref(), &tree_key.as_ref()],
+        bump
+    )]
+    pub allocation_tree: Account<'info, AllocationTree>,
+    #[account(
+        init,
+        seeds = [
+            b"allocation_claim",
+            allocation_tree.key().as_ref(),
+            claimant.key().as_ref()
+        ],
+        bump,
+        payer = claimant,
+        space = DISCRIMINATOR + AllocationClaim::INIT_SPACE
+    )]
+    pub allocation_claim: Account<'info, AllocationClaim>,
+    /// CHECK: Only used to derive the allocation tree and tokens PDAs
+    pub agency: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"tariff", agency.key().as_ref(), allocation_tree.tariff_key.as_ref()],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(
+        mut,
+        seeds = [b"reservoir", agency.key().as_ref(), allocation_tree.reservoir_key.as_ref()],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>,
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>,
+    #[account(mut, associated_token::mint = watc_mint, associated_token::authority = allocation_tree)]
+    pub allocation_vault: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        associated_token::mint = watc_mint,
+        associated_token::authority = consumer
+    )]
+    pub consumer_watc: Account<'info, TokenAccount>,
+    #[account(mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub watc_mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Create an allocation tree for onboarding an existing customer base
+///
+/// This function stores the Merkle root of the allocations along with the tariff, reservoir
+/// and block rate every claimant will be registered with, then mints the total WATC of the
+/// allocations into the tree's vault.
+///
+/// # Arguments
+/// * `ctx` - Context containing allocation tree, tariff, reservoir, agency and token accounts
+/// * `tree_key` - Public key identifying the allocation tree
+/// * `merkle_root` - Root of the tree of `(claimant, contracted_capacity)` leaves
+/// * `tariff_key` - Public key of the tariff assigned to the claimants
+/// * `reservoir_key` - Public key of the reservoir assigned to the claimants
+/// * `block_rate` - Rate charged per block of water usage (must be > 0)
+/// * `total_capacity` - Sum of the contracted capacities in the tree (must be > 0)
+///
+/// # Errors
+/// * `CustomError::InvalidRate` - If block_rate is 0
+/// * `CustomError::InvalidCapacity` - If total_capacity is 0
+///
+/// # Returns
+/// * `Ok(())` on successful creation
+pub fn create_allocation_tree(
+    ctx: Context<CreateAllocationTree>,
+    tree_key: Pubkey,
+    merkle_root: [u8; 32],
+    tariff_key: Pubkey,
+    reservoir_key: Pubkey,
+    block_rate: u64,
+    total_capacity: u64,
+) -> Result<()> {
+    require!(block_rate > 0, CustomError::InvalidRate);
+    require!(total_capacity > 0, CustomError::InvalidCapacity);
+
+    let allocation_tree = &mut ctx.accounts.allocation_tree;
+    allocation_tree.merkle_root = merkle_root;
+    allocation_tree.tariff_key = tariff_key;
+    allocation_tree.reservoir_key = reservoir_key;
+    allocation_tree.block_rate = block_rate;
+    allocation_tree.total_capacity = total_capacity;
+    allocation_tree.claimed_capacity = 0;
+    allocation_tree.tree_key = tree_key;
+
+    // Escrow the WATC of every allocation in the tree's vault
+    token::mint_to(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::MintTo {
+                to: ctx.accounts.allocation_vault.to_account_info(),
+                authority: ctx.accounts.agency.to_account_info(),
+                mint: ctx.accounts.watc_mint.to_account_info(),
+            },
+        ),
+        total_capacity,
+    )?;
+
+    msg!(
+        "Allocation tree created with {} WATC escrowed.",
+        total_capacity
+    );
+    Ok(())
+}
+
+/// Register a consumer by claiming its allocation from a tree
+///
+/// This function verifies that `(claimant, contracted_capacity)` is a leaf of the tree,
+/// initializes the consumer with the tree's tariff, reservoir and block rate, and transfers
+/// the contracted capacity in WATC from the tree's vault. The claimant becomes the owner of
+/// the consumer account, and each leaf can only be claimed once.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, claimant, allocation and token accounts
+/// * `tree_key` - Public key identifying the allocation tree
+/// * `contracted_capacity` - Capacity allocated to the claimant in the tree
+/// * `proof` - Sibling hashes from the claimant's leaf up to the Merkle root
+///
+/// # Errors
+/// * `CustomError::InvalidCapacity` - If contracted_capacity is 0
+/// * `CustomError::InvalidMerkleProof` - If the leaf is not in the tree
+/// * `CustomError::AllocationExhausted` - If the vault does not hold enough unclaimed WATC
+///
+/// # Returns
+/// * `Ok(())` on successful claim
+pub fn claim_allocation(
+    ctx: Context<ClaimAllocation>,
+    tree_key: Pubkey,
+    contracted_capacity: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    require!(contracted_capacity > 0, CustomError::InvalidCapacity);
+
+    let allocation_tree = &mut ctx.accounts.allocation_tree;
+    let leaf = AllocationTree::leaf(&ctx.accounts.claimant.key(), contracted_capacity);
+    require!(
+        verify_merkle_proof(&proof, &allocation_tree.merkle_root, leaf),
+        CustomError::InvalidMerkleProof
+    );
+
+    let claimed_capacity = allocation_tree
+        .claimed_capacity
+        .checked_add(contracted_capacity)
+        .filter(|claimed| *claimed <= allocation_tree.total_capacity)
+        .ok_or(CustomError::AllocationExhausted)?;
+    allocation_tree.claimed_capacity = claimed_capacity;
+
+    let consumer = &mut ctx.accounts.consumer;
+    consumer.assigned_tariff = allocation_tree.tariff_key;
+    consumer.assigned_reservoir = allocation_tree.reservoir_key;
+    ctx.accounts.tariff.assign_consumer();
+    ctx.accounts.reservoir.assign_consumer();
+    ctx.accounts.agency_stats.record_consumer();
+    consumer.block_rate = allocation_tree.block_rate;
+    consumer.contracted_capacity = contracted_capacity;
+    consumer.owner = ctx.accounts.claimant.key();
+
+    let slot = Clock::get()?.slot;
+    let allocation_claim = &mut ctx.accounts.allocation_claim;
+    allocation_claim.consumer = consumer.key();
+    allocation_claim.contracted_capacity = contracted_capacity;
+    allocation_claim.claimed_slot = slot;
+
+    // Transfer the allocation from the vault, signed by the allocation tree PDA
+    let agency_key = ctx.accounts.agency.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"allocation_tree",
+        agency_key.as_ref(),
+        tree_key.as_ref(),
+        &[ctx.bumps.allocation_tree],
+    ]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.allocation_vault.to_account_info(),
+                to: ctx.accounts.consumer_watc.to_account_info(),
+                authority: ctx.accounts.allocation_tree.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        contracted_capacity,
+    )?;
+
+    msg!(
+        "Consumer registered from allocation tree with contracted capacity {}.",
+        contracted_capacity
+    );
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use crate::{
+    state::{BillBreakdown, Consumer, Reservoir, Tariff, Tokens},
+    utils::FixedPoint,
+    CustomError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Bill estimated usage instruction context
+///
+/// The **BillEstimatedUsage** context is used by the agency to charge a consumer for a period in
+/// which no meter reading arrived. The consumer does not need to sign.
+///
+/// # Fields
+/// * `consumer` - The consumer account being billed
+/// * `tariff` - The PDA tariff account assigned to this consumer
+/// * `reservoir` - The PDA reservoir account assigned to this consumer
+/// * `agency` - The authority that can mint tokens
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `wtk_mint` - The WTK token mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+///
+/// # Seeds for Reservoir PDA
+/// * `"reservoir"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey, reservoir_key: Pubkey)]
+pub struct BillEstimatedUsage<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [b"tariff", agency.key().as_ref(), &tariff_key.as_ref()],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(
+        seeds = [
+            b"reservoir",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    #[account(mut)]
+    pub agency: Signer<'info>, // agency's authorized wallet
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub wtk_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Charge a consumer for their rolling average usage when no reading arrived
+///
+/// This function prices the consumer's rolling average usage under their tariff and
+/// mints the charge as WTK. The volume and charge are recorded as estimated, and WATC
+/// and period usage are left untouched, so the next actual reading can be reconciled with `true_up`.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, tariff, reservoir, agency and token accounts
+/// * `tariff_key` - Public key of the tariff assigned to this consumer
+/// * `reservoir_key` - Public key of the reservoir assigned to this consumer
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If tariff_key or reservoir_key do not match consumer's assigned values
+/// * `CustomError::NoUsageHistory` - If the consumer has no actual readings to estimate from
+/// * `CustomError::MathOverflow` - If the converted volume or cost does not fit in a u64
+///
+/// # Returns
+/// * `Ok(())` on successful billing
+pub fn bill_estimated_usage(
+    ctx: Context<BillEstimatedUsage>,
+    tariff_key: Pubkey,
+    reservoir_key: Pubkey,
+) -> Result<()> {
+    let consumer = &ctx.accounts.consumer;
+    let tariff = &ctx.accounts.tariff;
+    let reservoir = &ctx.accounts.reservoir;
+    let units = &ctx.accounts.tokens.units;
+
+    require_keys_eq!(
+        tariff_key,
+        consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+    require_keys_eq!(
+        reservoir_key,
+        consumer.assigned_reservoir,
+        CustomError::Unauthorized
+    );
+
+    let estimated_usage = consumer.average_usage;
+    require!(estimated_usage > 0, CustomError::NoUsageHistory);
+
+    let slot = Clock::get()?.slot;
+    let multiplier = reservoir.scarcity_multiplier(tariff.tariff_type, units)?;
+    let estimated_charge = BillBreakdown::price(
+        &tariff.charge_bounds(),
+        units,
+        units.to_volume(consumer.block_remaining(tariff, slot))?,
+        units.to_volume(estimated_usage)?,
+        FixedPoint::from(tariff.water_rate),
+        FixedPoint::from(consumer.block_rate),
+        multiplier,
+    )?
+    .total;
+
+    // Mint WTK tokens to the consumer for the estimated cost
+    token::mint_to(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::MintTo {
+                to: ctx.accounts.consumer_wtk.to_account_info(),
+                authority: ctx.accounts.agency.to_account_info(),
+                mint: ctx.accounts.wtk_mint.to_account_info(),
+            },
+        ),
+        estimated_charge,
+    )?;
+
+    let consumer = &mut ctx.accounts.consumer;
+    consumer.bill_water(estimated_charge, slot)?;
+    consumer.estimated_usage = consumer
+        .estimated_usage
+        .checked_add(estimated_usage)
+        .ok_or(error!(CustomError::MathOverflow))?;
+    consumer.estimated_charge = consumer
+        .estimated_charge
+        .checked_add(estimated_charge)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    msg!(
+        "Consumer billed an estimated {} units of water, charged: {}.",
+        estimated_usage,
+        estimated_charge
+    );
+    Ok(())
+}
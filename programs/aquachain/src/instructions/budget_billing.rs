@@ -0,0 +1,238 @@
+use crate::{
+    events::BudgetBillingReconciled,
+    state::{AgencyStats, Consumer, PaymentReceipt, BUDGET_PERIODS_PER_YEAR},
+    utils::burn_signed_by_consumer,
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+/// Budget billing instruction context
+///
+/// The **BudgetBilling** context is used to enroll a consumer in budget billing, or remove
+/// them from it. Both consumer and agency sign.
+///
+/// # Fields
+/// * `consumer` - The consumer account on budget billing (must be signer)
+/// * `agency` - The authority that manages the consumer
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `wtk_mint` - The WTK token mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+#[derive(Accounts)]
+pub struct BudgetBilling<'info> {
+    #[account(mut, signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(mut)]
+    pub agency: Signer<'info>, // agency's authorized wallet
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>, // Agency's aggregate statistics
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency)]
+    pub wtk_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Pay budget invoice instruction context
+///
+/// The **PayBudgetInvoice** context is used to collect a budget billing consumer's levelized
+/// invoices and reconcile them at the end of the year. Both consumer and agency sign.
+///
+/// # Fields
+/// * `consumer` - The consumer account on budget billing (must be signer)
+/// * `agency` - The authority that manages the consumer
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
+/// * `receipt` - The PDA account that will store the payment receipt
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `wtk_mint` - The WTK token mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for PaymentReceipt PDA
+/// * `"receipt"` - Constant string
+/// * `consumer` - Consumer's public key
+/// * `receipts_issued` - The consumer's receipt count, as little-endian bytes
+#[derive(Accounts)]
+pub struct PayBudgetInvoice<'info> {
+    #[account(mut, signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(mut)]
+    pub agency: Signer<'info>, // agency's authorized wallet
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>, // Agency's aggregate statistics
+    #[account(
+        init,
+        seeds = [
+            b"receipt",
+            consumer.key().as_ref(),
+            consumer.receipts_issued.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + PaymentReceipt::INIT_SPACE
+    )]
+    pub receipt: Account<'info, PaymentReceipt>,
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency)]
+    pub wtk_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Enroll a consumer in, or remove them from, budget billing
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, agency and token accounts
+/// * `enabled` - Whether the consumer pays levelized invoices
+/// * `budget_amount` - Levelized amount of WTK collected by each invoice
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If enabling with a zero budget_amount
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn set_budget_billing(
+    ctx: Context<BudgetBilling>,
+    enabled: bool,
+    budget_amount: u64,
+) -> Result<()> {
+    require!(!enabled || budget_amount > 0, CustomError::InvalidAmount);
+
+    let consumer = &mut ctx.accounts.consumer;
+    consumer.budget_billing = enabled;
+    consumer.budget_amount = budget_amount;
+
+    msg!(
+        "Budget billing set to {} with a levelized amount of {}.",
+        enabled,
+        budget_amount
+    );
+    Ok(())
+}
+
+/// Pay a budget billing invoice
+///
+/// This function burns the consumer's levelized amount of WTK, or their whole
+/// outstanding debt if it is lower, regardless of how much water was used in the
+/// period. The payment counts towards the year-end reconciliation, and issues a
+/// `PaymentReceipt` like any other payment.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, agency, receipt and token accounts
+///
+/// # Errors
+/// * `CustomError::BudgetBillingDisabled` - If the consumer is not on budget billing
+/// * `CustomError::InvalidAmount` - If the consumer has no outstanding debt
+///
+/// # Returns
+/// * `Ok(())` on successful payment
+pub fn pay_invoice(ctx: Context<PayBudgetInvoice>) -> Result<()> {
+    let consumer = &mut ctx.accounts.consumer;
+
+    require!(consumer.budget_billing, CustomError::BudgetBillingDisabled);
+
+    let amount = consumer.budget_amount.min(consumer.outstanding_water_debt);
+    require!(amount > 0, CustomError::InvalidAmount);
+
+    consumer.pay_water(amount)?;
+    ctx.accounts.agency_stats.record_payment(amount);
+    consumer.budget_paid = consumer
+        .budget_paid
+        .checked_add(amount)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    // Burn WTK tokens
+    burn_signed_by_consumer(
+        &ctx.accounts.token_program,
+        ctx.accounts.wtk_mint.to_account_info(),
+        ctx.accounts.consumer_wtk.to_account_info(),
+        ctx.accounts.consumer.to_account_info(),
+        amount,
+    )?;
+
+    let consumer_key = ctx.accounts.consumer.key();
+    ctx.accounts.receipt.issue(
+        consumer_key,
+        &mut ctx.accounts.consumer,
+        amount,
+        Clock::get()?.slot,
+    );
+
+    msg!("Budget invoice paid: {} WTK.", amount);
+    Ok(())
+}
+
+/// Settle the difference between levelized payments and true cost at year end
+///
+/// This function burns the outstanding water debt accumulated over the year beyond the
+/// levelized payments, and sets next year's levelized amount to a twelfth of this year's
+/// true cost. A `BudgetBillingReconciled` event is emitted for audit, and a
+/// `PaymentReceipt` is issued for the settled difference, with a zero amount when the
+/// levelized payments covered the year, as proof the year was settled.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, agency, receipt and token accounts
+///
+/// # Errors
+/// * `CustomError::BudgetBillingDisabled` - If the consumer is not on budget billing
+///
+/// # Returns
+/// * `Ok(())` on successful reconciliation
+pub fn reconcile_budget_billing(ctx: Context<PayBudgetInvoice>) -> Result<()> {
+    let consumer = &mut ctx.accounts.consumer;
+
+    require!(consumer.budget_billing, CustomError::BudgetBillingDisabled);
+
+    let levelized_paid = consumer.budget_paid;
+    let difference = consumer.outstanding_water_debt;
+    let true_cost = levelized_paid
+        .checked_add(difference)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    consumer.pay_water(difference)?;
+    ctx.accounts.agency_stats.record_payment(difference);
+    consumer.budget_paid = 0;
+    consumer.budget_amount = (true_cost / BUDGET_PERIODS_PER_YEAR).max(1);
+    let budget_amount = consumer.budget_amount;
+
+    // Burn the accumulated difference
+    if difference > 0 {
+        burn_signed_by_consumer(
+            &ctx.accounts.token_program,
+            ctx.accounts.wtk_mint.to_account_info(),
+            ctx.accounts.consumer_wtk.to_account_info(),
+            ctx.accounts.consumer.to_account_info(),
+            difference,
+        )?;
+    }
+
+    let slot = Clock::get()?.slot;
+    let consumer_key = ctx.accounts.consumer.key();
+    ctx.accounts
+        .receipt
+        .issue(consumer_key, &mut ctx.accounts.consumer, difference, slot);
+
+    emit!(BudgetBillingReconciled {
+        consumer: consumer_key,
+        levelized_paid,
+        difference,
+        budget_amount,
+        slot,
+    });
+
+    msg!(
+        "Budget billing reconciled: {} settled, new levelized amount {}.",
+        difference,
+        budget_amount
+    );
+    Ok(())
+}
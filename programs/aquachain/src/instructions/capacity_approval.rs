@@ -0,0 +1,45 @@
+use crate::{state::Consumer, CustomError};
+use anchor_lang::prelude::*;
+
+/// Set capacity approval instruction context
+///
+/// The **SetCapacityApproval** context is used by a consumer to pre-approve routine changes
+/// of its contracted capacity, which the agency can then make with `update_consumer`
+/// without the consumer's signature.
+///
+/// # Fields
+/// * `consumer` - The consumer account granting the approval (must be signer)
+#[derive(Accounts)]
+pub struct SetCapacityApproval<'info> {
+    #[account(mut, signer)]
+    pub consumer: Account<'info, Consumer>,
+}
+
+/// Set the capacity change per period a consumer pre-approves
+///
+/// Changes are bounded by `approval_bps` of the contracted capacity at the start of each
+/// capacity period, either way. Larger changes, and any change to the block rate, still
+/// need the consumer's signature. Setting the approval to zero withdraws it.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer account
+/// * `approval_bps` - Largest capacity change per period, in basis points
+///
+/// # Errors
+/// * `CustomError::InvalidShare` - If approval_bps exceeds 10,000
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn set_capacity_approval(ctx: Context<SetCapacityApproval>, approval_bps: u16) -> Result<()> {
+    require!(approval_bps <= 10_000, CustomError::InvalidShare);
+
+    let consumer = &mut ctx.accounts.consumer;
+    consumer.capacity_approval_bps = approval_bps;
+    consumer.reset_capacity_approval();
+
+    msg!(
+        "Consumer pre-approved capacity changes of {} bps.",
+        approval_bps
+    );
+    Ok(())
+}
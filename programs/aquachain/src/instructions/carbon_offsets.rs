@@ -0,0 +1,167 @@
+use crate::{
+    events::CarbonOffsetRetired,
+    state::{AgencyStats, CarbonOffsets},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Configure carbon offsets instruction context
+///
+/// The **ConfigureCarbonOffsets** context is used by the agency to set the carbon-credit
+/// token it retires against its emissions.
+///
+/// # Fields
+/// * `carbon_offsets` - The PDA account recording the agency's offset retirements
+/// * `credit_mint` - The carbon-credit token mint
+/// * `agency` - The agency retiring the credits
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for CarbonOffsets PDA
+/// * `"carbon_offsets"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct ConfigureCarbonOffsets<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"carbon_offsets", agency.key().as_ref()],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + CarbonOffsets::INIT_SPACE
+    )]
+    pub carbon_offsets: Account<'info, CarbonOffsets>,
+    pub credit_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Retire carbon offsets instruction context
+///
+/// The **RetireCarbonOffsets** context is used by the agency to burn carbon credits it
+/// holds against the emissions recorded in its statistics.
+///
+/// # Fields
+/// * `carbon_offsets` - The PDA account recording the agency's offset retirements
+/// * `agency_stats` - The PDA account holding the agency's recorded emissions
+/// * `agency_credits` - The agency's carbon-credit token account
+/// * `credit_mint` - The carbon-credit token mint
+/// * `agency` - The agency retiring the credits
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for CarbonOffsets PDA
+/// * `"carbon_offsets"` - Constant string
+/// * `agency` - Agency's public key
+///
+/// # Seeds for AgencyStats PDA
+/// * `"agency_stats"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct RetireCarbonOffsets<'info> {
+    #[account(
+        mut,
+        seeds = [b"carbon_offsets", agency.key().as_ref()],
+        bump,
+        has_one = credit_mint
+    )]
+    pub carbon_offsets: Account<'info, CarbonOffsets>,
+    #[account(seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>,
+    #[account(mut, associated_token::mint = credit_mint, associated_token::authority = agency)]
+    pub agency_credits: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub credit_mint: Account<'info, Mint>,
+    pub agency: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Set the carbon-credit token the agency retires against its emissions
+///
+/// Retirements already recorded are kept when the configuration changes.
+///
+/// # Arguments
+/// * `ctx` - Context containing the offsets, credit mint and agency accounts
+/// * `grams_per_credit` - Grams CO2e offset by one whole credit token (must be > 0)
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If grams_per_credit is 0
+///
+/// # Returns
+/// * `Ok(())` on successful configuration
+pub fn configure_carbon_offsets(
+    ctx: Context<ConfigureCarbonOffsets>,
+    grams_per_credit: u64,
+) -> Result<()> {
+    require!(grams_per_credit > 0, CustomError::InvalidAmount);
+
+    let carbon_offsets = &mut ctx.accounts.carbon_offsets;
+    carbon_offsets.agency = ctx.accounts.agency.key();
+    carbon_offsets.credit_mint = ctx.accounts.credit_mint.key();
+    carbon_offsets.grams_per_credit = grams_per_credit;
+
+    msg!(
+        "Carbon offsets configured at {} g CO2e per credit.",
+        grams_per_credit
+    );
+    Ok(())
+}
+
+/// Burn carbon credits against the agency's recorded emissions
+///
+/// The credits are burned from the agency's token account and the emissions they offset
+/// are added to the agency's retirements. A `CarbonOffsetRetired` event is emitted for
+/// carbon-disclosure reporting.
+///
+/// # Arguments
+/// * `ctx` - Context containing the offsets, statistics, agency and token accounts
+/// * `amount` - Credits to retire, in base units of the credit mint
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If amount is zero or offsets less than a gram
+/// * `CustomError::OffsetExceedsEmissions` - If the total retired would exceed the
+///   emissions recorded in the agency's statistics
+/// * `CustomError::MathOverflow` - If the offset does not fit in a u64
+///
+/// # Returns
+/// * `Ok(())` on successful retirement
+pub fn retire_carbon_offsets(ctx: Context<RetireCarbonOffsets>, amount: u64) -> Result<()> {
+    let co2e = ctx
+        .accounts
+        .carbon_offsets
+        .co2e_for(amount, ctx.accounts.credit_mint.decimals)?;
+    require!(co2e > 0, CustomError::InvalidAmount);
+
+    ctx.accounts
+        .carbon_offsets
+        .retire(amount, co2e, ctx.accounts.agency_stats.co2e_grams)?;
+
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Burn {
+                mint: ctx.accounts.credit_mint.to_account_info(),
+                from: ctx.accounts.agency_credits.to_account_info(),
+                authority: ctx.accounts.agency.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let carbon_offsets = &ctx.accounts.carbon_offsets;
+    emit!(CarbonOffsetRetired {
+        agency: carbon_offsets.agency,
+        credit_mint: carbon_offsets.credit_mint,
+        amount,
+        co2e_grams: co2e,
+        co2e_retired: carbon_offsets.co2e_retired,
+        slot: Clock::get()?.slot,
+    });
+
+    msg!("Retired {} credits offsetting {} g CO2e.", amount, co2e);
+    Ok(())
+}
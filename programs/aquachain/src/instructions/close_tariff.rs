@@ -0,0 +1,62 @@
+use crate::{state::Tariff, CustomError};
+use anchor_lang::prelude::*;
+
+/// Close tariff instruction context
+///
+/// The **CloseTariff** context is used by the agency to close a tariff that no longer
+/// prices any consumers, returning its rent.
+///
+/// # Fields
+/// * `tariff` - The PDA account to close, returning its rent to the agency
+/// * `agency` - The owner that is authorized to sign operations on its behalf
+///
+/// # Seeds
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for this tariff
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey)]
+pub struct CloseTariff<'info> {
+    #[account(
+        mut,
+        close = agency,
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            &tariff_key.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+}
+
+/// Close a tariff and reclaim its rent
+///
+/// All consumers assigned to the tariff must first be moved to another tariff with
+/// `update_consumer_tariff` or `migrate_tariff_consumers`, so no consumer is left
+/// pointing at a pricing account that no longer exists.
+///
+/// # Arguments
+/// * `ctx` - Context containing the tariff and agency accounts
+/// * `tariff_key` - Unique public key identifier for this tariff
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match the account's key
+/// * `CustomError::TariffInUse` - If consumers are still assigned to the tariff
+///
+/// # Returns
+/// * `Ok(())` on successful closure
+pub fn close_tariff(ctx: Context<CloseTariff>, tariff_key: Pubkey) -> Result<()> {
+    let tariff = &ctx.accounts.tariff;
+
+    require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
+    require!(
+        tariff.assigned_consumer_count == 0,
+        CustomError::TariffInUse
+    );
+
+    msg!("Tariff closed.");
+    Ok(())
+}
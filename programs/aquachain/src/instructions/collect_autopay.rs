@@ -0,0 +1,213 @@
+use crate::{
+    state::{AgencyStats, Consumer, FxOracle, PaymentReceipt, Tariff, Tokens},
+    utils::burn_as_billing_authority,
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::{prelude::*, solana_program::program_option::COption};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Collect auto-pay instruction context
+///
+/// The **CollectAutopay** context is used by any cranker to collect a consumer's outstanding
+/// WTK debt from their auto-pay delegation. Only the cranker needs to sign.
+///
+/// # Fields
+/// * `cranker` - Anyone submitting the collection, pays the transaction fee and the
+///   receipt's rent
+/// * `consumer` - The consumer account with auto-pay enabled
+/// * `tariff` - The PDA tariff account assigned to this consumer
+/// * `fx_oracle` - The PDA oracle account for the tariff's currency
+/// * `agency` - The authority that manages the consumer
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
+/// * `billing_authority` - The program PDA approved as delegate by the consumer
+/// * `receipt` - The PDA account that will store the payment receipt
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `consumer_settlement` - The consumer's stablecoin token account
+/// * `agency_settlement` - The agency's stablecoin treasury account
+/// * `wtk_mint` - The WTK token mint
+/// * `settlement_mint` - The stablecoin mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+///
+/// # Seeds for FxOracle PDA
+/// * `"fx_oracle"` - Constant string
+/// * `agency` - Agency's public key
+/// * `currency_code` - Currency code of the tariff
+///
+/// # Seeds for Billing Authority PDA
+/// * `"billing_authority"` - Constant string
+/// * `agency` - Agency's public key
+///
+/// # Seeds for PaymentReceipt PDA
+/// * `"receipt"` - Constant string
+/// * `consumer` - Consumer's public key
+/// * `receipts_issued` - The consumer's receipt count, as little-endian bytes
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey)]
+pub struct CollectAutopay<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    #[account(mut, constraint = consumer.autopay_enabled @ CustomError::AutopayDisabled)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [b"tariff", agency.key().as_ref(), &tariff_key.as_ref()],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(
+        seeds = [
+            b"fx_oracle",
+            agency.key().as_ref(),
+            tariff.currency_code.as_ref()
+        ],
+        bump,
+        has_one = settlement_mint
+    )]
+    pub fx_oracle: Account<'info, FxOracle>,
+    /// CHECK: validated as the authority of the WTK mint
+    pub agency: UncheckedAccount<'info>,
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>, // Agency's aggregate statistics
+    /// CHECK: PDA used only as an SPL token delegate, it holds no data
+    #[account(seeds = [b"billing_authority", agency.key().as_ref()], bump)]
+    pub billing_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        seeds = [
+            b"receipt",
+            consumer.key().as_ref(),
+            consumer.receipts_issued.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = cranker,
+        space = DISCRIMINATOR + PaymentReceipt::INIT_SPACE
+    )]
+    pub receipt: Account<'info, PaymentReceipt>,
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = consumer)]
+    pub consumer_settlement: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = agency)]
+    pub agency_settlement: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency)]
+    pub wtk_mint: Account<'info, Mint>,
+    pub settlement_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Collect a consumer's outstanding water debt through auto-pay
+///
+/// This permissionless crank converts the consumer's entire outstanding water debt into
+/// the settlement stablecoin at the FX oracle rate, pulls it from the consumer's delegation
+/// into the agency's treasury, and burns the collected WTK, so consumers who opted in are
+/// never disconnected for forgetting to pay. The debt is only collected once due, after
+/// the tariff's grace period counted from when it was first billed, leaving the consumer
+/// the same time to pay by hand as any other consumer. Each collection issues a
+/// `PaymentReceipt`, as a payment made by hand does.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, tariff, oracle, agency and token accounts
+/// * `tariff_key` - Public key of the tariff assigned to this consumer
+///
+/// # Errors
+/// * `CustomError::AutopayDisabled` - If the consumer has not enabled auto-pay
+/// * `CustomError::Unauthorized` - If tariff_key does not match the consumer's assigned tariff
+/// * `CustomError::InvalidAmount` - If the consumer has no outstanding debt
+/// * `CustomError::GracePeriodActive` - If the debt is still within the tariff's grace period
+/// * `CustomError::StaleOracle` - If the oracle rate is unset or too old
+/// * `CustomError::InsufficientDelegation` - If the remaining cap does not cover the debt
+///
+/// # Returns
+/// * `Ok(())` on successful collection
+pub fn collect_autopay(ctx: Context<CollectAutopay>, tariff_key: Pubkey) -> Result<()> {
+    let consumer = &ctx.accounts.consumer;
+    let fx_oracle = &ctx.accounts.fx_oracle;
+    let billing_authority = ctx.accounts.billing_authority.key();
+
+    require_keys_eq!(
+        tariff_key,
+        consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+
+    let amount = consumer.outstanding_water_debt;
+    require!(amount > 0, CustomError::InvalidAmount);
+    let slot = Clock::get()?.slot;
+    require!(
+        ctx.accounts
+            .tariff
+            .grace_period_elapsed(consumer.debt_since_slot, slot),
+        CustomError::GracePeriodActive
+    );
+    require!(!fx_oracle.is_stale(slot), CustomError::StaleOracle);
+
+    ctx.accounts.consumer.pay_water(amount)?;
+    ctx.accounts.agency_stats.record_payment(amount);
+
+    let settlement_amount =
+        fx_oracle.to_settlement(amount, ctx.accounts.tokens.units.currency_decimals)?;
+
+    let consumer_settlement = &ctx.accounts.consumer_settlement;
+    require!(
+        consumer_settlement.delegate == COption::Some(billing_authority)
+            && consumer_settlement.delegated_amount >= settlement_amount,
+        CustomError::InsufficientDelegation
+    );
+
+    let agency_key = ctx.accounts.agency.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"billing_authority",
+        agency_key.as_ref(),
+        &[ctx.bumps.billing_authority],
+    ]];
+
+    // Pull the converted amount from the consumer's delegation
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.consumer_settlement.to_account_info(),
+                to: ctx.accounts.agency_settlement.to_account_info(),
+                authority: ctx.accounts.billing_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        settlement_amount,
+    )?;
+
+    // Write off the collected WTK debt
+    burn_as_billing_authority(
+        &ctx.accounts.token_program,
+        ctx.accounts.wtk_mint.to_account_info(),
+        &ctx.accounts.consumer_wtk,
+        ctx.accounts.billing_authority.to_account_info(),
+        signer_seeds,
+        amount,
+    )?;
+
+    let consumer_key = ctx.accounts.consumer.key();
+    ctx.accounts
+        .receipt
+        .issue(consumer_key, &mut ctx.accounts.consumer, amount, slot);
+
+    msg!(
+        "Auto-pay collected {} WTK of debt for {} units of the settlement mint.",
+        amount,
+        settlement_amount
+    );
+    Ok(())
+}
@@ -0,0 +1,263 @@
+use crate::{
+    events::CommunalTopUp,
+    state::{CommunalPoint, Consumer, FxOracle, Tariff, Tokens},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Register communal point instruction context
+///
+/// The **RegisterCommunalPoint** context is used by the agency to turn a registered
+/// consumer into a community standpipe with a shared prepaid balance.
+///
+/// # Fields
+/// * `communal_point` - The PDA account that will store the standpipe's shared balance
+/// * `consumer` - The consumer account metering the standpipe
+/// * `agency` - The agency operating the standpipe
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for CommunalPoint PDA
+/// * `"communal_point"` - Constant string
+/// * `agency` - Agency's public key
+/// * `consumer` - Consumer's public key
+#[derive(Accounts)]
+pub struct RegisterCommunalPoint<'info> {
+    #[account(
+        init,
+        seeds = [
+            b"communal_point",
+            agency.key().as_ref(),
+            consumer.key().as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + CommunalPoint::INIT_SPACE
+    )]
+    pub communal_point: Account<'info, CommunalPoint>,
+    pub consumer: Account<'info, Consumer>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Add communal member instruction context
+///
+/// The **AddCommunalMember** context is used by the agency to register a wallet allowed
+/// to top up a standpipe's shared balance.
+///
+/// # Fields
+/// * `communal_point` - The PDA account of the standpipe's shared balance
+/// * `agency` - The agency operating the standpipe
+///
+/// # Seeds for CommunalPoint PDA
+/// * `"communal_point"` - Constant string
+/// * `agency` - Agency's public key
+/// * `consumer` - Consumer's public key
+#[derive(Accounts)]
+pub struct AddCommunalMember<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"communal_point",
+            agency.key().as_ref(),
+            communal_point.consumer.as_ref()
+        ],
+        bump
+    )]
+    pub communal_point: Account<'info, CommunalPoint>,
+    pub agency: Signer<'info>,
+}
+
+/// Top up communal point instruction context
+///
+/// The **TopUpCommunalPoint** context is used by a member wallet to add to a standpipe's
+/// shared balance, paying the agency in the settlement stablecoin at the FX oracle rate
+/// of the standpipe's tariff.
+///
+/// # Fields
+/// * `communal_point` - The PDA account of the standpipe's shared balance
+/// * `consumer` - The consumer account metering the standpipe
+/// * `tariff` - The PDA tariff account assigned to the standpipe
+/// * `fx_oracle` - The PDA oracle account for the tariff's currency
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `agency` - The agency operating the standpipe and receiving the payment
+/// * `member` - The member wallet paying for the top-up (must be signer)
+/// * `member_settlement` - The member's stablecoin token account
+/// * `agency_settlement` - The agency's stablecoin treasury account
+/// * `settlement_mint` - The stablecoin mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for CommunalPoint PDA
+/// * `"communal_point"` - Constant string
+/// * `agency` - Agency's public key
+/// * `consumer` - Consumer's public key
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+///
+/// # Seeds for FxOracle PDA
+/// * `"fx_oracle"` - Constant string
+/// * `agency` - Agency's public key
+/// * `currency_code` - Currency code of the tariff
+///
+/// # Seeds for Tokens PDA
+/// * `"tokens"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct TopUpCommunalPoint<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"communal_point",
+            agency.key().as_ref(),
+            consumer.key().as_ref()
+        ],
+        bump
+    )]
+    pub communal_point: Account<'info, CommunalPoint>,
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            consumer.assigned_tariff.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(
+        seeds = [
+            b"fx_oracle",
+            agency.key().as_ref(),
+            tariff.currency_code.as_ref()
+        ],
+        bump,
+        has_one = settlement_mint
+    )]
+    pub fx_oracle: Account<'info, FxOracle>,
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>,
+    /// CHECK: only used to derive the agency's accounts and receive the payment
+    pub agency: UncheckedAccount<'info>,
+    pub member: Signer<'info>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = member)]
+    pub member_settlement: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = agency)]
+    pub agency_settlement: Account<'info, TokenAccount>,
+    pub settlement_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Turn a consumer into a community standpipe with a shared prepaid balance
+///
+/// Once registered, passing the communal point to `use_water` draws the standpipe's usage
+/// from the shared balance before any remaining charge is billed to the consumer as WTK.
+///
+/// # Arguments
+/// * `ctx` - Context containing the communal point, consumer and agency accounts
+///
+/// # Returns
+/// * `Ok(())` on successful registration
+pub fn register_communal_point(ctx: Context<RegisterCommunalPoint>) -> Result<()> {
+    let communal_point = &mut ctx.accounts.communal_point;
+    communal_point.agency = ctx.accounts.agency.key();
+    communal_point.consumer = ctx.accounts.consumer.key();
+    communal_point.prepaid_balance = 0;
+    communal_point.total_drawn = 0;
+    communal_point.members = Vec::new();
+
+    msg!(
+        "Consumer {} registered as a communal point.",
+        communal_point.consumer
+    );
+    Ok(())
+}
+
+/// Register a wallet allowed to top up a standpipe's shared balance
+///
+/// # Arguments
+/// * `ctx` - Context containing the communal point and agency accounts
+/// * `wallet` - Member wallet to register
+///
+/// # Errors
+/// * `CustomError::MemberAlreadyRegistered` - If the wallet is already a member
+/// * `CustomError::CommunalPointFull` - If the standpipe has the maximum number of members
+///
+/// # Returns
+/// * `Ok(())` on successful registration
+pub fn add_communal_member(ctx: Context<AddCommunalMember>, wallet: Pubkey) -> Result<()> {
+    ctx.accounts.communal_point.add_member(wallet)?;
+
+    msg!("Wallet {} added to the communal point.", wallet);
+    Ok(())
+}
+
+/// Top up a standpipe's shared prepaid balance
+///
+/// This function transfers the equivalent of `amount`, converted at the FX oracle rate,
+/// from the member's stablecoin account to the agency's treasury, credits `amount` to the
+/// shared balance and records it in the member's contribution history. A `CommunalTopUp`
+/// event is emitted for fairness reporting.
+///
+/// # Arguments
+/// * `ctx` - Context containing the communal point, oracle, member and token accounts
+/// * `amount` - Amount to credit to the shared balance, in WTK base units
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If amount is zero
+/// * `CustomError::NotCommunalMember` - If the signer is not a member of the standpipe
+/// * `CustomError::StaleOracle` - If the oracle rate is unset or too old
+/// * `CustomError::MathOverflow` - If the converted amount or the balance overflows
+///
+/// # Returns
+/// * `Ok(())` on successful top-up
+pub fn top_up_communal_point(ctx: Context<TopUpCommunalPoint>, amount: u64) -> Result<()> {
+    let fx_oracle = &ctx.accounts.fx_oracle;
+    let slot = Clock::get()?.slot;
+
+    require!(amount > 0, CustomError::InvalidAmount);
+    require!(!fx_oracle.is_stale(slot), CustomError::StaleOracle);
+
+    ctx.accounts
+        .communal_point
+        .top_up(ctx.accounts.member.key(), amount, slot)?;
+
+    let settlement_amount =
+        fx_oracle.to_settlement(amount, ctx.accounts.tokens.units.currency_decimals)?;
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.member_settlement.to_account_info(),
+                to: ctx.accounts.agency_settlement.to_account_info(),
+                authority: ctx.accounts.member.to_account_info(),
+            },
+        ),
+        settlement_amount,
+    )?;
+
+    emit!(CommunalTopUp {
+        communal_point: ctx.accounts.communal_point.key(),
+        member: ctx.accounts.member.key(),
+        amount,
+        settlement_amount,
+        prepaid_balance: ctx.accounts.communal_point.prepaid_balance,
+        slot,
+    });
+
+    msg!(
+        "Communal point topped up by {}, {} prepaid.",
+        amount,
+        ctx.accounts.communal_point.prepaid_balance
+    );
+    Ok(())
+}
@@ -0,0 +1,245 @@
+use crate::{
+    events::ComplaintResolved,
+    state::{Complaint, ComplaintCategory, ComplaintPolicy, Consumer},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Configure complaint policy instruction context
+///
+/// The **ConfigureComplaintPolicy** context is used by the agency to set how quickly it
+/// commits to resolving complaints, and the goodwill credited when it does not.
+///
+/// # Fields
+/// * `complaint_policy` - The PDA account storing the agency's service-level commitment
+/// * `aqc_mint` - The AquaCoin mint goodwill is credited in
+/// * `agency` - The agency making the commitment
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for ComplaintPolicy PDA
+/// * `"complaint_policy"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct ConfigureComplaintPolicy<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"complaint_policy", agency.key().as_ref()],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + ComplaintPolicy::INIT_SPACE
+    )]
+    pub complaint_policy: Account<'info, ComplaintPolicy>,
+    #[account(mint::authority = agency)]
+    pub aqc_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// File complaint instruction context
+///
+/// The **FileComplaint** context is used by a consumer to open a complaint with its
+/// agency. The agency co-signs and pays for the complaint account.
+///
+/// # Fields
+/// * `complaint` - The PDA account that will store the complaint
+/// * `consumer` - The consumer account filing the complaint (must be signer)
+/// * `agency` - The agency the complaint is filed with
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for Complaint PDA
+/// * `"complaint"` - Constant string
+/// * `agency` - Agency's public key
+/// * `complaint_key` - Unique identifier for the complaint
+#[derive(Accounts)]
+#[instruction(complaint_key: Pubkey)]
+pub struct FileComplaint<'info> {
+    #[account(
+        init,
+        seeds = [
+            b"complaint",
+            agency.key().as_ref(),
+            &complaint_key.as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + Complaint::INIT_SPACE
+    )]
+    pub complaint: Account<'info, Complaint>,
+    #[account(signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Resolve complaint instruction context
+///
+/// The **ResolveComplaint** context is used by the agency to close a complaint, crediting
+/// the consumer goodwill in AquaCoin when the resolution breached the agency's SLA.
+///
+/// # Fields
+/// * `complaint` - The PDA account of the complaint
+/// * `complaint_policy` - The PDA account storing the agency's service-level commitment
+/// * `consumer` - The consumer account that filed the complaint
+/// * `consumer_aqc` - The consumer's AquaCoin token account
+/// * `aqc_mint` - The AquaCoin mint
+/// * `agency` - The authority that can mint AquaCoin
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for Complaint PDA
+/// * `"complaint"` - Constant string
+/// * `agency` - Agency's public key
+/// * `complaint_key` - Unique identifier for the complaint
+///
+/// # Seeds for ComplaintPolicy PDA
+/// * `"complaint_policy"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct ResolveComplaint<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"complaint",
+            agency.key().as_ref(),
+            complaint.complaint_key.as_ref()
+        ],
+        bump,
+        has_one = consumer
+    )]
+    pub complaint: Account<'info, Complaint>,
+    #[account(
+        seeds = [b"complaint_policy", agency.key().as_ref()],
+        bump,
+        has_one = aqc_mint
+    )]
+    pub complaint_policy: Account<'info, ComplaintPolicy>,
+    pub consumer: Account<'info, Consumer>,
+    #[account(mut, associated_token::mint = aqc_mint, associated_token::authority = consumer)]
+    pub consumer_aqc: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency)]
+    pub aqc_mint: Account<'info, Mint>,
+    pub agency: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Set the agency's complaint resolution SLA and goodwill credit
+///
+/// Changes apply to every complaint resolved afterwards, including complaints already
+/// open.
+///
+/// # Arguments
+/// * `ctx` - Context containing the policy, AquaCoin mint and agency accounts
+/// * `sla_slots` - Slots within which complaints must be resolved (0 disables credits)
+/// * `goodwill_amount` - Amount of AquaCoin credited for a late resolution
+///
+/// # Returns
+/// * `Ok(())` on successful configuration
+pub fn configure_complaint_policy(
+    ctx: Context<ConfigureComplaintPolicy>,
+    sla_slots: u64,
+    goodwill_amount: u64,
+) -> Result<()> {
+    let complaint_policy = &mut ctx.accounts.complaint_policy;
+    complaint_policy.agency = ctx.accounts.agency.key();
+    complaint_policy.aqc_mint = ctx.accounts.aqc_mint.key();
+    complaint_policy.sla_slots = sla_slots;
+    complaint_policy.goodwill_amount = goodwill_amount;
+
+    msg!(
+        "Complaint SLA set to {} slots with a goodwill credit of {}.",
+        sla_slots,
+        goodwill_amount
+    );
+    Ok(())
+}
+
+/// Open a complaint with the consumer's agency
+///
+/// # Arguments
+/// * `ctx` - Context containing the complaint, consumer and agency accounts
+/// * `complaint_key` - Unique identifier for the complaint
+/// * `category` - Subject of the complaint
+///
+/// # Returns
+/// * `Ok(())` on successful filing
+pub fn file_complaint(
+    ctx: Context<FileComplaint>,
+    complaint_key: Pubkey,
+    category: ComplaintCategory,
+) -> Result<()> {
+    let complaint = &mut ctx.accounts.complaint;
+    complaint.agency = ctx.accounts.agency.key();
+    complaint.complaint_key = complaint_key;
+    complaint.consumer = ctx.accounts.consumer.key();
+    complaint.category = category;
+    complaint.opened_slot = Clock::get()?.slot;
+    complaint.resolved_slot = 0;
+    complaint.goodwill_credited = 0;
+
+    msg!("{:?} complaint filed.", category);
+    Ok(())
+}
+
+/// Resolve a complaint, crediting goodwill if the SLA was breached
+///
+/// When the complaint stayed open for longer than the policy's `sla_slots`, the policy's
+/// goodwill amount is minted in AquaCoin to the consumer. A `ComplaintResolved` event is
+/// emitted either way, so resolution times can be reported on.
+///
+/// # Arguments
+/// * `ctx` - Context containing the complaint, policy, consumer, agency and token accounts
+///
+/// # Errors
+/// * `CustomError::ComplaintAlreadyResolved` - If the complaint was already resolved
+///
+/// # Returns
+/// * `Ok(())` on successful resolution
+pub fn resolve_complaint(ctx: Context<ResolveComplaint>) -> Result<()> {
+    require!(
+        ctx.accounts.complaint.is_open(),
+        CustomError::ComplaintAlreadyResolved
+    );
+
+    let slot = Clock::get()?.slot;
+    let goodwill = ctx
+        .accounts
+        .complaint_policy
+        .goodwill_for(ctx.accounts.complaint.opened_slot, slot);
+
+    if goodwill > 0 {
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    to: ctx.accounts.consumer_aqc.to_account_info(),
+                    authority: ctx.accounts.agency.to_account_info(),
+                    mint: ctx.accounts.aqc_mint.to_account_info(),
+                },
+            ),
+            goodwill,
+        )?;
+    }
+
+    let complaint = &mut ctx.accounts.complaint;
+    complaint.resolved_slot = slot;
+    complaint.goodwill_credited = goodwill;
+
+    emit!(ComplaintResolved {
+        complaint: complaint.key(),
+        consumer: complaint.consumer,
+        category: complaint.category,
+        opened_slot: complaint.opened_slot,
+        resolved_slot: slot,
+        goodwill,
+    });
+
+    msg!("Complaint resolved, {} AQC credited as goodwill.", goodwill);
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use crate::{
+    state::{ConservationCertificate, Consumer, Tariff},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, CreateAccount},
+};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token_2022::{
+        self,
+        spl_token_2022::{extension::ExtensionType, instruction::AuthorityType, state::Mint},
+        Token2022,
+    },
+    token_2022_extensions::{non_transferable_mint_initialize, NonTransferableMintInitialize},
+};
+
+/// Award conservation certificate instruction context
+///
+/// The **AwardConservationCertificate** context is used by the agency, or a crank holding
+/// its key, to mint a non-transferable certificate to a consumer whose conservation streak
+/// has reached the number of periods its tariff requires. The consumer does not need to
+/// sign since the certificate can only be received.
+///
+/// # Fields
+/// * `consumer` - The consumer account awarded the certificate
+/// * `tariff` - The PDA tariff account assigned to this consumer, defining the streak length
+/// * `certificate` - The PDA account recording the certificate
+/// * `certificate_mint` - A new keypair account initialised as the certificate's
+///   non-transferable mint (must be signer)
+/// * `consumer_certificate_token` - The consumer's associated token account for the
+///   certificate mint, created by the instruction
+/// * `agency` - The agency awarding the certificate and paying for its accounts
+/// * `token_2022_program` - Required for Token-2022 operations
+/// * `associated_token_program` - Required for associated token account creation
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+///
+/// # Seeds for ConservationCertificate PDA
+/// * `"conservation_certificate"` - Constant string
+/// * `consumer` - Consumer's public key
+/// * `index` - Number of certificates already awarded to the consumer, little-endian
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey)]
+pub struct AwardConservationCertificate<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            &tariff_key.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(
+        init,
+        seeds = [
+            b"conservation_certificate",
+            consumer.key().as_ref(),
+            &consumer.certificates_awarded.to_le_bytes()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + ConservationCertificate::INIT_SPACE
+    )]
+    pub certificate: Account<'info, ConservationCertificate>,
+    #[account(mut)]
+    pub certificate_mint: Signer<'info>,
+    /// CHECK: created as the consumer's associated token account by the instruction
+    #[account(mut)]
+    pub consumer_certificate_token: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Mint a non-transferable conservation certificate to a consumer
+///
+/// Spends the tariff's `conservation_periods` from the consumer's conservation streak,
+/// which `refresh_capacity` extends each time a period closes with usage under the
+/// tariff's baseline. A Token-2022 mint with the NonTransferable extension is created for
+/// the certificate, a single token is minted to the consumer's associated token account,
+/// and the mint authority is revoked so no further tokens can be issued.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, tariff, certificate and token accounts
+/// * `tariff_key` - Public key of the tariff assigned to this consumer
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If tariff_key does not match the consumer's assigned tariff
+/// * `CustomError::ConservationStreakIncomplete` - If the tariff awards no certificates or
+///   the consumer's streak is shorter than the tariff requires
+///
+/// # Returns
+/// * `Ok(())` on successful award
+pub fn award_conservation_certificate(
+    ctx: Context<AwardConservationCertificate>,
+    tariff_key: Pubkey,
+) -> Result<()> {
+    require_keys_eq!(
+        tariff_key,
+        ctx.accounts.consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+
+    let periods = ctx.accounts.tariff.conservation_periods;
+    let index = ctx.accounts.consumer.award_certificate(periods)?;
+
+    create_certificate_mint(&ctx)?;
+
+    associated_token::create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: ctx.accounts.agency.to_account_info(),
+            associated_token: ctx.accounts.consumer_certificate_token.to_account_info(),
+            authority: ctx.accounts.consumer.to_account_info(),
+            mint: ctx.accounts.certificate_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_2022_program.to_account_info(),
+        },
+    ))?;
+
+    token_2022::mint_to(
+        CpiContext::new(
+            ctx.accounts.token_2022_program.to_account_info(),
+            token_2022::MintTo {
+                mint: ctx.accounts.certificate_mint.to_account_info(),
+                to: ctx.accounts.consumer_certificate_token.to_account_info(),
+                authority: ctx.accounts.agency.to_account_info(),
+            },
+        ),
+        1,
+    )?;
+
+    token_2022::set_authority(
+        CpiContext::new(
+            ctx.accounts.token_2022_program.to_account_info(),
+            token_2022::SetAuthority {
+                current_authority: ctx.accounts.agency.to_account_info(),
+                account_or_mint: ctx.accounts.certificate_mint.to_account_info(),
+            },
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    let certificate = &mut ctx.accounts.certificate;
+    certificate.consumer = ctx.accounts.consumer.key();
+    certificate.mint = ctx.accounts.certificate_mint.key();
+    certificate.index = index;
+    certificate.periods = periods;
+    certificate.awarded_slot = Clock::get()?.slot;
+
+    msg!(
+        "Conservation certificate {} awarded for {} periods.",
+        index,
+        periods
+    );
+    Ok(())
+}
+
+/// Creates the certificate mint with the NonTransferable extension, authority the agency
+fn create_certificate_mint(ctx: &Context<AwardConservationCertificate>) -> Result<()> {
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::NonTransferable])?;
+    system_program::create_account(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.agency.to_account_info(),
+                to: ctx.accounts.certificate_mint.to_account_info(),
+            },
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &ctx.accounts.token_2022_program.key(),
+    )?;
+
+    non_transferable_mint_initialize(CpiContext::new(
+        ctx.accounts.token_2022_program.to_account_info(),
+        NonTransferableMintInitialize {
+            token_program_id: ctx.accounts.token_2022_program.to_account_info(),
+            mint: ctx.accounts.certificate_mint.to_account_info(),
+        },
+    ))?;
+
+    token_2022::initialize_mint2(
+        CpiContext::new(
+            ctx.accounts.token_2022_program.to_account_info(),
+            token_2022::InitializeMint2 {
+                mint: ctx.accounts.certificate_mint.to_account_info(),
+            },
+        ),
+        0,
+        &ctx.accounts.agency.key(),
+        None,
+    )
+}
@@ -0,0 +1,236 @@
+use crate::{
+    events::ConsumerCompressed,
+    state::{Consumer, ConsumerHydration, ConsumerTree},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+
+/// Initialize consumer tree instruction context
+///
+/// The **InitializeConsumerTree** context is used by the agency to create an empty tree
+/// that consumer accounts can be compressed into.
+///
+/// # Fields
+/// * `consumer_tree` - The PDA account storing the tree
+/// * `agency` - The authority that manages the consumers
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for ConsumerTree PDA
+/// * `"consumer_tree"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tree_key` - Unique identifier for the consumer tree
+#[derive(Accounts)]
+#[instruction(tree_key: Pubkey)]
+pub struct InitializeConsumerTree<'info> {
+    #[account(
+        init,
+        seeds = [b"consumer_tree", agency.key().as_ref(), &tree_key.as_ref()],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + ConsumerTree::INIT_SPACE
+    )]
+    pub consumer_tree: Account<'info, ConsumerTree>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Compress consumer instruction context
+///
+/// The **CompressConsumer** context is used by the agency to close an inactive consumer
+/// account into a leaf of a consumer tree, reclaiming its rent.
+///
+/// # Fields
+/// * `consumer` - The consumer account to compress, closed to the agency
+/// * `consumer_tree` - The PDA account storing the tree
+/// * `agency` - The authority that manages the consumers
+///
+/// # Seeds for ConsumerTree PDA
+/// * `"consumer_tree"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tree_key` - Unique identifier for the consumer tree
+#[derive(Accounts)]
+#[instruction(tree_key: Pubkey)]
+pub struct CompressConsumer<'info> {
+    #[account(mut, close = agency)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        mut,
+        seeds = [b"consumer_tree", agency.key().as_ref(), &tree_key.as_ref()],
+        bump
+    )]
+    pub consumer_tree: Account<'info, ConsumerTree>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+}
+
+/// Decompress consumer instruction context
+///
+/// The **DecompressConsumer** context is used to restore a compressed consumer account
+/// from its leaf when the consumer becomes active again.
+///
+/// # Fields
+/// * `consumer` - The consumer account to restore (must be signer)
+/// * `consumer_tree` - The PDA account storing the tree
+/// * `consumer_hydration` - The PDA account marking the leaf as restored
+/// * `agency` - The authority that manages the consumers
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for ConsumerTree PDA
+/// * `"consumer_tree"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tree_key` - Unique identifier for the consumer tree
+///
+/// # Seeds for ConsumerHydration PDA
+/// * `"consumer_hydration"` - Constant string
+/// * `consumer_tree` - Consumer tree's public key
+/// * `leaf_index` - Position of the leaf, as little-endian bytes
+#[derive(Accounts)]
+#[instruction(tree_key: Pubkey, leaf_index: u64)]
+pub struct DecompressConsumer<'info> {
+    #[account(init, payer = agency, space = DISCRIMINATOR + Consumer::INIT_SPACE)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [b"consumer_tree", agency.key().as_ref(), &tree_key.as_ref()],
+        bump
+    )]
+    pub consumer_tree: Account<'info, ConsumerTree>,
+    #[account(
+        init,
+        seeds = [
+            b"consumer_hydration",
+            consumer_tree.key().as_ref(),
+            &leaf_index.to_le_bytes()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + ConsumerHydration::INIT_SPACE
+    )]
+    pub consumer_hydration: Account<'info, ConsumerHydration>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Initialize an empty consumer tree
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer tree and agency accounts
+/// * `tree_key` - Public key identifying the consumer tree
+///
+/// # Returns
+/// * `Ok(())` on successful initialization
+pub fn initialize_consumer_tree(
+    ctx: Context<InitializeConsumerTree>,
+    tree_key: Pubkey,
+) -> Result<()> {
+    ctx.accounts.consumer_tree.initialize(tree_key);
+
+    msg!("Consumer tree initialized.");
+    Ok(())
+}
+
+/// Compress a consumer account into a consumer tree
+///
+/// This function appends a leaf hashing the consumer's key and account data to the tree,
+/// emits the data in a `ConsumerCompressed` event so indexers can serve proofs, and closes
+/// the account, returning its rent to the agency. Only consumers with no outstanding water
+/// debt and no sub-consumers can be compressed. Token accounts are left untouched.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, consumer tree and agency accounts
+/// * `tree_key` - Public key identifying the consumer tree
+///
+/// # Errors
+/// * `CustomError::OutstandingDebt` - If the consumer has outstanding water debt
+/// * `CustomError::InvalidConsumerHierarchy` - If the consumer has sub-consumers
+/// * `CustomError::ConsumerTreeFull` - If the tree has no leaves left
+///
+/// # Returns
+/// * `Ok(())` on successful compression
+pub fn compress_consumer(ctx: Context<CompressConsumer>, tree_key: Pubkey) -> Result<()> {
+    let consumer = &ctx.accounts.consumer;
+
+    require!(
+        consumer.outstanding_water_debt == 0,
+        CustomError::OutstandingDebt
+    );
+    require!(
+        consumer.sub_consumer_count == 0,
+        CustomError::InvalidConsumerHierarchy
+    );
+
+    let consumer_key = consumer.key();
+    let data = consumer.try_to_vec()?;
+    let leaf_index = ctx
+        .accounts
+        .consumer_tree
+        .append(ConsumerTree::leaf(&consumer_key, &data))?;
+
+    emit!(ConsumerCompressed {
+        consumer: consumer_key,
+        tree_key,
+        leaf_index,
+        data,
+        slot: Clock::get()?.slot,
+    });
+
+    msg!("Consumer compressed into leaf {}.", leaf_index);
+    Ok(())
+}
+
+/// Restore a compressed consumer account from its leaf
+///
+/// This function checks that the consumer's key and `data` hash into the leaf at
+/// `leaf_index`, using a proof built against a recent root of the tree, then recreates
+/// the consumer account with that data. Each leaf can only be restored once.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, consumer tree, hydration and agency accounts
+/// * `tree_key` - Public key identifying the consumer tree
+/// * `leaf_index` - Position of the consumer's leaf in the tree
+/// * `data` - The consumer's serialized account data, as emitted on compression
+/// * `root` - The root the proof was built against
+/// * `proof` - Sibling nodes from the leaf up to the root
+///
+/// # Errors
+/// * `CustomError::UnknownMerkleRoot` - If the root is not a recent root of the tree
+/// * `CustomError::InvalidMerkleProof` - If the leaf is not in the tree
+///
+/// # Returns
+/// * `Ok(())` on successful restoration
+pub fn decompress_consumer(
+    ctx: Context<DecompressConsumer>,
+    _tree_key: Pubkey,
+    leaf_index: u64,
+    data: Vec<u8>,
+    root: [u8; 32],
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let consumer_tree = &ctx.accounts.consumer_tree;
+    let consumer_key = ctx.accounts.consumer.key();
+
+    require!(
+        consumer_tree.is_recent_root(&root),
+        CustomError::UnknownMerkleRoot
+    );
+    require!(
+        consumer_tree.verify(
+            ConsumerTree::leaf(&consumer_key, &data),
+            leaf_index,
+            &root,
+            &proof
+        ),
+        CustomError::InvalidMerkleProof
+    );
+
+    let record = Consumer::try_from_slice(&data)?;
+    ctx.accounts.consumer.set_inner(record);
+
+    let consumer_hydration = &mut ctx.accounts.consumer_hydration;
+    consumer_hydration.consumer = consumer_key;
+    consumer_hydration.hydrated_slot = Clock::get()?.slot;
+
+    msg!("Consumer restored from leaf {}.", leaf_index);
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use crate::{
+    events::{ReservoirSpilled, WaterConveyed},
+    state::{RegulatorAuthority, Reservoir, Role, RoleKind, SpillRecord},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+
+/// Convey water instruction context
+///
+/// The **ConveyWater** context is used by the agency, or a staff key holding the
+/// MeterOperator role, to record water moved from one of its reservoirs to another.
+/// Transfers between reservoirs in different river basins must be co-signed by a
+/// regulator.
+///
+/// # Fields
+/// * `source` - The PDA account of the reservoir the water is drawn from
+/// * `destination` - The PDA account of the reservoir the water is delivered to
+/// * `destination_spill_record` - The PDA account tallying the destination's spills
+/// * `agency` - The agency the operation is performed for
+/// * `authority` - The agency or a staff key holding the MeterOperator role
+/// * `role` - Role account of the authority, when it is not the agency
+/// * `regulator_authority` - The PDA account recording the agency's regulator
+/// * `regulator` - The regulator approving an inter-basin transfer (must be signer)
+/// * `regulator_role` - Role account of the regulator, when it is not the regulator authority
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for Reservoir PDAs
+/// * `"reservoir"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+///
+/// # Seeds for SpillRecord PDA
+/// * `"spill_record"` - Constant string
+/// * `agency` - Agency's public key
+/// * `destination_key` - Unique identifier for the destination reservoir
+///
+/// # Seeds for RegulatorAuthority PDA
+/// * `"regulator_authority"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+#[instruction(source_key: Pubkey, destination_key: Pubkey)]
+pub struct ConveyWater<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"reservoir",
+            agency.key().as_ref(),
+            &source_key.as_ref()
+        ],
+        bump
+    )]
+    pub source: Account<'info, Reservoir>,
+    #[account(
+        mut,
+        seeds = [
+            b"reservoir",
+            agency.key().as_ref(),
+            &destination_key.as_ref()
+        ],
+        bump
+    )]
+    pub destination: Account<'info, Reservoir>,
+    #[account(
+        init_if_needed,
+        seeds = [
+            b"spill_record",
+            agency.key().as_ref(),
+            &destination_key.as_ref()
+        ],
+        bump,
+        payer = authority,
+        space = DISCRIMINATOR + SpillRecord::INIT_SPACE
+    )]
+    pub destination_spill_record: Account<'info, SpillRecord>,
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
+    #[account(seeds = [b"regulator_authority", agency.key().as_ref()], bump)]
+    pub regulator_authority: Option<Account<'info, RegulatorAuthority>>,
+    pub regulator: Option<Signer<'info>>,
+    pub regulator_role: Option<Account<'info, Role>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Move water from one reservoir to another
+///
+/// The volume is drawn from the source reservoir and added to the destination. When the
+/// two reservoirs lie in different basins, the transfer is legally controlled: the agency's
+/// regulator, or a key it granted the Regulator role, must co-sign it, and the hash of the
+/// permit it was granted under is recorded in the `WaterConveyed` event.
+///
+/// Water that would fill the destination past its capacity spills: the level is capped at
+/// capacity, the excess is tallied in the destination's spill record and a
+/// `ReservoirSpilled` event is emitted.
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir, agency, authority and regulator accounts
+/// * `source_key` - Unique identifier of the reservoir the water is drawn from
+/// * `destination_key` - Unique identifier of the reservoir the water is delivered to
+/// * `volume` - Volume of water conveyed
+/// * `permit_hash` - SHA-256 hash of the transfer permit, ignored within a basin
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the authority or regulator holds no permitting role
+/// * `CustomError::InvalidAmount` - If volume is zero
+/// * `CustomError::InvalidConveyance` - If the source and destination are the same reservoir
+/// * `CustomError::MissingRegulatorApproval` - If an inter-basin transfer is not co-signed by
+///   the agency's regulator
+/// * `CustomError::InvalidDocumentHash` - If an inter-basin transfer has an empty permit hash
+/// * `CustomError::InsufficientReservoirLevel` - If the source holds less than the volume
+///
+/// # Returns
+/// * `Ok(())` on successful conveyance
+pub fn convey_water(
+    ctx: Context<ConveyWater>,
+    source_key: Pubkey,
+    destination_key: Pubkey,
+    volume: u64,
+    permit_hash: [u8; 32],
+) -> Result<()> {
+    let agency = ctx.accounts.agency.key();
+    Role::authorize(
+        &agency,
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::MeterOperator,
+    )?;
+
+    require!(volume > 0, CustomError::InvalidAmount);
+    require_keys_neq!(source_key, destination_key, CustomError::InvalidConveyance);
+
+    let crosses_basin = ctx.accounts.source.crosses_basin(&ctx.accounts.destination);
+    let (regulator, permit_hash) = if crosses_basin {
+        let regulator = ctx
+            .accounts
+            .regulator
+            .as_ref()
+            .ok_or(CustomError::MissingRegulatorApproval)?
+            .key();
+        ctx.accounts
+            .regulator_authority
+            .as_ref()
+            .ok_or(CustomError::MissingRegulatorApproval)?
+            .authorize(&regulator, ctx.accounts.regulator_role.as_deref())?;
+        require!(permit_hash != [0; 32], CustomError::InvalidDocumentHash);
+        (Some(regulator), permit_hash)
+    } else {
+        (None, [0; 32])
+    };
+
+    let slot = Clock::get()?.slot;
+    ctx.accounts.source.withdraw(volume)?;
+    let spilled = ctx.accounts.destination.fill(volume);
+
+    emit!(WaterConveyed {
+        source: source_key,
+        destination: destination_key,
+        volume,
+        regulator,
+        permit_hash,
+        slot,
+    });
+    if spilled > 0 {
+        let spill_record = &mut ctx.accounts.destination_spill_record;
+        spill_record.reservoir_key = destination_key;
+        spill_record.record(spilled, slot);
+        emit!(ReservoirSpilled {
+            reservoir_key: destination_key,
+            spilled,
+            total_spilled: spill_record.total_spilled,
+            capacity: ctx.accounts.destination.capacity,
+            slot,
+        });
+    }
+
+    msg!("Conveyed {} between reservoirs.", volume);
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use crate::{state::Reservoir, CustomError};
+use anchor_lang::prelude::*;
+
+/// Decommission reservoir instruction context
+///
+/// The **DecommissionReservoir** context is used by the agency to close a reservoir that
+/// no longer serves any consumers, returning its rent.
+///
+/// # Fields
+/// * `reservoir` - The PDA account to close, returning its rent to the agency
+/// * `agency` - The owner that is authorized to sign operations on its behalf
+///
+/// # Seeds
+/// * `"reservoir"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for this reservoir
+#[derive(Accounts)]
+#[instruction(reservoir_key: Pubkey)]
+pub struct DecommissionReservoir<'info> {
+    #[account(
+        mut,
+        close = agency,
+        seeds = [
+            b"reservoir",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+}
+
+/// Decommission a reservoir and reclaim its rent
+///
+/// All consumers assigned to the reservoir must first be moved to another reservoir with
+/// `update_consumer_reservoir`. Compressed consumers still count as assigned, since they
+/// are restored with their original reservoir.
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir and agency accounts
+/// * `reservoir_key` - Unique public key identifier for this reservoir
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If reservoir_key doesn't match the account's key
+/// * `CustomError::ReservoirInUse` - If consumers are still assigned to the reservoir
+///
+/// # Returns
+/// * `Ok(())` on successful decommissioning
+pub fn decommission_reservoir(
+    ctx: Context<DecommissionReservoir>,
+    reservoir_key: Pubkey,
+) -> Result<()> {
+    let reservoir = &ctx.accounts.reservoir;
+
+    require_keys_eq!(
+        reservoir_key,
+        reservoir.reservoir_key,
+        CustomError::Unauthorized
+    );
+    require!(
+        reservoir.assigned_consumer_count == 0,
+        CustomError::ReservoirInUse
+    );
+
+    msg!("Reservoir decommissioned.");
+    Ok(())
+}
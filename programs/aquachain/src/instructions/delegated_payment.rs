@@ -0,0 +1,245 @@
+use crate::{
+    events::PaymentReceived,
+    state::{AgencyStats, Consumer, PaymentReceipt, Tariff},
+    utils::burn_as_billing_authority,
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+/// Pay for water delegated instruction context
+///
+/// The **PayForWaterDelegated** context is used by the agency's settlement crank to burn
+/// WTK from a consumer's account, through the delegation the consumer approved at
+/// registration, once the consumer's payment has been received.
+///
+/// # Fields
+/// * `consumer` - The consumer account whose debt is paid
+/// * `tariff` - The PDA tariff account assigned to this consumer
+/// * `agency` - The agency that received the payment (must be signer)
+/// * `billing_authority` - The program PDA the consumer approved as delegate
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
+/// * `receipt` - The PDA account that will store the payment receipt
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `wtk_mint` - The WTK token mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `assigned_tariff` - The consumer's assigned tariff
+///
+/// # Seeds for Billing Authority PDA
+/// * `"billing_authority"` - Constant string
+/// * `agency` - Agency's public key
+///
+/// # Seeds for PaymentReceipt PDA
+/// * `"receipt"` - Constant string
+/// * `consumer` - Consumer's public key
+/// * `receipts_issued` - The consumer's receipt count, as little-endian bytes
+#[derive(Accounts)]
+pub struct PayForWaterDelegated<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            consumer.assigned_tariff.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    /// CHECK: PDA used only as an SPL token delegate, it holds no data
+    #[account(seeds = [b"billing_authority", agency.key().as_ref()], bump)]
+    pub billing_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>,
+    #[account(
+        init,
+        seeds = [
+            b"receipt",
+            consumer.key().as_ref(),
+            consumer.receipts_issued.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + PaymentReceipt::INIT_SPACE
+    )]
+    pub receipt: Account<'info, PaymentReceipt>,
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency)]
+    pub wtk_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Pay for waste delegated instruction context
+///
+/// The **PayForWasteDelegated** context is used by the agency's settlement crank to burn
+/// WST from a consumer's account, through the delegation the consumer approved at
+/// registration, once the consumer's payment has been received.
+///
+/// # Fields
+/// * `consumer` - The consumer account whose debt is paid
+/// * `tariff` - The PDA tariff account assigned to this consumer
+/// * `agency` - The agency that received the payment (must be signer)
+/// * `billing_authority` - The program PDA the consumer approved as delegate
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
+/// * `consumer_wst` - The consumer's WST token account
+/// * `wst_mint` - The WST token mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `assigned_tariff` - The consumer's assigned tariff
+///
+/// # Seeds for Billing Authority PDA
+/// * `"billing_authority"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct PayForWasteDelegated<'info> {
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            consumer.assigned_tariff.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    pub agency: Signer<'info>,
+    /// CHECK: PDA used only as an SPL token delegate, it holds no data
+    #[account(seeds = [b"billing_authority", agency.key().as_ref()], bump)]
+    pub billing_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>,
+    #[account(mut, associated_token::mint = wst_mint, associated_token::authority = consumer)]
+    pub consumer_wst: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency)]
+    pub wst_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Pay for water on the consumer's behalf by burning delegated WTK tokens
+///
+/// This is the server-side counterpart of `pay_for_water`: the agency records a payment
+/// it received and burns the paid WTK as the billing authority, without the consumer's
+/// signature. The consumer keeps control of its account and can stop delegated payments
+/// at any time by revoking the delegation. Each payment issues a `PaymentReceipt`.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, tariff, agency and token accounts
+/// * `amount` - Amount of WTK tokens to burn as payment
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If amount is zero
+/// * `CustomError::OverPayment` - If payment amount exceeds consumer's outstanding debt or WTK balance
+/// * `CustomError::InsufficientDelegation` - If the consumer has not delegated its WTK account
+///   to the billing authority, or has revoked the delegation
+///
+/// # Returns
+/// * `Ok(())` on successful payment
+pub fn pay_for_water_delegated(ctx: Context<PayForWaterDelegated>, amount: u64) -> Result<()> {
+    require!(amount > 0, CustomError::InvalidAmount);
+    require!(
+        ctx.accounts.consumer_wtk.amount >= amount,
+        CustomError::OverPayment
+    );
+    ctx.accounts.consumer.pay_water(amount)?;
+    ctx.accounts.agency_stats.record_payment(amount);
+
+    let agency_key = ctx.accounts.agency.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"billing_authority",
+        agency_key.as_ref(),
+        &[ctx.bumps.billing_authority],
+    ]];
+
+    burn_as_billing_authority(
+        &ctx.accounts.token_program,
+        ctx.accounts.wtk_mint.to_account_info(),
+        &ctx.accounts.consumer_wtk,
+        ctx.accounts.billing_authority.to_account_info(),
+        signer_seeds,
+        amount,
+    )?;
+
+    let slot = Clock::get()?.slot;
+    let consumer_key = ctx.accounts.consumer.key();
+    ctx.accounts
+        .receipt
+        .issue(consumer_key, &mut ctx.accounts.consumer, amount, slot);
+
+    emit!(PaymentReceived {
+        consumer: consumer_key,
+        amount,
+        outstanding: ctx.accounts.consumer.outstanding_water_debt,
+        slot,
+    });
+
+    msg!(
+        "Burned {} delegated WTK tokens, {} outstanding.",
+        amount,
+        ctx.accounts.consumer.outstanding_water_debt
+    );
+    Ok(())
+}
+
+/// Pay for waste treatment on the consumer's behalf by burning delegated WST tokens
+///
+/// This is the server-side counterpart of `pay_for_waste`, burning as the billing
+/// authority without the consumer's signature.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, tariff, agency and token accounts
+/// * `amount` - Amount of WST tokens to burn as payment
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If amount is zero
+/// * `CustomError::OverPayment` - If payment amount exceeds consumer's WST balance
+/// * `CustomError::InsufficientDelegation` - If the consumer has not delegated its WST account
+///   to the billing authority, or has revoked the delegation
+///
+/// # Returns
+/// * `Ok(())` on successful payment
+pub fn pay_for_waste_delegated(ctx: Context<PayForWasteDelegated>, amount: u64) -> Result<()> {
+    require!(amount > 0, CustomError::InvalidAmount);
+    require!(
+        ctx.accounts.consumer_wst.amount >= amount,
+        CustomError::OverPayment
+    );
+
+    let agency_key = ctx.accounts.agency.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"billing_authority",
+        agency_key.as_ref(),
+        &[ctx.bumps.billing_authority],
+    ]];
+
+    burn_as_billing_authority(
+        &ctx.accounts.token_program,
+        ctx.accounts.wst_mint.to_account_info(),
+        &ctx.accounts.consumer_wst,
+        ctx.accounts.billing_authority.to_account_info(),
+        signer_seeds,
+        amount,
+    )?;
+    ctx.accounts.agency_stats.record_payment(amount);
+
+    msg!("Burned {} delegated WST tokens.", amount);
+    Ok(())
+}
@@ -0,0 +1,401 @@
+use crate::{
+    events::{CurtailmentBidSettled, DrEventAnnounced},
+    state::{Consumer, DrBid, DrEvent, Reservoir, Tariff},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Announce demand-response event instruction context
+///
+/// The **AnnounceDrEvent** context is used by the agency to call for reductions in usage
+/// from the consumers of a reservoir during a shortage window.
+///
+/// # Fields
+/// * `dr_event` - The PDA account that will store the event
+/// * `reservoir` - The PDA account of the reservoir the event relieves
+/// * `settlement_mint` - The stablecoin mint reductions are paid in
+/// * `agency` - The agency calling the event
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for DrEvent PDA
+/// * `"dr_event"` - Constant string
+/// * `agency` - Agency's public key
+/// * `event_key` - Unique identifier for the event
+///
+/// # Seeds for Reservoir PDA
+/// * `"reservoir"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+#[derive(Accounts)]
+#[instruction(event_key: Pubkey, reservoir_key: Pubkey)]
+pub struct AnnounceDrEvent<'info> {
+    #[account(
+        init,
+        seeds = [
+            b"dr_event",
+            agency.key().as_ref(),
+            &event_key.as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + DrEvent::INIT_SPACE
+    )]
+    pub dr_event: Account<'info, DrEvent>,
+    #[account(
+        seeds = [
+            b"reservoir",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    pub settlement_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Bid curtailment instruction context
+///
+/// The **BidCurtailment** context is used by a consumer to commit to a reduction in usage
+/// during a demand-response event. The agency co-signs and pays for the bid account.
+///
+/// # Fields
+/// * `dr_bid` - The PDA account that will store the bid
+/// * `dr_event` - The PDA account of the event bid on
+/// * `consumer` - The consumer account bidding (must be signer)
+/// * `tariff` - The PDA account of the consumer's tariff, whose billing period the
+///   consumer's baseline is averaged over
+/// * `agency` - The agency that called the event
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for DrBid PDA
+/// * `"dr_bid"` - Constant string
+/// * `dr_event` - Event's public key
+/// * `consumer` - Consumer's public key
+///
+/// # Seeds for DrEvent PDA
+/// * `"dr_event"` - Constant string
+/// * `agency` - Agency's public key
+/// * `event_key` - Unique identifier for the event
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `assigned_tariff` - The consumer's assigned tariff
+#[derive(Accounts)]
+pub struct BidCurtailment<'info> {
+    #[account(
+        init,
+        seeds = [
+            b"dr_bid",
+            dr_event.key().as_ref(),
+            consumer.key().as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + DrBid::INIT_SPACE
+    )]
+    pub dr_bid: Account<'info, DrBid>,
+    #[account(
+        mut,
+        seeds = [
+            b"dr_event",
+            agency.key().as_ref(),
+            dr_event.event_key.as_ref()
+        ],
+        bump
+    )]
+    pub dr_event: Account<'info, DrEvent>,
+    #[account(signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            consumer.assigned_tariff.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Settle demand-response event instruction context
+///
+/// The **SettleDrEvent** context is used by the agency to pay a consumer for the reduction
+/// it delivered during a closed demand-response event, from the agency's treasury.
+///
+/// # Fields
+/// * `dr_event` - The PDA account of the event
+/// * `dr_bid` - The PDA account of the consumer's bid
+/// * `consumer` - The consumer that bid
+/// * `consumer_settlement` - The consumer's stablecoin token account
+/// * `agency_settlement` - The agency's stablecoin treasury account
+/// * `settlement_mint` - The stablecoin mint
+/// * `agency` - The agency that called the event
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for DrEvent PDA
+/// * `"dr_event"` - Constant string
+/// * `agency` - Agency's public key
+/// * `event_key` - Unique identifier for the event
+///
+/// # Seeds for DrBid PDA
+/// * `"dr_bid"` - Constant string
+/// * `dr_event` - Event's public key
+/// * `consumer` - Consumer's public key
+#[derive(Accounts)]
+pub struct SettleDrEvent<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"dr_event",
+            agency.key().as_ref(),
+            dr_event.event_key.as_ref()
+        ],
+        bump,
+        has_one = settlement_mint
+    )]
+    pub dr_event: Account<'info, DrEvent>,
+    #[account(
+        mut,
+        seeds = [
+            b"dr_bid",
+            dr_event.key().as_ref(),
+            consumer.key().as_ref()
+        ],
+        bump
+    )]
+    pub dr_bid: Account<'info, DrBid>,
+    pub consumer: Account<'info, Consumer>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = consumer)]
+    pub consumer_settlement: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = agency)]
+    pub agency_settlement: Account<'info, TokenAccount>,
+    pub settlement_mint: Account<'info, Mint>,
+    pub agency: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Call for reductions in usage from a reservoir's consumers during a shortage window
+///
+/// Consumers may bid until the window starts. Emits a `DrEventAnnounced` event so
+/// consumers and their apps can be notified.
+///
+/// # Arguments
+/// * `ctx` - Context containing the event, reservoir, stablecoin mint and agency accounts
+/// * `event_key` - Unique identifier for the event
+/// * `reservoir_key` - Unique identifier for the reservoir the event relieves
+/// * `start_slot` - First slot of the window
+/// * `end_slot` - Slot at which the window closes
+/// * `target_volume` - Total reduction sought, in raw volume units
+/// * `payment_rate` - Stablecoin base units paid per raw volume unit of verified reduction
+/// * `weather_adjustment_bps` - Scaling of consumers' baselines for the forecast weather,
+///   10,000 for none, above it when hot or dry weather raises demand
+///
+/// # Errors
+/// * `CustomError::InvalidDrEvent` - If the window is not in the future or is empty
+/// * `CustomError::InvalidAmount` - If target_volume or payment_rate is zero
+/// * `CustomError::InvalidShare` - If weather_adjustment_bps is zero
+///
+/// # Returns
+/// * `Ok(())` on successful announcement
+#[allow(clippy::too_many_arguments)]
+pub fn announce_dr_event(
+    ctx: Context<AnnounceDrEvent>,
+    event_key: Pubkey,
+    reservoir_key: Pubkey,
+    start_slot: u64,
+    end_slot: u64,
+    target_volume: u64,
+    payment_rate: u64,
+    weather_adjustment_bps: u16,
+) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    require!(
+        start_slot > slot && end_slot > start_slot,
+        CustomError::InvalidDrEvent
+    );
+    require!(
+        target_volume > 0 && payment_rate > 0,
+        CustomError::InvalidAmount
+    );
+    require!(weather_adjustment_bps > 0, CustomError::InvalidShare);
+
+    let dr_event = &mut ctx.accounts.dr_event;
+    dr_event.agency = ctx.accounts.agency.key();
+    dr_event.event_key = event_key;
+    dr_event.reservoir_key = reservoir_key;
+    dr_event.settlement_mint = ctx.accounts.settlement_mint.key();
+    dr_event.payment_rate = payment_rate;
+    dr_event.weather_adjustment_bps = weather_adjustment_bps;
+    dr_event.target_volume = target_volume;
+    dr_event.bid_volume = 0;
+    dr_event.start_slot = start_slot;
+    dr_event.end_slot = end_slot;
+    dr_event.verified_volume = 0;
+    dr_event.paid = 0;
+
+    emit!(DrEventAnnounced {
+        agency: dr_event.agency,
+        event_key,
+        reservoir_key,
+        start_slot,
+        end_slot,
+        target_volume,
+        payment_rate,
+        weather_adjustment_bps,
+    });
+
+    msg!(
+        "Demand-response event announced for slots {} to {}, seeking {}.",
+        start_slot,
+        end_slot,
+        target_volume
+    );
+    Ok(())
+}
+
+/// Commit to a reduction in usage during a demand-response event
+///
+/// The consumer's baseline for the window is its average usage over its last closed
+/// capacity periods, extended over the window and scaled by the event's weather adjustment.
+/// Usage in the current period does not count, so it cannot be inflated ahead of a bid. A
+/// consumer cannot bid more than its baseline, and bids are accepted until the event's
+/// target volume is reached.
+///
+/// # Arguments
+/// * `ctx` - Context containing the bid, event, consumer, tariff and agency accounts
+/// * `volume` - Reduction bid, in raw volume units
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If volume is zero
+/// * `CustomError::DrEventClosed` - If the event's window has started
+/// * `CustomError::Unauthorized` - If the consumer is not served by the event's reservoir
+/// * `CustomError::InvalidCurtailmentBid` - If the bid exceeds the consumer's baseline,
+///   which is zero until a capacity period has closed, or the volume left to bid on the event
+///
+/// # Returns
+/// * `Ok(())` on successful bid
+pub fn bid_curtailment(ctx: Context<BidCurtailment>, volume: u64) -> Result<()> {
+    require!(volume > 0, CustomError::InvalidAmount);
+
+    let slot = Clock::get()?.slot;
+    let consumer = &ctx.accounts.consumer;
+    let dr_event = &mut ctx.accounts.dr_event;
+    require!(slot < dr_event.start_slot, CustomError::DrEventClosed);
+    require_keys_eq!(
+        consumer.assigned_reservoir,
+        dr_event.reservoir_key,
+        CustomError::Unauthorized
+    );
+
+    let baseline = consumer.usage_baseline(
+        ctx.accounts.tariff.billing_period_slots,
+        dr_event.window_slots(),
+        dr_event.weather_adjustment_bps,
+    );
+    require!(volume <= baseline, CustomError::InvalidCurtailmentBid);
+    dr_event.add_bid(volume)?;
+
+    let dr_bid = &mut ctx.accounts.dr_bid;
+    dr_bid.dr_event = dr_event.key();
+    dr_bid.consumer = consumer.key();
+    dr_bid.volume = volume;
+    dr_bid.baseline = baseline;
+    dr_bid.start_slot = dr_event.start_slot;
+    dr_bid.end_slot = dr_event.end_slot;
+    dr_bid.usage = 0;
+    dr_bid.settled = false;
+    dr_bid.payment = 0;
+
+    msg!(
+        "Curtailment of {} bid against a baseline of {}.",
+        volume,
+        baseline
+    );
+    Ok(())
+}
+
+/// Pay a consumer for the reduction it delivered during a closed demand-response event
+///
+/// The verified reduction is the consumer's baseline less the usage recorded on its bid
+/// during the window, up to the volume bid. It is paid at the event's payment rate from
+/// the agency's treasury. Each bid is settled once, and a `CurtailmentBidSettled` event
+/// is emitted even when nothing was saved.
+///
+/// # Arguments
+/// * `ctx` - Context containing the event, bid, consumer, agency and token accounts
+///
+/// # Errors
+/// * `CustomError::DrEventClosed` - If the event's window has not closed yet
+/// * `CustomError::CurtailmentAlreadySettled` - If the bid was already settled
+/// * `CustomError::MathOverflow` - If the payment does not fit in a u64
+///
+/// # Returns
+/// * `Ok(())` on successful settlement
+pub fn settle_dr_event(ctx: Context<SettleDrEvent>) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    require!(
+        slot >= ctx.accounts.dr_event.end_slot,
+        CustomError::DrEventClosed
+    );
+    require!(
+        !ctx.accounts.dr_bid.settled,
+        CustomError::CurtailmentAlreadySettled
+    );
+
+    let reduction = ctx.accounts.dr_bid.verified_reduction();
+    let payment = reduction
+        .checked_mul(ctx.accounts.dr_event.payment_rate)
+        .ok_or(CustomError::MathOverflow)?;
+
+    if payment > 0 {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.agency_settlement.to_account_info(),
+                    to: ctx.accounts.consumer_settlement.to_account_info(),
+                    authority: ctx.accounts.agency.to_account_info(),
+                },
+            ),
+            payment,
+        )?;
+    }
+
+    let dr_bid = &mut ctx.accounts.dr_bid;
+    dr_bid.settled = true;
+    dr_bid.payment = payment;
+
+    let dr_event = &mut ctx.accounts.dr_event;
+    dr_event.verified_volume = dr_event.verified_volume.saturating_add(reduction);
+    dr_event.paid = dr_event.paid.saturating_add(payment);
+
+    emit!(CurtailmentBidSettled {
+        event_key: dr_event.event_key,
+        consumer: dr_bid.consumer,
+        baseline: dr_bid.baseline,
+        usage: dr_bid.usage,
+        reduction,
+        payment,
+        slot,
+    });
+
+    msg!(
+        "Curtailment bid settled: {} saved, {} paid.",
+        reduction,
+        payment
+    );
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use crate::{
+    events::BulkDispensed,
+    state::{AgencyStats, Consumer, Reservoir, Role, RoleKind, Tariff, Tokens},
+    utils::FixedPoint,
+    CustomError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Dispense bulk instruction context
+///
+/// The **DispenseBulk** context is used by a tanker driver to bill a consumer for water
+/// delivered outside the network. The driver signs the volume dispensed, and the agency
+/// co-signs to mint the charge.
+///
+/// # Fields
+/// * `consumer` - The consumer account the water was delivered to
+/// * `tariff` - The PDA tariff account assigned to this consumer
+/// * `reservoir` - The PDA account of the reservoir the tanker was filled from
+/// * `agency` - The authority that can mint tokens
+/// * `driver` - The driver key that dispensed the water (must be signer)
+/// * `driver_role` - Role account of the driver, when it is not the agency
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `wtk_mint` - The WTK token mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+///
+/// # Seeds for Reservoir PDA
+/// * `"reservoir"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+///
+/// # Seeds for Tokens PDA
+/// * `"tokens"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey, reservoir_key: Pubkey)]
+pub struct DispenseBulk<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            &tariff_key.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(
+        mut,
+        seeds = [
+            b"reservoir",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    pub agency: Signer<'info>,
+    pub driver: Signer<'info>,
+    pub driver_role: Option<Account<'info, Role>>,
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>,
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>,
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub wtk_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Bill a consumer for a bulk tanker delivery
+///
+/// This function charges the dispensed volume at the flat bulk rate of the consumer's
+/// tariff, mints the charge as WTK to the consumer and adds it to the consumer's
+/// outstanding debt. The volume is drawn from the reservoir the tanker was filled from,
+/// which need not be the consumer's assigned reservoir. Bulk deliveries are not metered
+/// through the network, so they do not count towards the consumer's block usage.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, tariff, reservoir, driver, agency and token accounts
+/// * `tariff_key` - Public key of the tariff assigned to this consumer
+/// * `reservoir_key` - Public key of the reservoir the tanker was filled from
+/// * `volume` - Volume of water dispensed
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If volume is zero
+/// * `CustomError::Unauthorized` - If tariff_key does not match the consumer's assigned tariff
+/// * `CustomError::MissingRole` - If the driver holds no role permitting the operation
+/// * `CustomError::BulkDeliveryUnavailable` - If the tariff has no bulk rate
+/// * `CustomError::InsufficientReservoirLevel` - If the reservoir holds less than the volume
+/// * `CustomError::MathOverflow` - If the converted volume or charge does not fit in a u64
+///
+/// # Returns
+/// * `Ok(())` on successful billing
+pub fn dispense_bulk(
+    ctx: Context<DispenseBulk>,
+    tariff_key: Pubkey,
+    reservoir_key: Pubkey,
+    volume: u64,
+) -> Result<()> {
+    require!(volume > 0, CustomError::InvalidAmount);
+    require_keys_eq!(
+        tariff_key,
+        ctx.accounts.consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.driver.key(),
+        ctx.accounts.driver_role.as_deref(),
+        RoleKind::Driver,
+    )?;
+
+    let bulk_rate = ctx.accounts.tariff.bulk_rate;
+    require!(bulk_rate > 0, CustomError::BulkDeliveryUnavailable);
+
+    let units = &ctx.accounts.tokens.units;
+    let charge = units.to_currency(units.to_volume(volume)? * FixedPoint::from(bulk_rate))?;
+
+    ctx.accounts.reservoir.withdraw(volume)?;
+
+    let slot = Clock::get()?.slot;
+    if charge > 0 {
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    to: ctx.accounts.consumer_wtk.to_account_info(),
+                    authority: ctx.accounts.agency.to_account_info(),
+                    mint: ctx.accounts.wtk_mint.to_account_info(),
+                },
+            ),
+            charge,
+        )?;
+        ctx.accounts.consumer.bill_water(charge, slot)?;
+    }
+    ctx.accounts.agency_stats.record_usage(volume);
+
+    let co2e = ctx
+        .accounts
+        .reservoir
+        .emissions_for(volume, ctx.accounts.tokens.units.volume_scale);
+    ctx.accounts.consumer.record_emissions(co2e);
+    ctx.accounts.agency_stats.record_emissions(co2e);
+
+    emit!(BulkDispensed {
+        consumer: ctx.accounts.consumer.key(),
+        driver: ctx.accounts.driver.key(),
+        reservoir: reservoir_key,
+        volume,
+        charge,
+        slot,
+    });
+
+    msg!("Bulk delivery of {} billed {}.", volume, charge);
+    Ok(())
+}
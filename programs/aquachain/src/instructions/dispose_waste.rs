@@ -1,5 +1,5 @@
 use crate::{
-    state::{Consumer, Tariff},
+    state::{AgencyStats, Consumer, Tariff, Tokens},
     utils::FixedPoint,
     CustomError,
 };
@@ -17,6 +17,8 @@ use anchor_spl::{
 /// * `consumer` - The consumer account making the payment
 /// * `tariff` - The PDA tariff account assigned to this consumer
 /// * `agency` - The authority that can mint tokens
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
 /// * `consumer_wst` - The consumer's WST token account
 /// * `wst_mint` - The WST token mint
 /// * `token_program` - Required for token operations
@@ -26,6 +28,10 @@ use anchor_spl::{
 /// * `"tariff"` - Constant string
 /// * `agency` - Agency's public key
 /// * `tariff_key` - Unique identifier for the tariff
+///
+/// # Seeds for Tokens PDA
+/// * `"tokens"` - Constant string
+/// * `agency` - Agency's public key
 #[derive(Accounts)]
 #[instruction(tariff_key: Pubkey)]
 pub struct DisposeWaste<'info> {
@@ -38,15 +44,20 @@ pub struct DisposeWaste<'info> {
         bump
     )]
     pub tariff: Account<'info, Tariff>, // Tariff assigned to this consumer
+    #[account(mut)]
     pub consumer: Account<'info, Consumer>, // Consumer account
     #[account(mut)]
     pub agency: Signer<'info>,
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>, // Agency's aggregate statistics
 
     // Token account for the consumer to send WST from
     #[account(mut, associated_token::mint = wst_mint,  associated_token::authority = consumer)]
     pub consumer_wst: Account<'info, TokenAccount>,
     /// Mint of the WasteToken to ensure accounts align on token type
-    #[account(mut, mint::authority = agency, mint::decimals = 9)]
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
     pub wst_mint: Account<'info, Mint>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -56,7 +67,8 @@ pub struct DisposeWaste<'info> {
 ///
 /// This function charges a consumer for their waste disposal by minting WST tokens
 /// to their token account. The amount of tokens minted represents the payment for
-/// waste treatment based on the waste rate in the tariff.
+/// waste treatment based on the waste rate in the tariff. The volume is added to the
+/// consumer's waste for the current period.
 ///
 /// # Arguments
 /// * `ctx` - Context containing consumer, tariff, agency and token accounts
@@ -66,21 +78,23 @@ pub struct DisposeWaste<'info> {
 /// # Errors
 /// * `CustomError::Unauthorized` - If tariff_key does not match consumer's assigned value
 /// * `CustomError::InvalidAmount` - If amount is zero
+/// * `CustomError::MathOverflow` - If the converted volume or cost does not fit in a u64
 ///
 /// # Returns
 /// * `Ok(())` on successful payment
 pub fn dispose_waste(ctx: Context<DisposeWaste>, tariff_key: Pubkey, amount: u64) -> Result<()> {
     let tariff = &ctx.accounts.tariff;
+    let units = &ctx.accounts.tokens.units;
 
     require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
 
     require!(amount > 0, CustomError::InvalidAmount);
 
-    let amount_fp = FixedPoint::from(amount);
+    let amount_fp = units.to_volume(amount)?;
     let waste_rate_fp = FixedPoint::from(tariff.waste_rate);
 
     // Calculate the total cost based on the waste rate
-    let total_cost = amount_fp * waste_rate_fp;
+    let total_cost = units.to_currency(amount_fp * waste_rate_fp)?;
 
     // Mint WST tokens to the consumer's account for waste disposal
     token::mint_to(
@@ -92,9 +106,12 @@ pub fn dispose_waste(ctx: Context<DisposeWaste>, tariff_key: Pubkey, amount: u64
                 mint: ctx.accounts.wst_mint.to_account_info(),
             },
         ),
-        total_cost.into(),
+        total_cost,
     )?;
 
+    ctx.accounts.consumer.record_waste(amount);
+    ctx.accounts.agency_stats.record_waste(amount);
+
     msg!(
         "Disposed {} units of waste and charged {} WasteTokens.",
         amount,
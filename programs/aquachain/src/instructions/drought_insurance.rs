@@ -0,0 +1,349 @@
+use crate::{
+    state::{Consumer, DroughtCover, DroughtInsurance, Reservoir, Tokens},
+    utils::is_valid_bps,
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Configure drought insurance instruction context
+///
+/// The **ConfigureDroughtInsurance** context is used by the agency to create the drought
+/// insurance pool of a reservoir, or to change its parameters.
+///
+/// # Fields
+/// * `drought_insurance` - The PDA account of the reservoir's insurance pool
+/// * `reservoir` - The PDA account of the reservoir whose level triggers payouts
+/// * `agency` - The authority operating the pool
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for DroughtInsurance PDA
+/// * `"drought_insurance"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+///
+/// # Seeds for Reservoir PDA
+/// * `"reservoir"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+#[derive(Accounts)]
+#[instruction(reservoir_key: Pubkey)]
+pub struct ConfigureDroughtInsurance<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [
+            b"drought_insurance",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + DroughtInsurance::INIT_SPACE
+    )]
+    pub drought_insurance: Account<'info, DroughtInsurance>,
+    #[account(
+        seeds = [
+            b"reservoir",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Join drought insurance instruction context
+///
+/// The **JoinDroughtInsurance** context is used by a consumer to opt into the insurance
+/// pool of its reservoir. The agency co-signs and pays for the cover account.
+///
+/// # Fields
+/// * `consumer` - The consumer account opting in (must be signer)
+/// * `drought_insurance` - The PDA account of the reservoir's insurance pool
+/// * `drought_cover` - The PDA account recording the consumer's cover
+/// * `agency` - The authority operating the pool
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for DroughtInsurance PDA
+/// * `"drought_insurance"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+///
+/// # Seeds for DroughtCover PDA
+/// * `"drought_cover"` - Constant string
+/// * `consumer` - Consumer's public key
+#[derive(Accounts)]
+#[instruction(reservoir_key: Pubkey)]
+pub struct JoinDroughtInsurance<'info> {
+    #[account(signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        mut,
+        seeds = [
+            b"drought_insurance",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub drought_insurance: Account<'info, DroughtInsurance>,
+    #[account(
+        init,
+        seeds = [b"drought_cover", consumer.key().as_ref()],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + DroughtCover::INIT_SPACE
+    )]
+    pub drought_cover: Account<'info, DroughtCover>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Leave drought insurance instruction context
+///
+/// The **LeaveDroughtInsurance** context is used by a consumer to opt out of its
+/// reservoir's insurance pool, closing the cover account.
+///
+/// # Fields
+/// * `consumer` - The consumer account opting out (must be signer)
+/// * `drought_insurance` - The PDA account of the reservoir's insurance pool
+/// * `drought_cover` - The PDA account recording the consumer's cover
+/// * `agency` - The authority operating the pool, receiving the cover's rent
+///
+/// # Seeds for DroughtInsurance PDA
+/// * `"drought_insurance"` - Constant string
+/// * `agency` - Agency's public key
+/// * `drought_cover.reservoir_key` - Reservoir of the pool the consumer joined
+///
+/// # Seeds for DroughtCover PDA
+/// * `"drought_cover"` - Constant string
+/// * `consumer` - Consumer's public key
+#[derive(Accounts)]
+pub struct LeaveDroughtInsurance<'info> {
+    #[account(signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        mut,
+        seeds = [
+            b"drought_insurance",
+            agency.key().as_ref(),
+            drought_cover.reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub drought_insurance: Account<'info, DroughtInsurance>,
+    #[account(
+        mut,
+        seeds = [b"drought_cover", consumer.key().as_ref()],
+        bump,
+        close = agency
+    )]
+    pub drought_cover: Account<'info, DroughtCover>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+}
+
+/// Collect drought premium instruction context
+///
+/// The **CollectDroughtPremium** context is used by the agency to bill a covered consumer
+/// the pool's premium for the current capacity period.
+///
+/// # Fields
+/// * `consumer` - The covered consumer account
+/// * `drought_insurance` - The PDA account of the reservoir's insurance pool
+/// * `drought_cover` - The PDA account recording the consumer's cover
+/// * `agency` - The authority that can mint tokens
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `wtk_mint` - The WTK token mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for DroughtInsurance PDA
+/// * `"drought_insurance"` - Constant string
+/// * `agency` - Agency's public key
+/// * `drought_cover.reservoir_key` - Reservoir of the pool the consumer joined
+///
+/// # Seeds for DroughtCover PDA
+/// * `"drought_cover"` - Constant string
+/// * `consumer` - Consumer's public key
+#[derive(Accounts)]
+pub struct CollectDroughtPremium<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        mut,
+        seeds = [
+            b"drought_insurance",
+            agency.key().as_ref(),
+            drought_cover.reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub drought_insurance: Account<'info, DroughtInsurance>,
+    #[account(mut, seeds = [b"drought_cover", consumer.key().as_ref()], bump)]
+    pub drought_cover: Account<'info, DroughtCover>,
+    pub agency: Signer<'info>, // Authority of the provider
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub wtk_mint: Account<'info, Mint>, // Mint for the WaterToken
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Create or reconfigure a reservoir's drought insurance pool
+///
+/// Parameter changes apply from the next premium and the next payout. The pool's balance
+/// and members are kept.
+///
+/// # Arguments
+/// * `ctx` - Context containing the pool, reservoir and agency accounts
+/// * `reservoir_key` - Unique identifier for the reservoir
+/// * `premium` - Amount of WTK billed to each covered consumer per capacity period
+/// * `payout_bps` - Share of the surcharge paid out of the pool, in basis points
+/// * `trigger_level_bps` - Reservoir level below which payouts apply, in bps of capacity
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If premium is zero
+/// * `CustomError::InvalidShare` - If payout_bps or trigger_level_bps exceeds 10,000
+///
+/// # Returns
+/// * `Ok(())` on successful configuration
+pub fn configure_drought_insurance(
+    ctx: Context<ConfigureDroughtInsurance>,
+    reservoir_key: Pubkey,
+    premium: u64,
+    payout_bps: u16,
+    trigger_level_bps: u16,
+) -> Result<()> {
+    require!(premium > 0, CustomError::InvalidAmount);
+    require!(
+        is_valid_bps(payout_bps) && is_valid_bps(trigger_level_bps),
+        CustomError::InvalidShare
+    );
+
+    let drought_insurance = &mut ctx.accounts.drought_insurance;
+    drought_insurance.agency = ctx.accounts.agency.key();
+    drought_insurance.reservoir_key = reservoir_key;
+    drought_insurance.premium = premium;
+    drought_insurance.payout_bps = payout_bps;
+    drought_insurance.trigger_level_bps = trigger_level_bps;
+
+    msg!(
+        "Drought insurance configured: premium {}, payout {} bps below {} bps.",
+        premium,
+        payout_bps,
+        trigger_level_bps
+    );
+    Ok(())
+}
+
+/// Opt a consumer into its reservoir's drought insurance pool
+///
+/// Cover starts once the premium for the current capacity period has been collected.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, pool, cover and agency accounts
+/// * `reservoir_key` - Public key of the reservoir assigned to the consumer
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If reservoir_key doesn't match consumer's assigned reservoir
+///
+/// # Returns
+/// * `Ok(())` on successful opt-in
+pub fn join_drought_insurance(
+    ctx: Context<JoinDroughtInsurance>,
+    reservoir_key: Pubkey,
+) -> Result<()> {
+    let consumer = &ctx.accounts.consumer;
+
+    require_keys_eq!(
+        reservoir_key,
+        consumer.assigned_reservoir,
+        CustomError::Unauthorized
+    );
+
+    let drought_cover = &mut ctx.accounts.drought_cover;
+    drought_cover.consumer = consumer.key();
+    drought_cover.reservoir_key = reservoir_key;
+    drought_cover.paid_period_start = None;
+    drought_cover.total_payouts = 0;
+
+    let drought_insurance = &mut ctx.accounts.drought_insurance;
+    drought_insurance.member_count = drought_insurance.member_count.saturating_add(1);
+
+    msg!("Consumer joined drought insurance.");
+    Ok(())
+}
+
+/// Opt a consumer out of its drought insurance pool
+///
+/// Premiums already collected stay in the pool.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, pool, cover and agency accounts
+///
+/// # Returns
+/// * `Ok(())` on successful opt-out
+pub fn leave_drought_insurance(ctx: Context<LeaveDroughtInsurance>) -> Result<()> {
+    let drought_insurance = &mut ctx.accounts.drought_insurance;
+    drought_insurance.member_count = drought_insurance.member_count.saturating_sub(1);
+
+    msg!("Consumer left drought insurance.");
+    Ok(())
+}
+
+/// Bill a covered consumer the premium for the current capacity period
+///
+/// The premium is minted to the consumer as WTK debt, like any other charge, and added to
+/// the pool's balance. Each period's premium can only be collected once.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, pool, cover, agency and token accounts
+///
+/// # Errors
+/// * `CustomError::PremiumAlreadyPaid` - If the premium for the current period was collected
+/// * `CustomError::MathOverflow` - If the consumer's outstanding debt overflows
+///
+/// # Returns
+/// * `Ok(())` on successful collection
+pub fn collect_drought_premium(ctx: Context<CollectDroughtPremium>) -> Result<()> {
+    let period_start = ctx.accounts.consumer.capacity_period_start;
+    let premium = ctx.accounts.drought_insurance.premium;
+
+    require!(
+        !ctx.accounts.drought_cover.is_covered(period_start),
+        CustomError::PremiumAlreadyPaid
+    );
+
+    token::mint_to(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::MintTo {
+                to: ctx.accounts.consumer_wtk.to_account_info(),
+                authority: ctx.accounts.agency.to_account_info(),
+                mint: ctx.accounts.wtk_mint.to_account_info(),
+            },
+        ),
+        premium,
+    )?;
+
+    ctx.accounts
+        .consumer
+        .bill_water(premium, Clock::get()?.slot)?;
+    ctx.accounts.drought_insurance.deposit(premium);
+    ctx.accounts.drought_cover.paid_period_start = Some(period_start);
+
+    msg!("Drought premium of {} collected.", premium);
+    Ok(())
+}
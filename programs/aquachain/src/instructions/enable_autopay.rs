@@ -0,0 +1,95 @@
+use crate::{state::Consumer, CustomError};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Enable auto-pay instruction context
+///
+/// The **EnableAutopay** context is used by a consumer to opt into automatic collection of
+/// their water debt from a stablecoin account.
+///
+/// # Fields
+/// * `consumer` - The consumer account opting in (must be signer)
+/// * `agency` - The authority that manages the consumer
+/// * `billing_authority` - The program PDA approved as delegate on both token accounts
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `consumer_settlement` - The consumer's stablecoin token account
+/// * `wtk_mint` - The WTK token mint
+/// * `settlement_mint` - The stablecoin mint debts are settled in
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for Billing Authority PDA
+/// * `"billing_authority"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct EnableAutopay<'info> {
+    #[account(mut, signer)]
+    pub consumer: Account<'info, Consumer>,
+    /// CHECK: validated as the authority of the WTK mint
+    pub agency: UncheckedAccount<'info>,
+    /// CHECK: PDA used only as an SPL token delegate, it holds no data
+    #[account(seeds = [b"billing_authority", agency.key().as_ref()], bump)]
+    pub billing_authority: UncheckedAccount<'info>,
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = consumer)]
+    pub consumer_settlement: Account<'info, TokenAccount>,
+    #[account(mint::authority = agency)]
+    pub wtk_mint: Account<'info, Mint>,
+    pub settlement_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Opt a consumer into auto-pay
+///
+/// This function approves the billing authority as delegate on the consumer's stablecoin
+/// account up to `cap`, and on their WTK account so collected debt can be written off.
+/// The consumer can stop auto-pay at any time by revoking the stablecoin delegation.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, agency and token accounts
+/// * `cap` - Maximum amount of stablecoin auto-pay may collect
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If cap is zero
+///
+/// # Returns
+/// * `Ok(())` on successful opt-in
+pub fn enable_autopay(ctx: Context<EnableAutopay>, cap: u64) -> Result<()> {
+    require!(cap > 0, CustomError::InvalidAmount);
+
+    ctx.accounts.consumer.autopay_enabled = true;
+
+    // Consumer pre-approves collection of their debt up to the cap
+    token::approve(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Approve {
+                to: ctx.accounts.consumer_settlement.to_account_info(),
+                delegate: ctx.accounts.billing_authority.to_account_info(),
+                authority: ctx.accounts.consumer.to_account_info(),
+            },
+        ),
+        cap,
+    )?;
+
+    // Consumer allows collected debt to be written off
+    token::approve(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Approve {
+                to: ctx.accounts.consumer_wtk.to_account_info(),
+                delegate: ctx.accounts.billing_authority.to_account_info(),
+                authority: ctx.accounts.consumer.to_account_info(),
+            },
+        ),
+        u64::MAX,
+    )?;
+
+    msg!("Auto-pay enabled with a cap of {}.", cap);
+    Ok(())
+}
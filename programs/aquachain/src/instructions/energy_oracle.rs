@@ -0,0 +1,101 @@
+use crate::{state::EnergyOracle, CustomError, DISCRIMINATOR};
+use anchor_lang::prelude::*;
+
+/// Initialize **EnergyOracle** account context
+///
+/// The **EnergyOracle** account to be initialized requires a PDA with seeds composed
+/// of the agency's public key.
+///
+/// # Fields
+/// * `energy_oracle` - The PDA account that will store the electricity price
+/// * `agency` - The owner that is authorized to sign operations on its behalf
+/// * `system_program` - Required for account creation
+///
+/// # Seeds
+/// * `"energy_oracle"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct InitializeEnergyOracle<'info> {
+    #[account(
+        init,
+        seeds = [b"energy_oracle", agency.key().as_ref()],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + EnergyOracle::INIT_SPACE
+    )]
+    pub energy_oracle: Account<'info, EnergyOracle>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Update existing **EnergyOracle** price context
+///
+/// The price can only be posted by the oracle authority registered on the account.
+///
+/// # Fields
+/// * `energy_oracle` - The oracle account storing the electricity price
+/// * `authority` - The oracle authority posting the price
+#[derive(Accounts)]
+pub struct UpdateEnergyPrice<'info> {
+    #[account(mut, has_one = authority @ CustomError::Unauthorized)]
+    pub energy_oracle: Account<'info, EnergyOracle>,
+    pub authority: Signer<'info>,
+}
+
+/// Register the electricity price feed of an agency
+///
+/// The price starts unset and must be posted by the oracle authority before any energy
+/// surcharge can be billed.
+///
+/// # Arguments
+/// * `ctx` - Context containing the oracle account, agency signer and system program
+/// * `authority` - Public key allowed to post prices
+/// * `max_staleness_slots` - Maximum age in slots of a price usable for billing (must be > 0)
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If max_staleness_slots is 0
+///
+/// # Returns
+/// * `Ok(())` on successful initialization
+pub fn initialize_energy_oracle(
+    ctx: Context<InitializeEnergyOracle>,
+    authority: Pubkey,
+    max_staleness_slots: u64,
+) -> Result<()> {
+    require!(max_staleness_slots > 0, CustomError::InvalidAmount);
+
+    let energy_oracle = &mut ctx.accounts.energy_oracle;
+    energy_oracle.agency = ctx.accounts.agency.key();
+    energy_oracle.authority = authority;
+    energy_oracle.price = 0;
+    energy_oracle.max_staleness_slots = max_staleness_slots;
+    energy_oracle.last_updated_slot = 0;
+
+    msg!("Energy oracle initialized.");
+    Ok(())
+}
+
+/// Post a new electricity price
+///
+/// # Arguments
+/// * `ctx` - Context containing the oracle account and its authority
+/// * `price` - Price of one kWh in the tariff currency, with three implied decimals (must be > 0)
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the signer is not the oracle authority
+/// * `CustomError::InvalidRate` - If price is 0
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_energy_price(ctx: Context<UpdateEnergyPrice>, price: u64) -> Result<()> {
+    let energy_oracle = &mut ctx.accounts.energy_oracle;
+
+    require!(price > 0, CustomError::InvalidRate);
+
+    energy_oracle.price = price;
+    energy_oracle.last_updated_slot = Clock::get()?.slot;
+
+    msg!("Energy price updated to {}.", price);
+    Ok(())
+}
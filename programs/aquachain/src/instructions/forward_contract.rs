@@ -0,0 +1,268 @@
+use crate::{
+    events::CapacityAdjusted,
+    state::{AdjustmentReason, Consumer, ForwardContract, Tariff, Tokens},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Open forward contract instruction context
+///
+/// The **OpenForwardContract** context is used by a consumer to lock its tariff's current
+/// water rate for capacity delivered next period. The agency co-signs to bill the premium.
+///
+/// # Fields
+/// * `consumer` - The consumer account buying capacity forward (must be signer)
+/// * `tariff` - The PDA tariff account assigned to this consumer
+/// * `forward_contract` - The PDA account that will store the contract's terms
+/// * `agency` - The authority that can mint tokens
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `wtk_mint` - The WTK token mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+///
+/// # Seeds for ForwardContract PDA
+/// * `"forward_contract"` - Constant string
+/// * `consumer` - Consumer's public key
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey)]
+pub struct OpenForwardContract<'info> {
+    #[account(mut, signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            &tariff_key.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>, // Tariff assigned to this consumer
+    #[account(
+        init,
+        seeds = [b"forward_contract", consumer.key().as_ref()],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + ForwardContract::INIT_SPACE
+    )]
+    pub forward_contract: Account<'info, ForwardContract>,
+    #[account(mut)]
+    pub agency: Signer<'info>, // Authority of the provider
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub wtk_mint: Account<'info, Mint>, // Mint for the WaterToken
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Settle forward contract instruction context
+///
+/// The **SettleForwardContract** context is used by the agency, or a crank holding its key,
+/// to deliver capacity bought forward once its period has started.
+///
+/// # Fields
+/// * `consumer` - The consumer account the capacity is delivered to
+/// * `forward_contract` - The PDA account storing the contract's terms
+/// * `agency` - The authority that can mint tokens, receiving the contract's rent
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `consumer_watc` - The consumer's WATC token account
+/// * `wtk_mint` - The WTK token mint
+/// * `watc_mint` - The WATC token mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for ForwardContract PDA
+/// * `"forward_contract"` - Constant string
+/// * `consumer` - Consumer's public key
+#[derive(Accounts)]
+pub struct SettleForwardContract<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        mut,
+        seeds = [b"forward_contract", consumer.key().as_ref()],
+        bump,
+        close = agency
+    )]
+    pub forward_contract: Account<'info, ForwardContract>,
+    #[account(mut)]
+    pub agency: Signer<'info>, // Authority of the provider
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = watc_mint, associated_token::authority = consumer)]
+    pub consumer_watc: Account<'info, TokenAccount>, // Consumer's WaterCapacityToken account
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub wtk_mint: Account<'info, Mint>, // Mint for the WaterToken
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub watc_mint: Account<'info, Mint>, // Mint for the WaterCapacityToken
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Lock today's water rate for capacity delivered next period
+///
+/// This function records a forward contract for capacity on top of the consumer's
+/// contracted capacity, priced at the tariff's current water rate and deliverable from the
+/// start of the consumer's next capacity period. The premium is minted to the consumer as
+/// WTK debt straight away. A consumer holds at most one open contract.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, tariff, contract, agency and token accounts
+/// * `tariff_key` - Public key of the tariff assigned to this consumer
+/// * `capacity` - Raw volume of capacity bought forward (must be > 0)
+/// * `premium` - Amount of WTK billed upfront for the lock
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If tariff_key does not match the consumer's assigned tariff
+/// * `CustomError::InvalidAmount` - If capacity is zero
+/// * `CustomError::CapacityRefreshNotDue` - If the tariff has no billing period
+/// * `CustomError::MathOverflow` - If the delivery slot or the consumer's debt overflows
+///
+/// # Returns
+/// * `Ok(())` on successful opening
+pub fn open_forward_contract(
+    ctx: Context<OpenForwardContract>,
+    tariff_key: Pubkey,
+    capacity: u64,
+    premium: u64,
+) -> Result<()> {
+    let consumer = &ctx.accounts.consumer;
+    let tariff = &ctx.accounts.tariff;
+
+    require!(capacity > 0, CustomError::InvalidAmount);
+    require_keys_eq!(
+        tariff_key,
+        consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+    require!(
+        tariff.billing_period_slots > 0,
+        CustomError::CapacityRefreshNotDue
+    );
+
+    let delivery_slot = consumer
+        .capacity_period_start
+        .checked_add(tariff.billing_period_slots)
+        .ok_or(CustomError::MathOverflow)?;
+
+    let forward_contract = &mut ctx.accounts.forward_contract;
+    forward_contract.consumer = consumer.key();
+    forward_contract.tariff_key = tariff_key;
+    forward_contract.capacity = capacity;
+    forward_contract.locked_rate = tariff.water_rate;
+    forward_contract.premium = premium;
+    forward_contract.delivery_slot = delivery_slot;
+
+    if premium > 0 {
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    to: ctx.accounts.consumer_wtk.to_account_info(),
+                    authority: ctx.accounts.agency.to_account_info(),
+                    mint: ctx.accounts.wtk_mint.to_account_info(),
+                },
+            ),
+            premium,
+        )?;
+        ctx.accounts
+            .consumer
+            .bill_water(premium, Clock::get()?.slot)?;
+    }
+
+    msg!(
+        "Forward contract opened for {} capacity at rate {}, deliverable at slot {}.",
+        capacity,
+        ctx.accounts.forward_contract.locked_rate,
+        delivery_slot
+    );
+    Ok(())
+}
+
+/// Deliver capacity bought forward at the locked rate
+///
+/// Once the contract's delivery slot is reached, the capacity is minted to the consumer as
+/// WATC and billed as WTK at the locked rate, whatever the consumer's tariff charges by
+/// then. The contract is closed and a `CapacityAdjusted` event is emitted with the
+/// `ForwardDelivery` reason. The delivered capacity is added on top of the period's
+/// refreshed capacity.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, contract, agency and token accounts
+///
+/// # Errors
+/// * `CustomError::ForwardNotDue` - If the delivery slot has not been reached
+/// * `CustomError::MathOverflow` - If the cost or the consumer's debt overflows
+///
+/// # Returns
+/// * `Ok(())` on successful delivery
+pub fn settle_forward_contract(ctx: Context<SettleForwardContract>) -> Result<()> {
+    let forward_contract = &ctx.accounts.forward_contract;
+    let slot = Clock::get()?.slot;
+
+    require!(
+        slot >= forward_contract.delivery_slot,
+        CustomError::ForwardNotDue
+    );
+
+    let capacity = forward_contract.capacity;
+    let cost = forward_contract.delivery_cost(&ctx.accounts.tokens.units)?;
+
+    token::mint_to(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::MintTo {
+                to: ctx.accounts.consumer_watc.to_account_info(),
+                authority: ctx.accounts.agency.to_account_info(),
+                mint: ctx.accounts.watc_mint.to_account_info(),
+            },
+        ),
+        capacity,
+    )?;
+
+    token::mint_to(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::MintTo {
+                to: ctx.accounts.consumer_wtk.to_account_info(),
+                authority: ctx.accounts.agency.to_account_info(),
+                mint: ctx.accounts.wtk_mint.to_account_info(),
+            },
+        ),
+        cost,
+    )?;
+
+    ctx.accounts.consumer.bill_water(cost, slot)?;
+
+    emit!(CapacityAdjusted {
+        consumer: ctx.accounts.consumer.key(),
+        delta: i64::try_from(capacity).map_err(|_| error!(CustomError::MathOverflow))?,
+        reason: AdjustmentReason::ForwardDelivery,
+        balance: ctx.accounts.consumer_watc.amount.saturating_add(capacity),
+        slot,
+    });
+
+    msg!(
+        "Forward capacity of {} delivered, charged: {}.",
+        capacity,
+        cost
+    );
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use crate::{
+    state::{Role, RoleKind},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+
+/// Grant role instruction context
+///
+/// The **GrantRole** context is used by the agency, or a staff key holding the admin role,
+/// to give a staff key a role within the agency. Granting again changes the member's role.
+///
+/// # Fields
+/// * `role` - The PDA account recording the member's role
+/// * `agency` - The agency the role is granted for
+/// * `authority` - The agency or an admin, paying for the role account
+/// * `authority_role` - Role account of the authority, when it is not the agency
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for Role PDA
+/// * `"role"` - Constant string
+/// * `agency` - Agency's public key
+/// * `member` - Staff key holding the role
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"role", agency.key().as_ref(), member.as_ref()],
+        bump,
+        payer = authority,
+        space = DISCRIMINATOR + Role::INIT_SPACE
+    )]
+    pub role: Account<'info, Role>,
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub authority_role: Option<Account<'info, Role>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Revoke role instruction context
+///
+/// The **RevokeRole** context is used by the agency, or a staff key holding the admin role,
+/// to remove a staff key's role, closing its role account.
+///
+/// # Fields
+/// * `role` - The PDA account recording the member's role
+/// * `agency` - The agency the role was granted for
+/// * `authority` - The agency or an admin, receiving the account's rent
+/// * `authority_role` - Role account of the authority, when it is not the agency
+///
+/// # Seeds for Role PDA
+/// * `"role"` - Constant string
+/// * `agency` - Agency's public key
+/// * `member` - Staff key holding the role
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct RevokeRole<'info> {
+    #[account(
+        mut,
+        seeds = [b"role", agency.key().as_ref(), member.as_ref()],
+        bump,
+        close = authority
+    )]
+    pub role: Account<'info, Role>,
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub authority_role: Option<Account<'info, Role>>,
+}
+
+/// Grant a staff key a role within the agency
+///
+/// Each member holds a single role, so granting a role to a member that already has one
+/// replaces it. The agency key holds every role implicitly and cannot be granted one. The
+/// Regulator role is reserved to the agency's regulator authority, which grants it with
+/// `grant_regulator_role`, so the agency can neither grant nor replace it.
+///
+/// # Arguments
+/// * `ctx` - Context containing the role, agency and authority accounts
+/// * `member` - Staff key to grant the role to
+/// * `kind` - Role to grant
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer is neither the agency nor an admin
+/// * `CustomError::InvalidRoleMember` - If the member is the agency itself
+/// * `CustomError::ReservedRole` - If the role granted or replaced is the Regulator role
+///
+/// # Returns
+/// * `Ok(())` on successful grant
+pub fn grant_role(ctx: Context<GrantRole>, member: Pubkey, kind: RoleKind) -> Result<()> {
+    let agency = ctx.accounts.agency.key();
+
+    Role::authorize(
+        &agency,
+        &ctx.accounts.authority.key(),
+        ctx.accounts.authority_role.as_deref(),
+        RoleKind::Admin,
+    )?;
+    require_keys_neq!(member, agency, CustomError::InvalidRoleMember);
+
+    let role = &mut ctx.accounts.role;
+    require!(
+        kind != RoleKind::Regulator && role.kind != RoleKind::Regulator,
+        CustomError::ReservedRole
+    );
+    role.agency = agency;
+    role.member = member;
+    role.kind = kind;
+    role.granted_slot = Clock::get()?.slot;
+
+    msg!("Role {:?} granted to {}.", kind, member);
+    Ok(())
+}
+
+/// Revoke a staff key's role within the agency
+///
+/// Regulator roles are revoked by the regulator authority with `revoke_regulator_role`.
+///
+/// # Arguments
+/// * `ctx` - Context containing the role, agency and authority accounts
+/// * `member` - Staff key to revoke the role from
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer is neither the agency nor an admin
+/// * `CustomError::ReservedRole` - If the member holds the Regulator role
+///
+/// # Returns
+/// * `Ok(())` on successful revocation
+pub fn revoke_role(ctx: Context<RevokeRole>, member: Pubkey) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.authority_role.as_deref(),
+        RoleKind::Admin,
+    )?;
+    require!(
+        ctx.accounts.role.kind != RoleKind::Regulator,
+        CustomError::ReservedRole
+    );
+
+    msg!("Role revoked from {}.", member);
+    Ok(())
+}
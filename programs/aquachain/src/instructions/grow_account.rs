@@ -0,0 +1,212 @@
+use crate::{
+    state::{Consumer, Reservoir, Tariff, Tokens, UnitConfig},
+    utils::grow_account,
+    CustomError,
+};
+use anchor_lang::prelude::*;
+
+/// Grow consumer instruction context
+///
+/// The **GrowConsumer** context is used to grow a consumer account written by an older
+/// version of the program to the current layout. Growing only appends zeroed fields, so
+/// anyone may pay for it.
+///
+/// # Fields
+/// * `consumer` - The consumer account to grow
+/// * `payer` - The account paying the additional rent (must be signer)
+/// * `system_program` - Required for the rent transfer
+#[derive(Accounts)]
+pub struct GrowConsumer<'info> {
+    /// CHECK: owner and discriminator are checked by `grow_account`, as an account of an
+    /// older layout may not deserialize
+    #[account(mut)]
+    pub consumer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow tariff instruction context
+///
+/// # Fields
+/// * `tariff` - The PDA tariff account to grow
+/// * `agency` - The agency owning the tariff, paying the additional rent (must be signer)
+/// * `system_program` - Required for the rent transfer
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey)]
+pub struct GrowTariff<'info> {
+    /// CHECK: owner and discriminator are checked by `grow_account`, as an account of an
+    /// older layout may not deserialize
+    #[account(
+        mut,
+        seeds = [b"tariff", agency.key().as_ref(), tariff_key.as_ref()],
+        bump
+    )]
+    pub tariff: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow reservoir instruction context
+///
+/// # Fields
+/// * `reservoir` - The PDA reservoir account to grow
+/// * `agency` - The agency owning the reservoir, paying the additional rent (must be signer)
+/// * `system_program` - Required for the rent transfer
+///
+/// # Seeds for Reservoir PDA
+/// * `"reservoir"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+#[derive(Accounts)]
+#[instruction(reservoir_key: Pubkey)]
+pub struct GrowReservoir<'info> {
+    /// CHECK: owner and discriminator are checked by `grow_account`, as an account of an
+    /// older layout may not deserialize
+    #[account(
+        mut,
+        seeds = [b"reservoir", agency.key().as_ref(), reservoir_key.as_ref()],
+        bump
+    )]
+    pub reservoir: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow tokens instruction context
+///
+/// # Fields
+/// * `tokens` - The PDA tokens account to grow
+/// * `agency` - The agency owning the tokens account, paying the additional rent (must be signer)
+/// * `system_program` - Required for the rent transfer
+///
+/// # Seeds for Tokens PDA
+/// * `"tokens"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct GrowTokens<'info> {
+    /// CHECK: owner and discriminator are checked by `grow_account`, as an account of an
+    /// older layout may not deserialize
+    #[account(
+        mut,
+        seeds = [b"tokens", agency.key().as_ref()],
+        bump
+    )]
+    pub tokens: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow a consumer account to the current layout
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer and payer accounts
+///
+/// # Errors
+/// * `ErrorCode::AccountOwnedByWrongProgram` - If the account is not owned by the program
+/// * `ErrorCode::AccountDiscriminatorMismatch` - If the account is not a consumer
+///
+/// # Returns
+/// * `Ok(())` on success, including when the account is already current
+pub fn grow_consumer(ctx: Context<GrowConsumer>) -> Result<()> {
+    let size = grow_account::<Consumer>(
+        &ctx.accounts.consumer,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+
+    msg!("Consumer account is {} bytes.", size);
+    Ok(())
+}
+
+/// Grow a tariff account to the current layout
+///
+/// # Arguments
+/// * `ctx` - Context containing the tariff and agency accounts
+/// * `tariff_key` - Unique identifier of the tariff
+///
+/// # Errors
+/// * `ErrorCode::AccountOwnedByWrongProgram` - If the account is not owned by the program
+/// * `ErrorCode::AccountDiscriminatorMismatch` - If the account is not a tariff
+///
+/// # Returns
+/// * `Ok(())` on success, including when the account is already current
+pub fn grow_tariff(ctx: Context<GrowTariff>, _tariff_key: Pubkey) -> Result<()> {
+    let size = grow_account::<Tariff>(
+        &ctx.accounts.tariff,
+        &ctx.accounts.agency,
+        &ctx.accounts.system_program,
+    )?;
+
+    msg!("Tariff account is {} bytes.", size);
+    Ok(())
+}
+
+/// Grow a reservoir account to the current layout
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir and agency accounts
+/// * `reservoir_key` - Unique identifier of the reservoir
+///
+/// # Errors
+/// * `ErrorCode::AccountOwnedByWrongProgram` - If the account is not owned by the program
+/// * `ErrorCode::AccountDiscriminatorMismatch` - If the account is not a reservoir
+///
+/// # Returns
+/// * `Ok(())` on success, including when the account is already current
+pub fn grow_reservoir(ctx: Context<GrowReservoir>, _reservoir_key: Pubkey) -> Result<()> {
+    let size = grow_account::<Reservoir>(
+        &ctx.accounts.reservoir,
+        &ctx.accounts.agency,
+        &ctx.accounts.system_program,
+    )?;
+
+    msg!("Reservoir account is {} bytes.", size);
+    Ok(())
+}
+
+/// Grow a tokens account to the current layout
+///
+/// Tokens accounts written before billing units were configurable read zeroed units once
+/// grown, which no conversion accepts, so the agency sets its units while growing. Units
+/// already set are left unchanged.
+///
+/// # Arguments
+/// * `ctx` - Context containing the tokens and agency accounts
+/// * `units` - Volume scale and currency decimals to set when the account has none
+///
+/// # Errors
+/// * `ErrorCode::AccountOwnedByWrongProgram` - If the account is not owned by the program
+/// * `ErrorCode::AccountDiscriminatorMismatch` - If the account is not a tokens account
+/// * `CustomError::InvalidUnitConfig` - If units must be set and the volume scale is 0 or
+///   the decimals are out of range
+///
+/// # Returns
+/// * `Ok(())` on success, including when the account is already current
+pub fn grow_tokens(ctx: Context<GrowTokens>, units: UnitConfig) -> Result<()> {
+    let size = grow_account::<Tokens>(
+        &ctx.accounts.tokens,
+        &ctx.accounts.agency,
+        &ctx.accounts.system_program,
+    )?;
+
+    let info = ctx.accounts.tokens.to_account_info();
+    let mut tokens = Tokens::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    if !tokens.units.is_valid() {
+        require!(units.is_valid(), CustomError::InvalidUnitConfig);
+        tokens.units = units;
+        tokens.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        msg!("Tokens units set.");
+    }
+
+    msg!("Tokens account is {} bytes.", size);
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use crate::{
+    state::{HydroArchiveShard, Reservoir, ReservoirDailyStats, Role, RoleKind},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+
+/// Record hydro-meteorological day context
+///
+/// # Fields
+/// * `archive_shard` - The PDA account storing the shard of days covering today
+/// * `reservoir` - The PDA account of the reservoir the day is recorded for
+/// * `agency` - The agency the operation is performed for
+/// * `authority` - The agency or a staff key holding the MeterOperator role, paying for a
+///   new shard
+/// * `role` - Role account of the authority, when it is not the agency
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for HydroArchiveShard PDA
+/// * `"hydro_archive"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier of the reservoir
+/// * `shard` - Index of the shard, little-endian
+#[derive(Accounts)]
+#[instruction(reservoir_key: Pubkey, shard: u64)]
+pub struct RecordHydroDay<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [
+            b"hydro_archive",
+            agency.key().as_ref(),
+            reservoir_key.as_ref(),
+            &shard.to_le_bytes()
+        ],
+        bump,
+        payer = authority,
+        space = DISCRIMINATOR + HydroArchiveShard::INIT_SPACE
+    )]
+    pub archive_shard: Account<'info, HydroArchiveShard>,
+    #[account(
+        seeds = [b"reservoir", agency.key().as_ref(), reservoir_key.as_ref()],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Prune hydro-meteorological archive context
+///
+/// # Fields
+/// * `archive_shard` - The PDA account of the shard to close
+/// * `agency` - The agency the reservoir belongs to
+/// * `payer` - The key that paid the shard's rent, receiving it back
+///
+/// # Seeds for HydroArchiveShard PDA
+/// * `"hydro_archive"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier of the reservoir
+/// * `shard` - Index of the shard, little-endian
+#[derive(Accounts)]
+#[instruction(reservoir_key: Pubkey, shard: u64)]
+pub struct PruneHydroArchive<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"hydro_archive",
+            agency.key().as_ref(),
+            reservoir_key.as_ref(),
+            &shard.to_le_bytes()
+        ],
+        bump,
+        has_one = payer @ CustomError::Unauthorized,
+        close = payer
+    )]
+    pub archive_shard: Account<'info, HydroArchiveShard>,
+    /// CHECK: only used to derive the shard PDA
+    pub agency: UncheckedAccount<'info>,
+    /// CHECK: validated against the payer recorded on the shard, only receives rent
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+/// Archive today's level, inflow and outflow of a reservoir
+///
+/// The level is the reservoir's current level, and the inflow and outflow are added to
+/// those already recorded today, so a feed may report them in several parts. The shard
+/// covering today is created on its first record.
+///
+/// # Arguments
+/// * `ctx` - Context containing the shard, reservoir, agency and authority accounts
+/// * `reservoir_key` - Unique public key identifier of the reservoir
+/// * `shard` - Index of the shard covering today
+/// * `inflow` - Volume that flowed into the reservoir
+/// * `outflow` - Volume released from the reservoir
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::InvalidArchiveShard` - If the shard does not cover today
+///
+/// # Returns
+/// * `Ok(())` on successful recording
+pub fn record_hydro_day(
+    ctx: Context<RecordHydroDay>,
+    reservoir_key: Pubkey,
+    shard: u64,
+    inflow: u64,
+    outflow: u64,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::MeterOperator,
+    )?;
+
+    let day = ReservoirDailyStats::day_of(Clock::get()?.unix_timestamp);
+    require!(
+        HydroArchiveShard::shard_of(day) == shard,
+        CustomError::InvalidArchiveShard
+    );
+
+    let archive_shard = &mut ctx.accounts.archive_shard;
+    if archive_shard.payer == Pubkey::default() {
+        archive_shard.reservoir_key = reservoir_key;
+        archive_shard.shard = shard;
+        archive_shard.payer = ctx.accounts.authority.key();
+    }
+    archive_shard.record(day, ctx.accounts.reservoir.current_level, inflow, outflow);
+
+    msg!("Hydro-meteorological day {} archived.", day);
+    Ok(())
+}
+
+/// Close an archive shard past the retention window and refund its rent
+///
+/// Anyone may prune a shard once all its days are older than
+/// `HYDRO_ARCHIVE_RETENTION_DAYS`; the rent always returns to the key that paid it.
+///
+/// # Arguments
+/// * `ctx` - Context containing the shard, agency and payer accounts
+/// * `reservoir_key` - Unique public key identifier of the reservoir
+/// * `shard` - Index of the shard to close
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the payer is not the key that paid the shard's rent
+/// * `CustomError::ArchiveShardRetained` - If the shard holds days within the retention
+///   window
+///
+/// # Returns
+/// * `Ok(())` on successful pruning
+pub fn prune_hydro_archive(
+    ctx: Context<PruneHydroArchive>,
+    _reservoir_key: Pubkey,
+    shard: u64,
+) -> Result<()> {
+    let today = ReservoirDailyStats::day_of(Clock::get()?.unix_timestamp);
+    require!(
+        ctx.accounts.archive_shard.is_prunable(today),
+        CustomError::ArchiveShardRetained
+    );
+
+    msg!("Hydro-meteorological archive shard {} pruned.", shard);
+    Ok(())
+}
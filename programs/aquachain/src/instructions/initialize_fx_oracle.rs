@@ -0,0 +1,89 @@
+use crate::{
+    state::{is_valid_currency_code, FxOracle},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+/// Initialize **FxOracle** account context
+///
+/// The **FxOracle** account to be initialized requires a PDA with seeds composed
+/// of the agency's public key, and the ISO 4217 code of the local currency.
+///
+/// # Fields
+/// * `fx_oracle` - The PDA account that will store the exchange rate
+/// * `settlement_mint` - The stablecoin mint debts are settled in
+/// * `agency` - The owner that is authorized to sign operations on its behalf
+/// * `system_program` - Required for account creation
+///
+/// # Seeds
+/// * `"fx_oracle"` - Constant string
+/// * `agency` - Agency's public key
+/// * `currency_code` - ISO 4217 code of the local currency
+#[derive(Accounts)]
+#[instruction(currency_code: [u8; 3])]
+pub struct InitializeFxOracle<'info> {
+    #[account(
+        init,
+        seeds = [
+            b"fx_oracle",
+            agency.key().as_ref(),
+            currency_code.as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + FxOracle::INIT_SPACE
+    )]
+    pub fx_oracle: Account<'info, FxOracle>,
+    pub settlement_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Register an exchange rate feed for a local currency
+///
+/// This function initializes a new FxOracle account pairing a local currency with the
+/// stablecoin used for settlement. The rate starts unset and must be posted by the
+/// oracle authority before any debt can be settled.
+///
+/// # Arguments
+/// * `ctx` - Context containing the oracle account, settlement mint, agency signer and system program
+/// * `currency_code` - ISO 4217 code of the local currency (e.g. `TTD`)
+/// * `authority` - Public key allowed to post rates
+/// * `max_staleness_slots` - Maximum age in slots of a rate usable for settlement (must be > 0)
+///
+/// # Errors
+/// * `CustomError::InvalidCurrencyCode` - If currency_code is not three uppercase letters
+/// * `CustomError::InvalidAmount` - If max_staleness_slots is 0
+///
+/// # Returns
+/// * `Ok(())` on successful initialization
+pub fn initialize_fx_oracle(
+    ctx: Context<InitializeFxOracle>,
+    currency_code: [u8; 3],
+    authority: Pubkey,
+    max_staleness_slots: u64,
+) -> Result<()> {
+    let fx_oracle = &mut ctx.accounts.fx_oracle;
+
+    require!(
+        is_valid_currency_code(&currency_code),
+        CustomError::InvalidCurrencyCode
+    );
+    require!(max_staleness_slots > 0, CustomError::InvalidAmount);
+
+    fx_oracle.currency_code = currency_code;
+    fx_oracle.settlement_mint = ctx.accounts.settlement_mint.key();
+    fx_oracle.authority = authority;
+    fx_oracle.max_staleness_slots = max_staleness_slots;
+    fx_oracle.rate = 0;
+    fx_oracle.last_updated_slot = 0;
+
+    msg!(
+        "FX oracle initialized for currency {} settling in {}.",
+        String::from_utf8_lossy(&currency_code),
+        fx_oracle.settlement_mint
+    );
+    Ok(())
+}
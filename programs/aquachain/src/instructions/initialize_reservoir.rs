@@ -67,6 +67,11 @@ pub fn initialize_reservoir(
     reservoir.reservoir_key = reservoir_key;
     reservoir.current_level = current_level;
     reservoir.capacity = capacity;
+    reservoir.smoothing_bps = 0;
+    reservoir.smoothed_level = current_level;
+    let clock = Clock::get()?;
+    reservoir.level_updated_slot = clock.slot;
+    reservoir.level_updated_at = clock.unix_timestamp;
 
     msg!("Reservoir initialized for reservoir {} with rates.", reservoir_key);
     Ok(())
@@ -69,6 +69,7 @@ pub fn initialize_tariff(
     tariff.water_rate = water_rate;
     tariff.waste_rate = waste_rate;
     tariff.tariff_type = tariff_type;
+    tariff.activated_slot = Clock::get()?.slot;
 
 
     msg!("Tariff initialized for tariff {} with rates.", tariff_key);
@@ -1,4 +1,7 @@
-use crate::{state::Tokens, DISCRIMINATOR};
+use crate::{
+    state::{AgencyStats, Tokens, UnitConfig},
+    CustomError, DISCRIMINATOR,
+};
 use anchor_lang::prelude::*;
 
 /// Initialize **Tokens** account context
@@ -7,12 +10,17 @@ use anchor_lang::prelude::*;
 /// 
 /// # Fields
 /// * `tokens` - The PDA account that will store token addresses
+/// * `agency_stats` - The PDA account that will hold the agency's aggregate statistics
 /// * `authority` - The owner that is authorized to sign operations on its behalf 
 /// * `system_program` - Required for account creation
 ///
 /// # Seeds
 /// * `"tokens"` - Constant string
 /// * `authority` - Authority's public key 
+///
+/// # Seeds for AgencyStats PDA
+/// * `"agency_stats"` - Constant string
+/// * `authority` - Authority's public key
 #[derive(Accounts)]
 pub struct InitializeTokens<'info> {
     #[account(
@@ -23,6 +31,14 @@ pub struct InitializeTokens<'info> {
         bump
     )]
     pub tokens: Account<'info, Tokens>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = DISCRIMINATOR + AgencyStats::INIT_SPACE,
+        seeds = [b"agency_stats", authority.key().as_ref()],
+        bump
+    )]
+    pub agency_stats: Account<'info, AgencyStats>,
     #[account(mut)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -30,7 +46,9 @@ pub struct InitializeTokens<'info> {
 
 /// Initialize tokens with provided token addresses
 /// 
-/// This function initializes a new Tokens account with the provided token addresses.
+/// This function initializes a new Tokens account with the provided token addresses
+/// and the unit configuration used to convert usage into charges, along with the
+/// agency's AgencyStats account.
 /// The account is created as a PDA (Program Derived Address) using the authority's public key
 /// as a seed.
 ///
@@ -39,6 +57,10 @@ pub struct InitializeTokens<'info> {
 /// * `water_token` - Public key of the water token mint
 /// * `water_capacity_token` - Public key of the water capacity token mint
 /// * `waste_token` - Public key of the waste token mint
+/// * `units` - Volume scale and currency decimals used by all conversions
+///
+/// # Errors
+/// * `CustomError::InvalidUnitConfig` - If the volume scale is 0 or the decimals are out of range
 ///
 /// # Returns
 /// * `Ok(())` on successful initialization
@@ -47,7 +69,10 @@ pub fn initialize_tokens(
     water_token: Pubkey,
     water_capacity_token: Pubkey,
     waste_token: Pubkey,
+    units: UnitConfig,
 ) -> Result<()> {
+    require!(units.is_valid(), CustomError::InvalidUnitConfig);
+
     if ctx.accounts.tokens.wtk != Pubkey::default() {
         msg!("Tokens already initialized");
     } else {
@@ -55,6 +80,7 @@ pub fn initialize_tokens(
         tokens.wtk = water_token;
         tokens.watc = water_capacity_token;
         tokens.wst = waste_token;
+        tokens.units = units;
 
         msg!(
             "Token mints initialized with WaterToken: {}, WaterCapacityToken: {}, WasteToken: {}",
@@ -62,6 +88,11 @@ pub fn initialize_tokens(
             water_capacity_token,
             waste_token
         );
+        msg!(
+            "Billing units set to volume scale {} and {} currency decimals",
+            units.volume_scale,
+            units.currency_decimals
+        );
     }
 
     Ok(())
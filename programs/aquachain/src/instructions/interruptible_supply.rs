@@ -0,0 +1,332 @@
+use crate::{
+    events::{CurtailmentCompensated, InterruptibleSupplyCurtailed},
+    state::{Consumer, InterruptiblePolicy, Tariff},
+    utils::is_valid_bps,
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Configure interruptible supply instruction context
+///
+/// The **ConfigureInterruptibleSupply** context is used by the agency to set the discount
+/// and compensation it offers consumers on interruptible supply contracts.
+///
+/// # Fields
+/// * `interruptible_policy` - The PDA account storing the agency's interruptible supply terms
+/// * `aqc_mint` - The AquaCoin mint compensation is paid in
+/// * `agency` - The agency offering the contracts
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for InterruptiblePolicy PDA
+/// * `"interruptible_policy"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct ConfigureInterruptibleSupply<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"interruptible_policy", agency.key().as_ref()],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + InterruptiblePolicy::INIT_SPACE
+    )]
+    pub interruptible_policy: Account<'info, InterruptiblePolicy>,
+    #[account(mint::authority = agency)]
+    pub aqc_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Set interruptible instruction context
+///
+/// The **SetInterruptible** context is used by a consumer to opt into or out of an
+/// interruptible supply contract. The agency co-signs the change of contract.
+///
+/// # Fields
+/// * `consumer` - The consumer account changing its contract (must be signer)
+/// * `tariff` - The PDA tariff account assigned to the consumer, proving the agency supplies it
+/// * `interruptible_policy` - The PDA account storing the agency's interruptible supply terms
+/// * `agency` - The agency supplying the consumer
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Tariff assigned to the consumer
+///
+/// # Seeds for InterruptiblePolicy PDA
+/// * `"interruptible_policy"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct SetInterruptible<'info> {
+    #[account(mut, signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            consumer.assigned_tariff.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(seeds = [b"interruptible_policy", agency.key().as_ref()], bump)]
+    pub interruptible_policy: Account<'info, InterruptiblePolicy>,
+    pub agency: Signer<'info>,
+}
+
+/// Curtail interruptible supply instruction context
+///
+/// The **CurtailInterruptibleSupply** context is used by the agency to cut off the supply
+/// of its interruptible consumers in an emergency.
+///
+/// # Fields
+/// * `interruptible_policy` - The PDA account storing the agency's interruptible supply terms
+/// * `agency` - The agency curtailing supply
+///
+/// # Seeds for InterruptiblePolicy PDA
+/// * `"interruptible_policy"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct CurtailInterruptibleSupply<'info> {
+    #[account(mut, seeds = [b"interruptible_policy", agency.key().as_ref()], bump)]
+    pub interruptible_policy: Account<'info, InterruptiblePolicy>,
+    pub agency: Signer<'info>,
+}
+
+/// Compensate curtailment instruction context
+///
+/// The **CompensateCurtailment** context is used by the agency, or a crank signing with
+/// its key, to pay an interruptible consumer for the periods its supply was curtailed.
+///
+/// # Fields
+/// * `consumer` - The interruptible consumer being compensated
+/// * `tariff` - The PDA tariff account assigned to the consumer, proving the agency supplies it
+/// * `interruptible_policy` - The PDA account storing the agency's interruptible supply terms
+/// * `consumer_aqc` - The consumer's AquaCoin token account, created if missing
+/// * `aqc_mint` - The AquaCoin mint
+/// * `agency` - The authority that can mint AquaCoin
+/// * `system_program` - Required to create the consumer's token account
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Tariff assigned to the consumer
+///
+/// # Seeds for InterruptiblePolicy PDA
+/// * `"interruptible_policy"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct CompensateCurtailment<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            consumer.assigned_tariff.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(
+        seeds = [b"interruptible_policy", agency.key().as_ref()],
+        bump,
+        has_one = aqc_mint
+    )]
+    pub interruptible_policy: Account<'info, InterruptiblePolicy>,
+    #[account(
+        init_if_needed,
+        payer = agency,
+        associated_token::mint = aqc_mint,
+        associated_token::authority = consumer
+    )]
+    pub consumer_aqc: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency)]
+    pub aqc_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Set the discount and compensation the agency offers on interruptible supply contracts
+///
+/// Changes apply to every reading billed and every curtailment compensated afterwards,
+/// including curtailments already declared.
+///
+/// # Arguments
+/// * `ctx` - Context containing the policy, AquaCoin mint and agency accounts
+/// * `discount_bps` - Discount on interruptible consumers' charges, in basis points
+/// * `compensation_per_period` - AquaCoin paid for each curtailed period, in base units
+/// * `period_slots` - Length of a curtailment period in slots
+///
+/// # Errors
+/// * `CustomError::InvalidInterruptiblePolicy` - If the discount exceeds 10,000 bps or the
+///   period is zero
+///
+/// # Returns
+/// * `Ok(())` on successful configuration
+pub fn configure_interruptible_supply(
+    ctx: Context<ConfigureInterruptibleSupply>,
+    discount_bps: u16,
+    compensation_per_period: u64,
+    period_slots: u64,
+) -> Result<()> {
+    require!(
+        is_valid_bps(discount_bps) && period_slots > 0,
+        CustomError::InvalidInterruptiblePolicy
+    );
+
+    let interruptible_policy = &mut ctx.accounts.interruptible_policy;
+    interruptible_policy.agency = ctx.accounts.agency.key();
+    interruptible_policy.aqc_mint = ctx.accounts.aqc_mint.key();
+    interruptible_policy.discount_bps = discount_bps;
+    interruptible_policy.compensation_per_period = compensation_per_period;
+    interruptible_policy.period_slots = period_slots;
+
+    msg!(
+        "Interruptible supply offered at a {} bps discount, {} AQC per curtailed period.",
+        discount_bps,
+        compensation_per_period
+    );
+    Ok(())
+}
+
+/// Opt a consumer into or out of an interruptible supply contract
+///
+/// Consumers opting in are only compensated for curtailments declared afterwards. A
+/// consumer still owed compensation must be compensated before opting out.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, policy and agency accounts
+/// * `interruptible` - Whether the consumer's supply is interruptible
+///
+/// # Errors
+/// * `CustomError::CurtailmentUncompensated` - If the consumer opts out while owed compensation
+///
+/// # Returns
+/// * `Ok(())` on successful change of contract
+pub fn set_interruptible(ctx: Context<SetInterruptible>, interruptible: bool) -> Result<()> {
+    let policy = &ctx.accounts.interruptible_policy;
+    let consumer = &mut ctx.accounts.consumer;
+
+    if consumer.interruptible && !interruptible {
+        require!(
+            policy.uncompensated_periods(consumer.curtailed_periods_compensated) == 0,
+            CustomError::CurtailmentUncompensated
+        );
+    } else if !consumer.interruptible && interruptible {
+        consumer.curtailed_periods_compensated = policy.curtailed_periods;
+    }
+    consumer.interruptible = interruptible;
+
+    msg!("Consumer supply interruptible: {}.", interruptible);
+    Ok(())
+}
+
+/// Curtail the supply of the agency's interruptible consumers in an emergency
+///
+/// Interruptible consumers cannot draw water for `periods` periods of the policy's
+/// `period_slots`, and each of them is owed the policy's compensation for every period.
+///
+/// # Arguments
+/// * `ctx` - Context containing the policy and agency accounts
+/// * `periods` - Number of periods supply is curtailed for
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If periods is zero
+/// * `CustomError::SupplyCurtailed` - If a curtailment is still in progress
+///
+/// # Returns
+/// * `Ok(())` on successful curtailment
+pub fn curtail_interruptible_supply(
+    ctx: Context<CurtailInterruptibleSupply>,
+    periods: u64,
+) -> Result<()> {
+    require!(periods > 0, CustomError::InvalidAmount);
+
+    let slot = Clock::get()?.slot;
+    let interruptible_policy = &mut ctx.accounts.interruptible_policy;
+    require!(
+        !interruptible_policy.is_curtailed(slot),
+        CustomError::SupplyCurtailed
+    );
+    interruptible_policy.curtail(periods, slot);
+
+    emit!(InterruptibleSupplyCurtailed {
+        agency: ctx.accounts.agency.key(),
+        periods,
+        curtailed_until_slot: interruptible_policy.curtailed_until_slot,
+        slot,
+    });
+
+    msg!(
+        "Interruptible supply curtailed until slot {}.",
+        interruptible_policy.curtailed_until_slot
+    );
+    Ok(())
+}
+
+/// Compensate an interruptible consumer for the periods its supply was curtailed
+///
+/// Mints the policy's compensation in AquaCoin for every curtailed period declared since
+/// the consumer was last compensated. Consumers owed nothing are left untouched, so the
+/// crank can compensate every interruptible consumer after each curtailment.
+///
+/// # Arguments
+/// * `ctx` - Context containing the policy, consumer, agency and token accounts
+///
+/// # Errors
+/// * `CustomError::InvalidInterruptiblePolicy` - If the consumer's supply is not interruptible
+///
+/// # Returns
+/// * `Ok(())` on successful compensation
+pub fn compensate_curtailment(ctx: Context<CompensateCurtailment>) -> Result<()> {
+    require!(
+        ctx.accounts.consumer.interruptible,
+        CustomError::InvalidInterruptiblePolicy
+    );
+
+    let policy = &ctx.accounts.interruptible_policy;
+    let periods_compensated = ctx.accounts.consumer.curtailed_periods_compensated;
+    let periods = policy.uncompensated_periods(periods_compensated);
+    let compensation = policy.compensation_owed(periods_compensated);
+
+    if compensation > 0 {
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    to: ctx.accounts.consumer_aqc.to_account_info(),
+                    authority: ctx.accounts.agency.to_account_info(),
+                    mint: ctx.accounts.aqc_mint.to_account_info(),
+                },
+            ),
+            compensation,
+        )?;
+    }
+    ctx.accounts.consumer.curtailed_periods_compensated = policy.curtailed_periods;
+
+    if periods > 0 {
+        emit!(CurtailmentCompensated {
+            consumer: ctx.accounts.consumer.key(),
+            periods,
+            compensation,
+            slot: Clock::get()?.slot,
+        });
+    }
+
+    msg!(
+        "Consumer compensated {} AQC for {} curtailed periods.",
+        compensation,
+        periods
+    );
+    Ok(())
+}
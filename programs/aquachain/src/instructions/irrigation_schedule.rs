@@ -0,0 +1,89 @@
+use crate::{
+    state::{Consumer, CropSeason, IrrigationSchedule, Role, RoleKind},
+    DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+
+/// Set irrigation schedule instruction context
+///
+/// The **SetIrrigationSchedule** context is used by the agency, or a staff key holding the
+/// Billing role, to allot an agricultural consumer its weekly irrigation volumes.
+///
+/// # Fields
+/// * `irrigation_schedule` - The PDA account storing the consumer's irrigation schedule
+/// * `consumer` - The agricultural consumer the schedule applies to
+/// * `agency` - The agency operating the irrigation district
+/// * `authority` - The agency or a staff key holding the Billing role
+/// * `role` - Role account of the authority, when it is not the agency
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for IrrigationSchedule PDA
+/// * `"irrigation_schedule"` - Constant string
+/// * `agency` - Agency's public key
+/// * `consumer` - Consumer's public key
+#[derive(Accounts)]
+pub struct SetIrrigationSchedule<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [
+            b"irrigation_schedule",
+            agency.key().as_ref(),
+            consumer.key().as_ref()
+        ],
+        bump,
+        payer = authority,
+        space = DISCRIMINATOR + IrrigationSchedule::INIT_SPACE
+    )]
+    pub irrigation_schedule: Account<'info, IrrigationSchedule>,
+    pub consumer: Account<'info, Consumer>,
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Set the crop seasons and out-of-turn surcharge of an agricultural consumer
+///
+/// The seasons replace any previously scheduled ones. Volume already drawn in the current
+/// week keeps counting against the week's allotment.
+///
+/// # Arguments
+/// * `ctx` - Context containing the schedule, consumer, agency and role accounts
+/// * `surcharge_bps` - Surcharge on the water rate for volume drawn out of turn
+/// * `seasons` - Crop seasons and their weekly volumes
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::InvalidIrrigationSchedule` - If there are more than four seasons, or a
+///   season is empty or overlaps another
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn set_irrigation_schedule(
+    ctx: Context<SetIrrigationSchedule>,
+    surcharge_bps: u16,
+    seasons: Vec<CropSeason>,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+    IrrigationSchedule::validate_seasons(&seasons)?;
+
+    let schedule = &mut ctx.accounts.irrigation_schedule;
+    schedule.agency = ctx.accounts.agency.key();
+    schedule.consumer = ctx.accounts.consumer.key();
+    schedule.surcharge_bps = surcharge_bps;
+    schedule.seasons = seasons;
+
+    msg!(
+        "Irrigation schedule set with {} seasons and a {} basis point surcharge.",
+        schedule.seasons.len(),
+        surcharge_bps
+    );
+    Ok(())
+}
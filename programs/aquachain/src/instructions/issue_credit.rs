@@ -0,0 +1,122 @@
+use crate::{
+    state::{Consumer, CreditNote, CreditReason, DebtKind},
+    utils::burn_signed_by_consumer,
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+/// Issue credit instruction context
+///
+/// The **IssueCredit** context is used by the agency to correct an overcharge by writing off
+/// part of a consumer's water or waste debt. The consumer co-signs so its tokens can be burned.
+///
+/// # Fields
+/// * `consumer` - The consumer account receiving the credit (must be signer)
+/// * `credit_note` - The PDA account recording the correction
+/// * `agency` - The authority issuing the credit
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `consumer_wst` - The consumer's WST token account
+/// * `wtk_mint` - The WTK token mint
+/// * `wst_mint` - The WST token mint
+/// * `system_program` - Required for account creation
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for CreditNote PDA
+/// * `"credit_note"` - Constant string
+/// * `agency` - Agency's public key
+/// * `note_key` - Unique identifier for the credit note
+#[derive(Accounts)]
+#[instruction(note_key: Pubkey)]
+pub struct IssueCredit<'info> {
+    #[account(mut, signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        init,
+        seeds = [b"credit_note", agency.key().as_ref(), &note_key.as_ref()],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + CreditNote::INIT_SPACE
+    )]
+    pub credit_note: Account<'info, CreditNote>,
+    #[account(mut)]
+    pub agency: Signer<'info>, // agency's authorized wallet
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = wst_mint, associated_token::authority = consumer)]
+    pub consumer_wst: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency)]
+    pub wtk_mint: Account<'info, Mint>,
+    #[account(mut, mint::authority = agency)]
+    pub wst_mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Issue a credit note correcting a consumer's bill
+///
+/// This function burns `amount` of the consumer's WTK or WST debt and records the
+/// correction, with its reason code, on a new CreditNote account. Water credits also
+/// reduce the consumer's outstanding water debt.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, credit note, agency and token accounts
+/// * `note_key` - Public key identifying the credit note
+/// * `kind` - Which debt the credit applies to
+/// * `amount` - Amount of debt to write off
+/// * `reason` - Reason code for the correction
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If amount is zero
+/// * `CustomError::OverPayment` - If amount exceeds the consumer's outstanding debt
+///
+/// # Returns
+/// * `Ok(())` on successful issuance
+pub fn issue_credit(
+    ctx: Context<IssueCredit>,
+    note_key: Pubkey,
+    kind: DebtKind,
+    amount: u64,
+    reason: CreditReason,
+) -> Result<()> {
+    require!(amount > 0, CustomError::InvalidAmount);
+
+    let (from, mint) = match kind {
+        DebtKind::Water => {
+            ctx.accounts.consumer.pay_water(amount)?;
+            (&ctx.accounts.consumer_wtk, &ctx.accounts.wtk_mint)
+        }
+        DebtKind::Waste => (&ctx.accounts.consumer_wst, &ctx.accounts.wst_mint),
+    };
+    require!(from.amount >= amount, CustomError::OverPayment);
+
+    // Write off the credited debt
+    burn_signed_by_consumer(
+        &ctx.accounts.token_program,
+        mint.to_account_info(),
+        from.to_account_info(),
+        ctx.accounts.consumer.to_account_info(),
+        amount,
+    )?;
+
+    let credit_note = &mut ctx.accounts.credit_note;
+    credit_note.consumer = ctx.accounts.consumer.key();
+    credit_note.kind = kind;
+    credit_note.amount = amount;
+    credit_note.reason = reason;
+    credit_note.issued_slot = Clock::get()?.slot;
+    credit_note.note_key = note_key;
+
+    msg!(
+        "Credited {} of {:?} debt to consumer for {:?}.",
+        amount,
+        kind,
+        reason
+    );
+    Ok(())
+}
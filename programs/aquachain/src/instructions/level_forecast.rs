@@ -0,0 +1,114 @@
+use crate::{
+    events::LevelForecastPosted,
+    state::{LevelForecast, Reservoir, Role, RoleKind},
+    DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+
+/// Post level forecast instruction context
+///
+/// The **PostLevelForecast** context is used by the agency, or an oracle key holding the
+/// MeterOperator role, to post the projected levels of a reservoir.
+///
+/// # Fields
+/// * `level_forecast` - The PDA account storing the reservoir's projected levels
+/// * `reservoir` - The PDA account of the reservoir the forecast projects
+/// * `agency` - The agency operating the reservoir
+/// * `authority` - The agency or a key holding the MeterOperator role
+/// * `role` - Role account of the authority, when it is not the agency
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for LevelForecast PDA
+/// * `"level_forecast"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+///
+/// # Seeds for Reservoir PDA
+/// * `"reservoir"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+#[derive(Accounts)]
+#[instruction(reservoir_key: Pubkey)]
+pub struct PostLevelForecast<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [
+            b"level_forecast",
+            agency.key().as_ref(),
+            reservoir_key.as_ref()
+        ],
+        bump,
+        payer = authority,
+        space = DISCRIMINATOR + LevelForecast::INIT_SPACE
+    )]
+    pub level_forecast: Account<'info, LevelForecast>,
+    #[account(
+        seeds = [
+            b"reservoir",
+            agency.key().as_ref(),
+            reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Post the projected levels of a reservoir over the coming periods
+///
+/// The forecast replaces any previously posted one, and its first period starts at the
+/// current slot. A `LevelForecastPosted` event is emitted so consumers can anticipate
+/// price changes on tariffs pricing on forecasts.
+///
+/// # Arguments
+/// * `ctx` - Context containing the forecast, reservoir, agency and role accounts
+/// * `reservoir_key` - Unique public key identifier for the reservoir
+/// * `period_slots` - Length of each projected period in slots (must be > 0)
+/// * `levels` - Projected reservoir level for each period, at most twelve
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::InvalidLevelForecast` - If there are no periods, too many periods, or a
+///   level exceeds the reservoir's capacity
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn post_level_forecast(
+    ctx: Context<PostLevelForecast>,
+    reservoir_key: Pubkey,
+    period_slots: u64,
+    levels: Vec<u64>,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::MeterOperator,
+    )?;
+    LevelForecast::validate_levels(period_slots, &levels, ctx.accounts.reservoir.capacity)?;
+
+    let level_forecast = &mut ctx.accounts.level_forecast;
+    level_forecast.agency = ctx.accounts.agency.key();
+    level_forecast.reservoir_key = reservoir_key;
+    level_forecast.start_slot = Clock::get()?.slot;
+    level_forecast.period_slots = period_slots;
+    level_forecast.levels = levels;
+
+    emit!(LevelForecastPosted {
+        reservoir_key,
+        start_slot: level_forecast.start_slot,
+        period_slots,
+        levels: level_forecast.levels.clone(),
+    });
+
+    msg!(
+        "Level forecast posted for {} periods.",
+        level_forecast.levels.len()
+    );
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use crate::{
+    state::Consumer,
+    utils::{clear_stale_bytes, is_valid_bps},
+    CustomError,
+};
+use anchor_lang::prelude::*;
+
+/// Link or unlink a sub-consumer context
+///
+/// Used to attach a sub-consumer (e.g. a tenant) to a master consumer (e.g. a landlord's
+/// bulk meter), or to detach it again. Both consumers and the agency must sign.
+///
+/// # Fields
+/// * `sub_consumer` - The consumer account rolling up to the master (must be signer)
+/// * `master_consumer` - The master consumer account (must be signer)
+/// * `agency` - The authority that manages both consumers
+#[derive(Accounts)]
+pub struct LinkSubConsumer<'info> {
+    #[account(
+        mut,
+        signer,
+        constraint = sub_consumer.key() != master_consumer.key() @ CustomError::InvalidConsumerHierarchy
+    )]
+    pub sub_consumer: Account<'info, Consumer>,
+    #[account(mut, signer)]
+    pub master_consumer: Account<'info, Consumer>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+}
+
+/// Attach a sub-consumer to a master consumer
+///
+/// Charges reported for the sub-consumer through `use_water_split` are split between the
+/// sub-consumer and the master according to `tenant_share_bps`, so a building can be billed
+/// both individually and collectively. Hierarchies are a single level deep.
+///
+/// # Arguments
+/// * `ctx` - Context containing the sub-consumer, master consumer and agency signer
+/// * `tenant_share_bps` - Share of the charges billed to the sub-consumer, in basis points
+///
+/// # Errors
+/// * `CustomError::InvalidShare` - If tenant_share_bps exceeds 10,000
+/// * `CustomError::InvalidConsumerHierarchy` - If the link would create a nested or duplicate hierarchy
+///
+/// # Returns
+/// * `Ok(())` on successful link
+pub fn link_sub_consumer(ctx: Context<LinkSubConsumer>, tenant_share_bps: u16) -> Result<()> {
+    let sub_consumer = &mut ctx.accounts.sub_consumer;
+    let master_consumer = &mut ctx.accounts.master_consumer;
+
+    require!(is_valid_bps(tenant_share_bps), CustomError::InvalidShare);
+    require!(
+        sub_consumer.master_consumer.is_none()
+            && sub_consumer.sub_consumer_count == 0
+            && master_consumer.master_consumer.is_none(),
+        CustomError::InvalidConsumerHierarchy
+    );
+
+    sub_consumer.master_consumer = Some(master_consumer.key());
+    sub_consumer.tenant_share_bps = tenant_share_bps;
+    master_consumer.sub_consumer_count = master_consumer
+        .sub_consumer_count
+        .checked_add(1)
+        .ok_or(CustomError::MathOverflow)?;
+
+    msg!(
+        "Sub-consumer linked to master {} with a tenant share of {} bps.",
+        master_consumer.key(),
+        tenant_share_bps
+    );
+    Ok(())
+}
+
+/// Detach a sub-consumer from its master consumer
+///
+/// After unlinking, the sub-consumer is billed in full through `use_water`.
+///
+/// # Arguments
+/// * `ctx` - Context containing the sub-consumer, master consumer and agency signer
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the sub-consumer is not linked to this master
+///
+/// # Returns
+/// * `Ok(())` on successful unlink
+pub fn unlink_sub_consumer(ctx: Context<LinkSubConsumer>) -> Result<()> {
+    let sub_consumer = &mut ctx.accounts.sub_consumer;
+    let master_consumer = &mut ctx.accounts.master_consumer;
+
+    require!(
+        sub_consumer.master_consumer == Some(master_consumer.key()),
+        CustomError::Unauthorized
+    );
+
+    sub_consumer.master_consumer = None;
+    sub_consumer.tenant_share_bps = 0;
+    clear_stale_bytes(sub_consumer)?;
+    master_consumer.sub_consumer_count = master_consumer
+        .sub_consumer_count
+        .checked_sub(1)
+        .ok_or(CustomError::MathOverflow)?;
+
+    msg!(
+        "Sub-consumer unlinked from master {}.",
+        master_consumer.key()
+    );
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use crate::{
+    events::ConsumerCapacityMandated,
+    state::{Consumer, RegulatorAuthority, Reservoir, Role},
+    CustomError,
+};
+use anchor_lang::prelude::*;
+
+/// Mandate consumer capacity instruction context
+///
+/// The **MandateConsumerCapacity** context is used by the agency to enforce a regulated
+/// capacity reduction on a consumer without its signature. The reduction is only allowed
+/// while the consumer's reservoir is low, or when co-signed by the agency's regulator.
+///
+/// # Fields
+/// * `consumer` - The consumer account whose capacity is reduced
+/// * `reservoir` - The PDA account of the consumer's assigned reservoir
+/// * `agency` - The agency serving the consumer (must be signer)
+/// * `regulator_authority` - The PDA account recording the agency's regulator
+/// * `regulator` - The regulator approving the reduction outside an emergency (must be signer)
+/// * `regulator_role` - Role account of the regulator, when it is not the regulator authority
+///
+/// # Seeds for Reservoir PDA
+/// * `"reservoir"` - Constant string
+/// * `agency` - Agency's public key
+/// * `assigned_reservoir` - The consumer's assigned reservoir
+///
+/// # Seeds for RegulatorAuthority PDA
+/// * `"regulator_authority"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct MandateConsumerCapacity<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"reservoir",
+            agency.key().as_ref(),
+            consumer.assigned_reservoir.as_ref()
+        ],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    pub agency: Signer<'info>,
+    #[account(seeds = [b"regulator_authority", agency.key().as_ref()], bump)]
+    pub regulator_authority: Option<Account<'info, RegulatorAuthority>>,
+    pub regulator: Option<Signer<'info>>,
+    pub regulator_role: Option<Account<'info, Role>>,
+}
+
+/// Reduce a consumer's contracted capacity without its consent
+///
+/// This is the agency-mandated path of `update_consumer`, for regulated reductions during
+/// drought. The agency may mandate a reduction on its own while the consumer's reservoir
+/// is low, which counts as an emergency; otherwise the agency's regulator, or a key it
+/// granted the Regulator role, must co-sign it. Only the contracted capacity changes. Since WATC
+/// cannot be burned without the consumer's signature, its balance is left as it is and
+/// the reduction takes effect at the next capacity refresh. A `ConsumerCapacityMandated`
+/// event is emitted.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, reservoir, agency and regulator accounts
+/// * `contracted_capacity` - New contracted capacity, below the current one
+///
+/// # Errors
+/// * `CustomError::InvalidCapacity` - If contracted_capacity is 0 or not a reduction
+/// * `CustomError::MissingRegulatorApproval` - If the reservoir is not low and the reduction
+///   is not co-signed by the agency's regulator
+/// * `CustomError::MissingRole` - If the regulator is neither the regulator authority nor
+///   holds the Regulator role
+///
+/// # Returns
+/// * `Ok(())` on successful reduction
+pub fn mandate_consumer_capacity(
+    ctx: Context<MandateConsumerCapacity>,
+    contracted_capacity: u64,
+) -> Result<()> {
+    let consumer = &mut ctx.accounts.consumer;
+    let previous_capacity = consumer.contracted_capacity;
+
+    require!(
+        contracted_capacity > 0 && contracted_capacity < previous_capacity,
+        CustomError::InvalidCapacity
+    );
+
+    let emergency = ctx.accounts.reservoir.is_low();
+    let regulator = match ctx.accounts.regulator.as_ref() {
+        Some(regulator) => {
+            ctx.accounts
+                .regulator_authority
+                .as_ref()
+                .ok_or(CustomError::MissingRegulatorApproval)?
+                .authorize(&regulator.key(), ctx.accounts.regulator_role.as_deref())?;
+            Some(regulator.key())
+        }
+        None => None,
+    };
+    require!(
+        emergency || regulator.is_some(),
+        CustomError::MissingRegulatorApproval
+    );
+
+    consumer.contracted_capacity = contracted_capacity;
+    consumer.reset_capacity_approval();
+
+    emit!(ConsumerCapacityMandated {
+        consumer: consumer.key(),
+        previous_capacity,
+        contracted_capacity,
+        emergency,
+        regulator,
+        slot: Clock::get()?.slot,
+    });
+
+    msg!(
+        "Consumer capacity reduced from {} to {} by mandate.",
+        previous_capacity,
+        contracted_capacity
+    );
+    Ok(())
+}
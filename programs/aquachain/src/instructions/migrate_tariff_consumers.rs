@@ -0,0 +1,111 @@
+use crate::{Consumer, CustomError, Role, RoleKind, Tariff};
+use anchor_lang::prelude::*;
+
+/// Migrate **Consumer** accounts between tariffs context
+///
+/// Used by the agency to reassign a batch of consumers from one tariff to another in a
+/// single transaction. The consumer accounts are passed as writable remaining accounts.
+///
+/// # Fields
+/// * `current_tariff` - The PDA account of the tariff the consumers are leaving
+/// * `new_tariff` - The PDA account of the tariff the consumers are moved to
+/// * `agency` - The agency the operation is performed for
+/// * `authority` - The agency or a staff key holding the Billing role
+/// * `role` - Role account of the authority, when it is not the agency
+///
+/// # Seeds for tariff PDAs
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+#[derive(Accounts)]
+#[instruction(current_tariff_key: Pubkey, new_tariff_key: Pubkey)]
+pub struct MigrateTariffConsumers<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            &current_tariff_key.as_ref()
+        ],
+        bump
+    )]
+    pub current_tariff: Account<'info, Tariff>, // Tariff the consumers are currently assigned to
+    #[account(
+        mut,
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            &new_tariff_key.as_ref()
+        ],
+        bump
+    )]
+    pub new_tariff: Account<'info, Tariff>, // Tariff to assign to the consumers
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
+}
+
+/// Reassign every consumer in `remaining_accounts` from one tariff to another
+///
+/// Each remaining account must be a writable consumer account assigned to the current
+/// tariff; the whole batch fails if any of them is not. This replaces one
+/// `update_consumer_tariff` call per consumer when a tariff is retired.
+///
+/// # Arguments
+/// * `ctx` - Context containing both tariffs, the agency signer and the consumer accounts
+/// * `current_tariff_key` - Public key of the tariff the consumers are assigned to
+/// * `new_tariff_key` - Public key of the tariff to assign
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::EmptyBatch` - If no consumer accounts are passed
+/// * `CustomError::Unauthorized` - If new_tariff_key doesn't match the new tariff account's key
+/// * `CustomError::Unauthorized` - If a consumer is not assigned to current_tariff_key
+///
+/// # Returns
+/// * `Ok(())` on successful migration
+pub fn migrate_tariff_consumers<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MigrateTariffConsumers<'info>>,
+    current_tariff_key: Pubkey,
+    new_tariff_key: Pubkey,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    require!(!ctx.remaining_accounts.is_empty(), CustomError::EmptyBatch);
+    require_keys_eq!(
+        new_tariff_key,
+        ctx.accounts.new_tariff.tariff_key,
+        CustomError::Unauthorized
+    );
+
+    for account_info in ctx.remaining_accounts {
+        require!(account_info.is_writable, CustomError::Unauthorized);
+        let mut consumer = Account::<Consumer>::try_from(account_info)?;
+
+        require_keys_eq!(
+            current_tariff_key,
+            consumer.assigned_tariff,
+            CustomError::Unauthorized
+        );
+        consumer.assigned_tariff = new_tariff_key;
+        if current_tariff_key != new_tariff_key {
+            ctx.accounts.current_tariff.release_consumer();
+            ctx.accounts.new_tariff.assign_consumer();
+        }
+
+        // Remaining accounts are not persisted by Anchor, so write the change back
+        consumer.exit(&crate::ID)?;
+    }
+
+    msg!(
+        "Migrated {} consumers to a new tariff.",
+        ctx.remaining_accounts.len()
+    );
+    Ok(())
+}
@@ -1,29 +1,153 @@
 pub const DISCRIMINATOR: usize = 8;
 
+mod adjust_capacity;
+mod agency_bond;
+mod allocation;
+mod bill_estimated_usage;
+mod budget_billing;
+mod capacity_approval;
+mod carbon_offsets;
+mod claim_from_guarantor;
+mod close_tariff;
+mod collect_autopay;
+mod communal_point;
+mod complaint;
+mod conservation_certificate;
+mod consumer_compression;
+mod convey_water;
+mod decommission_reservoir;
+mod delegated_payment;
+mod demand_response;
+mod dispense_bulk;
 mod dispose_waste;
+mod drought_insurance;
+mod enable_autopay;
+mod energy_oracle;
+mod forward_contract;
+mod grant_role;
+mod grow_account;
+mod hydro_archive;
+mod interruptible_supply;
+mod initialize_fx_oracle;
 mod initialize_reservoir;
 mod initialize_tariff;
 mod initialize_tokens;
+mod irrigation_schedule;
+mod issue_credit;
+mod level_forecast;
+mod link_sub_consumer;
+mod mandate_consumer_capacity;
+mod migrate_tariff_consumers;
 mod pay_for_waste;
 mod pay_for_water;
+mod payment_gateway;
+mod prepaid_top_up;
+mod reassign_account_holder;
+mod rebill_period;
+mod record_audit;
+mod redemption;
+mod refresh_capacity;
 mod register_consumer;
+mod register_session_key;
+mod regulator_authority;
+mod relay_reading;
+mod renew_contract;
+mod report_usage_batch;
+mod sensor;
+mod set_guarantor;
+mod settle_water_debt;
+mod shared_reservoir;
+mod source_mix;
+mod submit_reading;
+mod true_up;
 mod update_consumer;
+mod update_consumer_household;
+mod update_consumer_identity;
+mod update_consumer_location;
 mod update_consumer_reservoir;
 mod update_consumer_tariff;
+mod update_fx_rate;
 mod update_reservoir;
 mod update_tariff;
 mod use_water;
+mod use_water_split;
+mod verify_charge;
+mod watc_market;
+mod weather_derivative;
+mod work_order;
 
+pub use adjust_capacity::*;
+pub use agency_bond::*;
+pub use allocation::*;
+pub use bill_estimated_usage::*;
+pub use budget_billing::*;
+pub use capacity_approval::*;
+pub use carbon_offsets::*;
+pub use claim_from_guarantor::*;
+pub use close_tariff::*;
+pub use collect_autopay::*;
+pub use communal_point::*;
+pub use complaint::*;
+pub use conservation_certificate::*;
+pub use consumer_compression::*;
+pub use convey_water::*;
+pub use decommission_reservoir::*;
+pub use delegated_payment::*;
+pub use demand_response::*;
+pub use dispense_bulk::*;
 pub use dispose_waste::*;
+pub use drought_insurance::*;
+pub use enable_autopay::*;
+pub use energy_oracle::*;
+pub use forward_contract::*;
+pub use grant_role::*;
+pub use grow_account::*;
+pub use hydro_archive::*;
+pub use interruptible_supply::*;
+pub use initialize_fx_oracle::*;
 pub use initialize_reservoir::*;
 pub use initialize_tariff::*;
 pub use initialize_tokens::*;
+pub use irrigation_schedule::*;
+pub use issue_credit::*;
+pub use level_forecast::*;
+pub use link_sub_consumer::*;
+pub use mandate_consumer_capacity::*;
+pub use migrate_tariff_consumers::*;
 pub use pay_for_waste::*;
 pub use pay_for_water::*;
+pub use payment_gateway::*;
+pub use prepaid_top_up::*;
+pub use reassign_account_holder::*;
+pub use rebill_period::*;
+pub use record_audit::*;
+pub use redemption::*;
+pub use refresh_capacity::*;
 pub use register_consumer::*;
+pub use register_session_key::*;
+pub use regulator_authority::*;
+pub use relay_reading::*;
+pub use renew_contract::*;
+pub use report_usage_batch::*;
+pub use sensor::*;
+pub use set_guarantor::*;
+pub use settle_water_debt::*;
+pub use shared_reservoir::*;
+pub use source_mix::*;
+pub use submit_reading::*;
+pub use true_up::*;
 pub use update_consumer::*;
+pub use update_consumer_household::*;
+pub use update_consumer_identity::*;
+pub use update_consumer_location::*;
 pub use update_consumer_reservoir::*;
 pub use update_consumer_tariff::*;
+pub use update_fx_rate::*;
 pub use update_reservoir::*;
 pub use update_tariff::*;
 pub use use_water::*;
+pub use use_water_split::*;
+pub use verify_charge::*;
+pub use watc_market::*;
+pub use weather_derivative::*;
+pub use work_order::*;
@@ -1,11 +1,12 @@
 use crate::{
-    state::{Consumer, Tariff},
+    state::{AgencyStats, Consumer, Tariff},
+    utils::burn_signed_by_consumer,
     CustomError,
 }; // Import necessary modules
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount},
+    token::{Mint, Token, TokenAccount},
 };
 
 /// Pay for waste instruction context
@@ -16,6 +17,7 @@ use anchor_spl::{
 /// * `consumer` - The consumer account making the payment
 /// * `tariff` - The PDA tariff account assigned to this consumer
 /// * `agency` - The authority that can burn tokens
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
 /// * `consumer_wst` - The consumer's WST token account
 /// * `wst_mint` - The WST token mint
 /// * `token_program` - Required for token operations
@@ -37,6 +39,8 @@ pub struct PayForWaste<'info> {
     pub tariff: Account<'info, Tariff>,
     #[account(mut)]
     pub agency: Signer<'info>, // agency's authorized wallet
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>, // Agency's aggregate statistics
     #[account(mut, associated_token::mint = wst_mint, associated_token::authority = consumer)]
     pub consumer_wst: Account<'info, TokenAccount>,
     #[account(mut, mint::authority = agency)]
@@ -78,17 +82,14 @@ pub fn pay_for_waste(ctx: Context<PayForWaste>, tariff_key: Pubkey, amount: u64)
     );
 
     // Burn WST tokens
-    token::burn(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            token::Burn {
-                mint: ctx.accounts.wst_mint.to_account_info(),
-                from: ctx.accounts.consumer_wst.to_account_info(),
-                authority: ctx.accounts.consumer.to_account_info(),
-            },
-        ),
+    burn_signed_by_consumer(
+        &ctx.accounts.token_program,
+        ctx.accounts.wst_mint.to_account_info(),
+        ctx.accounts.consumer_wst.to_account_info(),
+        ctx.accounts.consumer.to_account_info(),
         amount,
     )?;
+    ctx.accounts.agency_stats.record_payment(amount);
 
     msg!("Burned {} WST tokens on behalf of consumer.", amount);
     Ok(())
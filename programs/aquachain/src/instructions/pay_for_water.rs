@@ -1,11 +1,13 @@
 use crate::{
-    state::{Consumer, Reservoir, Tariff},
-    CustomError,
+    events::PaymentReceived,
+    state::{AgencyStats, Consumer, PaymentReceipt, Reservoir, Tariff},
+    utils::burn_signed_by_consumer,
+    CustomError, DISCRIMINATOR,
 }; // Import necessary modules
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount},
+    token::{Mint, Token, TokenAccount},
 };
 
 /// Pay for water instruction context
@@ -17,10 +19,13 @@ use anchor_spl::{
 /// * `tariff` - The PDA tariff account assigned to this consumer
 /// * `reservoir` - The PDA reservoir account assigned to this consumer
 /// * `agency` - The authority that can burn tokens
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
+/// * `receipt` - The PDA account that will store the payment receipt
 /// * `consumer_wtk` - The consumer's WTK token account
 /// * `wtk_mint` - The WTK token mint
 /// * `token_program` - Required for token operations
 /// * `associated_token_program` - Required for associated token account
+/// * `system_program` - Required for account creation
 ///
 /// # Seeds for Tariff PDA
 /// * `"tariff"` - Constant string
@@ -31,10 +36,15 @@ use anchor_spl::{
 /// * `"reservoir"` - Constant string
 /// * `agency` - Agency's public key
 /// * `reservoir_key` - Unique identifier for the reservoir
+///
+/// # Seeds for PaymentReceipt PDA
+/// * `"receipt"` - Constant string
+/// * `consumer` - Consumer's public key
+/// * `receipts_issued` - The consumer's receipt count, as little-endian bytes
 #[derive(Accounts)]
 #[instruction(tariff_key: Pubkey, reservoir_key: Pubkey)]
 pub struct PayForWater<'info> {
-    #[account(signer)]
+    #[account(mut, signer)]
     pub consumer: Account<'info, Consumer>,
     #[account(
         seeds = [b"tariff", agency.key().as_ref(), &tariff_key.as_ref()],
@@ -52,19 +62,36 @@ pub struct PayForWater<'info> {
     pub reservoir: Account<'info, Reservoir>, // Current Reservoir assigned to this consumer
     #[account(mut)]
     pub agency: Signer<'info>, // agency's authorized wallet
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>, // Agency's aggregate statistics
+    #[account(
+        init,
+        seeds = [
+            b"receipt",
+            consumer.key().as_ref(),
+            consumer.receipts_issued.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + PaymentReceipt::INIT_SPACE
+    )]
+    pub receipt: Account<'info, PaymentReceipt>,
     #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
     pub consumer_wtk: Account<'info, TokenAccount>,
     #[account(mut, mint::authority = agency)]
     pub wtk_mint: Account<'info, Mint>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
 /// Pay for water consumption by burning WTK tokens
 ///
 /// This function allows a consumer to pay for their water usage by burning WTK tokens
 /// from their token account. The amount of tokens burned represents the payment for
-/// water consumption.
+/// water consumption. Partial payments are allowed and reduce the consumer's
+/// outstanding water debt. Each payment issues a `PaymentReceipt` the consumer can
+/// present as proof of payment.
 ///
 /// # Arguments
 /// * `ctx` - Context containing consumer, tariff, reservoir, agency and token accounts
@@ -73,8 +100,9 @@ pub struct PayForWater<'info> {
 /// * `amount` - Amount of WTK tokens to burn as payment
 ///
 /// # Errors
+/// * `CustomError::InvalidAmount` - If amount is zero
 /// * `CustomError::Unauthorized` - If tariff_key or reservoir_key do not match consumer's assigned values
-/// * `CustomError::OverPayment` - If payment amount exceeds consumer's WTK balance
+/// * `CustomError::OverPayment` - If payment amount exceeds consumer's outstanding debt or WTK balance
 ///
 /// # Returns
 /// * `Ok(())` on successful payment
@@ -86,6 +114,8 @@ pub fn pay_for_water(
 ) -> Result<()> {
     let consumer = &mut ctx.accounts.consumer;
 
+    require!(amount > 0, CustomError::InvalidAmount);
+
     require_keys_eq!(
         tariff_key,
         consumer.assigned_tariff,
@@ -97,25 +127,40 @@ pub fn pay_for_water(
         CustomError::Unauthorized
     );
 
-    // ensure that the payment does not exceed the current balance
+    // ensure that the payment does not exceed what is owed or the current balance
     require!(
         ctx.accounts.consumer_wtk.amount >= amount,
         CustomError::OverPayment
     );
+    consumer.pay_water(amount)?;
+    ctx.accounts.agency_stats.record_payment(amount);
 
     // Burn WTK tokens
-    token::burn(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            token::Burn {
-                mint: ctx.accounts.wtk_mint.to_account_info(),
-                from: ctx.accounts.consumer_wtk.to_account_info(),
-                authority: ctx.accounts.consumer.to_account_info(),
-            },
-        ),
+    burn_signed_by_consumer(
+        &ctx.accounts.token_program,
+        ctx.accounts.wtk_mint.to_account_info(),
+        ctx.accounts.consumer_wtk.to_account_info(),
+        ctx.accounts.consumer.to_account_info(),
         amount,
     )?;
 
-    msg!("Burned {} WTK tokens on behalf of consumer.", amount);
+    let slot = Clock::get()?.slot;
+    let consumer_key = ctx.accounts.consumer.key();
+    ctx.accounts
+        .receipt
+        .issue(consumer_key, &mut ctx.accounts.consumer, amount, slot);
+
+    emit!(PaymentReceived {
+        consumer: consumer_key,
+        amount,
+        outstanding: ctx.accounts.consumer.outstanding_water_debt,
+        slot,
+    });
+
+    msg!(
+        "Burned {} WTK tokens on behalf of consumer, {} outstanding.",
+        amount,
+        ctx.accounts.consumer.outstanding_water_debt
+    );
     Ok(())
 }
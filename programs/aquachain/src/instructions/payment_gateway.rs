@@ -0,0 +1,232 @@
+use crate::{
+    events::OffchainPaymentRecorded,
+    state::{Consumer, OffchainPayment, PaymentGateway, Tariff},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+
+/// Register payment gateway instruction context
+///
+/// The **RegisterPaymentGateway** context is used by the agency to license a mobile-money
+/// gateway to credit its consumers' prepaid balances.
+///
+/// # Fields
+/// * `payment_gateway` - The PDA account that will store the gateway's licence
+/// * `agency` - The agency licensing the gateway (must be signer)
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for PaymentGateway PDA
+/// * `"payment_gateway"` - Constant string
+/// * `agency` - Agency's public key
+/// * `gateway_key` - Unique identifier of the gateway
+#[derive(Accounts)]
+#[instruction(gateway_key: Pubkey)]
+pub struct RegisterPaymentGateway<'info> {
+    #[account(
+        init,
+        seeds = [
+            b"payment_gateway",
+            agency.key().as_ref(),
+            gateway_key.as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + PaymentGateway::INIT_SPACE
+    )]
+    pub payment_gateway: Account<'info, PaymentGateway>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Update payment gateway instruction context
+///
+/// # Fields
+/// * `payment_gateway` - The PDA account of the gateway's licence
+/// * `agency` - The agency that licensed the gateway (must be signer)
+///
+/// # Seeds for PaymentGateway PDA
+/// * `"payment_gateway"` - Constant string
+/// * `agency` - Agency's public key
+/// * `gateway_key` - Unique identifier of the gateway
+#[derive(Accounts)]
+pub struct UpdatePaymentGateway<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"payment_gateway",
+            agency.key().as_ref(),
+            payment_gateway.gateway_key.as_ref()
+        ],
+        bump
+    )]
+    pub payment_gateway: Account<'info, PaymentGateway>,
+    pub agency: Signer<'info>,
+}
+
+/// Record offchain payment instruction context
+///
+/// The **RecordOffchainPayment** context is used by a gateway's attestor to credit a
+/// mobile-money payment to a consumer of the gateway's agency.
+///
+/// # Fields
+/// * `payment_gateway` - The PDA account of the gateway's licence
+/// * `payment` - The PDA account that will record the payment
+/// * `consumer` - The consumer account credited
+/// * `tariff` - The PDA tariff account assigned to the consumer by the gateway's agency
+/// * `attestor` - The gateway's attestor key, paying for the record (must be signer)
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for OffchainPayment PDA
+/// * `"offchain_payment"` - Constant string
+/// * `payment_gateway` - The gateway's PDA
+/// * `payment_id` - The gateway's identifier of the payment
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - The gateway's agency
+/// * `assigned_tariff` - The consumer's assigned tariff
+#[derive(Accounts)]
+#[instruction(payment_id: [u8; 32])]
+pub struct RecordOffchainPayment<'info> {
+    #[account(mut, has_one = attestor @ CustomError::Unauthorized)]
+    pub payment_gateway: Account<'info, PaymentGateway>,
+    #[account(
+        init,
+        seeds = [
+            b"offchain_payment",
+            payment_gateway.key().as_ref(),
+            payment_id.as_ref()
+        ],
+        bump,
+        payer = attestor,
+        space = DISCRIMINATOR + OffchainPayment::INIT_SPACE
+    )]
+    pub payment: Account<'info, OffchainPayment>,
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            payment_gateway.agency.as_ref(),
+            consumer.assigned_tariff.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(mut)]
+    pub attestor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// License a mobile-money gateway
+///
+/// # Arguments
+/// * `ctx` - Context containing the gateway and agency accounts
+/// * `gateway_key` - Unique public key identifier of the gateway
+/// * `attestor` - Public key allowed to attest the gateway's payments
+///
+/// # Returns
+/// * `Ok(())` on successful registration
+pub fn register_payment_gateway(
+    ctx: Context<RegisterPaymentGateway>,
+    gateway_key: Pubkey,
+    attestor: Pubkey,
+) -> Result<()> {
+    let gateway = &mut ctx.accounts.payment_gateway;
+    gateway.agency = ctx.accounts.agency.key();
+    gateway.gateway_key = gateway_key;
+    gateway.attestor = attestor;
+    gateway.active = true;
+    gateway.payments_recorded = 0;
+    gateway.total_credited = 0;
+
+    msg!("Payment gateway registered.");
+    Ok(())
+}
+
+/// Rotate a gateway's attestor key or suspend and reinstate its licence
+///
+/// # Arguments
+/// * `ctx` - Context containing the gateway and agency accounts
+/// * `attestor` - Public key allowed to attest the gateway's payments
+/// * `active` - Whether the gateway may record payments
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_payment_gateway(
+    ctx: Context<UpdatePaymentGateway>,
+    attestor: Pubkey,
+    active: bool,
+) -> Result<()> {
+    let gateway = &mut ctx.accounts.payment_gateway;
+    gateway.attestor = attestor;
+    gateway.active = active;
+
+    msg!("Payment gateway updated, active: {}.", active);
+    Ok(())
+}
+
+/// Credit a mobile-money payment attested by a gateway to a consumer's prepaid balance
+///
+/// The consumer paid the gateway off-chain and the gateway settles with the agency
+/// off-chain, so no tokens move. The payment identifier can only be recorded once per
+/// gateway. An `OffchainPaymentRecorded` event is emitted.
+///
+/// # Arguments
+/// * `ctx` - Context containing the gateway, payment, consumer and attestor accounts
+/// * `payment_id` - The gateway's identifier of the payment
+/// * `amount` - Amount to credit to the prepaid balance, in WTK base units
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the signer is not the gateway's attestor
+/// * `CustomError::GatewaySuspended` - If the gateway's licence is suspended
+/// * `CustomError::InvalidAmount` - If amount is zero
+/// * `CustomError::MathOverflow` - If the balance or the gateway's totals overflow
+///
+/// # Returns
+/// * `Ok(())` on successful recording
+pub fn record_offchain_payment(
+    ctx: Context<RecordOffchainPayment>,
+    payment_id: [u8; 32],
+    amount: u64,
+) -> Result<()> {
+    let gateway = &mut ctx.accounts.payment_gateway;
+    let slot = Clock::get()?.slot;
+
+    require!(gateway.active, CustomError::GatewaySuspended);
+    require!(amount > 0, CustomError::InvalidAmount);
+
+    ctx.accounts.consumer.credit_prepaid(amount)?;
+    gateway.payments_recorded = gateway
+        .payments_recorded
+        .checked_add(1)
+        .ok_or(CustomError::MathOverflow)?;
+    gateway.total_credited = gateway
+        .total_credited
+        .checked_add(amount)
+        .ok_or(CustomError::MathOverflow)?;
+
+    let payment = &mut ctx.accounts.payment;
+    payment.gateway = gateway.key();
+    payment.consumer = ctx.accounts.consumer.key();
+    payment.payment_id = payment_id;
+    payment.amount = amount;
+    payment.slot = slot;
+
+    emit!(OffchainPaymentRecorded {
+        gateway: gateway.key(),
+        consumer: payment.consumer,
+        payment_id,
+        amount,
+        prepaid_balance: ctx.accounts.consumer.prepaid_balance,
+        slot,
+    });
+
+    msg!(
+        "Offchain payment of {} recorded, {} prepaid.",
+        amount,
+        ctx.accounts.consumer.prepaid_balance
+    );
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use crate::{
+    events::PrepaidToppedUp,
+    state::{Consumer, FxOracle, Tariff, Tokens},
+    CustomError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Top up prepaid instruction context
+///
+/// The **TopUpPrepaid** context is used by any wallet, typically a customer scanning a
+/// kiosk or field agent's Solana Pay QR code, to pay for a consumer's prepaid balance in
+/// the settlement stablecoin at the FX oracle rate of the consumer's tariff. Solana Pay
+/// references may be appended as extra read-only accounts so the payment can be found.
+///
+/// # Fields
+/// * `consumer` - The consumer account topped up
+/// * `tariff` - The PDA tariff account assigned to the consumer
+/// * `fx_oracle` - The PDA oracle account for the tariff's currency
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `agency` - The agency serving the consumer and receiving the payment
+/// * `payer` - The wallet paying for the top-up (must be signer)
+/// * `payer_settlement` - The payer's stablecoin token account
+/// * `agency_settlement` - The agency's stablecoin treasury account
+/// * `settlement_mint` - The stablecoin mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `assigned_tariff` - The consumer's assigned tariff
+///
+/// # Seeds for FxOracle PDA
+/// * `"fx_oracle"` - Constant string
+/// * `agency` - Agency's public key
+/// * `currency_code` - Currency code of the tariff
+///
+/// # Seeds for Tokens PDA
+/// * `"tokens"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct TopUpPrepaid<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            consumer.assigned_tariff.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(
+        seeds = [
+            b"fx_oracle",
+            agency.key().as_ref(),
+            tariff.currency_code.as_ref()
+        ],
+        bump,
+        has_one = settlement_mint
+    )]
+    pub fx_oracle: Account<'info, FxOracle>,
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>,
+    /// CHECK: only used to derive the agency's accounts and receive the payment
+    pub agency: UncheckedAccount<'info>,
+    pub payer: Signer<'info>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = payer)]
+    pub payer_settlement: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = agency)]
+    pub agency_settlement: Account<'info, TokenAccount>,
+    pub settlement_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Top up a consumer's prepaid balance
+///
+/// This function transfers the equivalent of `amount`, converted at the FX oracle rate,
+/// from the payer's stablecoin account to the agency's treasury and credits `amount` to
+/// the consumer's prepaid balance in the same transaction, so a payment taken at a kiosk
+/// cannot land without crediting the consumer. The prepaid balance is drawn by
+/// `use_water` before any charge is billed as WTK. A `PrepaidToppedUp` event is emitted.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, oracle, payer and token accounts
+/// * `amount` - Amount to credit to the prepaid balance, in WTK base units
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If amount is zero
+/// * `CustomError::StaleOracle` - If the oracle rate is unset or too old
+/// * `CustomError::MathOverflow` - If the converted amount or the balance overflows
+///
+/// # Returns
+/// * `Ok(())` on successful top-up
+pub fn top_up_prepaid(ctx: Context<TopUpPrepaid>, amount: u64) -> Result<()> {
+    let fx_oracle = &ctx.accounts.fx_oracle;
+    let slot = Clock::get()?.slot;
+
+    require!(amount > 0, CustomError::InvalidAmount);
+    require!(!fx_oracle.is_stale(slot), CustomError::StaleOracle);
+
+    ctx.accounts.consumer.credit_prepaid(amount)?;
+
+    let settlement_amount =
+        fx_oracle.to_settlement(amount, ctx.accounts.tokens.units.currency_decimals)?;
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.payer_settlement.to_account_info(),
+                to: ctx.accounts.agency_settlement.to_account_info(),
+                authority: ctx.accounts.payer.to_account_info(),
+            },
+        ),
+        settlement_amount,
+    )?;
+
+    emit!(PrepaidToppedUp {
+        consumer: ctx.accounts.consumer.key(),
+        payer: ctx.accounts.payer.key(),
+        amount,
+        settlement_amount,
+        prepaid_balance: ctx.accounts.consumer.prepaid_balance,
+        slot,
+    });
+
+    msg!(
+        "Consumer topped up by {}, {} prepaid.",
+        amount,
+        ctx.accounts.consumer.prepaid_balance
+    );
+    Ok(())
+}
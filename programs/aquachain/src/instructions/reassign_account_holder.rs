@@ -0,0 +1,91 @@
+use crate::{events::AccountHolderReassigned, state::Consumer, CustomError};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+/// Reassign account holder instruction context
+///
+/// The **ReassignAccountHolder** context is used on a change of occupancy to record a new
+/// account holder for a consumer account. Both the current holder and the agency must sign.
+///
+/// # Fields
+/// * `consumer` - The consumer account whose holder changes
+/// * `owner` - The current account holder of the consumer account
+/// * `agency` - The authority that manages the consumer
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `consumer_wst` - The consumer's WST token account
+/// * `wtk_mint` - The WTK token mint
+/// * `wst_mint` - The WST token mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+#[derive(Accounts)]
+pub struct ReassignAccountHolder<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub consumer: Account<'info, Consumer>,
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    #[account(associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(associated_token::mint = wst_mint, associated_token::authority = consumer)]
+    pub consumer_wst: Account<'info, TokenAccount>,
+    #[account(mint::authority = agency)]
+    pub wtk_mint: Account<'info, Mint>,
+    #[account(mint::authority = agency)]
+    pub wst_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Record a new account holder for a consumer account
+///
+/// The account holder is the wallet of record for the occupant: it alone, besides the
+/// agency, registers and revokes the meter session keys of the account, and it is recorded
+/// as the payer on the account's payment receipts. Reassigning it does not hand over the
+/// consumer's token accounts, which remain under the consumer account's own key, nor any
+/// other instruction signed by that key.
+///
+/// All outstanding water and waste debt must be settled before the holder changes, so the
+/// new occupant never inherits the previous occupant's bills. An `AccountHolderReassigned`
+/// event is emitted for audit.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, owner, agency and token accounts
+/// * `new_owner` - Public key of the wallet becoming the account holder
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the signer is not the current holder or new_owner is unset
+/// * `CustomError::OutstandingDebt` - If the consumer still holds WTK or WST debt
+///
+/// # Returns
+/// * `Ok(())` on successful reassignment
+pub fn reassign_account_holder(
+    ctx: Context<ReassignAccountHolder>,
+    new_owner: Pubkey,
+) -> Result<()> {
+    require_keys_neq!(new_owner, Pubkey::default(), CustomError::Unauthorized);
+    require!(
+        ctx.accounts.consumer_wtk.amount == 0 && ctx.accounts.consumer_wst.amount == 0,
+        CustomError::OutstandingDebt
+    );
+
+    let consumer = &mut ctx.accounts.consumer;
+    let previous_owner = consumer.owner;
+    consumer.owner = new_owner;
+
+    emit!(AccountHolderReassigned {
+        consumer: consumer.key(),
+        previous_owner,
+        new_owner,
+        slot: Clock::get()?.slot,
+    });
+
+    msg!(
+        "Consumer account holder reassigned from {} to {}.",
+        previous_owner,
+        new_owner
+    );
+    Ok(())
+}
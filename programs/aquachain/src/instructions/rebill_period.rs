@@ -0,0 +1,201 @@
+use crate::{
+    events::PeriodRebilled,
+    state::{BillBreakdown, Consumer, Reservoir, Tariff, TariffHistory, Tokens},
+    utils::{burn_signed_by_consumer, FixedPoint},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Rebill period instruction context
+///
+/// The **RebillPeriod** context is used by the agency to correct the charge for a past billing
+/// period, e.g. after a late meter read. The consumer co-signs so overcharges can be burned.
+///
+/// # Fields
+/// * `consumer` - The consumer account being rebilled (must be signer)
+/// * `tariff` - The PDA tariff account assigned to this consumer
+/// * `tariff_history` - The PDA account recording superseded versions of the tariff
+/// * `reservoir` - The PDA reservoir account assigned to this consumer
+/// * `agency` - The authority that can mint tokens
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `wtk_mint` - The WTK token mint
+/// * `system_program` - Required for account creation
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+///
+/// # Seeds for TariffHistory PDA
+/// * `"tariff_history"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+///
+/// # Seeds for Reservoir PDA
+/// * `"reservoir"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey, reservoir_key: Pubkey)]
+pub struct RebillPeriod<'info> {
+    #[account(mut, signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [b"tariff", agency.key().as_ref(), &tariff_key.as_ref()],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(
+        init_if_needed,
+        seeds = [
+            b"tariff_history",
+            agency.key().as_ref(),
+            &tariff_key.as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + TariffHistory::INIT_SPACE
+    )]
+    pub tariff_history: Account<'info, TariffHistory>,
+    #[account(
+        seeds = [
+            b"reservoir",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    #[account(mut)]
+    pub agency: Signer<'info>, // agency's authorized wallet
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub wtk_mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Recompute the water charge for a past billing period
+///
+/// This function prices `actual_volume` under the tariff version that was in force at
+/// `period_slot`, starting from the tariff's full block threshold, and compares it
+/// with `billed_amount`, the WTK originally charged for the period. The difference is
+/// minted to the consumer if they were undercharged, or burned if they were overcharged,
+/// and the outstanding water debt is adjusted accordingly.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, tariff, history, reservoir, agency and token accounts
+/// * `tariff_key` - Public key of the tariff assigned to this consumer
+/// * `reservoir_key` - Public key of the reservoir assigned to this consumer
+/// * `period_slot` - Any slot within the billing period being corrected
+/// * `actual_volume` - Corrected volume of water used during the period
+/// * `billed_amount` - Amount of WTK originally charged for the period
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If tariff_key or reservoir_key do not match consumer's assigned values
+/// * `CustomError::TariffVersionUnavailable` - If the tariff in force at period_slot is no longer stored
+/// * `CustomError::OverPayment` - If an overcharge exceeds the consumer's outstanding debt
+///
+/// # Returns
+/// * `Ok(())` on successful rebilling
+pub fn rebill_period(
+    ctx: Context<RebillPeriod>,
+    tariff_key: Pubkey,
+    reservoir_key: Pubkey,
+    period_slot: u64,
+    actual_volume: u64,
+    billed_amount: u64,
+) -> Result<()> {
+    let consumer = &ctx.accounts.consumer;
+    let reservoir = &ctx.accounts.reservoir;
+    let units = &ctx.accounts.tokens.units;
+
+    require_keys_eq!(
+        tariff_key,
+        consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+    require_keys_eq!(
+        reservoir_key,
+        consumer.assigned_reservoir,
+        CustomError::Unauthorized
+    );
+
+    let version = ctx
+        .accounts
+        .tariff_history
+        .version_in_force(&ctx.accounts.tariff, period_slot)
+        .ok_or(error!(CustomError::TariffVersionUnavailable))?;
+
+    let multiplier = reservoir.scarcity_multiplier(version.tariff_type, units)?;
+    let corrected_amount = BillBreakdown::price(
+        &ctx.accounts.tariff.charge_bounds(),
+        units,
+        units.to_volume(
+            ctx.accounts
+                .tariff
+                .block_threshold_for(consumer.contracted_capacity, consumer.household_size),
+        )?,
+        units.to_volume(actual_volume)?,
+        FixedPoint::from(version.water_rate),
+        FixedPoint::from(consumer.block_rate),
+        multiplier,
+    )?
+    .total;
+
+    if corrected_amount > billed_amount {
+        // Undercharged: bill the difference
+        let delta = corrected_amount - billed_amount;
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    to: ctx.accounts.consumer_wtk.to_account_info(),
+                    authority: ctx.accounts.agency.to_account_info(),
+                    mint: ctx.accounts.wtk_mint.to_account_info(),
+                },
+            ),
+            delta,
+        )?;
+        let slot = Clock::get()?.slot;
+        ctx.accounts.consumer.bill_water(delta, slot)?;
+    } else if corrected_amount < billed_amount {
+        // Overcharged: write off the difference
+        let delta = billed_amount - corrected_amount;
+        ctx.accounts.consumer.pay_water(delta)?;
+        burn_signed_by_consumer(
+            &ctx.accounts.token_program,
+            ctx.accounts.wtk_mint.to_account_info(),
+            ctx.accounts.consumer_wtk.to_account_info(),
+            ctx.accounts.consumer.to_account_info(),
+            delta,
+        )?;
+    }
+
+    emit!(PeriodRebilled {
+        consumer: ctx.accounts.consumer.key(),
+        period_slot,
+        billed_amount,
+        corrected_amount,
+        slot: Clock::get()?.slot,
+    });
+
+    msg!(
+        "Period rebilled from {} to {} under the tariff activated at slot {}.",
+        billed_amount,
+        corrected_amount,
+        version.activated_slot
+    );
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use crate::{
+    state::{AuditCategory, AuditRecord, Role, RoleKind},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+
+/// Record audit instruction context
+///
+/// The **RecordAudit** context is used by the agency to append the hash of an off-chain
+/// document, such as a rate filing or lab report, to its audit log.
+///
+/// # Fields
+/// * `audit_record` - The PDA account anchoring the document hash
+/// * `agency` - The agency the operation is performed for
+/// * `authority` - The agency or a staff key holding the Auditor role
+/// * `role` - Role account of the authority, when it is not the agency
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for AuditRecord PDA
+/// * `"audit_record"` - Constant string
+/// * `agency` - Agency's public key
+/// * `document_hash` - SHA-256 hash of the document
+#[derive(Accounts)]
+#[instruction(document_hash: [u8; 32])]
+pub struct RecordAudit<'info> {
+    #[account(
+        init,
+        seeds = [b"audit_record", agency.key().as_ref(), &document_hash],
+        bump,
+        payer = authority,
+        space = DISCRIMINATOR + AuditRecord::INIT_SPACE
+    )]
+    pub audit_record: Account<'info, AuditRecord>,
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Anchor the hash of an off-chain document in the agency's audit log
+///
+/// Records are derived from the document hash, so each document can only be recorded
+/// once per agency and can be found again from a copy of the document alone.
+///
+/// # Arguments
+/// * `ctx` - Context containing the audit record and agency accounts
+/// * `document_hash` - SHA-256 hash of the document
+/// * `category` - Kind of document
+/// * `subject` - Account the document concerns, or the default key if none
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::InvalidDocumentHash` - If the hash is all zeros
+///
+/// # Returns
+/// * `Ok(())` on successful recording
+pub fn record_audit(
+    ctx: Context<RecordAudit>,
+    document_hash: [u8; 32],
+    category: AuditCategory,
+    subject: Pubkey,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Auditor,
+    )?;
+
+    require!(document_hash != [0; 32], CustomError::InvalidDocumentHash);
+
+    let clock = Clock::get()?;
+    let audit_record = &mut ctx.accounts.audit_record;
+    audit_record.document_hash = document_hash;
+    audit_record.category = category;
+    audit_record.subject = subject;
+    audit_record.recorded_at = clock.unix_timestamp;
+    audit_record.recorded_slot = clock.slot;
+
+    msg!("Audit record {:?} anchored.", category);
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use crate::{
+    state::{Consumer, RedemptionOffer, RedemptionVoucher},
+    utils::burn_signed_by_consumer,
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+/// Create redemption offer instruction context
+///
+/// The **CreateRedemptionOffer** context is used by the agency to list a perk consumers
+/// can redeem with AquaCoin.
+///
+/// # Fields
+/// * `redemption_offer` - The PDA account that will store the offer
+/// * `aqc_mint` - The AquaCoin mint the offer is priced in
+/// * `agency` - The agency honouring the perk
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for RedemptionOffer PDA
+/// * `"redemption_offer"` - Constant string
+/// * `agency` - Agency's public key
+/// * `offer_key` - Unique identifier for the offer
+#[derive(Accounts)]
+#[instruction(offer_key: Pubkey)]
+pub struct CreateRedemptionOffer<'info> {
+    #[account(
+        init,
+        seeds = [
+            b"redemption_offer",
+            agency.key().as_ref(),
+            &offer_key.as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + RedemptionOffer::INIT_SPACE
+    )]
+    pub redemption_offer: Account<'info, RedemptionOffer>,
+    pub aqc_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Redeem offer instruction context
+///
+/// The **RedeemOffer** context is used by a consumer to burn AquaCoin for a perk, receiving
+/// a voucher account. The agency co-signs and pays for the voucher.
+///
+/// # Fields
+/// * `consumer` - The consumer account redeeming the offer (must be signer)
+/// * `redemption_offer` - The offer being redeemed
+/// * `redemption_voucher` - The PDA account of the voucher issued to the consumer
+/// * `consumer_aqc` - The consumer's AquaCoin token account
+/// * `aqc_mint` - The AquaCoin mint
+/// * `agency` - The agency honouring the perk
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for RedemptionOffer PDA
+/// * `"redemption_offer"` - Constant string
+/// * `agency` - Agency's public key
+/// * `offer_key` - Unique identifier for the offer
+///
+/// # Seeds for RedemptionVoucher PDA
+/// * `"redemption_voucher"` - Constant string
+/// * `redemption_offer` - Offer's public key
+/// * `index` - Number of redemptions already made, little-endian
+#[derive(Accounts)]
+pub struct RedeemOffer<'info> {
+    #[account(signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        mut,
+        seeds = [
+            b"redemption_offer",
+            agency.key().as_ref(),
+            redemption_offer.offer_key.as_ref()
+        ],
+        bump,
+        has_one = aqc_mint
+    )]
+    pub redemption_offer: Account<'info, RedemptionOffer>,
+    #[account(
+        init,
+        seeds = [
+            b"redemption_voucher",
+            redemption_offer.key().as_ref(),
+            &redemption_offer.redeemed.to_le_bytes()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + RedemptionVoucher::INIT_SPACE
+    )]
+    pub redemption_voucher: Account<'info, RedemptionVoucher>,
+    #[account(mut, associated_token::mint = aqc_mint, associated_token::authority = consumer)]
+    pub consumer_aqc: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub aqc_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// List a perk redeemable with AquaCoin
+///
+/// # Arguments
+/// * `ctx` - Context containing the offer, AquaCoin mint and agency accounts
+/// * `offer_key` - Unique identifier for the offer
+/// * `name` - Name of the perk
+/// * `price` - Amount of AquaCoin burned per redemption (must be > 0)
+/// * `quantity` - Number of redemptions available (must be > 0)
+///
+/// # Errors
+/// * `CustomError::InvalidPerkName` - If name is empty or longer than 32 bytes
+/// * `CustomError::InvalidAmount` - If price or quantity is zero
+///
+/// # Returns
+/// * `Ok(())` on successful listing
+pub fn create_redemption_offer(
+    ctx: Context<CreateRedemptionOffer>,
+    offer_key: Pubkey,
+    name: String,
+    price: u64,
+    quantity: u64,
+) -> Result<()> {
+    require!(
+        RedemptionOffer::is_valid_name(&name),
+        CustomError::InvalidPerkName
+    );
+    require!(price > 0 && quantity > 0, CustomError::InvalidAmount);
+
+    let redemption_offer = &mut ctx.accounts.redemption_offer;
+    redemption_offer.agency = ctx.accounts.agency.key();
+    redemption_offer.offer_key = offer_key;
+    redemption_offer.aqc_mint = ctx.accounts.aqc_mint.key();
+    redemption_offer.price = price;
+    redemption_offer.quantity = quantity;
+    redemption_offer.redeemed = 0;
+    redemption_offer.name = name;
+
+    msg!(
+        "Redemption offer {} listed: {} at {} AQC.",
+        redemption_offer.name,
+        quantity,
+        price
+    );
+    Ok(())
+}
+
+/// Burn AquaCoin for a perk
+///
+/// This function burns the offer's price from the consumer's AquaCoin account and issues
+/// a voucher recording the claim, which the agency honours off-chain.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, offer, voucher, agency and token accounts
+///
+/// # Errors
+/// * `CustomError::OfferSoldOut` - If every listed redemption has been made
+///
+/// # Returns
+/// * `Ok(())` on successful redemption
+pub fn redeem_offer(ctx: Context<RedeemOffer>) -> Result<()> {
+    let index = ctx.accounts.redemption_offer.redeem()?;
+    let price = ctx.accounts.redemption_offer.price;
+
+    burn_signed_by_consumer(
+        &ctx.accounts.token_program,
+        ctx.accounts.aqc_mint.to_account_info(),
+        ctx.accounts.consumer_aqc.to_account_info(),
+        ctx.accounts.consumer.to_account_info(),
+        price,
+    )?;
+
+    let redemption_voucher = &mut ctx.accounts.redemption_voucher;
+    redemption_voucher.offer = ctx.accounts.redemption_offer.key();
+    redemption_voucher.consumer = ctx.accounts.consumer.key();
+    redemption_voucher.index = index;
+    redemption_voucher.price = price;
+    redemption_voucher.redeemed_at = Clock::get()?.unix_timestamp;
+
+    msg!("Offer redeemed for {} AQC, voucher {}.", price, index);
+    Ok(())
+}
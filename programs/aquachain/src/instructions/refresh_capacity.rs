@@ -0,0 +1,157 @@
+use crate::{
+    events::CapacityAdjusted,
+    state::{AdjustmentReason, Consumer, Tariff, Tokens},
+    CustomError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+use aquachain_core::NEUTRAL_WEATHER_BPS;
+
+/// Refresh capacity instruction context
+///
+/// The **RefreshCapacity** context is used by the agency, or a crank holding its key, to
+/// replenish a consumer's WATC at the start of a new billing period. The consumer does not
+/// need to sign since capacity is only ever minted.
+///
+/// # Fields
+/// * `consumer` - The consumer account whose capacity is refreshed
+/// * `tariff` - The PDA tariff account assigned to this consumer, defining the billing period
+///   and rollover policy
+/// * `agency` - The authority that can mint WATC tokens
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `consumer_watc` - The consumer's WaterCapacityToken account
+/// * `watc_mint` - The mint for WaterCapacityTokens
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token operations
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+///
+/// # Seeds for Tokens PDA
+/// * `"tokens"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey)]
+pub struct RefreshCapacity<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            &tariff_key.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>, // Tariff assigned to this consumer
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, associated_token::mint = watc_mint, associated_token::authority = consumer)]
+    pub consumer_watc: Account<'info, TokenAccount>, // Consumer's WaterCapacityToken account
+    #[account(
+        mut,
+        mint::authority = agency,
+        mint::decimals = tokens.units.currency_decimals
+    )]
+    pub watc_mint: Account<'info, Mint>, // Mint for the WaterCapacityToken
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Replenish a consumer's WATC up to its contracted capacity at period rollover
+///
+/// Once the tariff's billing period has elapsed since the consumer's capacity period
+/// began, the consumer's WATC balance is topped up and a new period is started, aligned on
+/// the previous one, with the consumer's period usage and waste reset. The tariff's
+/// `capacity_rollover_bps` decides how much of the unused balance is carried on top of the
+/// contracted capacity; the rest expires by counting towards the new allotment. Balances
+/// already above the target are left as they are, since burning requires the consumer's
+/// signature. Before the usage is reset, the closing period extends the consumer's
+/// conservation streak if its usage stayed under the tariff's conservation baseline and
+/// did not exceed the consumer's average over its previous periods, and resets it
+/// otherwise. The period's usage is then added to the consumer's usage history. A
+/// `CapacityAdjusted` event is emitted with the `PeriodRefresh` reason.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, tariff, agency and token accounts
+/// * `tariff_key` - Public key of the tariff assigned to this consumer
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If tariff_key does not match the consumer's assigned tariff
+/// * `CustomError::CapacityRefreshNotDue` - If the tariff has no billing period or the
+///   current period has not ended
+///
+/// # Returns
+/// * `Ok(())` on successful refresh
+pub fn refresh_capacity(ctx: Context<RefreshCapacity>, tariff_key: Pubkey) -> Result<()> {
+    let consumer = &mut ctx.accounts.consumer;
+
+    require_keys_eq!(
+        tariff_key,
+        consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+
+    let slot = Clock::get()?.slot;
+    let period_start = consumer
+        .capacity_refresh_due(ctx.accounts.tariff.billing_period_slots, slot)
+        .ok_or(error!(CustomError::CapacityRefreshNotDue))?;
+    let billing_period_slots = ctx.accounts.tariff.billing_period_slots;
+    let usage_baseline = (consumer.usage_history_len > 0).then(|| {
+        consumer.usage_baseline(
+            billing_period_slots,
+            billing_period_slots,
+            NEUTRAL_WEATHER_BPS,
+        )
+    });
+    let conserving = ctx.accounts.tariff.is_conserving(
+        consumer.period_usage,
+        consumer.contracted_capacity,
+        consumer.household_size,
+        usage_baseline,
+    );
+    consumer.close_conservation_period(conserving);
+    consumer.start_capacity_period(period_start);
+
+    let balance = ctx.accounts.consumer_watc.amount;
+    let target = ctx
+        .accounts
+        .tariff
+        .capacity_refresh_target(consumer.contracted_capacity, balance);
+    let top_up = target.saturating_sub(balance);
+    if top_up > 0 {
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    to: ctx.accounts.consumer_watc.to_account_info(),
+                    authority: ctx.accounts.agency.to_account_info(),
+                    mint: ctx.accounts.watc_mint.to_account_info(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+
+    emit!(CapacityAdjusted {
+        consumer: ctx.accounts.consumer.key(),
+        delta: i64::try_from(top_up).map_err(|_| error!(CustomError::MathOverflow))?,
+        reason: AdjustmentReason::PeriodRefresh,
+        balance: balance + top_up,
+        slot,
+    });
+
+    msg!(
+        "Consumer capacity refreshed by {} for the period starting at slot {}.",
+        top_up,
+        period_start
+    );
+    Ok(())
+}
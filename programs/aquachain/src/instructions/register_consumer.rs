@@ -1,5 +1,5 @@
 use crate::{
-    state::{Consumer, Reservoir, Tariff},
+    state::{AgencyStats, Consumer, Reservoir, Tariff, Tokens},
     CustomError, DISCRIMINATOR,
 };
 use anchor_lang::prelude::*;
@@ -18,8 +18,13 @@ use anchor_spl::{
 /// * `tariff` - The PDA tariff account assigned to this consumer
 /// * `reservoir` - The PDA reservoir account assigned to this consumer  
 /// * `agency` - The authority that can register new consumers
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
 /// * `consumer_watc` - The consumer's WATC token account
 /// * `watc_mint` - The WATC token mint
+/// * `billing_authority` - The program PDA approved as delegate for settlement, if opted in
+/// * `consumer_wtk` - The consumer's WTK token account, if delegated at registration
+/// * `consumer_wst` - The consumer's WST token account, if delegated at registration
 /// * `system_program` - Required for account creation
 /// * `token_program` - Required for token operations
 /// * `associated_token_program` - Required for associated token account
@@ -33,12 +38,21 @@ use anchor_spl::{
 /// * `"reservoir"` - Constant string
 /// * `agency` - Agency's public key
 /// * `reservoir_key` - Unique identifier for the reservoir
+///
+/// # Seeds for Tokens PDA
+/// * `"tokens"` - Constant string
+/// * `agency` - Agency's public key
+///
+/// # Seeds for Billing Authority PDA
+/// * `"billing_authority"` - Constant string
+/// * `agency` - Agency's public key
 #[derive(Accounts)]
 #[instruction(tariff_key: Pubkey, reservoir_key: Pubkey)]
 pub struct RegisterConsumer<'info> {
     #[account(init, payer = agency, space = DISCRIMINATOR + Consumer::INIT_SPACE)]
     pub consumer: Account<'info, Consumer>,
     #[account(
+        mut,
         seeds = [
             b"tariff",
             agency.key().as_ref(),
@@ -48,6 +62,7 @@ pub struct RegisterConsumer<'info> {
     )]
     pub tariff: Account<'info, Tariff>, // Tariff assigned to this consumer
     #[account(
+        mut,
         seeds = [
             b"reservoir",
             agency.key().as_ref(),
@@ -58,10 +73,29 @@ pub struct RegisterConsumer<'info> {
     pub reservoir: Account<'info, Reservoir>, // Reservoir assigned to this consumer
     #[account(mut)]
     pub agency: Signer<'info>,
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>, // Agency's aggregate statistics
     #[account(mut, associated_token::mint = watc_mint,  associated_token::authority = consumer)]
     pub consumer_watc: Account<'info, TokenAccount>, // Consumer's WaterCapacityToken account
-    #[account(mut, mint::authority = agency, mint::decimals = 9)]
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
     pub watc_mint: Account<'info, Mint>, // Mint for the WaterCapacityToken
+    /// CHECK: PDA used only as an SPL token delegate, it holds no data
+    #[account(seeds = [b"billing_authority", agency.key().as_ref()], bump)]
+    pub billing_authority: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        constraint = consumer_wtk.mint == tokens.wtk && consumer_wtk.owner == consumer.key()
+            @ CustomError::InvalidDelegatedAccount
+    )]
+    pub consumer_wtk: Option<Account<'info, TokenAccount>>, // Consumer's WaterToken account
+    #[account(
+        mut,
+        constraint = consumer_wst.mint == tokens.wst && consumer_wst.owner == consumer.key()
+            @ CustomError::InvalidDelegatedAccount
+    )]
+    pub consumer_wst: Option<Account<'info, TokenAccount>>, // Consumer's WasteToken account
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -74,6 +108,11 @@ pub struct RegisterConsumer<'info> {
 /// contracted capacity and block rate. It also mints WATC tokens to the consumer based
 /// on their contracted capacity.
 ///
+/// When the billing authority is passed, the consumer also approves it as delegate on the
+/// WTK and WST accounts passed, so the agency can later settle payments server-side with
+/// `pay_for_water_delegated` and `pay_for_waste_delegated`. The consumer keeps the right
+/// to revoke the delegation at any time.
+///
 /// # Arguments
 /// * `ctx` - Context containing consumer, tariff, reservoir, agency and token accounts
 /// * `tariff_key` - Public key of the tariff assigned to this consumer
@@ -84,6 +123,10 @@ pub struct RegisterConsumer<'info> {
 /// # Errors
 /// * `CustomError::InvalidCapacity` - If contracted_capacity is 0
 /// * `CustomError::InvalidRate` - If block_rate is 0
+/// * `CustomError::InvalidDelegatedAccount` - If a token account passed is not the consumer's
+///   WTK or WST account
+/// * `CustomError::MissingBillingAuthority` - If token accounts are passed without the
+///   billing authority
 ///
 /// # Returns
 /// * `Ok(())` on successful registration
@@ -95,6 +138,7 @@ pub fn register_consumer(
     block_rate: u64,
 ) -> Result<()> {
     let consumer = &mut ctx.accounts.consumer;
+    let consumer_key = consumer.key();
 
     // Validation: Ensure capacity and rate are non-zero
     require!(contracted_capacity > 0, CustomError::InvalidCapacity);
@@ -102,9 +146,14 @@ pub fn register_consumer(
 
     consumer.assigned_tariff = tariff_key;
     consumer.assigned_reservoir = reservoir_key;
+    ctx.accounts.tariff.assign_consumer();
+    ctx.accounts.reservoir.assign_consumer();
+    ctx.accounts.agency_stats.record_consumer();
 
     consumer.block_rate = block_rate;
     consumer.contracted_capacity = contracted_capacity;
+    consumer.owner = consumer_key;
+    consumer.capacity_period_start = Clock::get()?.slot;
 
     // Mint WATC tokens to the consumer based on contracted capacity
     token::mint_to(
@@ -119,6 +168,27 @@ pub fn register_consumer(
         contracted_capacity,
     )?;
 
+    // Consumer pre-approves settlement of its WTK and WST debt by the agency
+    let delegated = [&ctx.accounts.consumer_wtk, &ctx.accounts.consumer_wst];
+    for account in delegated.into_iter().flatten() {
+        let billing_authority = ctx
+            .accounts
+            .billing_authority
+            .as_ref()
+            .ok_or(CustomError::MissingBillingAuthority)?;
+        token::approve(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Approve {
+                    to: account.to_account_info(),
+                    delegate: billing_authority.to_account_info(),
+                    authority: ctx.accounts.consumer.to_account_info(),
+                },
+            ),
+            u64::MAX,
+        )?;
+    }
+
     msg!("New consumer registered with contracted capacity and block rate.");
     Ok(())
 }
@@ -0,0 +1,172 @@
+use crate::{
+    state::{Consumer, SessionKey, SessionScope, Tariff},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+
+/// Register session key instruction context
+///
+/// The **RegisterSessionKey** context is used by a consumer's owner, or by the agency, to
+/// delegate meter readings to a short-lived key held by the consumer's meter device.
+/// Registering the same key again renews it.
+///
+/// # Fields
+/// * `session` - The PDA account recording the session key
+/// * `consumer` - The consumer account the key acts for
+/// * `tariff` - The PDA account of the consumer's assigned tariff, when the agency signs
+/// * `authority` - The consumer's owner or the agency, paying for the session account
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for SessionKey PDA
+/// * `"session_key"` - Constant string
+/// * `consumer` - Consumer's public key
+/// * `session_key` - Public key held by the meter device
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `authority` - Agency's public key
+/// * `consumer.assigned_tariff` - The consumer's assigned tariff key
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct RegisterSessionKey<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"session_key", consumer.key().as_ref(), session_key.as_ref()],
+        bump,
+        payer = authority,
+        space = DISCRIMINATOR + SessionKey::INIT_SPACE
+    )]
+    pub session: Account<'info, SessionKey>,
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            authority.key().as_ref(),
+            consumer.assigned_tariff.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Option<Account<'info, Tariff>>, // Only passed when the agency signs
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Revoke session key instruction context
+///
+/// The **RevokeSessionKey** context is used by a consumer's owner, or by the agency, to
+/// withdraw a meter device's session key before it expires, closing its account.
+///
+/// # Fields
+/// * `session` - The PDA account recording the session key
+/// * `consumer` - The consumer account the key acts for
+/// * `tariff` - The PDA account of the consumer's assigned tariff, when the agency signs
+/// * `authority` - The consumer's owner or the agency, receiving the account's rent
+///
+/// # Seeds for SessionKey PDA
+/// * `"session_key"` - Constant string
+/// * `consumer` - Consumer's public key
+/// * `session_key` - Public key held by the meter device
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `authority` - Agency's public key
+/// * `consumer.assigned_tariff` - The consumer's assigned tariff key
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct RevokeSessionKey<'info> {
+    #[account(
+        mut,
+        seeds = [b"session_key", consumer.key().as_ref(), session_key.as_ref()],
+        bump,
+        close = authority
+    )]
+    pub session: Account<'info, SessionKey>,
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            authority.key().as_ref(),
+            consumer.assigned_tariff.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Option<Account<'info, Tariff>>, // Only passed when the agency signs
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Delegate meter readings to a short-lived device key
+///
+/// The key may only sign `submit_reading` for this consumer, and is rejected once
+/// `expires_at` has passed. Sessions last at most `MAX_SESSION_DURATION`, so devices have
+/// to be re-provisioned periodically rather than holding a long-lived key.
+///
+/// # Arguments
+/// * `ctx` - Context containing the session, consumer and authority accounts
+/// * `session_key` - Public key held by the meter device
+/// * `expires_at` - Unix timestamp after which the key is rejected
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the signer is neither the consumer's owner nor its agency
+/// * `CustomError::InvalidSessionExpiry` - If expires_at is not in the future or too far ahead
+///
+/// # Returns
+/// * `Ok(())` on successful registration
+pub fn register_session_key(
+    ctx: Context<RegisterSessionKey>,
+    session_key: Pubkey,
+    expires_at: i64,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    require_registrar(
+        &ctx.accounts.consumer,
+        &authority,
+        ctx.accounts.tariff.is_some(),
+    )?;
+    require!(
+        SessionKey::is_valid_expiry(expires_at, Clock::get()?.unix_timestamp),
+        CustomError::InvalidSessionExpiry
+    );
+
+    let session = &mut ctx.accounts.session;
+    session.consumer = ctx.accounts.consumer.key();
+    session.session_key = session_key;
+    session.scope = SessionScope::SubmitReading;
+    session.expires_at = expires_at;
+    session.registered_by = authority;
+
+    msg!("Session key {} registered.", session_key);
+    Ok(())
+}
+
+/// Withdraw a meter device's session key
+///
+/// # Arguments
+/// * `ctx` - Context containing the session, consumer and authority accounts
+/// * `session_key` - Public key held by the meter device
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the signer is neither the consumer's owner nor its agency
+///
+/// # Returns
+/// * `Ok(())` on successful revocation
+pub fn revoke_session_key(ctx: Context<RevokeSessionKey>, session_key: Pubkey) -> Result<()> {
+    require_registrar(
+        &ctx.accounts.consumer,
+        &ctx.accounts.authority.key(),
+        ctx.accounts.tariff.is_some(),
+    )?;
+
+    msg!("Session key {} revoked.", session_key);
+    Ok(())
+}
+
+/// Checks that the signer is the consumer's owner, or the agency of its assigned tariff
+fn require_registrar(consumer: &Consumer, authority: &Pubkey, is_agency: bool) -> Result<()> {
+    require!(
+        is_agency || *authority == consumer.owner,
+        CustomError::Unauthorized
+    );
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use crate::{
+    state::{RegulatorAuthority, Role, RoleKind},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+
+/// Appoint regulator instruction context
+///
+/// The **AppointRegulator** context is used by the agency, when it is set up, to record
+/// the regulator overseeing it. The regulator signs to accept the appointment, so the
+/// agency cannot appoint a key of its own choosing. The regulator can only be appointed
+/// once.
+///
+/// # Fields
+/// * `regulator_authority` - The PDA account that will record the regulator
+/// * `agency` - The agency overseen by the regulator (must be signer)
+/// * `authority` - The regulator accepting the appointment (must be signer)
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for RegulatorAuthority PDA
+/// * `"regulator_authority"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct AppointRegulator<'info> {
+    #[account(
+        init,
+        seeds = [b"regulator_authority", agency.key().as_ref()],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + RegulatorAuthority::INIT_SPACE
+    )]
+    pub regulator_authority: Account<'info, RegulatorAuthority>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Grant regulator role instruction context
+///
+/// The **GrantRegulatorRole** context is used by the regulator authority to let one of
+/// its own keys approve regulated operations on the agency.
+///
+/// # Fields
+/// * `role` - The PDA account recording the member's role
+/// * `agency` - The agency overseen by the regulator
+/// * `regulator_authority` - The PDA account recording the agency's regulator
+/// * `authority` - The regulator authority, paying for the role account (must be signer)
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for Role PDA
+/// * `"role"` - Constant string
+/// * `agency` - Agency's public key
+/// * `member` - Key holding the role
+///
+/// # Seeds for RegulatorAuthority PDA
+/// * `"regulator_authority"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct GrantRegulatorRole<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"role", agency.key().as_ref(), member.as_ref()],
+        bump,
+        payer = authority,
+        space = DISCRIMINATOR + Role::INIT_SPACE
+    )]
+    pub role: Account<'info, Role>,
+    /// CHECK: only used to derive the role and regulator authority PDAs
+    pub agency: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"regulator_authority", agency.key().as_ref()],
+        bump,
+        has_one = authority @ CustomError::MissingRegulatorApproval
+    )]
+    pub regulator_authority: Account<'info, RegulatorAuthority>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Revoke regulator role instruction context
+///
+/// The **RevokeRegulatorRole** context is used by the regulator authority to withdraw
+/// the Regulator role from a key, closing its role account.
+///
+/// # Fields
+/// * `role` - The PDA account recording the member's role
+/// * `agency` - The agency overseen by the regulator
+/// * `regulator_authority` - The PDA account recording the agency's regulator
+/// * `authority` - The regulator authority, receiving the account's rent (must be signer)
+///
+/// # Seeds for Role PDA
+/// * `"role"` - Constant string
+/// * `agency` - Agency's public key
+/// * `member` - Key holding the role
+///
+/// # Seeds for RegulatorAuthority PDA
+/// * `"regulator_authority"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct RevokeRegulatorRole<'info> {
+    #[account(
+        mut,
+        seeds = [b"role", agency.key().as_ref(), member.as_ref()],
+        bump,
+        close = authority
+    )]
+    pub role: Account<'info, Role>,
+    /// CHECK: only used to derive the role and regulator authority PDAs
+    pub agency: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"regulator_authority", agency.key().as_ref()],
+        bump,
+        has_one = authority @ CustomError::MissingRegulatorApproval
+    )]
+    pub regulator_authority: Account<'info, RegulatorAuthority>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Appoint the regulator overseeing the agency
+///
+/// Regulatory approvals, such as inter-basin transfers, tariff bounds, mandated capacity
+/// reductions and slashing the agency's bond, must come from the regulator or a key it
+/// granted the Regulator role. The regulator co-signs its appointment, and the
+/// appointment is permanent, so the agency can neither appoint nor later substitute a key
+/// of its own.
+///
+/// # Arguments
+/// * `ctx` - Context containing the regulator authority, agency and regulator accounts
+///
+/// # Errors
+/// * `CustomError::InvalidRegulator` - If the regulator is the agency
+///
+/// # Returns
+/// * `Ok(())` on successful appointment
+pub fn appoint_regulator(ctx: Context<AppointRegulator>) -> Result<()> {
+    let agency = ctx.accounts.agency.key();
+    let authority = ctx.accounts.authority.key();
+    require_keys_neq!(authority, agency, CustomError::InvalidRegulator);
+
+    let regulator_authority = &mut ctx.accounts.regulator_authority;
+    regulator_authority.agency = agency;
+    regulator_authority.authority = authority;
+    regulator_authority.appointed_slot = Clock::get()?.slot;
+
+    msg!("Regulator {} appointed for agency {}.", authority, agency);
+    Ok(())
+}
+
+/// Grant a key the Regulator role within the agency
+///
+/// # Arguments
+/// * `ctx` - Context containing the role, agency and regulator authority accounts
+/// * `member` - Key to grant the Regulator role to
+///
+/// # Errors
+/// * `CustomError::MissingRegulatorApproval` - If the signer is not the regulator authority
+/// * `CustomError::InvalidRoleMember` - If the member is the agency itself
+/// * `CustomError::ReservedRole` - If the member holds a staff role granted by the agency
+///
+/// # Returns
+/// * `Ok(())` on successful grant
+pub fn grant_regulator_role(ctx: Context<GrantRegulatorRole>, member: Pubkey) -> Result<()> {
+    let agency = ctx.accounts.agency.key();
+    require_keys_neq!(member, agency, CustomError::InvalidRoleMember);
+
+    let role = &mut ctx.accounts.role;
+    require!(
+        role.member == Pubkey::default() || role.kind == RoleKind::Regulator,
+        CustomError::ReservedRole
+    );
+
+    role.agency = agency;
+    role.member = member;
+    role.kind = RoleKind::Regulator;
+    role.granted_slot = Clock::get()?.slot;
+
+    msg!("Regulator role granted to {}.", member);
+    Ok(())
+}
+
+/// Revoke a key's Regulator role within the agency
+///
+/// # Arguments
+/// * `ctx` - Context containing the role, agency and regulator authority accounts
+/// * `member` - Key to revoke the Regulator role from
+///
+/// # Errors
+/// * `CustomError::MissingRegulatorApproval` - If the signer is not the regulator authority
+/// * `CustomError::ReservedRole` - If the member holds a staff role granted by the agency
+///
+/// # Returns
+/// * `Ok(())` on successful revocation
+pub fn revoke_regulator_role(ctx: Context<RevokeRegulatorRole>, member: Pubkey) -> Result<()> {
+    require!(
+        ctx.accounts.role.kind == RoleKind::Regulator,
+        CustomError::ReservedRole
+    );
+
+    msg!("Regulator role revoked from {}.", member);
+    Ok(())
+}
@@ -0,0 +1,220 @@
+use crate::{
+    state::{Consumer, SessionKey, SessionScope},
+    utils::{meter_payload, parse_ed25519_instruction, parse_secp256k1_instruction},
+    CustomError,
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        ed25519_program,
+        instruction::Instruction,
+        secp256k1_program,
+        sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+    },
+};
+
+/// Relay reading instruction context
+///
+/// The **RelayReading** context is used by anyone, such as a gateway collecting readings
+/// from meters without connectivity, to post a meter payload signed by the device.
+///
+/// # Fields
+/// * `consumer` - The consumer account the reading is for
+/// * `session` - The PDA account recording the device's key
+/// * `relayer` - The account relaying the payload and paying the fees
+/// * `instructions_sysvar` - The instructions sysvar, used to read the Ed25519 instruction
+///
+/// # Seeds for SessionKey PDA
+/// * `"session_key"` - Constant string
+/// * `consumer` - Consumer's public key
+/// * `device` - Public key held by the meter device
+#[derive(Accounts)]
+#[instruction(device: Pubkey)]
+pub struct RelayReading<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [b"session_key", consumer.key().as_ref(), device.as_ref()],
+        bump
+    )]
+    pub session: Account<'info, SessionKey>,
+    pub relayer: Signer<'info>,
+    /// CHECK: address checked against the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+/// Relay secp256k1-signed reading instruction context
+///
+/// The **RelayReadingSecp256k1** context is used by anyone, such as an AMI head-end, to
+/// post a meter payload signed with a device's existing secp256k1 key.
+///
+/// # Fields
+/// * `consumer` - The consumer account the reading is for
+/// * `session` - The PDA account recording the device's Ethereum address
+/// * `relayer` - The account relaying the payload and paying the fees
+/// * `instructions_sysvar` - The instructions sysvar, used to read the Secp256k1 instruction
+///
+/// # Seeds for SessionKey PDA
+/// * `"session_key"` - Constant string
+/// * `consumer` - Consumer's public key
+/// * `eth_address` - Device's Ethereum address, left-padded to a public key
+#[derive(Accounts)]
+#[instruction(eth_address: [u8; 20])]
+pub struct RelayReadingSecp256k1<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"session_key",
+            consumer.key().as_ref(),
+            SessionKey::eth_address_key(&eth_address).as_ref()
+        ],
+        bump
+    )]
+    pub session: Account<'info, SessionKey>,
+    pub relayer: Signer<'info>,
+    /// CHECK: address checked against the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+/// Record a meter reading from a payload signed by the device
+///
+/// The transaction must verify the device's signature over the payload with an Ed25519
+/// program instruction placed immediately before this one. The payload binds the consumer,
+/// the reading and the time it was taken, so it cannot be replayed for another consumer
+/// or after a newer reading.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, session, relayer and instructions sysvar
+/// * `device` - Public key held by the meter device
+/// * `reading` - Cumulative register value reported by the meter
+/// * `timestamp` - Unix timestamp at which the meter took the reading
+///
+/// # Errors
+/// * `CustomError::SessionExpired` - If the device's key has expired or is out of scope
+/// * `CustomError::InvalidMeterSignature` - If no matching Ed25519 verification precedes
+///   this instruction
+/// * `CustomError::StaleMeterReading` - If the payload is not newer than the last reading
+///   or is dated in the future
+/// * `CustomError::InvalidMeterReading` - If the reading is below the previous one
+///
+/// # Returns
+/// * `Ok(())` on successful relay
+pub fn relay_reading(
+    ctx: Context<RelayReading>,
+    device: Pubkey,
+    reading: u64,
+    timestamp: i64,
+) -> Result<()> {
+    let (_, verify_ix) = preceding_instruction(&ctx.accounts.instructions_sysvar)?;
+    require_keys_eq!(
+        verify_ix.program_id,
+        ed25519_program::ID,
+        CustomError::InvalidMeterSignature
+    );
+    let (signer, message) = parse_ed25519_instruction(&verify_ix.data)
+        .ok_or(error!(CustomError::InvalidMeterSignature))?;
+    require_keys_eq!(signer, device, CustomError::InvalidMeterSignature);
+
+    record_signed_reading(
+        &mut ctx.accounts.consumer,
+        &ctx.accounts.session,
+        message,
+        reading,
+        timestamp,
+    )?;
+
+    msg!("Meter reading {} relayed.", reading);
+    Ok(())
+}
+
+/// Record a meter reading from a payload signed with a device's secp256k1 key
+///
+/// Works like `relay_reading` for meters that sign with secp256k1, as many AMI head-ends
+/// do, so they can feed readings without re-keying. The transaction must recover the
+/// device's Ethereum address from its signature over the payload with a Secp256k1
+/// program instruction placed immediately before this one. The device is registered with
+/// `register_session_key` under its address left-padded with zeroes to a public key.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, session, relayer and instructions sysvar
+/// * `eth_address` - Ethereum address of the device's secp256k1 key
+/// * `reading` - Cumulative register value reported by the meter
+/// * `timestamp` - Unix timestamp at which the meter took the reading
+///
+/// # Errors
+/// * `CustomError::SessionExpired` - If the device's registration has expired
+/// * `CustomError::InvalidMeterSignature` - If no matching Secp256k1 verification precedes
+///   this instruction
+/// * `CustomError::StaleMeterReading` - If the payload is not newer than the last reading
+///   or is dated in the future
+/// * `CustomError::InvalidMeterReading` - If the reading is below the previous one
+///
+/// # Returns
+/// * `Ok(())` on successful relay
+pub fn relay_reading_secp256k1(
+    ctx: Context<RelayReadingSecp256k1>,
+    eth_address: [u8; 20],
+    reading: u64,
+    timestamp: i64,
+) -> Result<()> {
+    let (index, verify_ix) = preceding_instruction(&ctx.accounts.instructions_sysvar)?;
+    require_keys_eq!(
+        verify_ix.program_id,
+        secp256k1_program::ID,
+        CustomError::InvalidMeterSignature
+    );
+    let own_index = u8::try_from(index).map_err(|_| error!(CustomError::InvalidMeterSignature))?;
+    let (signer, message) = parse_secp256k1_instruction(&verify_ix.data, own_index)
+        .ok_or(error!(CustomError::InvalidMeterSignature))?;
+    require!(signer == eth_address, CustomError::InvalidMeterSignature);
+
+    record_signed_reading(
+        &mut ctx.accounts.consumer,
+        &ctx.accounts.session,
+        message,
+        reading,
+        timestamp,
+    )?;
+
+    msg!("Meter reading {} relayed.", reading);
+    Ok(())
+}
+
+/// Loads the instruction placed immediately before the current one, with its index
+fn preceding_instruction(instructions_sysvar: &AccountInfo) -> Result<(usize, Instruction)> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, CustomError::InvalidMeterSignature);
+    let index = current_index as usize - 1;
+    Ok((
+        index,
+        load_instruction_at_checked(index, instructions_sysvar)?,
+    ))
+}
+
+/// Records a reading once its payload has been verified as signed by the session's device
+fn record_signed_reading(
+    consumer: &mut Account<Consumer>,
+    session: &SessionKey,
+    message: &[u8],
+    reading: u64,
+    timestamp: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.permits(SessionScope::SubmitReading, now),
+        CustomError::SessionExpired
+    );
+    require!(
+        message == meter_payload(&consumer.key(), reading, timestamp),
+        CustomError::InvalidMeterSignature
+    );
+    require!(
+        timestamp > consumer.meter_read_at && timestamp <= now,
+        CustomError::StaleMeterReading
+    );
+    consumer.submit_reading(reading, timestamp)
+}
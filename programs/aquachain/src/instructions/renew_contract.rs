@@ -0,0 +1,88 @@
+use crate::{Consumer, CustomError, Role, RoleKind, Tariff};
+use anchor_lang::prelude::*;
+
+/// Renew **Consumer** contract context
+///
+/// The **Consumer** account's fixed-term supply contract is extended by the agency.
+///
+/// # Fields
+/// * `consumer` - The consumer account whose contract is renewed
+/// * `tariff` - The PDA account of the consumer's assigned tariff
+/// * `agency` - The agency the operation is performed for
+/// * `authority` - The agency or a staff key holding the Billing role
+/// * `role` - Role account of the authority, when it is not the agency
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey)]
+pub struct RenewContract<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            &tariff_key.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>, // Tariff assigned to this consumer
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
+}
+
+/// Set the slot at which a consumer's supply contract expires
+///
+/// Extends, or makes open-ended, the consumer's fixed-term contract. Usage billed after
+/// `contract_expiry_slot` is charged at the block rate until the contract is renewed
+/// again.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, its tariff and the agency signer
+/// * `tariff_key` - Public key of the consumer's assigned tariff
+/// * `contract_expiry_slot` - New expiry slot of the contract, or 0 for an open-ended one
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match consumer's assigned tariff
+/// * `CustomError::InvalidContractExpiry` - If the new expiry slot is not in the future
+///
+/// # Returns
+/// * `Ok(())` on successful renewal
+pub fn renew_contract(
+    ctx: Context<RenewContract>,
+    tariff_key: Pubkey,
+    contract_expiry_slot: u64,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let consumer = &mut ctx.accounts.consumer;
+
+    require_keys_eq!(
+        tariff_key,
+        consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+    require!(
+        contract_expiry_slot == 0 || contract_expiry_slot > Clock::get()?.slot,
+        CustomError::InvalidContractExpiry
+    );
+
+    consumer.contract_expiry_slot = contract_expiry_slot;
+
+    msg!(
+        "Consumer contract renewed until slot {}.",
+        contract_expiry_slot
+    );
+    Ok(())
+}
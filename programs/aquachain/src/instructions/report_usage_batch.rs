@@ -0,0 +1,223 @@
+use crate::{
+    events::{ContractExpired, WaterBilled},
+    state::{
+        is_within_zone, AgencyStats, Consumer, MeterReading, PricingSnapshot, Reservoir, Tariff,
+        Tokens,
+    },
+    CustomError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+/// Report usage batch instruction context
+///
+/// The **ReportUsageBatch** context is used by the agency's back office to bill many meter
+/// readings in one transaction. Every consumer in the batch must be assigned to the same
+/// tariff and reservoir, and located within the reservoir's zone. The consumers do not
+/// sign, so their WATC is not burned; tiering follows their period usage instead.
+///
+/// For each consumer, the remaining accounts hold the consumer account followed by its
+/// WTK token account, both writable.
+///
+/// # Fields
+/// * `tariff` - The PDA tariff account assigned to the consumers
+/// * `reservoir` - The PDA reservoir account assigned to the consumers
+/// * `agency` - The authority that can mint tokens
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
+/// * `wtk_mint` - The WTK token mint
+/// * `token_program` - Required for token operations
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+///
+/// # Seeds for Reservoir PDA
+/// * `"reservoir"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+///
+/// # Seeds for Tokens PDA
+/// * `"tokens"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey, reservoir_key: Pubkey)]
+pub struct ReportUsageBatch<'info> {
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            &tariff_key.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>, // Tariff assigned to the consumers
+    #[account(
+        seeds = [
+            b"reservoir",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>, // Reservoir assigned to the consumers
+    pub agency: Signer<'info>, // Authority of the provider
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>, // Agency's aggregate statistics
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub wtk_mint: Account<'info, Mint>, // Mint for the WaterToken
+    pub token_program: Program<'info, Token>,
+}
+
+/// Bill a batch of meter readings by minting WTK tokens to each consumer
+///
+/// Each reading is priced exactly as `use_water` prices it, from the consumer's usage in
+/// the current period, and emits a `WaterBilled` event. A consumer may appear in several
+/// readings, which are then billed in order. The whole batch fails if any reading is
+/// invalid.
+///
+/// # Arguments
+/// * `ctx` - Context containing the tariff, reservoir, agency, token and consumer accounts
+/// * `tariff_key` - Public key of the tariff assigned to the consumers
+/// * `reservoir_key` - Public key of the reservoir assigned to the consumers
+/// * `readings` - Meter readings, each referring to a consumer account pair by index
+///
+/// # Errors
+/// * `CustomError::EmptyBatch` - If no readings are passed
+/// * `CustomError::InvalidBatchIndex` - If a reading refers to a missing account pair
+/// * `CustomError::InvalidAmount` - If a reading is zero
+/// * `CustomError::Unauthorized` - If a consumer is not assigned to tariff_key and
+///   reservoir_key, or its WTK account does not belong to it
+/// * `CustomError::OutsideZone` - If a consumer is located outside the reservoir's zone
+/// * `CustomError::MathOverflow` - If the converted volume or cost does not fit in a u64
+///
+/// # Returns
+/// * `Ok(())` on successful billing
+pub fn report_usage_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ReportUsageBatch<'info>>,
+    tariff_key: Pubkey,
+    reservoir_key: Pubkey,
+    readings: Vec<MeterReading>,
+) -> Result<()> {
+    let tariff = &ctx.accounts.tariff;
+    let reservoir = &ctx.accounts.reservoir;
+    let units = &ctx.accounts.tokens.units;
+
+    require!(!readings.is_empty(), CustomError::EmptyBatch);
+
+    let slot = Clock::get()?.slot;
+    let mut total_charged = 0u64;
+    for reading in &readings {
+        require!(reading.amount > 0, CustomError::InvalidAmount);
+
+        let index = reading.consumer_index as usize * 2;
+        let Some([consumer_info, consumer_wtk_info]) = ctx.remaining_accounts.get(index..index + 2)
+        else {
+            return err!(CustomError::InvalidBatchIndex);
+        };
+        require!(
+            consumer_info.is_writable && consumer_wtk_info.is_writable,
+            CustomError::Unauthorized
+        );
+
+        let mut consumer = Account::<Consumer>::try_from(consumer_info)?;
+        let consumer_wtk = Account::<TokenAccount>::try_from(consumer_wtk_info)?;
+
+        require_keys_eq!(
+            tariff_key,
+            consumer.assigned_tariff,
+            CustomError::Unauthorized
+        );
+        require_keys_eq!(
+            reservoir_key,
+            consumer.assigned_reservoir,
+            CustomError::Unauthorized
+        );
+        require!(
+            is_within_zone(&consumer.geohash, &reservoir.zone_geohash),
+            CustomError::OutsideZone
+        );
+        require_keys_eq!(
+            consumer_wtk.mint,
+            ctx.accounts.wtk_mint.key(),
+            CustomError::Unauthorized
+        );
+        require_keys_eq!(
+            consumer_wtk.owner,
+            consumer.key(),
+            CustomError::Unauthorized
+        );
+
+        let scarcity_inputs = reservoir.scarcity_inputs(tariff.tariff_type, None);
+        let multiplier = scarcity_inputs.multiplier(units)?;
+        let pricing = PricingSnapshot {
+            tariff_activated_slot: tariff.activated_slot,
+            water_rate: tariff.water_rate,
+            block_rate: consumer.block_rate,
+            max_multiplier: tariff.max_multiplier,
+            min_charge: tariff.min_charge,
+            block_remaining: consumer.block_remaining(tariff, slot),
+            volume: reading.amount,
+            level: scarcity_inputs.level(),
+            multiplier: multiplier.into(),
+            volume_scale: units.volume_scale,
+            currency_decimals: units.currency_decimals,
+            slot,
+        };
+        let bill = pricing.bill()?;
+        let total_cost = bill.total;
+
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    to: consumer_wtk_info.clone(),
+                    authority: ctx.accounts.agency.to_account_info(),
+                    mint: ctx.accounts.wtk_mint.to_account_info(),
+                },
+            ),
+            total_cost,
+        )?;
+
+        consumer.bill_water(total_cost, slot)?;
+        consumer.record_actual_usage(reading.amount);
+        consumer.last_bill = bill;
+        consumer.last_pricing = pricing;
+        consumer.last_scarcity_inputs = scarcity_inputs;
+        ctx.accounts.agency_stats.record_usage(reading.amount);
+
+        let co2e = reservoir.emissions_for(reading.amount, units.volume_scale);
+        consumer.record_emissions(co2e);
+        ctx.accounts.agency_stats.record_emissions(co2e);
+
+        emit!(WaterBilled {
+            consumer: consumer.key(),
+            volume: reading.amount,
+            charge: total_cost,
+            outstanding: consumer.outstanding_water_debt,
+            breakdown: bill,
+            slot,
+        });
+        if consumer.contract_expired(slot) {
+            emit!(ContractExpired {
+                consumer: consumer.key(),
+                expiry_slot: consumer.contract_expiry_slot,
+                slot,
+            });
+        }
+
+        // Remaining accounts are not persisted by Anchor, so write the changes back
+        consumer.exit(&crate::ID)?;
+        total_charged = total_charged.saturating_add(total_cost);
+    }
+
+    msg!(
+        "Billed {} meter readings, charged: {}.",
+        readings.len(),
+        total_charged
+    );
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use crate::{
+    instructions::apply_reservoir_level,
+    state::{median_level, Reservoir, Sensor},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+
+/// Register **Sensor** account context
+///
+/// Used by the agency to add a level gauge to a reservoir's sensor network.
+///
+/// # Fields
+/// * `sensor` - The PDA account that will store the sensor's readings
+/// * `reservoir` - The PDA account of the gauged reservoir
+/// * `agency` - The agency owning the reservoir (must be signer)
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for Sensor PDA
+/// * `"sensor"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier of the reservoir
+/// * `sensor_key` - Unique identifier of the sensor
+#[derive(Accounts)]
+#[instruction(reservoir_key: Pubkey, sensor_key: Pubkey)]
+pub struct RegisterSensor<'info> {
+    #[account(
+        init,
+        seeds = [
+            b"sensor",
+            agency.key().as_ref(),
+            reservoir_key.as_ref(),
+            sensor_key.as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + Sensor::INIT_SPACE
+    )]
+    pub sensor: Account<'info, Sensor>,
+    #[account(
+        seeds = [b"reservoir", agency.key().as_ref(), reservoir_key.as_ref()],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Submit **Sensor** reading context
+///
+/// # Fields
+/// * `sensor` - The sensor account storing the reading
+/// * `authority` - The sensor's reporting key
+#[derive(Accounts)]
+pub struct SubmitSensorReading<'info> {
+    #[account(mut, has_one = authority @ CustomError::Unauthorized)]
+    pub sensor: Account<'info, Sensor>,
+    pub authority: Signer<'info>,
+}
+
+/// Aggregate sensor levels context
+///
+/// The reservoir's sensor accounts are passed as remaining accounts.
+///
+/// # Fields
+/// * `reservoir` - The PDA account whose level is updated
+/// * `agency` - The agency owning the reservoir, signing only to exceed the hourly change
+///   limit
+///
+/// # Seeds
+/// * `"reservoir"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for this reservoir
+#[derive(Accounts)]
+#[instruction(reservoir_key: Pubkey)]
+pub struct AggregateSensorLevels<'info> {
+    #[account(
+        mut,
+        seeds = [b"reservoir", agency.key().as_ref(), reservoir_key.as_ref()],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    /// CHECK: only used to derive the reservoir PDA and check for a co-signature
+    pub agency: UncheckedAccount<'info>,
+}
+
+/// Add a level gauge to a reservoir's sensor network
+///
+/// # Arguments
+/// * `ctx` - Context containing the sensor, reservoir and agency accounts
+/// * `reservoir_key` - Unique public key identifier of the reservoir
+/// * `sensor_key` - Unique public key identifier of the sensor
+/// * `sensor_authority` - Public key allowed to submit the sensor's readings
+///
+/// # Returns
+/// * `Ok(())` on successful registration
+pub fn register_sensor(
+    ctx: Context<RegisterSensor>,
+    reservoir_key: Pubkey,
+    sensor_key: Pubkey,
+    sensor_authority: Pubkey,
+) -> Result<()> {
+    let sensor = &mut ctx.accounts.sensor;
+    sensor.agency = ctx.accounts.agency.key();
+    sensor.reservoir_key = reservoir_key;
+    sensor.sensor_key = sensor_key;
+    sensor.authority = sensor_authority;
+    sensor.level = 0;
+    sensor.read_slot = 0;
+
+    msg!("Sensor registered.");
+    Ok(())
+}
+
+/// Record a sensor's reading of its reservoir level
+///
+/// The reading only reaches the reservoir once the network's readings are aggregated.
+///
+/// # Arguments
+/// * `ctx` - Context containing the sensor account and its reporting key
+/// * `level` - Level read by the sensor (must be > 0)
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the signer is not the sensor's reporting key
+/// * `CustomError::InvalidReservoirLevel` - If level is 0
+///
+/// # Returns
+/// * `Ok(())` on successful submission
+pub fn submit_sensor_reading(ctx: Context<SubmitSensorReading>, level: u64) -> Result<()> {
+    require!(level > 0, CustomError::InvalidReservoirLevel);
+
+    let sensor = &mut ctx.accounts.sensor;
+    sensor.level = level;
+    sensor.read_slot = Clock::get()?.slot;
+
+    msg!("Sensor reading of {} submitted.", level);
+    Ok(())
+}
+
+/// Set a reservoir's level to the median of its sensors' recent readings
+///
+/// Anyone may aggregate. Each remaining account must be a distinct sensor of the reservoir;
+/// sensors without a recent reading are skipped. The median goes through the same bounds
+/// and hourly change limit as a directly reported level.
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir, agency and sensor accounts
+/// * `reservoir_key` - Unique public key identifier of the reservoir
+///
+/// # Errors
+/// * `CustomError::EmptyBatch` - If no sensor accounts are passed
+/// * `CustomError::InvalidSensor` - If a sensor belongs to another reservoir or is passed twice
+/// * `CustomError::NoRecentSensorReadings` - If no sensor has a recent reading
+/// * `CustomError::InvalidReservoirLevel` - If the median exceeds the reservoir's capacity
+/// * `CustomError::ImplausibleLevelChange` - If the median changed by more than the hourly
+///   limit without the agency's signature
+///
+/// # Returns
+/// * `Ok(())` on successful aggregation
+pub fn aggregate_sensor_levels<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AggregateSensorLevels<'info>>,
+    reservoir_key: Pubkey,
+) -> Result<()> {
+    require!(!ctx.remaining_accounts.is_empty(), CustomError::EmptyBatch);
+
+    let clock = Clock::get()?;
+    let agency_key = ctx.accounts.agency.key();
+    let mut sensor_keys = Vec::with_capacity(ctx.remaining_accounts.len());
+    let mut levels = Vec::with_capacity(ctx.remaining_accounts.len());
+
+    for account_info in ctx.remaining_accounts {
+        let sensor = Account::<Sensor>::try_from(account_info)?;
+        require!(
+            sensor.agency == agency_key
+                && sensor.reservoir_key == reservoir_key
+                && !sensor_keys.contains(&sensor.sensor_key),
+            CustomError::InvalidSensor
+        );
+        sensor_keys.push(sensor.sensor_key);
+
+        if sensor.has_recent_reading(clock.slot) {
+            levels.push(sensor.level);
+        }
+    }
+
+    let level = median_level(&mut levels).ok_or(CustomError::NoRecentSensorReadings)?;
+    apply_reservoir_level(
+        &mut ctx.accounts.reservoir,
+        level,
+        &clock,
+        ctx.accounts.agency.is_signer,
+    )?;
+
+    msg!(
+        "Reservoir level set to the median of {} sensor readings.",
+        levels.len()
+    );
+    Ok(())
+}
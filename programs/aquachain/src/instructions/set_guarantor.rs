@@ -0,0 +1,104 @@
+use crate::{state::Consumer, CustomError};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Set guarantor instruction context
+///
+/// The **SetGuarantor** context is used to register a guarantor for a consumer. The consumer,
+/// the guarantor and the agency must all sign.
+///
+/// # Fields
+/// * `consumer` - The consumer account being guaranteed (must be signer)
+/// * `guarantor` - The co-signer liable for the consumer's unpaid debt
+/// * `agency` - The authority that manages the consumer
+/// * `billing_authority` - The program PDA approved as delegate on both token accounts
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `guarantor_settlement` - The guarantor's stablecoin token account
+/// * `wtk_mint` - The WTK token mint
+/// * `settlement_mint` - The stablecoin mint debts are settled in
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for Billing Authority PDA
+/// * `"billing_authority"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct SetGuarantor<'info> {
+    #[account(mut, signer)]
+    pub consumer: Account<'info, Consumer>,
+    pub guarantor: Signer<'info>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    /// CHECK: PDA used only as an SPL token delegate, it holds no data
+    #[account(seeds = [b"billing_authority", agency.key().as_ref()], bump)]
+    pub billing_authority: UncheckedAccount<'info>,
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = guarantor)]
+    pub guarantor_settlement: Account<'info, TokenAccount>,
+    #[account(mint::authority = agency)]
+    pub wtk_mint: Account<'info, Mint>,
+    pub settlement_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Register a guarantor for a consumer
+///
+/// This function records the guarantor on the consumer account and sets up the token
+/// delegations used when the consumer defaults: the guarantor approves the billing
+/// authority up to `guarantee_limit` on their stablecoin account, and the consumer
+/// approves it on their WTK account so guaranteed debt can be written off once collected.
+/// The guarantor can revoke the delegation at any time.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, guarantor, agency and token accounts
+/// * `guarantee_limit` - Maximum amount of stablecoin the guarantor pre-approves
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If guarantee_limit is zero
+///
+/// # Returns
+/// * `Ok(())` on successful registration
+pub fn set_guarantor(ctx: Context<SetGuarantor>, guarantee_limit: u64) -> Result<()> {
+    require!(guarantee_limit > 0, CustomError::InvalidAmount);
+
+    let guarantor_key = ctx.accounts.guarantor.key();
+    ctx.accounts.consumer.guarantor = Some(guarantor_key);
+
+    // Guarantor pre-approves collection of defaulted debt up to the limit
+    token::approve(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Approve {
+                to: ctx.accounts.guarantor_settlement.to_account_info(),
+                delegate: ctx.accounts.billing_authority.to_account_info(),
+                authority: ctx.accounts.guarantor.to_account_info(),
+            },
+        ),
+        guarantee_limit,
+    )?;
+
+    // Consumer allows collected debt to be written off
+    token::approve(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Approve {
+                to: ctx.accounts.consumer_wtk.to_account_info(),
+                delegate: ctx.accounts.billing_authority.to_account_info(),
+                authority: ctx.accounts.consumer.to_account_info(),
+            },
+        ),
+        u64::MAX,
+    )?;
+
+    msg!(
+        "Guarantor {} registered with a limit of {}.",
+        guarantor_key,
+        guarantee_limit
+    );
+    Ok(())
+}
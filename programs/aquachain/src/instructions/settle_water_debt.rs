@@ -0,0 +1,182 @@
+use crate::{
+    state::{AgencyStats, Consumer, FxOracle, PaymentReceipt, Tariff, Tokens},
+    utils::burn_signed_by_consumer,
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Settle water debt instruction context
+///
+/// The **SettleWaterDebt** context is used to settle a consumer's WTK debt, denominated in
+/// the tariff's local currency, with a stablecoin at the current FX oracle rate.
+///
+/// # Fields
+/// * `consumer` - The consumer account settling its debt
+/// * `tariff` - The PDA tariff account assigned to this consumer
+/// * `fx_oracle` - The PDA oracle account for the tariff's currency
+/// * `agency` - The authority receiving the settlement
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
+/// * `receipt` - The PDA account that will store the payment receipt
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `consumer_settlement` - The consumer's stablecoin token account
+/// * `agency_settlement` - The agency's stablecoin treasury account
+/// * `wtk_mint` - The WTK token mint
+/// * `settlement_mint` - The stablecoin mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+///
+/// # Seeds for FxOracle PDA
+/// * `"fx_oracle"` - Constant string
+/// * `agency` - Agency's public key
+/// * `currency_code` - Currency code of the tariff
+///
+/// # Seeds for PaymentReceipt PDA
+/// * `"receipt"` - Constant string
+/// * `consumer` - Consumer's public key
+/// * `receipts_issued` - The consumer's receipt count, as little-endian bytes
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey)]
+pub struct SettleWaterDebt<'info> {
+    #[account(mut, signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [b"tariff", agency.key().as_ref(), &tariff_key.as_ref()],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(
+        seeds = [
+            b"fx_oracle",
+            agency.key().as_ref(),
+            tariff.currency_code.as_ref()
+        ],
+        bump,
+        has_one = settlement_mint
+    )]
+    pub fx_oracle: Account<'info, FxOracle>,
+    #[account(mut)]
+    pub agency: Signer<'info>, // agency's authorized wallet
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>, // Agency's aggregate statistics
+    #[account(
+        init,
+        seeds = [
+            b"receipt",
+            consumer.key().as_ref(),
+            consumer.receipts_issued.to_le_bytes().as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + PaymentReceipt::INIT_SPACE
+    )]
+    pub receipt: Account<'info, PaymentReceipt>,
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = consumer)]
+    pub consumer_settlement: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = agency)]
+    pub agency_settlement: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency)]
+    pub wtk_mint: Account<'info, Mint>,
+    pub settlement_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Settle water debt in a stablecoin at the current exchange rate
+///
+/// This function burns WTK tokens from the consumer's account and transfers the
+/// equivalent amount of the settlement stablecoin, converted at the FX oracle rate,
+/// from the consumer to the agency's treasury. Posted rates stay stable in local
+/// terms while settlement follows the stablecoin. Each settlement issues a
+/// `PaymentReceipt` for the WTK settled.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, tariff, oracle, agency and token accounts
+/// * `tariff_key` - Public key of the tariff assigned to this consumer
+/// * `amount` - Amount of WTK tokens to settle
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If tariff_key does not match the consumer's assigned tariff
+/// * `CustomError::InvalidAmount` - If amount is zero
+/// * `CustomError::OverPayment` - If amount exceeds the consumer's outstanding debt or WTK balance
+/// * `CustomError::StaleOracle` - If the oracle rate is unset or too old
+/// * `CustomError::MathOverflow` - If the converted amount does not fit in a u64
+///
+/// # Returns
+/// * `Ok(())` on successful settlement
+pub fn settle_water_debt(
+    ctx: Context<SettleWaterDebt>,
+    tariff_key: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let consumer = &ctx.accounts.consumer;
+    let fx_oracle = &ctx.accounts.fx_oracle;
+
+    require_keys_eq!(
+        tariff_key,
+        consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+    require!(amount > 0, CustomError::InvalidAmount);
+    require!(
+        ctx.accounts.consumer_wtk.amount >= amount,
+        CustomError::OverPayment
+    );
+    let slot = Clock::get()?.slot;
+    require!(!fx_oracle.is_stale(slot), CustomError::StaleOracle);
+
+    ctx.accounts.consumer.pay_water(amount)?;
+    ctx.accounts.agency_stats.record_payment(amount);
+
+    let settlement_amount =
+        fx_oracle.to_settlement(amount, ctx.accounts.tokens.units.currency_decimals)?;
+
+    // Transfer the converted amount to the agency's treasury
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.consumer_settlement.to_account_info(),
+                to: ctx.accounts.agency_settlement.to_account_info(),
+                authority: ctx.accounts.consumer.to_account_info(),
+            },
+        ),
+        settlement_amount,
+    )?;
+
+    // Burn the settled WTK debt
+    burn_signed_by_consumer(
+        &ctx.accounts.token_program,
+        ctx.accounts.wtk_mint.to_account_info(),
+        ctx.accounts.consumer_wtk.to_account_info(),
+        ctx.accounts.consumer.to_account_info(),
+        amount,
+    )?;
+
+    let consumer_key = ctx.accounts.consumer.key();
+    ctx.accounts
+        .receipt
+        .issue(consumer_key, &mut ctx.accounts.consumer, amount, slot);
+
+    msg!(
+        "Settled {} WTK for {} units of the settlement mint.",
+        amount,
+        settlement_amount
+    );
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use crate::{
+    events::SharedDrawSettled,
+    state::{DrawShare, Reservoir, SharedReservoir},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+
+/// Configure shared reservoir instruction context
+///
+/// The **ConfigureSharedReservoir** context is used by the agency operating a reservoir to
+/// share it with other agencies, or to change their draw rights.
+///
+/// # Fields
+/// * `shared_reservoir` - The PDA account holding the reservoir's draw rights
+/// * `reservoir` - The PDA account of the reservoir being shared
+/// * `agency` - The agency operating the reservoir
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for SharedReservoir PDA
+/// * `"shared_reservoir"` - Constant string
+/// * `agency` - Operating agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+///
+/// # Seeds for Reservoir PDA
+/// * `"reservoir"` - Constant string
+/// * `agency` - Operating agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+#[derive(Accounts)]
+#[instruction(reservoir_key: Pubkey)]
+pub struct ConfigureSharedReservoir<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [
+            b"shared_reservoir",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + SharedReservoir::INIT_SPACE
+    )]
+    pub shared_reservoir: Account<'info, SharedReservoir>,
+    #[account(
+        seeds = [
+            b"reservoir",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Settle shared draw instruction context
+///
+/// The **SettleSharedDraw** context is used by the agency operating a shared reservoir to
+/// record that an agency settled volume it drew beyond its share.
+///
+/// # Fields
+/// * `shared_reservoir` - The PDA account holding the reservoir's draw rights
+/// * `agency` - The agency operating the reservoir
+///
+/// # Seeds for SharedReservoir PDA
+/// * `"shared_reservoir"` - Constant string
+/// * `agency` - Operating agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+#[derive(Accounts)]
+#[instruction(reservoir_key: Pubkey)]
+pub struct SettleSharedDraw<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"shared_reservoir",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub shared_reservoir: Account<'info, SharedReservoir>,
+    pub agency: Signer<'info>,
+}
+
+/// Share a reservoir between agencies, or change their draw rights
+///
+/// Each agency's consumers draw against its share of `period_volume` in every period of
+/// `period_slots`. The operating agency must list itself to draw from the reservoir. Agencies
+/// keeping a right keep what they drew this period and what they owe, and an agency cannot
+/// be removed while it owes volume.
+///
+/// # Arguments
+/// * `ctx` - Context containing the shared reservoir, reservoir and agency accounts
+/// * `reservoir_key` - Unique identifier for the reservoir
+/// * `period_volume` - Volume drawable by all agencies each period, in raw volume units
+/// * `period_slots` - Length of a draw period in slots
+/// * `draw_shares` - Share of each agency, in basis points totalling 10,000
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If period_volume or period_slots is zero
+/// * `CustomError::InvalidDrawRights` - If the shares are malformed
+/// * `CustomError::OutstandingSettlement` - If a removed agency still owes volume
+///
+/// # Returns
+/// * `Ok(())` on successful configuration
+pub fn configure_shared_reservoir(
+    ctx: Context<ConfigureSharedReservoir>,
+    reservoir_key: Pubkey,
+    period_volume: u64,
+    period_slots: u64,
+    draw_shares: Vec<DrawShare>,
+) -> Result<()> {
+    require!(
+        period_volume > 0 && period_slots > 0,
+        CustomError::InvalidAmount
+    );
+    SharedReservoir::validate_draw_shares(&draw_shares)?;
+
+    let shared_reservoir = &mut ctx.accounts.shared_reservoir;
+    if shared_reservoir.operator == Pubkey::default() {
+        shared_reservoir.operator = ctx.accounts.agency.key();
+        shared_reservoir.reservoir_key = reservoir_key;
+        shared_reservoir.period_start_slot = Clock::get()?.slot;
+    }
+    shared_reservoir.set_draw_shares(&draw_shares)?;
+    shared_reservoir.period_volume = period_volume;
+    shared_reservoir.period_slots = period_slots;
+
+    msg!(
+        "Reservoir shared between {} agencies: {} per {} slots.",
+        draw_shares.len(),
+        period_volume,
+        period_slots
+    );
+    Ok(())
+}
+
+/// Record that an agency settled volume it drew beyond its share of a shared reservoir
+///
+/// The operator records settlements agreed between the agencies, whether paid or offset
+/// by water returned. At most the volume the agency owes is cleared.
+///
+/// # Arguments
+/// * `ctx` - Context containing the shared reservoir and agency accounts
+/// * `reservoir_key` - Unique identifier for the reservoir
+/// * `member` - The agency whose obligation is settled
+/// * `volume` - Volume settled, in raw volume units
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If volume is zero
+/// * `CustomError::InvalidSharedReservoir` - If the member holds no draw right
+///
+/// # Returns
+/// * `Ok(())` on successful settlement
+pub fn settle_shared_draw(
+    ctx: Context<SettleSharedDraw>,
+    reservoir_key: Pubkey,
+    member: Pubkey,
+    volume: u64,
+) -> Result<()> {
+    require!(volume > 0, CustomError::InvalidAmount);
+
+    let shared_reservoir = &mut ctx.accounts.shared_reservoir;
+    let settled = shared_reservoir.settle(&member, volume)?;
+    let owed = shared_reservoir
+        .right_of(&member)
+        .map_or(0, |right| right.owed);
+
+    emit!(SharedDrawSettled {
+        reservoir_key,
+        agency: member,
+        settled,
+        owed,
+        slot: Clock::get()?.slot,
+    });
+
+    msg!("Shared draw of {} settled, {} still owed.", settled, owed);
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use crate::{
+    state::{SourceMix, SupplySource},
+    DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+
+/// Publish source mix instruction context
+///
+/// The **PublishSourceMix** context is used by the agency to publish the mix of sources
+/// its water is currently supplied from.
+///
+/// # Fields
+/// * `source_mix` - The PDA account storing the agency's source mix
+/// * `agency` - The agency supplying the water
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for SourceMix PDA
+/// * `"source_mix"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct PublishSourceMix<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"source_mix", agency.key().as_ref()],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + SourceMix::INIT_SPACE
+    )]
+    pub source_mix: Account<'info, SourceMix>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Publish the mix of sources the agency's water is supplied from
+///
+/// Replaces the previous mix. Readings billed with the mix from then on pay the blended
+/// unit cost of the new sources.
+///
+/// # Arguments
+/// * `ctx` - Context containing the source mix and agency accounts
+/// * `sources` - Sources of the supply, with unit costs and shares totalling 10,000 bps
+///
+/// # Errors
+/// * `CustomError::InvalidSourceMix` - If the mix is malformed
+///
+/// # Returns
+/// * `Ok(())` on successful publication
+pub fn publish_source_mix(
+    ctx: Context<PublishSourceMix>,
+    sources: Vec<SupplySource>,
+) -> Result<()> {
+    SourceMix::validate_sources(&sources)?;
+
+    let source_mix = &mut ctx.accounts.source_mix;
+    source_mix.agency = ctx.accounts.agency.key();
+    source_mix.sources = sources;
+    source_mix.published_slot = Clock::get()?.slot;
+
+    msg!(
+        "Source mix published with {} sources, blended unit cost {}.",
+        source_mix.sources.len(),
+        source_mix.blended_unit_cost()
+    );
+    Ok(())
+}
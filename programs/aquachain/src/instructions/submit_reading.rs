@@ -0,0 +1,63 @@
+use crate::{
+    state::{Consumer, SessionKey, SessionScope},
+    CustomError,
+};
+use anchor_lang::prelude::*;
+
+/// Submit reading instruction context
+///
+/// The **SubmitReading** context is used by a consumer's meter device to post its register
+/// value, signing with the session key registered for it.
+///
+/// # Fields
+/// * `consumer` - The consumer account the reading is for
+/// * `session` - The PDA account recording the device's session key
+/// * `device` - The meter device signing with its session key
+///
+/// # Seeds for SessionKey PDA
+/// * `"session_key"` - Constant string
+/// * `consumer` - Consumer's public key
+/// * `device` - Public key held by the meter device
+#[derive(Accounts)]
+pub struct SubmitReading<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [b"session_key", consumer.key().as_ref(), device.key().as_ref()],
+        bump
+    )]
+    pub session: Account<'info, SessionKey>,
+    pub device: Signer<'info>,
+}
+
+/// Record a meter register value signed by a device session key
+///
+/// The reading is stored on the consumer as the latest cumulative register value, for the
+/// agency's billing run to charge the usage since the previous reading. Expired session
+/// keys are rejected.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, session and device accounts
+/// * `reading` - Cumulative register value reported by the meter
+///
+/// # Errors
+/// * `CustomError::SessionExpired` - If the session key has expired or is out of scope
+/// * `CustomError::InvalidMeterReading` - If the reading is below the previous one
+///
+/// # Returns
+/// * `Ok(())` on successful submission
+pub fn submit_reading(ctx: Context<SubmitReading>, reading: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        ctx.accounts
+            .session
+            .permits(SessionScope::SubmitReading, now),
+        CustomError::SessionExpired
+    );
+
+    ctx.accounts.consumer.submit_reading(reading, now)?;
+
+    msg!("Meter reading {} submitted.", reading);
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use super::use_water::UseWater;
+use crate::{
+    state::BillBreakdown,
+    utils::{burn_signed_by_consumer, FixedPoint},
+    CustomError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+
+/// Reconcile estimated billing against an actual meter reading
+///
+/// This function prices the actual volume used since the last actual reading and
+/// compares it with the WTK charged on estimate. The difference is minted to the
+/// consumer if the estimate was too low, or burned if it was too high. WATC tokens
+/// are then burned for the actual usage and the estimate is cleared. Uses the same
+/// accounts as `use_water`.
+///
+/// # Arguments
+/// * `ctx` - Context containing consumer, tariff, reservoir, agency and token accounts
+/// * `tariff_key` - Public key of the tariff assigned to this consumer
+/// * `reservoir_key` - Public key of the reservoir assigned to this consumer
+/// * `actual_volume` - Volume actually used over the estimated periods
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If tariff_key or reservoir_key do not match consumer's assigned values
+/// * `CustomError::NoEstimatePending` - If no estimated usage is awaiting a true-up
+/// * `CustomError::OverPayment` - If a credit exceeds the consumer's outstanding debt
+/// * `CustomError::MathOverflow` - If the converted volume or cost does not fit in a u64
+/// * `CustomError::InvalidLevelForecast` - If the forecast projects another reservoir
+/// * `CustomError::ForecastUnavailable` - If the tariff prices on a forecast that is missing
+///   or does not cover the current slot
+///
+/// # Returns
+/// * `Ok(())` on successful reconciliation
+pub fn true_up(
+    ctx: Context<UseWater>,
+    tariff_key: Pubkey,
+    reservoir_key: Pubkey,
+    actual_volume: u64,
+) -> Result<()> {
+    let consumer = &ctx.accounts.consumer;
+    let tariff = &ctx.accounts.tariff;
+    let reservoir = &ctx.accounts.reservoir;
+    let units = &ctx.accounts.tokens.units;
+
+    require_keys_eq!(
+        tariff_key,
+        consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+    require_keys_eq!(
+        reservoir_key,
+        consumer.assigned_reservoir,
+        CustomError::Unauthorized
+    );
+    require!(consumer.estimated_usage > 0, CustomError::NoEstimatePending);
+
+    let slot = Clock::get()?.slot;
+    let estimated_charge = consumer.estimated_charge;
+    let forecast_level = reservoir.forecast_level(
+        tariff,
+        ctx.accounts.level_forecast.as_deref(),
+        &ctx.accounts.agency.key(),
+        slot,
+    )?;
+    let multiplier = reservoir
+        .scarcity_inputs(tariff.tariff_type, forecast_level)
+        .multiplier(units)?;
+    let actual_charge = BillBreakdown::price(
+        &tariff.charge_bounds(),
+        units,
+        units.to_volume(consumer.block_remaining(tariff, slot))?,
+        units.to_volume(actual_volume)?,
+        FixedPoint::from(tariff.water_rate),
+        FixedPoint::from(consumer.block_rate),
+        multiplier,
+    )?
+    .total;
+
+    if actual_charge > estimated_charge {
+        // Estimate was too low: bill the difference
+        let delta = actual_charge - estimated_charge;
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    to: ctx.accounts.consumer_wtk.to_account_info(),
+                    authority: ctx.accounts.agency.to_account_info(),
+                    mint: ctx.accounts.wtk_mint.to_account_info(),
+                },
+            ),
+            delta,
+        )?;
+        ctx.accounts.consumer.bill_water(delta, slot)?;
+    } else if actual_charge < estimated_charge {
+        // Estimate was too high: credit the difference
+        let delta = estimated_charge - actual_charge;
+        ctx.accounts.consumer.pay_water(delta)?;
+        burn_signed_by_consumer(
+            &ctx.accounts.token_program,
+            ctx.accounts.wtk_mint.to_account_info(),
+            ctx.accounts.consumer_wtk.to_account_info(),
+            ctx.accounts.consumer.to_account_info(),
+            delta,
+        )?;
+    }
+
+    // Deduct WATC tokens for the actual usage
+    let watc_burn = actual_volume.min(ctx.accounts.consumer_watc.amount);
+    if watc_burn > 0 {
+        burn_signed_by_consumer(
+            &ctx.accounts.token_program,
+            ctx.accounts.watc_mint.to_account_info(),
+            ctx.accounts.consumer_watc.to_account_info(),
+            ctx.accounts.consumer.to_account_info(),
+            watc_burn,
+        )?;
+    }
+
+    let consumer = &mut ctx.accounts.consumer;
+    consumer.estimated_usage = 0;
+    consumer.estimated_charge = 0;
+    consumer.record_actual_usage(actual_volume);
+    ctx.accounts.agency_stats.record_usage(actual_volume);
+
+    let co2e = ctx
+        .accounts
+        .reservoir
+        .emissions_for(actual_volume, ctx.accounts.tokens.units.volume_scale);
+    ctx.accounts.consumer.record_emissions(co2e);
+    ctx.accounts.agency_stats.record_emissions(co2e);
+
+    msg!(
+        "Estimated charge of {} trued up to {} for {} units of water.",
+        estimated_charge,
+        actual_charge,
+        actual_volume
+    );
+    Ok(())
+}
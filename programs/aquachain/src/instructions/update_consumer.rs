@@ -1,5 +1,7 @@
 use crate::{
-    state::{Consumer, Reservoir, Tariff},
+    events::ConsumerUpdateConsented,
+    state::{Consumer, Reservoir, Tariff, Tokens},
+    utils::burn_signed_by_consumer,
     CustomError,
 };
 use anchor_lang::prelude::*;
@@ -12,12 +14,16 @@ use anchor_spl::{
 ///
 /// Updates an existing **Consumer** account with new tariff, reservoir, contracted capacity and block rate.
 /// Also handles burning existing WATC tokens and minting new ones based on the updated contracted capacity.
+/// This is the consumer-consented path: the consumer signs, unless the update only changes
+/// its capacity within its standing approval. Reductions the consumer does not consent to
+/// are mandated with `mandate_consumer_capacity` instead.
 ///
 /// # Fields
-/// * `consumer` - The consumer account to be updated (must be signer)
+/// * `consumer` - The consumer account to be updated
 /// * `tariff` - The PDA account containing tariff configuration
 /// * `reservoir` - The PDA account containing reservoir configuration  
 /// * `agency` - The authority that can sign for minting tokens
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
 /// * `consumer_watc` - The consumer's WaterCapacityToken account
 /// * `watc_mint` - The mint for WaterCapacityTokens
 /// * `system_program` - Required for account operations
@@ -33,10 +39,14 @@ use anchor_spl::{
 /// * `"reservoir"` - Constant string
 /// * `agency` - Agency's public key
 /// * `reservoir_key` - Unique identifier for this reservoir
+///
+/// # Seeds for Tokens PDA
+/// * `"tokens"` - Constant string
+/// * `agency` - Agency's public key
 #[derive(Accounts)]
 #[instruction(tariff_key: Pubkey, reservoir_key: Pubkey)]
 pub struct UpdateConsumer<'info> {
-    #[account(mut, signer)]
+    #[account(mut)]
     pub consumer: Account<'info, Consumer>,
     #[account(
         seeds = [
@@ -58,9 +68,11 @@ pub struct UpdateConsumer<'info> {
     pub reservoir: Account<'info, Reservoir>, // Reservoir assigned to this consumer
     #[account(mut)]
     pub agency: Signer<'info>,
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
     #[account(mut, associated_token::mint = watc_mint,  associated_token::authority = consumer)]
     pub consumer_watc: Account<'info, TokenAccount>, // Consumer's WaterCapacityToken account
-    #[account(mut, mint::authority = agency, mint::decimals = 9)]
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
     pub watc_mint: Account<'info, Mint>, // Mint for the WaterCapacityToken
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
@@ -72,6 +84,13 @@ pub struct UpdateConsumer<'info> {
 /// Updates an existing consumer's configuration including contracted capacity and block rate.
 /// Burns any existing WATC tokens and mints new ones based on the updated capacity.
 ///
+/// Without the consumer's signature, only the contracted capacity can change, and only
+/// within the consumer's standing approval for the period. Since burning requires the
+/// consumer's signature, its WATC balance is then topped up by an increase and left as it
+/// is on a decrease, which takes effect at the next capacity refresh. A
+/// `ConsumerUpdateConsented` event is emitted, recording whether the change was signed or
+/// pre-approved.
+///
 /// # Arguments
 /// * `ctx` - Context containing consumer account, tariff, reservoir, agency and token accounts
 /// * `tariff_key` - Public key identifying the tariff to assign
@@ -83,6 +102,8 @@ pub struct UpdateConsumer<'info> {
 /// * `CustomError::Unauthorized` - If tariff_key or reservoir_key don't match accounts
 /// * `CustomError::InvalidCapacity` - If contracted_capacity is 0
 /// * `CustomError::InvalidRate` - If block_rate is 0
+/// * `CustomError::CapacityChangeNotApproved` - If the consumer did not sign and the update
+///   changes the block rate or exceeds its standing approval
 ///
 /// # Returns
 /// * `Ok(())` on successful update
@@ -108,36 +129,68 @@ pub fn update_consumer(
     require!(contracted_capacity > 0, CustomError::InvalidCapacity);
     require!(block_rate > 0, CustomError::InvalidRate);
 
-    consumer.contracted_capacity = contracted_capacity;
-    consumer.block_rate = block_rate;
+    let consented = consumer.to_account_info().is_signer;
+    if consented {
+        consumer.contracted_capacity = contracted_capacity;
+        consumer.block_rate = block_rate;
+        consumer.reset_capacity_approval();
 
-    // Burn any existing WATC tokens from the consumer
-    if ctx.accounts.consumer_watc.amount > 0 {
-        token::burn(
+        // Burn any existing WATC tokens from the consumer
+        if ctx.accounts.consumer_watc.amount > 0 {
+            burn_signed_by_consumer(
+                &ctx.accounts.token_program,
+                ctx.accounts.watc_mint.to_account_info(),
+                ctx.accounts.consumer_watc.to_account_info(),
+                ctx.accounts.consumer.to_account_info(),
+                ctx.accounts.consumer_watc.amount,
+            )?;
+        }
+
+        // Mint WATC tokens to the consumer based on contracted capacity
+        token::mint_to(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                token::Burn {
+                token::MintTo {
+                    to: ctx.accounts.consumer_watc.to_account_info(),
+                    authority: ctx.accounts.agency.to_account_info(),
                     mint: ctx.accounts.watc_mint.to_account_info(),
-                    from: ctx.accounts.consumer_watc.to_account_info(),
-                    authority: ctx.accounts.consumer.to_account_info(),
                 },
             ),
-            ctx.accounts.consumer_watc.amount,
+            contracted_capacity,
         )?;
+    } else {
+        require!(
+            block_rate == consumer.block_rate,
+            CustomError::CapacityChangeNotApproved
+        );
+        consumer.check_capacity_approval(contracted_capacity)?;
+
+        let increase = contracted_capacity.saturating_sub(consumer.contracted_capacity);
+        consumer.contracted_capacity = contracted_capacity;
+
+        // Only top up, as the consumer's WATC cannot be burned without its signature
+        if increase > 0 {
+            token::mint_to(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::MintTo {
+                        to: ctx.accounts.consumer_watc.to_account_info(),
+                        authority: ctx.accounts.agency.to_account_info(),
+                        mint: ctx.accounts.watc_mint.to_account_info(),
+                    },
+                ),
+                increase,
+            )?;
+        }
     }
 
-    // Mint WATC tokens to the consumer based on contracted capacity
-    token::mint_to(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            token::MintTo {
-                to: ctx.accounts.consumer_watc.to_account_info(),
-                authority: ctx.accounts.agency.to_account_info(),
-                mint: ctx.accounts.watc_mint.to_account_info(),
-            },
-        ),
+    emit!(ConsumerUpdateConsented {
+        consumer: ctx.accounts.consumer.key(),
         contracted_capacity,
-    )?;
+        block_rate,
+        pre_approved: !consented,
+        slot: Clock::get()?.slot,
+    });
 
     msg!("Consumer block rate and consumer capacity updated.");
     Ok(())
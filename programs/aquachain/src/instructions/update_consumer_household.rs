@@ -0,0 +1,81 @@
+use crate::{Consumer, CustomError, Role, RoleKind, Tariff};
+use anchor_lang::prelude::*;
+
+/// Update **Consumer** household context
+///
+/// The **Consumer** account's household size is updated by the agency once it has been
+/// verified, e.g. from a census or social services record.
+///
+/// # Fields
+/// * `consumer` - The consumer account to be updated
+/// * `tariff` - The PDA account of the consumer's assigned tariff
+/// * `agency` - The agency the operation is performed for
+/// * `authority` - The agency or a staff key holding the Billing role
+/// * `role` - Role account of the authority, when it is not the agency
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey)]
+pub struct UpdateConsumerHousehold<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            &tariff_key.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>, // Tariff assigned to this consumer
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
+}
+
+/// Set the number of people in a consumer's household
+///
+/// Tariffs with a per-capita allowance size the consumer's lifeline allocation from the
+/// household size. Setting it to zero marks the size as unknown, so the tariff's flat
+/// block threshold applies instead.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, its tariff and the agency signer
+/// * `tariff_key` - Public key of the consumer's assigned tariff
+/// * `household_size` - Number of people in the household
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match consumer's assigned tariff
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_consumer_household(
+    ctx: Context<UpdateConsumerHousehold>,
+    tariff_key: Pubkey,
+    household_size: u16,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let consumer = &mut ctx.accounts.consumer;
+
+    require_keys_eq!(
+        tariff_key,
+        consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+
+    consumer.household_size = household_size;
+
+    msg!("Consumer household size set to {}.", household_size);
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::{utils::clear_stale_bytes, Consumer, CustomError, Role, RoleKind, Tariff};
+use anchor_lang::prelude::*;
+
+/// Update **Consumer** identity context
+///
+/// The **Consumer** account is bound to the agency's off-chain KYC record for the customer,
+/// and rebound whenever that record changes.
+///
+/// # Fields
+/// * `consumer` - The consumer account to be updated
+/// * `tariff` - The PDA account of the consumer's assigned tariff
+/// * `agency` - The agency the operation is performed for
+/// * `authority` - The agency or a staff key holding the Billing role
+/// * `role` - Role account of the authority, when it is not the agency
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey)]
+pub struct UpdateConsumerIdentity<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            &tariff_key.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>, // Tariff assigned to this consumer
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
+}
+
+/// Bind a consumer to the hash of its off-chain identity record
+///
+/// Only the hash and an optional URI of the encrypted record are stored, so no personal
+/// data is exposed on-chain. The binding is replaced as a whole on each call, e.g. when
+/// the customer record is corrected or the account changes hands; an all-zero hash
+/// unbinds the account.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, its tariff and the agency signer
+/// * `tariff_key` - Public key of the consumer's assigned tariff
+/// * `identity_hash` - Hash of the customer's KYC record
+/// * `identity_uri` - Optional URI of the encrypted KYC record
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match consumer's assigned tariff
+/// * `CustomError::InvalidIdentityUri` - If the URI is empty or too long
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_consumer_identity(
+    ctx: Context<UpdateConsumerIdentity>,
+    tariff_key: Pubkey,
+    identity_hash: [u8; 32],
+    identity_uri: Option<String>,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let consumer = &mut ctx.accounts.consumer;
+
+    require_keys_eq!(
+        tariff_key,
+        consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+
+    consumer.set_identity(identity_hash, identity_uri)?;
+    clear_stale_bytes(consumer)?;
+
+    msg!("Consumer identity updated.");
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::{
+    state::{is_valid_geohash, GEOHASH_LEN},
+    Consumer, CustomError, Role, RoleKind, Tariff,
+};
+use anchor_lang::prelude::*;
+
+/// Update **Consumer** location context
+///
+/// The **Consumer** account's meter location is recorded by the agency, e.g. from its GIS
+/// records, so usage reports can be checked against the zone they are billed in.
+///
+/// # Fields
+/// * `consumer` - The consumer account to be updated
+/// * `tariff` - The PDA account of the consumer's assigned tariff
+/// * `agency` - The agency the operation is performed for
+/// * `authority` - The agency or a staff key holding the Billing role
+/// * `role` - Role account of the authority, when it is not the agency
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey)]
+pub struct UpdateConsumerLocation<'info> {
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            &tariff_key.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>, // Tariff assigned to this consumer
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
+}
+
+/// Set the geohash of a consumer's meter location
+///
+/// Batched usage reports for the consumer are only accepted against reservoirs whose zone
+/// contains this location. An empty geohash marks the location as unknown, exempting the
+/// consumer from zone validation.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, its tariff, the agency and authority
+/// * `tariff_key` - Public key of the consumer's assigned tariff
+/// * `geohash` - Geohash of the meter location, padded with zeroes
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match consumer's assigned tariff
+/// * `CustomError::InvalidGeohash` - If the geohash is not a valid zero-padded geohash
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_consumer_location(
+    ctx: Context<UpdateConsumerLocation>,
+    tariff_key: Pubkey,
+    geohash: [u8; GEOHASH_LEN],
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let consumer = &mut ctx.accounts.consumer;
+
+    require_keys_eq!(
+        tariff_key,
+        consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+    require!(is_valid_geohash(&geohash), CustomError::InvalidGeohash);
+
+    consumer.geohash = geohash;
+
+    msg!("Consumer location updated.");
+    Ok(())
+}
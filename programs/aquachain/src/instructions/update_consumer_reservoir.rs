@@ -1,4 +1,4 @@
-use crate::{Consumer, CustomError, Reservoir};
+use crate::{Consumer, CustomError, Reservoir, Role, RoleKind};
 use anchor_lang::prelude::*;
 
 /// Update existing **Consumer** reservoir account context
@@ -10,7 +10,9 @@ use anchor_lang::prelude::*;
 /// * `consumer` - The consumer account to be updated
 /// * `current_reservoir` - The PDA account of the consumer's current assigned reservoir
 /// * `new_reservoir` - The PDA account of the new reservoir to assign
-/// * `agency` - The owner that is authorized to sign operations on its behalf
+/// * `agency` - The agency the operation is performed for
+/// * `authority` - The agency or a staff key holding the Billing role
+/// * `role` - Role account of the authority, when it is not the agency
 /// * `system_program` - Required for account operations
 ///
 /// # Seeds for reservoir PDAs
@@ -23,6 +25,7 @@ pub struct UpdateConsumerReservoir<'info> {
     #[account(mut)]
     pub consumer: Account<'info, Consumer>,
     #[account(
+        mut,
         seeds = [
             b"reservoir",
             agency.key().as_ref(),
@@ -32,6 +35,7 @@ pub struct UpdateConsumerReservoir<'info> {
     )]
     pub current_reservoir: Account<'info, Reservoir>, // Current Reservoir assigned to this consumer
     #[account(
+        mut,
         seeds = [
             b"reservoir",
             agency.key().as_ref(),
@@ -40,8 +44,10 @@ pub struct UpdateConsumerReservoir<'info> {
         bump
     )]
     pub new_reservoir: Account<'info, Reservoir>, // New Reservoir to assign to this consumer
-    #[account(mut)]
-    pub agency: Signer<'info>,
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -56,6 +62,7 @@ pub struct UpdateConsumerReservoir<'info> {
 /// * `new_reservoir_key` - Public key of the new reservoir to assign
 ///
 /// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
 /// * `CustomError::Unauthorized` - If current_reservoir_key doesn't match consumer's assigned reservoir
 /// * `CustomError::Unauthorized` - If new_reservoir_key doesn't match the new reservoir account's key
 ///
@@ -66,6 +73,13 @@ pub fn update_consumer_reservoir(
     current_reservoir_key: Pubkey,
     new_reservoir_key: Pubkey,
 ) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
     let consumer = &mut ctx.accounts.consumer;
     let new_reservoir = &mut ctx.accounts.new_reservoir;
 
@@ -82,6 +96,10 @@ pub fn update_consumer_reservoir(
 
     // Update the consumer's assigned reservoir to the new one
     consumer.assigned_reservoir = new_reservoir_key;
+    if current_reservoir_key != new_reservoir_key {
+        ctx.accounts.current_reservoir.release_consumer();
+        new_reservoir.assign_consumer();
+    }
 
     msg!("Consumer assigned to a new reservoir.");
     Ok(())
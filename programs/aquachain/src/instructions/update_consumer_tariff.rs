@@ -1,4 +1,4 @@
-use crate::{Consumer, CustomError, Tariff};
+use crate::{Consumer, CustomError, Role, RoleKind, Tariff};
 use anchor_lang::prelude::*;
 
 /// Update existing **Consumer** tariff account context
@@ -10,7 +10,9 @@ use anchor_lang::prelude::*;
 /// * `consumer` - The consumer account to be updated
 /// * `current_tariff` - The PDA account of the consumer's current assigned tariff
 /// * `new_tariff` - The PDA account of the new tariff to assign
-/// * `agency` - The owner that is authorized to sign operations on its behalf
+/// * `agency` - The agency the operation is performed for
+/// * `authority` - The agency or a staff key holding the Billing role
+/// * `role` - Role account of the authority, when it is not the agency
 /// * `system_program` - Required for account operations
 ///
 /// # Seeds for tariff PDAs
@@ -23,6 +25,7 @@ pub struct UpdateConsumerTariff<'info> {
     #[account(mut)]
     pub consumer: Account<'info, Consumer>,
     #[account(
+        mut,
         seeds = [
             b"tariff",
             agency.key().as_ref(),
@@ -32,6 +35,7 @@ pub struct UpdateConsumerTariff<'info> {
     )]
     pub current_tariff: Account<'info, Tariff>, // Current Tariff assigned to this consumer
     #[account(
+        mut,
         seeds = [
             b"tariff",
             agency.key().as_ref(),
@@ -40,8 +44,10 @@ pub struct UpdateConsumerTariff<'info> {
         bump
     )]
     pub new_tariff: Account<'info, Tariff>, // New Tariff to assign to this consumer
-    #[account(mut)]
-    pub agency: Signer<'info>,
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -56,6 +62,7 @@ pub struct UpdateConsumerTariff<'info> {
 /// * `new_tariff_key` - Public key of the new tariff to assign
 ///
 /// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
 /// * `CustomError::Unauthorized` - If current_tariff_key doesn't match consumer's assigned tariff
 /// * `CustomError::Unauthorized` - If new_tariff_key doesn't match the new tariff account's key
 ///
@@ -66,8 +73,15 @@ pub fn update_consumer_tariff(
     current_tariff_key: Pubkey,
     new_tariff_key: Pubkey,
 ) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
     let consumer = &mut ctx.accounts.consumer;
-    let new_tariff = &ctx.accounts.new_tariff;
+    let new_tariff = &mut ctx.accounts.new_tariff;
 
     require_keys_eq!(
         current_tariff_key,
@@ -82,6 +96,10 @@ pub fn update_consumer_tariff(
 
     // Update the consumer's assigned tariff to the new one
     consumer.assigned_tariff = new_tariff_key;
+    if current_tariff_key != new_tariff_key {
+        ctx.accounts.current_tariff.release_consumer();
+        new_tariff.assign_consumer();
+    }
 
     msg!("Consumer assigned to a new tariff.");
     Ok(())
@@ -0,0 +1,43 @@
+use crate::{state::FxOracle, CustomError};
+use anchor_lang::prelude::*;
+
+/// Update existing **FxOracle** rate context
+///
+/// The rate can only be posted by the oracle authority registered on the account.
+///
+/// # Fields
+/// * `fx_oracle` - The oracle account storing the exchange rate
+/// * `authority` - The oracle authority posting the rate
+#[derive(Accounts)]
+pub struct UpdateFxRate<'info> {
+    #[account(mut, has_one = authority @ CustomError::Unauthorized)]
+    pub fx_oracle: Account<'info, FxOracle>,
+    pub authority: Signer<'info>,
+}
+
+/// Post a new exchange rate
+///
+/// This function records the latest rate between the local currency and the settlement
+/// mint, along with the current slot used for staleness checks at settlement.
+///
+/// # Arguments
+/// * `ctx` - Context containing the oracle account and its authority
+/// * `rate` - Settlement base units per whole unit of local currency (must be > 0)
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the signer is not the oracle authority
+/// * `CustomError::InvalidExchangeRate` - If rate is 0
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_fx_rate(ctx: Context<UpdateFxRate>, rate: u64) -> Result<()> {
+    let fx_oracle = &mut ctx.accounts.fx_oracle;
+
+    require!(rate > 0, CustomError::InvalidExchangeRate);
+
+    fx_oracle.rate = rate;
+    fx_oracle.last_updated_slot = Clock::get()?.slot;
+
+    msg!("FX rate updated to {}.", rate);
+    Ok(())
+}
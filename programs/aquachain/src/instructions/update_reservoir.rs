@@ -1,4 +1,9 @@
-use crate::{state::Reservoir, CustomError};
+use crate::{
+    events::{ReservoirAlert, ReservoirConfigUpdated, ReservoirLevelUpdated, ReservoirLow},
+    state::{is_valid_geohash, Reservoir, Role, RoleKind, ScarcityBand, GEOHASH_LEN},
+    utils::{clear_stale_bytes, is_valid_bps},
+    CustomError,
+};
 use anchor_lang::prelude::*;
 
 /// Update existing **Reservoir** account context
@@ -8,7 +13,10 @@ use anchor_lang::prelude::*;
 ///
 /// # Fields
 /// * `reservoir` - The PDA account that stores reservoir levels and configuration
-/// * `agency` - The owner that is authorized to sign operations on its behalf
+/// * `agency` - The agency the operation is performed for
+/// * `authority` - The agency, a staff key holding the MeterOperator role, or the reservoir's
+///   telemetry key when reporting levels
+/// * `role` - Role account of the authority, when it is not the agency
 /// * `system_program` - Required for account operations
 ///
 /// # Seeds
@@ -28,53 +36,609 @@ pub struct UpdateReservoir<'info> {
         bump
     )]
     pub reservoir: Account<'info, Reservoir>,
-    #[account(mut)]
-    pub agency: Signer<'info>,
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
     pub system_program: Program<'info, System>,
 }
 
-/// Update reservoir levels and capacity
+/// Record the current level of a reservoir
 ///
-/// This function updates the current level and capacity for an existing Reservoir account.
-/// The account must be a PDA derived from the agency's public key and the provided
-/// reservoir key.
+/// This function records a new level reading, typically pushed by a telemetry feed, for an
+/// existing Reservoir account. The account must be a PDA derived from the agency's public
+/// key and the provided reservoir key. The new level is also folded into the reservoir's
+/// smoothed level. Levels reported by the reservoir's telemetry key must stay within the
+/// plausible rate of change since the previous report. Changes beyond the reservoir's
+/// hourly limit must be co-signed by the agency.
 ///
 /// # Arguments
-/// * `ctx` - Context containing the reservoir account, agency signer and system program
+/// * `ctx` - Context containing the reservoir account, agency and authority
 /// * `reservoir_key` - Unique public key identifier for this reservoir
-/// * `current_level` - New current water level to set (must be greater than 0 and less than capacity)
-/// * `capacity` - New maximum capacity to set (must be greater than 0)
+/// * `current_level` - New current water level to set (must be greater than 0 and at most capacity)
 ///
 /// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
 /// * `CustomError::Unauthorized` - If reservoir_key doesn't match the account's key
 /// * `CustomError::InvalidReservoirLevel` - If current_level is 0 or greater than capacity
-/// * `CustomError::InvalidReservoirCapacity` - If capacity is 0
+/// * `CustomError::ImplausibleLevelChange` - If the telemetry key reports a level changing
+///   faster than `max_level_rate`, or the level changed by more than the hourly limit
+///   without the agency's signature
 ///
 /// # Returns
 /// * `Ok(())` on successful update
-pub fn update_reservoir(
+pub fn update_reservoir_level(
     ctx: Context<UpdateReservoir>,
     reservoir_key: Pubkey,
     current_level: u64,
-    capacity: u64,
 ) -> Result<()> {
+    let clock = Clock::get()?;
+    let slot = clock.slot;
     let reservoir = &mut ctx.accounts.reservoir;
 
+    // The telemetry key may only report physically plausible levels
+    if reservoir.telemetry_authority == Some(ctx.accounts.authority.key()) {
+        require!(
+            reservoir.is_plausible_level(current_level, slot),
+            CustomError::ImplausibleLevelChange
+        );
+    } else {
+        Role::authorize(
+            &ctx.accounts.agency.key(),
+            &ctx.accounts.authority.key(),
+            ctx.accounts.role.as_deref(),
+            RoleKind::MeterOperator,
+        )?;
+    }
+
     require_keys_eq!(
         reservoir_key,
         reservoir.reservoir_key,
         CustomError::Unauthorized
     );
+    apply_reservoir_level(
+        reservoir,
+        current_level,
+        &clock,
+        ctx.accounts.agency.is_signer,
+    )?;
+
+    msg!("Reservoir level updated.");
+    Ok(())
+}
 
+/// Records a new current level on a reservoir and emits the level events
+///
+/// A `ReservoirAlert` is emitted when the new level crosses one of the reservoir's dam
+/// safety thresholds.
+/// Shared by direct level reports and sensor network aggregation, so both paths apply the
+/// same bounds and hourly change limit.
+///
+/// # Errors
+/// * `CustomError::InvalidReservoirLevel` - If current_level is 0 or greater than capacity
+/// * `CustomError::ImplausibleLevelChange` - If the level changed by more than the hourly
+///   limit without the agency's signature
+pub(crate) fn apply_reservoir_level(
+    reservoir: &mut Reservoir,
+    current_level: u64,
+    clock: &Clock,
+    agency_signed: bool,
+) -> Result<()> {
+    let slot = clock.slot;
     require!(
-        current_level > 0 && current_level <= capacity,
+        current_level > 0 && current_level <= reservoir.capacity,
         CustomError::InvalidReservoirLevel
     );
-    require!(capacity > 0, CustomError::InvalidReservoirCapacity);
+    // Catch fat-fingered or spoofed readings before they reach seasonal pricing
+    require!(
+        agency_signed || reservoir.is_within_change_limit(current_level, clock.unix_timestamp),
+        CustomError::ImplausibleLevelChange
+    );
 
+    let previous_level = reservoir.current_level;
     reservoir.current_level = current_level;
+    reservoir.level_updated_slot = slot;
+    reservoir.level_updated_at = clock.unix_timestamp;
+    reservoir.record_level(current_level);
+
+    emit!(ReservoirLevelUpdated {
+        reservoir_key: reservoir.reservoir_key,
+        current_level,
+        smoothed_level: reservoir.smoothed_level,
+        slot,
+    });
+    if reservoir.is_low() {
+        emit!(ReservoirLow {
+            reservoir_key: reservoir.reservoir_key,
+            current_level,
+            capacity: reservoir.capacity,
+            slot,
+        });
+    }
+    if let Some((kind, threshold)) = reservoir.crossed_alert(previous_level) {
+        emit!(ReservoirAlert {
+            reservoir_key: reservoir.reservoir_key,
+            kind,
+            threshold,
+            current_level,
+            slot,
+        });
+    }
+    Ok(())
+}
+
+/// Set the capacity of a reservoir
+///
+/// Capacity scales the level ratio seasonal tariffs are priced on, so it can only be
+/// changed by the agency itself, never by a delegated telemetry or staff key.
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir account, agency and authority
+/// * `reservoir_key` - Unique public key identifier for this reservoir
+/// * `capacity` - New maximum capacity to set (must be at least the current level)
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the signer is not the agency, or reservoir_key doesn't
+///   match the account's key
+/// * `CustomError::InvalidReservoirCapacity` - If capacity is 0 or below the current level
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_reservoir_config(
+    ctx: Context<UpdateReservoir>,
+    reservoir_key: Pubkey,
+    capacity: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        ctx.accounts.agency.key(),
+        CustomError::Unauthorized
+    );
+
+    let reservoir = &mut ctx.accounts.reservoir;
+
+    require_keys_eq!(
+        reservoir_key,
+        reservoir.reservoir_key,
+        CustomError::Unauthorized
+    );
+    require!(
+        capacity > 0 && capacity >= reservoir.current_level,
+        CustomError::InvalidReservoirCapacity
+    );
+
     reservoir.capacity = capacity;
 
-    msg!("Reservoir levels updated.");
+    emit!(ReservoirConfigUpdated {
+        reservoir_key,
+        capacity,
+        slot: Clock::get()?.slot,
+    });
+
+    msg!("Reservoir capacity set to {}.", capacity);
+    Ok(())
+}
+
+/// Delegate level reports of a reservoir to a telemetry key
+///
+/// A SCADA relay holding the telemetry key can report levels with `update_reservoir_level`
+/// without holding the agency wallet. It cannot change anything else on the reservoir.
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir account, agency and authority
+/// * `reservoir_key` - Unique public key identifier for this reservoir
+/// * `telemetry_authority` - Key allowed to report levels, or `None` to revoke it
+/// * `max_level_rate` - Largest level change per slot accepted from the key; 0 is unbounded
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the signer is not the agency, or reservoir_key doesn't
+///   match the account's key
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_reservoir_telemetry(
+    ctx: Context<UpdateReservoir>,
+    reservoir_key: Pubkey,
+    telemetry_authority: Option<Pubkey>,
+    max_level_rate: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        ctx.accounts.agency.key(),
+        CustomError::Unauthorized
+    );
+
+    let reservoir = &mut ctx.accounts.reservoir;
+
+    require_keys_eq!(
+        reservoir_key,
+        reservoir.reservoir_key,
+        CustomError::Unauthorized
+    );
+
+    reservoir.telemetry_authority = telemetry_authority;
+    reservoir.max_level_rate = max_level_rate;
+    clear_stale_bytes(reservoir)?;
+
+    msg!(
+        "Reservoir telemetry key {}.",
+        if telemetry_authority.is_some() {
+            "set"
+        } else {
+            "revoked"
+        }
+    );
+    Ok(())
+}
+
+/// Limit how fast the level of a reservoir may change without the agency's signature
+///
+/// Level reports moving by more than `max_change_bps_per_hour` of capacity per hour since
+/// the previous report are refused unless the agency co-signs them.
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir account, agency and authority
+/// * `reservoir_key` - Unique public key identifier for this reservoir
+/// * `max_change_bps_per_hour` - Largest change per hour, in basis points of capacity; 0 is
+///   unlimited
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the signer is not the agency, or reservoir_key doesn't
+///   match the account's key
+/// * `CustomError::InvalidShare` - If max_change_bps_per_hour exceeds 10,000
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_reservoir_change_limit(
+    ctx: Context<UpdateReservoir>,
+    reservoir_key: Pubkey,
+    max_change_bps_per_hour: u16,
+) -> Result<()> {
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        ctx.accounts.agency.key(),
+        CustomError::Unauthorized
+    );
+
+    let reservoir = &mut ctx.accounts.reservoir;
+
+    require_keys_eq!(
+        reservoir_key,
+        reservoir.reservoir_key,
+        CustomError::Unauthorized
+    );
+    require!(
+        is_valid_bps(max_change_bps_per_hour),
+        CustomError::InvalidShare
+    );
+
+    reservoir.max_change_bps_per_hour = max_change_bps_per_hour;
+
+    msg!(
+        "Reservoir level changes limited to {} bps per hour.",
+        max_change_bps_per_hour
+    );
+    Ok(())
+}
+
+/// Set the dam safety alert levels of a reservoir
+///
+/// Level updates crossing either threshold emit a `ReservoirAlert` event that downstream
+/// alerting can subscribe to.
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir account, agency and authority
+/// * `reservoir_key` - Unique public key identifier for this reservoir
+/// * `alert_level_high` - Level at or above which a high alert is raised; 0 disables it
+/// * `alert_level_low` - Level at or below which a low alert is raised; 0 disables it
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the signer is not the agency, or reservoir_key doesn't
+///   match the account's key
+/// * `CustomError::InvalidAlertLevels` - If the high alert level exceeds capacity, or the low
+///   alert level is not below it
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_reservoir_alerts(
+    ctx: Context<UpdateReservoir>,
+    reservoir_key: Pubkey,
+    alert_level_high: u64,
+    alert_level_low: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        ctx.accounts.agency.key(),
+        CustomError::Unauthorized
+    );
+
+    let reservoir = &mut ctx.accounts.reservoir;
+
+    require_keys_eq!(
+        reservoir_key,
+        reservoir.reservoir_key,
+        CustomError::Unauthorized
+    );
+    require!(
+        alert_level_high <= reservoir.capacity
+            && (alert_level_high == 0 || alert_level_low < alert_level_high),
+        CustomError::InvalidAlertLevels
+    );
+
+    reservoir.alert_level_high = alert_level_high;
+    reservoir.alert_level_low = alert_level_low;
+
+    msg!(
+        "Reservoir alert levels set to {} and {}.",
+        alert_level_low,
+        alert_level_high
+    );
+    Ok(())
+}
+
+/// Set the geohash prefix of the zone a reservoir serves
+///
+/// Batched usage reports billed against the reservoir are rejected for consumers located
+/// outside the zone, catching readings misrouted by a mis-configured head-end. An empty
+/// geohash lifts the restriction.
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir account, agency and authority
+/// * `reservoir_key` - Unique public key identifier for this reservoir
+/// * `zone_geohash` - Geohash prefix of the zone, padded with zeroes
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If reservoir_key doesn't match the account's key
+/// * `CustomError::InvalidGeohash` - If the geohash is not a valid zero-padded geohash
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_reservoir_zone(
+    ctx: Context<UpdateReservoir>,
+    reservoir_key: Pubkey,
+    zone_geohash: [u8; GEOHASH_LEN],
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::MeterOperator,
+    )?;
+
+    let reservoir = &mut ctx.accounts.reservoir;
+
+    require_keys_eq!(
+        reservoir_key,
+        reservoir.reservoir_key,
+        CustomError::Unauthorized
+    );
+    require!(is_valid_geohash(&zone_geohash), CustomError::InvalidGeohash);
+
+    reservoir.zone_geohash = zone_geohash;
+
+    msg!("Reservoir zone updated.");
+    Ok(())
+}
+
+/// Set the river basin a reservoir lies in
+///
+/// Conveyance between reservoirs in different basins requires a regulator's approval.
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir account, agency and authority
+/// * `reservoir_key` - Unique public key identifier for this reservoir
+/// * `basin_id` - Identifier of the basin, or 0 to leave it unassigned
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If reservoir_key doesn't match the account's key
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_reservoir_basin(
+    ctx: Context<UpdateReservoir>,
+    reservoir_key: Pubkey,
+    basin_id: u32,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::MeterOperator,
+    )?;
+
+    let reservoir = &mut ctx.accounts.reservoir;
+
+    require_keys_eq!(
+        reservoir_key,
+        reservoir.reservoir_key,
+        CustomError::Unauthorized
+    );
+
+    reservoir.basin_id = basin_id;
+
+    msg!("Reservoir basin set to {}.", basin_id);
+    Ok(())
+}
+
+/// Set the pumping intensity of the zone a reservoir serves
+///
+/// When the agency's energy oracle is passed to `use_water`, the intensity is multiplied
+/// by the posted electricity price to add an energy surcharge to the bill.
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir account, agency and authority
+/// * `reservoir_key` - Unique public key identifier for this reservoir
+/// * `pumping_intensity` - kWh per billed unit of volume, with three implied decimals
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If reservoir_key doesn't match the account's key
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_reservoir_pumping(
+    ctx: Context<UpdateReservoir>,
+    reservoir_key: Pubkey,
+    pumping_intensity: u64,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::MeterOperator,
+    )?;
+
+    let reservoir = &mut ctx.accounts.reservoir;
+
+    require_keys_eq!(
+        reservoir_key,
+        reservoir.reservoir_key,
+        CustomError::Unauthorized
+    );
+
+    reservoir.pumping_intensity = pumping_intensity;
+
+    msg!("Reservoir pumping intensity set to {}.", pumping_intensity);
+    Ok(())
+}
+
+/// Set the emissions factor of the zone a reservoir serves
+///
+/// Emissions are attributed to consumers and the agency as water is delivered, for carbon
+/// disclosure. They do not affect billing.
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir account, agency and authority
+/// * `reservoir_key` - Unique public key identifier for this reservoir
+/// * `emissions_factor` - Grams CO2e emitted per billed unit of water delivered
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If reservoir_key doesn't match the account's key
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_reservoir_emissions(
+    ctx: Context<UpdateReservoir>,
+    reservoir_key: Pubkey,
+    emissions_factor: u64,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::MeterOperator,
+    )?;
+
+    let reservoir = &mut ctx.accounts.reservoir;
+
+    require_keys_eq!(
+        reservoir_key,
+        reservoir.reservoir_key,
+        CustomError::Unauthorized
+    );
+
+    reservoir.emissions_factor = emissions_factor;
+
+    msg!(
+        "Reservoir emissions factor set to {} g CO2e.",
+        emissions_factor
+    );
+    Ok(())
+}
+
+/// Set the scarcity curve pricing usage above the block threshold of seasonal tariffs
+///
+/// Each band sets the multiplier applied to the block rate while the reservoir level is at
+/// or below its level ratio, replacing the linear adjustment to the level. An empty curve
+/// restores the linear adjustment.
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir account, agency and authority
+/// * `reservoir_key` - Unique public key identifier for this reservoir
+/// * `scarcity_curve` - Bands in ascending order of level, the last covering a full reservoir
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If reservoir_key doesn't match the account's key
+/// * `CustomError::InvalidScarcityCurve` - If the curve is malformed
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_reservoir_scarcity_curve(
+    ctx: Context<UpdateReservoir>,
+    reservoir_key: Pubkey,
+    scarcity_curve: Vec<ScarcityBand>,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let reservoir = &mut ctx.accounts.reservoir;
+
+    require_keys_eq!(
+        reservoir_key,
+        reservoir.reservoir_key,
+        CustomError::Unauthorized
+    );
+    Reservoir::validate_scarcity_curve(&scarcity_curve)?;
+
+    msg!(
+        "Reservoir scarcity curve set with {} bands.",
+        scarcity_curve.len()
+    );
+    reservoir.scarcity_curve = scarcity_curve;
+    clear_stale_bytes(reservoir)?;
+    Ok(())
+}
+
+/// Set how strongly reported levels are smoothed before pricing seasonal tariffs
+///
+/// Each level reported with `update_reservoir_level` moves the smoothed level by `smoothing_bps`
+/// of the gap, so a single stormy week does not swing bills between cycles. The smoothed
+/// level restarts from the current level.
+///
+/// # Arguments
+/// * `ctx` - Context containing the reservoir account, agency and authority
+/// * `reservoir_key` - Unique public key identifier for this reservoir
+/// * `smoothing_bps` - Weight of each reported level, in basis points; 0 disables smoothing
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If reservoir_key doesn't match the account's key
+/// * `CustomError::InvalidShare` - If smoothing_bps exceeds 10,000
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_reservoir_smoothing(
+    ctx: Context<UpdateReservoir>,
+    reservoir_key: Pubkey,
+    smoothing_bps: u16,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let reservoir = &mut ctx.accounts.reservoir;
+
+    require_keys_eq!(
+        reservoir_key,
+        reservoir.reservoir_key,
+        CustomError::Unauthorized
+    );
+    require!(is_valid_bps(smoothing_bps), CustomError::InvalidShare);
+
+    reservoir.smoothing_bps = smoothing_bps;
+    reservoir.smoothed_level = reservoir.current_level;
+
+    msg!("Reservoir level smoothing set to {} bps.", smoothing_bps);
     Ok(())
 }
@@ -1,6 +1,10 @@
 use crate::{
-    state::{Tariff, TariffType},
-    CustomError,
+    state::{
+        is_valid_currency_code, RegulatorAuthority, Role, RoleKind, Tariff, TariffHistory,
+        TariffType,
+    },
+    utils::is_valid_bps,
+    CustomError, DISCRIMINATOR,
 };
 use anchor_lang::prelude::*;
 
@@ -11,13 +15,27 @@ use anchor_lang::prelude::*;
 ///
 /// # Fields
 /// * `tariff` - The PDA account that stores tariff rates and configuration
-/// * `agency` - The owner that is authorized to sign operations on its behalf
+/// * `tariff_history` - The PDA account recording superseded versions of the tariff
+/// * `agency` - The agency the operation is performed for
+/// * `authority` - The agency or a staff key holding the Billing role
+/// * `role` - Role account of the authority, when it is not the agency
+/// * `regulator_authority` - The PDA account recording the agency's regulator, when the
+///   regulator sets the tariff's bounds
 /// * `system_program` - Required for account operations
 ///
 /// # Seeds
 /// * `"tariff"` - Constant string
 /// * `agency` - Agency's public key
 /// * `tariff_key` - Unique identifier for this tariff
+///
+/// # Seeds for TariffHistory PDA
+/// * `"tariff_history"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for this tariff
+///
+/// # Seeds for RegulatorAuthority PDA
+/// * `"regulator_authority"` - Constant string
+/// * `agency` - Agency's public key
 #[derive(Accounts)]
 #[instruction(tariff_key: Pubkey)]
 pub struct UpdateTariff<'info> {
@@ -31,8 +49,25 @@ pub struct UpdateTariff<'info> {
         bump
     )]
     pub tariff: Account<'info, Tariff>,
+    #[account(
+        init_if_needed,
+        seeds = [
+            b"tariff_history",
+            agency.key().as_ref(),
+            &tariff_key.as_ref()
+        ],
+        bump,
+        payer = authority,
+        space = DISCRIMINATOR + TariffHistory::INIT_SPACE
+    )]
+    pub tariff_history: Account<'info, TariffHistory>,
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
     #[account(mut)]
-    pub agency: Signer<'info>,
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
+    #[account(seeds = [b"regulator_authority", agency.key().as_ref()], bump)]
+    pub regulator_authority: Option<Account<'info, RegulatorAuthority>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -40,7 +75,7 @@ pub struct UpdateTariff<'info> {
 ///
 /// This function updates the water and waste rates for an existing Tariff account.
 /// The account must be a PDA derived from the agency's public key and the provided
-/// tariff key.
+/// tariff key. The replaced rates are recorded in the tariff's history.
 ///
 /// # Arguments
 /// * `ctx` - Context containing the tariff account, agency signer and system program
@@ -49,6 +84,7 @@ pub struct UpdateTariff<'info> {
 /// * `waste_rate` - New waste rate to set (must be greater than 0)
 ///
 /// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
 /// * `CustomError::Unauthorized` - If tariff_key doesn't match the account's key
 /// * `CustomError::InvalidRate` - If water_rate or waste_rate is 0
 ///
@@ -60,6 +96,13 @@ pub fn update_tariff_rates(
     water_rate: u64,
     waste_rate: u64,
 ) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
     let tariff = &mut ctx.accounts.tariff;
 
     require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
@@ -67,8 +110,11 @@ pub fn update_tariff_rates(
     require!(water_rate > 0, CustomError::InvalidRate);
     require!(waste_rate > 0, CustomError::InvalidRate);
 
+    record_superseded_version(&mut ctx.accounts.tariff_history, tariff)?;
+
     tariff.water_rate = water_rate;
     tariff.waste_rate = waste_rate;
+    tariff.activated_slot = Clock::get()?.slot;
 
     msg!("Rates updated.");
     Ok(())
@@ -78,7 +124,7 @@ pub fn update_tariff_rates(
 ///
 /// This function updates the tariff type (e.g. UniformIBT, SeasonalIBT) for an existing
 /// Tariff account. The account must be a PDA derived from the agency's public key and
-/// the provided tariff key.
+/// the provided tariff key. The replaced tariff type is recorded in the tariff's history.
 ///
 /// # Arguments
 /// * `ctx` - Context containing the tariff account, agency signer and system program
@@ -86,6 +132,7 @@ pub fn update_tariff_rates(
 /// * `tariff_type` - New tariff type to set
 ///
 /// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
 /// * `CustomError::Unauthorized` - If tariff_key doesn't match the account's key
 ///
 /// # Returns
@@ -95,12 +142,461 @@ pub fn update_tariff_type(
     tariff_key: Pubkey,
     tariff_type: TariffType,
 ) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
     let tariff = &mut ctx.accounts.tariff;
 
     require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
 
+    record_superseded_version(&mut ctx.accounts.tariff_history, tariff)?;
+
     tariff.tariff_type = tariff_type;
+    tariff.activated_slot = Clock::get()?.slot;
 
     msg!("Tariff type updated.");
     Ok(())
 }
+
+/// Update the local currency an existing tariff is denominated in
+///
+/// This function sets the ISO 4217 currency code of an existing Tariff account.
+/// Debts charged under the tariff are converted into the settlement mint using the
+/// FX oracle registered for this currency when they are settled.
+///
+/// # Arguments
+/// * `ctx` - Context containing the tariff account, agency signer and system program
+/// * `tariff_key` - Unique public key identifier for this tariff
+/// * `currency_code` - ISO 4217 code of the local currency (e.g. `TTD`)
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match the account's key
+/// * `CustomError::InvalidCurrencyCode` - If currency_code is not three uppercase letters
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_tariff_currency(
+    ctx: Context<UpdateTariff>,
+    tariff_key: Pubkey,
+    currency_code: [u8; 3],
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let tariff = &mut ctx.accounts.tariff;
+
+    require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
+    require!(
+        is_valid_currency_code(&currency_code),
+        CustomError::InvalidCurrencyCode
+    );
+
+    tariff.currency_code = currency_code;
+
+    msg!("Tariff currency updated.");
+    Ok(())
+}
+
+/// Update the grace period of an existing tariff account
+///
+/// This function sets the minimum number of slots a consumer's debt must remain unpaid
+/// before punitive action such as late fees, guarantor claims or suspension, so agencies
+/// can comply with jurisdictions that mandate a notice period.
+///
+/// # Arguments
+/// * `ctx` - Context containing the tariff account, agency signer and system program
+/// * `tariff_key` - Unique public key identifier for this tariff
+/// * `grace_period_slots` - Minimum number of slots before punitive action (0 disables)
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match the account's key
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_tariff_grace_period(
+    ctx: Context<UpdateTariff>,
+    tariff_key: Pubkey,
+    grace_period_slots: u64,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let tariff = &mut ctx.accounts.tariff;
+
+    require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
+
+    tariff.grace_period_slots = grace_period_slots;
+
+    msg!("Tariff grace period set to {} slots.", grace_period_slots);
+    Ok(())
+}
+
+/// Update the billing period of an existing tariff account
+///
+/// This function sets how many slots make up a billing period for consumers on this
+/// tariff. Once a period has elapsed, `refresh_capacity` replenishes their WATC up to
+/// their contracted capacity.
+///
+/// # Arguments
+/// * `ctx` - Context containing the tariff account, agency signer and system program
+/// * `tariff_key` - Unique public key identifier for this tariff
+/// * `billing_period_slots` - Number of slots in a billing period (0 disables refreshes)
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match the account's key
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_tariff_billing_period(
+    ctx: Context<UpdateTariff>,
+    tariff_key: Pubkey,
+    billing_period_slots: u64,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let tariff = &mut ctx.accounts.tariff;
+
+    require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
+
+    tariff.billing_period_slots = billing_period_slots;
+
+    msg!(
+        "Tariff billing period set to {} slots.",
+        billing_period_slots
+    );
+    Ok(())
+}
+
+/// Update the capacity rollover policy of an existing tariff account
+///
+/// This function sets how much unused WATC consumers on this tariff carry into the next
+/// billing period when `refresh_capacity` runs. Zero makes unused capacity expire, while
+/// 10,000 carries all of it over.
+///
+/// # Arguments
+/// * `ctx` - Context containing the tariff account, agency signer and system program
+/// * `tariff_key` - Unique public key identifier for this tariff
+/// * `capacity_rollover_bps` - Share of unused capacity carried over, in basis points
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match the account's key
+/// * `CustomError::InvalidShare` - If capacity_rollover_bps exceeds 10,000
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_tariff_capacity_rollover(
+    ctx: Context<UpdateTariff>,
+    tariff_key: Pubkey,
+    capacity_rollover_bps: u16,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let tariff = &mut ctx.accounts.tariff;
+
+    require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
+    require!(
+        is_valid_bps(capacity_rollover_bps),
+        CustomError::InvalidShare
+    );
+
+    tariff.capacity_rollover_bps = capacity_rollover_bps;
+
+    msg!(
+        "Tariff capacity rollover set to {} basis points.",
+        capacity_rollover_bps
+    );
+    Ok(())
+}
+
+/// Update the block threshold of an existing tariff account
+///
+/// This function sets the volume each consumer on this tariff can use per billing period
+/// at the water rate before usage is charged at the block rate. Setting it to zero falls
+/// back to each consumer's contracted capacity.
+///
+/// # Arguments
+/// * `ctx` - Context containing the tariff account, agency signer and system program
+/// * `tariff_key` - Unique public key identifier for this tariff
+/// * `block_threshold` - Raw volume per billing period charged at the water rate
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match the account's key
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_tariff_block_threshold(
+    ctx: Context<UpdateTariff>,
+    tariff_key: Pubkey,
+    block_threshold: u64,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let tariff = &mut ctx.accounts.tariff;
+
+    require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
+
+    tariff.block_threshold = block_threshold;
+
+    msg!("Tariff block threshold set to {}.", block_threshold);
+    Ok(())
+}
+
+/// Update the per-capita lifeline allowance of an existing tariff account
+///
+/// This function sets the volume each household member can use per billing period at
+/// the water rate. Consumers with a known household size are allocated
+/// `per_capita_allowance * household_size` before usage is charged at the block rate.
+///
+/// # Arguments
+/// * `ctx` - Context containing the tariff account, agency signer and system program
+/// * `tariff_key` - Unique public key identifier for this tariff
+/// * `per_capita_allowance` - Raw volume per person per billing period (0 disables it)
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match the account's key
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_tariff_per_capita_allowance(
+    ctx: Context<UpdateTariff>,
+    tariff_key: Pubkey,
+    per_capita_allowance: u64,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let tariff = &mut ctx.accounts.tariff;
+
+    require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
+
+    tariff.per_capita_allowance = per_capita_allowance;
+
+    msg!(
+        "Tariff per-capita allowance set to {}.",
+        per_capita_allowance
+    );
+    Ok(())
+}
+
+/// Update the conservation certificate policy of an existing tariff account
+///
+/// This function sets the share of the block threshold a consumer's period usage must stay
+/// below to count as conserving, and the number of consecutive conserving periods that
+/// earn a non-transferable conservation certificate. Setting `conservation_periods` to 0
+/// disables certificates for the tariff.
+///
+/// # Arguments
+/// * `ctx` - Context containing the tariff account, agency signer and system program
+/// * `tariff_key` - Unique public key identifier for this tariff
+/// * `conservation_baseline_bps` - Share of the block threshold, in basis points
+/// * `conservation_periods` - Consecutive conserving periods per certificate
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match the account's key
+/// * `CustomError::InvalidShare` - If conservation_baseline_bps exceeds 10,000
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_tariff_conservation(
+    ctx: Context<UpdateTariff>,
+    tariff_key: Pubkey,
+    conservation_baseline_bps: u16,
+    conservation_periods: u16,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let tariff = &mut ctx.accounts.tariff;
+
+    require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
+    require!(
+        is_valid_bps(conservation_baseline_bps),
+        CustomError::InvalidShare
+    );
+
+    tariff.conservation_baseline_bps = conservation_baseline_bps;
+    tariff.conservation_periods = conservation_periods;
+
+    msg!(
+        "Tariff conservation baseline set to {} basis points over {} periods.",
+        conservation_baseline_bps,
+        conservation_periods
+    );
+    Ok(())
+}
+
+/// Update the bulk delivery rate of an existing tariff account
+///
+/// This function sets the rate charged per unit of water delivered by tanker to consumers
+/// on this tariff. Setting `bulk_rate` to 0 stops bulk deliveries under the tariff.
+///
+/// # Arguments
+/// * `ctx` - Context containing the tariff account, agency signer and system program
+/// * `tariff_key` - Unique public key identifier for this tariff
+/// * `bulk_rate` - Rate charged per unit of water delivered in bulk
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match the account's key
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_tariff_bulk_rate(
+    ctx: Context<UpdateTariff>,
+    tariff_key: Pubkey,
+    bulk_rate: u64,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let tariff = &mut ctx.accounts.tariff;
+
+    require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
+
+    tariff.bulk_rate = bulk_rate;
+
+    msg!("Tariff bulk rate set to {}.", bulk_rate);
+    Ok(())
+}
+
+/// Choose whether an existing tariff prices seasonal usage on level forecasts
+///
+/// When enabled, usage billed through `use_water` and `true_up` is priced on the level
+/// the reservoir's posted forecast projects for the current period, so consumers see
+/// price changes coming. Billing is refused while no forecast covers the current slot.
+///
+/// # Arguments
+/// * `ctx` - Context containing the tariff account, agency signer and system program
+/// * `tariff_key` - Unique public key identifier for this tariff
+/// * `forecast_pricing` - Whether to price on the reservoir's level forecast
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match the account's key
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_tariff_forecast_pricing(
+    ctx: Context<UpdateTariff>,
+    tariff_key: Pubkey,
+    forecast_pricing: bool,
+) -> Result<()> {
+    Role::authorize(
+        &ctx.accounts.agency.key(),
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+
+    let tariff = &mut ctx.accounts.tariff;
+
+    require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
+
+    tariff.forecast_pricing = forecast_pricing;
+
+    msg!("Tariff forecast pricing set to {}.", forecast_pricing);
+    Ok(())
+}
+
+/// Set the regulated bounds of bills under an existing tariff
+///
+/// The scarcity multiplier applied to the block rate is capped at `max_multiplier`, and
+/// every reading is billed at least `min_charge`, whatever the reservoir level. The bounds
+/// can only be set by the agency's regulator, or a key it granted the Regulator role, so
+/// the agency cannot lift them itself.
+///
+/// # Arguments
+/// * `ctx` - Context containing the tariff account, agency, regulator authority and the
+///   regulator as authority
+/// * `tariff_key` - Unique public key identifier for this tariff
+/// * `max_multiplier` - Highest multiplier, with three implied decimals; 0 leaves it uncapped
+/// * `min_charge` - Lowest charge of a reading, with three implied decimals; 0 for no floor
+///
+/// # Errors
+/// * `CustomError::MissingRegulatorApproval` - If the regulator authority is missing or the
+///   authority is the agency itself
+/// * `CustomError::MissingRole` - If the authority is neither the regulator authority nor
+///   holds the Regulator role
+/// * `CustomError::Unauthorized` - If tariff_key doesn't match the account's key
+///
+/// # Returns
+/// * `Ok(())` on successful update
+pub fn update_tariff_bounds(
+    ctx: Context<UpdateTariff>,
+    tariff_key: Pubkey,
+    max_multiplier: u64,
+    min_charge: u64,
+) -> Result<()> {
+    ctx.accounts
+        .regulator_authority
+        .as_ref()
+        .ok_or(CustomError::MissingRegulatorApproval)?
+        .authorize(&ctx.accounts.authority.key(), ctx.accounts.role.as_deref())?;
+
+    let tariff = &mut ctx.accounts.tariff;
+
+    require_keys_eq!(tariff_key, tariff.tariff_key, CustomError::Unauthorized);
+
+    tariff.max_multiplier = max_multiplier;
+    tariff.min_charge = min_charge;
+
+    msg!(
+        "Tariff bounded to a {} multiplier and a {} minimum charge.",
+        max_multiplier,
+        min_charge
+    );
+    Ok(())
+}
+
+/// Records the tariff's current version in its history before it is replaced
+fn record_superseded_version(history: &mut TariffHistory, tariff: &Tariff) -> Result<()> {
+    history.tariff_key = tariff.tariff_key;
+    history.record(tariff, Clock::get()?.slot);
+    Ok(())
+}
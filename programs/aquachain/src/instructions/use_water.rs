@@ -1,13 +1,19 @@
 use crate::{
-    state::{Consumer, Reservoir, Tariff, TariffType},
-    utils::FixedPoint,
-    CustomError,
+    events::{ContractExpired, SharedDrawExceeded, WaterBilled},
+    state::{
+        AgencyStats, CommunalPoint, Consumer, DrBid, DroughtCover, DroughtInsurance, EnergyOracle,
+        InterruptiblePolicy, IrrigationSchedule, LevelForecast, PricingSnapshot, Reservoir,
+        ReservoirDailyStats, SharedReservoir, SourceMix, Tariff, TariffType, Tokens,
+    },
+    utils::{bps_of, burn_signed_by_consumer, FixedPoint},
+    CustomError, DISCRIMINATOR,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::{self, Mint, Token, TokenAccount},
 };
+use aquachain_core::ChargeBounds;
 
 /// Use water instruction context
 ///
@@ -18,12 +24,26 @@ use anchor_spl::{
 /// * `tariff` - The PDA tariff account assigned to this consumer
 /// * `reservoir` - The PDA reservoir account assigned to this consumer
 /// * `agency` - The authority that can mint tokens
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
+/// * `reservoir_daily_stats` - The PDA account recording the reservoir's daily withdrawals
 /// * `consumer_wtk` - The consumer's WTK token account
 /// * `consumer_watc` - The consumer's WATC token account
 /// * `wtk_mint` - The WTK token mint
 /// * `watc_mint` - The WATC token mint
 /// * `token_program` - Required for token operations
 /// * `associated_token_program` - Required for associated token account
+/// * `system_program` - Required to create the reservoir's daily statistics
+/// * `drought_cover` - The consumer's drought cover, if it opted into insurance
+/// * `drought_insurance` - The insurance pool of the consumer's reservoir, if covered
+/// * `communal_point` - The shared prepaid balance, if the consumer is a community standpipe
+/// * `irrigation_schedule` - The consumer's irrigation turns, if it is an agricultural consumer
+/// * `energy_oracle` - The agency's electricity price, to pass pumping costs through
+/// * `level_forecast` - The reservoir's projected levels, for tariffs pricing on forecasts
+/// * `shared_reservoir` - The draw rights on the reservoir, if it is shared between agencies
+/// * `source_mix` - The agency's supply sources, to bill the blended cost of supplementary sources
+/// * `interruptible_policy` - The agency's interruptible supply terms, if the consumer's supply is interruptible
+/// * `dr_bid` - The consumer's curtailment bid, if it bid on a demand-response event
 ///
 /// # Seeds for Tariff PDA
 /// * `"tariff"` - Constant string
@@ -34,10 +54,19 @@ use anchor_spl::{
 /// * `"reservoir"` - Constant string
 /// * `agency` - Agency's public key
 /// * `reservoir_key` - Unique identifier for the reservoir
+///
+/// # Seeds for Tokens PDA
+/// * `"tokens"` - Constant string
+/// * `agency` - Agency's public key
+///
+/// # Seeds for ReservoirDailyStats PDA
+/// * `"reservoir_daily_stats"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
 #[derive(Accounts)]
 #[instruction(tariff_key: Pubkey, reservoir_key: Pubkey)]
 pub struct UseWater<'info> {
-    #[account(signer)]
+    #[account(mut, signer)]
     pub consumer: Account<'info, Consumer>, // Consumer account
     #[account(
         seeds = [
@@ -59,6 +88,22 @@ pub struct UseWater<'info> {
     pub reservoir: Account<'info, Reservoir>, // Current Reservoir assigned to this consumer
     #[account(mut)]
     pub agency: Signer<'info>, // Authority of the provider
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>, // Agency's aggregate statistics
+    #[account(
+        init_if_needed,
+        seeds = [
+            b"reservoir_daily_stats",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + ReservoirDailyStats::INIT_SPACE
+    )]
+    pub reservoir_daily_stats: Account<'info, ReservoirDailyStats>, // Reservoir's daily withdrawals
 
     // Token account for the consumer to send WTK from
     #[account(mut, associated_token::mint = wtk_mint,  associated_token::authority = consumer)]
@@ -67,20 +112,76 @@ pub struct UseWater<'info> {
     // Additional accounts for token transfer
     #[account(mut, associated_token::mint = watc_mint,  associated_token::authority = consumer)]
     pub consumer_watc: Account<'info, TokenAccount>, // Consumer's WaterCapacityToken account
-    #[account(mut,  mint::authority = agency, mint::decimals = 9)]
+    #[account(mut,  mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
     /// Mint of the WaterToken to ensure accounts align on token type
     pub wtk_mint: Account<'info, Mint>,
-    #[account(mut, mint::authority = agency, mint::decimals = 9)]
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
     pub watc_mint: Account<'info, Mint>, // Mint for the WaterCapacityToken
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub drought_cover: Option<Account<'info, DroughtCover>>, // Consumer's drought cover
+    #[account(mut)]
+    pub drought_insurance: Option<Account<'info, DroughtInsurance>>, // Reservoir's insurance pool
+    #[account(mut)]
+    pub communal_point: Option<Account<'info, CommunalPoint>>, // Standpipe's shared balance
+    #[account(mut)]
+    pub irrigation_schedule: Option<Account<'info, IrrigationSchedule>>, // Farm's irrigation turns
+    pub energy_oracle: Option<Account<'info, EnergyOracle>>, // Agency's electricity price
+    pub level_forecast: Option<Account<'info, LevelForecast>>, // Reservoir's projected levels
+    #[account(mut)]
+    pub shared_reservoir: Option<Account<'info, SharedReservoir>>, // Agencies' draw rights
+    pub source_mix: Option<Account<'info, SourceMix>>,       // Agency's supply sources
+    pub interruptible_policy: Option<Account<'info, InterruptiblePolicy>>, // Interruptible supply terms
+    #[account(mut)]
+    pub dr_bid: Option<Account<'info, DrBid>>,          // Consumer's demand-response bid
 }
 
 /// Charge consumer for water consumption by minting WTK tokens
 ///
 /// This function charges a consumer for their water usage by minting WTK tokens
 /// to their token account. The amount of tokens minted represents the payment for
-/// water consumption. WATC tokens are burned in proportion to water usage.
+/// water consumption. WATC tokens are burned in proportion to water usage, and the
+/// volume is added to the reservoir's withdrawals for the current day. Once the
+/// consumer's contract has expired, all usage is charged at the block rate and a
+/// `ContractExpired` event is emitted.
+///
+/// When the consumer's drought cover and its reservoir's insurance pool are passed, the
+/// cover is paid for the current period and the reservoir is below the pool's trigger
+/// level, part of the surcharge above the flat water rate is paid out of the pool and is
+/// not billed to the consumer.
+///
+/// When the consumer is a community standpipe and its communal point is passed, as much of
+/// the charge as the shared prepaid balance covers is drawn from it. As much of the rest
+/// as the consumer's own prepaid balance covers is drawn from that, and only the remainder
+/// is billed to the consumer as WTK.
+///
+/// When the consumer's irrigation schedule is passed, volume drawn beyond the current
+/// week's allotment, or outside every crop season, is drawn out of turn and billed with
+/// the schedule's surcharge on the water rate.
+///
+/// When the agency's energy oracle is passed, the volume is also billed the energy used to
+/// pump it to the reservoir's zone at the posted electricity price, so bills track
+/// pumping costs.
+///
+/// When the agency's source mix is passed, the volume is also billed the blended unit
+/// cost of the sources the agency supplies from, so the cost of aquifer or desalinated
+/// water topping up the supply reaches bills.
+///
+/// When the consumer's supply is interruptible, the agency's interruptible policy must be
+/// passed. Readings are refused while the agency curtails interruptible supply, and are
+/// otherwise billed at the policy's discount.
+///
+/// When the tariff prices on forecasts, seasonal usage is priced on the level the
+/// reservoir's forecast projects for the current period, which must then be passed.
+///
+/// When the reservoir is shared between agencies and its draw rights are passed, the
+/// volume is drawn against the agency's share. Volume beyond the share is added to what
+/// the agency owes the other agencies and a `SharedDrawExceeded` event is emitted.
+///
+/// When the consumer bid on a demand-response event and its bid is passed, volume used
+/// during the event's window is recorded on the bid, so its reduction can be verified.
 ///
 /// # Arguments
 /// * `ctx` - Context containing consumer, tariff, reservoir, agency and token accounts
@@ -91,6 +192,22 @@ pub struct UseWater<'info> {
 /// # Errors
 /// * `CustomError::Unauthorized` - If tariff_key or reservoir_key do not match consumer's assigned values
 /// * `CustomError::InvalidAmount` - If amount is zero
+/// * `CustomError::MathOverflow` - If the converted volume or cost does not fit in a u64
+/// * `CustomError::InvalidDroughtCover` - If the cover or pool belongs to another consumer or reservoir
+/// * `CustomError::InvalidCommunalPoint` - If the communal point belongs to another consumer
+/// * `CustomError::InvalidIrrigationSchedule` - If the schedule belongs to another consumer
+/// * `CustomError::InvalidEnergyOracle` - If the energy oracle belongs to another agency
+/// * `CustomError::StaleOracle` - If the electricity price is unset or too old
+/// * `CustomError::InvalidSourceMix` - If the source mix belongs to another agency
+/// * `CustomError::InvalidInterruptiblePolicy` - If the consumer's supply is interruptible and
+///   the agency's interruptible policy is missing or belongs to another agency
+/// * `CustomError::SupplyCurtailed` - If the consumer's interruptible supply is curtailed
+/// * `CustomError::InvalidLevelForecast` - If the forecast projects another reservoir
+/// * `CustomError::ForecastUnavailable` - If the tariff prices on a forecast that is missing
+///   or does not cover the current slot
+/// * `CustomError::InvalidSharedReservoir` - If the draw rights cover another reservoir or
+///   grant the agency no right
+/// * `CustomError::InvalidCurtailmentBid` - If the curtailment bid belongs to another consumer
 ///
 /// # Returns
 /// * `Ok(())` on successful payment
@@ -103,6 +220,7 @@ pub fn use_water(
     let consumer = &mut ctx.accounts.consumer;
     let tariff = &ctx.accounts.tariff;
     let reservoir = &ctx.accounts.reservoir;
+    let units = &ctx.accounts.tokens.units;
 
     require!(amount > 0, CustomError::InvalidAmount);
 
@@ -117,26 +235,144 @@ pub fn use_water(
         CustomError::Unauthorized
     );
 
-    // Apply block rate or standard rate based on the consumer's contracted capacity
-    let amount_fp = FixedPoint::from(amount);
+    let clock = Clock::get()?;
+    let slot = clock.slot;
+
+    // Apply block rate or standard rate based on the consumer's usage this period
+    let amount_fp = units.to_volume(amount)?;
     let water_rate_fp = FixedPoint::from(tariff.water_rate);
-    let block_rate_fp = FixedPoint::from(consumer.block_rate);
-    let consumer_watc_balance = FixedPoint::from(ctx.accounts.consumer_watc.amount);
 
-    let (level, level_max) = (
-        FixedPoint::from(reservoir.current_level),
-        FixedPoint::from(reservoir.capacity),
-    );
+    // Price on the projected level when the tariff gives advance price signals
+    let forecast_level = reservoir.forecast_level(
+        tariff,
+        ctx.accounts.level_forecast.as_deref(),
+        &ctx.accounts.agency.key(),
+        slot,
+    )?;
 
-    let total_cost = calculate_total_cost(
-        consumer_watc_balance,
-        amount_fp,
-        water_rate_fp,
-        tariff.tariff_type,
-        block_rate_fp,
-        level_max,
-        level,
-    );
+    // Record the inputs of the charge so it can be recomputed later
+    let scarcity_inputs = reservoir.scarcity_inputs(tariff.tariff_type, forecast_level);
+    let multiplier = scarcity_inputs.multiplier(units)?;
+    let pricing = PricingSnapshot {
+        tariff_activated_slot: tariff.activated_slot,
+        water_rate: tariff.water_rate,
+        block_rate: consumer.block_rate,
+        max_multiplier: tariff.max_multiplier,
+        min_charge: tariff.min_charge,
+        block_remaining: consumer.block_remaining(tariff, slot),
+        volume: amount,
+        level: scarcity_inputs.level(),
+        multiplier: multiplier.into(),
+        volume_scale: units.volume_scale,
+        currency_decimals: units.currency_decimals,
+        slot,
+    };
+    let mut bill = pricing.bill()?;
+
+    // Offset part of the drought surcharge from the insurance pool
+    let payout = match (
+        ctx.accounts.drought_cover.as_deref(),
+        ctx.accounts.drought_insurance.as_deref(),
+    ) {
+        (Some(cover), Some(pool)) => {
+            require!(
+                cover.consumer == consumer.key()
+                    && cover.reservoir_key == reservoir_key
+                    && pool.agency == ctx.accounts.agency.key()
+                    && pool.reservoir_key == reservoir_key,
+                CustomError::InvalidDroughtCover
+            );
+
+            if cover.is_covered(consumer.capacity_period_start) && pool.is_triggered(reservoir) {
+                let flat_cost = units.to_currency(amount_fp * water_rate_fp)?;
+                pool.payout_for(bill.total.saturating_sub(flat_cost))
+            } else {
+                0
+            }
+        }
+        _ => 0,
+    };
+
+    // Surcharge volume drawn outside the farm's irrigation turns
+    let surcharge = match ctx.accounts.irrigation_schedule.as_mut() {
+        Some(schedule) => {
+            require!(
+                schedule.consumer == consumer.key() && schedule.agency == ctx.accounts.agency.key(),
+                CustomError::InvalidIrrigationSchedule
+            );
+            let out_of_turn = schedule.record_draw(amount, clock.unix_timestamp);
+            let out_of_turn_cost =
+                units.to_currency(units.to_volume(out_of_turn)? * water_rate_fp)?;
+            bps_of(out_of_turn_cost, schedule.surcharge_bps)
+        }
+        None => 0,
+    };
+
+    // Pass the cost of pumping the water to the zone through to the consumer
+    let energy_surcharge = match ctx.accounts.energy_oracle.as_deref() {
+        Some(oracle) => {
+            require_keys_eq!(
+                oracle.agency,
+                ctx.accounts.agency.key(),
+                CustomError::InvalidEnergyOracle
+            );
+            require!(!oracle.is_stale(slot), CustomError::StaleOracle);
+            units.to_currency(
+                amount_fp
+                    * FixedPoint::from(reservoir.pumping_intensity)
+                    * FixedPoint::from(oracle.price),
+            )?
+        }
+        None => 0,
+    };
+    // Bill the blended cost of the sources the water is supplied from
+    let source_surcharge = match ctx.accounts.source_mix.as_deref() {
+        Some(source_mix) => {
+            require_keys_eq!(
+                source_mix.agency,
+                ctx.accounts.agency.key(),
+                CustomError::InvalidSourceMix
+            );
+            units.to_currency(amount_fp * FixedPoint::from(source_mix.blended_unit_cost()))?
+        }
+        None => 0,
+    };
+    bill.add_penalty(surcharge)?;
+    bill.add_penalty(energy_surcharge)?;
+    bill.add_penalty(source_surcharge)?;
+    bill.apply_discount(payout);
+
+    // Discount interruptible supply, which is cut off first in emergencies
+    if consumer.interruptible {
+        let policy = ctx
+            .accounts
+            .interruptible_policy
+            .as_deref()
+            .ok_or(CustomError::InvalidInterruptiblePolicy)?;
+        require_keys_eq!(
+            policy.agency,
+            ctx.accounts.agency.key(),
+            CustomError::InvalidInterruptiblePolicy
+        );
+        require!(!policy.is_curtailed(slot), CustomError::SupplyCurtailed);
+        bill.apply_discount(policy.discount_for(bill.total));
+    }
+    let billed = bill.total;
+
+    // Draw what the standpipe's shared balance covers
+    let drawn = match ctx.accounts.communal_point.as_mut() {
+        Some(point) => {
+            require!(
+                point.consumer == consumer.key() && point.agency == ctx.accounts.agency.key(),
+                CustomError::InvalidCommunalPoint
+            );
+            point.draw(billed)
+        }
+        None => 0,
+    };
+    // Then what the consumer's own prepaid balance covers
+    let prepaid = consumer.draw_prepaid(billed - drawn);
+    let charge = billed - drawn - prepaid;
 
     // Mint WTK tokens to the consumer for the usage cost
     token::mint_to(
@@ -148,64 +384,186 @@ pub fn use_water(
                 mint: ctx.accounts.wtk_mint.to_account_info(),
             },
         ),
-        total_cost,
+        charge,
     )?;
 
     // Deduct WATC tokens
     if ctx.accounts.consumer_watc.amount > 0 {
-        token::burn(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                token::Burn {
-                    mint: ctx.accounts.watc_mint.to_account_info(),
-                    from: ctx.accounts.consumer_watc.to_account_info(),
-                    authority: ctx.accounts.consumer.to_account_info(),
-                },
-            ),
-            if consumer_watc_balance >= amount_fp {
-                amount
-            } else {
-                consumer_watc_balance.into()
-            },
+        burn_signed_by_consumer(
+            &ctx.accounts.token_program,
+            ctx.accounts.watc_mint.to_account_info(),
+            ctx.accounts.consumer_watc.to_account_info(),
+            ctx.accounts.consumer.to_account_info(),
+            amount.min(ctx.accounts.consumer_watc.amount),
         )?;
     }
 
+    ctx.accounts.consumer.bill_water(charge, slot)?;
+    ctx.accounts.consumer.record_actual_usage(amount);
+    ctx.accounts.consumer.last_bill = bill;
+    ctx.accounts.consumer.last_pricing = pricing;
+    ctx.accounts.consumer.last_scarcity_inputs = scarcity_inputs;
+
+    if payout > 0 {
+        if let Some(pool) = ctx.accounts.drought_insurance.as_mut() {
+            pool.pay_out(payout);
+        }
+        if let Some(cover) = ctx.accounts.drought_cover.as_mut() {
+            cover.total_payouts = cover.total_payouts.saturating_add(payout);
+        }
+        msg!("Drought insurance paid out {}.", payout);
+    }
+    if surcharge > 0 {
+        msg!("Out-of-turn irrigation surcharge of {}.", surcharge);
+    }
+    if energy_surcharge > 0 {
+        msg!("Energy surcharge of {}.", energy_surcharge);
+    }
+    if source_surcharge > 0 {
+        msg!("Supply source surcharge of {}.", source_surcharge);
+    }
+    if drawn > 0 {
+        msg!("Communal balance paid {}.", drawn);
+    }
+    ctx.accounts.agency_stats.record_usage(amount);
+
+    let co2e = ctx
+        .accounts
+        .reservoir
+        .emissions_for(amount, ctx.accounts.tokens.units.volume_scale);
+    ctx.accounts.consumer.record_emissions(co2e);
+    ctx.accounts.agency_stats.record_emissions(co2e);
+
+    let daily_stats = &mut ctx.accounts.reservoir_daily_stats;
+    daily_stats.reservoir_key = reservoir_key;
+    daily_stats.record(ReservoirDailyStats::day_of(clock.unix_timestamp), amount);
+
+    // Draw against the agency's share of a reservoir shared with other agencies
+    if let Some(shared_reservoir) = ctx.accounts.shared_reservoir.as_mut() {
+        require_keys_eq!(
+            shared_reservoir.reservoir_key,
+            reservoir_key,
+            CustomError::InvalidSharedReservoir
+        );
+        let agency = ctx.accounts.agency.key();
+        let exceeded = shared_reservoir.record_draw(&agency, amount, slot)?;
+        if exceeded > 0 {
+            emit!(SharedDrawExceeded {
+                reservoir_key,
+                agency,
+                exceeded,
+                owed: shared_reservoir
+                    .right_of(&agency)
+                    .map_or(0, |right| right.owed),
+                slot,
+            });
+        }
+    }
+
+    // Record usage against the consumer's demand-response commitment
+    if let Some(dr_bid) = ctx.accounts.dr_bid.as_mut() {
+        require_keys_eq!(
+            dr_bid.consumer,
+            ctx.accounts.consumer.key(),
+            CustomError::InvalidCurtailmentBid
+        );
+        dr_bid.record_usage(amount, slot);
+    }
+
+    emit!(WaterBilled {
+        consumer: ctx.accounts.consumer.key(),
+        volume: amount,
+        charge,
+        outstanding: ctx.accounts.consumer.outstanding_water_debt,
+        breakdown: bill,
+        slot,
+    });
+
+    if ctx.accounts.consumer.contract_expired(slot) {
+        emit!(ContractExpired {
+            consumer: ctx.accounts.consumer.key(),
+            expiry_slot: ctx.accounts.consumer.contract_expiry_slot,
+            slot,
+        });
+    }
+
     msg!(
         "Consumer used {} units of water, charged: {}.",
         amount,
-        total_cost
+        charge
     );
     Ok(())
 }
 
-fn calculate_total_cost(
-    consumer_watc_balance: FixedPoint,
+/// Compute the cost of a water reading under a tariff
+///
+/// Delegates to the shared pricing core with its default linear scarcity curve, which the
+/// program prices on for reservoirs without a scarcity curve. Reservoirs with a curve are
+/// priced with `Reservoir::scarcity_multiplier` and [`calculate_scaled_cost`] instead.
+///
+/// # Arguments
+/// * `block_remaining` - Billed volume still charged at the water rate this billing period
+/// * `amount_fp` - Billed volume of the reading
+/// * `water_rate_fp` - Tariff water rate
+/// * `tariff_type` - Tariff structure applied to usage above the block threshold
+/// * `block_rate_fp` - Consumer's block rate
+/// * `level_max` - Reservoir capacity
+/// * `level` - Current reservoir level
+/// * `bounds` - Regulated bounds of the tariff, see `Tariff::charge_bounds`
+///
+/// # Returns
+/// The cost as a fixed-point amount of the billing currency
+#[allow(clippy::too_many_arguments)]
+pub fn calculate_total_cost(
+    block_remaining: FixedPoint,
     amount_fp: FixedPoint,
     water_rate_fp: FixedPoint,
     tariff_type: TariffType,
     block_rate_fp: FixedPoint,
     level_max: FixedPoint,
     level: FixedPoint,
-) -> u64 {
-    let total_cost: u64 = if consumer_watc_balance >= amount_fp {
-        // Simple case: standard rate
-        (amount_fp * water_rate_fp).into()
-    } else {
-        let base_cost = consumer_watc_balance * water_rate_fp;
-        let excess = amount_fp - consumer_watc_balance;
-        // Cases above contracted capacity
-        let extra_cost = match tariff_type {
-            TariffType::UniformIBT => excess * block_rate_fp,
-            TariffType::SeasonalIBT => excess * block_rate_fp * (level_max - level),
-            TariffType::SeasonalDBT => {
-                excess
-                    * block_rate_fp
-                    * (FixedPoint::one() + FixedPoint::one() - (level / level_max))
-            }
-        };
-        (base_cost + extra_cost).into()
-    };
-    total_cost
+    bounds: &ChargeBounds,
+) -> FixedPoint {
+    aquachain_core::calculate_total_cost(
+        block_remaining,
+        amount_fp,
+        water_rate_fp,
+        tariff_type.into(),
+        block_rate_fp,
+        level_max,
+        level,
+        bounds,
+    )
+}
+
+/// Compute the cost of a water reading with a given scarcity multiplier
+///
+/// Delegates to the shared pricing core. The multiplier is usually the one returned by
+/// `Reservoir::scarcity_multiplier` for the reservoir the consumer draws from.
+///
+/// # Arguments
+/// * `block_remaining` - Billed volume still charged at the water rate this billing period
+/// * `amount_fp` - Billed volume of the reading
+/// * `water_rate_fp` - Tariff water rate
+/// * `block_rate_fp` - Consumer's block rate
+/// * `multiplier` - Scarcity multiplier applied to the block rate
+///
+/// # Returns
+/// The cost as a fixed-point amount of the billing currency
+pub fn calculate_scaled_cost(
+    block_remaining: FixedPoint,
+    amount_fp: FixedPoint,
+    water_rate_fp: FixedPoint,
+    block_rate_fp: FixedPoint,
+    multiplier: FixedPoint,
+) -> FixedPoint {
+    aquachain_core::calculate_scaled_cost(
+        block_remaining,
+        amount_fp,
+        water_rate_fp,
+        block_rate_fp,
+        multiplier,
+    )
 }
 
 #[cfg(test)]
@@ -215,86 +573,133 @@ mod tests {
 
     #[test]
     fn test_total_cost_under_cap() {
-        let consumer_watc_balance = FixedPoint::from(100000);
+        let block_remaining = FixedPoint::from(100000);
         let amount_fp = FixedPoint::from(100000);
         let water_rate_fp = FixedPoint::from(500);
         let block_rate_fp = FixedPoint::from(800);
         let level_max = FixedPoint::from(1000000);
         let level = FixedPoint::from(950000);
 
-        let total_cost = calculate_total_cost(
-            consumer_watc_balance,
+        let total_cost: u64 = calculate_total_cost(
+            block_remaining,
             amount_fp,
             water_rate_fp,
             TariffType::UniformIBT,
             block_rate_fp,
             level_max,
             level,
-        );
+            &ChargeBounds::default(),
+        )
+        .into();
 
         assert_eq!(total_cost, 50000);
     }
 
     #[test]
     fn test_total_cost_ibt() {
-        let consumer_watc_balance = FixedPoint::from(100000);
+        let block_remaining = FixedPoint::from(100000);
         let amount_fp = FixedPoint::from(120000);
         let water_rate_fp = FixedPoint::from(500);
         let block_rate_fp = FixedPoint::from(800);
         let level_max = FixedPoint::from(1000000);
         let level = FixedPoint::from(950000);
 
-        let total_cost = calculate_total_cost(
-            consumer_watc_balance,
+        let total_cost: u64 = calculate_total_cost(
+            block_remaining,
             amount_fp,
             water_rate_fp,
             TariffType::UniformIBT,
             block_rate_fp,
             level_max,
             level,
-        );
+            &ChargeBounds::default(),
+        )
+        .into();
         assert_eq!(total_cost, 66000);
     }
 
     #[test]
     fn test_total_cost_seasonal_ibt() {
-        let consumer_watc_balance = FixedPoint::from(100000);
+        let block_remaining = FixedPoint::from(100000);
         let amount_fp = FixedPoint::from(120000);
         let water_rate_fp = FixedPoint::from(500);
         let block_rate_fp = FixedPoint::from(800);
         let level_max = FixedPoint::from(1000000);
         let level = FixedPoint::from(950000);
 
-        let total_cost = calculate_total_cost(
-            consumer_watc_balance,
+        let total_cost: u64 = calculate_total_cost(
+            block_remaining,
             amount_fp,
             water_rate_fp,
             TariffType::SeasonalIBT,
             block_rate_fp,
             level_max,
             level,
-        );
+            &ChargeBounds::default(),
+        )
+        .into();
         assert_eq!(total_cost, 850000);
     }
 
+    #[test]
+    fn test_total_cost_within_bounds() {
+        let block_remaining = FixedPoint::from(100000);
+        let water_rate_fp = FixedPoint::from(500);
+        let block_rate_fp = FixedPoint::from(800);
+        let level_max = FixedPoint::from(1000000);
+        let level = FixedPoint::from(950000);
+        let bounds = ChargeBounds {
+            max_multiplier: 2000,
+            min_charge: 5000,
+        };
+
+        let capped: u64 = calculate_total_cost(
+            block_remaining,
+            FixedPoint::from(120000),
+            water_rate_fp,
+            TariffType::SeasonalIBT,
+            block_rate_fp,
+            level_max,
+            level,
+            &bounds,
+        )
+        .into();
+        assert_eq!(capped, 82000);
+
+        let floored: u64 = calculate_total_cost(
+            block_remaining,
+            FixedPoint::from(1000),
+            water_rate_fp,
+            TariffType::SeasonalIBT,
+            block_rate_fp,
+            level_max,
+            level,
+            &bounds,
+        )
+        .into();
+        assert_eq!(floored, 5000);
+    }
+
     #[test]
     fn test_total_cost_seasonal_dbt() {
-        let consumer_watc_balance = FixedPoint::from(100000);
+        let block_remaining = FixedPoint::from(100000);
         let amount_fp = FixedPoint::from(120000);
         let water_rate_fp = FixedPoint::from(500);
         let block_rate_fp = FixedPoint::from(800);
         let level_max = FixedPoint::from(1000000);
         let level = FixedPoint::from(950000);
 
-        let total_cost = calculate_total_cost(
-            consumer_watc_balance,
+        let total_cost: u64 = calculate_total_cost(
+            block_remaining,
             amount_fp,
             water_rate_fp,
             TariffType::SeasonalDBT,
             block_rate_fp,
             level_max,
             level,
-        );
+            &ChargeBounds::default(),
+        )
+        .into();
         assert_eq!(total_cost, 66800);
     }
 }
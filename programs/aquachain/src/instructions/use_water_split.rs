@@ -0,0 +1,198 @@
+use crate::{
+    state::{AgencyStats, BillBreakdown, Consumer, Reservoir, Tariff, Tokens},
+    utils::{burn_signed_by_consumer, split_bps, FixedPoint},
+    CustomError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Use water as a sub-consumer instruction context
+///
+/// The **UseWaterSplit** context is used to charge water used by a sub-consumer, splitting
+/// the minted WTK between the sub-consumer and its master consumer.
+///
+/// # Fields
+/// * `consumer` - The sub-consumer account reporting usage
+/// * `master_consumer` - The master consumer the sub-consumer rolls up to
+/// * `tariff` - The PDA tariff account assigned to the sub-consumer
+/// * `reservoir` - The PDA reservoir account assigned to the sub-consumer
+/// * `agency` - The authority that can mint tokens
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `agency_stats` - The PDA account holding the agency's aggregate statistics
+/// * `consumer_wtk` - The sub-consumer's WTK token account
+/// * `master_wtk` - The master consumer's WTK token account
+/// * `consumer_watc` - The sub-consumer's WATC token account
+/// * `wtk_mint` - The WTK token mint
+/// * `watc_mint` - The WATC token mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for Tariff PDA
+/// * `"tariff"` - Constant string
+/// * `agency` - Agency's public key
+/// * `tariff_key` - Unique identifier for the tariff
+///
+/// # Seeds for Reservoir PDA
+/// * `"reservoir"` - Constant string
+/// * `agency` - Agency's public key
+/// * `reservoir_key` - Unique identifier for the reservoir
+#[derive(Accounts)]
+#[instruction(tariff_key: Pubkey, reservoir_key: Pubkey)]
+pub struct UseWaterSplit<'info> {
+    #[account(
+        mut,
+        signer,
+        constraint = consumer.master_consumer == Some(master_consumer.key()) @ CustomError::Unauthorized
+    )]
+    pub consumer: Account<'info, Consumer>,
+    #[account(mut)]
+    pub master_consumer: Account<'info, Consumer>,
+    #[account(
+        seeds = [
+            b"tariff",
+            agency.key().as_ref(),
+            &tariff_key.as_ref()
+        ],
+        bump
+    )]
+    pub tariff: Account<'info, Tariff>,
+    #[account(
+        seeds = [
+            b"reservoir",
+            agency.key().as_ref(),
+            &reservoir_key.as_ref()
+        ],
+        bump
+    )]
+    pub reservoir: Account<'info, Reservoir>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>,
+    #[account(mut, seeds = [b"agency_stats", agency.key().as_ref()], bump)]
+    pub agency_stats: Account<'info, AgencyStats>,
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = master_consumer)]
+    pub master_wtk: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = watc_mint, associated_token::authority = consumer)]
+    pub consumer_watc: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub wtk_mint: Account<'info, Mint>,
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub watc_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Charge a sub-consumer for water usage, splitting the bill with its master consumer
+///
+/// The cost is computed exactly as in `use_water` from the sub-consumer's tariff, capacity
+/// and reservoir. The tenant share of the cost is minted as WTK to the sub-consumer and the
+/// remainder to the master consumer. WATC tokens are burned from the sub-consumer.
+///
+/// # Arguments
+/// * `ctx` - Context containing both consumers, tariff, reservoir, agency and token accounts
+/// * `tariff_key` - Public key of the tariff assigned to the sub-consumer
+/// * `reservoir_key` - Public key of the reservoir assigned to the sub-consumer
+/// * `amount` - Amount of water units consumed
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the keys do not match the sub-consumer or it is not linked to the master
+/// * `CustomError::InvalidAmount` - If amount is zero
+/// * `CustomError::MathOverflow` - If the converted volume or cost does not fit in a u64
+///
+/// # Returns
+/// * `Ok(())` on successful charge
+pub fn use_water_split(
+    ctx: Context<UseWaterSplit>,
+    tariff_key: Pubkey,
+    reservoir_key: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let consumer = &ctx.accounts.consumer;
+    let tariff = &ctx.accounts.tariff;
+    let reservoir = &ctx.accounts.reservoir;
+    let units = &ctx.accounts.tokens.units;
+
+    require!(amount > 0, CustomError::InvalidAmount);
+
+    require_keys_eq!(
+        tariff_key,
+        consumer.assigned_tariff,
+        CustomError::Unauthorized
+    );
+    require_keys_eq!(
+        reservoir_key,
+        consumer.assigned_reservoir,
+        CustomError::Unauthorized
+    );
+
+    let slot = Clock::get()?.slot;
+    let multiplier = reservoir.scarcity_multiplier(tariff.tariff_type, units)?;
+    let total_cost = BillBreakdown::price(
+        &tariff.charge_bounds(),
+        units,
+        units.to_volume(consumer.block_remaining(tariff, slot))?,
+        units.to_volume(amount)?,
+        FixedPoint::from(tariff.water_rate),
+        FixedPoint::from(consumer.block_rate),
+        multiplier,
+    )?
+    .total;
+    let (tenant_cost, master_cost) = split_bps(total_cost, consumer.tenant_share_bps);
+
+    // Mint the tenant share to the sub-consumer and the rest to the master
+    for (to, cost) in [
+        (ctx.accounts.consumer_wtk.to_account_info(), tenant_cost),
+        (ctx.accounts.master_wtk.to_account_info(), master_cost),
+    ] {
+        if cost > 0 {
+            token::mint_to(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::MintTo {
+                        to,
+                        authority: ctx.accounts.agency.to_account_info(),
+                        mint: ctx.accounts.wtk_mint.to_account_info(),
+                    },
+                ),
+                cost,
+            )?;
+        }
+    }
+
+    // Deduct WATC tokens from the sub-consumer
+    if ctx.accounts.consumer_watc.amount > 0 {
+        burn_signed_by_consumer(
+            &ctx.accounts.token_program,
+            ctx.accounts.watc_mint.to_account_info(),
+            ctx.accounts.consumer_watc.to_account_info(),
+            ctx.accounts.consumer.to_account_info(),
+            amount.min(ctx.accounts.consumer_watc.amount),
+        )?;
+    }
+
+    ctx.accounts.consumer.bill_water(tenant_cost, slot)?;
+    ctx.accounts.master_consumer.bill_water(master_cost, slot)?;
+    ctx.accounts.consumer.record_actual_usage(amount);
+    ctx.accounts.agency_stats.record_usage(amount);
+
+    let co2e = ctx
+        .accounts
+        .reservoir
+        .emissions_for(amount, ctx.accounts.tokens.units.volume_scale);
+    ctx.accounts.consumer.record_emissions(co2e);
+    ctx.accounts.agency_stats.record_emissions(co2e);
+
+    msg!(
+        "Sub-consumer used {} units of water, charged {} to the tenant and {} to the master.",
+        amount,
+        tenant_cost,
+        master_cost
+    );
+    Ok(())
+}
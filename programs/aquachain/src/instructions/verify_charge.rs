@@ -0,0 +1,59 @@
+use crate::{events::ChargeDiscrepancyFlagged, state::Consumer};
+use anchor_lang::prelude::*;
+
+/// Verify charge instruction context
+///
+/// The **VerifyCharge** context is used by anyone to check the last metered charge of a
+/// consumer against its pricing snapshot. Only the verifier needs to sign.
+///
+/// # Fields
+/// * `verifier` - Anyone submitting the check, pays the transaction fee
+/// * `consumer` - The consumer account whose last charge is checked
+#[derive(Accounts)]
+pub struct VerifyCharge<'info> {
+    pub verifier: Signer<'info>,
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+}
+
+/// Recompute the last metered charge of a consumer and flag it if it was mischarged
+///
+/// The scarcity multiplier is derived again from the reservoir inputs recorded when the
+/// reading was charged, and the pricing function re-run on the recorded snapshot with it, so
+/// a charge billed by a faulty pricing version can be caught after the fix is deployed. When
+/// the billed usage charge differs, the charge is flagged on the consumer and a
+/// `ChargeDiscrepancyFlagged` event is emitted.
+///
+/// # Arguments
+/// * `ctx` - Context containing the verifier and consumer accounts
+///
+/// # Errors
+/// * `CustomError::NoChargeToVerify` - If no metered reading has been charged
+/// * `CustomError::ChargeAlreadyFlagged` - If a flagged charge is still unresolved
+/// * `CustomError::MathOverflow` - If the recomputed charge does not fit in a u64
+///
+/// # Returns
+/// * `Ok(())` on successful verification, whether or not the charge was flagged
+pub fn verify_charge(ctx: Context<VerifyCharge>) -> Result<()> {
+    let consumer = &mut ctx.accounts.consumer;
+
+    match consumer.verify_last_charge()? {
+        Some(flagged) => {
+            emit!(ChargeDiscrepancyFlagged {
+                consumer: consumer.key(),
+                verifier: ctx.accounts.verifier.key(),
+                billed: flagged.billed,
+                recomputed: flagged.recomputed,
+                charge_slot: flagged.slot,
+                slot: Clock::get()?.slot,
+            });
+            msg!(
+                "Charge flagged: billed {}, recomputed {}.",
+                flagged.billed,
+                flagged.recomputed
+            );
+        }
+        None => msg!("Charge verified."),
+    }
+    Ok(())
+}
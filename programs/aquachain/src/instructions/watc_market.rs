@@ -0,0 +1,626 @@
+use crate::{
+    events::WatcTraded,
+    state::{Consumer, OrderSide, Tokens, WatcMarket, WatcOrder},
+    utils::is_valid_bps,
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Configure WATC market instruction context
+///
+/// The **ConfigureWatcMarket** context is used by the agency to open the marketplace where
+/// its consumers trade unused capacity, or to change its fee and trade cap.
+///
+/// # Fields
+/// * `watc_market` - The PDA account of the agency's marketplace
+/// * `settlement_mint` - The stablecoin WATC is traded for
+/// * `agency` - The authority operating the marketplace
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for WatcMarket PDA
+/// * `"watc_market"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct ConfigureWatcMarket<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [b"watc_market", agency.key().as_ref()],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + WatcMarket::INIT_SPACE
+    )]
+    pub watc_market: Account<'info, WatcMarket>,
+    pub settlement_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub agency: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Place ask instruction context
+///
+/// The **PlaceAsk** context is used by a registered consumer to offer unused WATC for sale,
+/// escrowing it in the order. The agency co-signs and pays for the order's accounts.
+///
+/// # Fields
+/// * `consumer` - The consumer account selling capacity (must be signer)
+/// * `watc_market` - The PDA account of the agency's marketplace
+/// * `watc_order` - The PDA account of the new order
+/// * `escrow` - The order's WATC account holding the capacity for sale
+/// * `agency` - The authority operating the marketplace
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `consumer_watc` - The consumer's WATC token account
+/// * `watc_mint` - The WATC token mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for WatcMarket PDA
+/// * `"watc_market"` - Constant string
+/// * `agency` - Agency's public key
+///
+/// # Seeds for WatcOrder PDA
+/// * `"watc_order"` - Constant string
+/// * `agency` - Agency's public key
+/// * `order_id` - The marketplace's order count, little-endian
+#[derive(Accounts)]
+pub struct PlaceAsk<'info> {
+    #[account(signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(mut, seeds = [b"watc_market", agency.key().as_ref()], bump)]
+    pub watc_market: Account<'info, WatcMarket>,
+    #[account(
+        init,
+        seeds = [
+            b"watc_order",
+            agency.key().as_ref(),
+            &watc_market.order_count.to_le_bytes()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + WatcOrder::INIT_SPACE
+    )]
+    pub watc_order: Account<'info, WatcOrder>,
+    #[account(
+        init,
+        payer = agency,
+        associated_token::mint = watc_mint,
+        associated_token::authority = watc_order
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub agency: Signer<'info>, // Authority of the provider
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>, // Agency's token mints and unit configuration
+    #[account(mut, associated_token::mint = watc_mint, associated_token::authority = consumer)]
+    pub consumer_watc: Account<'info, TokenAccount>, // Consumer's WaterCapacityToken account
+    #[account(mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub watc_mint: Account<'info, Mint>, // Mint for the WaterCapacityToken
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Place bid instruction context
+///
+/// The **PlaceBid** context is used by a registered consumer to bid for WATC, escrowing
+/// the payment in the order. The agency co-signs and pays for the order's accounts.
+///
+/// # Fields
+/// * `consumer` - The consumer account buying capacity (must be signer)
+/// * `watc_market` - The PDA account of the agency's marketplace
+/// * `watc_order` - The PDA account of the new order
+/// * `escrow` - The order's stablecoin account holding the payment
+/// * `agency` - The authority operating the marketplace
+/// * `consumer_settlement` - The consumer's stablecoin token account
+/// * `settlement_mint` - The stablecoin mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for WatcMarket PDA
+/// * `"watc_market"` - Constant string
+/// * `agency` - Agency's public key
+///
+/// # Seeds for WatcOrder PDA
+/// * `"watc_order"` - Constant string
+/// * `agency` - Agency's public key
+/// * `order_id` - The marketplace's order count, little-endian
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        mut,
+        seeds = [b"watc_market", agency.key().as_ref()],
+        bump,
+        has_one = settlement_mint
+    )]
+    pub watc_market: Account<'info, WatcMarket>,
+    #[account(
+        init,
+        seeds = [
+            b"watc_order",
+            agency.key().as_ref(),
+            &watc_market.order_count.to_le_bytes()
+        ],
+        bump,
+        payer = agency,
+        space = DISCRIMINATOR + WatcOrder::INIT_SPACE
+    )]
+    pub watc_order: Account<'info, WatcOrder>,
+    #[account(
+        init,
+        payer = agency,
+        associated_token::mint = settlement_mint,
+        associated_token::authority = watc_order
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub agency: Signer<'info>, // Authority of the provider
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = consumer)]
+    pub consumer_settlement: Account<'info, TokenAccount>,
+    pub settlement_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Match orders instruction context
+///
+/// The **MatchOrders** context is used by the agency, or a crank holding its key, to trade
+/// WATC between a crossing ask and bid.
+///
+/// # Fields
+/// * `watc_market` - The PDA account of the agency's marketplace
+/// * `ask_order` - The order selling WATC
+/// * `bid_order` - The order buying WATC
+/// * `ask_escrow` - The ask's WATC account
+/// * `bid_escrow` - The bid's stablecoin account
+/// * `seller` - The consumer that placed the ask
+/// * `buyer` - The consumer that placed the bid
+/// * `seller_settlement` - The seller's stablecoin token account
+/// * `buyer_watc` - The buyer's WATC token account
+/// * `buyer_settlement` - The buyer's stablecoin token account, refunded any price surplus
+/// * `agency_settlement` - The agency's stablecoin treasury account, receiving the fee
+/// * `agency` - The authority operating the marketplace
+/// * `watc_mint` - The WATC token mint
+/// * `settlement_mint` - The stablecoin mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for WatcMarket PDA
+/// * `"watc_market"` - Constant string
+/// * `agency` - Agency's public key
+///
+/// # Seeds for WatcOrder PDAs
+/// * `"watc_order"` - Constant string
+/// * `agency` - Agency's public key
+/// * `order_id` - Sequence number of the order, little-endian
+#[derive(Accounts)]
+pub struct MatchOrders<'info> {
+    #[account(
+        seeds = [b"watc_market", agency.key().as_ref()],
+        bump,
+        has_one = settlement_mint
+    )]
+    pub watc_market: Account<'info, WatcMarket>,
+    #[account(
+        mut,
+        seeds = [
+            b"watc_order",
+            agency.key().as_ref(),
+            &ask_order.order_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub ask_order: Account<'info, WatcOrder>,
+    #[account(
+        mut,
+        seeds = [
+            b"watc_order",
+            agency.key().as_ref(),
+            &bid_order.order_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub bid_order: Account<'info, WatcOrder>,
+    #[account(mut, associated_token::mint = watc_mint, associated_token::authority = ask_order)]
+    pub ask_escrow: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = settlement_mint,
+        associated_token::authority = bid_order
+    )]
+    pub bid_escrow: Account<'info, TokenAccount>,
+    #[account(address = ask_order.consumer @ CustomError::Unauthorized)]
+    pub seller: Account<'info, Consumer>,
+    #[account(mut, address = bid_order.consumer @ CustomError::Unauthorized)]
+    pub buyer: Account<'info, Consumer>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = seller)]
+    pub seller_settlement: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = watc_mint, associated_token::authority = buyer)]
+    pub buyer_watc: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = buyer)]
+    pub buyer_settlement: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = agency)]
+    pub agency_settlement: Account<'info, TokenAccount>,
+    pub agency: Signer<'info>, // Authority of the provider
+    #[account(mint::authority = agency)]
+    pub watc_mint: Account<'info, Mint>, // Mint for the WaterCapacityToken
+    pub settlement_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Cancel order instruction context
+///
+/// The **CancelOrder** context is used by a consumer to withdraw an order, returning what
+/// is left in its escrow. Filled orders are closed the same way.
+///
+/// # Fields
+/// * `consumer` - The consumer that placed the order (must be signer)
+/// * `watc_order` - The order being cancelled
+/// * `escrow` - The order's escrow account
+/// * `consumer_token` - The consumer's account for the escrowed token
+/// * `agency` - The authority operating the marketplace, receiving the accounts' rent
+/// * `mint` - The escrowed token's mint: WATC for asks, the stablecoin for bids
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for WatcOrder PDA
+/// * `"watc_order"` - Constant string
+/// * `agency` - Agency's public key
+/// * `order_id` - Sequence number of the order, little-endian
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    #[account(signer)]
+    pub consumer: Account<'info, Consumer>,
+    #[account(
+        mut,
+        seeds = [
+            b"watc_order",
+            agency.key().as_ref(),
+            &watc_order.order_id.to_le_bytes()
+        ],
+        bump,
+        has_one = consumer @ CustomError::Unauthorized,
+        close = agency
+    )]
+    pub watc_order: Account<'info, WatcOrder>,
+    #[account(mut, associated_token::mint = mint, associated_token::authority = watc_order)]
+    pub escrow: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = mint, associated_token::authority = consumer)]
+    pub consumer_token: Account<'info, TokenAccount>,
+    /// CHECK: validated against the agency recorded on the order, only receives rent
+    #[account(mut, address = watc_order.agency @ CustomError::Unauthorized)]
+    pub agency: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Open or reconfigure the agency's WATC marketplace
+///
+/// The settlement mint is fixed when the marketplace is opened, since open bids are
+/// escrowed in it.
+///
+/// # Arguments
+/// * `ctx` - Context containing the marketplace, settlement mint and agency accounts
+/// * `fee_bps` - Share of each trade's proceeds paid to the agency, in basis points
+/// * `trade_cap` - Raw volume of WATC a consumer may buy per capacity period, 0 if uncapped
+///
+/// # Errors
+/// * `CustomError::InvalidShare` - If fee_bps exceeds 10,000
+/// * `CustomError::Unauthorized` - If the settlement mint differs from the marketplace's
+///
+/// # Returns
+/// * `Ok(())` on successful configuration
+pub fn configure_watc_market(
+    ctx: Context<ConfigureWatcMarket>,
+    fee_bps: u16,
+    trade_cap: u64,
+) -> Result<()> {
+    let watc_market = &mut ctx.accounts.watc_market;
+    let settlement_mint = ctx.accounts.settlement_mint.key();
+
+    require!(is_valid_bps(fee_bps), CustomError::InvalidShare);
+
+    if watc_market.agency == Pubkey::default() {
+        watc_market.agency = ctx.accounts.agency.key();
+        watc_market.settlement_mint = settlement_mint;
+    }
+    require_keys_eq!(
+        watc_market.settlement_mint,
+        settlement_mint,
+        CustomError::Unauthorized
+    );
+
+    watc_market.fee_bps = fee_bps;
+    watc_market.trade_cap = trade_cap;
+
+    msg!(
+        "WATC market configured: fee {} bps, trade cap {}.",
+        fee_bps,
+        trade_cap
+    );
+    Ok(())
+}
+
+/// Offer unused WATC for sale
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, marketplace, order and token accounts
+/// * `amount` - Raw volume of WATC offered (must be > 0)
+/// * `price` - Settlement base units asked per raw unit of WATC (must be > 0)
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If amount or price is zero
+/// * `CustomError::InsufficientCapacity` - If the consumer holds less WATC than offered
+///
+/// # Returns
+/// * `Ok(())` on successful placement
+pub fn place_ask(ctx: Context<PlaceAsk>, amount: u64, price: u64) -> Result<()> {
+    require!(amount > 0 && price > 0, CustomError::InvalidAmount);
+    require!(
+        ctx.accounts.consumer_watc.amount >= amount,
+        CustomError::InsufficientCapacity
+    );
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.consumer_watc.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.consumer.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    open_order(
+        &mut ctx.accounts.watc_market,
+        &mut ctx.accounts.watc_order,
+        &ctx.accounts.consumer,
+        OrderSide::Ask,
+        amount,
+        price,
+    );
+
+    msg!("Ask placed for {} WATC at {}.", amount, price);
+    Ok(())
+}
+
+/// Bid for WATC
+///
+/// The full payment is escrowed at the bid price. Trades executing at a lower ask price
+/// refund the difference.
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, marketplace, order and token accounts
+/// * `amount` - Raw volume of WATC wanted (must be > 0)
+/// * `price` - Settlement base units bid per raw unit of WATC (must be > 0)
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If amount or price is zero
+/// * `CustomError::TradeCapExceeded` - If the amount would take the consumer over the trade cap
+/// * `CustomError::MathOverflow` - If the payment does not fit in a u64
+///
+/// # Returns
+/// * `Ok(())` on successful placement
+pub fn place_bid(ctx: Context<PlaceBid>, amount: u64, price: u64) -> Result<()> {
+    require!(amount > 0 && price > 0, CustomError::InvalidAmount);
+    require!(
+        ctx.accounts
+            .watc_market
+            .within_cap(ctx.accounts.consumer.period_watc_bought, amount),
+        CustomError::TradeCapExceeded
+    );
+
+    open_order(
+        &mut ctx.accounts.watc_market,
+        &mut ctx.accounts.watc_order,
+        &ctx.accounts.consumer,
+        OrderSide::Bid,
+        amount,
+        price,
+    );
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.consumer_settlement.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.consumer.to_account_info(),
+            },
+        ),
+        ctx.accounts.watc_order.value_of(amount)?,
+    )?;
+
+    msg!("Bid placed for {} WATC at {}.", amount, price);
+    Ok(())
+}
+
+/// Trade WATC between a crossing ask and bid
+///
+/// As much WATC as both orders have left is traded at the ask price. The seller receives
+/// the proceeds less the marketplace fee, which goes to the agency's treasury, and the
+/// buyer is refunded the difference between its bid price and the ask price. Both orders
+/// must have been placed in their consumers' current capacity period.
+///
+/// # Arguments
+/// * `ctx` - Context containing the marketplace, orders, consumers and token accounts
+///
+/// # Errors
+/// * `CustomError::InvalidOrderSide` - If the orders are not an ask and a bid
+/// * `CustomError::SelfTrade` - If both orders were placed by the same consumer
+/// * `CustomError::OrdersDoNotCross` - If the bid price is below the ask price
+/// * `CustomError::OrderExpired` - If either order was placed in an earlier capacity period
+/// * `CustomError::InvalidAmount` - If either order is already filled
+/// * `CustomError::TradeCapExceeded` - If the trade would take the buyer over the trade cap
+/// * `CustomError::MathOverflow` - If the trade's value does not fit in a u64
+///
+/// # Returns
+/// * `Ok(())` on successful trade
+pub fn match_orders(ctx: Context<MatchOrders>) -> Result<()> {
+    let ask = &ctx.accounts.ask_order;
+    let bid = &ctx.accounts.bid_order;
+
+    require!(
+        ask.side == OrderSide::Ask && bid.side == OrderSide::Bid,
+        CustomError::InvalidOrderSide
+    );
+    require_keys_neq!(ask.consumer, bid.consumer, CustomError::SelfTrade);
+    require!(bid.price >= ask.price, CustomError::OrdersDoNotCross);
+    require!(
+        ask.period_start == ctx.accounts.seller.capacity_period_start
+            && bid.period_start == ctx.accounts.buyer.capacity_period_start,
+        CustomError::OrderExpired
+    );
+
+    let amount = ask.remaining.min(bid.remaining);
+    require!(amount > 0, CustomError::InvalidAmount);
+    require!(
+        ctx.accounts
+            .watc_market
+            .within_cap(ctx.accounts.buyer.period_watc_bought, amount),
+        CustomError::TradeCapExceeded
+    );
+
+    let proceeds = ask.value_of(amount)?;
+    let fee = ctx.accounts.watc_market.fee_for(proceeds);
+    let surplus = bid.value_of(amount)? - proceeds;
+
+    let agency_key = ctx.accounts.agency.key();
+    let ask_id = ask.order_id.to_le_bytes();
+    let bid_id = bid.order_id.to_le_bytes();
+    let ask_bump = [ctx.bumps.ask_order];
+    let bid_bump = [ctx.bumps.bid_order];
+    let ask_seeds: &[&[&[u8]]] = &[&[b"watc_order", agency_key.as_ref(), &ask_id, &ask_bump]];
+    let bid_seeds: &[&[&[u8]]] = &[&[b"watc_order", agency_key.as_ref(), &bid_id, &bid_bump]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.ask_escrow.to_account_info(),
+                to: ctx.accounts.buyer_watc.to_account_info(),
+                authority: ctx.accounts.ask_order.to_account_info(),
+            },
+            ask_seeds,
+        ),
+        amount,
+    )?;
+
+    for (to, value) in [
+        (
+            ctx.accounts.seller_settlement.to_account_info(),
+            proceeds - fee,
+        ),
+        (ctx.accounts.agency_settlement.to_account_info(), fee),
+        (ctx.accounts.buyer_settlement.to_account_info(), surplus),
+    ] {
+        if value > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.bid_escrow.to_account_info(),
+                        to,
+                        authority: ctx.accounts.bid_order.to_account_info(),
+                    },
+                    bid_seeds,
+                ),
+                value,
+            )?;
+        }
+    }
+
+    ctx.accounts.ask_order.remaining -= amount;
+    ctx.accounts.bid_order.remaining -= amount;
+    let buyer = &mut ctx.accounts.buyer;
+    buyer.period_watc_bought = buyer.period_watc_bought.saturating_add(amount);
+
+    let price = ctx.accounts.ask_order.price;
+    emit!(WatcTraded {
+        ask_order: ctx.accounts.ask_order.key(),
+        bid_order: ctx.accounts.bid_order.key(),
+        amount,
+        price,
+        fee,
+        slot: Clock::get()?.slot,
+    });
+
+    msg!("Traded {} WATC at {}, fee: {}.", amount, price, fee);
+    Ok(())
+}
+
+/// Withdraw an order, returning what is left in its escrow
+///
+/// # Arguments
+/// * `ctx` - Context containing the consumer, order, escrow and token accounts
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the order belongs to another consumer
+///
+/// # Returns
+/// * `Ok(())` on successful cancellation
+pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+    let agency_key = ctx.accounts.agency.key();
+    let order_id = ctx.accounts.watc_order.order_id.to_le_bytes();
+    let bump = [ctx.bumps.watc_order];
+    let signer_seeds: &[&[&[u8]]] = &[&[b"watc_order", agency_key.as_ref(), &order_id, &bump]];
+
+    let balance = ctx.accounts.escrow.amount;
+    if balance > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.escrow.to_account_info(),
+                    to: ctx.accounts.consumer_token.to_account_info(),
+                    authority: ctx.accounts.watc_order.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            balance,
+        )?;
+    }
+
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        token::CloseAccount {
+            account: ctx.accounts.escrow.to_account_info(),
+            destination: ctx.accounts.agency.to_account_info(),
+            authority: ctx.accounts.watc_order.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    msg!("Order cancelled, {} returned.", balance);
+    Ok(())
+}
+
+/// Records a new order and advances the marketplace's order count
+fn open_order(
+    watc_market: &mut WatcMarket,
+    watc_order: &mut WatcOrder,
+    consumer: &Account<Consumer>,
+    side: OrderSide,
+    amount: u64,
+    price: u64,
+) {
+    watc_order.agency = watc_market.agency;
+    watc_order.order_id = watc_market.order_count;
+    watc_order.consumer = consumer.key();
+    watc_order.side = side;
+    watc_order.price = price;
+    watc_order.remaining = amount;
+    watc_order.period_start = consumer.capacity_period_start;
+    watc_market.order_count += 1;
+}
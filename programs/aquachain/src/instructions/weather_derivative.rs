@@ -0,0 +1,417 @@
+use crate::{
+    state::{DerivativeStatus, WeatherDerivative},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Offer weather derivative instruction context
+///
+/// The **OfferWeatherDerivative** context is used by a counterparty to write rainfall cover
+/// for an agency, escrowing the payout until the derivative settles.
+///
+/// # Fields
+/// * `weather_derivative` - The PDA account that will store the derivative's terms
+/// * `escrow` - The derivative's stablecoin account holding the payout
+/// * `agency` - The agency the cover is offered to
+/// * `counterparty` - The writer of the cover, funding the escrow (must be signer)
+/// * `counterparty_settlement` - The counterparty's stablecoin token account
+/// * `settlement_mint` - The stablecoin mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for WeatherDerivative PDA
+/// * `"weather_derivative"` - Constant string
+/// * `agency` - Agency's public key
+/// * `derivative_key` - Unique identifier for the derivative
+#[derive(Accounts)]
+#[instruction(derivative_key: Pubkey)]
+pub struct OfferWeatherDerivative<'info> {
+    #[account(
+        init,
+        seeds = [
+            b"weather_derivative",
+            agency.key().as_ref(),
+            &derivative_key.as_ref()
+        ],
+        bump,
+        payer = counterparty,
+        space = DISCRIMINATOR + WeatherDerivative::INIT_SPACE
+    )]
+    pub weather_derivative: Account<'info, WeatherDerivative>,
+    #[account(
+        init,
+        payer = counterparty,
+        associated_token::mint = settlement_mint,
+        associated_token::authority = weather_derivative
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+    /// CHECK: only used as a seed, the agency accepts the offer with buy_weather_derivative
+    pub agency: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub counterparty: Signer<'info>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = counterparty)]
+    pub counterparty_settlement: Account<'info, TokenAccount>,
+    pub settlement_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Buy weather derivative instruction context
+///
+/// The **BuyWeatherDerivative** context is used by the agency to accept an offered
+/// derivative, paying its premium to the counterparty from the agency's treasury.
+///
+/// # Fields
+/// * `weather_derivative` - The offered derivative
+/// * `agency` - The agency buying the cover (must be signer)
+/// * `counterparty` - The writer of the cover
+/// * `agency_settlement` - The agency's stablecoin treasury account
+/// * `counterparty_settlement` - The counterparty's stablecoin token account
+/// * `settlement_mint` - The stablecoin mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+#[derive(Accounts)]
+pub struct BuyWeatherDerivative<'info> {
+    #[account(
+        mut,
+        has_one = agency @ CustomError::Unauthorized,
+        has_one = counterparty @ CustomError::Unauthorized,
+        has_one = settlement_mint
+    )]
+    pub weather_derivative: Account<'info, WeatherDerivative>,
+    pub agency: Signer<'info>,
+    /// CHECK: validated against the counterparty recorded on the derivative
+    pub counterparty: UncheckedAccount<'info>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = agency)]
+    pub agency_settlement: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = counterparty)]
+    pub counterparty_settlement: Account<'info, TokenAccount>,
+    pub settlement_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Report rainfall instruction context
+///
+/// The rainfall can only be reported by the oracle authority registered on the derivative.
+///
+/// # Fields
+/// * `weather_derivative` - The derivative tracking the rainfall
+/// * `oracle_authority` - The rainfall oracle posting the observation
+#[derive(Accounts)]
+pub struct ReportRainfall<'info> {
+    #[account(mut, has_one = oracle_authority @ CustomError::Unauthorized)]
+    pub weather_derivative: Account<'info, WeatherDerivative>,
+    pub oracle_authority: Signer<'info>,
+}
+
+/// Settle weather derivative instruction context
+///
+/// The **SettleWeatherDerivative** context is used by anyone, once the window has ended,
+/// to release the escrow to the agency's treasury or back to the counterparty.
+///
+/// # Fields
+/// * `weather_derivative` - The derivative being settled
+/// * `escrow` - The derivative's stablecoin account holding the payout
+/// * `agency` - The agency that bought the cover
+/// * `counterparty` - The writer of the cover, receiving the escrow's rent
+/// * `agency_settlement` - The agency's stablecoin treasury account
+/// * `counterparty_settlement` - The counterparty's stablecoin token account
+/// * `settlement_mint` - The stablecoin mint
+/// * `payer` - Any signer paying for the transaction
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for WeatherDerivative PDA
+/// * `"weather_derivative"` - Constant string
+/// * `agency` - Agency's public key
+/// * `derivative_key` - Unique identifier for the derivative
+#[derive(Accounts)]
+pub struct SettleWeatherDerivative<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"weather_derivative",
+            agency.key().as_ref(),
+            weather_derivative.derivative_key.as_ref()
+        ],
+        bump,
+        has_one = agency @ CustomError::Unauthorized,
+        has_one = counterparty @ CustomError::Unauthorized,
+        has_one = settlement_mint
+    )]
+    pub weather_derivative: Account<'info, WeatherDerivative>,
+    #[account(
+        mut,
+        associated_token::mint = settlement_mint,
+        associated_token::authority = weather_derivative
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+    /// CHECK: validated against the agency recorded on the derivative
+    pub agency: UncheckedAccount<'info>,
+    /// CHECK: validated against the counterparty recorded on the derivative
+    #[account(mut)]
+    pub counterparty: UncheckedAccount<'info>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = agency)]
+    pub agency_settlement: Account<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = settlement_mint, associated_token::authority = counterparty)]
+    pub counterparty_settlement: Account<'info, TokenAccount>,
+    pub settlement_mint: Account<'info, Mint>,
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Write rainfall cover for an agency
+///
+/// This function records the derivative's terms and moves the payout from the
+/// counterparty into the derivative's escrow. The cover stays on offer until the agency
+/// buys it, and an offer that is never bought is refunded on settlement.
+///
+/// # Arguments
+/// * `ctx` - Context containing the derivative, escrow, counterparty and token accounts
+/// * `derivative_key` - Unique identifier for the derivative
+/// * `oracle_authority` - Public key allowed to report rainfall observations
+/// * `strike` - Cumulative rainfall below which the cover pays out, in tenths of a millimetre
+/// * `window_start` - Unix timestamp from which rainfall counts
+/// * `window_end` - Unix timestamp at which rainfall stops counting
+/// * `payout` - Amount escrowed for the agency, in settlement base units
+/// * `premium` - Amount asked of the agency, in settlement base units
+///
+/// # Errors
+/// * `CustomError::InvalidAmount` - If strike or payout is zero
+/// * `CustomError::InvalidWeatherWindow` - If the window is empty or has already started
+///
+/// # Returns
+/// * `Ok(())` on successful offer
+#[allow(clippy::too_many_arguments)]
+pub fn offer_weather_derivative(
+    ctx: Context<OfferWeatherDerivative>,
+    derivative_key: Pubkey,
+    oracle_authority: Pubkey,
+    strike: u64,
+    window_start: i64,
+    window_end: i64,
+    payout: u64,
+    premium: u64,
+) -> Result<()> {
+    require!(strike > 0 && payout > 0, CustomError::InvalidAmount);
+    require!(
+        window_start < window_end && Clock::get()?.unix_timestamp < window_start,
+        CustomError::InvalidWeatherWindow
+    );
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.counterparty_settlement.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.counterparty.to_account_info(),
+            },
+        ),
+        payout,
+    )?;
+
+    let weather_derivative = &mut ctx.accounts.weather_derivative;
+    weather_derivative.agency = ctx.accounts.agency.key();
+    weather_derivative.counterparty = ctx.accounts.counterparty.key();
+    weather_derivative.derivative_key = derivative_key;
+    weather_derivative.settlement_mint = ctx.accounts.settlement_mint.key();
+    weather_derivative.oracle_authority = oracle_authority;
+    weather_derivative.strike = strike;
+    weather_derivative.window_start = window_start;
+    weather_derivative.window_end = window_end;
+    weather_derivative.payout = payout;
+    weather_derivative.premium = premium;
+    weather_derivative.cumulative_rainfall = 0;
+    weather_derivative.last_observed_at = 0;
+    weather_derivative.status = DerivativeStatus::Offered;
+
+    msg!(
+        "Weather derivative offered: {} below {} rainfall for a premium of {}.",
+        payout,
+        strike,
+        premium
+    );
+    Ok(())
+}
+
+/// Buy offered rainfall cover
+///
+/// This function pays the derivative's premium from the agency's treasury to the
+/// counterparty and starts tracking rainfall. Cover must be bought before its window
+/// starts.
+///
+/// # Arguments
+/// * `ctx` - Context containing the derivative, agency, counterparty and token accounts
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the agency or counterparty doesn't match the derivative
+/// * `CustomError::InvalidDerivativeStatus` - If the derivative is no longer on offer
+/// * `CustomError::InvalidWeatherWindow` - If the window has already started
+///
+/// # Returns
+/// * `Ok(())` on successful purchase
+pub fn buy_weather_derivative(ctx: Context<BuyWeatherDerivative>) -> Result<()> {
+    let premium = ctx.accounts.weather_derivative.premium;
+
+    require!(
+        ctx.accounts.weather_derivative.status == DerivativeStatus::Offered,
+        CustomError::InvalidDerivativeStatus
+    );
+    require!(
+        Clock::get()?.unix_timestamp < ctx.accounts.weather_derivative.window_start,
+        CustomError::InvalidWeatherWindow
+    );
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.agency_settlement.to_account_info(),
+                to: ctx.accounts.counterparty_settlement.to_account_info(),
+                authority: ctx.accounts.agency.to_account_info(),
+            },
+        ),
+        premium,
+    )?;
+
+    ctx.accounts.weather_derivative.status = DerivativeStatus::Active;
+
+    msg!("Weather derivative bought for a premium of {}.", premium);
+    Ok(())
+}
+
+/// Report rainfall observed over part of the window
+///
+/// # Arguments
+/// * `ctx` - Context containing the derivative and its oracle authority
+/// * `rainfall` - Rainfall since the previous observation, in tenths of a millimetre
+/// * `observed_at` - Unix timestamp of the observation
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the signer is not the oracle authority
+/// * `CustomError::InvalidDerivativeStatus` - If the derivative is not active
+/// * `CustomError::InvalidRainfallObservation` - If the observation is outside the window, in the future or not newer than the last
+/// * `CustomError::MathOverflow` - If the cumulative rainfall overflows
+///
+/// # Returns
+/// * `Ok(())` on successful report
+pub fn report_rainfall(
+    ctx: Context<ReportRainfall>,
+    rainfall: u64,
+    observed_at: i64,
+) -> Result<()> {
+    let weather_derivative = &mut ctx.accounts.weather_derivative;
+
+    require!(
+        weather_derivative.status == DerivativeStatus::Active,
+        CustomError::InvalidDerivativeStatus
+    );
+    require!(
+        weather_derivative.accepts_observation(observed_at)
+            && observed_at <= Clock::get()?.unix_timestamp,
+        CustomError::InvalidRainfallObservation
+    );
+
+    weather_derivative.cumulative_rainfall = weather_derivative
+        .cumulative_rainfall
+        .checked_add(rainfall)
+        .ok_or(CustomError::MathOverflow)?;
+    weather_derivative.last_observed_at = observed_at;
+
+    msg!(
+        "Rainfall of {} reported, cumulative: {}.",
+        rainfall,
+        weather_derivative.cumulative_rainfall
+    );
+    Ok(())
+}
+
+/// Settle rainfall cover once its window has ended
+///
+/// This function releases the escrow to the agency's treasury if the cover was bought and
+/// the cumulative rainfall ended below the strike, and back to the counterparty otherwise.
+/// The escrow account is then closed, returning its rent to the counterparty.
+///
+/// # Arguments
+/// * `ctx` - Context containing the derivative, escrow, parties and token accounts
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the agency or counterparty doesn't match the derivative
+/// * `CustomError::InvalidDerivativeStatus` - If the derivative is already settled
+/// * `CustomError::WeatherWindowOpen` - If the window has not ended yet
+///
+/// # Returns
+/// * `Ok(())` on successful settlement
+pub fn settle_weather_derivative(ctx: Context<SettleWeatherDerivative>) -> Result<()> {
+    let weather_derivative = &ctx.accounts.weather_derivative;
+
+    require!(
+        weather_derivative.status != DerivativeStatus::Settled,
+        CustomError::InvalidDerivativeStatus
+    );
+    require!(
+        Clock::get()?.unix_timestamp >= weather_derivative.window_end,
+        CustomError::WeatherWindowOpen
+    );
+
+    let in_the_money = weather_derivative.is_in_the_money();
+    let recipient = if in_the_money {
+        ctx.accounts.agency_settlement.to_account_info()
+    } else {
+        ctx.accounts.counterparty_settlement.to_account_info()
+    };
+
+    let agency_key = weather_derivative.agency;
+    let derivative_key = weather_derivative.derivative_key;
+    let bump = [ctx.bumps.weather_derivative];
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"weather_derivative",
+        agency_key.as_ref(),
+        derivative_key.as_ref(),
+        &bump,
+    ]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.escrow.to_account_info(),
+                to: recipient,
+                authority: ctx.accounts.weather_derivative.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        ctx.accounts.escrow.amount,
+    )?;
+
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        token::CloseAccount {
+            account: ctx.accounts.escrow.to_account_info(),
+            destination: ctx.accounts.counterparty.to_account_info(),
+            authority: ctx.accounts.weather_derivative.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    ctx.accounts.weather_derivative.status = DerivativeStatus::Settled;
+
+    msg!(
+        "Weather derivative settled to the {}.",
+        if in_the_money {
+            "agency"
+        } else {
+            "counterparty"
+        }
+    );
+    Ok(())
+}
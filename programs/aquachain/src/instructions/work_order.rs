@@ -0,0 +1,277 @@
+use crate::{
+    state::{Consumer, Role, RoleKind, Tokens, WorkOrder, WorkOrderKind, WorkOrderStatus},
+    CustomError, DISCRIMINATOR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+/// Create work order instruction context
+///
+/// The **CreateWorkOrder** context is used by the agency, or a staff key holding the
+/// Billing role, to dispatch a field operation to a worker holding the FieldOperator role.
+///
+/// # Fields
+/// * `work_order` - The PDA account that will store the work order
+/// * `consumer` - The consumer account the work is carried out for
+/// * `prerequisite` - The work order this one depends on, if any
+/// * `agency` - The agency the operation is performed for
+/// * `authority` - The agency or a staff key holding the Billing role
+/// * `role` - Role account of the authority, when it is not the agency
+/// * `worker_role` - Role account of the assigned worker, when it is not the agency
+/// * `system_program` - Required for account creation
+///
+/// # Seeds for WorkOrder PDA
+/// * `"work_order"` - Constant string
+/// * `agency` - Agency's public key
+/// * `order_key` - Unique identifier for the work order
+#[derive(Accounts)]
+#[instruction(order_key: Pubkey)]
+pub struct CreateWorkOrder<'info> {
+    #[account(
+        init,
+        seeds = [
+            b"work_order",
+            agency.key().as_ref(),
+            &order_key.as_ref()
+        ],
+        bump,
+        payer = authority,
+        space = DISCRIMINATOR + WorkOrder::INIT_SPACE
+    )]
+    pub work_order: Account<'info, WorkOrder>,
+    pub consumer: Account<'info, Consumer>,
+    pub prerequisite: Option<Account<'info, WorkOrder>>,
+    /// CHECK: validated against the signer or the agency recorded on its role
+    pub agency: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub role: Option<Account<'info, Role>>,
+    pub worker_role: Option<Account<'info, Role>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Complete work order instruction context
+///
+/// The **CompleteWorkOrder** context is used by the worker assigned to a work order to
+/// record the work as carried out.
+///
+/// # Fields
+/// * `work_order` - The PDA account of the work order
+/// * `worker` - The worker assigned to the work order (must be signer)
+///
+/// # Seeds for WorkOrder PDA
+/// * `"work_order"` - Constant string
+/// * `agency` - Agency's public key
+/// * `order_key` - Unique identifier for the work order
+#[derive(Accounts)]
+pub struct CompleteWorkOrder<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"work_order",
+            work_order.agency.as_ref(),
+            work_order.order_key.as_ref()
+        ],
+        bump
+    )]
+    pub work_order: Account<'info, WorkOrder>,
+    pub worker: Signer<'info>,
+}
+
+/// Invoice work order instruction context
+///
+/// The **InvoiceWorkOrder** context is used by the agency to bill the fee of a completed
+/// work order to its consumer, or to waive it once the work order it depends on has been
+/// completed.
+///
+/// # Fields
+/// * `work_order` - The PDA account of the completed work order
+/// * `prerequisite` - The work order this one depends on, to waive the fee
+/// * `consumer` - The consumer account the work was carried out for
+/// * `agency` - The authority that can mint tokens
+/// * `tokens` - The PDA account holding the agency's token mints and unit configuration
+/// * `consumer_wtk` - The consumer's WTK token account
+/// * `wtk_mint` - The WTK token mint
+/// * `token_program` - Required for token operations
+/// * `associated_token_program` - Required for associated token account
+///
+/// # Seeds for WorkOrder PDA
+/// * `"work_order"` - Constant string
+/// * `agency` - Agency's public key
+/// * `order_key` - Unique identifier for the work order
+///
+/// # Seeds for Tokens PDA
+/// * `"tokens"` - Constant string
+/// * `agency` - Agency's public key
+#[derive(Accounts)]
+pub struct InvoiceWorkOrder<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"work_order",
+            agency.key().as_ref(),
+            work_order.order_key.as_ref()
+        ],
+        bump,
+        has_one = consumer
+    )]
+    pub work_order: Account<'info, WorkOrder>,
+    pub prerequisite: Option<Account<'info, WorkOrder>>,
+    #[account(mut)]
+    pub consumer: Account<'info, Consumer>,
+    pub agency: Signer<'info>,
+    #[account(seeds = [b"tokens", agency.key().as_ref()], bump)]
+    pub tokens: Account<'info, Tokens>,
+    #[account(mut, associated_token::mint = wtk_mint, associated_token::authority = consumer)]
+    pub consumer_wtk: Account<'info, TokenAccount>,
+    #[account(mut, mint::authority = agency, mint::decimals = tokens.units.currency_decimals)]
+    pub wtk_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Dispatch a field operation to a worker
+///
+/// The assigned worker must be the agency or hold the FieldOperator role. When a
+/// prerequisite work order for the same consumer is passed, the new work order depends on
+/// it, and its fee is waived once the prerequisite has been completed.
+///
+/// # Arguments
+/// * `ctx` - Context containing the work order, consumer, agency and role accounts
+/// * `order_key` - Unique identifier for the work order
+/// * `kind` - Kind of field operation
+/// * `worker` - Worker key assigned to carry out the work
+/// * `fee` - Amount of WTK billed to the consumer once the work is completed
+///
+/// # Errors
+/// * `CustomError::MissingRole` - If the signer or the worker holds no permitting role
+/// * `CustomError::InvalidWorkOrder` - If the prerequisite concerns another consumer or agency
+///
+/// # Returns
+/// * `Ok(())` on successful creation
+pub fn create_work_order(
+    ctx: Context<CreateWorkOrder>,
+    order_key: Pubkey,
+    kind: WorkOrderKind,
+    worker: Pubkey,
+    fee: u64,
+) -> Result<()> {
+    let agency = ctx.accounts.agency.key();
+    Role::authorize(
+        &agency,
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        RoleKind::Billing,
+    )?;
+    Role::authorize(
+        &agency,
+        &worker,
+        ctx.accounts.worker_role.as_deref(),
+        RoleKind::FieldOperator,
+    )?;
+
+    let depends_on = match ctx.accounts.prerequisite.as_ref() {
+        Some(prerequisite) => {
+            require!(
+                prerequisite.agency == agency
+                    && prerequisite.consumer == ctx.accounts.consumer.key(),
+                CustomError::InvalidWorkOrder
+            );
+            Some(prerequisite.key())
+        }
+        None => None,
+    };
+
+    let work_order = &mut ctx.accounts.work_order;
+    work_order.agency = agency;
+    work_order.order_key = order_key;
+    work_order.consumer = ctx.accounts.consumer.key();
+    work_order.kind = kind;
+    work_order.worker = worker;
+    work_order.status = WorkOrderStatus::Open;
+    work_order.fee = fee;
+    work_order.depends_on = depends_on;
+    work_order.created_slot = Clock::get()?.slot;
+    work_order.completed_slot = 0;
+
+    msg!("{:?} work order dispatched to {}.", kind, worker);
+    Ok(())
+}
+
+/// Record a work order as carried out by its assigned worker
+///
+/// # Arguments
+/// * `ctx` - Context containing the work order and worker accounts
+///
+/// # Errors
+/// * `CustomError::Unauthorized` - If the signer is not the assigned worker
+/// * `CustomError::InvalidWorkOrderStatus` - If the work order is not open
+///
+/// # Returns
+/// * `Ok(())` on successful completion
+pub fn complete_work_order(ctx: Context<CompleteWorkOrder>) -> Result<()> {
+    let work_order = &mut ctx.accounts.work_order;
+    work_order.complete(&ctx.accounts.worker.key(), Clock::get()?.slot)?;
+
+    msg!("{:?} work order completed.", work_order.kind);
+    Ok(())
+}
+
+/// Bill the fee of a completed work order, unless it is waived
+///
+/// This function mints the work order's fee as WTK to the consumer and adds it to the
+/// consumer's outstanding debt. When the work order it depends on is passed and has been
+/// completed, such as the repair preceding a reconnection, the fee is waived instead.
+///
+/// # Arguments
+/// * `ctx` - Context containing the work order, consumer, agency and token accounts
+///
+/// # Errors
+/// * `CustomError::InvalidWorkOrderStatus` - If the work order is not completed
+/// * `CustomError::InvalidWorkOrder` - If the prerequisite is not the one it depends on
+/// * `CustomError::MathOverflow` - If the consumer's outstanding debt overflows
+///
+/// # Returns
+/// * `Ok(())` on successful invoicing
+pub fn invoice_work_order(ctx: Context<InvoiceWorkOrder>) -> Result<()> {
+    let work_order = &ctx.accounts.work_order;
+    require!(
+        work_order.status == WorkOrderStatus::Completed,
+        CustomError::InvalidWorkOrderStatus
+    );
+
+    let prerequisite = ctx
+        .accounts
+        .prerequisite
+        .as_ref()
+        .map(|prerequisite| (prerequisite.key(), &**prerequisite));
+    let waived = work_order.fee_waived(prerequisite)?;
+    let fee = if waived { 0 } else { work_order.fee };
+
+    if fee > 0 {
+        token::mint_to(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    to: ctx.accounts.consumer_wtk.to_account_info(),
+                    authority: ctx.accounts.agency.to_account_info(),
+                    mint: ctx.accounts.wtk_mint.to_account_info(),
+                },
+            ),
+            fee,
+        )?;
+        ctx.accounts.consumer.bill_water(fee, Clock::get()?.slot)?;
+    }
+
+    ctx.accounts.work_order.status = WorkOrderStatus::Invoiced;
+
+    if waived {
+        msg!("Work order fee waived.");
+    } else {
+        msg!("Work order fee of {} billed.", fee);
+    }
+    Ok(())
+}
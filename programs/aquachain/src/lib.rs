@@ -2,10 +2,20 @@ use anchor_lang::prelude::*;
 
 declare_id!("62BMhEVwxxV1RQjd9rxgyhW8ebvyxiDfRDbZRxERw8yC");
 
+pub mod errors;
+pub mod events;
 mod instructions;
 pub mod state;
 mod utils;
 
+/// Pricing functions of the program, exposed so off-chain tools bill as it does
+pub mod pricing {
+    pub use crate::instructions::{calculate_scaled_cost, calculate_total_cost};
+    pub use crate::utils::{FixedPoint, SCALE};
+    pub use aquachain_core::ChargeBounds;
+}
+
+pub use errors::CustomError;
 use instructions::*;
 use state::*;
 
@@ -13,6 +23,7 @@ use state::*;
 pub mod aquachain {
     use super::*;
 
+    /// Creates a tariff with its water and waste rates and tariff structure
     pub fn initialize_tariff(
         ctx: Context<InitializeTariff>,
         tariff_key: Pubkey,
@@ -23,6 +34,7 @@ pub mod aquachain {
         instructions::initialize_tariff(ctx, tariff_key, water_rate, waste_rate, tariff_type)
     }
 
+    /// Updates the water and waste rates of a tariff
     pub fn update_tariff_rates(
         ctx: Context<UpdateTariff>,
         tariff_key: Pubkey,
@@ -32,6 +44,7 @@ pub mod aquachain {
         instructions::update_tariff_rates(ctx, tariff_key, water_rate, waste_rate)
     }
 
+    /// Changes the tariff structure applied above contracted capacity
     pub fn update_tariff_type(
         ctx: Context<UpdateTariff>,
         tariff_key: Pubkey,
@@ -40,6 +53,7 @@ pub mod aquachain {
         instructions::update_tariff_type(ctx, tariff_key, tariff_type)
     }
 
+    /// Creates a reservoir with its current level and capacity
     pub fn initialize_reservoir(
         ctx: Context<InitializeReservoir>,
         reservoir_key: Pubkey,
@@ -49,15 +63,59 @@ pub mod aquachain {
         instructions::initialize_reservoir(ctx, reservoir_key, current_level, capacity)
     }
 
-    pub fn update_reservoir(
+    /// Records a new level for a reservoir
+    pub fn update_reservoir_level(
         ctx: Context<UpdateReservoir>,
         reservoir_key: Pubkey,
         current_level: u64,
+    ) -> Result<()> {
+        instructions::update_reservoir_level(ctx, reservoir_key, current_level)
+    }
+
+    /// Delegates level reports of a reservoir to a telemetry key
+    pub fn update_reservoir_telemetry(
+        ctx: Context<UpdateReservoir>,
+        reservoir_key: Pubkey,
+        telemetry_authority: Option<Pubkey>,
+        max_level_rate: u64,
+    ) -> Result<()> {
+        instructions::update_reservoir_telemetry(
+            ctx,
+            reservoir_key,
+            telemetry_authority,
+            max_level_rate,
+        )
+    }
+
+    /// Limits how fast a reservoir's level may change without the agency's signature
+    pub fn update_reservoir_change_limit(
+        ctx: Context<UpdateReservoir>,
+        reservoir_key: Pubkey,
+        max_change_bps_per_hour: u16,
+    ) -> Result<()> {
+        instructions::update_reservoir_change_limit(ctx, reservoir_key, max_change_bps_per_hour)
+    }
+
+    /// Sets the dam safety alert levels of a reservoir
+    pub fn update_reservoir_alerts(
+        ctx: Context<UpdateReservoir>,
+        reservoir_key: Pubkey,
+        alert_level_high: u64,
+        alert_level_low: u64,
+    ) -> Result<()> {
+        instructions::update_reservoir_alerts(ctx, reservoir_key, alert_level_high, alert_level_low)
+    }
+
+    /// Sets the capacity of a reservoir
+    pub fn update_reservoir_config(
+        ctx: Context<UpdateReservoir>,
+        reservoir_key: Pubkey,
         capacity: u64,
     ) -> Result<()> {
-        instructions::update_reservoir(ctx, reservoir_key, current_level, capacity)
+        instructions::update_reservoir_config(ctx, reservoir_key, capacity)
     }
 
+    /// Registers a consumer on a tariff and reservoir with its contracted capacity
     pub fn register_consumer(
         ctx: Context<RegisterConsumer>,
         tariff_key: Pubkey,
@@ -74,6 +132,7 @@ pub mod aquachain {
         )
     }
 
+    /// Updates a consumer's tariff, reservoir, contracted capacity and block rate
     pub fn update_consumer(
         ctx: Context<UpdateConsumer>,
         tariff_key: Pubkey,
@@ -90,6 +149,23 @@ pub mod aquachain {
         )
     }
 
+    /// Reduces a consumer's capacity without its consent, in an emergency or with a regulator
+    pub fn mandate_consumer_capacity(
+        ctx: Context<MandateConsumerCapacity>,
+        contracted_capacity: u64,
+    ) -> Result<()> {
+        instructions::mandate_consumer_capacity(ctx, contracted_capacity)
+    }
+
+    /// Pre-approves capacity changes the agency can make without the consumer's signature
+    pub fn set_capacity_approval(
+        ctx: Context<SetCapacityApproval>,
+        approval_bps: u16,
+    ) -> Result<()> {
+        instructions::set_capacity_approval(ctx, approval_bps)
+    }
+
+    /// Moves a consumer to another tariff
     pub fn update_consumer_tariff(
         ctx: Context<UpdateConsumerTariff>,
         current_tariff_key: Pubkey,
@@ -98,6 +174,7 @@ pub mod aquachain {
         instructions::update_consumer_tariff(ctx, current_tariff_key, new_tariff_key)
     }
 
+    /// Moves a consumer to another reservoir
     pub fn update_consumer_reservoir(
         ctx: Context<UpdateConsumerReservoir>,
         current_reservoir_key: Pubkey,
@@ -106,6 +183,7 @@ pub mod aquachain {
         instructions::update_consumer_reservoir(ctx, current_reservoir_key, new_reservoir_key)
     }
 
+    /// Bills a water reading, minting WTK for the cost and burning the WATC used
     pub fn use_water(
         ctx: Context<UseWater>,
         tariff_key: Pubkey,
@@ -115,6 +193,7 @@ pub mod aquachain {
         instructions::use_water(ctx, tariff_key, reservoir_key, amount)
     }
 
+    /// Bills a waste disposal, minting WST debt at the tariff's waste rate
     pub fn dispose_waste(
         ctx: Context<DisposeWaste>,
         tariff_key: Pubkey,
@@ -123,6 +202,7 @@ pub mod aquachain {
         instructions::dispose_waste(ctx, tariff_key, amount)
     }
 
+    /// Pays down a consumer's WTK debt
     pub fn pay_for_water(
         ctx: Context<PayForWater>,
         tariff_key: Pubkey,
@@ -132,35 +212,1028 @@ pub mod aquachain {
         instructions::pay_for_water(ctx, tariff_key, reservoir_key, amount)
     }
 
+    /// Pays down a consumer's WST debt
     pub fn pay_for_waste(ctx: Context<PayForWaste>, tariff_key: Pubkey, amount: u64) -> Result<()> {
         instructions::pay_for_waste(ctx, tariff_key, amount)
     }
 
+    /// Pays down a consumer's WTK debt through the delegation approved at registration
+    pub fn pay_for_water_delegated(ctx: Context<PayForWaterDelegated>, amount: u64) -> Result<()> {
+        instructions::pay_for_water_delegated(ctx, amount)
+    }
+
+    /// Pays down a consumer's WST debt through the delegation approved at registration
+    pub fn pay_for_waste_delegated(ctx: Context<PayForWasteDelegated>, amount: u64) -> Result<()> {
+        instructions::pay_for_waste_delegated(ctx, amount)
+    }
+
+    /// Registers the agency's WTK, WATC and WST mints and its unit configuration
     pub fn initialize_tokens(
         ctx: Context<InitializeTokens>,
         water_token: Pubkey,
         water_capacity_token: Pubkey,
         waste_token: Pubkey,
+        units: UnitConfig,
     ) -> Result<()> {
-        instructions::initialize_tokens(ctx, water_token, water_capacity_token, waste_token)
+        instructions::initialize_tokens(ctx, water_token, water_capacity_token, waste_token, units)
+    }
+
+    /// Sets the ISO 4217 currency a tariff's rates are posted in
+    pub fn update_tariff_currency(
+        ctx: Context<UpdateTariff>,
+        tariff_key: Pubkey,
+        currency_code: [u8; 3],
+    ) -> Result<()> {
+        instructions::update_tariff_currency(ctx, tariff_key, currency_code)
+    }
+
+    /// Creates the exchange rate oracle for a local currency
+    pub fn initialize_fx_oracle(
+        ctx: Context<InitializeFxOracle>,
+        currency_code: [u8; 3],
+        authority: Pubkey,
+        max_staleness_slots: u64,
+    ) -> Result<()> {
+        instructions::initialize_fx_oracle(ctx, currency_code, authority, max_staleness_slots)
+    }
+
+    /// Publishes a new exchange rate to an oracle
+    pub fn update_fx_rate(ctx: Context<UpdateFxRate>, rate: u64) -> Result<()> {
+        instructions::update_fx_rate(ctx, rate)
+    }
+
+    /// Settles WTK debt in the settlement currency at the oracle rate
+    pub fn settle_water_debt(
+        ctx: Context<SettleWaterDebt>,
+        tariff_key: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::settle_water_debt(ctx, tariff_key, amount)
+    }
+
+    /// Sets the guarantor wallet backing a consumer's debt, up to a limit
+    pub fn set_guarantor(ctx: Context<SetGuarantor>, guarantee_limit: u64) -> Result<()> {
+        instructions::set_guarantor(ctx, guarantee_limit)
+    }
+
+    /// Collects overdue WTK debt from a consumer's guarantor
+    pub fn claim_from_guarantor(
+        ctx: Context<ClaimFromGuarantor>,
+        tariff_key: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::claim_from_guarantor(ctx, tariff_key, amount)
+    }
+
+    /// Links a tenant sub-consumer to a landlord consumer with a billing share
+    pub fn link_sub_consumer(ctx: Context<LinkSubConsumer>, tenant_share_bps: u16) -> Result<()> {
+        instructions::link_sub_consumer(ctx, tenant_share_bps)
+    }
+
+    /// Removes the link between a sub-consumer and its landlord
+    pub fn unlink_sub_consumer(ctx: Context<LinkSubConsumer>) -> Result<()> {
+        instructions::unlink_sub_consumer(ctx)
+    }
+
+    /// Bills a sub-consumer's reading, splitting the charge with its landlord
+    pub fn use_water_split(
+        ctx: Context<UseWaterSplit>,
+        tariff_key: Pubkey,
+        reservoir_key: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::use_water_split(ctx, tariff_key, reservoir_key, amount)
+    }
+
+    /// Records a new account holder for a consumer account on a change of occupancy
+    pub fn reassign_account_holder(
+        ctx: Context<ReassignAccountHolder>,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        instructions::reassign_account_holder(ctx, new_owner)
+    }
+
+    /// Opts a consumer into automatic collection of water debt from a stablecoin account
+    pub fn enable_autopay(ctx: Context<EnableAutopay>, cap: u64) -> Result<()> {
+        instructions::enable_autopay(ctx, cap)
+    }
+
+    /// Collects a consumer's water debt from their auto-pay stablecoin account
+    pub fn collect_autopay(ctx: Context<CollectAutopay>, tariff_key: Pubkey) -> Result<()> {
+        instructions::collect_autopay(ctx, tariff_key)
+    }
+
+    /// Writes off part of a consumer's water or waste debt to correct an overcharge
+    pub fn issue_credit(
+        ctx: Context<IssueCredit>,
+        note_key: Pubkey,
+        kind: DebtKind,
+        amount: u64,
+        reason: CreditReason,
+    ) -> Result<()> {
+        instructions::issue_credit(ctx, note_key, kind, amount, reason)
+    }
+
+    /// Adjusts a consumer's contracted capacity, minting or burning WATC
+    pub fn adjust_capacity(
+        ctx: Context<AdjustCapacity>,
+        delta: i64,
+        reason: AdjustmentReason,
+    ) -> Result<()> {
+        instructions::adjust_capacity(ctx, delta, reason)
+    }
+
+    /// Corrects the charge of a past billing period from its actual volume
+    pub fn rebill_period(
+        ctx: Context<RebillPeriod>,
+        tariff_key: Pubkey,
+        reservoir_key: Pubkey,
+        period_slot: u64,
+        actual_volume: u64,
+        billed_amount: u64,
+    ) -> Result<()> {
+        instructions::rebill_period(
+            ctx,
+            tariff_key,
+            reservoir_key,
+            period_slot,
+            actual_volume,
+            billed_amount,
+        )
+    }
+
+    /// Bills an estimated reading when no meter reading is available
+    pub fn bill_estimated_usage(
+        ctx: Context<BillEstimatedUsage>,
+        tariff_key: Pubkey,
+        reservoir_key: Pubkey,
+    ) -> Result<()> {
+        instructions::bill_estimated_usage(ctx, tariff_key, reservoir_key)
+    }
+
+    /// Reconciles estimated charges against an actual meter reading
+    pub fn true_up(
+        ctx: Context<UseWater>,
+        tariff_key: Pubkey,
+        reservoir_key: Pubkey,
+        actual_volume: u64,
+    ) -> Result<()> {
+        instructions::true_up(ctx, tariff_key, reservoir_key, actual_volume)
+    }
+
+    /// Enables or disables levelized budget billing for a consumer
+    pub fn set_budget_billing(
+        ctx: Context<BudgetBilling>,
+        enabled: bool,
+        budget_amount: u64,
+    ) -> Result<()> {
+        instructions::set_budget_billing(ctx, enabled, budget_amount)
+    }
+
+    /// Pays the consumer's levelized budget billing instalment
+    pub fn pay_invoice(ctx: Context<PayBudgetInvoice>) -> Result<()> {
+        instructions::pay_invoice(ctx)
+    }
+
+    /// Settles the difference between levelized payments and actual charges
+    pub fn reconcile_budget_billing(ctx: Context<PayBudgetInvoice>) -> Result<()> {
+        instructions::reconcile_budget_billing(ctx)
+    }
+
+    /// Sets how long debt must be overdue before punitive action
+    pub fn update_tariff_grace_period(
+        ctx: Context<UpdateTariff>,
+        tariff_key: Pubkey,
+        grace_period_slots: u64,
+    ) -> Result<()> {
+        instructions::update_tariff_grace_period(ctx, tariff_key, grace_period_slots)
+    }
+
+    /// Publishes a Merkle tree of WATC allocations claimable by consumers
+    pub fn create_allocation_tree(
+        ctx: Context<CreateAllocationTree>,
+        tree_key: Pubkey,
+        merkle_root: [u8; 32],
+        tariff_key: Pubkey,
+        reservoir_key: Pubkey,
+        block_rate: u64,
+        total_capacity: u64,
+    ) -> Result<()> {
+        instructions::create_allocation_tree(
+            ctx,
+            tree_key,
+            merkle_root,
+            tariff_key,
+            reservoir_key,
+            block_rate,
+            total_capacity,
+        )
+    }
+
+    /// Claims a WATC allocation with a Merkle proof, registering the claimant
+    pub fn claim_allocation(
+        ctx: Context<ClaimAllocation>,
+        tree_key: Pubkey,
+        contracted_capacity: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::claim_allocation(ctx, tree_key, contracted_capacity, proof)
+    }
+
+    /// Creates a Merkle tree that compressed consumer accounts are appended to
+    pub fn initialize_consumer_tree(
+        ctx: Context<InitializeConsumerTree>,
+        tree_key: Pubkey,
+    ) -> Result<()> {
+        instructions::initialize_consumer_tree(ctx, tree_key)
+    }
+
+    /// Closes a settled consumer account, appending its data to a consumer tree
+    pub fn compress_consumer(ctx: Context<CompressConsumer>, tree_key: Pubkey) -> Result<()> {
+        instructions::compress_consumer(ctx, tree_key)
+    }
+
+    /// Restores a compressed consumer account from a Merkle proof
+    pub fn decompress_consumer(
+        ctx: Context<DecompressConsumer>,
+        tree_key: Pubkey,
+        leaf_index: u64,
+        data: Vec<u8>,
+        root: [u8; 32],
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::decompress_consumer(ctx, tree_key, leaf_index, data, root, proof)
+    }
+
+    /// Sets the number of slots after which consumers' capacity is refreshed
+    pub fn update_tariff_billing_period(
+        ctx: Context<UpdateTariff>,
+        tariff_key: Pubkey,
+        billing_period_slots: u64,
+    ) -> Result<()> {
+        instructions::update_tariff_billing_period(ctx, tariff_key, billing_period_slots)
+    }
+
+    /// Tops a consumer's WATC up to its contracted capacity once a billing period has ended
+    pub fn refresh_capacity(ctx: Context<RefreshCapacity>, tariff_key: Pubkey) -> Result<()> {
+        instructions::refresh_capacity(ctx, tariff_key)
+    }
+
+    /// Sets the share of unused capacity carried into the next billing period
+    pub fn update_tariff_capacity_rollover(
+        ctx: Context<UpdateTariff>,
+        tariff_key: Pubkey,
+        capacity_rollover_bps: u16,
+    ) -> Result<()> {
+        instructions::update_tariff_capacity_rollover(ctx, tariff_key, capacity_rollover_bps)
+    }
+
+    /// Sets the volume per billing period charged at the water rate
+    pub fn update_tariff_block_threshold(
+        ctx: Context<UpdateTariff>,
+        tariff_key: Pubkey,
+        block_threshold: u64,
+    ) -> Result<()> {
+        instructions::update_tariff_block_threshold(ctx, tariff_key, block_threshold)
+    }
+
+    /// Sets the lifeline volume allowed per household member each billing period
+    pub fn update_tariff_per_capita_allowance(
+        ctx: Context<UpdateTariff>,
+        tariff_key: Pubkey,
+        per_capita_allowance: u64,
+    ) -> Result<()> {
+        instructions::update_tariff_per_capita_allowance(ctx, tariff_key, per_capita_allowance)
+    }
+
+    /// Sets the usage baseline and streak length earning conservation certificates
+    pub fn update_tariff_conservation(
+        ctx: Context<UpdateTariff>,
+        tariff_key: Pubkey,
+        conservation_baseline_bps: u16,
+        conservation_periods: u16,
+    ) -> Result<()> {
+        instructions::update_tariff_conservation(
+            ctx,
+            tariff_key,
+            conservation_baseline_bps,
+            conservation_periods,
+        )
+    }
+
+    /// Sets the rate charged for bulk tanker deliveries under a tariff
+    pub fn update_tariff_bulk_rate(
+        ctx: Context<UpdateTariff>,
+        tariff_key: Pubkey,
+        bulk_rate: u64,
+    ) -> Result<()> {
+        instructions::update_tariff_bulk_rate(ctx, tariff_key, bulk_rate)
+    }
+
+    /// Sets the number of people in a consumer's household
+    pub fn update_consumer_household(
+        ctx: Context<UpdateConsumerHousehold>,
+        tariff_key: Pubkey,
+        household_size: u16,
+    ) -> Result<()> {
+        instructions::update_consumer_household(ctx, tariff_key, household_size)
+    }
+
+    /// Extends a consumer's fixed-term supply contract
+    pub fn renew_contract(
+        ctx: Context<RenewContract>,
+        tariff_key: Pubkey,
+        contract_expiry_slot: u64,
+    ) -> Result<()> {
+        instructions::renew_contract(ctx, tariff_key, contract_expiry_slot)
+    }
+
+    /// Reassigns a batch of consumers, passed as remaining accounts, to a new tariff
+    pub fn migrate_tariff_consumers<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateTariffConsumers<'info>>,
+        current_tariff_key: Pubkey,
+        new_tariff_key: Pubkey,
+    ) -> Result<()> {
+        instructions::migrate_tariff_consumers(ctx, current_tariff_key, new_tariff_key)
+    }
+
+    /// Grows a consumer account written by an older version to the current layout
+    pub fn grow_consumer(ctx: Context<GrowConsumer>) -> Result<()> {
+        instructions::grow_consumer(ctx)
+    }
+
+    /// Grows a tariff account written by an older version to the current layout
+    pub fn grow_tariff(ctx: Context<GrowTariff>, tariff_key: Pubkey) -> Result<()> {
+        instructions::grow_tariff(ctx, tariff_key)
+    }
+
+    /// Grows a reservoir account written by an older version to the current layout
+    pub fn grow_reservoir(ctx: Context<GrowReservoir>, reservoir_key: Pubkey) -> Result<()> {
+        instructions::grow_reservoir(ctx, reservoir_key)
+    }
+
+    /// Grows a tokens account written by an older version to the current layout
+    pub fn grow_tokens(ctx: Context<GrowTokens>, units: UnitConfig) -> Result<()> {
+        instructions::grow_tokens(ctx, units)
+    }
+
+    /// Bills a batch of meter readings for consumers passed as remaining accounts
+    pub fn report_usage_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReportUsageBatch<'info>>,
+        tariff_key: Pubkey,
+        reservoir_key: Pubkey,
+        readings: Vec<MeterReading>,
+    ) -> Result<()> {
+        instructions::report_usage_batch(ctx, tariff_key, reservoir_key, readings)
+    }
+
+    /// Closes a reservoir once no consumers are assigned to it
+    pub fn decommission_reservoir(
+        ctx: Context<DecommissionReservoir>,
+        reservoir_key: Pubkey,
+    ) -> Result<()> {
+        instructions::decommission_reservoir(ctx, reservoir_key)
+    }
+
+    /// Closes a tariff once no consumers are assigned to it
+    pub fn close_tariff(ctx: Context<CloseTariff>, tariff_key: Pubkey) -> Result<()> {
+        instructions::close_tariff(ctx, tariff_key)
+    }
+
+    /// Anchors the SHA-256 hash of an off-chain document in the agency's audit log
+    pub fn record_audit(
+        ctx: Context<RecordAudit>,
+        document_hash: [u8; 32],
+        category: AuditCategory,
+        subject: Pubkey,
+    ) -> Result<()> {
+        instructions::record_audit(ctx, document_hash, category, subject)
+    }
+
+    /// Binds a consumer to the hash of its off-chain KYC record
+    pub fn update_consumer_identity(
+        ctx: Context<UpdateConsumerIdentity>,
+        tariff_key: Pubkey,
+        identity_hash: [u8; 32],
+        identity_uri: Option<String>,
+    ) -> Result<()> {
+        instructions::update_consumer_identity(ctx, tariff_key, identity_hash, identity_uri)
+    }
+
+    /// Grants a staff key a role within the agency
+    pub fn grant_role(ctx: Context<GrantRole>, member: Pubkey, kind: RoleKind) -> Result<()> {
+        instructions::grant_role(ctx, member, kind)
+    }
+
+    /// Revokes a staff key's role within the agency
+    pub fn revoke_role(ctx: Context<RevokeRole>, member: Pubkey) -> Result<()> {
+        instructions::revoke_role(ctx, member)
+    }
+
+    /// Appoints the regulator overseeing the agency, once
+    pub fn appoint_regulator(ctx: Context<AppointRegulator>) -> Result<()> {
+        instructions::appoint_regulator(ctx)
+    }
+
+    /// Grants a key the Regulator role on behalf of the agency's regulator
+    pub fn grant_regulator_role(ctx: Context<GrantRegulatorRole>, member: Pubkey) -> Result<()> {
+        instructions::grant_regulator_role(ctx, member)
+    }
+
+    /// Revokes a key's Regulator role on behalf of the agency's regulator
+    pub fn revoke_regulator_role(ctx: Context<RevokeRegulatorRole>, member: Pubkey) -> Result<()> {
+        instructions::revoke_regulator_role(ctx, member)
+    }
+
+    /// Delegates meter readings to a short-lived device key
+    pub fn register_session_key(
+        ctx: Context<RegisterSessionKey>,
+        session_key: Pubkey,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::register_session_key(ctx, session_key, expires_at)
+    }
+
+    /// Withdraws a meter device's session key
+    pub fn revoke_session_key(ctx: Context<RevokeSessionKey>, session_key: Pubkey) -> Result<()> {
+        instructions::revoke_session_key(ctx, session_key)
     }
-}
 
-// Define custom errors
-#[error_code]
-pub enum CustomError {
-    #[msg("Invalid contracted capacity: must be greater than zero.")]
-    InvalidCapacity,
-    #[msg("Invalid rate: must be greater than zero.")]
-    InvalidRate,
-    #[msg("Invalid reservoir level: must be greater than zero and not exceed capacity.")]
-    InvalidReservoirLevel,
-    #[msg("Invalid reservoir capacity: must be greater than zero.")]
-    InvalidReservoirCapacity,
-    #[msg("Invalid amount: must be greater than zero.")]
-    InvalidAmount,
-    #[msg("Unauthorized: only the owner can perform this action.")]
-    Unauthorized,
-    #[msg("Overpaid: payment exceeds the necessary amount.")]
-    OverPayment
+    /// Records a meter register value signed by a device session key
+    pub fn submit_reading(ctx: Context<SubmitReading>, reading: u64) -> Result<()> {
+        instructions::submit_reading(ctx, reading)
+    }
+
+    /// Records a meter reading from a payload signed by the device, relayed by anyone
+    pub fn relay_reading(
+        ctx: Context<RelayReading>,
+        device: Pubkey,
+        reading: u64,
+        timestamp: i64,
+    ) -> Result<()> {
+        instructions::relay_reading(ctx, device, reading, timestamp)
+    }
+
+    /// Records a meter reading from a payload signed with a device's secp256k1 key
+    pub fn relay_reading_secp256k1(
+        ctx: Context<RelayReadingSecp256k1>,
+        eth_address: [u8; 20],
+        reading: u64,
+        timestamp: i64,
+    ) -> Result<()> {
+        instructions::relay_reading_secp256k1(ctx, eth_address, reading, timestamp)
+    }
+
+    /// Sets the geohash prefix of the zone a reservoir serves
+    pub fn update_reservoir_zone(
+        ctx: Context<UpdateReservoir>,
+        reservoir_key: Pubkey,
+        zone_geohash: [u8; 12],
+    ) -> Result<()> {
+        instructions::update_reservoir_zone(ctx, reservoir_key, zone_geohash)
+    }
+
+    /// Sets the river basin a reservoir lies in
+    pub fn update_reservoir_basin(
+        ctx: Context<UpdateReservoir>,
+        reservoir_key: Pubkey,
+        basin_id: u32,
+    ) -> Result<()> {
+        instructions::update_reservoir_basin(ctx, reservoir_key, basin_id)
+    }
+
+    /// Sets the energy used to pump water to the zone a reservoir serves
+    pub fn update_reservoir_pumping(
+        ctx: Context<UpdateReservoir>,
+        reservoir_key: Pubkey,
+        pumping_intensity: u64,
+    ) -> Result<()> {
+        instructions::update_reservoir_pumping(ctx, reservoir_key, pumping_intensity)
+    }
+
+    /// Sets the emissions attributed to water delivered to the zone a reservoir serves
+    pub fn update_reservoir_emissions(
+        ctx: Context<UpdateReservoir>,
+        reservoir_key: Pubkey,
+        emissions_factor: u64,
+    ) -> Result<()> {
+        instructions::update_reservoir_emissions(ctx, reservoir_key, emissions_factor)
+    }
+
+    /// Sets the geohash of a consumer's meter location
+    pub fn update_consumer_location(
+        ctx: Context<UpdateConsumerLocation>,
+        tariff_key: Pubkey,
+        geohash: [u8; 12],
+    ) -> Result<()> {
+        instructions::update_consumer_location(ctx, tariff_key, geohash)
+    }
+
+    /// Creates or reconfigures a reservoir's drought insurance pool
+    pub fn configure_drought_insurance(
+        ctx: Context<ConfigureDroughtInsurance>,
+        reservoir_key: Pubkey,
+        premium: u64,
+        payout_bps: u16,
+        trigger_level_bps: u16,
+    ) -> Result<()> {
+        instructions::configure_drought_insurance(
+            ctx,
+            reservoir_key,
+            premium,
+            payout_bps,
+            trigger_level_bps,
+        )
+    }
+
+    /// Opts a consumer into its reservoir's drought insurance pool
+    pub fn join_drought_insurance(
+        ctx: Context<JoinDroughtInsurance>,
+        reservoir_key: Pubkey,
+    ) -> Result<()> {
+        instructions::join_drought_insurance(ctx, reservoir_key)
+    }
+
+    /// Opts a consumer out of its drought insurance pool
+    pub fn leave_drought_insurance(ctx: Context<LeaveDroughtInsurance>) -> Result<()> {
+        instructions::leave_drought_insurance(ctx)
+    }
+
+    /// Bills a covered consumer the drought premium for the current capacity period
+    pub fn collect_drought_premium(ctx: Context<CollectDroughtPremium>) -> Result<()> {
+        instructions::collect_drought_premium(ctx)
+    }
+
+    /// Writes rainfall cover for an agency, escrowing the payout
+    #[allow(clippy::too_many_arguments)]
+    pub fn offer_weather_derivative(
+        ctx: Context<OfferWeatherDerivative>,
+        derivative_key: Pubkey,
+        oracle_authority: Pubkey,
+        strike: u64,
+        window_start: i64,
+        window_end: i64,
+        payout: u64,
+        premium: u64,
+    ) -> Result<()> {
+        instructions::offer_weather_derivative(
+            ctx,
+            derivative_key,
+            oracle_authority,
+            strike,
+            window_start,
+            window_end,
+            payout,
+            premium,
+        )
+    }
+
+    /// Buys offered rainfall cover, paying the premium from the agency's treasury
+    pub fn buy_weather_derivative(ctx: Context<BuyWeatherDerivative>) -> Result<()> {
+        instructions::buy_weather_derivative(ctx)
+    }
+
+    /// Reports rainfall observed over a weather derivative's window
+    pub fn report_rainfall(
+        ctx: Context<ReportRainfall>,
+        rainfall: u64,
+        observed_at: i64,
+    ) -> Result<()> {
+        instructions::report_rainfall(ctx, rainfall, observed_at)
+    }
+
+    /// Releases a weather derivative's escrow once its window has ended
+    pub fn settle_weather_derivative(ctx: Context<SettleWeatherDerivative>) -> Result<()> {
+        instructions::settle_weather_derivative(ctx)
+    }
+
+    /// Locks the tariff's current water rate for capacity delivered next period
+    pub fn open_forward_contract(
+        ctx: Context<OpenForwardContract>,
+        tariff_key: Pubkey,
+        capacity: u64,
+        premium: u64,
+    ) -> Result<()> {
+        instructions::open_forward_contract(ctx, tariff_key, capacity, premium)
+    }
+
+    /// Delivers capacity bought forward, billed at the locked rate
+    pub fn settle_forward_contract(ctx: Context<SettleForwardContract>) -> Result<()> {
+        instructions::settle_forward_contract(ctx)
+    }
+
+    /// Opens or reconfigures the agency's WATC marketplace
+    pub fn configure_watc_market(
+        ctx: Context<ConfigureWatcMarket>,
+        fee_bps: u16,
+        trade_cap: u64,
+    ) -> Result<()> {
+        instructions::configure_watc_market(ctx, fee_bps, trade_cap)
+    }
+
+    /// Offers unused WATC for sale, escrowing it in the order
+    pub fn place_ask(ctx: Context<PlaceAsk>, amount: u64, price: u64) -> Result<()> {
+        instructions::place_ask(ctx, amount, price)
+    }
+
+    /// Bids for WATC, escrowing the payment in the order
+    pub fn place_bid(ctx: Context<PlaceBid>, amount: u64, price: u64) -> Result<()> {
+        instructions::place_bid(ctx, amount, price)
+    }
+
+    /// Trades WATC between a crossing ask and bid
+    pub fn match_orders(ctx: Context<MatchOrders>) -> Result<()> {
+        instructions::match_orders(ctx)
+    }
+
+    /// Withdraws an order, returning what is left in its escrow
+    pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+        instructions::cancel_order(ctx)
+    }
+
+    /// Lists a perk consumers can redeem with AquaCoin
+    pub fn create_redemption_offer(
+        ctx: Context<CreateRedemptionOffer>,
+        offer_key: Pubkey,
+        name: String,
+        price: u64,
+        quantity: u64,
+    ) -> Result<()> {
+        instructions::create_redemption_offer(ctx, offer_key, name, price, quantity)
+    }
+
+    /// Burns AquaCoin for a perk, issuing a claim voucher
+    pub fn redeem_offer(ctx: Context<RedeemOffer>) -> Result<()> {
+        instructions::redeem_offer(ctx)
+    }
+
+    /// Mints a non-transferable certificate for a completed conservation streak
+    pub fn award_conservation_certificate(
+        ctx: Context<AwardConservationCertificate>,
+        tariff_key: Pubkey,
+    ) -> Result<()> {
+        instructions::award_conservation_certificate(ctx, tariff_key)
+    }
+
+    /// Turns a consumer into a community standpipe with a shared prepaid balance
+    pub fn register_communal_point(ctx: Context<RegisterCommunalPoint>) -> Result<()> {
+        instructions::register_communal_point(ctx)
+    }
+
+    /// Registers a wallet allowed to top up a standpipe's shared balance
+    pub fn add_communal_member(ctx: Context<AddCommunalMember>, wallet: Pubkey) -> Result<()> {
+        instructions::add_communal_member(ctx, wallet)
+    }
+
+    /// Tops up a standpipe's shared balance, paying in the settlement stablecoin
+    pub fn top_up_communal_point(ctx: Context<TopUpCommunalPoint>, amount: u64) -> Result<()> {
+        instructions::top_up_communal_point(ctx, amount)
+    }
+
+    /// Tops up a consumer's prepaid balance, paying in the settlement stablecoin
+    pub fn top_up_prepaid(ctx: Context<TopUpPrepaid>, amount: u64) -> Result<()> {
+        instructions::top_up_prepaid(ctx, amount)
+    }
+
+    /// Licenses a mobile-money gateway to credit prepaid balances
+    pub fn register_payment_gateway(
+        ctx: Context<RegisterPaymentGateway>,
+        gateway_key: Pubkey,
+        attestor: Pubkey,
+    ) -> Result<()> {
+        instructions::register_payment_gateway(ctx, gateway_key, attestor)
+    }
+
+    /// Rotates a gateway's attestor key or suspends and reinstates its licence
+    pub fn update_payment_gateway(
+        ctx: Context<UpdatePaymentGateway>,
+        attestor: Pubkey,
+        active: bool,
+    ) -> Result<()> {
+        instructions::update_payment_gateway(ctx, attestor, active)
+    }
+
+    /// Credits a mobile-money payment attested by a gateway to a consumer's prepaid balance
+    pub fn record_offchain_payment(
+        ctx: Context<RecordOffchainPayment>,
+        payment_id: [u8; 32],
+        amount: u64,
+    ) -> Result<()> {
+        instructions::record_offchain_payment(ctx, payment_id, amount)
+    }
+
+    /// Dispatches a field operation to a worker holding the FieldOperator role
+    pub fn create_work_order(
+        ctx: Context<CreateWorkOrder>,
+        order_key: Pubkey,
+        kind: WorkOrderKind,
+        worker: Pubkey,
+        fee: u64,
+    ) -> Result<()> {
+        instructions::create_work_order(ctx, order_key, kind, worker, fee)
+    }
+
+    /// Records a work order as carried out by its assigned worker
+    pub fn complete_work_order(ctx: Context<CompleteWorkOrder>) -> Result<()> {
+        instructions::complete_work_order(ctx)
+    }
+
+    /// Bills a completed work order's fee, waived once the order it depends on is done
+    pub fn invoice_work_order(ctx: Context<InvoiceWorkOrder>) -> Result<()> {
+        instructions::invoice_work_order(ctx)
+    }
+
+    /// Sets the agency's complaint resolution SLA and goodwill credit
+    pub fn configure_complaint_policy(
+        ctx: Context<ConfigureComplaintPolicy>,
+        sla_slots: u64,
+        goodwill_amount: u64,
+    ) -> Result<()> {
+        instructions::configure_complaint_policy(ctx, sla_slots, goodwill_amount)
+    }
+
+    /// Opens a complaint with the consumer's agency
+    pub fn file_complaint(
+        ctx: Context<FileComplaint>,
+        complaint_key: Pubkey,
+        category: ComplaintCategory,
+    ) -> Result<()> {
+        instructions::file_complaint(ctx, complaint_key, category)
+    }
+
+    /// Resolves a complaint, crediting goodwill in AquaCoin if the SLA was breached
+    pub fn resolve_complaint(ctx: Context<ResolveComplaint>) -> Result<()> {
+        instructions::resolve_complaint(ctx)
+    }
+
+    /// Bills a consumer for water dispensed by a tanker at the tariff's bulk rate
+    pub fn dispense_bulk(
+        ctx: Context<DispenseBulk>,
+        tariff_key: Pubkey,
+        reservoir_key: Pubkey,
+        volume: u64,
+    ) -> Result<()> {
+        instructions::dispense_bulk(ctx, tariff_key, reservoir_key, volume)
+    }
+
+    /// Sets the crop seasons and out-of-turn surcharge of an agricultural consumer
+    pub fn set_irrigation_schedule(
+        ctx: Context<SetIrrigationSchedule>,
+        surcharge_bps: u16,
+        seasons: Vec<CropSeason>,
+    ) -> Result<()> {
+        instructions::set_irrigation_schedule(ctx, surcharge_bps, seasons)
+    }
+
+    /// Conveys water between reservoirs, with regulator approval across basins
+    pub fn convey_water(
+        ctx: Context<ConveyWater>,
+        source_key: Pubkey,
+        destination_key: Pubkey,
+        volume: u64,
+        permit_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::convey_water(ctx, source_key, destination_key, volume, permit_hash)
+    }
+
+    /// Registers the agency's electricity price feed
+    pub fn initialize_energy_oracle(
+        ctx: Context<InitializeEnergyOracle>,
+        authority: Pubkey,
+        max_staleness_slots: u64,
+    ) -> Result<()> {
+        instructions::initialize_energy_oracle(ctx, authority, max_staleness_slots)
+    }
+
+    /// Posts a new electricity price
+    pub fn update_energy_price(ctx: Context<UpdateEnergyPrice>, price: u64) -> Result<()> {
+        instructions::update_energy_price(ctx, price)
+    }
+
+    /// Sets the carbon-credit token the agency retires against its emissions
+    pub fn configure_carbon_offsets(
+        ctx: Context<ConfigureCarbonOffsets>,
+        grams_per_credit: u64,
+    ) -> Result<()> {
+        instructions::configure_carbon_offsets(ctx, grams_per_credit)
+    }
+
+    /// Burns carbon credits against the agency's recorded emissions
+    pub fn retire_carbon_offsets(ctx: Context<RetireCarbonOffsets>, amount: u64) -> Result<()> {
+        instructions::retire_carbon_offsets(ctx, amount)
+    }
+
+    /// Sets the curve of level bands pricing a reservoir's seasonal block rate
+    pub fn update_reservoir_scarcity_curve(
+        ctx: Context<UpdateReservoir>,
+        reservoir_key: Pubkey,
+        scarcity_curve: Vec<ScarcityBand>,
+    ) -> Result<()> {
+        instructions::update_reservoir_scarcity_curve(ctx, reservoir_key, scarcity_curve)
+    }
+
+    /// Sets how strongly reported levels are smoothed before pricing seasonal tariffs
+    pub fn update_reservoir_smoothing(
+        ctx: Context<UpdateReservoir>,
+        reservoir_key: Pubkey,
+        smoothing_bps: u16,
+    ) -> Result<()> {
+        instructions::update_reservoir_smoothing(ctx, reservoir_key, smoothing_bps)
+    }
+
+    /// Posts the projected levels of a reservoir over the coming periods
+    pub fn post_level_forecast(
+        ctx: Context<PostLevelForecast>,
+        reservoir_key: Pubkey,
+        period_slots: u64,
+        levels: Vec<u64>,
+    ) -> Result<()> {
+        instructions::post_level_forecast(ctx, reservoir_key, period_slots, levels)
+    }
+
+    /// Chooses whether a tariff prices seasonal usage on level forecasts
+    pub fn update_tariff_forecast_pricing(
+        ctx: Context<UpdateTariff>,
+        tariff_key: Pubkey,
+        forecast_pricing: bool,
+    ) -> Result<()> {
+        instructions::update_tariff_forecast_pricing(ctx, tariff_key, forecast_pricing)
+    }
+
+    /// Sets the regulated multiplier cap and minimum charge of a tariff
+    pub fn update_tariff_bounds(
+        ctx: Context<UpdateTariff>,
+        tariff_key: Pubkey,
+        max_multiplier: u64,
+        min_charge: u64,
+    ) -> Result<()> {
+        instructions::update_tariff_bounds(ctx, tariff_key, max_multiplier, min_charge)
+    }
+
+    /// Recomputes a consumer's last charge and flags it if it was mischarged
+    pub fn verify_charge(ctx: Context<VerifyCharge>) -> Result<()> {
+        instructions::verify_charge(ctx)
+    }
+
+    /// Escrows a stablecoin bond against mischarges
+    pub fn post_agency_bond(
+        ctx: Context<PostAgencyBond>,
+        amount: u64,
+        slash_bps: u16,
+    ) -> Result<()> {
+        instructions::post_agency_bond(ctx, amount, slash_bps)
+    }
+
+    /// Confirms or dismisses a flagged charge, compensating the consumer from the bond
+    pub fn resolve_flagged_charge(
+        ctx: Context<ResolveFlaggedCharge>,
+        confirmed: bool,
+    ) -> Result<()> {
+        instructions::resolve_flagged_charge(ctx, confirmed)
+    }
+
+    /// Adds a level gauge to a reservoir's sensor network
+    pub fn register_sensor(
+        ctx: Context<RegisterSensor>,
+        reservoir_key: Pubkey,
+        sensor_key: Pubkey,
+        sensor_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::register_sensor(ctx, reservoir_key, sensor_key, sensor_authority)
+    }
+
+    /// Records a sensor's reading of its reservoir level
+    pub fn submit_sensor_reading(ctx: Context<SubmitSensorReading>, level: u64) -> Result<()> {
+        instructions::submit_sensor_reading(ctx, level)
+    }
+
+    /// Sets a reservoir's level to the median of its sensors' recent readings
+    pub fn aggregate_sensor_levels<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AggregateSensorLevels<'info>>,
+        reservoir_key: Pubkey,
+    ) -> Result<()> {
+        instructions::aggregate_sensor_levels(ctx, reservoir_key)
+    }
+
+    /// Archives today's level, inflow and outflow of a reservoir
+    pub fn record_hydro_day(
+        ctx: Context<RecordHydroDay>,
+        reservoir_key: Pubkey,
+        shard: u64,
+        inflow: u64,
+        outflow: u64,
+    ) -> Result<()> {
+        instructions::record_hydro_day(ctx, reservoir_key, shard, inflow, outflow)
+    }
+
+    /// Closes an archive shard past the retention window, refunding its rent
+    pub fn prune_hydro_archive(
+        ctx: Context<PruneHydroArchive>,
+        reservoir_key: Pubkey,
+        shard: u64,
+    ) -> Result<()> {
+        instructions::prune_hydro_archive(ctx, reservoir_key, shard)
+    }
+
+    /// Shares a reservoir between agencies in proportion to their draw rights
+    pub fn configure_shared_reservoir(
+        ctx: Context<ConfigureSharedReservoir>,
+        reservoir_key: Pubkey,
+        period_volume: u64,
+        period_slots: u64,
+        draw_shares: Vec<DrawShare>,
+    ) -> Result<()> {
+        instructions::configure_shared_reservoir(
+            ctx,
+            reservoir_key,
+            period_volume,
+            period_slots,
+            draw_shares,
+        )
+    }
+
+    /// Records that an agency settled volume it drew beyond its share of a shared reservoir
+    pub fn settle_shared_draw(
+        ctx: Context<SettleSharedDraw>,
+        reservoir_key: Pubkey,
+        member: Pubkey,
+        volume: u64,
+    ) -> Result<()> {
+        instructions::settle_shared_draw(ctx, reservoir_key, member, volume)
+    }
+
+    /// Publishes the mix of sources an agency's water is supplied from
+    pub fn publish_source_mix(
+        ctx: Context<PublishSourceMix>,
+        sources: Vec<SupplySource>,
+    ) -> Result<()> {
+        instructions::publish_source_mix(ctx, sources)
+    }
+
+    /// Sets the discount and compensation an agency offers on interruptible supply contracts
+    pub fn configure_interruptible_supply(
+        ctx: Context<ConfigureInterruptibleSupply>,
+        discount_bps: u16,
+        compensation_per_period: u64,
+        period_slots: u64,
+    ) -> Result<()> {
+        instructions::configure_interruptible_supply(
+            ctx,
+            discount_bps,
+            compensation_per_period,
+            period_slots,
+        )
+    }
+
+    /// Opts a consumer into or out of an interruptible supply contract
+    pub fn set_interruptible(ctx: Context<SetInterruptible>, interruptible: bool) -> Result<()> {
+        instructions::set_interruptible(ctx, interruptible)
+    }
+
+    /// Curtails the supply of an agency's interruptible consumers in an emergency
+    pub fn curtail_interruptible_supply(
+        ctx: Context<CurtailInterruptibleSupply>,
+        periods: u64,
+    ) -> Result<()> {
+        instructions::curtail_interruptible_supply(ctx, periods)
+    }
+
+    /// Compensates an interruptible consumer for the periods its supply was curtailed
+    pub fn compensate_curtailment(ctx: Context<CompensateCurtailment>) -> Result<()> {
+        instructions::compensate_curtailment(ctx)
+    }
+
+    /// Calls for reductions in usage from a reservoir's consumers during a shortage window
+    #[allow(clippy::too_many_arguments)]
+    pub fn announce_dr_event(
+        ctx: Context<AnnounceDrEvent>,
+        event_key: Pubkey,
+        reservoir_key: Pubkey,
+        start_slot: u64,
+        end_slot: u64,
+        target_volume: u64,
+        payment_rate: u64,
+        weather_adjustment_bps: u16,
+    ) -> Result<()> {
+        instructions::announce_dr_event(
+            ctx,
+            event_key,
+            reservoir_key,
+            start_slot,
+            end_slot,
+            target_volume,
+            payment_rate,
+            weather_adjustment_bps,
+        )
+    }
+
+    /// Commits a consumer to a reduction in usage during a demand-response event
+    pub fn bid_curtailment(ctx: Context<BidCurtailment>, volume: u64) -> Result<()> {
+        instructions::bid_curtailment(ctx, volume)
+    }
+
+    /// Pays a consumer from the treasury for the reduction it delivered during an event
+    pub fn settle_dr_event(ctx: Context<SettleDrEvent>) -> Result<()> {
+        instructions::settle_dr_event(ctx)
+    }
 }
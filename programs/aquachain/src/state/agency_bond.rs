@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::utils::bps_of;
+
+/// Bond an agency escrows as accountability for its charges.
+///
+/// The bond is held in a stablecoin account owned by this PDA. When a regulator confirms
+/// a charge flagged by `verify_charge`, a share of the escrowed balance is paid to the
+/// mischarged consumer.
+///
+/// # Fields
+/// * `agency` - Agency posting the bond
+/// * `settlement_mint` - Stablecoin the bond is escrowed in
+/// * `slash_bps` - Share of the escrowed balance paid out per confirmed mischarge, in basis points
+/// * `total_posted` - Amount posted into the escrow, in settlement base units
+/// * `total_slashed` - Amount paid out of the escrow to consumers, in settlement base units
+///
+/// # Example
+/// ```ignore
+/// let bond = AgencyBond {
+///     agency: agency_pubkey,
+///     settlement_mint: usdc_mint,
+///     slash_bps: 1_000,            // 10% of the bond per confirmed mischarge
+///     total_posted: 10_000_000_000, // 10,000 USDC
+///     total_slashed: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct AgencyBond {
+    /// Agency posting the bond.
+    pub agency: Pubkey,
+
+    /// Stablecoin the bond is escrowed in.
+    pub settlement_mint: Pubkey,
+
+    /// Share of the escrowed balance paid out per confirmed mischarge, in basis points.
+    pub slash_bps: u16,
+
+    /// Amount posted into the escrow, in settlement base units.
+    pub total_posted: u64,
+
+    /// Amount paid out of the escrow to consumers, in settlement base units.
+    pub total_slashed: u64,
+}
+
+impl AgencyBond {
+    /// Returns the compensation owed for a confirmed mischarge out of the escrowed balance
+    pub fn compensation_for(&self, balance: u64) -> u64 {
+        bps_of(balance, self.slash_bps)
+    }
+
+    /// Records an amount posted into the escrow
+    pub fn post(&mut self, amount: u64) {
+        self.total_posted = self.total_posted.saturating_add(amount);
+    }
+
+    /// Records a compensation paid out of the escrow
+    pub fn slash(&mut self, amount: u64) {
+        self.total_slashed = self.total_slashed.saturating_add(amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compensation() {
+        let mut bond = AgencyBond {
+            agency: Pubkey::default(),
+            settlement_mint: Pubkey::default(),
+            slash_bps: 1_000,
+            total_posted: 0,
+            total_slashed: 0,
+        };
+        bond.post(50_000_000);
+        assert_eq!(bond.compensation_for(50_000_000), 5_000_000);
+
+        bond.slash(5_000_000);
+        assert_eq!(bond.compensation_for(45_000_000), 4_500_000);
+        assert_eq!(bond.compensation_for(0), 0);
+        assert_eq!(
+            (bond.total_posted, bond.total_slashed),
+            (50_000_000, 5_000_000)
+        );
+    }
+}
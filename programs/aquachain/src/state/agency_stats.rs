@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+
+/// Aggregate statistics of an agency, kept up to date by the billing instructions.
+///
+/// This account lets a utility dashboard render headline figures from a single fetch
+/// instead of scanning every consumer account. All totals are cumulative since the
+/// account was created.
+///
+/// # Fields
+/// * `total_consumers` - Number of consumers registered or claimed under the agency
+/// * `total_usage` - Metered water usage billed across all consumers, in raw volume units
+/// * `total_waste` - Waste disposed across all consumers, in raw volume units
+/// * `revenue_collected` - WTK and WST paid towards consumers' debts, in currency base units
+/// * `co2e_grams` - Emissions attributed to the water delivered, in grams CO2e
+///
+/// # Example
+/// ```ignore
+/// let agency_stats = AgencyStats {
+///     total_consumers: 50,
+///     total_usage: 4_200_000,
+///     total_waste: 900_000,
+///     revenue_collected: 2_100_000,
+///     co2e_grams: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct AgencyStats {
+    /// Number of consumer accounts created by registration or allocation claims.
+    pub total_consumers: u64,
+
+    /// Metered water usage recorded by actual readings, in raw volume units.
+    pub total_usage: u64,
+
+    /// Waste volume disposed by consumers, in raw volume units.
+    pub total_waste: u64,
+
+    /// Payments applied to consumers' water and waste debts, in currency base units.
+    pub revenue_collected: u64,
+
+    /// Greenhouse gas emissions attributed to the water delivered, in grams CO2e.
+    pub co2e_grams: u64,
+}
+
+impl AgencyStats {
+    /// Records a newly created consumer
+    pub fn record_consumer(&mut self) {
+        self.total_consumers = self.total_consumers.saturating_add(1);
+    }
+
+    /// Records an actual meter reading
+    pub fn record_usage(&mut self, amount: u64) {
+        self.total_usage = self.total_usage.saturating_add(amount);
+    }
+
+    /// Records the emissions attributed to delivered water
+    pub fn record_emissions(&mut self, co2e_grams: u64) {
+        self.co2e_grams = self.co2e_grams.saturating_add(co2e_grams);
+    }
+
+    /// Records disposed waste
+    pub fn record_waste(&mut self, amount: u64) {
+        self.total_waste = self.total_waste.saturating_add(amount);
+    }
+
+    /// Records a payment towards a consumer's debt
+    pub fn record_payment(&mut self, amount: u64) {
+        self.revenue_collected = self.revenue_collected.saturating_add(amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_totals() {
+        let mut agency_stats = AgencyStats {
+            total_consumers: 0,
+            total_usage: 0,
+            total_waste: 0,
+            revenue_collected: u64::MAX - 1,
+            co2e_grams: 0,
+        };
+        agency_stats.record_consumer();
+        agency_stats.record_usage(30000);
+        agency_stats.record_usage(20000);
+        agency_stats.record_waste(5000);
+        agency_stats.record_payment(10);
+
+        assert_eq!(agency_stats.total_consumers, 1);
+        assert_eq!(agency_stats.total_usage, 50000);
+        assert_eq!(agency_stats.total_waste, 5000);
+        assert_eq!(agency_stats.revenue_collected, u64::MAX);
+    }
+}
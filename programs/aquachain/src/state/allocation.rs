@@ -0,0 +1,100 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+/// Represents a batch of initial WATC allocations committed to as a Merkle tree.
+///
+/// The agency posts only the root of a tree whose leaves pair each claimant with its
+/// contracted capacity, and escrows the total WATC in a vault owned by this account.
+/// Claimants then register themselves by proving their leaf is in the tree.
+///
+/// # Fields
+/// * `merkle_root` - Root of the tree of claimant and capacity leaves
+/// * `tariff_key` - Tariff assigned to every consumer registered from this tree
+/// * `reservoir_key` - Reservoir assigned to every consumer registered from this tree
+/// * `block_rate` - Block rate assigned to every consumer registered from this tree
+/// * `total_capacity` - Total WATC escrowed for the tree
+/// * `claimed_capacity` - WATC already claimed from the escrow
+/// * `tree_key` - Unique identifier for this allocation tree
+///
+/// # Example
+/// ```ignore
+/// let allocation_tree = AllocationTree {
+///     merkle_root: root,
+///     tariff_key,
+///     reservoir_key,
+///     block_rate: 800,              // 0.800
+///     total_capacity: 250_000_000,  // 250000.000 WATC
+///     claimed_capacity: 0,
+///     tree_key: pubkey,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct AllocationTree {
+    /// Root of the Merkle tree of `(claimant, contracted_capacity)` leaves.
+    pub merkle_root: [u8; 32],
+
+    /// The tariff assigned to consumers registered from this tree.
+    pub tariff_key: Pubkey,
+
+    /// The reservoir assigned to consumers registered from this tree.
+    pub reservoir_key: Pubkey,
+
+    /// The block rate assigned to consumers registered from this tree.
+    pub block_rate: u64,
+
+    /// Total WATC escrowed when the tree was created.
+    pub total_capacity: u64,
+
+    /// WATC already transferred to claimants.
+    /// This value must always be less than or equal to total_capacity.
+    pub claimed_capacity: u64,
+
+    /// The public key associated with this allocation tree,
+    /// used for identification.
+    pub tree_key: Pubkey,
+}
+
+impl AllocationTree {
+    /// Hashes a claimant and its contracted capacity into a leaf of the tree
+    pub fn leaf(claimant: &Pubkey, contracted_capacity: u64) -> [u8; 32] {
+        hashv(&[claimant.as_ref(), &contracted_capacity.to_le_bytes()]).to_bytes()
+    }
+}
+
+/// Records that a claimant has registered from an allocation tree, so each leaf can
+/// only be claimed once.
+///
+/// # Fields
+/// * `consumer` - The consumer account registered by the claim
+/// * `contracted_capacity` - WATC received by the claim
+/// * `claimed_slot` - Slot at which the claim was made
+#[account]
+#[derive(InitSpace)]
+pub struct AllocationClaim {
+    /// The consumer account registered by the claim.
+    pub consumer: Pubkey,
+
+    /// WATC received by the claim, equal to the consumer's contracted capacity.
+    pub contracted_capacity: u64,
+
+    /// Slot at which the claim was made.
+    pub claimed_slot: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaf_depends_on_capacity() {
+        let claimant = Pubkey::new_unique();
+        assert_eq!(
+            AllocationTree::leaf(&claimant, 100000),
+            AllocationTree::leaf(&claimant, 100000)
+        );
+        assert_ne!(
+            AllocationTree::leaf(&claimant, 100000),
+            AllocationTree::leaf(&claimant, 100001)
+        );
+    }
+}
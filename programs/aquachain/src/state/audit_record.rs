@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+/// Kind of off-chain document anchored in an audit record.
+///
+/// # Variants
+/// * `RateFiling` - A tariff or rate filing submitted by the agency
+/// * `LabReport` - A water quality or treatment lab report
+/// * `RegulatorApproval` - An approval issued by the regulator
+/// * `Other` - Any other document, described off-chain
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AuditCategory {
+    /// A tariff or rate filing submitted by the agency
+    RateFiling,
+
+    /// A water quality or treatment lab report
+    LabReport,
+
+    /// An approval issued by the regulator
+    RegulatorApproval,
+
+    /// Any other document, described off-chain
+    Other,
+}
+
+/// Anchors the SHA-256 hash of an off-chain document on chain.
+///
+/// Auditors can hash a copy of the document and look up the record derived from that
+/// hash, proving the document existed unchanged when it was recorded. The subject links
+/// the document to the on-chain account it concerns, such as the tariff a rate filing
+/// approved.
+///
+/// # Fields
+/// * `document_hash` - SHA-256 hash of the document
+/// * `category` - Kind of document
+/// * `subject` - Account the document concerns, or the default key if none
+/// * `recorded_at` - Unix timestamp at which the hash was recorded
+/// * `recorded_slot` - Slot at which the hash was recorded
+///
+/// # Example
+/// ```ignore
+/// let audit_record = AuditRecord {
+///     document_hash: sha256(&filing_pdf),
+///     category: AuditCategory::RateFiling,
+///     subject: tariff_key,
+///     recorded_at: 1_760_000_000,
+///     recorded_slot: 1000,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct AuditRecord {
+    /// SHA-256 hash of the off-chain document.
+    pub document_hash: [u8; 32],
+
+    /// Kind of document the hash refers to.
+    pub category: AuditCategory,
+
+    /// The account the document concerns, e.g. the key of the tariff a filing approved.
+    /// The default public key when the document is not tied to an account.
+    pub subject: Pubkey,
+
+    /// Unix timestamp at which the hash was recorded.
+    pub recorded_at: i64,
+
+    /// Slot at which the hash was recorded.
+    pub recorded_slot: u64,
+}
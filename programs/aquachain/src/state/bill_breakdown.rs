@@ -0,0 +1,269 @@
+use anchor_lang::prelude::*;
+
+use super::UnitConfig;
+use crate::{utils::FixedPoint, CustomError};
+use aquachain_core::ChargeBounds;
+
+/// How the charge of a metered reading was composed, in base units of the billing currency.
+///
+/// Each part is computed deterministically by the program, so a consumer can check how
+/// every bill was reached.
+///
+/// # Fields
+/// * `fixed` - Amount added to reach the tariff's minimum charge
+/// * `base` - Usage within the block threshold, charged at the water rate
+/// * `excess` - Usage above the block threshold, charged at the scaled block rate
+/// * `penalty` - Out-of-turn irrigation, energy and supply source surcharges added on top
+/// * `discount` - Drought insurance payout and interruptible supply discount deducted from the charge
+/// * `total` - Amount billed: fixed + base + excess + penalty - discount
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, Eq, PartialEq, InitSpace,
+)]
+pub struct BillBreakdown {
+    pub fixed: u64,
+    pub base: u64,
+    pub excess: u64,
+    pub penalty: u64,
+    pub discount: u64,
+    pub total: u64,
+}
+
+impl BillBreakdown {
+    /// Prices a reading within the tariff's regulated bounds
+    ///
+    /// The scarcity multiplier is capped and the cost floored by the pricing core, as
+    /// off-chain previews do. The base and excess parts add up to the cost of the reading
+    /// converted at once, so the total is the same as pricing without a breakdown.
+    ///
+    /// # Arguments
+    /// * `bounds` - Regulated bounds of the tariff the reading is billed under
+    /// * `units` - Unit configuration of the agency
+    /// * `block_remaining` - Billed volume still charged at the water rate this billing period
+    /// * `amount_fp` - Billed volume of the reading
+    /// * `water_rate_fp` - Water rate in force
+    /// * `block_rate_fp` - Consumer's block rate
+    /// * `multiplier` - Scarcity multiplier of the reservoir, before the tariff's cap
+    ///
+    /// # Errors
+    /// * `CustomError::MathOverflow` - If the cost does not fit in a u64
+    pub fn price(
+        bounds: &ChargeBounds,
+        units: &UnitConfig,
+        block_remaining: FixedPoint,
+        amount_fp: FixedPoint,
+        water_rate_fp: FixedPoint,
+        block_rate_fp: FixedPoint,
+        multiplier: FixedPoint,
+    ) -> Result<Self> {
+        let (base, excess) = aquachain_core::calculate_cost_breakdown(
+            block_remaining,
+            amount_fp,
+            water_rate_fp,
+            block_rate_fp,
+            bounds.cap_multiplier(multiplier),
+        );
+        let usage = units.to_currency(base + excess)?;
+        let base = units.to_currency(base)?.min(usage);
+        let total = units.to_currency(bounds.floor_cost(base + excess))?;
+        Ok(Self {
+            fixed: total - usage,
+            base,
+            excess: usage - base,
+            penalty: 0,
+            discount: 0,
+            total,
+        })
+    }
+
+    /// Adds a surcharge on top of the bill
+    ///
+    /// # Errors
+    /// * `CustomError::MathOverflow` - If the total does not fit in a u64
+    pub fn add_penalty(&mut self, amount: u64) -> Result<()> {
+        self.total = self
+            .total
+            .checked_add(amount)
+            .ok_or(CustomError::MathOverflow)?;
+        self.penalty = self.penalty.saturating_add(amount);
+        Ok(())
+    }
+
+    /// Charge for the volume of the reading, before penalties and discounts
+    pub fn usage_charge(&self) -> u64 {
+        self.fixed
+            .saturating_add(self.base)
+            .saturating_add(self.excess)
+    }
+
+    /// Deducts a discount from the bill, up to its total
+    pub fn apply_discount(&mut self, amount: u64) {
+        let discount = amount.min(self.total);
+        self.total -= discount;
+        self.discount = self.discount.saturating_add(discount);
+    }
+}
+
+/// A billed charge that differs from its recomputation.
+///
+/// # Fields
+/// * `billed` - Usage charge that was billed, in base units of the billing currency
+/// * `recomputed` - Usage charge recomputed from the pricing snapshot
+/// * `slot` - Slot the flagged reading was charged at
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq, InitSpace)]
+pub struct FlaggedCharge {
+    pub billed: u64,
+    pub recomputed: u64,
+    pub slot: u64,
+}
+
+/// Pricing inputs a metered reading was charged on, so the charge can be recomputed later.
+///
+/// # Fields
+/// * `tariff_activated_slot` - Slot the tariff version in force was activated at
+/// * `water_rate` - Water rate of the tariff (3 decimals)
+/// * `block_rate` - Consumer's block rate (3 decimals)
+/// * `max_multiplier` - Maximum multiplier of the tariff (3 decimals)
+/// * `min_charge` - Minimum charge of the tariff (3 decimals)
+/// * `block_remaining` - Raw volume still charged at the water rate before the reading
+/// * `volume` - Raw volume of the reading
+/// * `level` - Reservoir level the reading was priced on
+/// * `multiplier` - Scarcity multiplier of the reservoir, before the tariff's cap (3 decimals)
+/// * `volume_scale` - Volume scale of the agency's unit configuration
+/// * `currency_decimals` - Currency decimals of the agency's unit configuration
+/// * `slot` - Slot the reading was charged at
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, Eq, PartialEq, InitSpace,
+)]
+pub struct PricingSnapshot {
+    pub tariff_activated_slot: u64,
+    pub water_rate: u64,
+    pub block_rate: u64,
+    pub max_multiplier: u64,
+    pub min_charge: u64,
+    pub block_remaining: u64,
+    pub volume: u64,
+    pub level: u64,
+    pub multiplier: u64,
+    pub volume_scale: u64,
+    pub currency_decimals: u8,
+    pub slot: u64,
+}
+
+impl PricingSnapshot {
+    /// Regulated bounds of the tariff the reading was billed under
+    pub fn charge_bounds(&self) -> ChargeBounds {
+        ChargeBounds {
+            max_multiplier: self.max_multiplier,
+            min_charge: self.min_charge,
+        }
+    }
+
+    /// Unit configuration the reading was converted with
+    pub fn units(&self) -> UnitConfig {
+        UnitConfig {
+            volume_scale: self.volume_scale,
+            currency_decimals: self.currency_decimals,
+        }
+    }
+
+    /// Prices the reading from the recorded inputs
+    ///
+    /// Penalties and discounts are not part of the snapshot, so only the fixed, base and
+    /// excess parts are recomputed.
+    ///
+    /// # Errors
+    /// * `CustomError::MathOverflow` - If the volume or cost does not fit in a u64
+    pub fn bill(&self) -> Result<BillBreakdown> {
+        let units = self.units();
+        BillBreakdown::price(
+            &self.charge_bounds(),
+            &units,
+            units.to_volume(self.block_remaining)?,
+            units.to_volume(self.volume)?,
+            FixedPoint::from(self.water_rate),
+            FixedPoint::from(self.block_rate),
+            FixedPoint::from(self.multiplier),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNITS: UnitConfig = UnitConfig {
+        volume_scale: 1000,
+        currency_decimals: 3,
+    };
+
+    fn price(min_charge: u64, amount: u64) -> BillBreakdown {
+        BillBreakdown::price(
+            &ChargeBounds {
+                max_multiplier: 0,
+                min_charge,
+            },
+            &UNITS,
+            FixedPoint::from(100000),
+            FixedPoint::from(amount),
+            FixedPoint::from(500),
+            FixedPoint::from(800),
+            FixedPoint::one(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_price() {
+        let bill = price(0, 120000);
+        assert_eq!((bill.base, bill.excess, bill.fixed), (50000, 16000, 0));
+        assert_eq!(bill.total, 66000);
+
+        // A small reading is topped up to the minimum charge
+        let bill = price(5000, 1000);
+        assert_eq!((bill.base, bill.excess, bill.fixed), (500, 0, 4500));
+        assert_eq!(bill.total, 5000);
+    }
+
+    #[test]
+    fn test_penalty_and_discount() {
+        let mut bill = price(0, 120000);
+        bill.add_penalty(4000).unwrap();
+        bill.apply_discount(10000);
+        assert_eq!((bill.penalty, bill.discount), (4000, 10000));
+        assert_eq!(bill.total, 60000);
+        assert_eq!(bill.usage_charge(), 66000);
+    }
+
+    #[test]
+    fn test_snapshot_bill() {
+        let snapshot = PricingSnapshot {
+            tariff_activated_slot: 0,
+            water_rate: 500,
+            block_rate: 800,
+            max_multiplier: 0,
+            min_charge: 0,
+            block_remaining: 100000,
+            volume: 120000,
+            level: 950000,
+            multiplier: 1000,
+            volume_scale: 1000,
+            currency_decimals: 3,
+            slot: 10,
+        };
+        assert_eq!(snapshot.bill().unwrap(), price(0, 120000));
+
+        let doubled = PricingSnapshot {
+            multiplier: 2000,
+            ..snapshot
+        };
+        assert_eq!(doubled.bill().unwrap().excess, 32000);
+
+        // The multiplier is recorded before the tariff's cap, which is applied on billing
+        let capped = PricingSnapshot {
+            multiplier: 5000,
+            max_multiplier: 2000,
+            ..snapshot
+        };
+        assert_eq!(capped.bill().unwrap(), doubled.bill().unwrap());
+    }
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+
+use crate::CustomError;
+
+/// Carbon-credit token an agency retires against its emissions, and the retirements made.
+///
+/// Retiring credits burns them from the agency's token account, so they cannot be sold or
+/// retired again. Retirements are capped by the emissions recorded in the agency's
+/// statistics, so the agency can never claim to have offset more than it emitted.
+///
+/// # Fields
+/// * `agency` - Agency retiring the credits
+/// * `credit_mint` - Mint of the carbon-credit token
+/// * `grams_per_credit` - Grams CO2e offset by one whole credit token
+/// * `credits_retired` - Credits burned so far, in base units of the credit mint
+/// * `co2e_retired` - Emissions offset by the credits burned so far, in grams CO2e
+/// * `retirements` - Number of retirements made
+///
+/// # Example
+/// ```ignore
+/// let offsets = CarbonOffsets {
+///     agency: agency_pubkey,
+///     credit_mint: credit_mint_pubkey,
+///     grams_per_credit: 1_000_000, // one tonne per credit
+///     credits_retired: 0,
+///     co2e_retired: 0,
+///     retirements: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct CarbonOffsets {
+    /// Agency retiring the credits.
+    pub agency: Pubkey,
+
+    /// Mint of the carbon-credit token retired.
+    pub credit_mint: Pubkey,
+
+    /// Grams CO2e offset by one whole credit token, typically a tonne.
+    pub grams_per_credit: u64,
+
+    /// Credits burned so far, in base units of the credit mint.
+    pub credits_retired: u64,
+
+    /// Emissions offset by the credits burned so far, in grams CO2e.
+    pub co2e_retired: u64,
+
+    /// Number of retirements made.
+    pub retirements: u32,
+}
+
+impl CarbonOffsets {
+    /// Returns the grams CO2e offset by `amount` base units of a mint with `decimals`
+    ///
+    /// # Errors
+    /// * `CustomError::MathOverflow` - If the offset does not fit in a u64
+    pub fn co2e_for(&self, amount: u64, decimals: u8) -> Result<u64> {
+        let grams = (amount as u128)
+            .checked_mul(self.grams_per_credit as u128)
+            .ok_or(error!(CustomError::MathOverflow))?
+            / 10u128.pow(decimals as u32);
+        u64::try_from(grams).map_err(|_| error!(CustomError::MathOverflow))
+    }
+
+    /// Records the retirement of `amount` credits offsetting `co2e` grams
+    ///
+    /// # Errors
+    /// * `CustomError::OffsetExceedsEmissions` - If the total retired would exceed `emitted`
+    pub fn retire(&mut self, amount: u64, co2e: u64, emitted: u64) -> Result<()> {
+        let co2e_retired = self
+            .co2e_retired
+            .checked_add(co2e)
+            .filter(|retired| *retired <= emitted)
+            .ok_or(CustomError::OffsetExceedsEmissions)?;
+        self.co2e_retired = co2e_retired;
+        self.credits_retired = self.credits_retired.saturating_add(amount);
+        self.retirements = self.retirements.saturating_add(1);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offsets() -> CarbonOffsets {
+        CarbonOffsets {
+            agency: Pubkey::default(),
+            credit_mint: Pubkey::default(),
+            grams_per_credit: 1_000_000,
+            credits_retired: 0,
+            co2e_retired: 0,
+            retirements: 0,
+        }
+    }
+
+    #[test]
+    fn test_co2e_for() {
+        // 0.014 credits of a tonne each, with 3 decimals
+        assert_eq!(offsets().co2e_for(14, 3).unwrap(), 14_000);
+    }
+
+    #[test]
+    fn test_retire_within_emissions() {
+        let mut offsets = offsets();
+        offsets.retire(10, 10_000, 14_000).unwrap();
+        assert!(offsets.retire(5, 5_000, 14_000).is_err());
+        offsets.retire(4, 4_000, 14_000).unwrap();
+
+        assert_eq!(offsets.co2e_retired, 14_000);
+        assert_eq!(offsets.credits_retired, 14);
+        assert_eq!(offsets.retirements, 2);
+    }
+}
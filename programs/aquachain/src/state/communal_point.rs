@@ -0,0 +1,171 @@
+use anchor_lang::prelude::*;
+
+use crate::CustomError;
+
+/// Largest number of member wallets registered on a communal point
+pub const COMMUNAL_MEMBERS_MAX: usize = 16;
+
+/// Contribution history of a wallet sharing a communal point.
+///
+/// # Fields
+/// * `wallet` - Member wallet allowed to top up the shared balance
+/// * `contributed` - Total amount the member has topped up, in WTK base units
+/// * `top_ups` - Number of top-ups the member has made
+/// * `last_top_up_slot` - Slot of the member's latest top-up, 0 if none
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq, InitSpace)]
+pub struct CommunalMember {
+    pub wallet: Pubkey,
+    pub contributed: u64,
+    pub top_ups: u32,
+    pub last_top_up_slot: u64,
+}
+
+/// Shared prepaid balance of a community standpipe.
+///
+/// A communal point turns a registered consumer into a standpipe shared by several
+/// households. Each member wallet can top up the prepaid balance, and water used at the
+/// standpipe is drawn from it before any remaining charge is billed to the consumer as
+/// WTK. Contributions are recorded per member so the community can report on fairness.
+///
+/// # Fields
+/// * `agency` - Agency operating the standpipe
+/// * `consumer` - Consumer account metering the standpipe
+/// * `prepaid_balance` - Amount topped up and not yet drawn, in WTK base units
+/// * `total_drawn` - Amount drawn from the balance to pay for usage, in WTK base units
+/// * `members` - Member wallets and their contribution history
+///
+/// # Example
+/// ```ignore
+/// let point = CommunalPoint {
+///     agency: agency_pubkey,
+///     consumer: standpipe_pubkey,
+///     prepaid_balance: 25_000, // 25.000 WTK
+///     total_drawn: 0,
+///     members: vec![],
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct CommunalPoint {
+    /// Agency operating the standpipe.
+    pub agency: Pubkey,
+
+    /// Consumer account metering the standpipe.
+    pub consumer: Pubkey,
+
+    /// Amount topped up and not yet drawn, in WTK base units.
+    pub prepaid_balance: u64,
+
+    /// Amount drawn from the balance to pay for usage, in WTK base units.
+    pub total_drawn: u64,
+
+    /// Member wallets and their contribution history.
+    #[max_len(COMMUNAL_MEMBERS_MAX)]
+    pub members: Vec<CommunalMember>,
+}
+
+impl CommunalPoint {
+    /// Registers a wallet as a member of the communal point
+    ///
+    /// # Errors
+    /// * `CustomError::MemberAlreadyRegistered` - If the wallet is already a member
+    /// * `CustomError::CommunalPointFull` - If the point has the maximum number of members
+    pub fn add_member(&mut self, wallet: Pubkey) -> Result<()> {
+        require!(
+            !self.members.iter().any(|member| member.wallet == wallet),
+            CustomError::MemberAlreadyRegistered
+        );
+        require!(
+            self.members.len() < COMMUNAL_MEMBERS_MAX,
+            CustomError::CommunalPointFull
+        );
+        self.members.push(CommunalMember {
+            wallet,
+            contributed: 0,
+            top_ups: 0,
+            last_top_up_slot: 0,
+        });
+        Ok(())
+    }
+
+    /// Credits a member's top-up to the shared balance and its contribution history
+    ///
+    /// # Errors
+    /// * `CustomError::NotCommunalMember` - If the wallet is not a member
+    /// * `CustomError::MathOverflow` - If the prepaid balance overflows
+    pub fn top_up(&mut self, wallet: Pubkey, amount: u64, slot: u64) -> Result<()> {
+        let member = self
+            .members
+            .iter_mut()
+            .find(|member| member.wallet == wallet)
+            .ok_or(error!(CustomError::NotCommunalMember))?;
+        member.contributed = member.contributed.saturating_add(amount);
+        member.top_ups = member.top_ups.saturating_add(1);
+        member.last_top_up_slot = slot;
+
+        self.prepaid_balance = self
+            .prepaid_balance
+            .checked_add(amount)
+            .ok_or(error!(CustomError::MathOverflow))?;
+        Ok(())
+    }
+
+    /// Draws as much of a charge as the prepaid balance covers, returning the amount drawn
+    pub fn draw(&mut self, charge: u64) -> u64 {
+        let drawn = charge.min(self.prepaid_balance);
+        self.prepaid_balance -= drawn;
+        self.total_drawn = self.total_drawn.saturating_add(drawn);
+        drawn
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn communal_point() -> CommunalPoint {
+        CommunalPoint {
+            agency: Pubkey::default(),
+            consumer: Pubkey::default(),
+            prepaid_balance: 0,
+            total_drawn: 0,
+            members: vec![],
+        }
+    }
+
+    #[test]
+    fn test_add_member() {
+        let mut point = communal_point();
+        let wallet = Pubkey::new_unique();
+        point.add_member(wallet).unwrap();
+        assert!(point.add_member(wallet).is_err());
+
+        for _ in 1..COMMUNAL_MEMBERS_MAX {
+            point.add_member(Pubkey::new_unique()).unwrap();
+        }
+        assert!(point.add_member(Pubkey::new_unique()).is_err());
+    }
+
+    #[test]
+    fn test_top_up_and_draw() {
+        let mut point = communal_point();
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        point.add_member(alice).unwrap();
+        point.add_member(bob).unwrap();
+
+        point.top_up(alice, 3_000, 10).unwrap();
+        point.top_up(alice, 2_000, 20).unwrap();
+        point.top_up(bob, 1_000, 30).unwrap();
+        assert!(point.top_up(Pubkey::new_unique(), 1_000, 40).is_err());
+        assert_eq!(point.prepaid_balance, 6_000);
+        assert_eq!(point.members[0].contributed, 5_000);
+        assert_eq!(point.members[0].top_ups, 2);
+        assert_eq!(point.members[0].last_top_up_slot, 20);
+
+        // Charges beyond the balance are only partly drawn
+        assert_eq!(point.draw(4_000), 4_000);
+        assert_eq!(point.draw(4_000), 2_000);
+        assert_eq!(point.prepaid_balance, 0);
+        assert_eq!(point.total_drawn, 6_000);
+    }
+}
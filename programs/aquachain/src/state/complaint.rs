@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+
+/// Subject of a consumer complaint.
+///
+/// # Variants
+/// * `Billing` - A disputed or unexpected charge
+/// * `Supply` - An interruption or low pressure
+/// * `Quality` - Discoloured, odorous or unsafe water
+/// * `Meter` - A faulty or misread meter
+/// * `Other` - Any other subject, described off-chain
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ComplaintCategory {
+    /// A disputed or unexpected charge
+    Billing,
+
+    /// An interruption or low pressure
+    Supply,
+
+    /// Discoloured, odorous or unsafe water
+    Quality,
+
+    /// A faulty or misread meter
+    Meter,
+
+    /// Any other subject, described off-chain
+    Other,
+}
+
+/// Service-level commitment an agency makes on resolving complaints.
+///
+/// Complaints resolved later than the SLA allows earn the consumer a goodwill credit in
+/// AquaCoin, minted by the agency when the complaint is resolved.
+///
+/// # Fields
+/// * `agency` - Agency making the commitment
+/// * `aqc_mint` - Mint of the AquaCoin credited as goodwill
+/// * `sla_slots` - Slots within which complaints must be resolved, 0 to disable credits
+/// * `goodwill_amount` - Amount of AquaCoin credited for a late resolution, in base units
+///
+/// # Example
+/// ```ignore
+/// let policy = ComplaintPolicy {
+///     agency: agency_pubkey,
+///     aqc_mint: aqc_mint_pubkey,
+///     sla_slots: 432_000,      // about two days
+///     goodwill_amount: 10_000, // 10.000 AQC
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct ComplaintPolicy {
+    /// Agency making the commitment.
+    pub agency: Pubkey,
+
+    /// Mint of the AquaCoin credited as goodwill.
+    pub aqc_mint: Pubkey,
+
+    /// Slots within which complaints must be resolved, 0 to disable goodwill credits.
+    pub sla_slots: u64,
+
+    /// Amount of AquaCoin credited for a late resolution, in base units.
+    pub goodwill_amount: u64,
+}
+
+impl ComplaintPolicy {
+    /// Returns the goodwill owed for a complaint resolved at `resolved_slot`
+    pub fn goodwill_for(&self, opened_slot: u64, resolved_slot: u64) -> u64 {
+        if self.sla_slots > 0 && resolved_slot.saturating_sub(opened_slot) > self.sla_slots {
+            self.goodwill_amount
+        } else {
+            0
+        }
+    }
+}
+
+/// A complaint filed by a consumer and its resolution.
+///
+/// # Fields
+/// * `agency` - Agency the complaint was filed with
+/// * `complaint_key` - Unique identifier for the complaint
+/// * `consumer` - Consumer that filed the complaint
+/// * `category` - Subject of the complaint
+/// * `opened_slot` - Slot at which the complaint was filed
+/// * `resolved_slot` - Slot at which the complaint was resolved, 0 while open
+/// * `goodwill_credited` - Amount of AquaCoin credited for a late resolution
+///
+/// # Example
+/// ```ignore
+/// let complaint = Complaint {
+///     agency: agency_pubkey,
+///     complaint_key: complaint_pubkey,
+///     consumer: consumer_pubkey,
+///     category: ComplaintCategory::Supply,
+///     opened_slot: 1000,
+///     resolved_slot: 0,
+///     goodwill_credited: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct Complaint {
+    /// Agency the complaint was filed with.
+    pub agency: Pubkey,
+
+    /// Unique identifier for the complaint.
+    pub complaint_key: Pubkey,
+
+    /// Consumer that filed the complaint.
+    pub consumer: Pubkey,
+
+    /// Subject of the complaint.
+    pub category: ComplaintCategory,
+
+    /// Slot at which the complaint was filed.
+    pub opened_slot: u64,
+
+    /// Slot at which the complaint was resolved, 0 while it is open.
+    pub resolved_slot: u64,
+
+    /// Amount of AquaCoin credited for a late resolution.
+    pub goodwill_credited: u64,
+}
+
+impl Complaint {
+    /// Returns true if the complaint has not been resolved
+    pub fn is_open(&self) -> bool {
+        self.resolved_slot == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_goodwill_for() {
+        let mut policy = ComplaintPolicy {
+            agency: Pubkey::default(),
+            aqc_mint: Pubkey::default(),
+            sla_slots: 100,
+            goodwill_amount: 10_000,
+        };
+        assert_eq!(policy.goodwill_for(1000, 1100), 0);
+        assert_eq!(policy.goodwill_for(1000, 1101), 10_000);
+
+        // Without an SLA, no goodwill is owed
+        policy.sla_slots = 0;
+        assert_eq!(policy.goodwill_for(1000, 5000), 0);
+    }
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+/// Record of a non-transferable conservation certificate awarded to a consumer.
+///
+/// A certificate is awarded once a consumer's usage has stayed under its tariff's
+/// conservation baseline for the number of consecutive periods the tariff requires. The
+/// certificate itself is a Token-2022 mint with the NonTransferable extension, of which a
+/// single token is minted to the consumer before the mint authority is revoked.
+///
+/// # Fields
+/// * `consumer` - Consumer the certificate was awarded to
+/// * `mint` - Non-transferable mint of the certificate token
+/// * `index` - Sequence number of the certificate among the consumer's certificates
+/// * `periods` - Number of consecutive conserving periods the certificate recognises
+/// * `awarded_slot` - Slot at which the certificate was awarded
+///
+/// # Example
+/// ```ignore
+/// let certificate = ConservationCertificate {
+///     consumer: consumer_pubkey,
+///     mint: certificate_mint_pubkey,
+///     index: 0,
+///     periods: 6,
+///     awarded_slot: 15_552_000,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct ConservationCertificate {
+    /// Consumer the certificate was awarded to.
+    pub consumer: Pubkey,
+
+    /// Non-transferable mint of the certificate token.
+    pub mint: Pubkey,
+
+    /// Sequence number of the certificate among the consumer's certificates.
+    pub index: u16,
+
+    /// Number of consecutive conserving periods the certificate recognises.
+    pub periods: u16,
+
+    /// Slot at which the certificate was awarded.
+    pub awarded_slot: u64,
+}
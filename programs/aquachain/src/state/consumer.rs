@@ -1,5 +1,59 @@
 use anchor_lang::prelude::*;
 
+use super::{BillBreakdown, FlaggedCharge, PricingSnapshot, ScarcityInputs, Tariff, GEOHASH_LEN};
+use crate::CustomError;
+use aquachain_core::{record_period_usage, usage_baseline, BASELINE_PERIODS};
+
+/// Number of readings the rolling average usage is smoothed over
+pub const USAGE_AVERAGE_WINDOW: u64 = 6;
+
+/// Number of levelized payments in a budget billing year
+pub const BUDGET_PERIODS_PER_YEAR: u64 = 12;
+
+/// Maximum length in bytes of the URI pointing at a consumer's encrypted identity record
+pub const IDENTITY_URI_MAX_LEN: usize = 128;
+
+/// Reason code recorded when a consumer's capacity balance is adjusted.
+///
+/// # Variants
+/// * `MintingError` - Capacity was minted in error and is being clawed back or topped up
+/// * `ServiceInterruption` - Capacity is restored after an outage or supply restriction
+/// * `Chargeback` - Capacity is reversed following a disputed or failed payment
+/// * `Other` - Any other adjustment, documented off-chain
+/// * `PeriodRefresh` - Capacity is replenished at the start of a new billing period
+/// * `ForwardDelivery` - Capacity bought forward is delivered at the agreed rate
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AdjustmentReason {
+    /// Capacity was minted in error
+    MintingError,
+
+    /// Capacity is restored after an outage or supply restriction
+    ServiceInterruption,
+
+    /// Capacity is reversed following a disputed or failed payment
+    Chargeback,
+
+    /// Any other adjustment, documented off-chain
+    Other,
+
+    /// Capacity is replenished at the start of a new billing period
+    PeriodRefresh,
+
+    /// Capacity bought forward is delivered at the agreed rate
+    ForwardDelivery,
+}
+
+/// A meter reading reported in a batch, e.g. from an AMR meter file
+///
+/// # Fields
+/// * `consumer_index` - Position of the consumer's account pair in the remaining accounts
+/// * `amount` - Raw volume of water reported by the meter
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MeterReading {
+    pub consumer_index: u16,
+    pub amount: u64,
+}
+
 /// Represents a water consumer account in the Aquachain system.
 ///
 /// This account stores information about a water consumer's consumption parameters,
@@ -11,6 +65,48 @@ use anchor_lang::prelude::*;
 /// * `contracted_capacity` - The maximum amount of water allocated to this consumer
 /// * `assigned_tariff` - Reference to the tariff structure applied to this consumer
 /// * `assigned_reservoir` - Reference to the reservoir serving this consumer
+/// * `guarantor` - Optional co-signer liable for unpaid debt
+/// * `owner` - Account holder, the wallet of the occupant responsible for this account
+/// * `master_consumer` - Optional master account (e.g. landlord bulk meter) this consumer rolls up to
+/// * `tenant_share_bps` - Share of this sub-consumer's charges billed to itself, in basis points
+/// * `sub_consumer_count` - Number of sub-consumers rolling up to this account
+/// * `autopay_enabled` - Whether outstanding debt may be collected from the consumer's delegation
+/// * `outstanding_water_debt` - Amount of WTK billed for water usage and not yet paid
+/// * `average_usage` - Rolling average of the volumes reported by actual meter readings
+/// * `estimated_usage` - Volume billed on estimate that has not been trued up yet
+/// * `estimated_charge` - Amount of WTK charged for the estimated volume
+/// * `budget_billing` - Whether the consumer pays a levelized amount each period
+/// * `budget_amount` - Levelized amount of WTK collected each period under budget billing
+/// * `budget_paid` - Levelized payments collected since the last reconciliation
+/// * `debt_since_slot` - Slot at which the current outstanding water debt was first billed
+/// * `capacity_period_start` - Slot at which the current capacity period began
+/// * `period_usage` - Volume of water used since the current capacity period began
+/// * `period_waste` - Volume of waste disposed since the current capacity period began
+/// * `household_size` - Number of people in the household, 0 if unknown
+/// * `contract_expiry_slot` - Slot at which the supply contract expires, 0 if open-ended
+/// * `identity_hash` - Hash of the off-chain KYC record the account is bound to, zero if unbound
+/// * `identity_uri` - Optional URI of the encrypted KYC record
+/// * `co2e_grams` - Emissions attributed to the water delivered to the consumer, in grams CO2e
+/// * `last_bill` - How the consumer's last metered reading was billed
+/// * `last_pricing` - Pricing inputs the consumer's last metered reading was charged on
+/// * `flagged_charge` - Charge found to differ from its recomputation, if any
+/// * `interruptible` - Whether the consumer accepts curtailment in emergencies for a lower rate
+/// * `curtailed_periods_compensated` - Agency's curtailed periods the consumer was compensated up to
+/// * `usage_history` - Usage of the most recently closed capacity periods, newest first
+/// * `usage_history_len` - Number of closed periods recorded in the usage history
+/// * `prepaid_balance` - Amount topped up in advance and not yet drawn, in WTK base units
+/// * `receipts_issued` - Number of payment receipts issued to the consumer
+/// * `capacity_approval_bps` - Capacity change per period the consumer pre-approved, in basis points
+/// * `capacity_approval_base` - Contracted capacity pre-approved changes are measured from
+/// * `capacity_approval_period` - Capacity period the approval base was taken in
+/// * `meter_reading` - Latest cumulative register value submitted by the consumer's meter
+/// * `meter_read_at` - Unix timestamp of the latest submitted meter reading, 0 if none
+/// * `geohash` - Geohash of the consumer's meter location, empty if unknown
+/// * `period_watc_bought` - Volume of WATC bought on the marketplace since the current period began
+/// * `conservation_streak` - Consecutive closed periods with usage under the tariff's baseline
+/// * `certificates_awarded` - Number of conservation certificates awarded to the consumer
+/// * `last_scarcity_inputs` - Reservoir inputs the multiplier of the last metered reading was
+///   derived from
 ///
 /// # Example
 /// ```ignore
@@ -19,6 +115,47 @@ use anchor_lang::prelude::*;
 ///     contracted_capacity: 1000,  // Maximum allocation
 ///     assigned_tariff: tariff_pubkey,
 ///     assigned_reservoir: reservoir_pubkey,
+///     guarantor: None,
+///     owner: owner_pubkey,
+///     master_consumer: None,
+///     tenant_share_bps: 0,
+///     sub_consumer_count: 0,
+///     autopay_enabled: false,
+///     outstanding_water_debt: 0,
+///     average_usage: 0,
+///     estimated_usage: 0,
+///     estimated_charge: 0,
+///     budget_billing: false,
+///     budget_amount: 0,
+///     budget_paid: 0,
+///     debt_since_slot: 0,
+///     capacity_period_start: 0,
+///     period_usage: 0,
+///     period_waste: 0,
+///     household_size: 0,
+///     contract_expiry_slot: 0,
+///     identity_hash: [0; 32],
+///     identity_uri: None,
+///     co2e_grams: 0,
+///     last_bill: BillBreakdown::default(),
+///     last_pricing: PricingSnapshot::default(),
+///     flagged_charge: None,
+///     interruptible: false,
+///     curtailed_periods_compensated: 0,
+///     usage_history: [0; BASELINE_PERIODS],
+///     usage_history_len: 0,
+///     prepaid_balance: 0,
+///     receipts_issued: 0,
+///     capacity_approval_bps: 1_000, // ±10% a period without the consumer's signature
+///     capacity_approval_base: 0,
+///     capacity_approval_period: 0,
+///     meter_reading: 0,
+///     meter_read_at: 0,
+///     geohash: [0; 12],
+///     period_watc_bought: 0,
+///     conservation_streak: 0,
+///     certificates_awarded: 0,
+///     last_scarcity_inputs: ScarcityInputs::default(),
 /// };
 /// ```
 #[account]
@@ -39,4 +176,761 @@ pub struct Consumer {
     /// Reference to the reservoir from which this consumer draws water.
     /// Links to a Reservoir account that supplies water to this consumer.
     pub assigned_reservoir: Pubkey,
+
+    /// Optional guarantor who co-signed this consumer's account.
+    /// The agency can collect defaulted debt from the guarantor's pre-approved delegation.
+    pub guarantor: Option<Pubkey>,
+
+    /// Optional master consumer (e.g. a landlord's bulk meter) this account rolls up to.
+    /// Charges for this sub-consumer are split between itself and the master.
+    pub master_consumer: Option<Pubkey>,
+
+    /// Share of this sub-consumer's charges billed to itself, in basis points.
+    /// The remainder is billed to the master consumer.
+    pub tenant_share_bps: u16,
+
+    /// Number of sub-consumers currently rolling up to this account.
+    pub sub_consumer_count: u32,
+
+    /// Account holder: wallet of the occupant currently responsible for this account.
+    /// Registers the account's meter session keys and is recorded as the payer on its
+    /// receipts. Defaults to the consumer account's own key and is reassigned on a change
+    /// of occupancy; the token accounts stay under the consumer account's own key.
+    pub owner: Pubkey,
+
+    /// Whether the consumer opted into auto-pay.
+    /// When set, anyone may crank collection of outstanding WTK debt from the consumer's
+    /// stablecoin delegation.
+    pub autopay_enabled: bool,
+
+    /// Amount of WTK billed for water usage that has not been paid yet.
+    /// Increased when water is used and reduced by every payment, settlement or collection.
+    pub outstanding_water_debt: u64,
+
+    /// Rolling average of the volumes reported by actual meter readings.
+    /// Used to bill periods for which no reading arrives.
+    pub average_usage: u64,
+
+    /// Volume billed on estimate since the last actual reading.
+    /// Reconciled against the next actual reading by a true-up.
+    pub estimated_usage: u64,
+
+    /// Amount of WTK charged for the estimated volume.
+    pub estimated_charge: u64,
+
+    /// Whether the consumer is on budget billing.
+    /// Usage still accrues at true cost, but each invoice collects `budget_amount`.
+    pub budget_billing: bool,
+
+    /// Levelized amount of WTK collected by each invoice under budget billing.
+    pub budget_amount: u64,
+
+    /// Total levelized payments collected since the last year-end reconciliation.
+    pub budget_paid: u64,
+
+    /// Slot at which the consumer last went from no water debt to owing water debt.
+    /// Grace periods before punitive action are counted from this slot.
+    pub debt_since_slot: u64,
+
+    /// Slot at which the consumer's current capacity period began.
+    /// WATC is refreshed up to the contracted capacity once a full billing period has passed.
+    pub capacity_period_start: u64,
+
+    /// Volume of water reported by actual meter readings since the current capacity
+    /// period began. Compared with the tariff's block threshold to tier usage.
+    pub period_usage: u64,
+
+    /// Volume of waste disposed since the current capacity period began.
+    pub period_waste: u64,
+
+    /// Number of people living in the household, as registered with the agency.
+    /// Sizes the lifeline allocation of per-capita tariffs; 0 if unknown.
+    pub household_size: u16,
+
+    /// Slot at which the consumer's fixed-term supply contract expires, 0 if open-ended.
+    /// Once expired, all usage is charged at the block rate until the contract is renewed.
+    pub contract_expiry_slot: u64,
+
+    /// Hash of the agency's off-chain KYC record for the customer, all zeroes if unbound.
+    /// Binds the account to a verified identity without storing any personal data on-chain.
+    pub identity_hash: [u8; 32],
+
+    /// Optional URI of the encrypted KYC record, readable only by the agency.
+    #[max_len(IDENTITY_URI_MAX_LEN)]
+    pub identity_uri: Option<String>,
+
+    /// Greenhouse gas emissions attributed to the water delivered to the consumer, in
+    /// grams of CO2 equivalent. Reported for carbon disclosure; never billed.
+    pub co2e_grams: u64,
+
+    /// How the consumer's last metered reading was billed, so the consumer can check how
+    /// the charge was composed.
+    pub last_bill: BillBreakdown,
+
+    /// Pricing inputs the consumer's last metered reading was charged on, so the charge can
+    /// be recomputed.
+    pub last_pricing: PricingSnapshot,
+
+    /// Last charge found by `verify_charge` to differ from its recomputation, until it is
+    /// resolved.
+    pub flagged_charge: Option<FlaggedCharge>,
+
+    /// Whether the consumer is on an interruptible supply contract, billed at a lower rate
+    /// in exchange for being curtailed first in emergencies.
+    pub interruptible: bool,
+
+    /// Count of the agency's curtailed periods up to which the consumer was compensated.
+    /// Set to the agency's count when the consumer opts in, so earlier curtailments are not
+    /// compensated.
+    pub curtailed_periods_compensated: u64,
+
+    /// Usage of the most recently closed capacity periods, newest first. The baseline
+    /// reductions and conservation are measured against is averaged over it.
+    pub usage_history: [u64; BASELINE_PERIODS],
+
+    /// Number of closed periods recorded in `usage_history`.
+    pub usage_history_len: u8,
+
+    /// Amount topped up in advance with `top_up_prepaid` and not yet drawn to pay for usage,
+    /// in WTK base units.
+    pub prepaid_balance: u64,
+
+    /// Number of payment receipts issued to the consumer, and so the sequence number of the
+    /// next receipt.
+    pub receipts_issued: u64,
+
+    /// Largest change of contracted capacity per capacity period the consumer pre-approved,
+    /// in basis points of the capacity at the start of the period. The agency can make such
+    /// changes without the consumer's signature; zero requires it for every change.
+    pub capacity_approval_bps: u16,
+
+    /// Contracted capacity pre-approved changes in the current period are measured from.
+    pub capacity_approval_base: u64,
+
+    /// Start of the capacity period `capacity_approval_base` was taken in.
+    pub capacity_approval_period: u64,
+
+    /// Latest cumulative register value submitted by the consumer's meter device.
+    /// Registers only count up, so the agency bills the difference between readings.
+    pub meter_reading: u64,
+
+    /// Unix timestamp at which the latest meter reading was submitted, 0 if none.
+    pub meter_read_at: i64,
+
+    /// Geohash of the consumer's meter location, zero-padded; empty if unknown.
+    pub geohash: [u8; GEOHASH_LEN],
+
+    /// Volume of WATC bought on the capacity marketplace since the current capacity
+    /// period began. Capped by the market's trade cap to prevent hoarding.
+    pub period_watc_bought: u64,
+
+    /// Number of consecutive closed periods in which usage stayed under the tariff's
+    /// conservation baseline, not yet spent on a certificate.
+    pub conservation_streak: u16,
+
+    /// Number of conservation certificates awarded to the consumer.
+    pub certificates_awarded: u16,
+
+    /// Curve and levels of the reservoir the scarcity multiplier of the last metered
+    /// reading was derived from, so verification can derive it again.
+    pub last_scarcity_inputs: ScarcityInputs,
+}
+
+impl Consumer {
+    /// Records newly billed water usage at the given slot
+    ///
+    /// # Errors
+    /// * `CustomError::MathOverflow` - If the outstanding debt overflows
+    pub fn bill_water(&mut self, amount: u64, slot: u64) -> Result<()> {
+        if self.outstanding_water_debt == 0 && amount > 0 {
+            self.debt_since_slot = slot;
+        }
+        self.outstanding_water_debt = self
+            .outstanding_water_debt
+            .checked_add(amount)
+            .ok_or(error!(CustomError::MathOverflow))?;
+        Ok(())
+    }
+
+    /// Records a full or partial payment of water debt
+    ///
+    /// # Errors
+    /// * `CustomError::OverPayment` - If amount exceeds the outstanding debt
+    pub fn pay_water(&mut self, amount: u64) -> Result<()> {
+        self.outstanding_water_debt = self
+            .outstanding_water_debt
+            .checked_sub(amount)
+            .ok_or(error!(CustomError::OverPayment))?;
+        if self.outstanding_water_debt == 0 {
+            self.debt_since_slot = 0;
+        }
+        Ok(())
+    }
+
+    /// Returns the start of the current billing period if the consumer's capacity period
+    /// has ended, or `None` if refreshes are disabled or the period is still running
+    ///
+    /// # Arguments
+    /// * `billing_period_slots` - Length of the tariff's billing period (0 disables refreshes)
+    /// * `slot` - The current slot
+    pub fn capacity_refresh_due(&self, billing_period_slots: u64, slot: u64) -> Option<u64> {
+        if billing_period_slots == 0 {
+            return None;
+        }
+        let elapsed = slot.checked_sub(self.capacity_period_start)?;
+        if elapsed < billing_period_slots {
+            return None;
+        }
+        // Periods stay aligned on the original start, even if a refresh runs late
+        Some(self.capacity_period_start + elapsed / billing_period_slots * billing_period_slots)
+    }
+
+    /// Returns true if the consumer's fixed-term contract has expired at the given slot
+    pub fn contract_expired(&self, slot: u64) -> bool {
+        self.contract_expiry_slot != 0 && slot >= self.contract_expiry_slot
+    }
+
+    /// Returns the volume the consumer can still use this period at the water rate
+    ///
+    /// Tiering follows cumulative period usage rather than the WATC balance, so capacity
+    /// adjustments and rollovers do not move the consumer between pricing tiers. Nothing
+    /// remains at the water rate once the consumer's contract has expired.
+    pub fn block_remaining(&self, tariff: &Tariff, slot: u64) -> u64 {
+        if self.contract_expired(slot) {
+            return 0;
+        }
+        tariff
+            .block_threshold_for(self.contracted_capacity, self.household_size)
+            .saturating_sub(self.period_usage)
+    }
+
+    /// Resets the base pre-approved capacity changes are measured from to the current capacity
+    pub fn reset_capacity_approval(&mut self) {
+        self.capacity_approval_base = self.contracted_capacity;
+        self.capacity_approval_period = self.capacity_period_start;
+    }
+
+    /// Checks that a capacity change made without the consumer's signature is pre-approved
+    ///
+    /// Changes are measured from the contracted capacity at the first unsigned change of
+    /// the capacity period, so repeated changes cannot add up beyond the approval.
+    ///
+    /// # Errors
+    /// * `CustomError::CapacityChangeNotApproved` - If the change exceeds the approval
+    pub fn check_capacity_approval(&mut self, contracted_capacity: u64) -> Result<()> {
+        if self.capacity_approval_period != self.capacity_period_start
+            || self.capacity_approval_base == 0
+        {
+            self.reset_capacity_approval();
+        }
+        let base = self.capacity_approval_base;
+        let bound = base as u128 * self.capacity_approval_bps as u128 / 10_000;
+        require!(
+            contracted_capacity.abs_diff(base) as u128 <= bound,
+            CustomError::CapacityChangeNotApproved
+        );
+        Ok(())
+    }
+
+    /// Starts a new capacity period at the given slot, clearing the period's counters
+    ///
+    /// The closing period's usage is recorded in the consumer's usage history first.
+    pub fn start_capacity_period(&mut self, period_start: u64) {
+        record_period_usage(
+            &mut self.usage_history,
+            &mut self.usage_history_len,
+            self.period_usage,
+        );
+        self.capacity_period_start = period_start;
+        self.period_usage = 0;
+        self.period_waste = 0;
+        self.period_watc_bought = 0;
+    }
+
+    /// Returns the volume the consumer is expected to use over `window_slots` slots
+    ///
+    /// Averaged over the consumer's closed capacity periods of `period_slots` slots and
+    /// scaled by `weather_adjustment_bps`; 0 until a period has closed.
+    pub fn usage_baseline(
+        &self,
+        period_slots: u64,
+        window_slots: u64,
+        weather_adjustment_bps: u16,
+    ) -> u64 {
+        usage_baseline(
+            &self.usage_history[..self.usage_history_len as usize],
+            period_slots,
+            window_slots,
+            weather_adjustment_bps,
+        )
+    }
+
+    /// Extends or resets the conservation streak as the current period closes
+    pub fn close_conservation_period(&mut self, conserving: bool) {
+        self.conservation_streak = if conserving {
+            self.conservation_streak.saturating_add(1)
+        } else {
+            0
+        };
+    }
+
+    /// Spends a completed conservation streak on a certificate, returning its index
+    ///
+    /// # Errors
+    /// * `CustomError::ConservationStreakIncomplete` - If certificates are disabled or the
+    ///   streak is shorter than `required_periods`
+    pub fn award_certificate(&mut self, required_periods: u16) -> Result<u16> {
+        require!(
+            required_periods > 0 && self.conservation_streak >= required_periods,
+            CustomError::ConservationStreakIncomplete
+        );
+        self.conservation_streak -= required_periods;
+        let index = self.certificates_awarded;
+        self.certificates_awarded = self.certificates_awarded.saturating_add(1);
+        Ok(index)
+    }
+
+    /// Credits a top-up to the consumer's prepaid balance
+    ///
+    /// # Errors
+    /// * `CustomError::MathOverflow` - If the prepaid balance overflows
+    pub fn credit_prepaid(&mut self, amount: u64) -> Result<()> {
+        self.prepaid_balance = self
+            .prepaid_balance
+            .checked_add(amount)
+            .ok_or(error!(CustomError::MathOverflow))?;
+        Ok(())
+    }
+
+    /// Draws as much of a charge as the prepaid balance covers, returning the amount drawn
+    pub fn draw_prepaid(&mut self, charge: u64) -> u64 {
+        let drawn = charge.min(self.prepaid_balance);
+        self.prepaid_balance -= drawn;
+        drawn
+    }
+
+    /// Adds disposed waste to the period's waste volume
+    pub fn record_waste(&mut self, amount: u64) {
+        self.period_waste = self.period_waste.saturating_add(amount);
+    }
+
+    /// Attributes the emissions of delivered water to the consumer
+    pub fn record_emissions(&mut self, co2e_grams: u64) {
+        self.co2e_grams = self.co2e_grams.saturating_add(co2e_grams);
+    }
+
+    /// Folds an actual meter reading into the rolling average and the period's usage
+    pub fn record_actual_usage(&mut self, amount: u64) {
+        self.period_usage = self.period_usage.saturating_add(amount);
+        self.average_usage = if self.average_usage == 0 {
+            amount
+        } else {
+            ((self.average_usage as u128 * (USAGE_AVERAGE_WINDOW - 1) as u128 + amount as u128)
+                / USAGE_AVERAGE_WINDOW as u128) as u64
+        };
+    }
+
+    /// Records a cumulative meter register value submitted at the given timestamp
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidMeterReading` - If the value is below the previous reading
+    pub fn submit_reading(&mut self, reading: u64, unix_timestamp: i64) -> Result<()> {
+        require!(
+            reading >= self.meter_reading,
+            CustomError::InvalidMeterReading
+        );
+        self.meter_reading = reading;
+        self.meter_read_at = unix_timestamp;
+        Ok(())
+    }
+
+    /// Binds the consumer to an off-chain identity record, replacing any previous binding
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidIdentityUri` - If the URI is empty or longer than
+    ///   `IDENTITY_URI_MAX_LEN` bytes
+    pub fn set_identity(
+        &mut self,
+        identity_hash: [u8; 32],
+        identity_uri: Option<String>,
+    ) -> Result<()> {
+        if let Some(uri) = &identity_uri {
+            require!(
+                !uri.is_empty() && uri.len() <= IDENTITY_URI_MAX_LEN,
+                CustomError::InvalidIdentityUri
+            );
+        }
+        self.identity_hash = identity_hash;
+        self.identity_uri = identity_uri;
+        Ok(())
+    }
+
+    /// Recomputes the last metered charge from its pricing snapshot, flagging it if the
+    /// billed usage charge differs
+    ///
+    /// The scarcity multiplier is derived again from the recorded reservoir inputs and
+    /// capped by the recorded tariff bounds, so a faulty multiplier is flagged too. Charges
+    /// billed before the inputs were recorded are recomputed on the recorded multiplier.
+    ///
+    /// # Returns
+    /// * The flagged charge, or `None` if the charge was billed correctly
+    ///
+    /// # Errors
+    /// * `CustomError::NoChargeToVerify` - If no metered reading has been charged
+    /// * `CustomError::ChargeAlreadyFlagged` - If a flagged charge is still unresolved
+    /// * `CustomError::MathOverflow` - If the recomputed charge does not fit in a u64
+    pub fn verify_last_charge(&mut self) -> Result<Option<FlaggedCharge>> {
+        require!(self.last_pricing.slot > 0, CustomError::NoChargeToVerify);
+        require!(
+            self.flagged_charge.is_none(),
+            CustomError::ChargeAlreadyFlagged
+        );
+
+        let mut pricing = self.last_pricing;
+        if self.last_scarcity_inputs.capacity > 0 {
+            pricing.multiplier = self
+                .last_scarcity_inputs
+                .multiplier(&pricing.units())?
+                .into();
+        }
+
+        let billed = self.last_bill.usage_charge();
+        let recomputed = pricing.bill()?.usage_charge();
+        if billed == recomputed {
+            return Ok(None);
+        }
+
+        let flagged = FlaggedCharge {
+            billed,
+            recomputed,
+            slot: self.last_pricing.slot,
+        };
+        self.flagged_charge = Some(flagged);
+        Ok(Some(flagged))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{ScarcityBand, TariffType, SCARCITY_BANDS_MAX};
+
+    fn consumer() -> Consumer {
+        Consumer {
+            block_rate: 800,
+            contracted_capacity: 100000,
+            assigned_tariff: Pubkey::default(),
+            assigned_reservoir: Pubkey::default(),
+            guarantor: None,
+            master_consumer: None,
+            tenant_share_bps: 0,
+            sub_consumer_count: 0,
+            owner: Pubkey::default(),
+            autopay_enabled: false,
+            outstanding_water_debt: 0,
+            average_usage: 0,
+            estimated_usage: 0,
+            estimated_charge: 0,
+            budget_billing: false,
+            budget_amount: 0,
+            budget_paid: 0,
+            debt_since_slot: 0,
+            capacity_period_start: 0,
+            period_usage: 0,
+            period_waste: 0,
+            period_watc_bought: 0,
+            conservation_streak: 0,
+            certificates_awarded: 0,
+            household_size: 0,
+            contract_expiry_slot: 0,
+            meter_reading: 0,
+            meter_read_at: 0,
+            geohash: [0; 12],
+            identity_hash: [0; 32],
+            identity_uri: None,
+            co2e_grams: 0,
+            last_bill: BillBreakdown::default(),
+            last_pricing: PricingSnapshot::default(),
+            flagged_charge: None,
+            interruptible: false,
+            curtailed_periods_compensated: 0,
+            usage_history: [0; BASELINE_PERIODS],
+            usage_history_len: 0,
+            prepaid_balance: 0,
+            receipts_issued: 0,
+            capacity_approval_bps: 0,
+            capacity_approval_base: 0,
+            capacity_approval_period: 0,
+            last_scarcity_inputs: ScarcityInputs::default(),
+        }
+    }
+
+    #[test]
+    fn test_partial_water_payments() {
+        let mut consumer = consumer();
+        consumer.bill_water(50000, 10).unwrap();
+        consumer.bill_water(10000, 20).unwrap();
+        assert_eq!(consumer.debt_since_slot, 10);
+        consumer.pay_water(20000).unwrap();
+        assert_eq!(consumer.outstanding_water_debt, 40000);
+        assert_eq!(consumer.debt_since_slot, 10);
+        consumer.pay_water(40000).unwrap();
+        assert_eq!(consumer.outstanding_water_debt, 0);
+        assert_eq!(consumer.debt_since_slot, 0);
+    }
+
+    #[test]
+    fn test_water_overpayment_rejected() {
+        let mut consumer = consumer();
+        consumer.bill_water(50000, 10).unwrap();
+        assert!(consumer.pay_water(50001).is_err());
+        assert_eq!(consumer.outstanding_water_debt, 50000);
+    }
+
+    #[test]
+    fn test_water_debt_overflow() {
+        let mut consumer = consumer();
+        consumer.bill_water(u64::MAX, 10).unwrap();
+        assert!(consumer.bill_water(1, 20).is_err());
+    }
+
+    #[test]
+    fn test_prepaid_balance() {
+        let mut consumer = consumer();
+        consumer.credit_prepaid(6_000).unwrap();
+        assert_eq!(consumer.draw_prepaid(4_000), 4_000);
+        // Charges beyond the balance are only partly drawn
+        assert_eq!(consumer.draw_prepaid(4_000), 2_000);
+        assert_eq!(consumer.prepaid_balance, 0);
+        consumer.credit_prepaid(u64::MAX).unwrap();
+        assert!(consumer.credit_prepaid(1).is_err());
+    }
+
+    #[test]
+    fn test_capacity_approval() {
+        let mut consumer = consumer();
+        consumer.capacity_approval_bps = 1_000;
+        consumer.capacity_period_start = 100;
+
+        // Changes within 10% of the period's starting capacity are approved
+        consumer.check_capacity_approval(110_000).unwrap();
+        consumer.contracted_capacity = 110_000;
+        consumer.check_capacity_approval(90_000).unwrap();
+        // Repeated changes cannot drift beyond it
+        assert!(consumer.check_capacity_approval(120_000).is_err());
+
+        // A new period measures from the capacity it starts with
+        consumer.capacity_period_start = 200;
+        consumer.check_capacity_approval(120_000).unwrap();
+
+        consumer.capacity_approval_bps = 0;
+        assert!(consumer.check_capacity_approval(110_001).is_err());
+    }
+
+    #[test]
+    fn test_rolling_average_usage() {
+        let mut consumer = consumer();
+        consumer.record_actual_usage(6000);
+        assert_eq!(consumer.average_usage, 6000);
+        consumer.record_actual_usage(12000);
+        assert_eq!(consumer.average_usage, 7000);
+        consumer.record_actual_usage(u64::MAX);
+        assert!(consumer.average_usage > 7000);
+    }
+
+    #[test]
+    fn test_capacity_refresh_due() {
+        let mut consumer = consumer();
+        consumer.capacity_period_start = 1000;
+
+        assert_eq!(consumer.capacity_refresh_due(0, 5000), None);
+        assert_eq!(consumer.capacity_refresh_due(100, 1099), None);
+        assert_eq!(consumer.capacity_refresh_due(100, 1100), Some(1100));
+        assert_eq!(consumer.capacity_refresh_due(100, 1350), Some(1300));
+        assert_eq!(consumer.capacity_refresh_due(100, 500), None);
+    }
+
+    #[test]
+    fn test_start_capacity_period() {
+        let mut consumer = consumer();
+        consumer.record_actual_usage(40000);
+        consumer.record_actual_usage(20000);
+        consumer.record_waste(5000);
+        consumer.period_watc_bought = 10000;
+        assert_eq!(consumer.period_usage, 60000);
+        assert_eq!(consumer.period_waste, 5000);
+
+        consumer.start_capacity_period(1100);
+        assert_eq!(consumer.capacity_period_start, 1100);
+        assert_eq!(consumer.period_usage, 0);
+        assert_eq!(consumer.period_waste, 0);
+        assert_eq!(consumer.period_watc_bought, 0);
+        assert_eq!(consumer.usage_history[0], 60000);
+        assert_eq!(consumer.usage_history_len, 1);
+        // The rolling average outlives the period
+        assert!(consumer.average_usage > 0);
+    }
+
+    #[test]
+    fn test_block_remaining() {
+        let tariff = Tariff {
+            water_rate: 500,
+            waste_rate: 200,
+            tariff_type: TariffType::UniformIBT,
+            tariff_key: Pubkey::default(),
+            currency_code: [0; 3],
+            activated_slot: 0,
+            grace_period_slots: 0,
+            billing_period_slots: 0,
+            capacity_rollover_bps: 0,
+            block_threshold: 0,
+            per_capita_allowance: 15000,
+            assigned_consumer_count: 0,
+            conservation_baseline_bps: 0,
+            conservation_periods: 0,
+            bulk_rate: 0,
+            forecast_pricing: false,
+            max_multiplier: 0,
+            min_charge: 0,
+        };
+        let mut consumer = consumer();
+        consumer.record_actual_usage(40000);
+        assert_eq!(consumer.block_remaining(&tariff, 1000), 60000);
+
+        // Expired contracts are charged at the block rate
+        consumer.contract_expiry_slot = 1000;
+        assert_eq!(consumer.block_remaining(&tariff, 999), 60000);
+        assert_eq!(consumer.block_remaining(&tariff, 1000), 0);
+
+        consumer.contract_expiry_slot = 0;
+        consumer.household_size = 2;
+        assert_eq!(consumer.block_remaining(&tariff, 1000), 0);
+    }
+
+    #[test]
+    fn test_set_identity() {
+        let mut consumer = consumer();
+        consumer
+            .set_identity([7; 32], Some("ipfs://record".to_string()))
+            .unwrap();
+        assert_eq!(consumer.identity_hash, [7; 32]);
+
+        // Rejected URIs leave the previous binding in place
+        assert!(consumer.set_identity([8; 32], Some(String::new())).is_err());
+        let too_long = "x".repeat(IDENTITY_URI_MAX_LEN + 1);
+        assert!(consumer.set_identity([8; 32], Some(too_long)).is_err());
+        assert_eq!(consumer.identity_hash, [7; 32]);
+        assert_eq!(consumer.identity_uri.as_deref(), Some("ipfs://record"));
+
+        consumer.set_identity([8; 32], None).unwrap();
+        assert_eq!(consumer.identity_uri, None);
+    }
+
+    #[test]
+    fn test_submit_reading() {
+        let mut consumer = consumer();
+        consumer.submit_reading(1200, 1_760_000_000).unwrap();
+        consumer.submit_reading(1200, 1_760_000_600).unwrap();
+        assert_eq!(consumer.meter_read_at, 1_760_000_600);
+
+        // Registers never run backwards
+        assert!(consumer.submit_reading(1199, 1_760_001_200).is_err());
+        assert_eq!(consumer.meter_reading, 1200);
+    }
+
+    #[test]
+    fn test_conservation_streak() {
+        let mut consumer = consumer();
+        consumer.close_conservation_period(true);
+        consumer.close_conservation_period(false);
+        assert_eq!(consumer.conservation_streak, 0);
+
+        consumer.close_conservation_period(true);
+        consumer.close_conservation_period(true);
+        consumer.close_conservation_period(true);
+        assert!(consumer.award_certificate(4).is_err());
+        assert!(consumer.award_certificate(0).is_err());
+
+        assert_eq!(consumer.award_certificate(2).unwrap(), 0);
+        assert_eq!(consumer.conservation_streak, 1);
+        assert_eq!(consumer.certificates_awarded, 1);
+    }
+
+    #[test]
+    fn test_verify_last_charge() {
+        let mut consumer = consumer();
+        assert!(consumer.verify_last_charge().is_err());
+
+        consumer.last_pricing = PricingSnapshot {
+            tariff_activated_slot: 0,
+            water_rate: 500,
+            block_rate: 800,
+            max_multiplier: 0,
+            min_charge: 0,
+            block_remaining: 100000,
+            volume: 120000,
+            level: 950000,
+            multiplier: 1000,
+            volume_scale: 1000,
+            currency_decimals: 3,
+            slot: 10,
+        };
+        consumer.last_bill = consumer.last_pricing.bill().unwrap();
+        consumer.last_bill.add_penalty(4000).unwrap();
+        assert_eq!(consumer.verify_last_charge().unwrap(), None);
+
+        // An excess billed at twice the recorded multiplier is flagged
+        consumer.last_bill.excess = 32000;
+        let flagged = consumer.verify_last_charge().unwrap().unwrap();
+        assert_eq!((flagged.billed, flagged.recomputed), (82000, 66000));
+        assert_eq!(consumer.flagged_charge, Some(flagged));
+        assert!(consumer.verify_last_charge().is_err());
+    }
+
+    #[test]
+    fn test_verify_last_charge_derives_multiplier() {
+        let mut consumer = consumer();
+        let mut curve = [ScarcityBand::default(); SCARCITY_BANDS_MAX];
+        curve[0] = ScarcityBand {
+            max_level_bps: 3_000,
+            multiplier: 3000,
+        };
+        curve[1] = ScarcityBand {
+            max_level_bps: 10_000,
+            multiplier: 1000,
+        };
+        consumer.last_scarcity_inputs = ScarcityInputs {
+            tariff_type: TariffType::SeasonalIBT,
+            capacity: 1000000,
+            current_level: 950000,
+            smoothed_level: 250000,
+            smoothing_bps: 1_000,
+            forecast_level: None,
+            curve_len: 2,
+            curve,
+        };
+        consumer.last_pricing = PricingSnapshot {
+            tariff_activated_slot: 0,
+            water_rate: 500,
+            block_rate: 800,
+            max_multiplier: 0,
+            min_charge: 0,
+            block_remaining: 100000,
+            volume: 120000,
+            level: 250000,
+            multiplier: 3000,
+            volume_scale: 1000,
+            currency_decimals: 3,
+            slot: 10,
+        };
+        consumer.last_bill = consumer.last_pricing.bill().unwrap();
+        assert_eq!(consumer.verify_last_charge().unwrap(), None);
+
+        // Pricing on the current instead of the smoothed level picks the wrong band, which
+        // is flagged although the charge matches the multiplier recorded with it
+        consumer.last_pricing.multiplier = 1000;
+        consumer.last_bill = consumer.last_pricing.bill().unwrap();
+        let flagged = consumer.verify_last_charge().unwrap().unwrap();
+        assert_eq!((flagged.billed, flagged.recomputed), (66000, 98000));
+    }
 }
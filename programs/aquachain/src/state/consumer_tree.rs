@@ -0,0 +1,204 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+use crate::{utils::merkle_root_from_path, CustomError};
+
+/// Depth of a consumer tree, allowing 2^20 (about one million) compressed consumers
+pub const CONSUMER_TREE_DEPTH: usize = 20;
+
+/// Number of recent roots against which proofs are accepted
+pub const CONSUMER_TREE_ROOT_HISTORY: usize = 16;
+
+/// Represents an append-only Merkle tree of compressed consumer records.
+///
+/// Compressed consumers only exist as leaves of the tree, so they cost no rent. The
+/// full records are emitted in `ConsumerCompressed` events, from which an indexer can
+/// rebuild the tree and serve proofs. Only the rightmost path is stored on-chain, which
+/// is enough to append leaves, and a short history of roots lets a proof built against
+/// a recent root stay valid while other consumers are being compressed.
+///
+/// # Fields
+/// * `frontier` - Left siblings of the next leaf, one per level
+/// * `roots` - Ring buffer of the most recent roots
+/// * `root_index` - Index in `roots` of the current root
+/// * `next_index` - Position of the next leaf to be appended
+/// * `tree_key` - Unique identifier for this consumer tree
+#[account]
+#[derive(InitSpace)]
+pub struct ConsumerTree {
+    /// Left siblings of the next leaf to be appended, one per level of the tree.
+    pub frontier: [[u8; 32]; CONSUMER_TREE_DEPTH],
+
+    /// The most recent roots of the tree, oldest overwritten first.
+    pub roots: [[u8; 32]; CONSUMER_TREE_ROOT_HISTORY],
+
+    /// Index in `roots` of the current root.
+    pub root_index: u8,
+
+    /// Position of the next leaf, equal to the number of leaves appended so far.
+    pub next_index: u64,
+
+    /// The public key associated with this consumer tree,
+    /// used for identification.
+    pub tree_key: Pubkey,
+}
+
+/// Returns the root of an empty subtree for each level of the tree
+fn zero_hashes() -> [[u8; 32]; CONSUMER_TREE_DEPTH + 1] {
+    let mut zeros = [[0u8; 32]; CONSUMER_TREE_DEPTH + 1];
+    for level in 0..CONSUMER_TREE_DEPTH {
+        zeros[level + 1] = hashv(&[&zeros[level], &zeros[level]]).to_bytes();
+    }
+    zeros
+}
+
+impl ConsumerTree {
+    /// Resets the tree to an empty tree with the given key
+    pub fn initialize(&mut self, tree_key: Pubkey) {
+        self.frontier = [[0u8; 32]; CONSUMER_TREE_DEPTH];
+        self.roots = [[0u8; 32]; CONSUMER_TREE_ROOT_HISTORY];
+        self.roots[0] = zero_hashes()[CONSUMER_TREE_DEPTH];
+        self.root_index = 0;
+        self.next_index = 0;
+        self.tree_key = tree_key;
+    }
+
+    /// Hashes a consumer account's key and serialized data into a leaf
+    pub fn leaf(consumer: &Pubkey, data: &[u8]) -> [u8; 32] {
+        hashv(&[consumer.as_ref(), data]).to_bytes()
+    }
+
+    /// Returns the current root of the tree
+    pub fn root(&self) -> [u8; 32] {
+        self.roots[self.root_index as usize]
+    }
+
+    /// Appends a leaf to the tree and records the new root
+    ///
+    /// # Errors
+    /// * `CustomError::ConsumerTreeFull` - If every leaf of the tree is used
+    ///
+    /// # Returns
+    /// The position of the appended leaf
+    pub fn append(&mut self, leaf: [u8; 32]) -> Result<u64> {
+        let leaf_index = self.next_index;
+        require!(
+            leaf_index < (1u64 << CONSUMER_TREE_DEPTH),
+            CustomError::ConsumerTreeFull
+        );
+
+        let zeros = zero_hashes();
+        let mut node = leaf;
+        for (level, zero) in zeros.iter().enumerate().take(CONSUMER_TREE_DEPTH) {
+            if (leaf_index >> level) & 1 == 0 {
+                self.frontier[level] = node;
+                node = hashv(&[&node, zero]).to_bytes();
+            } else {
+                node = hashv(&[&self.frontier[level], &node]).to_bytes();
+            }
+        }
+
+        self.root_index = ((self.root_index as usize + 1) % CONSUMER_TREE_ROOT_HISTORY) as u8;
+        self.roots[self.root_index as usize] = node;
+        self.next_index += 1;
+        Ok(leaf_index)
+    }
+
+    /// Returns true if the root is one of the recent roots of the tree
+    pub fn is_recent_root(&self, root: &[u8; 32]) -> bool {
+        self.roots.contains(root)
+    }
+
+    /// Checks that a leaf is at the given position of the tree, as of a recent root
+    ///
+    /// # Arguments
+    /// * `leaf` - The hashed leaf to check
+    /// * `leaf_index` - Position of the leaf in the tree
+    /// * `root` - The root the proof was built against, which must be a recent root
+    /// * `proof` - Sibling nodes from the leaf up to the root
+    pub fn verify(
+        &self,
+        leaf: [u8; 32],
+        leaf_index: u64,
+        root: &[u8; 32],
+        proof: &[[u8; 32]],
+    ) -> bool {
+        proof.len() == CONSUMER_TREE_DEPTH
+            && leaf_index < self.next_index
+            && self.is_recent_root(root)
+            && merkle_root_from_path(leaf, leaf_index, proof) == *root
+    }
+}
+
+/// Marks a leaf of a consumer tree as decompressed, so the record it holds can only be
+/// restored once. Recompressing the consumer appends a new leaf.
+///
+/// # Fields
+/// * `consumer` - The consumer account restored from the leaf
+/// * `hydrated_slot` - Slot at which the consumer was restored
+#[account]
+#[derive(InitSpace)]
+pub struct ConsumerHydration {
+    /// The consumer account restored from the leaf.
+    pub consumer: Pubkey,
+
+    /// Slot at which the consumer was restored.
+    pub hydrated_slot: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree() -> ConsumerTree {
+        let mut tree = ConsumerTree {
+            frontier: [[0u8; 32]; CONSUMER_TREE_DEPTH],
+            roots: [[0u8; 32]; CONSUMER_TREE_ROOT_HISTORY],
+            root_index: 0,
+            next_index: 0,
+            tree_key: Pubkey::new_unique(),
+        };
+        tree.initialize(Pubkey::new_unique());
+        tree
+    }
+
+    #[test]
+    fn test_append_and_verify() {
+        let mut tree = tree();
+        let zeros = zero_hashes();
+        let first = ConsumerTree::leaf(&Pubkey::new_unique(), b"first");
+        let second = ConsumerTree::leaf(&Pubkey::new_unique(), b"second");
+
+        assert_eq!(tree.append(first).unwrap(), 0);
+        assert_eq!(tree.append(second).unwrap(), 1);
+
+        let mut proof = zeros[..CONSUMER_TREE_DEPTH].to_vec();
+        proof[0] = first;
+        assert!(tree.verify(second, 1, &tree.root(), &proof));
+        proof[0] = second;
+        assert!(tree.verify(first, 0, &tree.root(), &proof));
+    }
+
+    #[test]
+    fn test_recent_roots_remain_valid() {
+        let mut tree = tree();
+        let zeros = zero_hashes();
+        let first = ConsumerTree::leaf(&Pubkey::new_unique(), b"first");
+
+        tree.append(first).unwrap();
+        let root = tree.root();
+        tree.append(ConsumerTree::leaf(&Pubkey::new_unique(), b"second"))
+            .unwrap();
+
+        // A proof built before the second append still verifies against its root
+        let proof = zeros[..CONSUMER_TREE_DEPTH].to_vec();
+        assert!(tree.verify(first, 0, &root, &proof));
+        assert!(!tree.verify(first, 0, &[7u8; 32], &proof));
+    }
+
+    #[test]
+    fn test_reject_unappended_leaf() {
+        let tree = tree();
+        let proof = zero_hashes()[..CONSUMER_TREE_DEPTH].to_vec();
+        assert!(!tree.verify([0u8; 32], 0, &tree.root(), &proof));
+    }
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+/// Identifies which of a consumer's debts a credit applies to.
+///
+/// # Variants
+/// * `Water` - Water usage debt, held as WTK
+/// * `Waste` - Waste treatment debt, held as WST
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DebtKind {
+    /// Debt billed for water usage, denominated in WTK
+    Water,
+
+    /// Debt billed for waste treatment, denominated in WST
+    Waste,
+}
+
+/// Reason recorded on a credit note for a billing correction.
+///
+/// # Variants
+/// * `MeterMisread` - The meter reading used for billing was wrong
+/// * `PricingError` - The consumer was billed at the wrong rate
+/// * `Goodwill` - Discretionary credit granted by the agency
+/// * `Other` - Any other correction, documented off-chain
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CreditReason {
+    /// The meter reading used for billing was wrong
+    MeterMisread,
+
+    /// A tariff or pricing bug overcharged the consumer
+    PricingError,
+
+    /// Discretionary credit granted by the agency
+    Goodwill,
+
+    /// Any other correction, documented off-chain
+    Other,
+}
+
+/// Represents a billing correction issued to a consumer.
+///
+/// Each credit note permanently records how much debt was written off and why,
+/// so corrections can be audited after the tokens have been burned.
+///
+/// # Fields
+/// * `consumer` - The consumer account that received the credit
+/// * `kind` - Which debt the credit was applied to
+/// * `amount` - Amount of debt written off, in token base units
+/// * `reason` - Reason code for the correction
+/// * `issued_slot` - Slot at which the credit was issued
+/// * `note_key` - Public key associated with this credit note
+///
+/// # Example
+/// ```ignore
+/// let credit_note = CreditNote {
+///     consumer: consumer_pubkey,
+///     kind: DebtKind::Water,
+///     amount: 12500,  // 12.500 WTK written off
+///     reason: CreditReason::MeterMisread,
+///     issued_slot: 1000,
+///     note_key: pubkey,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct CreditNote {
+    /// The consumer account that received the credit.
+    pub consumer: Pubkey,
+
+    /// Which of the consumer's debts the credit was applied to.
+    pub kind: DebtKind,
+
+    /// Amount of debt written off, in base units of the WTK or WST mint.
+    pub amount: u64,
+
+    /// Reason code explaining why the correction was issued.
+    pub reason: CreditReason,
+
+    /// Slot at which the credit was issued.
+    pub issued_slot: u64,
+
+    /// The public key associated with this credit note,
+    /// used for identification.
+    pub note_key: Pubkey,
+}
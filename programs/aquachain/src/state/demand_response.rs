@@ -0,0 +1,220 @@
+use anchor_lang::prelude::*;
+
+use crate::CustomError;
+
+/// A demand-response event called by an agency during a shortage.
+///
+/// The agency announces a window of slots in which it wants to relieve a reservoir and
+/// the total volume of reductions it seeks. Consumers of the reservoir bid the volume
+/// they commit not to use during the window, against a baseline of their usage over
+/// their last closed capacity periods, adjusted for the forecast weather.
+/// Once the window has closed, every verified reduction is paid from the agency's
+/// treasury at the event's payment rate.
+///
+/// # Fields
+/// * `agency` - Agency calling the event
+/// * `event_key` - Unique identifier for the event
+/// * `reservoir_key` - Reservoir the event relieves; only its consumers may bid
+/// * `settlement_mint` - Stablecoin mint reductions are paid in
+/// * `payment_rate` - Stablecoin base units paid per raw volume unit of verified reduction
+/// * `weather_adjustment_bps` - Scaling of consumers' baselines for the forecast weather
+/// * `target_volume` - Total reduction sought, in raw volume units
+/// * `bid_volume` - Total reduction bid by consumers so far, in raw volume units
+/// * `start_slot` - First slot of the window
+/// * `end_slot` - Slot at which the window closes
+/// * `verified_volume` - Total reduction verified at settlement, in raw volume units
+/// * `paid` - Total paid to consumers for verified reductions, in stablecoin base units
+///
+/// # Example
+/// ```ignore
+/// let dr_event = DrEvent {
+///     agency: agency_pubkey,
+///     event_key: event_pubkey,
+///     reservoir_key: reservoir_pubkey,
+///     settlement_mint: usdc_mint,
+///     payment_rate: 2,            // 2 micro-USDC per litre, 2 USDC per m³
+///     weather_adjustment_bps: 11_500, // heatwave, demand 15% above baseline
+///     target_volume: 50_000_000,  // 50,000 m³ metered in litres
+///     bid_volume: 0,
+///     start_slot: 250_000_000,
+///     end_slot: 250_216_000,      // about a day
+///     verified_volume: 0,
+///     paid: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct DrEvent {
+    /// Agency calling the event.
+    pub agency: Pubkey,
+
+    /// Unique identifier for the event.
+    pub event_key: Pubkey,
+
+    /// Reservoir the event relieves.
+    pub reservoir_key: Pubkey,
+
+    /// Stablecoin mint reductions are paid in.
+    pub settlement_mint: Pubkey,
+
+    /// Stablecoin base units paid per raw volume unit of verified reduction.
+    pub payment_rate: u64,
+
+    /// Scaling of consumers' baselines for the forecast weather, in basis points.
+    pub weather_adjustment_bps: u16,
+
+    /// Total reduction sought, in raw volume units.
+    pub target_volume: u64,
+
+    /// Total reduction bid by consumers so far, in raw volume units.
+    pub bid_volume: u64,
+
+    /// First slot of the window.
+    pub start_slot: u64,
+
+    /// Slot at which the window closes.
+    pub end_slot: u64,
+
+    /// Total reduction verified at settlement, in raw volume units.
+    pub verified_volume: u64,
+
+    /// Total paid to consumers for verified reductions, in stablecoin base units.
+    pub paid: u64,
+}
+
+impl DrEvent {
+    /// Returns the number of slots in the event's window
+    pub fn window_slots(&self) -> u64 {
+        self.end_slot.saturating_sub(self.start_slot)
+    }
+
+    /// Adds a bid of `volume` to the reductions bid on the event
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidCurtailmentBid` - If the bid would exceed the volume sought
+    pub fn add_bid(&mut self, volume: u64) -> Result<()> {
+        let bid_volume = self
+            .bid_volume
+            .checked_add(volume)
+            .filter(|bid_volume| *bid_volume <= self.target_volume)
+            .ok_or(CustomError::InvalidCurtailmentBid)?;
+        self.bid_volume = bid_volume;
+        Ok(())
+    }
+}
+
+/// A consumer's bid to shed load during a demand-response event.
+///
+/// # Fields
+/// * `dr_event` - Event the bid was made on
+/// * `consumer` - Consumer committing to the reduction
+/// * `volume` - Reduction bid, in raw volume units
+/// * `baseline` - Volume the consumer was expected to use during the window
+/// * `start_slot` - First slot of the event's window
+/// * `end_slot` - Slot at which the event's window closes
+/// * `usage` - Volume the consumer used during the window
+/// * `settled` - Whether the bid was settled
+/// * `payment` - Amount paid for the verified reduction, in stablecoin base units
+#[account]
+#[derive(InitSpace)]
+pub struct DrBid {
+    /// Event the bid was made on.
+    pub dr_event: Pubkey,
+
+    /// Consumer committing to the reduction.
+    pub consumer: Pubkey,
+
+    /// Reduction bid, in raw volume units.
+    pub volume: u64,
+
+    /// Volume the consumer was expected to use during the window, in raw volume units.
+    pub baseline: u64,
+
+    /// First slot of the event's window.
+    pub start_slot: u64,
+
+    /// Slot at which the event's window closes.
+    pub end_slot: u64,
+
+    /// Volume the consumer used during the window, in raw volume units.
+    pub usage: u64,
+
+    /// Whether the bid was settled.
+    pub settled: bool,
+
+    /// Amount paid for the verified reduction, in stablecoin base units.
+    pub payment: u64,
+}
+
+impl DrBid {
+    /// Adds `amount` used at `slot` to the usage during the window
+    pub fn record_usage(&mut self, amount: u64, slot: u64) {
+        if slot >= self.start_slot && slot < self.end_slot {
+            self.usage = self.usage.saturating_add(amount);
+        }
+    }
+
+    /// Returns the verified reduction: the usage saved against the baseline, up to the bid
+    pub fn verified_reduction(&self) -> u64 {
+        self.baseline.saturating_sub(self.usage).min(self.volume)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bid(volume: u64, baseline: u64) -> DrBid {
+        DrBid {
+            dr_event: Pubkey::default(),
+            consumer: Pubkey::default(),
+            volume,
+            baseline,
+            start_slot: 1_000,
+            end_slot: 2_000,
+            usage: 0,
+            settled: false,
+            payment: 0,
+        }
+    }
+
+    #[test]
+    fn test_verified_reduction() {
+        let mut bid = bid(6_000, 10_000);
+
+        // Usage outside the window does not count
+        bid.record_usage(5_000, 999);
+        bid.record_usage(5_000, 2_000);
+        assert_eq!(bid.usage, 0);
+        // Reductions beyond the bid are not paid
+        assert_eq!(bid.verified_reduction(), 6_000);
+
+        bid.record_usage(7_000, 1_500);
+        assert_eq!(bid.verified_reduction(), 3_000);
+        bid.record_usage(4_000, 1_999);
+        assert_eq!(bid.verified_reduction(), 0);
+    }
+
+    #[test]
+    fn test_add_bid() {
+        let mut dr_event = DrEvent {
+            agency: Pubkey::default(),
+            event_key: Pubkey::default(),
+            reservoir_key: Pubkey::default(),
+            settlement_mint: Pubkey::default(),
+            payment_rate: 2,
+            weather_adjustment_bps: 10_000,
+            target_volume: 10_000,
+            bid_volume: 0,
+            start_slot: 1_000,
+            end_slot: 2_000,
+            verified_volume: 0,
+            paid: 0,
+        };
+        dr_event.add_bid(6_000).unwrap();
+        assert!(dr_event.add_bid(5_000).is_err());
+        dr_event.add_bid(4_000).unwrap();
+        assert_eq!(dr_event.bid_volume, 10_000);
+        assert_eq!(dr_event.window_slots(), 1_000);
+    }
+}
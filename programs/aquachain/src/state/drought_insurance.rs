@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+
+use super::Reservoir;
+use crate::utils::bps_of;
+
+/// Insurance pool offsetting drought surcharges for the consumers of a reservoir.
+///
+/// Consumers who opt in are billed a premium each capacity period, which is added to the
+/// pool. While the reservoir is below the trigger level, part of the surcharge each
+/// covered consumer is billed above the flat water rate is paid out of the pool instead.
+///
+/// # Fields
+/// * `agency` - Agency operating the pool
+/// * `reservoir_key` - Reservoir whose level triggers payouts
+/// * `premium` - Amount of WTK billed to each covered consumer per capacity period
+/// * `payout_bps` - Share of the surcharge paid out of the pool, in basis points
+/// * `trigger_level_bps` - Reservoir level below which payouts apply, in bps of capacity
+/// * `balance` - Premiums collected less payouts made, in WTK base units
+/// * `member_count` - Number of consumers currently covered
+///
+/// # Example
+/// ```ignore
+/// let pool = DroughtInsurance {
+///     agency: agency_pubkey,
+///     reservoir_key: reservoir_pubkey,
+///     premium: 500,             // 0.500 WTK per period
+///     payout_bps: 5_000,        // half of the surcharge
+///     trigger_level_bps: 2_000, // below 20% of capacity
+///     balance: 0,
+///     member_count: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct DroughtInsurance {
+    /// Agency operating the pool.
+    pub agency: Pubkey,
+
+    /// Reservoir whose level triggers payouts.
+    pub reservoir_key: Pubkey,
+
+    /// Amount of WTK billed to each covered consumer per capacity period.
+    pub premium: u64,
+
+    /// Share of a covered consumer's drought surcharge paid out of the pool, in basis points.
+    pub payout_bps: u16,
+
+    /// Reservoir level below which payouts apply, in basis points of its capacity.
+    pub trigger_level_bps: u16,
+
+    /// Premiums collected less payouts made, in WTK base units.
+    pub balance: u64,
+
+    /// Number of consumers currently covered.
+    pub member_count: u64,
+}
+
+impl DroughtInsurance {
+    /// Returns true if the reservoir is below the level that triggers payouts
+    pub fn is_triggered(&self, reservoir: &Reservoir) -> bool {
+        reservoir.current_level < bps_of(reservoir.capacity, self.trigger_level_bps)
+    }
+
+    /// Returns the payout offsetting a surcharge, capped by the pool's balance
+    pub fn payout_for(&self, surcharge: u64) -> u64 {
+        bps_of(surcharge, self.payout_bps).min(self.balance)
+    }
+
+    /// Adds a collected premium to the pool
+    pub fn deposit(&mut self, premium: u64) {
+        self.balance = self.balance.saturating_add(premium);
+    }
+
+    /// Takes a payout out of the pool
+    pub fn pay_out(&mut self, amount: u64) {
+        self.balance = self.balance.saturating_sub(amount);
+    }
+}
+
+/// A consumer's membership of a drought insurance pool.
+///
+/// # Fields
+/// * `consumer` - Covered consumer account
+/// * `reservoir_key` - Reservoir of the pool the consumer joined
+/// * `paid_period_start` - Start of the latest capacity period the premium was paid for
+/// * `total_payouts` - Surcharges paid out of the pool for this consumer, in WTK base units
+#[account]
+#[derive(InitSpace)]
+pub struct DroughtCover {
+    /// Covered consumer account.
+    pub consumer: Pubkey,
+
+    /// Reservoir of the pool the consumer joined.
+    pub reservoir_key: Pubkey,
+
+    /// Start slot of the latest capacity period the premium was paid for, if any.
+    pub paid_period_start: Option<u64>,
+
+    /// Surcharges paid out of the pool for this consumer, in WTK base units.
+    pub total_payouts: u64,
+}
+
+impl DroughtCover {
+    /// Returns true if the premium has been paid for the period starting at `period_start`
+    pub fn is_covered(&self, period_start: u64) -> bool {
+        self.paid_period_start == Some(period_start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> DroughtInsurance {
+        DroughtInsurance {
+            agency: Pubkey::default(),
+            reservoir_key: Pubkey::default(),
+            premium: 500,
+            payout_bps: 5_000,
+            trigger_level_bps: 2_000,
+            balance: 0,
+            member_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_is_triggered() {
+        let mut reservoir = Reservoir {
+            current_level: 200_000,
+            capacity: 1_000_000,
+            reservoir_key: Pubkey::default(),
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 0,
+            scarcity_curve: vec![],
+            smoothing_bps: 0,
+            smoothed_level: 0,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 0,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 0,
+            alert_level_high: 0,
+            alert_level_low: 0,
+        };
+        assert!(!pool().is_triggered(&reservoir));
+        reservoir.current_level = 199_999;
+        assert!(pool().is_triggered(&reservoir));
+    }
+
+    #[test]
+    fn test_payout_capped_by_balance() {
+        let mut pool = pool();
+        pool.deposit(1_000);
+        assert_eq!(pool.payout_for(1_200), 600);
+        assert_eq!(pool.payout_for(4_000), 1_000);
+
+        pool.pay_out(600);
+        assert_eq!(pool.balance, 400);
+        assert_eq!(pool.payout_for(1_200), 400);
+    }
+
+    #[test]
+    fn test_is_covered() {
+        let mut cover = DroughtCover {
+            consumer: Pubkey::default(),
+            reservoir_key: Pubkey::default(),
+            paid_period_start: None,
+            total_payouts: 0,
+        };
+        assert!(!cover.is_covered(0));
+        cover.paid_period_start = Some(0);
+        assert!(cover.is_covered(0));
+        assert!(!cover.is_covered(1_000));
+    }
+}
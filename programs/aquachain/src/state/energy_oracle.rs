@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+/// Represents an electricity price feed used to pass pumping costs through to consumers.
+///
+/// Pumping water up to high pressure zones is a utility's largest energy cost. The price
+/// posted here is multiplied by the pumping intensity of a consumer's reservoir zone to
+/// add an energy surcharge to each bill, so tariffs track power price spikes. The price is
+/// pushed by a dedicated oracle authority.
+///
+/// # Fields
+/// * `agency` - Agency the price feed belongs to
+/// * `authority` - Key allowed to post new prices
+/// * `price` - Price of one kWh in the tariff currency, with three implied decimals
+/// * `max_staleness_slots` - Maximum age of a price before billing is refused
+/// * `last_updated_slot` - Slot at which the current price was posted
+///
+/// # Example
+/// ```ignore
+/// let oracle = EnergyOracle {
+///     agency: agency_pubkey,
+///     authority: oracle_pubkey,
+///     price: 250, // 0.250 per kWh
+///     max_staleness_slots: 9_000,
+///     last_updated_slot: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct EnergyOracle {
+    /// Agency the price feed belongs to.
+    pub agency: Pubkey,
+
+    /// Public key allowed to post new electricity prices.
+    pub authority: Pubkey,
+
+    /// Price of one kWh in the tariff currency, with three implied decimals.
+    pub price: u64,
+
+    /// Maximum number of slots a price remains usable for billing.
+    pub max_staleness_slots: u64,
+
+    /// Slot at which the current price was posted.
+    pub last_updated_slot: u64,
+}
+
+impl EnergyOracle {
+    /// Checks whether the posted price is too old to be used at the given slot
+    pub fn is_stale(&self, slot: u64) -> bool {
+        self.price == 0 || slot.saturating_sub(self.last_updated_slot) > self.max_staleness_slots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staleness() {
+        let mut oracle = EnergyOracle {
+            agency: Pubkey::default(),
+            authority: Pubkey::default(),
+            price: 250,
+            max_staleness_slots: 100,
+            last_updated_slot: 1_000,
+        };
+        assert!(!oracle.is_stale(1_100));
+        assert!(oracle.is_stale(1_101));
+
+        // An unset price is never usable
+        oracle.price = 0;
+        assert!(oracle.is_stale(1_000));
+    }
+}
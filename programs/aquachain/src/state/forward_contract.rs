@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+use super::UnitConfig;
+use crate::utils::FixedPoint;
+
+/// Capacity a consumer has bought forward for its next billing period.
+///
+/// The consumer locks the tariff's current water rate for capacity delivered at the start
+/// of the next period, paying a premium upfront. On delivery the capacity is minted as WATC
+/// and billed at the locked rate, whatever the tariff's rate is by then.
+///
+/// # Fields
+/// * `consumer` - Consumer the capacity is delivered to
+/// * `tariff_key` - Tariff whose rate was locked
+/// * `capacity` - Raw volume of capacity to deliver
+/// * `locked_rate` - Water rate locked for the capacity, with three implied decimals
+/// * `premium` - Amount of WTK billed upfront for the lock
+/// * `delivery_slot` - Slot from which the capacity can be delivered
+///
+/// # Example
+/// ```ignore
+/// let forward = ForwardContract {
+///     consumer: consumer_pubkey,
+///     tariff_key: tariff_pubkey,
+///     capacity: 100_000,        // 100.000 units of capacity
+///     locked_rate: 500,         // 0.500 per unit
+///     premium: 2_000,           // 2.000 WTK
+///     delivery_slot: 2_592_000, // start of the next period
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct ForwardContract {
+    /// Consumer the capacity is delivered to.
+    pub consumer: Pubkey,
+
+    /// Tariff whose rate was locked.
+    pub tariff_key: Pubkey,
+
+    /// Raw volume of capacity to deliver.
+    pub capacity: u64,
+
+    /// Water rate locked for the capacity, with three implied decimals.
+    pub locked_rate: u64,
+
+    /// Amount of WTK billed upfront for the lock.
+    pub premium: u64,
+
+    /// Slot from which the capacity can be delivered.
+    pub delivery_slot: u64,
+}
+
+impl ForwardContract {
+    /// Computes the cost of delivering the capacity at the locked rate
+    ///
+    /// # Errors
+    /// * `CustomError::MathOverflow` - If the converted volume or cost does not fit in a u64
+    pub fn delivery_cost(&self, units: &UnitConfig) -> Result<u64> {
+        units.to_currency(units.to_volume(self.capacity)? * FixedPoint::from(self.locked_rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delivery_cost_at_locked_rate() {
+        let forward = ForwardContract {
+            consumer: Pubkey::default(),
+            tariff_key: Pubkey::default(),
+            capacity: 100_000,
+            locked_rate: 500,
+            premium: 2_000,
+            delivery_slot: 0,
+        };
+        let units = UnitConfig {
+            volume_scale: 1000,
+            currency_decimals: 3,
+        };
+        // 100.000 units at 0.500 is 50.000 WTK
+        assert_eq!(forward.delivery_cost(&units).unwrap(), 50_000);
+    }
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+
+use crate::CustomError;
+
+/// Represents an exchange rate feed between a tariff's local currency and a settlement mint.
+///
+/// Tariff rates, and therefore WTK/WST debts, are denominated in a local fiat reference.
+/// Settlement happens in an SPL stablecoin, so the debt is converted at the rate posted
+/// here at the moment of payment. The rate is pushed by a dedicated oracle authority.
+///
+/// # Fields
+/// * `currency_code` - ISO 4217 code of the local currency (e.g. `b"TTD"`)
+/// * `settlement_mint` - Mint of the stablecoin used to settle debts
+/// * `authority` - Key allowed to post new rates
+/// * `rate` - Settlement base units per whole unit of the local currency
+/// * `max_staleness_slots` - Maximum age of a rate before settlement is refused
+/// * `last_updated_slot` - Slot at which the current rate was posted
+///
+/// # Example
+/// ```ignore
+/// let oracle = FxOracle {
+///     currency_code: *b"TTD",
+///     settlement_mint: usdc_mint,
+///     authority: oracle_pubkey,
+///     rate: 147_300,            // 1 TTD = 0.1473 USDC (6 decimals)
+///     max_staleness_slots: 9_000,
+///     last_updated_slot: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct FxOracle {
+    /// ISO 4217 code of the local currency the tariff is denominated in.
+    pub currency_code: [u8; 3],
+
+    /// Mint of the stablecoin in which debts are settled.
+    pub settlement_mint: Pubkey,
+
+    /// Public key allowed to post new exchange rates.
+    pub authority: Pubkey,
+
+    /// Number of settlement base units worth one whole unit of the local currency.
+    pub rate: u64,
+
+    /// Maximum number of slots a rate remains usable for settlement.
+    pub max_staleness_slots: u64,
+
+    /// Slot at which the current rate was posted.
+    pub last_updated_slot: u64,
+}
+
+impl FxOracle {
+    /// Checks whether the posted rate is too old to be used at the given slot
+    pub fn is_stale(&self, slot: u64) -> bool {
+        self.rate == 0 || slot.saturating_sub(self.last_updated_slot) > self.max_staleness_slots
+    }
+
+    /// Converts an amount of local currency base units into settlement base units
+    ///
+    /// # Arguments
+    /// * `amount` - Amount in base units of the local currency
+    /// * `local_decimals` - Decimals of the local currency (the WTK/WST mints)
+    ///
+    /// # Errors
+    /// * `CustomError::MathOverflow` - If the converted amount does not fit in a u64
+    pub fn to_settlement(&self, amount: u64, local_decimals: u8) -> Result<u64> {
+        let converted = (amount as u128)
+            .checked_mul(self.rate as u128)
+            .ok_or(error!(CustomError::MathOverflow))?
+            / 10u128.pow(local_decimals as u32);
+        u64::try_from(converted).map_err(|_| error!(CustomError::MathOverflow))
+    }
+}
+
+/// Checks that a currency code is made of three uppercase ASCII letters
+pub fn is_valid_currency_code(code: &[u8; 3]) -> bool {
+    code.iter().all(|c| c.is_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oracle(rate: u64, last_updated_slot: u64) -> FxOracle {
+        FxOracle {
+            currency_code: *b"TTD",
+            settlement_mint: Pubkey::default(),
+            authority: Pubkey::default(),
+            rate,
+            max_staleness_slots: 100,
+            last_updated_slot,
+        }
+    }
+
+    #[test]
+    fn test_to_settlement_conversion() {
+        // 1 TTD = 0.147300 USDC, WTK has 3 decimals
+        let oracle = oracle(147_300, 0);
+        // 50.000 TTD -> 7.365000 USDC
+        assert_eq!(oracle.to_settlement(50_000, 3).unwrap(), 7_365_000);
+    }
+
+    #[test]
+    fn test_staleness() {
+        let oracle = oracle(147_300, 1_000);
+        assert!(!oracle.is_stale(1_100));
+        assert!(oracle.is_stale(1_101));
+    }
+
+    #[test]
+    fn test_unset_rate_is_stale() {
+        let oracle = oracle(0, 1_000);
+        assert!(oracle.is_stale(1_000));
+    }
+
+    #[test]
+    fn test_currency_code_validation() {
+        assert!(is_valid_currency_code(b"TTD"));
+        assert!(!is_valid_currency_code(b"ttd"));
+        assert!(!is_valid_currency_code(&[0, 0, 0]));
+    }
+}
@@ -0,0 +1,75 @@
+/// Number of geohash characters stored, about 4 cm of precision
+pub const GEOHASH_LEN: usize = 12;
+
+/// Characters of the geohash base32 alphabet, in value order
+const GEOHASH_ALPHABET: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Returns the number of characters set in a zero-padded geohash
+///
+/// # Arguments
+/// * `geohash` - The geohash, padded with zeroes after its last character
+pub fn geohash_precision(geohash: &[u8; GEOHASH_LEN]) -> usize {
+    geohash.iter().take_while(|c| **c != 0).count()
+}
+
+/// Checks that a geohash uses the base32 alphabet and is only padded at the end
+///
+/// # Arguments
+/// * `geohash` - The geohash, padded with zeroes after its last character
+pub fn is_valid_geohash(geohash: &[u8; GEOHASH_LEN]) -> bool {
+    let (set, padding) = geohash.split_at(geohash_precision(geohash));
+    set.iter().all(|c| GEOHASH_ALPHABET.contains(c)) && padding.iter().all(|c| *c == 0)
+}
+
+/// Checks that a location falls within a zone, i.e. that the zone's geohash is a prefix
+/// of the location's
+///
+/// Locations and zones are optional, so an unset geohash on either side matches.
+///
+/// # Arguments
+/// * `location` - The geohash of the meter or consumer
+/// * `zone` - The geohash prefix registered for the zone
+pub fn is_within_zone(location: &[u8; GEOHASH_LEN], zone: &[u8; GEOHASH_LEN]) -> bool {
+    let zone_precision = geohash_precision(zone);
+    let location_precision = geohash_precision(location);
+    zone_precision == 0
+        || location_precision == 0
+        || (location_precision >= zone_precision
+            && location[..zone_precision] == zone[..zone_precision])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geohash(value: &str) -> [u8; GEOHASH_LEN] {
+        let mut geohash = [0; GEOHASH_LEN];
+        geohash[..value.len()].copy_from_slice(value.as_bytes());
+        geohash
+    }
+
+    #[test]
+    fn test_is_valid_geohash() {
+        assert!(is_valid_geohash(&geohash("d5ttkz")));
+        assert!(is_valid_geohash(&geohash("")));
+        // 'a' is not part of the geohash alphabet
+        assert!(!is_valid_geohash(&geohash("d5ta")));
+
+        let mut gap = geohash("d5");
+        gap[3] = b'k';
+        assert!(!is_valid_geohash(&gap));
+    }
+
+    #[test]
+    fn test_is_within_zone() {
+        let zone = geohash("d5tt");
+        assert!(is_within_zone(&geohash("d5ttkz9x"), &zone));
+        assert!(is_within_zone(&geohash("d5tt"), &zone));
+        assert!(!is_within_zone(&geohash("d5tv9x"), &zone));
+        assert!(!is_within_zone(&geohash("d5t"), &zone));
+
+        // Unset geohashes are not validated
+        assert!(is_within_zone(&geohash(""), &zone));
+        assert!(is_within_zone(&geohash("d5tv9x"), &geohash("")));
+    }
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+
+/// Number of consecutive days stored in one archive shard
+pub const HYDRO_ARCHIVE_SHARD_DAYS: usize = 32;
+
+/// Number of days a shard is kept after its last day before it may be pruned
+pub const HYDRO_ARCHIVE_RETENTION_DAYS: u64 = 366;
+
+/// Hydro-meteorological summary of a reservoir for one day.
+///
+/// # Fields
+/// * `level` - Reservoir level when the day was last recorded, 0 if the day was not recorded
+/// * `inflow` - Volume that flowed into the reservoir that day
+/// * `outflow` - Volume released from the reservoir that day
+#[derive(
+    InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, Eq, PartialEq,
+)]
+pub struct HydroDay {
+    pub level: u64,
+    pub inflow: u64,
+    pub outflow: u64,
+}
+
+/// One shard of a reservoir's hydro-meteorological archive.
+///
+/// The archive is split into shards of `HYDRO_ARCHIVE_SHARD_DAYS` consecutive days, each
+/// its own PDA, so modelers can read about a year of daily history from the chain while
+/// shards older than `HYDRO_ARCHIVE_RETENTION_DAYS` are closed and their rent reclaimed.
+///
+/// # Fields
+/// * `reservoir_key` - Public key of the reservoir the shard belongs to
+/// * `shard` - Index of the shard, its first day divided by the shard length
+/// * `payer` - Key that paid the shard's rent, refunded when the shard is pruned
+/// * `days` - Daily summaries, the entry for `day` stored at `day % HYDRO_ARCHIVE_SHARD_DAYS`
+#[account]
+#[derive(InitSpace)]
+pub struct HydroArchiveShard {
+    /// The public key of the reservoir the shard belongs to.
+    pub reservoir_key: Pubkey,
+
+    /// Index of the shard, counted in shards of days since the unix epoch.
+    pub shard: u64,
+
+    /// The key that paid the shard's rent, refunded when it is pruned.
+    pub payer: Pubkey,
+
+    /// Daily summaries of the shard's days.
+    pub days: [HydroDay; HYDRO_ARCHIVE_SHARD_DAYS],
+}
+
+impl HydroArchiveShard {
+    /// Returns the index of the shard storing `day`
+    pub fn shard_of(day: u64) -> u64 {
+        day / HYDRO_ARCHIVE_SHARD_DAYS as u64
+    }
+
+    /// Returns the last day stored in the shard
+    pub fn last_day(&self) -> u64 {
+        (self.shard + 1) * HYDRO_ARCHIVE_SHARD_DAYS as u64 - 1
+    }
+
+    /// Records the level of `day` and adds its inflow and outflow
+    pub fn record(&mut self, day: u64, level: u64, inflow: u64, outflow: u64) {
+        let entry = &mut self.days[day as usize % HYDRO_ARCHIVE_SHARD_DAYS];
+        entry.level = level;
+        entry.inflow = entry.inflow.saturating_add(inflow);
+        entry.outflow = entry.outflow.saturating_add(outflow);
+    }
+
+    /// Returns true if every day of the shard is past the retention window on `today`
+    pub fn is_prunable(&self, today: u64) -> bool {
+        self.last_day().saturating_add(HYDRO_ARCHIVE_RETENTION_DAYS) < today
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shard(day: u64) -> HydroArchiveShard {
+        HydroArchiveShard {
+            reservoir_key: Pubkey::default(),
+            shard: HydroArchiveShard::shard_of(day),
+            payer: Pubkey::default(),
+            days: [HydroDay::default(); HYDRO_ARCHIVE_SHARD_DAYS],
+        }
+    }
+
+    #[test]
+    fn test_record() {
+        let mut archive = shard(20031);
+        archive.record(20031, 800000, 1000, 4000);
+        archive.record(20031, 795000, 500, 2000);
+
+        let entry = archive.days[20031 % HYDRO_ARCHIVE_SHARD_DAYS];
+        assert_eq!(entry.level, 795000);
+        assert_eq!((entry.inflow, entry.outflow), (1500, 6000));
+    }
+
+    #[test]
+    fn test_is_prunable() {
+        // Day 20031 falls in the shard covering days 20000 to 20031
+        let archive = shard(20031);
+        assert_eq!(archive.last_day(), 20031);
+        assert!(!archive.is_prunable(20031 + HYDRO_ARCHIVE_RETENTION_DAYS));
+        assert!(archive.is_prunable(20032 + HYDRO_ARCHIVE_RETENTION_DAYS));
+    }
+}
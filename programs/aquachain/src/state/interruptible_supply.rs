@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+
+use crate::utils::bps_of;
+
+/// Terms an agency offers consumers on interruptible supply contracts.
+///
+/// Interruptible consumers are billed at a discount on every reading, and in exchange are
+/// the first to be cut off when the agency curtails supply in an emergency. Each period
+/// of a curtailment earns every interruptible consumer a fixed compensation in AquaCoin,
+/// as demand-response programs pay large electricity users for shedding load.
+///
+/// # Fields
+/// * `agency` - Agency offering the contracts
+/// * `aqc_mint` - Mint of the AquaCoin compensation is paid in
+/// * `discount_bps` - Discount on interruptible consumers' charges, in basis points
+/// * `compensation_per_period` - AquaCoin paid for each curtailed period, in base units
+/// * `period_slots` - Length of a curtailment period in slots
+/// * `curtailed_until_slot` - Slot until which interruptible supply is curtailed
+/// * `curtailed_periods` - Total number of periods interruptible supply was curtailed for
+///
+/// # Example
+/// ```ignore
+/// let policy = InterruptiblePolicy {
+///     agency: agency_pubkey,
+///     aqc_mint: aqc_mint_pubkey,
+///     discount_bps: 1_500,            // 15% off every charge
+///     compensation_per_period: 5_000, // 5.000 AQC per curtailed period
+///     period_slots: 216_000,          // about a day
+///     curtailed_until_slot: 0,
+///     curtailed_periods: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct InterruptiblePolicy {
+    /// Agency offering the contracts.
+    pub agency: Pubkey,
+
+    /// Mint of the AquaCoin compensation is paid in.
+    pub aqc_mint: Pubkey,
+
+    /// Discount on interruptible consumers' charges, in basis points.
+    pub discount_bps: u16,
+
+    /// AquaCoin paid to each interruptible consumer for each curtailed period, in base units.
+    pub compensation_per_period: u64,
+
+    /// Length of a curtailment period in slots.
+    pub period_slots: u64,
+
+    /// Slot until which interruptible supply is curtailed, 0 if it never was.
+    pub curtailed_until_slot: u64,
+
+    /// Total number of periods interruptible supply was curtailed for.
+    pub curtailed_periods: u64,
+}
+
+impl InterruptiblePolicy {
+    /// Returns whether interruptible supply is curtailed at `slot`
+    pub fn is_curtailed(&self, slot: u64) -> bool {
+        slot < self.curtailed_until_slot
+    }
+
+    /// Curtails interruptible supply for `periods` periods from `slot`
+    pub fn curtail(&mut self, periods: u64, slot: u64) {
+        self.curtailed_until_slot = slot.saturating_add(periods.saturating_mul(self.period_slots));
+        self.curtailed_periods = self.curtailed_periods.saturating_add(periods);
+    }
+
+    /// Returns the discount on a charge of `amount` billed to an interruptible consumer
+    pub fn discount_for(&self, amount: u64) -> u64 {
+        bps_of(amount, self.discount_bps)
+    }
+
+    /// Returns the curtailed periods a consumer compensated up to `periods_compensated` was
+    /// not compensated for
+    pub fn uncompensated_periods(&self, periods_compensated: u64) -> u64 {
+        self.curtailed_periods.saturating_sub(periods_compensated)
+    }
+
+    /// Returns the compensation owed to a consumer compensated up to `periods_compensated`
+    pub fn compensation_owed(&self, periods_compensated: u64) -> u64 {
+        self.uncompensated_periods(periods_compensated)
+            .saturating_mul(self.compensation_per_period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> InterruptiblePolicy {
+        InterruptiblePolicy {
+            agency: Pubkey::default(),
+            aqc_mint: Pubkey::default(),
+            discount_bps: 1_500,
+            compensation_per_period: 5_000,
+            period_slots: 100,
+            curtailed_until_slot: 0,
+            curtailed_periods: 0,
+        }
+    }
+
+    #[test]
+    fn test_curtail() {
+        let mut policy = policy();
+        assert!(!policy.is_curtailed(0));
+
+        policy.curtail(3, 1_000);
+        assert!(policy.is_curtailed(1_299));
+        assert!(!policy.is_curtailed(1_300));
+        assert_eq!(policy.curtailed_periods, 3);
+
+        assert_eq!(policy.discount_for(10_000), 1_500);
+    }
+
+    #[test]
+    fn test_compensation_owed() {
+        let mut policy = policy();
+        policy.curtail(2, 0);
+
+        // Consumers that opted in after a curtailment are not owed for it
+        assert_eq!(policy.compensation_owed(2), 0);
+
+        policy.curtail(3, 1_000);
+        assert_eq!(policy.uncompensated_periods(2), 3);
+        assert_eq!(policy.compensation_owed(2), 15_000);
+        assert_eq!(policy.compensation_owed(0), 25_000);
+    }
+}
@@ -0,0 +1,210 @@
+use anchor_lang::prelude::*;
+
+use super::SECONDS_PER_DAY;
+use crate::CustomError;
+
+/// Largest number of crop seasons on an irrigation schedule
+pub const IRRIGATION_SEASONS_MAX: usize = 4;
+
+/// Number of seconds in an irrigation week
+pub const SECONDS_PER_WEEK: i64 = 7 * SECONDS_PER_DAY;
+
+/// Window of a crop season and the volume an agricultural consumer may draw in it.
+///
+/// # Fields
+/// * `start_timestamp` - Unix timestamp at which the season opens
+/// * `end_timestamp` - Unix timestamp at which the season closes
+/// * `weekly_volume` - Volume the consumer may draw per week of the season
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq, InitSpace)]
+pub struct CropSeason {
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub weekly_volume: u64,
+}
+
+impl CropSeason {
+    /// Returns true if `unix_timestamp` falls within the season
+    pub fn contains(&self, unix_timestamp: i64) -> bool {
+        self.start_timestamp <= unix_timestamp && unix_timestamp < self.end_timestamp
+    }
+}
+
+/// Irrigation turns allotted to an agricultural consumer.
+///
+/// Irrigation districts ration water by allotting each farm a weekly volume during its
+/// crop seasons. Water drawn beyond the week's allotment, or outside every season, is
+/// drawn out of turn and billed with a surcharge on top of the usual charge.
+///
+/// # Fields
+/// * `agency` - Agency operating the irrigation district
+/// * `consumer` - Agricultural consumer the schedule applies to
+/// * `surcharge_bps` - Surcharge on the water rate for volume drawn out of turn, in basis points
+/// * `seasons` - Crop seasons and their weekly volumes
+/// * `week` - Index of the week `week_usage` was recorded in, counted from the Unix epoch
+/// * `week_usage` - Volume drawn in turn during `week`
+///
+/// # Example
+/// ```ignore
+/// let schedule = IrrigationSchedule {
+///     agency: agency_pubkey,
+///     consumer: farm_pubkey,
+///     surcharge_bps: 5_000, // 50% on top of the water rate
+///     seasons: vec![CropSeason {
+///         start_timestamp: 1_711_929_600,
+///         end_timestamp: 1_719_792_000,
+///         weekly_volume: 50_000,
+///     }],
+///     week: 0,
+///     week_usage: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct IrrigationSchedule {
+    /// Agency operating the irrigation district.
+    pub agency: Pubkey,
+
+    /// Agricultural consumer the schedule applies to.
+    pub consumer: Pubkey,
+
+    /// Surcharge on the water rate for volume drawn out of turn, in basis points.
+    pub surcharge_bps: u16,
+
+    /// Crop seasons and their weekly volumes, in no particular order.
+    #[max_len(IRRIGATION_SEASONS_MAX)]
+    pub seasons: Vec<CropSeason>,
+
+    /// Index of the week `week_usage` was recorded in, counted from the Unix epoch.
+    pub week: u64,
+
+    /// Volume drawn in turn during `week`.
+    pub week_usage: u64,
+}
+
+impl IrrigationSchedule {
+    /// Checks that seasons are well formed and do not overlap
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidIrrigationSchedule` - If there are too many seasons, or a
+    ///   season is empty or overlaps another
+    pub fn validate_seasons(seasons: &[CropSeason]) -> Result<()> {
+        require!(
+            seasons.len() <= IRRIGATION_SEASONS_MAX,
+            CustomError::InvalidIrrigationSchedule
+        );
+        for (i, season) in seasons.iter().enumerate() {
+            require!(
+                season.start_timestamp < season.end_timestamp,
+                CustomError::InvalidIrrigationSchedule
+            );
+            require!(
+                seasons[..i].iter().all(|other| {
+                    season.end_timestamp <= other.start_timestamp
+                        || other.end_timestamp <= season.start_timestamp
+                }),
+                CustomError::InvalidIrrigationSchedule
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the week `unix_timestamp` falls in, counted from the Unix epoch
+    pub fn week_of(unix_timestamp: i64) -> u64 {
+        (unix_timestamp.max(0) / SECONDS_PER_WEEK) as u64
+    }
+
+    /// Returns the crop season open at `unix_timestamp`, if any
+    pub fn active_season(&self, unix_timestamp: i64) -> Option<&CropSeason> {
+        self.seasons
+            .iter()
+            .find(|season| season.contains(unix_timestamp))
+    }
+
+    /// Records a draw of `amount` and returns the volume drawn out of turn
+    ///
+    /// Within a season, the draw counts against the current week's allotment and only the
+    /// volume beyond it is out of turn. Outside every season, the whole draw is out of turn.
+    pub fn record_draw(&mut self, amount: u64, unix_timestamp: i64) -> u64 {
+        let weekly_volume = match self.active_season(unix_timestamp) {
+            Some(season) => season.weekly_volume,
+            None => return amount,
+        };
+
+        let week = Self::week_of(unix_timestamp);
+        if week != self.week {
+            self.week = week;
+            self.week_usage = 0;
+        }
+
+        let in_turn = amount.min(weekly_volume.saturating_sub(self.week_usage));
+        self.week_usage = self.week_usage.saturating_add(in_turn);
+        amount - in_turn
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEASON_START: i64 = 100 * SECONDS_PER_WEEK;
+
+    fn schedule() -> IrrigationSchedule {
+        IrrigationSchedule {
+            agency: Pubkey::default(),
+            consumer: Pubkey::default(),
+            surcharge_bps: 5_000,
+            seasons: vec![CropSeason {
+                start_timestamp: SEASON_START,
+                end_timestamp: SEASON_START + 10 * SECONDS_PER_WEEK,
+                weekly_volume: 50_000,
+            }],
+            week: 0,
+            week_usage: 0,
+        }
+    }
+
+    #[test]
+    fn test_record_draw() {
+        let mut schedule = schedule();
+        assert_eq!(schedule.record_draw(40_000, SEASON_START), 0);
+        assert_eq!(schedule.record_draw(20_000, SEASON_START + 60), 10_000);
+        assert_eq!(schedule.week_usage, 50_000);
+
+        // A new week restores the allotment
+        assert_eq!(
+            schedule.record_draw(20_000, SEASON_START + SECONDS_PER_WEEK),
+            0
+        );
+        assert_eq!(schedule.week_usage, 20_000);
+
+        // Outside the season every draw is out of turn
+        assert_eq!(schedule.record_draw(5_000, SEASON_START - 1), 5_000);
+        assert_eq!(schedule.week_usage, 20_000);
+    }
+
+    #[test]
+    fn test_validate_seasons() {
+        let season = schedule().seasons[0];
+        assert!(IrrigationSchedule::validate_seasons(&[season]).is_ok());
+
+        let later = CropSeason {
+            start_timestamp: season.end_timestamp,
+            end_timestamp: season.end_timestamp + SECONDS_PER_WEEK,
+            weekly_volume: 30_000,
+        };
+        assert!(IrrigationSchedule::validate_seasons(&[season, later]).is_ok());
+
+        let overlapping = CropSeason {
+            start_timestamp: season.end_timestamp - 1,
+            ..later
+        };
+        assert!(IrrigationSchedule::validate_seasons(&[season, overlapping]).is_err());
+
+        let empty = CropSeason {
+            end_timestamp: later.start_timestamp,
+            ..later
+        };
+        assert!(IrrigationSchedule::validate_seasons(&[empty]).is_err());
+        assert!(IrrigationSchedule::validate_seasons(&[season; 5]).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+
+use super::Reservoir;
+use crate::CustomError;
+
+/// Largest number of periods a level forecast can project
+pub const LEVEL_FORECAST_PERIODS_MAX: usize = 12;
+
+/// Projected levels of a reservoir over the coming periods.
+///
+/// Agencies post the levels their hydrological models project so tariffs can price usage
+/// against the expected scarcity rather than the instantaneous level, giving consumers an
+/// advance signal of price changes. Each posted forecast replaces the previous one.
+///
+/// # Fields
+/// * `agency` - Agency that posted the forecast
+/// * `reservoir_key` - Unique identifier of the reservoir the forecast projects
+/// * `start_slot` - Slot at which the first projected period begins
+/// * `period_slots` - Length of each projected period in slots
+/// * `levels` - Projected reservoir level for each period, in order
+///
+/// # Example
+/// ```ignore
+/// let forecast = LevelForecast {
+///     agency: agency_pubkey,
+///     reservoir_key: reservoir_pubkey,
+///     start_slot: 0,
+///     period_slots: 216_000, // about a day
+///     levels: vec![950_000, 900_000, 820_000],
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct LevelForecast {
+    /// Agency that posted the forecast.
+    pub agency: Pubkey,
+
+    /// Unique identifier of the reservoir the forecast projects.
+    pub reservoir_key: Pubkey,
+
+    /// Slot at which the first projected period begins.
+    pub start_slot: u64,
+
+    /// Length of each projected period in slots.
+    pub period_slots: u64,
+
+    /// Projected reservoir level for each period, in order.
+    #[max_len(LEVEL_FORECAST_PERIODS_MAX)]
+    pub levels: Vec<u64>,
+}
+
+impl LevelForecast {
+    /// Checks that projected levels can be posted for a reservoir
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidLevelForecast` - If there are no periods, too many periods, or a
+    ///   level exceeds the reservoir's capacity
+    pub fn validate_levels(period_slots: u64, levels: &[u64], capacity: u64) -> Result<()> {
+        require!(
+            period_slots > 0
+                && !levels.is_empty()
+                && levels.len() <= LEVEL_FORECAST_PERIODS_MAX
+                && levels.iter().all(|level| *level <= capacity),
+            CustomError::InvalidLevelForecast
+        );
+        Ok(())
+    }
+
+    /// Returns the level projected for the period containing `slot`, if it is covered
+    pub fn level_at(&self, slot: u64) -> Option<u64> {
+        let elapsed = slot.checked_sub(self.start_slot)?;
+        let period = elapsed.checked_div(self.period_slots)?;
+        self.levels.get(usize::try_from(period).ok()?).copied()
+    }
+
+    /// Returns the level `reservoir` is projected to be at over the period containing `slot`
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidLevelForecast` - If the forecast belongs to another agency or
+    ///   reservoir
+    /// * `CustomError::ForecastUnavailable` - If the forecast does not cover `slot`
+    pub fn level_for(&self, agency: &Pubkey, reservoir: &Reservoir, slot: u64) -> Result<u64> {
+        require!(
+            self.agency == *agency && self.reservoir_key == reservoir.reservoir_key,
+            CustomError::InvalidLevelForecast
+        );
+        Ok(self
+            .level_at(slot)
+            .ok_or(CustomError::ForecastUnavailable)?
+            .min(reservoir.capacity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_at() {
+        let forecast = LevelForecast {
+            agency: Pubkey::default(),
+            reservoir_key: Pubkey::default(),
+            start_slot: 1_000,
+            period_slots: 100,
+            levels: vec![950_000, 900_000],
+        };
+        assert_eq!(forecast.level_at(999), None);
+        assert_eq!(forecast.level_at(1_000), Some(950_000));
+        assert_eq!(forecast.level_at(1_150), Some(900_000));
+        assert_eq!(forecast.level_at(1_200), None);
+    }
+
+    #[test]
+    fn test_validate_levels() {
+        assert!(LevelForecast::validate_levels(100, &[950_000], 1_000_000).is_ok());
+        assert!(LevelForecast::validate_levels(0, &[950_000], 1_000_000).is_err());
+        assert!(LevelForecast::validate_levels(100, &[], 1_000_000).is_err());
+        assert!(LevelForecast::validate_levels(100, &[1_000_001], 1_000_000).is_err());
+        assert!(LevelForecast::validate_levels(100, &[1; 13], 1_000_000).is_err());
+    }
+}
@@ -1,9 +1,81 @@
+mod agency_bond;
+mod agency_stats;
+mod allocation;
+mod audit_record;
+mod bill_breakdown;
+mod carbon_offsets;
+mod communal_point;
+mod complaint;
+mod conservation_certificate;
 mod consumer;
+mod consumer_tree;
+mod credit_note;
+mod demand_response;
+mod drought_insurance;
+mod energy_oracle;
+mod forward_contract;
+mod fx_oracle;
+mod geohash;
+mod hydro_archive;
+mod interruptible_supply;
+mod irrigation_schedule;
+mod level_forecast;
+mod payment_gateway;
+mod payment_receipt;
+mod redemption;
+mod regulator_authority;
 mod reservoir;
+mod reservoir_daily_stats;
+mod role;
+mod sensor;
+mod session_key;
+mod shared_reservoir;
+mod source_mix;
+mod spill_record;
 mod tariff;
+mod tariff_history;
 mod tokens;
+mod watc_market;
+mod weather_derivative;
+mod work_order;
 
+pub use agency_bond::*;
+pub use agency_stats::*;
+pub use allocation::*;
+pub use audit_record::*;
+pub use bill_breakdown::*;
+pub use carbon_offsets::*;
+pub use communal_point::*;
+pub use complaint::*;
+pub use conservation_certificate::*;
 pub use consumer::*;
+pub use consumer_tree::*;
+pub use credit_note::*;
+pub use demand_response::*;
+pub use drought_insurance::*;
+pub use energy_oracle::*;
+pub use forward_contract::*;
+pub use fx_oracle::*;
+pub use geohash::*;
+pub use hydro_archive::*;
+pub use interruptible_supply::*;
+pub use irrigation_schedule::*;
+pub use level_forecast::*;
+pub use payment_gateway::*;
+pub use payment_receipt::*;
+pub use redemption::*;
+pub use regulator_authority::*;
 pub use reservoir::*;
+pub use reservoir_daily_stats::*;
+pub use role::*;
+pub use sensor::*;
+pub use session_key::*;
+pub use shared_reservoir::*;
+pub use source_mix::*;
+pub use spill_record::*;
 pub use tariff::*;
+pub use tariff_history::*;
 pub use tokens::*;
+pub use watc_market::*;
+pub use weather_derivative::*;
+pub use work_order::*;
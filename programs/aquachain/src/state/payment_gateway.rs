@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+
+/// Represents a licensed mobile-money gateway allowed to credit prepaid balances.
+///
+/// Consumers without stablecoins pay the gateway off-chain, through a mobile-money
+/// operator. The gateway's attestor key then records each payment on-chain and the
+/// consumer's prepaid balance is credited, while the gateway settles with the agency
+/// off-chain. The agency can suspend a gateway at any time.
+///
+/// # Fields
+/// * `agency` - Agency that licensed the gateway
+/// * `gateway_key` - Unique identifier of the gateway
+/// * `attestor` - Key allowed to attest the gateway's payments
+/// * `active` - Whether the gateway may record payments
+/// * `payments_recorded` - Number of payments recorded by the gateway
+/// * `total_credited` - Total prepaid balance credited by the gateway
+///
+/// # Example
+/// ```ignore
+/// let gateway = PaymentGateway {
+///     agency: agency_pubkey,
+///     gateway_key: gateway_pubkey,
+///     attestor: attestor_pubkey,
+///     active: true,
+///     payments_recorded: 0,
+///     total_credited: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct PaymentGateway {
+    /// Agency that licensed the gateway.
+    pub agency: Pubkey,
+
+    /// Unique identifier of the gateway.
+    pub gateway_key: Pubkey,
+
+    /// Public key allowed to attest the gateway's payments.
+    pub attestor: Pubkey,
+
+    /// Whether the gateway may record payments; cleared when its licence is suspended.
+    pub active: bool,
+
+    /// Number of payments recorded by the gateway.
+    pub payments_recorded: u64,
+
+    /// Total prepaid balance credited by the gateway, in WTK base units.
+    pub total_credited: u64,
+}
+
+/// Records a mobile-money payment attested by a gateway.
+///
+/// One record exists per gateway and payment identifier, so a payment cannot be credited
+/// twice.
+///
+/// # Fields
+/// * `gateway` - Gateway that attested the payment
+/// * `consumer` - Consumer credited
+/// * `payment_id` - The gateway's identifier of the payment, such as a hash of the
+///   operator's transaction ID
+/// * `amount` - Amount credited to the prepaid balance, in WTK base units
+/// * `slot` - Slot at which the payment was recorded
+///
+/// # Example
+/// ```ignore
+/// let payment = OffchainPayment {
+///     gateway: gateway_pda,
+///     consumer: consumer_pubkey,
+///     payment_id: hash(b"MPESA-QK81XZ4T2B").to_bytes(),
+///     amount: 20_000,
+///     slot: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct OffchainPayment {
+    /// Gateway that attested the payment.
+    pub gateway: Pubkey,
+
+    /// Consumer credited.
+    pub consumer: Pubkey,
+
+    /// The gateway's identifier of the payment.
+    pub payment_id: [u8; 32],
+
+    /// Amount credited to the prepaid balance, in WTK base units.
+    pub amount: u64,
+
+    /// Slot at which the payment was recorded.
+    pub slot: u64,
+}
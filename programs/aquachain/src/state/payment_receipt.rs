@@ -0,0 +1,131 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+use super::{BillBreakdown, Consumer};
+
+/// Represents proof that a consumer paid water debt.
+///
+/// A receipt is issued by every payment of water debt, numbered in sequence per consumer,
+/// so the consumer can present it in a billing dispute or on a disconnection visit. The
+/// invoice hash binds the receipt to the bill that was outstanding when it was paid.
+///
+/// # Fields
+/// * `consumer` - The consumer account that paid
+/// * `receipt_number` - Sequence number of the receipt among the consumer's receipts
+/// * `invoice_hash` - Hash of the consumer, its billing period start and its last bill
+/// * `payer` - Wallet of the occupant responsible for the account when it paid
+/// * `amount` - Amount of water debt paid, in WTK base units
+/// * `outstanding` - Water debt left outstanding after the payment
+/// * `slot` - Slot at which the payment was made
+///
+/// # Example
+/// ```ignore
+/// let receipt = PaymentReceipt {
+///     consumer: consumer_pubkey,
+///     receipt_number: 0,
+///     invoice_hash: invoice_hash(&consumer_pubkey, 0, &BillBreakdown::default()),
+///     payer: owner_pubkey,
+///     amount: 12500,  // 12.500 WTK paid
+///     outstanding: 0,
+///     slot: 1000,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct PaymentReceipt {
+    /// The consumer account that paid.
+    pub consumer: Pubkey,
+
+    /// Sequence number of the receipt among the consumer's receipts, starting at zero.
+    pub receipt_number: u64,
+
+    /// Hash identifying the invoice paid, from `invoice_hash`.
+    pub invoice_hash: [u8; 32],
+
+    /// Wallet of the occupant responsible for the account when it paid.
+    pub payer: Pubkey,
+
+    /// Amount of water debt paid, in WTK base units.
+    pub amount: u64,
+
+    /// Water debt left outstanding after the payment, zero once the invoice is settled.
+    pub outstanding: u64,
+
+    /// Slot at which the payment was made.
+    pub slot: u64,
+}
+
+impl PaymentReceipt {
+    /// Records a payment the consumer just made and advances its receipt sequence
+    ///
+    /// # Arguments
+    /// * `consumer_key` - Public key of the consumer account
+    /// * `consumer` - The consumer account, after the payment was applied
+    /// * `amount` - Amount of water debt paid
+    /// * `slot` - The current slot
+    pub fn issue(&mut self, consumer_key: Pubkey, consumer: &mut Consumer, amount: u64, slot: u64) {
+        self.consumer = consumer_key;
+        self.receipt_number = consumer.receipts_issued;
+        self.invoice_hash = invoice_hash(
+            &consumer_key,
+            consumer.capacity_period_start,
+            &consumer.last_bill,
+        );
+        self.payer = consumer.owner;
+        self.amount = amount;
+        self.outstanding = consumer.outstanding_water_debt;
+        self.slot = slot;
+        consumer.receipts_issued = consumer.receipts_issued.saturating_add(1);
+    }
+}
+
+/// Hash identifying a consumer's invoice
+///
+/// # Arguments
+/// * `consumer` - Public key of the consumer account
+/// * `period_start` - Slot at which the consumer's billing period started
+/// * `bill` - The consumer's last bill
+pub fn invoice_hash(consumer: &Pubkey, period_start: u64, bill: &BillBreakdown) -> [u8; 32] {
+    hashv(&[
+        consumer.as_ref(),
+        &period_start.to_le_bytes(),
+        &bill.fixed.to_le_bytes(),
+        &bill.base.to_le_bytes(),
+        &bill.excess.to_le_bytes(),
+        &bill.penalty.to_le_bytes(),
+        &bill.discount.to_le_bytes(),
+        &bill.total.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invoice_hash() {
+        let consumer = Pubkey::new_unique();
+        let bill = BillBreakdown {
+            base: 10_000,
+            total: 10_000,
+            ..Default::default()
+        };
+        assert_eq!(
+            invoice_hash(&consumer, 100, &bill),
+            invoice_hash(&consumer, 100, &bill)
+        );
+        // The same bill in another period or for another consumer is another invoice
+        assert_ne!(
+            invoice_hash(&consumer, 100, &bill),
+            invoice_hash(&consumer, 200, &bill)
+        );
+        assert_ne!(
+            invoice_hash(&consumer, 100, &bill),
+            invoice_hash(&Pubkey::new_unique(), 100, &bill)
+        );
+        assert_ne!(
+            invoice_hash(&consumer, 100, &bill),
+            invoice_hash(&consumer, 100, &BillBreakdown::default())
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+
+use crate::CustomError;
+
+/// Longest name of a perk listed for redemption, in bytes
+pub const PERK_NAME_MAX_LEN: usize = 32;
+
+/// A utility perk the agency lists in exchange for AquaCoin.
+///
+/// Consumers burn the offer's price in AquaCoin to redeem it, and receive a voucher the
+/// agency honours off-chain, such as a free leak inspection or a rebate on a water-saving
+/// fixture. Each offer is limited to the quantity the agency listed.
+///
+/// # Fields
+/// * `agency` - Agency honouring the perk
+/// * `offer_key` - Unique identifier for the offer
+/// * `aqc_mint` - Mint of the AquaCoin burned to redeem the offer
+/// * `price` - Amount of AquaCoin burned per redemption, in base units
+/// * `quantity` - Number of redemptions the offer was listed with
+/// * `redeemed` - Number of redemptions made so far
+/// * `name` - Name of the perk
+///
+/// # Example
+/// ```ignore
+/// let offer = RedemptionOffer {
+///     agency: agency_pubkey,
+///     offer_key: offer_pubkey,
+///     aqc_mint: aqc_mint_pubkey,
+///     price: 5_000,       // 5.000 AQC
+///     quantity: 100,
+///     redeemed: 0,
+///     name: "Leak inspection".to_string(),
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct RedemptionOffer {
+    /// Agency honouring the perk.
+    pub agency: Pubkey,
+
+    /// Unique identifier for the offer.
+    pub offer_key: Pubkey,
+
+    /// Mint of the AquaCoin burned to redeem the offer.
+    pub aqc_mint: Pubkey,
+
+    /// Amount of AquaCoin burned per redemption, in base units.
+    pub price: u64,
+
+    /// Number of redemptions the offer was listed with.
+    pub quantity: u64,
+
+    /// Number of redemptions made so far.
+    pub redeemed: u64,
+
+    /// Name of the perk.
+    #[max_len(PERK_NAME_MAX_LEN)]
+    pub name: String,
+}
+
+impl RedemptionOffer {
+    /// Checks that a perk name is non-empty and fits in the account
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty() && name.len() <= PERK_NAME_MAX_LEN
+    }
+
+    /// Records a redemption, returning the voucher's sequence number
+    ///
+    /// # Errors
+    /// * `CustomError::OfferSoldOut` - If every listed redemption has been made
+    pub fn redeem(&mut self) -> Result<u64> {
+        require!(self.redeemed < self.quantity, CustomError::OfferSoldOut);
+        let index = self.redeemed;
+        self.redeemed += 1;
+        Ok(index)
+    }
+}
+
+/// Claim on a perk, issued to a consumer that redeemed an offer.
+///
+/// # Fields
+/// * `offer` - Offer the voucher was redeemed from
+/// * `consumer` - Consumer holding the claim
+/// * `index` - Sequence number of the redemption within the offer
+/// * `price` - Amount of AquaCoin burned for the voucher
+/// * `redeemed_at` - Unix timestamp at which the offer was redeemed
+#[account]
+#[derive(InitSpace)]
+pub struct RedemptionVoucher {
+    /// Offer the voucher was redeemed from.
+    pub offer: Pubkey,
+
+    /// Consumer holding the claim.
+    pub consumer: Pubkey,
+
+    /// Sequence number of the redemption within the offer.
+    pub index: u64,
+
+    /// Amount of AquaCoin burned for the voucher.
+    pub price: u64,
+
+    /// Unix timestamp at which the offer was redeemed.
+    pub redeemed_at: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perk_name_validation() {
+        assert!(RedemptionOffer::is_valid_name("Leak inspection"));
+        assert!(!RedemptionOffer::is_valid_name(""));
+        assert!(!RedemptionOffer::is_valid_name(
+            &"a".repeat(PERK_NAME_MAX_LEN + 1)
+        ));
+    }
+
+    #[test]
+    fn test_redeem_until_sold_out() {
+        let mut offer = RedemptionOffer {
+            agency: Pubkey::default(),
+            offer_key: Pubkey::default(),
+            aqc_mint: Pubkey::default(),
+            price: 5_000,
+            quantity: 2,
+            redeemed: 0,
+            name: "Leak inspection".to_string(),
+        };
+        assert_eq!(offer.redeem().unwrap(), 0);
+        assert_eq!(offer.redeem().unwrap(), 1);
+        assert!(offer.redeem().is_err());
+        assert_eq!(offer.redeemed, 2);
+    }
+}
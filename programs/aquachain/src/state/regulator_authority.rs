@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    state::{Role, RoleKind},
+    CustomError,
+};
+
+/// The regulator overseeing an agency.
+///
+/// Regulatory approvals only check the agency if they come from a key it does not
+/// control. The regulator is therefore appointed once, when the agency is set up, and
+/// cannot be replaced afterwards. The agency cannot grant the Regulator role: only the
+/// regulator authority grants and revokes it, to delegate approvals to its own staff.
+///
+/// # Fields
+/// * `agency` - Agency overseen by the regulator
+/// * `authority` - Key of the regulator, granting and revoking the Regulator role
+/// * `appointed_slot` - Slot at which the regulator was appointed
+#[account]
+#[derive(InitSpace)]
+pub struct RegulatorAuthority {
+    /// Agency overseen by the regulator.
+    pub agency: Pubkey,
+
+    /// Key of the regulator, granting and revoking the Regulator role.
+    pub authority: Pubkey,
+
+    /// Slot at which the regulator was appointed.
+    pub appointed_slot: u64,
+}
+
+impl RegulatorAuthority {
+    /// Checks that `signer` approves an operation on behalf of the regulator
+    ///
+    /// The regulator authority approves, as do the keys it granted the Regulator role.
+    ///
+    /// # Errors
+    /// * `CustomError::MissingRegulatorApproval` - If the signer is the agency itself
+    /// * `CustomError::MissingRole` - If the signer is neither the regulator authority nor
+    ///   holds the Regulator role
+    pub fn authorize(&self, signer: &Pubkey, role: Option<&Role>) -> Result<()> {
+        require_keys_neq!(*signer, self.agency, CustomError::MissingRegulatorApproval);
+        if *signer == self.authority {
+            return Ok(());
+        }
+        Role::authorize(&self.agency, signer, role, RoleKind::Regulator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorize() {
+        let agency = Pubkey::new_unique();
+        let regulator = RegulatorAuthority {
+            agency,
+            authority: Pubkey::new_unique(),
+            appointed_slot: 0,
+        };
+        let staff = Pubkey::new_unique();
+        let role = Role {
+            agency,
+            member: staff,
+            kind: RoleKind::Regulator,
+            granted_slot: 0,
+        };
+
+        assert!(regulator.authorize(&regulator.authority, None).is_ok());
+        assert!(regulator.authorize(&staff, Some(&role)).is_ok());
+
+        // The agency approves nothing on the regulator's behalf, even holding every role
+        assert!(regulator.authorize(&agency, None).is_err());
+
+        let admin = Role {
+            kind: RoleKind::Admin,
+            ..role
+        };
+        assert!(regulator.authorize(&staff, Some(&admin)).is_err());
+        assert!(regulator.authorize(&staff, None).is_err());
+    }
+}
@@ -1,5 +1,116 @@
 use anchor_lang::prelude::*;
 
+use super::{LevelForecast, Tariff, TariffType, UnitConfig, GEOHASH_LEN};
+use crate::{
+    utils::{bps_of, FixedPoint, BPS_DENOMINATOR},
+    CustomError,
+};
+
+/// Largest number of bands on a reservoir's scarcity curve
+pub const SCARCITY_BANDS_MAX: usize = 8;
+
+/// Number of seconds in an hour, the window level changes are limited over
+pub const SECONDS_PER_HOUR: i64 = 3_600;
+
+/// Band of a reservoir's scarcity curve.
+///
+/// # Fields
+/// * `max_level_bps` - Highest reservoir level covered by the band, in basis points of capacity
+/// * `multiplier` - Factor applied to the block rate in the band, with three implied decimals
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, Eq, PartialEq, InitSpace,
+)]
+pub struct ScarcityBand {
+    pub max_level_bps: u16,
+    pub multiplier: u64,
+}
+
+/// Reservoir inputs a scarcity multiplier is derived from.
+///
+/// Recorded with each metered charge, so verifying the charge re-derives the multiplier
+/// from the curve and levels it was priced on rather than trusting the recorded value.
+///
+/// # Fields
+/// * `tariff_type` - Type of the tariff priced, only seasonal tariffs are scaled
+/// * `capacity` - Capacity of the reservoir, 0 if the inputs were not recorded
+/// * `current_level` - Latest level reported for the reservoir
+/// * `smoothed_level` - Smoothed level of the reservoir
+/// * `smoothing_bps` - Smoothing weight of the reservoir, 0 to price on the current level
+/// * `forecast_level` - Level projected for the period, for tariffs pricing on forecasts
+/// * `curve_len` - Number of bands of the reservoir's scarcity curve
+/// * `curve` - Bands of the reservoir's scarcity curve, the first `curve_len` of them set
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, Eq, PartialEq, InitSpace,
+)]
+pub struct ScarcityInputs {
+    pub tariff_type: TariffType,
+    pub capacity: u64,
+    pub current_level: u64,
+    pub smoothed_level: u64,
+    pub smoothing_bps: u16,
+    pub forecast_level: Option<u64>,
+    pub curve_len: u8,
+    pub curve: [ScarcityBand; SCARCITY_BANDS_MAX],
+}
+
+impl ScarcityInputs {
+    /// Returns the level the multiplier is derived at
+    ///
+    /// This is the forecast level for tariffs pricing on forecasts, then the smoothed
+    /// level when smoothing is enabled, and the current level otherwise.
+    pub fn level(&self) -> u64 {
+        let level = match self.forecast_level {
+            Some(level) => level,
+            None if self.smoothing_bps == 0 => self.current_level,
+            None => self.smoothed_level,
+        };
+        level.min(self.capacity)
+    }
+
+    /// Returns the multiplier applied to the block rate, before the tariff's cap
+    ///
+    /// Seasonal tariffs use the first band of the scarcity curve covering the level ratio,
+    /// or the linear default of the pricing core when no curve is set. Uniform tariffs are
+    /// never scaled.
+    ///
+    /// # Errors
+    /// * `CustomError::MathOverflow` - If the converted levels do not fit in a u64
+    pub fn multiplier(&self, units: &UnitConfig) -> Result<FixedPoint> {
+        let level = self.level();
+        let curve = &self.curve[..(self.curve_len as usize).min(SCARCITY_BANDS_MAX)];
+        if self.tariff_type == TariffType::UniformIBT || curve.is_empty() {
+            return Ok(aquachain_core::scarcity_multiplier(
+                self.tariff_type.into(),
+                units.to_volume(self.capacity)?,
+                units.to_volume(level)?,
+            ));
+        }
+
+        let level_bps =
+            (level as u128 * BPS_DENOMINATOR as u128 / self.capacity.max(1) as u128) as u16;
+        let band = curve
+            .iter()
+            .find(|band| level_bps <= band.max_level_bps)
+            .or(curve.last())
+            .ok_or(CustomError::InvalidScarcityCurve)?;
+        Ok(FixedPoint::from(band.multiplier))
+    }
+}
+
+/// Dam safety threshold crossed by a reservoir level.
+///
+/// # Variants
+/// * `High` - The level rose to or above the high alert level
+/// * `Low` - The level fell to or below the low alert level
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReservoirAlertKind {
+    /// The level rose to or above the high alert level
+    High,
+
+    /// The level fell to or below the low alert level
+    Low,
+}
+
 /// Represents a water reservoir in the Aquachain system.
 ///
 /// This account tracks the current water level and maximum capacity of a reservoir,
@@ -10,6 +121,22 @@ use anchor_lang::prelude::*;
 /// * `current_level` - The current amount of water in the reservoir
 /// * `capacity` - The maximum amount of water the reservoir can hold
 /// * `reservoir_key` - Unique identifier for this reservoir
+/// * `assigned_consumer_count` - Number of consumers currently assigned to this reservoir
+/// * `zone_geohash` - Geohash prefix of the zone the reservoir serves, empty if unrestricted
+/// * `basin_id` - Identifier of the river basin the reservoir lies in, 0 if unassigned
+/// * `pumping_intensity` - Energy used to pump a unit of water to the zone, in kWh
+/// * `emissions_factor` - Grams CO2e emitted to treat and deliver a unit of water to the zone
+/// * `scarcity_curve` - Bands of level ratio setting the seasonal block rate multiplier
+/// * `smoothing_bps` - Weight of each level update in the smoothed level, 0 to disable smoothing
+/// * `smoothed_level` - Moving average of the reported levels, pricing seasonal tariffs
+/// * `telemetry_authority` - Key of the SCADA relay allowed to report levels, if any
+/// * `max_level_rate` - Largest level change per slot accepted from the telemetry key
+/// * `level_updated_slot` - Slot at which the level was last reported
+/// * `max_change_bps_per_hour` - Largest level change per hour without the agency's
+///   signature, in basis points of capacity; 0 if unlimited
+/// * `level_updated_at` - Unix timestamp at which the level was last reported
+/// * `alert_level_high` - Level at or above which a high alert is raised, 0 if disabled
+/// * `alert_level_low` - Level at or below which a low alert is raised, 0 if disabled
 ///
 /// # Example
 /// ```ignore
@@ -17,6 +144,21 @@ use anchor_lang::prelude::*;
 ///     current_level: 1000,    // Current water level
 ///     capacity: 5000,         // Maximum capacity
 ///     reservoir_key: pubkey,  // Unique identifier
+///     assigned_consumer_count: 0,
+///     zone_geohash: [0; 12],
+///     basin_id: 0,
+///     pumping_intensity: 0,
+///     emissions_factor: 0,
+///     scarcity_curve: vec![],
+///     smoothing_bps: 0,
+///     smoothed_level: 1000,
+///     telemetry_authority: None,
+///     max_level_rate: 0,
+///     level_updated_slot: 0,
+///     max_change_bps_per_hour: 0,
+///     level_updated_at: 0,
+///     alert_level_high: 0,
+///     alert_level_low: 0,
 /// };
 /// ```
 #[account]
@@ -33,4 +175,707 @@ pub struct Reservoir {
     /// The unique public key identifying this reservoir in the system.
     /// Used for authentication and reference in transactions.
     pub reservoir_key: Pubkey,
+
+    /// The number of consumers assigned to this reservoir.
+    /// The reservoir can only be decommissioned once this reaches zero.
+    pub assigned_consumer_count: u64,
+
+    /// Geohash prefix of the zone served by this reservoir, zero-padded; empty if unrestricted.
+    /// Readings billed against the reservoir must come from consumers located in the zone.
+    pub zone_geohash: [u8; GEOHASH_LEN],
+
+    /// Identifier of the river basin the reservoir lies in, 0 if unassigned.
+    /// Conveying water between reservoirs in different basins requires regulator approval.
+    pub basin_id: u32,
+
+    /// Energy used to pump one billed unit of water to the zone served, in kWh with three
+    /// implied decimals. Zero when the zone is gravity fed and carries no energy surcharge.
+    pub pumping_intensity: u64,
+
+    /// Grams of CO2 equivalent emitted by the plant and pumps to treat and deliver one
+    /// billed unit of water to the zone served.
+    pub emissions_factor: u64,
+
+    /// Bands of level ratio, in ascending order, setting the multiplier applied to the block
+    /// rate of seasonal tariffs. Empty to scale the block rate linearly with the level.
+    #[max_len(SCARCITY_BANDS_MAX)]
+    pub scarcity_curve: Vec<ScarcityBand>,
+
+    /// Weight given to each reported level in the smoothed level, in basis points.
+    /// Zero disables smoothing, so seasonal tariffs are priced on the current level.
+    pub smoothing_bps: u16,
+
+    /// Exponential moving average of the levels reported with `update_reservoir_level`.
+    /// Prices seasonal tariffs when smoothing is enabled, damping swings in bills.
+    pub smoothed_level: u64,
+
+    /// Key of the SCADA relay allowed to report levels without holding the agency wallet.
+    /// It can only report levels, within the plausible rate of change.
+    pub telemetry_authority: Option<Pubkey>,
+
+    /// Largest level change per slot accepted from the telemetry key, in level units.
+    /// Zero leaves the telemetry key's updates unbounded.
+    pub max_level_rate: u64,
+
+    /// Slot at which the current level was last reported.
+    pub level_updated_slot: u64,
+
+    /// Largest level change per hour accepted without the agency's signature, in basis
+    /// points of capacity. Zero leaves level updates unlimited.
+    pub max_change_bps_per_hour: u16,
+
+    /// Unix timestamp at which the current level was last reported.
+    pub level_updated_at: i64,
+
+    /// Dam safety level at or above which level updates raise a high alert.
+    /// Zero disables the high alert.
+    pub alert_level_high: u64,
+
+    /// Dam safety level at or below which level updates raise a low alert.
+    /// Zero disables the low alert.
+    pub alert_level_low: u64,
+}
+
+impl Reservoir {
+    /// Level below which a reservoir is considered low, in basis points of its capacity
+    pub const LOW_LEVEL_BPS: u16 = 2_000;
+
+    /// Returns true if the current level is below `LOW_LEVEL_BPS` of capacity
+    pub fn is_low(&self) -> bool {
+        self.current_level < bps_of(self.capacity, Self::LOW_LEVEL_BPS)
+    }
+
+    /// Records a consumer being assigned to this reservoir
+    pub fn assign_consumer(&mut self) {
+        self.assigned_consumer_count = self.assigned_consumer_count.saturating_add(1);
+    }
+
+    /// Records a consumer being moved off this reservoir
+    ///
+    /// Saturates at zero so consumers assigned before the count was tracked can still move.
+    pub fn release_consumer(&mut self) {
+        self.assigned_consumer_count = self.assigned_consumer_count.saturating_sub(1);
+    }
+
+    /// Returns true if conveying water to `other` crosses a basin boundary
+    pub fn crosses_basin(&self, other: &Reservoir) -> bool {
+        self.basin_id != other.basin_id
+    }
+
+    /// Adds `volume` to the reservoir, such as water conveyed from another reservoir
+    ///
+    /// The level is capped at capacity. Returns the volume spilled over the capacity.
+    pub fn fill(&mut self, volume: u64) -> u64 {
+        let room = self.capacity.saturating_sub(self.current_level);
+        let spilled = volume.saturating_sub(room);
+        self.current_level += volume - spilled;
+        spilled
+    }
+
+    /// Returns the grams CO2e emitted delivering `volume` raw units of water
+    pub fn emissions_for(&self, volume: u64, volume_scale: u64) -> u64 {
+        let grams = volume as u128 * self.emissions_factor as u128 / volume_scale.max(1) as u128;
+        u64::try_from(grams).unwrap_or(u64::MAX)
+    }
+
+    /// Checks that a scarcity curve is usable for pricing
+    ///
+    /// Bands must be in strictly ascending order of level, the last one must cover a full
+    /// reservoir, and every multiplier must be non-zero. An empty curve is valid and
+    /// restores the linear default.
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidScarcityCurve` - If the curve is malformed
+    pub fn validate_scarcity_curve(bands: &[ScarcityBand]) -> Result<()> {
+        require!(
+            bands.len() <= SCARCITY_BANDS_MAX,
+            CustomError::InvalidScarcityCurve
+        );
+        require!(
+            bands.iter().all(|band| band.multiplier > 0)
+                && bands
+                    .windows(2)
+                    .all(|pair| pair[0].max_level_bps < pair[1].max_level_bps),
+            CustomError::InvalidScarcityCurve
+        );
+        if let Some(last) = bands.last() {
+            require!(
+                last.max_level_bps as u64 == BPS_DENOMINATOR,
+                CustomError::InvalidScarcityCurve
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the level seasonal tariffs are priced on
+    ///
+    /// This is the smoothed level when smoothing is enabled, and the current level otherwise.
+    pub fn pricing_level(&self) -> u64 {
+        if self.smoothing_bps == 0 {
+            self.current_level
+        } else {
+            self.smoothed_level.min(self.capacity)
+        }
+    }
+
+    /// Returns the forecast level usage under `tariff` is priced on at `slot`
+    ///
+    /// Tariffs pricing on forecasts use the level `forecast` projects for the current
+    /// period, and other tariffs the pricing level, for which `None` is returned.
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidLevelForecast` - If the forecast projects another reservoir
+    /// * `CustomError::ForecastUnavailable` - If the forecast is missing or does not cover
+    ///   `slot`
+    pub fn forecast_level(
+        &self,
+        tariff: &Tariff,
+        forecast: Option<&LevelForecast>,
+        agency: &Pubkey,
+        slot: u64,
+    ) -> Result<Option<u64>> {
+        if !tariff.forecast_pricing {
+            return Ok(None);
+        }
+        forecast
+            .ok_or(error!(CustomError::ForecastUnavailable))?
+            .level_for(agency, self, slot)
+            .map(Some)
+    }
+
+    /// Folds a newly reported level into the smoothed level
+    ///
+    /// The smoothed level moves towards `level` by `smoothing_bps` of the gap, so a single
+    /// wet or dry reading only shifts prices gradually. It tracks `level` exactly while
+    /// smoothing is disabled.
+    pub fn record_level(&mut self, level: u64) {
+        self.smoothed_level = if self.smoothing_bps == 0 {
+            level
+        } else if level >= self.smoothed_level {
+            self.smoothed_level + bps_of(level - self.smoothed_level, self.smoothing_bps)
+        } else {
+            self.smoothed_level - bps_of(self.smoothed_level - level, self.smoothing_bps)
+        };
+    }
+
+    /// Returns true if a report of `level` at `slot` is within the plausible rate of change
+    ///
+    /// The level may move by at most `max_level_rate` per slot since the last report.
+    pub fn is_plausible_level(&self, level: u64, slot: u64) -> bool {
+        if self.max_level_rate == 0 {
+            return true;
+        }
+        let elapsed = slot.saturating_sub(self.level_updated_slot);
+        level.abs_diff(self.current_level) <= self.max_level_rate.saturating_mul(elapsed)
+    }
+
+    /// Returns the alert threshold the level crossed when it moved from `previous_level`
+    ///
+    /// An alert is only raised when the level crosses into the alert zone, not while it
+    /// stays there, so repeated reports do not flood alerting.
+    pub fn crossed_alert(&self, previous_level: u64) -> Option<(ReservoirAlertKind, u64)> {
+        let level = self.current_level;
+        let high = self.alert_level_high;
+        let low = self.alert_level_low;
+        if high > 0 && level >= high && previous_level < high {
+            Some((ReservoirAlertKind::High, high))
+        } else if low > 0 && level <= low && previous_level > low {
+            Some((ReservoirAlertKind::Low, low))
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if a report of `level` at `unix_timestamp` is within the hourly change limit
+    ///
+    /// The level may move by at most `max_change_bps_per_hour` of capacity for each hour
+    /// elapsed since the last report, pro rata.
+    pub fn is_within_change_limit(&self, level: u64, unix_timestamp: i64) -> bool {
+        if self.max_change_bps_per_hour == 0 {
+            return true;
+        }
+        let elapsed = unix_timestamp.saturating_sub(self.level_updated_at).max(0) as u128;
+        let allowed = (self.capacity as u128) * (self.max_change_bps_per_hour as u128) * elapsed
+            / (BPS_DENOMINATOR as u128 * SECONDS_PER_HOUR as u128);
+        (level.abs_diff(self.current_level) as u128) <= allowed
+    }
+
+    /// Returns the inputs the multiplier of `tariff_type` is derived from
+    ///
+    /// # Arguments
+    /// * `tariff_type` - Type of the tariff priced
+    /// * `forecast_level` - Level projected for the period, for tariffs pricing on forecasts
+    pub fn scarcity_inputs(
+        &self,
+        tariff_type: TariffType,
+        forecast_level: Option<u64>,
+    ) -> ScarcityInputs {
+        let curve_len = self.scarcity_curve.len().min(SCARCITY_BANDS_MAX);
+        let mut curve = [ScarcityBand::default(); SCARCITY_BANDS_MAX];
+        curve[..curve_len].copy_from_slice(&self.scarcity_curve[..curve_len]);
+        ScarcityInputs {
+            tariff_type,
+            capacity: self.capacity,
+            current_level: self.current_level,
+            smoothed_level: self.smoothed_level,
+            smoothing_bps: self.smoothing_bps,
+            forecast_level,
+            curve_len: curve_len as u8,
+            curve,
+        }
+    }
+
+    /// Returns the multiplier applied to the block rate of `tariff_type` at the pricing level
+    ///
+    /// # Errors
+    /// * `CustomError::MathOverflow` - If the converted levels do not fit in a u64
+    pub fn scarcity_multiplier(
+        &self,
+        tariff_type: TariffType,
+        units: &UnitConfig,
+    ) -> Result<FixedPoint> {
+        self.scarcity_inputs(tariff_type, None).multiplier(units)
+    }
+
+    /// Draws `volume` out of the reservoir, such as water dispensed into a tanker
+    pub fn withdraw(&mut self, volume: u64) -> Result<()> {
+        self.current_level = self
+            .current_level
+            .checked_sub(volume)
+            .ok_or(CustomError::InsufficientReservoirLevel)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_low() {
+        let mut reservoir = Reservoir {
+            current_level: 200000,
+            capacity: 1000000,
+            reservoir_key: Pubkey::default(),
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 0,
+            scarcity_curve: vec![],
+            smoothing_bps: 0,
+            smoothed_level: 0,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 0,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 0,
+            alert_level_high: 0,
+            alert_level_low: 0,
+        };
+        assert!(!reservoir.is_low());
+        reservoir.current_level = 199999;
+        assert!(reservoir.is_low());
+    }
+
+    #[test]
+    fn test_assigned_consumer_count() {
+        let mut reservoir = Reservoir {
+            current_level: 200000,
+            capacity: 1000000,
+            reservoir_key: Pubkey::default(),
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 0,
+            scarcity_curve: vec![],
+            smoothing_bps: 0,
+            smoothed_level: 0,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 0,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 0,
+            alert_level_high: 0,
+            alert_level_low: 0,
+        };
+        reservoir.assign_consumer();
+        reservoir.assign_consumer();
+        reservoir.release_consumer();
+        assert_eq!(reservoir.assigned_consumer_count, 1);
+        reservoir.release_consumer();
+        reservoir.release_consumer();
+        assert_eq!(reservoir.assigned_consumer_count, 0);
+    }
+
+    #[test]
+    fn test_withdraw() {
+        let mut reservoir = Reservoir {
+            current_level: 200000,
+            capacity: 1000000,
+            reservoir_key: Pubkey::default(),
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 0,
+            scarcity_curve: vec![],
+            smoothing_bps: 0,
+            smoothed_level: 0,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 0,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 0,
+            alert_level_high: 0,
+            alert_level_low: 0,
+        };
+        reservoir.withdraw(150000).unwrap();
+        assert_eq!(reservoir.current_level, 50000);
+        assert!(reservoir.withdraw(50001).is_err());
+        assert_eq!(reservoir.current_level, 50000);
+    }
+
+    #[test]
+    fn test_fill() {
+        let mut reservoir = Reservoir {
+            current_level: 900000,
+            capacity: 1000000,
+            reservoir_key: Pubkey::default(),
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 0,
+            scarcity_curve: vec![],
+            smoothing_bps: 0,
+            smoothed_level: 0,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 0,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 0,
+            alert_level_high: 0,
+            alert_level_low: 0,
+        };
+        assert_eq!(reservoir.fill(60000), 0);
+        assert_eq!(reservoir.current_level, 960000);
+
+        // Water over the capacity spills instead of raising the level
+        assert_eq!(reservoir.fill(100000), 60000);
+        assert_eq!(reservoir.current_level, 1000000);
+        assert_eq!(reservoir.fill(1), 1);
+    }
+
+    #[test]
+    fn test_emissions_for() {
+        let mut reservoir = Reservoir {
+            current_level: 200000,
+            capacity: 1000000,
+            reservoir_key: Pubkey::default(),
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 350,
+            scarcity_curve: vec![],
+            smoothing_bps: 0,
+            smoothed_level: 0,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 0,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 0,
+            alert_level_high: 0,
+            alert_level_low: 0,
+        };
+        // 40.000 units at 350 g per unit
+        assert_eq!(reservoir.emissions_for(40000, 1000), 14000);
+        reservoir.emissions_factor = 0;
+        assert_eq!(reservoir.emissions_for(40000, 1000), 0);
+    }
+
+    #[test]
+    fn test_scarcity_multiplier() {
+        let mut reservoir = Reservoir {
+            current_level: 950000,
+            capacity: 1000000,
+            reservoir_key: Pubkey::default(),
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 0,
+            scarcity_curve: vec![],
+            smoothing_bps: 0,
+            smoothed_level: 0,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 0,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 0,
+            alert_level_high: 0,
+            alert_level_low: 0,
+        };
+        let units = UnitConfig {
+            volume_scale: 1000,
+            currency_decimals: 3,
+        };
+        // Without a curve the block rate scales linearly with the missing volume
+        assert_eq!(
+            reservoir
+                .scarcity_multiplier(TariffType::SeasonalIBT, &units)
+                .unwrap(),
+            FixedPoint::from(50000)
+        );
+
+        let curve = vec![
+            ScarcityBand {
+                max_level_bps: 3_000,
+                multiplier: 3000,
+            },
+            ScarcityBand {
+                max_level_bps: 10_000,
+                multiplier: 1000,
+            },
+        ];
+        Reservoir::validate_scarcity_curve(&curve).unwrap();
+        reservoir.scarcity_curve = curve;
+        assert_eq!(
+            reservoir
+                .scarcity_multiplier(TariffType::SeasonalIBT, &units)
+                .unwrap(),
+            FixedPoint::from(1000)
+        );
+        reservoir.current_level = 300000;
+        assert_eq!(
+            reservoir
+                .scarcity_multiplier(TariffType::SeasonalDBT, &units)
+                .unwrap(),
+            FixedPoint::from(3000)
+        );
+        assert_eq!(
+            reservoir
+                .scarcity_multiplier(TariffType::UniformIBT, &units)
+                .unwrap(),
+            FixedPoint::one()
+        );
+    }
+
+    #[test]
+    fn test_scarcity_inputs() {
+        let reservoir = Reservoir {
+            current_level: 950000,
+            capacity: 1000000,
+            reservoir_key: Pubkey::default(),
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 0,
+            scarcity_curve: vec![
+                ScarcityBand {
+                    max_level_bps: 3_000,
+                    multiplier: 3000,
+                },
+                ScarcityBand {
+                    max_level_bps: 10_000,
+                    multiplier: 1000,
+                },
+            ],
+            smoothing_bps: 1_000,
+            smoothed_level: 250000,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 0,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 0,
+            alert_level_high: 0,
+            alert_level_low: 0,
+        };
+        let units = UnitConfig {
+            volume_scale: 1000,
+            currency_decimals: 3,
+        };
+
+        // The inputs derive the same multiplier as the reservoir, on the smoothed level
+        let inputs = reservoir.scarcity_inputs(TariffType::SeasonalIBT, None);
+        assert_eq!(inputs.curve_len, 2);
+        assert_eq!(inputs.level(), reservoir.pricing_level());
+        assert_eq!(
+            inputs.multiplier(&units).unwrap(),
+            reservoir
+                .scarcity_multiplier(TariffType::SeasonalIBT, &units)
+                .unwrap()
+        );
+        assert_eq!(inputs.multiplier(&units).unwrap(), FixedPoint::from(3000));
+
+        // A forecast level takes precedence, capped at capacity
+        let inputs = reservoir.scarcity_inputs(TariffType::SeasonalIBT, Some(2000000));
+        assert_eq!(inputs.level(), 1000000);
+        assert_eq!(inputs.multiplier(&units).unwrap(), FixedPoint::from(1000));
+    }
+
+    #[test]
+    fn test_validate_scarcity_curve() {
+        let band = |max_level_bps, multiplier| ScarcityBand {
+            max_level_bps,
+            multiplier,
+        };
+        assert!(Reservoir::validate_scarcity_curve(&[]).is_ok());
+        assert!(Reservoir::validate_scarcity_curve(&[band(10_000, 1000)]).is_ok());
+        // The last band must cover a full reservoir
+        assert!(Reservoir::validate_scarcity_curve(&[band(9_000, 1000)]).is_err());
+        // Bands must be ascending and non-zero
+        assert!(
+            Reservoir::validate_scarcity_curve(&[band(5_000, 2000), band(5_000, 1000)]).is_err()
+        );
+        assert!(Reservoir::validate_scarcity_curve(&[band(10_000, 0)]).is_err());
+    }
+
+    #[test]
+    fn test_record_level() {
+        let mut reservoir = Reservoir {
+            current_level: 800000,
+            capacity: 1000000,
+            reservoir_key: Pubkey::default(),
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 0,
+            scarcity_curve: vec![],
+            smoothing_bps: 0,
+            smoothed_level: 800000,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 0,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 0,
+            alert_level_high: 0,
+            alert_level_low: 0,
+        };
+        // Without smoothing the reported level prices directly
+        reservoir.current_level = 400000;
+        reservoir.record_level(400000);
+        assert_eq!(reservoir.pricing_level(), 400000);
+
+        // A quarter of each gap is closed per update
+        reservoir.smoothing_bps = 2_500;
+        reservoir.current_level = 800000;
+        reservoir.record_level(800000);
+        assert_eq!(reservoir.pricing_level(), 500000);
+        reservoir.current_level = 100000;
+        reservoir.record_level(100000);
+        assert_eq!(reservoir.pricing_level(), 400000);
+    }
+
+    #[test]
+    fn test_is_plausible_level() {
+        let mut reservoir = Reservoir {
+            current_level: 800000,
+            capacity: 1000000,
+            reservoir_key: Pubkey::default(),
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 0,
+            scarcity_curve: vec![],
+            smoothing_bps: 0,
+            smoothed_level: 800000,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 100,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 0,
+            alert_level_high: 0,
+            alert_level_low: 0,
+        };
+        // Unbounded by default
+        assert!(reservoir.is_plausible_level(0, 100));
+
+        reservoir.max_level_rate = 50;
+        assert!(reservoir.is_plausible_level(805000, 200));
+        assert!(reservoir.is_plausible_level(795000, 200));
+        assert!(!reservoir.is_plausible_level(805001, 200));
+        assert!(!reservoir.is_plausible_level(799999, 100));
+    }
+
+    #[test]
+    fn test_is_within_change_limit() {
+        let mut reservoir = Reservoir {
+            current_level: 800000,
+            capacity: 1000000,
+            reservoir_key: Pubkey::default(),
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 0,
+            scarcity_curve: vec![],
+            smoothing_bps: 0,
+            smoothed_level: 800000,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 0,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 1_760_000_000,
+            alert_level_high: 0,
+            alert_level_low: 0,
+        };
+        // Unlimited by default
+        assert!(reservoir.is_within_change_limit(0, 1_760_000_000));
+
+        // 5% of capacity per hour, so 2.5% over half an hour
+        reservoir.max_change_bps_per_hour = 500;
+        assert!(reservoir.is_within_change_limit(825000, 1_760_001_800));
+        assert!(reservoir.is_within_change_limit(775000, 1_760_001_800));
+        assert!(!reservoir.is_within_change_limit(825001, 1_760_001_800));
+        assert!(!reservoir.is_within_change_limit(800001, 1_759_999_000));
+    }
+
+    #[test]
+    fn test_crossed_alert() {
+        let mut reservoir = Reservoir {
+            current_level: 900000,
+            capacity: 1000000,
+            reservoir_key: Pubkey::default(),
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 0,
+            scarcity_curve: vec![],
+            smoothing_bps: 0,
+            smoothed_level: 0,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 0,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 0,
+            alert_level_high: 950000,
+            alert_level_low: 200000,
+        };
+        assert_eq!(reservoir.crossed_alert(800000), None);
+
+        reservoir.current_level = 950000;
+        assert_eq!(
+            reservoir.crossed_alert(900000),
+            Some((ReservoirAlertKind::High, 950000))
+        );
+        // Staying above the threshold raises no further alert
+        assert_eq!(reservoir.crossed_alert(960000), None);
+
+        reservoir.current_level = 150000;
+        assert_eq!(
+            reservoir.crossed_alert(250000),
+            Some((ReservoirAlertKind::Low, 200000))
+        );
+
+        // Disabled thresholds never alert
+        reservoir.alert_level_low = 0;
+        assert_eq!(reservoir.crossed_alert(250000), None);
+    }
 }
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+
+/// Number of days of withdrawals kept before the oldest day is overwritten
+pub const RESERVOIR_DAILY_STATS_LEN: usize = 32;
+
+/// Length of a day in seconds of the cluster's unix timestamp
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Water withdrawn from a reservoir on one day.
+///
+/// # Fields
+/// * `day` - Days since the unix epoch, in UTC
+/// * `volume` - Metered usage billed against the reservoir that day, in raw volume units
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DailyUsage {
+    pub day: u64,
+    pub volume: u64,
+}
+
+/// Rolling record of a reservoir's daily withdrawals.
+///
+/// Every `use_water` reading adds its volume to the entry for the current day, so demand
+/// forecasts and seasonal tariff calibration can read actual withdrawals from the chain.
+/// Each day maps to a fixed slot of the ring buffer, which is cleared when a new day
+/// reaches it.
+///
+/// # Fields
+/// * `reservoir_key` - Public key of the reservoir these statistics belong to
+/// * `days` - Ring buffer of daily withdrawals, indexed by day modulo its length
+#[account]
+#[derive(InitSpace)]
+pub struct ReservoirDailyStats {
+    /// The public key of the reservoir these statistics belong to.
+    pub reservoir_key: Pubkey,
+
+    /// Daily withdrawals, the entry for `day` stored at `day % RESERVOIR_DAILY_STATS_LEN`.
+    pub days: [DailyUsage; RESERVOIR_DAILY_STATS_LEN],
+}
+
+impl ReservoirDailyStats {
+    /// Returns the day a unix timestamp falls on, counted from the unix epoch
+    pub fn day_of(unix_timestamp: i64) -> u64 {
+        (unix_timestamp.max(0) / SECONDS_PER_DAY) as u64
+    }
+
+    /// Adds a withdrawal to the given day, replacing the entry of an older day
+    pub fn record(&mut self, day: u64, volume: u64) {
+        let entry = &mut self.days[day as usize % RESERVOIR_DAILY_STATS_LEN];
+        if entry.day != day {
+            *entry = DailyUsage { day, volume: 0 };
+        }
+        entry.volume = entry.volume.saturating_add(volume);
+    }
+
+    /// Returns the volume withdrawn on the given day, or 0 if it is no longer stored
+    pub fn volume_on(&self, day: u64) -> u64 {
+        let entry = &self.days[day as usize % RESERVOIR_DAILY_STATS_LEN];
+        if entry.day == day {
+            entry.volume
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn daily_stats() -> ReservoirDailyStats {
+        ReservoirDailyStats {
+            reservoir_key: Pubkey::default(),
+            days: [DailyUsage { day: 0, volume: 0 }; RESERVOIR_DAILY_STATS_LEN],
+        }
+    }
+
+    #[test]
+    fn test_day_of() {
+        assert_eq!(ReservoirDailyStats::day_of(0), 0);
+        assert_eq!(ReservoirDailyStats::day_of(SECONDS_PER_DAY - 1), 0);
+        assert_eq!(ReservoirDailyStats::day_of(SECONDS_PER_DAY), 1);
+        assert_eq!(ReservoirDailyStats::day_of(-1), 0);
+    }
+
+    #[test]
+    fn test_record_accumulates_per_day() {
+        let mut daily_stats = daily_stats();
+        daily_stats.record(20000, 30000);
+        daily_stats.record(20000, 20000);
+        daily_stats.record(20001, 10000);
+
+        assert_eq!(daily_stats.volume_on(20000), 50000);
+        assert_eq!(daily_stats.volume_on(20001), 10000);
+        assert_eq!(daily_stats.volume_on(20002), 0);
+    }
+
+    #[test]
+    fn test_record_overwrites_oldest_day() {
+        let mut daily_stats = daily_stats();
+        let later = 20000 + RESERVOIR_DAILY_STATS_LEN as u64;
+        daily_stats.record(20000, 30000);
+        daily_stats.record(later, 5000);
+
+        assert_eq!(daily_stats.volume_on(20000), 0);
+        assert_eq!(daily_stats.volume_on(later), 5000);
+    }
+}
@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+
+use crate::CustomError;
+
+/// Duty a staff key is allowed to perform on behalf of an agency.
+///
+/// # Variants
+/// * `Admin` - Manages staff roles and may perform every gated operation
+/// * `Billing` - Manages tariffs and consumer accounts
+/// * `MeterOperator` - Reports reservoir levels
+/// * `Auditor` - Anchors documents in the agency's audit log
+/// * `FieldOperator` - Carries out work orders in the field
+/// * `Driver` - Dispenses bulk deliveries from water tankers
+/// * `Regulator` - Approves regulated operations on behalf of the agency's regulator authority
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RoleKind {
+    /// Manages staff roles and may perform every gated operation
+    Admin,
+
+    /// Manages tariffs and consumer accounts
+    Billing,
+
+    /// Reports reservoir levels
+    MeterOperator,
+
+    /// Anchors documents in the agency's audit log
+    Auditor,
+
+    /// Carries out work orders in the field
+    FieldOperator,
+
+    /// Dispenses bulk deliveries from water tankers
+    Driver,
+
+    /// Approves regulated operations on behalf of the agency's regulator authority, which
+    /// alone grants this role
+    Regulator,
+}
+
+impl RoleKind {
+    /// Returns true if holders of this role may perform operations requiring `required`
+    ///
+    /// Admins may perform every operation except regulatory approvals, which must come
+    /// from a key independent of the agency's staff.
+    pub fn permits(self, required: RoleKind) -> bool {
+        self == required || (self == RoleKind::Admin && required != RoleKind::Regulator)
+    }
+}
+
+/// Grants a staff key one role within an agency.
+///
+/// Large utilities separate duties across staff keys instead of sharing the agency's
+/// key. The agency key itself holds every role implicitly; any other signer must hold a
+/// role account permitting the operation.
+///
+/// # Fields
+/// * `agency` - Agency the role was granted by
+/// * `member` - Staff key holding the role
+/// * `kind` - Duty the member may perform
+/// * `granted_slot` - Slot at which the role was last granted
+///
+/// # Example
+/// ```ignore
+/// let role = Role {
+///     agency: agency_pubkey,
+///     member: staff_pubkey,
+///     kind: RoleKind::Billing,
+///     granted_slot: 1000,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct Role {
+    /// Agency the role was granted by.
+    pub agency: Pubkey,
+
+    /// Staff key holding the role.
+    pub member: Pubkey,
+
+    /// Duty the member may perform.
+    pub kind: RoleKind,
+
+    /// Slot at which the role was last granted or changed.
+    pub granted_slot: u64,
+}
+
+impl Role {
+    /// Checks that `authority` may act for `agency` in operations requiring `required`
+    ///
+    /// The agency key is always authorized. Any other signer must pass the role it was
+    /// granted by that agency.
+    ///
+    /// # Errors
+    /// * `CustomError::MissingRole` - If the signer holds no role permitting the operation
+    pub fn authorize(
+        agency: &Pubkey,
+        authority: &Pubkey,
+        role: Option<&Role>,
+        required: RoleKind,
+    ) -> Result<()> {
+        if authority == agency {
+            return Ok(());
+        }
+        match role {
+            Some(role)
+                if role.agency == *agency
+                    && role.member == *authority
+                    && role.kind.permits(required) =>
+            {
+                Ok(())
+            }
+            _ => err!(CustomError::MissingRole),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorize() {
+        let agency = Pubkey::new_unique();
+        let staff = Pubkey::new_unique();
+        let role = Role {
+            agency,
+            member: staff,
+            kind: RoleKind::Billing,
+            granted_slot: 0,
+        };
+
+        // The agency key needs no role
+        assert!(Role::authorize(&agency, &agency, None, RoleKind::Admin).is_ok());
+
+        assert!(Role::authorize(&agency, &staff, Some(&role), RoleKind::Billing).is_ok());
+        assert!(Role::authorize(&agency, &staff, Some(&role), RoleKind::Auditor).is_err());
+        assert!(Role::authorize(&agency, &staff, None, RoleKind::Billing).is_err());
+
+        // Roles are bound to the member and agency they were granted to
+        let other = Pubkey::new_unique();
+        assert!(Role::authorize(&agency, &other, Some(&role), RoleKind::Billing).is_err());
+        assert!(Role::authorize(&other, &staff, Some(&role), RoleKind::Billing).is_err());
+    }
+
+    #[test]
+    fn test_admin_permits_every_role() {
+        for kind in [
+            RoleKind::Admin,
+            RoleKind::Billing,
+            RoleKind::MeterOperator,
+            RoleKind::Auditor,
+        ] {
+            assert!(RoleKind::Admin.permits(kind));
+        }
+        assert!(!RoleKind::MeterOperator.permits(RoleKind::Billing));
+    }
+
+    #[test]
+    fn test_only_regulators_approve_transfers() {
+        assert!(RoleKind::Regulator.permits(RoleKind::Regulator));
+        assert!(!RoleKind::Admin.permits(RoleKind::Regulator));
+    }
+}
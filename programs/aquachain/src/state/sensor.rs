@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+
+/// Number of slots a sensor reading remains usable for aggregation (about one hour)
+pub const SENSOR_READING_MAX_AGE_SLOTS: u64 = 9_000;
+
+/// Represents one level gauge of a reservoir's sensor network.
+///
+/// Each sensor reports its own reading of the reservoir level. The effective level is the
+/// median of the recent readings across the network, so a single faulty or spoofed gauge
+/// cannot move the level tariffs are priced on.
+///
+/// # Fields
+/// * `agency` - Agency the reservoir belongs to
+/// * `reservoir_key` - Reservoir the sensor gauges
+/// * `sensor_key` - Unique identifier of the sensor
+/// * `authority` - Key allowed to submit the sensor's readings
+/// * `level` - Latest level read by the sensor
+/// * `read_slot` - Slot at which the latest reading was submitted
+///
+/// # Example
+/// ```ignore
+/// let sensor = Sensor {
+///     agency: agency_pubkey,
+///     reservoir_key: reservoir_pubkey,
+///     sensor_key: sensor_pubkey,
+///     authority: gauge_pubkey,
+///     level: 0,
+///     read_slot: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct Sensor {
+    /// Agency the reservoir belongs to.
+    pub agency: Pubkey,
+
+    /// Reservoir the sensor gauges.
+    pub reservoir_key: Pubkey,
+
+    /// Unique identifier of the sensor.
+    pub sensor_key: Pubkey,
+
+    /// Public key allowed to submit the sensor's readings.
+    pub authority: Pubkey,
+
+    /// Latest level read by the sensor, zero until the first reading.
+    pub level: u64,
+
+    /// Slot at which the latest reading was submitted.
+    pub read_slot: u64,
+}
+
+impl Sensor {
+    /// Checks whether the sensor has a reading recent enough to aggregate at the given slot
+    pub fn has_recent_reading(&self, slot: u64) -> bool {
+        self.level > 0 && slot.saturating_sub(self.read_slot) <= SENSOR_READING_MAX_AGE_SLOTS
+    }
+}
+
+/// Median of the given levels, averaging the two middle levels of an even count
+///
+/// Returns `None` when no level is given.
+pub fn median_level(levels: &mut [u64]) -> Option<u64> {
+    if levels.is_empty() {
+        return None;
+    }
+    levels.sort_unstable();
+    let mid = levels.len() / 2;
+    if levels.len() % 2 == 1 {
+        Some(levels[mid])
+    } else {
+        let (low, high) = (levels[mid - 1], levels[mid]);
+        Some(low + (high - low) / 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median_level() {
+        assert_eq!(median_level(&mut []), None);
+        assert_eq!(median_level(&mut [700]), Some(700));
+
+        // A single faulty sensor does not move the median
+        assert_eq!(median_level(&mut [710, 5_000_000, 700]), Some(710));
+        assert_eq!(median_level(&mut [700, 710, 0, 720]), Some(705));
+    }
+
+    #[test]
+    fn test_recent_reading() {
+        let mut sensor = Sensor {
+            agency: Pubkey::default(),
+            reservoir_key: Pubkey::default(),
+            sensor_key: Pubkey::default(),
+            authority: Pubkey::default(),
+            level: 0,
+            read_slot: 1_000,
+        };
+        // A sensor that never reported has no reading
+        assert!(!sensor.has_recent_reading(1_000));
+
+        sensor.level = 700;
+        assert!(sensor.has_recent_reading(1_000 + SENSOR_READING_MAX_AGE_SLOTS));
+        assert!(!sensor.has_recent_reading(1_001 + SENSOR_READING_MAX_AGE_SLOTS));
+    }
+}
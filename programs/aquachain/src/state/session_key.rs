@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+
+/// Longest a session key may stay valid, in seconds
+pub const MAX_SESSION_DURATION: i64 = 30 * 86_400;
+
+/// Operation a session key is allowed to sign.
+///
+/// # Variants
+/// * `SubmitReading` - Submitting meter readings for the consumer
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SessionScope {
+    /// Submitting meter readings for the consumer
+    SubmitReading,
+}
+
+/// Short-lived key delegated to a consumer's meter device.
+///
+/// IoT meters sign readings with an ephemeral key instead of holding the consumer's or
+/// the agency's wallet key. The key is limited to its scope and rejected once it expires,
+/// so a compromised device can only submit readings until the session runs out.
+///
+/// # Fields
+/// * `consumer` - Consumer account the key acts for
+/// * `session_key` - Public key held by the meter device
+/// * `scope` - Operation the key may sign
+/// * `expires_at` - Unix timestamp after which the key is rejected
+/// * `registered_by` - Consumer owner or agency that registered the key
+///
+/// # Example
+/// ```ignore
+/// let session = SessionKey {
+///     consumer: consumer_pubkey,
+///     session_key: device_pubkey,
+///     scope: SessionScope::SubmitReading,
+///     expires_at: 1_760_086_400,
+///     registered_by: owner_pubkey,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct SessionKey {
+    /// Consumer account the key acts for.
+    pub consumer: Pubkey,
+
+    /// Public key held by the meter device.
+    pub session_key: Pubkey,
+
+    /// Operation the key may sign.
+    pub scope: SessionScope,
+
+    /// Unix timestamp after which the key is rejected.
+    pub expires_at: i64,
+
+    /// Consumer owner or agency that registered the key.
+    pub registered_by: Pubkey,
+}
+
+impl SessionKey {
+    /// Returns the key a device identified by an Ethereum address is registered under,
+    /// the address left-padded with zeroes
+    pub fn eth_address_key(eth_address: &[u8; 20]) -> Pubkey {
+        let mut key = [0; 32];
+        key[12..].copy_from_slice(eth_address);
+        Pubkey::new_from_array(key)
+    }
+
+    /// Returns true if the key may sign operations of `scope` at the given timestamp
+    pub fn permits(&self, scope: SessionScope, unix_timestamp: i64) -> bool {
+        self.scope == scope && unix_timestamp < self.expires_at
+    }
+
+    /// Returns true if `expires_at` is in the future and within the longest session duration
+    pub fn is_valid_expiry(expires_at: i64, unix_timestamp: i64) -> bool {
+        expires_at > unix_timestamp && expires_at - unix_timestamp <= MAX_SESSION_DURATION
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_expiry() {
+        let now = 1_760_000_000;
+        assert!(SessionKey::is_valid_expiry(now + 3600, now));
+        assert!(SessionKey::is_valid_expiry(now + MAX_SESSION_DURATION, now));
+        assert!(!SessionKey::is_valid_expiry(now, now));
+        assert!(!SessionKey::is_valid_expiry(
+            now + MAX_SESSION_DURATION + 1,
+            now
+        ));
+
+        let session = SessionKey {
+            consumer: Pubkey::new_unique(),
+            session_key: Pubkey::new_unique(),
+            scope: SessionScope::SubmitReading,
+            expires_at: now + 3600,
+            registered_by: Pubkey::new_unique(),
+        };
+        assert!(session.permits(SessionScope::SubmitReading, now + 3599));
+        assert!(!session.permits(SessionScope::SubmitReading, now + 3600));
+    }
+
+    #[test]
+    fn test_eth_address_key() {
+        let key = SessionKey::eth_address_key(&[7; 20]);
+        assert_eq!(&key.as_ref()[..12], &[0; 12]);
+        assert_eq!(&key.as_ref()[12..], &[7; 20]);
+    }
+}
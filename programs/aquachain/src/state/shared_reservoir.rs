@@ -0,0 +1,298 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    utils::{bps_of, BPS_DENOMINATOR},
+    CustomError,
+};
+
+/// Largest number of agencies holding draw rights on a shared reservoir
+pub const SHARED_RESERVOIR_AGENCIES_MAX: usize = 8;
+
+/// Share of a shared reservoir granted to an agency.
+///
+/// # Fields
+/// * `agency` - Agency whose consumers draw against the share
+/// * `share_bps` - Share of the volume drawable each period, in basis points
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq, InitSpace)]
+pub struct DrawShare {
+    pub agency: Pubkey,
+    pub share_bps: u16,
+}
+
+/// An agency's draw right on a shared reservoir and what it drew against it.
+///
+/// # Fields
+/// * `agency` - Agency whose consumers draw against the right
+/// * `share_bps` - Share of the volume drawable each period, in basis points
+/// * `drawn` - Volume the agency's consumers drew in the current period, in raw volume units
+/// * `owed` - Volume drawn beyond the share and not yet settled with the other agencies
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq, InitSpace)]
+pub struct DrawRight {
+    pub agency: Pubkey,
+    pub share_bps: u16,
+    pub drawn: u64,
+    pub owed: u64,
+}
+
+impl DrawRight {
+    /// Returns the volume the right lets the agency draw in a period of `period_volume`
+    pub fn allowance(&self, period_volume: u64) -> u64 {
+        bps_of(period_volume, self.share_bps)
+    }
+}
+
+/// A reservoir drawn from by several agencies in proportion to their draw rights.
+///
+/// The operating agency lists every agency drawing from the reservoir, itself included,
+/// with its share of the volume drawable each period. Every `use_water` reading passing the
+/// shared reservoir adds its volume to the consumer's agency, and volume drawn beyond the
+/// agency's share is recorded as owed to the other agencies until the operator settles it.
+///
+/// # Fields
+/// * `operator` - Agency operating the reservoir and settling obligations
+/// * `reservoir_key` - Reservoir the draw rights apply to
+/// * `period_volume` - Volume drawable by all agencies each period, in raw volume units
+/// * `period_slots` - Length of a draw period in slots
+/// * `period_start_slot` - Slot at which the current draw period began
+/// * `draw_rights` - Draw right of each agency, with shares totalling 10,000 bps
+///
+/// # Example
+/// ```ignore
+/// let shared_reservoir = SharedReservoir {
+///     operator: agency_pubkey,
+///     reservoir_key: reservoir_pubkey,
+///     period_volume: 5_000_000_000, // 5,000,000 m³ metered in litres
+///     period_slots: 6_480_000,      // about 30 days
+///     period_start_slot: 0,
+///     draw_rights: vec![],
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct SharedReservoir {
+    /// Agency operating the reservoir and settling obligations between agencies.
+    pub operator: Pubkey,
+
+    /// The reservoir the draw rights apply to.
+    pub reservoir_key: Pubkey,
+
+    /// Volume drawable by all agencies each period, in raw volume units.
+    pub period_volume: u64,
+
+    /// Length of a draw period in slots.
+    pub period_slots: u64,
+
+    /// Slot at which the current draw period began.
+    pub period_start_slot: u64,
+
+    /// Draw right of each agency drawing from the reservoir.
+    #[max_len(SHARED_RESERVOIR_AGENCIES_MAX)]
+    pub draw_rights: Vec<DrawRight>,
+}
+
+impl SharedReservoir {
+    /// Checks that the shares name distinct agencies and split the whole reservoir
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidDrawRights` - If the shares are malformed
+    pub fn validate_draw_shares(shares: &[DrawShare]) -> Result<()> {
+        require!(
+            !shares.is_empty() && shares.len() <= SHARED_RESERVOIR_AGENCIES_MAX,
+            CustomError::InvalidDrawRights
+        );
+        require!(
+            shares.iter().all(|share| share.share_bps > 0)
+                && shares.iter().enumerate().all(|(index, share)| {
+                    shares[..index]
+                        .iter()
+                        .all(|other| other.agency != share.agency)
+                }),
+            CustomError::InvalidDrawRights
+        );
+        require!(
+            shares
+                .iter()
+                .map(|share| share.share_bps as u64)
+                .sum::<u64>()
+                == BPS_DENOMINATOR,
+            CustomError::InvalidDrawRights
+        );
+        Ok(())
+    }
+
+    /// Replaces the draw rights with `shares`
+    ///
+    /// Agencies that keep a right keep what they drew this period and what they owe.
+    ///
+    /// # Errors
+    /// * `CustomError::OutstandingSettlement` - If a removed agency still owes volume
+    pub fn set_draw_shares(&mut self, shares: &[DrawShare]) -> Result<()> {
+        require!(
+            self.draw_rights
+                .iter()
+                .all(|right| right.owed == 0
+                    || shares.iter().any(|share| share.agency == right.agency)),
+            CustomError::OutstandingSettlement
+        );
+        self.draw_rights = shares
+            .iter()
+            .map(|share| {
+                let (drawn, owed) = self
+                    .right_of(&share.agency)
+                    .map_or((0, 0), |right| (right.drawn, right.owed));
+                DrawRight {
+                    agency: share.agency,
+                    share_bps: share.share_bps,
+                    drawn,
+                    owed,
+                }
+            })
+            .collect();
+        Ok(())
+    }
+
+    /// Returns the draw right of `agency`, if it holds one
+    pub fn right_of(&self, agency: &Pubkey) -> Option<&DrawRight> {
+        self.draw_rights
+            .iter()
+            .find(|right| right.agency == *agency)
+    }
+
+    /// Starts a new draw period once the current one has elapsed at `slot`
+    ///
+    /// Volume drawn is reset with each period, while volume owed carries over until it is
+    /// settled.
+    pub fn roll_period(&mut self, slot: u64) {
+        if self.period_slots == 0 || slot < self.period_start_slot + self.period_slots {
+            return;
+        }
+        let elapsed = (slot - self.period_start_slot) / self.period_slots;
+        self.period_start_slot += elapsed * self.period_slots;
+        for right in self.draw_rights.iter_mut() {
+            right.drawn = 0;
+        }
+    }
+
+    /// Adds a draw of `volume` by the consumers of `agency` at `slot`
+    ///
+    /// # Returns
+    /// The volume of the draw beyond the agency's share, which is added to what it owes
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidSharedReservoir` - If the agency holds no draw right
+    pub fn record_draw(&mut self, agency: &Pubkey, volume: u64, slot: u64) -> Result<u64> {
+        self.roll_period(slot);
+        let period_volume = self.period_volume;
+        let right = self
+            .draw_rights
+            .iter_mut()
+            .find(|right| right.agency == *agency)
+            .ok_or(CustomError::InvalidSharedReservoir)?;
+
+        let allowance = right.allowance(period_volume);
+        let exceeded_before = right.drawn.saturating_sub(allowance);
+        right.drawn = right.drawn.saturating_add(volume);
+        let exceeded = right.drawn.saturating_sub(allowance) - exceeded_before;
+        right.owed = right.owed.saturating_add(exceeded);
+        Ok(exceeded)
+    }
+
+    /// Clears up to `volume` of what `agency` owes, returning the volume cleared
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidSharedReservoir` - If the agency holds no draw right
+    pub fn settle(&mut self, agency: &Pubkey, volume: u64) -> Result<u64> {
+        let right = self
+            .draw_rights
+            .iter_mut()
+            .find(|right| right.agency == *agency)
+            .ok_or(CustomError::InvalidSharedReservoir)?;
+        let settled = volume.min(right.owed);
+        right.owed -= settled;
+        Ok(settled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shared_reservoir(shares: &[DrawShare]) -> SharedReservoir {
+        let mut shared_reservoir = SharedReservoir {
+            operator: Pubkey::default(),
+            reservoir_key: Pubkey::default(),
+            period_volume: 100_000,
+            period_slots: 1_000,
+            period_start_slot: 0,
+            draw_rights: vec![],
+        };
+        shared_reservoir.set_draw_shares(shares).unwrap();
+        shared_reservoir
+    }
+
+    fn share(agency: Pubkey, share_bps: u16) -> DrawShare {
+        DrawShare { agency, share_bps }
+    }
+
+    #[test]
+    fn test_validate_draw_shares() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(SharedReservoir::validate_draw_shares(&[share(a, 6_000), share(b, 4_000)]).is_ok());
+        // Shares must split the whole reservoir between distinct agencies
+        assert!(
+            SharedReservoir::validate_draw_shares(&[share(a, 6_000), share(b, 3_000)]).is_err()
+        );
+        assert!(
+            SharedReservoir::validate_draw_shares(&[share(a, 5_000), share(a, 5_000)]).is_err()
+        );
+        assert!(SharedReservoir::validate_draw_shares(&[share(a, 10_000), share(b, 0)]).is_err());
+        assert!(SharedReservoir::validate_draw_shares(&[]).is_err());
+    }
+
+    #[test]
+    fn test_record_draw_beyond_share() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut shared = shared_reservoir(&[share(a, 6_000), share(b, 4_000)]);
+
+        // Agency b may draw 40,000 per period
+        assert_eq!(shared.record_draw(&b, 30_000, 10).unwrap(), 0);
+        assert_eq!(shared.record_draw(&b, 15_000, 20).unwrap(), 5_000);
+        assert_eq!(shared.record_draw(&b, 2_000, 30).unwrap(), 2_000);
+        assert_eq!(shared.right_of(&b).unwrap().owed, 7_000);
+        assert_eq!(shared.right_of(&a).unwrap().owed, 0);
+
+        assert!(shared.record_draw(&Pubkey::new_unique(), 1, 40).is_err());
+    }
+
+    #[test]
+    fn test_roll_period_keeps_owed() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut shared = shared_reservoir(&[share(a, 6_000), share(b, 4_000)]);
+        shared.record_draw(&b, 45_000, 10).unwrap();
+
+        // A new period resets what was drawn but not what is owed
+        assert_eq!(shared.record_draw(&b, 40_000, 2_500).unwrap(), 0);
+        assert_eq!(shared.period_start_slot, 2_000);
+        assert_eq!(shared.right_of(&b).unwrap().drawn, 40_000);
+        assert_eq!(shared.right_of(&b).unwrap().owed, 5_000);
+
+        assert_eq!(shared.settle(&b, 8_000).unwrap(), 5_000);
+        assert_eq!(shared.right_of(&b).unwrap().owed, 0);
+    }
+
+    #[test]
+    fn test_set_draw_shares_keeps_obligations() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut shared = shared_reservoir(&[share(a, 6_000), share(b, 4_000)]);
+        shared.record_draw(&b, 45_000, 10).unwrap();
+
+        // An agency cannot be removed while it still owes volume
+        assert!(shared.set_draw_shares(&[share(a, 10_000)]).is_err());
+
+        shared
+            .set_draw_shares(&[share(a, 5_000), share(b, 5_000)])
+            .unwrap();
+        assert_eq!(shared.right_of(&b).unwrap().owed, 5_000);
+        assert_eq!(shared.right_of(&b).unwrap().drawn, 45_000);
+    }
+}
@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+
+use crate::{utils::BPS_DENOMINATOR, CustomError};
+
+/// Largest number of sources in an agency's supply mix
+pub const SOURCE_MIX_MAX: usize = 8;
+
+/// Kind of a water supply source.
+///
+/// # Variants
+/// * `Reservoir` - Surface water impounded behind a dam
+/// * `Aquifer` - Groundwater pumped from wells
+/// * `Desalination` - Seawater or brackish water treated by a desalination plant
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SourceKind {
+    /// Surface water impounded behind a dam
+    Reservoir,
+
+    /// Groundwater pumped from wells
+    Aquifer,
+
+    /// Seawater or brackish water treated by a desalination plant
+    Desalination,
+}
+
+/// A source supplying part of an agency's water.
+///
+/// # Fields
+/// * `kind` - Kind of the source
+/// * `unit_cost` - Cost of one billed unit of volume from the source, with three implied decimals
+/// * `share_bps` - Share of the supply drawn from the source, in basis points
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SupplySource {
+    pub kind: SourceKind,
+    pub unit_cost: u64,
+    pub share_bps: u16,
+}
+
+/// Mix of sources an agency currently supplies its water from.
+///
+/// Utilities topping up their reservoirs with groundwater or desalinated water pay far
+/// more for those volumes. The agency publishes the share each source contributes and
+/// its unit cost, and every `use_water` reading passing the mix is billed the blended
+/// marginal cost of the volume on top of the tariff.
+///
+/// # Fields
+/// * `agency` - Agency the mix belongs to
+/// * `sources` - Sources of the supply, with shares totalling 10,000 bps
+/// * `published_slot` - Slot at which the mix was last published
+///
+/// # Example
+/// ```ignore
+/// let source_mix = SourceMix {
+///     agency: agency_pubkey,
+///     sources: vec![
+///         SupplySource { kind: SourceKind::Reservoir, unit_cost: 0, share_bps: 8_000 },
+///         SupplySource { kind: SourceKind::Desalination, unit_cost: 1_500, share_bps: 2_000 },
+///     ],
+///     published_slot: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct SourceMix {
+    /// Agency the mix belongs to.
+    pub agency: Pubkey,
+
+    /// Sources of the agency's supply, with their shares and unit costs.
+    #[max_len(SOURCE_MIX_MAX)]
+    pub sources: Vec<SupplySource>,
+
+    /// Slot at which the mix was last published.
+    pub published_slot: u64,
+}
+
+impl SourceMix {
+    /// Checks that every source has a share and that the shares cover the whole supply
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidSourceMix` - If the mix is malformed
+    pub fn validate_sources(sources: &[SupplySource]) -> Result<()> {
+        require!(
+            !sources.is_empty() && sources.len() <= SOURCE_MIX_MAX,
+            CustomError::InvalidSourceMix
+        );
+        require!(
+            sources.iter().all(|source| source.share_bps > 0)
+                && sources
+                    .iter()
+                    .map(|source| source.share_bps as u64)
+                    .sum::<u64>()
+                    == BPS_DENOMINATOR,
+            CustomError::InvalidSourceMix
+        );
+        Ok(())
+    }
+
+    /// Returns the cost of one billed unit of volume drawn from the mix
+    ///
+    /// Each source's unit cost is weighted by its share of the supply, rounding down.
+    pub fn blended_unit_cost(&self) -> u64 {
+        let weighted: u128 = self
+            .sources
+            .iter()
+            .map(|source| source.unit_cost as u128 * source.share_bps as u128)
+            .sum();
+        (weighted / BPS_DENOMINATOR as u128) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(kind: SourceKind, unit_cost: u64, share_bps: u16) -> SupplySource {
+        SupplySource {
+            kind,
+            unit_cost,
+            share_bps,
+        }
+    }
+
+    #[test]
+    fn test_validate_sources() {
+        assert!(SourceMix::validate_sources(&[
+            source(SourceKind::Reservoir, 0, 7_000),
+            source(SourceKind::Aquifer, 400, 3_000),
+        ])
+        .is_ok());
+        // Shares must cover the whole supply, each with a share
+        assert!(SourceMix::validate_sources(&[source(SourceKind::Reservoir, 0, 9_000)]).is_err());
+        assert!(SourceMix::validate_sources(&[
+            source(SourceKind::Reservoir, 0, 10_000),
+            source(SourceKind::Desalination, 1_500, 0),
+        ])
+        .is_err());
+        assert!(SourceMix::validate_sources(&[]).is_err());
+    }
+
+    #[test]
+    fn test_blended_unit_cost() {
+        let source_mix = SourceMix {
+            agency: Pubkey::default(),
+            sources: vec![
+                source(SourceKind::Reservoir, 100, 7_000),
+                source(SourceKind::Aquifer, 400, 2_000),
+                source(SourceKind::Desalination, 1_500, 1_000),
+            ],
+            published_slot: 0,
+        };
+        // 0.7 * 100 + 0.2 * 400 + 0.1 * 1500
+        assert_eq!(source_mix.blended_unit_cost(), 300);
+    }
+}
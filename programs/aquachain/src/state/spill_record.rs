@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+/// Running record of the water a reservoir spilled over its capacity.
+///
+/// Inflows that would fill a reservoir past its capacity are recorded up to the capacity,
+/// and the excess is tallied here for dam safety reporting rather than rejecting the inflow.
+///
+/// # Fields
+/// * `reservoir_key` - Public key of the reservoir that spilled
+/// * `spill_count` - Number of inflows that overflowed the reservoir
+/// * `total_spilled` - Total volume spilled over the reservoir's capacity
+/// * `last_spilled` - Volume spilled by the most recent overflow
+/// * `last_spill_slot` - Slot of the most recent overflow
+#[account]
+#[derive(InitSpace)]
+pub struct SpillRecord {
+    /// The public key of the reservoir that spilled.
+    pub reservoir_key: Pubkey,
+
+    /// Number of inflows that overflowed the reservoir.
+    pub spill_count: u64,
+
+    /// Total volume spilled over the reservoir's capacity, in level units.
+    pub total_spilled: u64,
+
+    /// Volume spilled by the most recent overflow, in level units.
+    pub last_spilled: u64,
+
+    /// Slot of the most recent overflow.
+    pub last_spill_slot: u64,
+}
+
+impl SpillRecord {
+    /// Tallies a spill of `volume` at the given slot
+    pub fn record(&mut self, volume: u64, slot: u64) {
+        self.spill_count = self.spill_count.saturating_add(1);
+        self.total_spilled = self.total_spilled.saturating_add(volume);
+        self.last_spilled = volume;
+        self.last_spill_slot = slot;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut record = SpillRecord {
+            reservoir_key: Pubkey::default(),
+            spill_count: 0,
+            total_spilled: 0,
+            last_spilled: 0,
+            last_spill_slot: 0,
+        };
+        record.record(500, 10);
+        record.record(200, 20);
+        assert_eq!((record.spill_count, record.total_spilled), (2, 700));
+        assert_eq!((record.last_spilled, record.last_spill_slot), (200, 20));
+    }
+}
@@ -1,4 +1,6 @@
+use crate::utils::bps_of;
 use anchor_lang::prelude::*;
+use aquachain_core::{lifeline_allocation, ChargeBounds};
 
 /// Represents different types of water tariff structures that can be applied to billing.
 ///
@@ -6,10 +8,13 @@ use anchor_lang::prelude::*;
 /// * `UniformIBT` - Uniform Increasing Block Tariff where rates increase with consumption
 /// * `SeasonalIBT` - Seasonal Increasing Block Tariff that varies by season with increasing rates
 /// * `SeasonalDBT` - Seasonal Decreasing Block Tariff that varies by season with decreasing rates
-#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(
+    InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, Eq, PartialEq,
+)]
 pub enum TariffType {
     /// Standard tariff structure where rates increase with consumption blocks,
     /// applied uniformly throughout the year
+    #[default]
     UniformIBT,
 
     /// Seasonal tariff structure where rates increase with consumption blocks
@@ -21,6 +26,16 @@ pub enum TariffType {
     SeasonalDBT,
 }
 
+impl From<TariffType> for aquachain_core::TariffType {
+    fn from(tariff_type: TariffType) -> Self {
+        match tariff_type {
+            TariffType::UniformIBT => aquachain_core::TariffType::UniformIBT,
+            TariffType::SeasonalIBT => aquachain_core::TariffType::SeasonalIBT,
+            TariffType::SeasonalDBT => aquachain_core::TariffType::SeasonalDBT,
+        }
+    }
+}
+
 /// Represents a water utility tariff account containing rate information and configuration.
 ///
 /// This account stores the basic rate structure for both water usage and waste treatment,
@@ -31,6 +46,20 @@ pub enum TariffType {
 /// * `waste_rate` - Base rate charged per unit of waste treatment
 /// * `tariff_type` - The type of tariff structure being applied
 /// * `tariff_key` - Public key associated with this tariff configuration
+/// * `currency_code` - ISO 4217 code of the local currency the rates are posted in
+/// * `activated_slot` - Slot from which the current rates and tariff type apply
+/// * `grace_period_slots` - Minimum number of slots debt must be overdue before punitive action
+/// * `billing_period_slots` - Length of the billing period after which capacity is refreshed
+/// * `capacity_rollover_bps` - Share of unused capacity carried into the next period, in bps
+/// * `block_threshold` - Volume per billing period charged at the water rate
+/// * `per_capita_allowance` - Lifeline volume per household member per billing period
+/// * `assigned_consumer_count` - Number of consumers currently assigned to this tariff
+/// * `conservation_baseline_bps` - Share of the block threshold a conserving period stays below
+/// * `conservation_periods` - Consecutive conserving periods that earn a certificate, 0 if none
+/// * `bulk_rate` - Rate charged for water delivered by tanker, 0 if bulk delivery is not offered
+/// * `forecast_pricing` - Whether seasonal usage is priced on the reservoir's level forecast
+/// * `max_multiplier` - Highest scarcity multiplier charged, 0 if uncapped
+/// * `min_charge` - Lowest amount a reading is billed, 0 if there is no floor
 ///
 /// # Example
 /// ```ignore
@@ -39,6 +68,20 @@ pub enum TariffType {
 ///     waste_rate: 50,   // Base rate for waste treatment
 ///     tariff_type: TariffType::UniformIBT,
 ///     tariff_key: pubkey,
+///     currency_code: *b"TTD",
+///     activated_slot: 1000,
+///     grace_period_slots: 0,
+///     billing_period_slots: 0,
+///     capacity_rollover_bps: 0,
+///     block_threshold: 0,
+///     per_capita_allowance: 0,
+///     assigned_consumer_count: 0,
+///     conservation_baseline_bps: 0,
+///     conservation_periods: 0,
+///     bulk_rate: 0,
+///     forecast_pricing: false,
+///     max_multiplier: 0,
+///     min_charge: 0,
 /// };
 /// ```
 #[account]
@@ -59,4 +102,230 @@ pub struct Tariff {
     /// The public key associated with this tariff account,
     /// used for identification and authorization.
     pub tariff_key: Pubkey,
+
+    /// ISO 4217 code of the local currency the rates are denominated in.
+    /// All zeros when the tariff settles directly in its own tokens.
+    pub currency_code: [u8; 3],
+
+    /// Slot from which the current rates and tariff type apply.
+    /// Earlier versions are kept in the tariff's TariffHistory account.
+    pub activated_slot: u64,
+
+    /// Minimum number of slots a debt must remain unpaid before late fees,
+    /// guarantor claims or suspension can be applied to consumers on this tariff.
+    pub grace_period_slots: u64,
+
+    /// Length of the billing period in slots, after which consumers' WATC is refreshed
+    /// up to their contracted capacity. Zero disables capacity refreshes.
+    pub billing_period_slots: u64,
+
+    /// Share of a consumer's unused WATC, in basis points, carried into the next billing
+    /// period on top of the contracted capacity. Zero means unused capacity expires.
+    pub capacity_rollover_bps: u16,
+
+    /// Volume a consumer can use each billing period at the water rate before usage is
+    /// charged at the block rate. Zero uses each consumer's contracted capacity instead.
+    pub block_threshold: u64,
+
+    /// Lifeline volume allowed per household member each billing period at the water rate.
+    /// When set, it replaces the block threshold for consumers with a known household size.
+    pub per_capita_allowance: u64,
+
+    /// The number of consumers assigned to this tariff.
+    /// The tariff can only be closed once this reaches zero.
+    pub assigned_consumer_count: u64,
+
+    /// Share of a consumer's block threshold, in basis points, its period usage must stay
+    /// below for the period to count towards a conservation certificate.
+    pub conservation_baseline_bps: u16,
+
+    /// Number of consecutive periods under the baseline that earn a conservation
+    /// certificate. Zero disables certificates.
+    pub conservation_periods: u16,
+
+    /// Rate charged per unit of water delivered in bulk by tanker, outside the network's
+    /// block structure. Zero means bulk delivery is not offered under this tariff.
+    pub bulk_rate: u64,
+
+    /// Whether seasonal usage is priced on the reservoir's posted level forecast for the
+    /// current period rather than its current level.
+    pub forecast_pricing: bool,
+
+    /// Highest multiplier applied to the block rate, with three implied decimals, whatever
+    /// the reservoir level. Zero leaves the multiplier uncapped. Set by a regulator.
+    pub max_multiplier: u64,
+
+    /// Lowest amount a reading is billed, with three implied decimals like the rates.
+    /// Zero means there is no floor. Set by a regulator.
+    pub min_charge: u64,
+}
+
+impl Tariff {
+    /// Returns true if a debt outstanding since `debt_since_slot` is past its grace period
+    pub fn grace_period_elapsed(&self, debt_since_slot: u64, slot: u64) -> bool {
+        slot >= debt_since_slot.saturating_add(self.grace_period_slots)
+    }
+
+    /// Returns the WATC balance a consumer starts a new billing period with
+    ///
+    /// # Arguments
+    /// * `contracted_capacity` - The consumer's contracted capacity for a period
+    /// * `unused` - WATC left unused at the end of the previous period
+    pub fn capacity_refresh_target(&self, contracted_capacity: u64, unused: u64) -> u64 {
+        contracted_capacity.saturating_add(bps_of(unused, self.capacity_rollover_bps))
+    }
+
+    /// Returns the volume a consumer can use each billing period at the water rate
+    ///
+    /// The per-capita lifeline allocation applies first, then the tariff's flat block
+    /// threshold, and finally the consumer's contracted capacity.
+    ///
+    /// # Arguments
+    /// * `contracted_capacity` - The consumer's contracted capacity, used without a threshold
+    /// * `household_size` - Number of people in the consumer's household, 0 if unknown
+    pub fn block_threshold_for(&self, contracted_capacity: u64, household_size: u16) -> u64 {
+        if self.per_capita_allowance > 0 && household_size > 0 {
+            lifeline_allocation(self.per_capita_allowance, household_size)
+        } else if self.block_threshold > 0 {
+            self.block_threshold
+        } else {
+            contracted_capacity
+        }
+    }
+
+    /// Returns true if a period's usage stayed below the tariff's conservation baseline
+    ///
+    /// Once the consumer has a usage baseline, the period must also not exceed it, so usage
+    /// creeping up under the tariff's baseline does not count as conserving.
+    ///
+    /// # Arguments
+    /// * `period_usage` - Volume the consumer used over the period
+    /// * `contracted_capacity` - The consumer's contracted capacity for a period
+    /// * `household_size` - Number of people in the consumer's household, 0 if unknown
+    /// * `usage_baseline` - The consumer's average usage over its previous periods, if any
+    pub fn is_conserving(
+        &self,
+        period_usage: u64,
+        contracted_capacity: u64,
+        household_size: u16,
+        usage_baseline: Option<u64>,
+    ) -> bool {
+        let threshold = self.block_threshold_for(contracted_capacity, household_size);
+        period_usage < bps_of(threshold, self.conservation_baseline_bps)
+            && period_usage <= usage_baseline.unwrap_or(u64::MAX)
+    }
+
+    /// Records a consumer being assigned to this tariff
+    pub fn assign_consumer(&mut self) {
+        self.assigned_consumer_count = self.assigned_consumer_count.saturating_add(1);
+    }
+
+    /// Records a consumer being moved off this tariff
+    ///
+    /// Saturates at zero so consumers assigned before the count was tracked can still move.
+    pub fn release_consumer(&mut self) {
+        self.assigned_consumer_count = self.assigned_consumer_count.saturating_sub(1);
+    }
+
+    /// Returns the regulated bounds of the charge of a reading under this tariff
+    pub fn charge_bounds(&self) -> ChargeBounds {
+        ChargeBounds {
+            max_multiplier: self.max_multiplier,
+            min_charge: self.min_charge,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tariff() -> Tariff {
+        Tariff {
+            water_rate: 500,
+            waste_rate: 200,
+            tariff_type: TariffType::UniformIBT,
+            tariff_key: Pubkey::default(),
+            currency_code: [0; 3],
+            activated_slot: 0,
+            grace_period_slots: 100,
+            billing_period_slots: 0,
+            capacity_rollover_bps: 0,
+            block_threshold: 0,
+            per_capita_allowance: 0,
+            assigned_consumer_count: 0,
+            conservation_baseline_bps: 0,
+            conservation_periods: 0,
+            bulk_rate: 0,
+            forecast_pricing: false,
+            max_multiplier: 0,
+            min_charge: 0,
+        }
+    }
+
+    #[test]
+    fn test_assigned_consumer_count() {
+        let mut tariff = tariff();
+        tariff.assign_consumer();
+        tariff.assign_consumer();
+        tariff.release_consumer();
+        assert_eq!(tariff.assigned_consumer_count, 1);
+        tariff.release_consumer();
+        tariff.release_consumer();
+        assert_eq!(tariff.assigned_consumer_count, 0);
+    }
+
+    #[test]
+    fn test_grace_period_elapsed() {
+        let tariff = tariff();
+        assert!(!tariff.grace_period_elapsed(1000, 1099));
+        assert!(tariff.grace_period_elapsed(1000, 1100));
+        assert!(tariff.grace_period_elapsed(u64::MAX, u64::MAX));
+    }
+
+    #[test]
+    fn test_capacity_refresh_target() {
+        let mut tariff = tariff();
+        // Use it or lose it: unused capacity does not stack
+        assert_eq!(tariff.capacity_refresh_target(100000, 40000), 100000);
+
+        tariff.capacity_rollover_bps = 5_000;
+        assert_eq!(tariff.capacity_refresh_target(100000, 40000), 120000);
+
+        tariff.capacity_rollover_bps = 10_000;
+        assert_eq!(tariff.capacity_refresh_target(100000, 40000), 140000);
+        assert_eq!(tariff.capacity_refresh_target(u64::MAX, 40000), u64::MAX);
+    }
+
+    #[test]
+    fn test_block_threshold_for() {
+        let mut tariff = tariff();
+        // Without a threshold, the contracted capacity is billed at the water rate
+        assert_eq!(tariff.block_threshold_for(100000, 4), 100000);
+
+        tariff.block_threshold = 50000;
+        assert_eq!(tariff.block_threshold_for(100000, 4), 50000);
+
+        // The lifeline allocation applies once the household size is known
+        tariff.per_capita_allowance = 15000;
+        assert_eq!(tariff.block_threshold_for(100000, 4), 60000);
+        assert_eq!(tariff.block_threshold_for(100000, 0), 50000);
+    }
+
+    #[test]
+    fn test_is_conserving() {
+        let mut tariff = tariff();
+        tariff.block_threshold = 50000;
+        tariff.conservation_baseline_bps = 8_000;
+        assert!(tariff.is_conserving(39999, 100000, 0, None));
+        assert!(!tariff.is_conserving(40000, 100000, 0, None));
+
+        // Usage above the consumer's own baseline does not count
+        assert!(tariff.is_conserving(30000, 100000, 0, Some(30000)));
+        assert!(!tariff.is_conserving(30001, 100000, 0, Some(30000)));
+
+        // A zero baseline never counts as conserving
+        tariff.conservation_baseline_bps = 0;
+        assert!(!tariff.is_conserving(0, 100000, 0, None));
+    }
 }
@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+
+use super::{Tariff, TariffType};
+
+/// Number of superseded tariff versions kept before the oldest is overwritten
+pub const TARIFF_HISTORY_LEN: usize = 16;
+
+/// A superseded version of a tariff, kept so past bills can be verified.
+///
+/// # Fields
+/// * `water_rate` - Water rate that applied during this version
+/// * `waste_rate` - Waste rate that applied during this version
+/// * `tariff_type` - Tariff structure that applied during this version
+/// * `activated_slot` - First slot at which this version applied
+/// * `superseded_slot` - Slot at which this version was replaced
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TariffVersion {
+    pub water_rate: u64,
+    pub waste_rate: u64,
+    pub tariff_type: TariffType,
+    pub activated_slot: u64,
+    pub superseded_slot: u64,
+}
+
+impl TariffVersion {
+    /// Returns true if this version was in force at the given slot
+    pub fn applies_at(&self, slot: u64) -> bool {
+        self.activated_slot <= slot && slot < self.superseded_slot
+    }
+}
+
+/// Append-only record of a tariff's previous rates.
+///
+/// Every time a tariff's rates or type change, the version being replaced is written
+/// to this ring buffer. Once full, the oldest version is overwritten.
+///
+/// # Fields
+/// * `tariff_key` - Public key of the tariff this history belongs to
+/// * `head` - Index the next superseded version will be written to
+/// * `len` - Number of versions currently stored
+/// * `versions` - Ring buffer of superseded versions
+#[account]
+#[derive(InitSpace)]
+pub struct TariffHistory {
+    /// The public key of the tariff this history belongs to.
+    pub tariff_key: Pubkey,
+
+    /// Index in `versions` the next superseded version will be written to.
+    pub head: u8,
+
+    /// Number of versions stored, up to `TARIFF_HISTORY_LEN`.
+    pub len: u8,
+
+    /// Superseded versions, oldest overwritten first once the buffer is full.
+    pub versions: [TariffVersion; TARIFF_HISTORY_LEN],
+}
+
+impl TariffHistory {
+    /// Records the current version of a tariff as superseded at the given slot
+    pub fn record(&mut self, tariff: &Tariff, superseded_slot: u64) {
+        self.versions[self.head as usize] = TariffVersion {
+            water_rate: tariff.water_rate,
+            waste_rate: tariff.waste_rate,
+            tariff_type: tariff.tariff_type,
+            activated_slot: tariff.activated_slot,
+            superseded_slot,
+        };
+        self.head = ((self.head as usize + 1) % TARIFF_HISTORY_LEN) as u8;
+        self.len = (self.len as usize + 1).min(TARIFF_HISTORY_LEN) as u8;
+    }
+
+    /// Returns the superseded version that was in force at the given slot, if still stored
+    pub fn version_at(&self, slot: u64) -> Option<&TariffVersion> {
+        self.versions[..self.len as usize]
+            .iter()
+            .find(|version| version.applies_at(slot))
+    }
+
+    /// Returns the version of `tariff` in force at the given slot, including the current one
+    pub fn version_in_force(&self, tariff: &Tariff, slot: u64) -> Option<TariffVersion> {
+        if slot >= tariff.activated_slot {
+            return Some(TariffVersion {
+                water_rate: tariff.water_rate,
+                waste_rate: tariff.waste_rate,
+                tariff_type: tariff.tariff_type,
+                activated_slot: tariff.activated_slot,
+                superseded_slot: u64::MAX,
+            });
+        }
+        self.version_at(slot).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tariff(water_rate: u64, activated_slot: u64) -> Tariff {
+        Tariff {
+            water_rate,
+            waste_rate: 200,
+            tariff_type: TariffType::UniformIBT,
+            tariff_key: Pubkey::default(),
+            currency_code: [0; 3],
+            activated_slot,
+            grace_period_slots: 0,
+            billing_period_slots: 0,
+            capacity_rollover_bps: 0,
+            block_threshold: 0,
+            per_capita_allowance: 0,
+            assigned_consumer_count: 0,
+            conservation_baseline_bps: 0,
+            conservation_periods: 0,
+            bulk_rate: 0,
+            forecast_pricing: false,
+            max_multiplier: 0,
+            min_charge: 0,
+        }
+    }
+
+    fn history() -> TariffHistory {
+        TariffHistory {
+            tariff_key: Pubkey::default(),
+            head: 0,
+            len: 0,
+            versions: [TariffVersion {
+                water_rate: 0,
+                waste_rate: 0,
+                tariff_type: TariffType::UniformIBT,
+                activated_slot: 0,
+                superseded_slot: 0,
+            }; TARIFF_HISTORY_LEN],
+        }
+    }
+
+    #[test]
+    fn test_version_lookup() {
+        let mut history = history();
+        history.record(&tariff(500, 10), 20);
+        history.record(&tariff(600, 20), 30);
+
+        assert_eq!(history.len, 2);
+        assert_eq!(history.version_at(15).unwrap().water_rate, 500);
+        assert_eq!(history.version_at(20).unwrap().water_rate, 600);
+        assert!(history.version_at(5).is_none());
+        assert!(history.version_at(30).is_none()); // current tariff applies
+    }
+
+    #[test]
+    fn test_ring_buffer_overwrites_oldest() {
+        let mut history = history();
+        for i in 0..(TARIFF_HISTORY_LEN as u64 + 2) {
+            history.record(&tariff(100 + i, i * 10), (i + 1) * 10);
+        }
+
+        assert_eq!(history.len as usize, TARIFF_HISTORY_LEN);
+        assert_eq!(history.head, 2);
+        assert!(history.version_at(5).is_none());
+        assert!(history.version_at(15).is_none());
+        assert_eq!(history.version_at(25).unwrap().water_rate, 102);
+    }
+
+    #[test]
+    fn test_version_in_force_falls_back_to_current() {
+        let mut history = history();
+        history.record(&tariff(500, 10), 20);
+        let current = tariff(600, 20);
+
+        assert_eq!(
+            history.version_in_force(&current, 15).unwrap().water_rate,
+            500
+        );
+        assert_eq!(
+            history.version_in_force(&current, 40).unwrap().water_rate,
+            600
+        );
+        assert!(history.version_in_force(&current, 5).is_none());
+    }
+}
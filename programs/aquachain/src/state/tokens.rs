@@ -1,11 +1,71 @@
 use anchor_lang::prelude::*;
 
+use crate::{utils::FixedPoint, CustomError};
+
+/// Declares how raw on-chain amounts map onto physical volumes and currency.
+///
+/// Meter readings, reservoir levels, contracted capacities and WATC balances are
+/// raw volume amounts, while WTK and WST balances are base units of the billing
+/// currency. Every cost and volume conversion goes through this configuration,
+/// so agencies in different regions can bill in units that suit them.
+///
+/// # Fields
+/// * `volume_scale` - Number of raw volume units that make up one billed unit of volume
+/// * `currency_decimals` - Number of decimals used by the WTK, WST and WATC mints
+///
+/// # Example
+/// ```ignore
+/// let units = UnitConfig {
+///     volume_scale: 1000,     // meters report litres, tariffs are priced per m³
+///     currency_decimals: 6,   // e.g. a USDC-like billing currency
+/// };
+/// ```
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UnitConfig {
+    /// Number of raw volume units per billed unit of volume.
+    /// A scale of 1000 means a reading of 1000 is billed as 1.000 units.
+    pub volume_scale: u64,
+
+    /// Number of decimals used by the token mints.
+    /// Costs computed by the tariffs are converted into base units using this value.
+    pub currency_decimals: u8,
+}
+
+impl UnitConfig {
+    /// Largest number of currency decimals whose scale still fits in a u64
+    pub const MAX_CURRENCY_DECIMALS: u8 = aquachain_core::MAX_CURRENCY_DECIMALS;
+
+    /// Checks that the configuration can be used for conversions
+    pub fn is_valid(&self) -> bool {
+        self.volume_scale > 0 && self.currency_decimals <= Self::MAX_CURRENCY_DECIMALS
+    }
+
+    /// Converts a raw volume amount into a fixed-point billed volume
+    ///
+    /// # Errors
+    /// * `CustomError::MathOverflow` - If the scaled volume does not fit in a u64
+    pub fn to_volume(&self, raw: u64) -> Result<FixedPoint> {
+        aquachain_core::to_volume(raw, self.volume_scale).ok_or(error!(CustomError::MathOverflow))
+    }
+
+    /// Converts a fixed-point cost into base units of the billing currency
+    ///
+    /// # Errors
+    /// * `CustomError::MathOverflow` - If the converted cost does not fit in a u64
+    pub fn to_currency(&self, cost: FixedPoint) -> Result<u64> {
+        aquachain_core::to_currency(cost, self.currency_decimals)
+            .ok_or(error!(CustomError::MathOverflow))
+    }
+}
+
 /// Represents the core token addresses used in the Aquachain system.
-/// 
+///
 /// This account stores the public keys for the three main tokens:
 /// - Water Token (WTK)
 /// - Waste Token (WST)
 /// - Water Capacity Token (WATC)
+///
+/// along with the unit configuration shared by all of them.
 #[account]
 #[derive(InitSpace)]
 pub struct Tokens {
@@ -19,5 +79,61 @@ pub struct Tokens {
 
     /// The mint address for the Water Capacity Token (WATC),
     /// used to represent the consumer's remaining contracted water capacity
-    pub watc: Pubkey
+    pub watc: Pubkey,
+
+    /// Volume and currency units used when converting usage into charges
+    pub units: UnitConfig,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_config_identity_scale() {
+        let units = UnitConfig {
+            volume_scale: 1000,
+            currency_decimals: 3,
+        };
+        assert_eq!(units.to_volume(2500).unwrap(), FixedPoint::from(2500));
+        assert_eq!(units.to_currency(FixedPoint::from(2500)).unwrap(), 2500);
+    }
+
+    #[test]
+    fn test_unit_config_litres_to_cubic_meters() {
+        let units = UnitConfig {
+            volume_scale: 1_000_000, // readings in millilitres, billed per m³
+            currency_decimals: 6,
+        };
+        // 2,500,000 mL = 2.500 m³
+        assert_eq!(units.to_volume(2_500_000).unwrap(), FixedPoint::from(2500));
+        // 1.250 currency units = 1,250,000 base units
+        assert_eq!(
+            units.to_currency(FixedPoint::from(1250)).unwrap(),
+            1_250_000
+        );
+    }
+
+    #[test]
+    fn test_unit_config_validation() {
+        let zero_scale = UnitConfig {
+            volume_scale: 0,
+            currency_decimals: 9,
+        };
+        let too_many_decimals = UnitConfig {
+            volume_scale: 1000,
+            currency_decimals: 19,
+        };
+        assert!(!zero_scale.is_valid());
+        assert!(!too_many_decimals.is_valid());
+    }
+
+    #[test]
+    fn test_unit_config_currency_overflow() {
+        let units = UnitConfig {
+            volume_scale: 1000,
+            currency_decimals: 18,
+        };
+        assert!(units.to_currency(FixedPoint::from(u64::MAX)).is_err());
+    }
 }
@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+
+use crate::{utils::bps_of, CustomError};
+
+/// Side of an order on the WATC marketplace.
+///
+/// # Variants
+/// * `Ask` - Selling unused WATC, escrowed in the order
+/// * `Bid` - Buying WATC, with the payment escrowed in the order
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OrderSide {
+    /// Selling unused WATC, escrowed in the order
+    Ask,
+    /// Buying WATC, with the payment escrowed in the order
+    Bid,
+}
+
+/// Peer-to-peer marketplace where an agency's consumers trade unused capacity.
+///
+/// Consumers with capacity to spare in a period sell it to consumers who need more, for a
+/// stablecoin. The agency takes a fee on every trade and caps how much WATC a consumer can
+/// buy per period, so capacity can't be hoarded.
+///
+/// # Fields
+/// * `agency` - Agency operating the marketplace
+/// * `settlement_mint` - Stablecoin WATC is traded for
+/// * `fee_bps` - Share of each trade's proceeds paid to the agency, in basis points
+/// * `trade_cap` - Raw volume of WATC a consumer may buy per capacity period, 0 if uncapped
+/// * `order_count` - Number of orders placed, used to derive order addresses
+///
+/// # Example
+/// ```ignore
+/// let market = WatcMarket {
+///     agency: agency_pubkey,
+///     settlement_mint: usdc_mint,
+///     fee_bps: 100,        // 1% of proceeds
+///     trade_cap: 50_000,   // 50.000 units per period
+///     order_count: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct WatcMarket {
+    /// Agency operating the marketplace.
+    pub agency: Pubkey,
+
+    /// Stablecoin WATC is traded for.
+    pub settlement_mint: Pubkey,
+
+    /// Share of each trade's proceeds paid to the agency, in basis points.
+    pub fee_bps: u16,
+
+    /// Raw volume of WATC a consumer may buy per capacity period, 0 if uncapped.
+    pub trade_cap: u64,
+
+    /// Number of orders placed, used to derive order addresses.
+    pub order_count: u64,
+}
+
+impl WatcMarket {
+    /// Returns the agency's fee on the proceeds of a trade
+    pub fn fee_for(&self, proceeds: u64) -> u64 {
+        bps_of(proceeds, self.fee_bps)
+    }
+
+    /// Returns true if a consumer that bought `bought` this period may buy `amount` more
+    pub fn within_cap(&self, bought: u64, amount: u64) -> bool {
+        self.trade_cap == 0 || bought.saturating_add(amount) <= self.trade_cap
+    }
+}
+
+/// An order resting on the WATC marketplace.
+///
+/// Orders only trade within the capacity period they were placed in, since unused
+/// capacity is refreshed at the period's end.
+///
+/// # Fields
+/// * `agency` - Agency operating the marketplace
+/// * `order_id` - Sequence number of the order in its marketplace
+/// * `consumer` - Consumer that placed the order
+/// * `side` - Whether the order sells or buys WATC
+/// * `price` - Settlement base units per raw unit of WATC
+/// * `remaining` - Raw volume of WATC left to trade
+/// * `period_start` - Capacity period of the consumer when the order was placed
+///
+/// # Example
+/// ```ignore
+/// let order = WatcOrder {
+///     agency: agency_pubkey,
+///     order_id: 0,
+///     consumer: consumer_pubkey,
+///     side: OrderSide::Ask,
+///     price: 2,             // 2.000000 USDC per 1.000 units
+///     remaining: 20_000,    // 20.000 units
+///     period_start: 1_000,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct WatcOrder {
+    /// Agency operating the marketplace.
+    pub agency: Pubkey,
+
+    /// Sequence number of the order in its marketplace.
+    pub order_id: u64,
+
+    /// Consumer that placed the order.
+    pub consumer: Pubkey,
+
+    /// Whether the order sells or buys WATC.
+    pub side: OrderSide,
+
+    /// Settlement base units per raw unit of WATC.
+    pub price: u64,
+
+    /// Raw volume of WATC left to trade.
+    pub remaining: u64,
+
+    /// Capacity period of the consumer when the order was placed.
+    pub period_start: u64,
+}
+
+impl WatcOrder {
+    /// Computes the value of an amount of WATC at the order's price
+    ///
+    /// # Errors
+    /// * `CustomError::MathOverflow` - If the value does not fit in a u64
+    pub fn value_of(&self, amount: u64) -> Result<u64> {
+        amount
+            .checked_mul(self.price)
+            .ok_or(error!(CustomError::MathOverflow))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(trade_cap: u64) -> WatcMarket {
+        WatcMarket {
+            agency: Pubkey::default(),
+            settlement_mint: Pubkey::default(),
+            fee_bps: 100,
+            trade_cap,
+            order_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_fee_for_proceeds() {
+        assert_eq!(market(0).fee_for(40_000), 400);
+    }
+
+    #[test]
+    fn test_trade_cap() {
+        let market = market(50_000);
+        assert!(market.within_cap(30_000, 20_000));
+        assert!(!market.within_cap(30_000, 20_001));
+    }
+
+    #[test]
+    fn test_uncapped_market() {
+        assert!(market(0).within_cap(u64::MAX, 1));
+    }
+
+    #[test]
+    fn test_order_value_overflow() {
+        let order = WatcOrder {
+            agency: Pubkey::default(),
+            order_id: 0,
+            consumer: Pubkey::default(),
+            side: OrderSide::Ask,
+            price: 2,
+            remaining: 20_000,
+            period_start: 0,
+        };
+        assert_eq!(order.value_of(20_000).unwrap(), 40_000);
+        assert!(order.value_of(u64::MAX).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+
+/// Lifecycle of a weather derivative.
+///
+/// # Variants
+/// * `Offered` - Payout escrowed by the counterparty, awaiting the agency's premium
+/// * `Active` - Premium paid, rainfall is being tracked over the window
+/// * `Settled` - Escrow released to the agency or back to the counterparty
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DerivativeStatus {
+    /// Payout escrowed by the counterparty, awaiting the agency's premium
+    Offered,
+    /// Premium paid, rainfall is being tracked over the window
+    Active,
+    /// Escrow released to the agency or back to the counterparty
+    Settled,
+}
+
+/// Parametric rainfall cover bought by an agency's treasury.
+///
+/// Conservation pricing ties an agency's revenue to the weather: a dry season cuts
+/// withdrawals. A counterparty escrows a payout, and the agency pays a premium for it.
+/// A rainfall oracle reports observations over the window, and if the cumulative rainfall
+/// ends below the strike, the escrow is paid to the agency's treasury. Otherwise it returns
+/// to the counterparty.
+///
+/// # Fields
+/// * `agency` - Agency buying the cover
+/// * `counterparty` - Writer of the cover, who escrows the payout and receives the premium
+/// * `derivative_key` - Unique identifier for the derivative
+/// * `settlement_mint` - Stablecoin the payout and premium are paid in
+/// * `oracle_authority` - Key allowed to report rainfall observations
+/// * `strike` - Cumulative rainfall below which the cover pays out, in tenths of a millimetre
+/// * `window_start` - Unix timestamp from which rainfall counts
+/// * `window_end` - Unix timestamp at which rainfall stops counting
+/// * `payout` - Amount escrowed by the counterparty, in settlement base units
+/// * `premium` - Amount paid by the agency, in settlement base units
+/// * `cumulative_rainfall` - Rainfall reported over the window, in tenths of a millimetre
+/// * `last_observed_at` - Unix timestamp of the latest observation
+/// * `status` - Lifecycle of the derivative
+///
+/// # Example
+/// ```ignore
+/// let derivative = WeatherDerivative {
+///     agency: agency_pubkey,
+///     counterparty: writer_pubkey,
+///     derivative_key: derivative_pubkey,
+///     settlement_mint: usdc_mint,
+///     oracle_authority: oracle_pubkey,
+///     strike: 3_000,              // 300 mm over the season
+///     window_start: 1_717_200_000,
+///     window_end: 1_725_148_800,
+///     payout: 50_000_000_000,     // 50,000 USDC
+///     premium: 2_500_000_000,     // 2,500 USDC
+///     cumulative_rainfall: 0,
+///     last_observed_at: 0,
+///     status: DerivativeStatus::Offered,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct WeatherDerivative {
+    /// Agency buying the cover.
+    pub agency: Pubkey,
+
+    /// Writer of the cover, who escrows the payout and receives the premium.
+    pub counterparty: Pubkey,
+
+    /// Unique identifier for the derivative.
+    pub derivative_key: Pubkey,
+
+    /// Stablecoin the payout and premium are paid in.
+    pub settlement_mint: Pubkey,
+
+    /// Public key allowed to report rainfall observations.
+    pub oracle_authority: Pubkey,
+
+    /// Cumulative rainfall below which the cover pays out, in tenths of a millimetre.
+    pub strike: u64,
+
+    /// Unix timestamp from which rainfall counts.
+    pub window_start: i64,
+
+    /// Unix timestamp at which rainfall stops counting.
+    pub window_end: i64,
+
+    /// Amount escrowed by the counterparty, in settlement base units.
+    pub payout: u64,
+
+    /// Amount paid by the agency, in settlement base units.
+    pub premium: u64,
+
+    /// Rainfall reported over the window, in tenths of a millimetre.
+    pub cumulative_rainfall: u64,
+
+    /// Unix timestamp of the latest observation.
+    pub last_observed_at: i64,
+
+    /// Lifecycle of the derivative.
+    pub status: DerivativeStatus,
+}
+
+impl WeatherDerivative {
+    /// Returns true if an observation taken at `observed_at` can be counted
+    ///
+    /// Observations must fall within the window and be newer than the previous one, so
+    /// the same rainfall cannot be reported twice.
+    pub fn accepts_observation(&self, observed_at: i64) -> bool {
+        observed_at >= self.window_start
+            && observed_at < self.window_end
+            && observed_at > self.last_observed_at
+    }
+
+    /// Returns true if the escrow is owed to the agency on settlement
+    pub fn is_in_the_money(&self) -> bool {
+        self.status == DerivativeStatus::Active && self.cumulative_rainfall < self.strike
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn derivative(status: DerivativeStatus, cumulative_rainfall: u64) -> WeatherDerivative {
+        WeatherDerivative {
+            agency: Pubkey::default(),
+            counterparty: Pubkey::default(),
+            derivative_key: Pubkey::default(),
+            settlement_mint: Pubkey::default(),
+            oracle_authority: Pubkey::default(),
+            strike: 3_000,
+            window_start: 1_000,
+            window_end: 2_000,
+            payout: 50_000,
+            premium: 2_500,
+            cumulative_rainfall,
+            last_observed_at: 0,
+            status,
+        }
+    }
+
+    #[test]
+    fn test_accepts_observation_within_window() {
+        let mut derivative = derivative(DerivativeStatus::Active, 0);
+        assert!(!derivative.accepts_observation(999));
+        assert!(derivative.accepts_observation(1_000));
+        assert!(derivative.accepts_observation(1_999));
+        assert!(!derivative.accepts_observation(2_000));
+
+        derivative.last_observed_at = 1_500;
+        assert!(!derivative.accepts_observation(1_500));
+        assert!(derivative.accepts_observation(1_501));
+    }
+
+    #[test]
+    fn test_in_the_money_below_strike() {
+        assert!(derivative(DerivativeStatus::Active, 2_999).is_in_the_money());
+        assert!(!derivative(DerivativeStatus::Active, 3_000).is_in_the_money());
+    }
+
+    #[test]
+    fn test_unbought_cover_never_pays_out() {
+        assert!(!derivative(DerivativeStatus::Offered, 0).is_in_the_money());
+        assert!(!derivative(DerivativeStatus::Settled, 0).is_in_the_money());
+    }
+}
@@ -0,0 +1,200 @@
+use anchor_lang::prelude::*;
+
+use crate::CustomError;
+
+/// Kind of field operation a work order dispatches.
+///
+/// # Variants
+/// * `Reconnection` - Restores supply to a disconnected consumer
+/// * `Repair` - Repairs a fault on the consumer's connection
+/// * `MeterSwap` - Replaces the consumer's meter
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WorkOrderKind {
+    /// Restores supply to a disconnected consumer
+    Reconnection,
+
+    /// Repairs a fault on the consumer's connection
+    Repair,
+
+    /// Replaces the consumer's meter
+    MeterSwap,
+}
+
+/// Progress of a work order.
+///
+/// # Variants
+/// * `Open` - Dispatched to the assigned worker and not yet carried out
+/// * `Completed` - Carried out by the assigned worker, its fee not yet invoiced
+/// * `Invoiced` - Its fee has been billed to the consumer or waived
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WorkOrderStatus {
+    /// Dispatched to the assigned worker and not yet carried out
+    Open,
+
+    /// Carried out by the assigned worker, its fee not yet invoiced
+    Completed,
+
+    /// Its fee has been billed to the consumer or waived
+    Invoiced,
+}
+
+/// A field operation dispatched to a utility worker.
+///
+/// Work orders bring field operations on chain. A work order may depend on another one
+/// for the same consumer, such as a reconnection following the repair of the fault that
+/// caused the disconnection. Once the order it depends on is completed, the dependent
+/// order's fee is waived when it is invoiced.
+///
+/// # Fields
+/// * `agency` - Agency that dispatched the work order
+/// * `order_key` - Unique identifier for the work order
+/// * `consumer` - Consumer the work is carried out for
+/// * `kind` - Kind of field operation
+/// * `worker` - Worker key assigned to carry out the work
+/// * `status` - Progress of the work order
+/// * `fee` - Amount of WTK billed to the consumer once the work is completed
+/// * `depends_on` - Work order that waives the fee once completed, if any
+/// * `created_slot` - Slot at which the work order was dispatched
+/// * `completed_slot` - Slot at which the work was completed, 0 if still open
+///
+/// # Example
+/// ```ignore
+/// let work_order = WorkOrder {
+///     agency: agency_pubkey,
+///     order_key: order_pubkey,
+///     consumer: consumer_pubkey,
+///     kind: WorkOrderKind::Reconnection,
+///     worker: worker_pubkey,
+///     status: WorkOrderStatus::Open,
+///     fee: 25_000,                      // 25.000 WTK
+///     depends_on: Some(repair_pubkey),
+///     created_slot: 1000,
+///     completed_slot: 0,
+/// };
+/// ```
+#[account]
+#[derive(InitSpace)]
+pub struct WorkOrder {
+    /// Agency that dispatched the work order.
+    pub agency: Pubkey,
+
+    /// Unique identifier for the work order.
+    pub order_key: Pubkey,
+
+    /// Consumer the work is carried out for.
+    pub consumer: Pubkey,
+
+    /// Kind of field operation.
+    pub kind: WorkOrderKind,
+
+    /// Worker key assigned to carry out the work.
+    pub worker: Pubkey,
+
+    /// Progress of the work order.
+    pub status: WorkOrderStatus,
+
+    /// Amount of WTK billed to the consumer once the work is completed.
+    pub fee: u64,
+
+    /// Work order whose completion waives this order's fee, if any.
+    pub depends_on: Option<Pubkey>,
+
+    /// Slot at which the work order was dispatched.
+    pub created_slot: u64,
+
+    /// Slot at which the work was completed, 0 if still open.
+    pub completed_slot: u64,
+}
+
+impl WorkOrder {
+    /// Records the work as carried out by the assigned worker
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the assigned worker
+    /// * `CustomError::InvalidWorkOrderStatus` - If the work order is not open
+    pub fn complete(&mut self, worker: &Pubkey, slot: u64) -> Result<()> {
+        require_keys_eq!(*worker, self.worker, CustomError::Unauthorized);
+        require!(
+            self.status == WorkOrderStatus::Open,
+            CustomError::InvalidWorkOrderStatus
+        );
+        self.status = WorkOrderStatus::Completed;
+        self.completed_slot = slot;
+        Ok(())
+    }
+
+    /// Returns true if the work order it depends on has been completed
+    ///
+    /// # Arguments
+    /// * `prerequisite` - Address and state of the work order passed as the dependency
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidWorkOrder` - If the passed work order is not the dependency
+    pub fn fee_waived(&self, prerequisite: Option<(Pubkey, &WorkOrder)>) -> Result<bool> {
+        match (self.depends_on, prerequisite) {
+            (_, None) => Ok(false),
+            (Some(depends_on), Some((key, order))) => {
+                require_keys_eq!(depends_on, key, CustomError::InvalidWorkOrder);
+                Ok(order.status != WorkOrderStatus::Open)
+            }
+            (None, Some(_)) => err!(CustomError::InvalidWorkOrder),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn work_order(kind: WorkOrderKind, worker: Pubkey) -> WorkOrder {
+        WorkOrder {
+            agency: Pubkey::default(),
+            order_key: Pubkey::default(),
+            consumer: Pubkey::default(),
+            kind,
+            worker,
+            status: WorkOrderStatus::Open,
+            fee: 25_000,
+            depends_on: None,
+            created_slot: 0,
+            completed_slot: 0,
+        }
+    }
+
+    #[test]
+    fn test_complete() {
+        let worker = Pubkey::new_unique();
+        let mut order = work_order(WorkOrderKind::MeterSwap, worker);
+        assert!(order.complete(&Pubkey::new_unique(), 10).is_err());
+
+        order.complete(&worker, 10).unwrap();
+        assert_eq!(order.status, WorkOrderStatus::Completed);
+        assert_eq!(order.completed_slot, 10);
+        assert!(order.complete(&worker, 20).is_err());
+    }
+
+    #[test]
+    fn test_fee_waived_once_dependency_completed() {
+        let worker = Pubkey::new_unique();
+        let repair_key = Pubkey::new_unique();
+        let mut repair = work_order(WorkOrderKind::Repair, worker);
+        let mut reconnection = work_order(WorkOrderKind::Reconnection, worker);
+        reconnection.depends_on = Some(repair_key);
+
+        assert!(!reconnection.fee_waived(None).unwrap());
+        assert!(!reconnection
+            .fee_waived(Some((repair_key, &repair)))
+            .unwrap());
+        assert!(reconnection
+            .fee_waived(Some((Pubkey::new_unique(), &repair)))
+            .is_err());
+
+        repair.complete(&worker, 10).unwrap();
+        assert!(reconnection
+            .fee_waived(Some((repair_key, &repair)))
+            .unwrap());
+
+        // Orders without a dependency never accept one
+        assert!(repair.fee_waived(Some((repair_key, &repair))).is_err());
+    }
+}
@@ -0,0 +1,70 @@
+/// Denominator for values expressed in basis points (100% = 10_000 bps)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Checks that a basis point value does not exceed 100%
+///
+/// # Arguments
+/// * `bps` - The value in basis points
+pub fn is_valid_bps(bps: u16) -> bool {
+    (bps as u64) <= BPS_DENOMINATOR
+}
+
+/// Computes the share of an amount expressed in basis points, rounding down
+///
+/// # Arguments
+/// * `amount` - The amount to take a share of
+/// * `bps` - The share in basis points
+///
+/// # Returns
+/// The share of the amount, never greater than the amount for valid basis points
+pub fn bps_of(amount: u64, bps: u16) -> u64 {
+    ((amount as u128) * (bps as u128) / (BPS_DENOMINATOR as u128)) as u64
+}
+
+/// Splits an amount into a share and its remainder
+///
+/// # Arguments
+/// * `amount` - The amount to split
+/// * `bps` - The share of the first part in basis points
+///
+/// # Returns
+/// A tuple of the share and the remainder, which always add up to the amount
+pub fn split_bps(amount: u64, bps: u16) -> (u64, u64) {
+    let share = bps_of(amount, bps);
+    (share, amount - share)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bps_of() {
+        assert_eq!(bps_of(50_000, 7_000), 35_000);
+        assert_eq!(bps_of(50_000, 10_000), 50_000);
+        assert_eq!(bps_of(50_000, 0), 0);
+    }
+
+    #[test]
+    fn test_bps_of_rounds_down() {
+        assert_eq!(bps_of(3, 5_000), 1);
+    }
+
+    #[test]
+    fn test_split_bps_adds_up() {
+        let (share, rest) = split_bps(12_345, 3_333);
+        assert_eq!(share, 4_114);
+        assert_eq!(share + rest, 12_345);
+    }
+
+    #[test]
+    fn test_bps_of_large_amounts() {
+        assert_eq!(bps_of(u64::MAX, 10_000), u64::MAX);
+    }
+
+    #[test]
+    fn test_is_valid_bps() {
+        assert!(is_valid_bps(10_000));
+        assert!(!is_valid_bps(10_001));
+    }
+}
@@ -0,0 +1,82 @@
+use anchor_lang::{prelude::*, solana_program::program_option::COption};
+use anchor_spl::token::{self, Token, TokenAccount};
+
+use crate::CustomError;
+
+/// Burns tokens from a consumer-held account on the consumer's own signature
+///
+/// Consumer-held token accounts are owned by the consumer account, so burning from them
+/// needs either the consumer's signature or the billing authority's delegation, from
+/// `burn_as_billing_authority`. No other authority can burn consumer-held tokens.
+///
+/// # Arguments
+/// * `token_program` - The SPL token program
+/// * `mint` - Mint of the burned tokens
+/// * `from` - The consumer's token account
+/// * `consumer` - The consumer account owning `from`
+/// * `amount` - Amount to burn
+///
+/// # Errors
+/// * `CustomError::MissingConsumerSignature` - If the consumer did not sign
+pub fn burn_signed_by_consumer<'info>(
+    token_program: &Program<'info, Token>,
+    mint: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    consumer: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    require!(consumer.is_signer, CustomError::MissingConsumerSignature);
+    token::burn(
+        CpiContext::new(
+            token_program.to_account_info(),
+            token::Burn {
+                mint,
+                from,
+                authority: consumer,
+            },
+        ),
+        amount,
+    )
+}
+
+/// Burns tokens from a consumer-held account as the agency's billing authority
+///
+/// The billing authority is a program PDA the consumer approved as delegate on the
+/// account, so the burn runs without the consumer's signature.
+///
+/// # Arguments
+/// * `token_program` - The SPL token program
+/// * `mint` - Mint of the burned tokens
+/// * `from` - The consumer's token account
+/// * `billing_authority` - The billing authority PDA of the agency
+/// * `signer_seeds` - Seeds of the billing authority PDA, with its bump
+/// * `amount` - Amount to burn
+///
+/// # Errors
+/// * `CustomError::InsufficientDelegation` - If the billing authority is not the account's
+///   delegate for at least `amount`
+pub fn burn_as_billing_authority<'info>(
+    token_program: &Program<'info, Token>,
+    mint: AccountInfo<'info>,
+    from: &Account<'info, TokenAccount>,
+    billing_authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    require!(
+        from.delegate == COption::Some(billing_authority.key()) && from.delegated_amount >= amount,
+        CustomError::InsufficientDelegation
+    );
+    token::burn(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::Burn {
+                mint,
+                from: from.to_account_info(),
+                authority: billing_authority,
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
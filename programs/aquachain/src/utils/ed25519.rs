@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+
+/// Length of the offsets header that precedes each signature in an Ed25519 instruction
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+
+/// Instruction index the Ed25519 program uses for data stored in its own instruction
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Length of a signed meter payload: consumer key, reading and timestamp
+pub const METER_PAYLOAD_LEN: usize = 48;
+
+/// Serializes the meter payload a device signs: the consumer's key, then the reading and
+/// the unix timestamp as little-endian integers
+///
+/// # Arguments
+/// * `consumer` - Consumer account the reading is for
+/// * `reading` - Cumulative register value reported by the meter
+/// * `timestamp` - Unix timestamp at which the meter took the reading
+pub fn meter_payload(consumer: &Pubkey, reading: u64, timestamp: i64) -> [u8; METER_PAYLOAD_LEN] {
+    let mut payload = [0; METER_PAYLOAD_LEN];
+    payload[..32].copy_from_slice(consumer.as_ref());
+    payload[32..40].copy_from_slice(&reading.to_le_bytes());
+    payload[40..].copy_from_slice(&timestamp.to_le_bytes());
+    payload
+}
+
+/// Extracts the public key and message of an Ed25519 program instruction verifying a
+/// single signature, with the key, signature and message all stored in the instruction
+///
+/// The Ed25519 program has already checked the signature by the time a later instruction
+/// can read it, so matching the key and message is enough to trust the message.
+///
+/// # Arguments
+/// * `data` - Data of the Ed25519 program instruction
+///
+/// # Returns
+/// The signing key and the signed message, or `None` if the instruction has a different
+/// layout
+pub fn parse_ed25519_instruction(data: &[u8]) -> Option<(Pubkey, &[u8])> {
+    if data.len() < 2 + SIGNATURE_OFFSETS_LEN || data[0] != 1 {
+        return None;
+    }
+    let offsets: Vec<u16> = data[2..2 + SIGNATURE_OFFSETS_LEN]
+        .chunks_exact(2)
+        .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+        .collect();
+    let [_, signature_ix, key_offset, key_ix, message_offset, message_size, message_ix] =
+        offsets[..]
+    else {
+        return None;
+    };
+    if [signature_ix, key_ix, message_ix]
+        .iter()
+        .any(|ix| *ix != CURRENT_INSTRUCTION)
+    {
+        return None;
+    }
+
+    let key = data.get(key_offset as usize..key_offset as usize + 32)?;
+    let message =
+        data.get(message_offset as usize..message_offset as usize + message_size as usize)?;
+    Some((Pubkey::try_from(key).ok()?, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lays out an instruction the way the Ed25519 program's client helpers do
+    fn ed25519_instruction(key: &Pubkey, message: &[u8], ix: u16) -> Vec<u8> {
+        let key_offset = 2 + SIGNATURE_OFFSETS_LEN as u16;
+        let signature_offset = key_offset + 32;
+        let message_offset = signature_offset + 64;
+        let mut data = vec![1, 0];
+        for value in [
+            signature_offset,
+            ix,
+            key_offset,
+            ix,
+            message_offset,
+            message.len() as u16,
+            ix,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(key.as_ref());
+        data.extend_from_slice(&[0; 64]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[test]
+    fn test_parse_ed25519_instruction() {
+        let device = Pubkey::new_unique();
+        let payload = meter_payload(&Pubkey::new_unique(), 1200, 1_760_000_000);
+
+        let data = ed25519_instruction(&device, &payload, CURRENT_INSTRUCTION);
+        let (key, message) = parse_ed25519_instruction(&data).unwrap();
+        assert_eq!(key, device);
+        assert_eq!(message, payload);
+    }
+
+    #[test]
+    fn test_parse_rejects_other_layouts() {
+        let device = Pubkey::new_unique();
+        let payload = meter_payload(&Pubkey::new_unique(), 1200, 1_760_000_000);
+
+        // Data borrowed from another instruction is not covered by this one
+        let data = ed25519_instruction(&device, &payload, 0);
+        assert!(parse_ed25519_instruction(&data).is_none());
+
+        let mut data = ed25519_instruction(&device, &payload, CURRENT_INSTRUCTION);
+        data[0] = 2;
+        assert!(parse_ed25519_instruction(&data).is_none());
+
+        let data = ed25519_instruction(&device, &payload, CURRENT_INSTRUCTION);
+        assert!(parse_ed25519_instruction(&data[..data.len() - 1]).is_none());
+    }
+
+    #[test]
+    fn test_meter_payload() {
+        let consumer = Pubkey::new_unique();
+        let payload = meter_payload(&consumer, 1200, 1_760_000_000);
+        assert_eq!(&payload[..32], consumer.as_ref());
+        assert_eq!(
+            u64::from_le_bytes(payload[32..40].try_into().unwrap()),
+            1200
+        );
+        assert_eq!(
+            i64::from_le_bytes(payload[40..].try_into().unwrap()),
+            1_760_000_000
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use anchor_lang::solana_program::hash::hashv;
+
+/// Hashes two sibling nodes of a Merkle tree, ordering them first so proofs do not
+/// need to record whether each sibling sits on the left or the right
+///
+/// # Arguments
+/// * `a` - One of the sibling nodes
+/// * `b` - The other sibling node
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[left, right]).to_bytes()
+}
+
+/// Computes the root of a tree from a leaf, its position and its sibling path, hashing
+/// each pair in tree order rather than sorted order
+///
+/// # Arguments
+/// * `leaf` - The hashed leaf
+/// * `index` - Position of the leaf in the tree
+/// * `proof` - Sibling nodes from the leaf up to the root
+///
+/// # Returns
+/// The root of the tree the path leads to
+pub fn merkle_root_from_path(leaf: [u8; 32], index: u64, proof: &[[u8; 32]]) -> [u8; 32] {
+    proof
+        .iter()
+        .enumerate()
+        .fold(leaf, |node, (level, sibling)| {
+            if (index >> level) & 1 == 0 {
+                hashv(&[&node, sibling]).to_bytes()
+            } else {
+                hashv(&[sibling, &node]).to_bytes()
+            }
+        })
+}
+
+/// Checks that a leaf is included in a Merkle tree
+///
+/// # Arguments
+/// * `proof` - Sibling nodes from the leaf up to the root
+/// * `root` - The root of the tree
+/// * `leaf` - The hashed leaf to check
+///
+/// # Returns
+/// `true` if hashing the leaf up the proof yields the root
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    computed == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_merkle_proof() {
+        let leaves = [[1u8; 32], [2u8; 32], [3u8; 32], [4u8; 32]];
+        let left = hash_pair(&leaves[0], &leaves[1]);
+        let right = hash_pair(&leaves[2], &leaves[3]);
+        let root = hash_pair(&left, &right);
+
+        assert!(verify_merkle_proof(&[leaves[1], right], &root, leaves[0]));
+        assert!(verify_merkle_proof(&[leaves[2], left], &root, leaves[3]));
+    }
+
+    #[test]
+    fn test_reject_invalid_proof() {
+        let leaves = [[1u8; 32], [2u8; 32]];
+        let root = hash_pair(&leaves[0], &leaves[1]);
+
+        assert!(!verify_merkle_proof(&[leaves[1]], &root, [9u8; 32]));
+        assert!(!verify_merkle_proof(&[], &root, leaves[0]));
+    }
+}
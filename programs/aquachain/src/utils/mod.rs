@@ -1,3 +1,14 @@
-mod fixed_point;
+mod bps;
+mod custody;
+mod ed25519;
+mod merkle;
+mod realloc;
+mod secp256k1;
 
-pub use fixed_point::*;
\ No newline at end of file
+pub use aquachain_core::{FixedPoint, SCALE};
+pub use bps::*;
+pub use custody::*;
+pub use ed25519::*;
+pub use merkle::*;
+pub use realloc::*;
+pub use secp256k1::*;
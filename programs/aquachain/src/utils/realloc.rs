@@ -0,0 +1,445 @@
+use anchor_lang::{
+    error::ErrorCode,
+    prelude::*,
+    solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE,
+    system_program::{self, Transfer},
+    Discriminator, Space,
+};
+
+use crate::DISCRIMINATOR;
+
+/// Grows a program account to the size of its current layout
+///
+/// New fields are appended to the end of an account and read as zero, so an account
+/// written by an older version of the program keeps its data and reads the new fields as
+/// their defaults. The bytes added by the realloc are zeroed, and the bytes left behind
+/// the serialized data are kept zero by `clear_stale_bytes`, which every instruction
+/// shrinking a variable-length field calls. The field order of the growable accounts is
+/// pinned by the tests below. The account is taken unchecked since it may be too short
+/// to deserialize until grown. The payer tops the account up to the rent-exempt minimum
+/// of its new size. An account can grow by at most `MAX_PERMITTED_DATA_INCREASE` bytes
+/// per call, so accounts far behind the layout are grown over several calls.
+///
+/// # Arguments
+/// * `account` - The account to grow
+/// * `payer` - The account paying the additional rent (must be signer)
+/// * `system_program` - The system program
+///
+/// # Errors
+/// * `ErrorCode::AccountOwnedByWrongProgram` - If the account is not owned by the program
+/// * `ErrorCode::AccountDiscriminatorMismatch` - If the account is not of type `T`
+///
+/// # Returns
+/// The size of the account after growing, equal to its size before when already current
+pub fn grow_account<'info, T: Discriminator + Space>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<usize> {
+    require_keys_eq!(
+        *account.owner,
+        crate::ID,
+        ErrorCode::AccountOwnedByWrongProgram
+    );
+    require!(
+        account.try_borrow_data()?.get(..DISCRIMINATOR) == Some(&T::DISCRIMINATOR[..]),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    let current = account.data_len();
+    let space = (DISCRIMINATOR + T::INIT_SPACE).min(current + MAX_PERMITTED_DATA_INCREASE);
+    if space <= current {
+        return Ok(current);
+    }
+
+    let rent = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+    if rent > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent,
+        )?;
+    }
+    account.realloc(space, true)?;
+
+    Ok(space)
+}
+
+/// Zeroes the bytes of an account left behind its serialized data
+///
+/// Anchor writes an account back over its old data without clearing the rest, so
+/// shrinking an `Option`, `String` or `Vec` field leaves stale bytes at the end of the
+/// account. Once grown, fields appended by a later layout would read those bytes instead
+/// of zero, so instructions that may shrink such a field call this after updating it.
+///
+/// # Arguments
+/// * `account` - The account whose trailing bytes are cleared
+///
+/// # Errors
+/// * `ErrorCode::AccountDidNotSerialize` - If the account cannot be serialized
+pub fn clear_stale_bytes<'info, T>(account: &Account<'info, T>) -> Result<()>
+where
+    T: AccountSerialize + AccountDeserialize + AnchorSerialize + Owner + Clone,
+{
+    let info = account.to_account_info();
+    let mut data = info.try_borrow_mut_data()?;
+    zero_after_serialized(&mut data, &**account)
+}
+
+/// Zeroes `data` past the discriminator and the serialized `account`
+fn zero_after_serialized<T: AnchorSerialize>(data: &mut [u8], account: &T) -> Result<()> {
+    let mut serialized = Vec::new();
+    account
+        .serialize(&mut serialized)
+        .map_err(|_| ErrorCode::AccountDidNotSerialize)?;
+    if let Some(stale) = data.get_mut(DISCRIMINATOR + serialized.len()..) {
+        stale.fill(0);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{
+        BillBreakdown, Consumer, FlaggedCharge, PricingSnapshot, Reservoir, ScarcityBand,
+        ScarcityInputs, Tariff, TariffType, Tokens, UnitConfig, SCARCITY_BANDS_MAX,
+    };
+    use aquachain_core::BASELINE_PERIODS;
+
+    /// Serializes the fields of an account one after the other, in the order given
+    macro_rules! serialize_fields {
+        ($account:expr, [$($field:ident),+ $(,)?]) => {{
+            let mut data = Vec::new();
+            $(AnchorSerialize::serialize(&$account.$field, &mut data).unwrap();)+
+            data
+        }};
+    }
+
+    fn serialize<T: AnchorSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_growable_accounts_fit_a_single_init() {
+        // Accounts created through a CPI to the system program are capped at
+        // MAX_PERMITTED_DATA_INCREASE, so a layout past it could no longer be initialized
+        for space in [
+            Consumer::INIT_SPACE,
+            Tariff::INIT_SPACE,
+            Reservoir::INIT_SPACE,
+            Tokens::INIT_SPACE,
+        ] {
+            assert!(DISCRIMINATOR + space <= MAX_PERMITTED_DATA_INCREASE);
+        }
+    }
+
+    #[test]
+    fn test_shrunk_fields_grow_as_zero() {
+        /// A consumer followed by a field appended by a later layout
+        #[derive(AnchorDeserialize)]
+        struct Grown {
+            _consumer: Consumer,
+            appended: u64,
+        }
+
+        // Fill the end of the account, where the shrunk fields leave bytes behind
+        let mut consumer = consumer();
+        consumer.last_scarcity_inputs = ScarcityInputs {
+            tariff_type: TariffType::SeasonalIBT,
+            capacity: u64::MAX,
+            current_level: u64::MAX,
+            smoothed_level: u64::MAX,
+            smoothing_bps: u16::MAX,
+            forecast_level: Some(u64::MAX),
+            curve_len: u8::MAX,
+            curve: [ScarcityBand {
+                max_level_bps: u16::MAX,
+                multiplier: u64::MAX,
+            }; SCARCITY_BANDS_MAX],
+        };
+        let mut data = vec![0; DISCRIMINATOR + Consumer::INIT_SPACE];
+        consumer.serialize(&mut &mut data[DISCRIMINATOR..]).unwrap();
+
+        // Shrinking the optional fields writes the account back over its old data
+        consumer.guarantor = None;
+        consumer.master_consumer = None;
+        consumer.identity_uri = None;
+        consumer.flagged_charge = None;
+        consumer.serialize(&mut &mut data[DISCRIMINATOR..]).unwrap();
+        let mut stale = data.clone();
+        zero_after_serialized(&mut data, &consumer).unwrap();
+
+        let appended = |data: &mut Vec<u8>| {
+            data.resize(data.len() + 8, 0);
+            Grown::deserialize(&mut &data[DISCRIMINATOR..])
+                .unwrap()
+                .appended
+        };
+        assert_ne!(appended(&mut stale), 0);
+        assert_eq!(appended(&mut data), 0);
+    }
+
+    // The field order tests below pin the layouts grown by `grow_account`. Every field
+    // holds a distinct value, so moving one changes the serialized bytes. Append new
+    // fields at the end of both the account and its list; never reorder either.
+
+    /// A consumer holding a distinct value in every field
+    fn consumer() -> Consumer {
+        Consumer {
+            block_rate: 1,
+            contracted_capacity: 2,
+            assigned_tariff: Pubkey::new_unique(),
+            assigned_reservoir: Pubkey::new_unique(),
+            guarantor: Some(Pubkey::new_unique()),
+            master_consumer: Some(Pubkey::new_unique()),
+            tenant_share_bps: 3,
+            sub_consumer_count: 4,
+            owner: Pubkey::new_unique(),
+            autopay_enabled: true,
+            outstanding_water_debt: 5,
+            average_usage: 6,
+            estimated_usage: 7,
+            estimated_charge: 8,
+            budget_billing: true,
+            budget_amount: 9,
+            budget_paid: 10,
+            debt_since_slot: 11,
+            capacity_period_start: 12,
+            period_usage: 13,
+            period_waste: 14,
+            household_size: 15,
+            contract_expiry_slot: 16,
+            identity_hash: [17; 32],
+            identity_uri: Some("ipfs://kyc".to_string()),
+            co2e_grams: 18,
+            last_bill: BillBreakdown {
+                fixed: 19,
+                base: 20,
+                excess: 21,
+                penalty: 22,
+                discount: 23,
+                total: 24,
+            },
+            last_pricing: PricingSnapshot {
+                slot: 25,
+                ..Default::default()
+            },
+            flagged_charge: Some(FlaggedCharge {
+                billed: 26,
+                recomputed: 27,
+                slot: 28,
+            }),
+            interruptible: true,
+            curtailed_periods_compensated: 29,
+            usage_history: [30; BASELINE_PERIODS],
+            usage_history_len: 31,
+            prepaid_balance: 32,
+            receipts_issued: 33,
+            capacity_approval_bps: 34,
+            capacity_approval_base: 35,
+            capacity_approval_period: 36,
+            meter_reading: 37,
+            meter_read_at: 38,
+            geohash: [39; 12],
+            period_watc_bought: 40,
+            conservation_streak: 41,
+            certificates_awarded: 42,
+            last_scarcity_inputs: ScarcityInputs {
+                capacity: 43,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_consumer_field_order() {
+        let consumer = consumer();
+
+        assert_eq!(
+            serialize(&consumer),
+            serialize_fields!(
+                consumer,
+                [
+                    block_rate,
+                    contracted_capacity,
+                    assigned_tariff,
+                    assigned_reservoir,
+                    guarantor,
+                    master_consumer,
+                    tenant_share_bps,
+                    sub_consumer_count,
+                    owner,
+                    autopay_enabled,
+                    outstanding_water_debt,
+                    average_usage,
+                    estimated_usage,
+                    estimated_charge,
+                    budget_billing,
+                    budget_amount,
+                    budget_paid,
+                    debt_since_slot,
+                    capacity_period_start,
+                    period_usage,
+                    period_waste,
+                    household_size,
+                    contract_expiry_slot,
+                    identity_hash,
+                    identity_uri,
+                    co2e_grams,
+                    last_bill,
+                    last_pricing,
+                    flagged_charge,
+                    interruptible,
+                    curtailed_periods_compensated,
+                    usage_history,
+                    usage_history_len,
+                    prepaid_balance,
+                    receipts_issued,
+                    capacity_approval_bps,
+                    capacity_approval_base,
+                    capacity_approval_period,
+                    meter_reading,
+                    meter_read_at,
+                    geohash,
+                    period_watc_bought,
+                    conservation_streak,
+                    certificates_awarded,
+                    last_scarcity_inputs,
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_tariff_field_order() {
+        let tariff = Tariff {
+            water_rate: 1,
+            waste_rate: 2,
+            tariff_type: TariffType::SeasonalIBT,
+            tariff_key: Pubkey::new_unique(),
+            currency_code: *b"TTD",
+            activated_slot: 3,
+            grace_period_slots: 4,
+            billing_period_slots: 5,
+            capacity_rollover_bps: 6,
+            block_threshold: 7,
+            per_capita_allowance: 8,
+            assigned_consumer_count: 9,
+            conservation_baseline_bps: 10,
+            conservation_periods: 11,
+            bulk_rate: 12,
+            forecast_pricing: true,
+            max_multiplier: 13,
+            min_charge: 14,
+        };
+
+        assert_eq!(
+            serialize(&tariff),
+            serialize_fields!(
+                tariff,
+                [
+                    water_rate,
+                    waste_rate,
+                    tariff_type,
+                    tariff_key,
+                    currency_code,
+                    activated_slot,
+                    grace_period_slots,
+                    billing_period_slots,
+                    capacity_rollover_bps,
+                    block_threshold,
+                    per_capita_allowance,
+                    assigned_consumer_count,
+                    conservation_baseline_bps,
+                    conservation_periods,
+                    bulk_rate,
+                    forecast_pricing,
+                    max_multiplier,
+                    min_charge,
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_reservoir_field_order() {
+        let reservoir = Reservoir {
+            current_level: 1,
+            capacity: 2,
+            reservoir_key: Pubkey::new_unique(),
+            assigned_consumer_count: 3,
+            zone_geohash: [4; 12],
+            basin_id: 5,
+            pumping_intensity: 6,
+            emissions_factor: 7,
+            scarcity_curve: vec![ScarcityBand {
+                max_level_bps: 8,
+                multiplier: 9,
+            }],
+            smoothing_bps: 10,
+            smoothed_level: 11,
+            telemetry_authority: Some(Pubkey::new_unique()),
+            max_level_rate: 12,
+            level_updated_slot: 13,
+            max_change_bps_per_hour: 14,
+            level_updated_at: 15,
+            alert_level_high: 16,
+            alert_level_low: 17,
+        };
+
+        assert_eq!(
+            serialize(&reservoir),
+            serialize_fields!(
+                reservoir,
+                [
+                    current_level,
+                    capacity,
+                    reservoir_key,
+                    assigned_consumer_count,
+                    zone_geohash,
+                    basin_id,
+                    pumping_intensity,
+                    emissions_factor,
+                    scarcity_curve,
+                    smoothing_bps,
+                    smoothed_level,
+                    telemetry_authority,
+                    max_level_rate,
+                    level_updated_slot,
+                    max_change_bps_per_hour,
+                    level_updated_at,
+                    alert_level_high,
+                    alert_level_low,
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_tokens_field_order() {
+        let tokens = Tokens {
+            wtk: Pubkey::new_unique(),
+            wst: Pubkey::new_unique(),
+            watc: Pubkey::new_unique(),
+            units: UnitConfig {
+                volume_scale: 1,
+                currency_decimals: 2,
+            },
+        };
+
+        assert_eq!(
+            serialize(&tokens),
+            serialize_fields!(tokens, [wtk, wst, watc, units])
+        );
+    }
+}
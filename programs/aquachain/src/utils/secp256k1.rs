@@ -0,0 +1,73 @@
+/// Length of the offsets header that precedes each signature in a Secp256k1 instruction
+const SIGNATURE_OFFSETS_LEN: usize = 11;
+
+/// Extracts the Ethereum address and message of a Secp256k1 program instruction verifying
+/// a single signature, with the address, signature and message all stored in the
+/// instruction itself
+///
+/// The Secp256k1 program has already recovered the signer and checked it against the
+/// address by the time a later instruction can read it, so matching the address and
+/// message is enough to trust the message.
+///
+/// # Arguments
+/// * `data` - Data of the Secp256k1 program instruction
+/// * `own_index` - Position of the Secp256k1 instruction in the transaction
+///
+/// # Returns
+/// The signer's Ethereum address and the signed message, or `None` if the instruction has
+/// a different layout
+pub fn parse_secp256k1_instruction(data: &[u8], own_index: u8) -> Option<([u8; 20], &[u8])> {
+    if data.len() < 1 + SIGNATURE_OFFSETS_LEN || data[0] != 1 {
+        return None;
+    }
+    let offsets = &data[1..1 + SIGNATURE_OFFSETS_LEN];
+    let u16_at = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]) as usize;
+    let (address_offset, message_offset, message_size) = (u16_at(3), u16_at(6), u16_at(8));
+    if [offsets[2], offsets[5], offsets[10]]
+        .iter()
+        .any(|ix| *ix != own_index)
+    {
+        return None;
+    }
+
+    let address = data.get(address_offset..address_offset + 20)?;
+    let message = data.get(message_offset..message_offset + message_size)?;
+    Some((address.try_into().ok()?, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lays out an instruction the way the Secp256k1 program's client helpers do
+    fn secp256k1_instruction(address: &[u8; 20], message: &[u8], ix: u8) -> Vec<u8> {
+        let address_offset = 1 + SIGNATURE_OFFSETS_LEN as u16;
+        let signature_offset = address_offset + 20;
+        let message_offset = signature_offset + 65;
+        let mut data = vec![1];
+        data.extend_from_slice(&signature_offset.to_le_bytes());
+        data.push(ix);
+        data.extend_from_slice(&address_offset.to_le_bytes());
+        data.push(ix);
+        data.extend_from_slice(&message_offset.to_le_bytes());
+        data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        data.push(ix);
+        data.extend_from_slice(address);
+        data.extend_from_slice(&[0; 65]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[test]
+    fn test_parse_secp256k1_instruction() {
+        let address = [7; 20];
+        let data = secp256k1_instruction(&address, b"reading", 2);
+        let (signer, message) = parse_secp256k1_instruction(&data, 2).unwrap();
+        assert_eq!(signer, address);
+        assert_eq!(message, b"reading");
+
+        // Data stored in another instruction is not covered by this one
+        assert!(parse_secp256k1_instruction(&data, 1).is_none());
+        assert!(parse_secp256k1_instruction(&data[..data.len() - 1], 2).is_none());
+    }
+}
@@ -0,0 +1,159 @@
+//! Python bindings for the AquaChain pricing functions.
+//!
+//! Exposes the pricing core the program bills with, so tariffs and block rates
+//! calibrated in a notebook charge what the chain would at the same scarcity multiplier:
+//!
+//! ```python
+//! import aquachain_pricing as pricing
+//!
+//! # 120.000 units used with 100.000 left below the block threshold, unscaled
+//! pricing.bill(100_000, 120_000, 500, 800)  # 66000
+//! ```
+
+use aquachain_core::{
+    calculate_total_cost, ChargeBounds, FixedPoint, PricingError, Reading, TariffType, SCALE,
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// Surfaces a pricing error as a Python `ValueError`
+fn value_error(err: PricingError) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+/// Bills a reading in currency base units, as the program would at the given multiplier.
+///
+/// Rates, the multiplier and bounds carry three implied decimals, volumes are raw metered
+/// amounts. The multiplier is the reservoir's scarcity multiplier the program prices at,
+/// before the tariff's cap. A zero `max_multiplier` or `min_charge` leaves the charge
+/// unbounded.
+#[pyfunction]
+#[pyo3(signature = (
+    block_remaining,
+    usage,
+    water_rate,
+    block_rate,
+    multiplier = 1000,
+    volume_scale = 1000,
+    currency_decimals = 3,
+    max_multiplier = 0,
+    min_charge = 0
+))]
+#[allow(clippy::too_many_arguments)]
+fn bill(
+    block_remaining: u64,
+    usage: u64,
+    water_rate: u64,
+    block_rate: u64,
+    multiplier: u64,
+    volume_scale: u64,
+    currency_decimals: u8,
+    max_multiplier: u64,
+    min_charge: u64,
+) -> PyResult<u64> {
+    Reading {
+        block_remaining,
+        usage,
+        water_rate,
+        block_rate,
+        multiplier,
+        volume_scale,
+        currency_decimals,
+        bounds: ChargeBounds {
+            max_multiplier,
+            min_charge,
+        },
+    }
+    .bill()
+    .map_err(value_error)
+}
+
+/// Computes the fixed-point cost of a billed volume, without unit conversions.
+///
+/// Seasonal tariffs are scaled by the default linear curve of reservoirs without a
+/// scarcity curve. Every argument and the result are fixed-point values scaled by 1000. A zero
+/// `max_multiplier` or `min_charge` leaves the cost unbounded.
+#[pyfunction]
+#[pyo3(signature = (
+    block_remaining,
+    amount,
+    water_rate,
+    tariff_type,
+    block_rate,
+    level_max,
+    level,
+    max_multiplier = 0,
+    min_charge = 0
+))]
+#[allow(clippy::too_many_arguments)]
+fn total_cost(
+    block_remaining: u64,
+    amount: u64,
+    water_rate: u64,
+    tariff_type: &str,
+    block_rate: u64,
+    level_max: u64,
+    level: u64,
+    max_multiplier: u64,
+    min_charge: u64,
+) -> PyResult<u64> {
+    if level > level_max {
+        return Err(PyValueError::new_err("level exceeds level_max"));
+    }
+    let tariff_type: TariffType = tariff_type.parse().map_err(value_error)?;
+    Ok(calculate_total_cost(
+        FixedPoint::from(block_remaining),
+        FixedPoint::from(amount),
+        FixedPoint::from(water_rate),
+        tariff_type,
+        FixedPoint::from(block_rate),
+        FixedPoint::from(level_max),
+        FixedPoint::from(level),
+        &ChargeBounds {
+            max_multiplier,
+            min_charge,
+        },
+    )
+    .into())
+}
+
+/// Computes a household's lifeline allocation for a billing period.
+///
+/// The allowance is a raw volume per person per billing period.
+#[pyfunction]
+fn lifeline_allocation(per_capita_allowance: u64, household_size: u16) -> u64 {
+    aquachain_core::lifeline_allocation(per_capita_allowance, household_size)
+}
+
+#[pymodule]
+fn aquachain_pricing(_py: Python<'_>, module: &PyModule) -> PyResult<()> {
+    module.add("SCALE", SCALE)?;
+    module.add_function(wrap_pyfunction!(bill, module)?)?;
+    module.add_function(wrap_pyfunction!(total_cost, module)?)?;
+    module.add_function(wrap_pyfunction!(lifeline_allocation, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bill_matches_use_water() {
+        let cost = bill(100000, 120000, 500, 800, 1000, 1000, 3, 0, 0);
+        assert_eq!(cost.ok(), Some(66000));
+    }
+
+    #[test]
+    fn test_bill_within_bounds() {
+        // The seasonal IBT multiplier of a reservoir at 95%, capped at 2.000
+        let cost = bill(100000, 120000, 500, 800, 50000, 1000, 3, 2000, 0);
+        assert_eq!(cost.ok(), Some(82000));
+    }
+
+    #[test]
+    fn test_invalid_input_is_rejected() {
+        assert!(bill(0, 1000, 500, 800, 1000, 0, 3, 0, 0).is_err());
+        assert!(total_cost(0, 1000, 500, "flat", 800, 1000, 500, 0, 0).is_err());
+        assert!(total_cost(0, 1000, 500, "uniform_ibt", 800, 1000, 2000, 0, 0).is_err());
+    }
+}
@@ -0,0 +1,571 @@
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, Discriminator};
+use aquachain::state::{
+    AgencyStats, Consumer, HydroArchiveShard, Reservoir, ReservoirDailyStats, SpillRecord, Tariff,
+    TariffHistory, HYDRO_ARCHIVE_SHARD_DAYS,
+};
+use serde_json::{json, Value};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+
+/// Offset of `tariff_key` in a Tariff account: discriminator, two rates and the tariff type
+const TARIFF_KEY_OFFSET: usize = 8 + 8 + 8 + 1;
+/// Offset of `reservoir_key` in a Reservoir account: discriminator, level and capacity
+const RESERVOIR_KEY_OFFSET: usize = 8 + 8 + 8;
+/// Offset of `tariff_key` in a TariffHistory account: right after the discriminator
+const HISTORY_TARIFF_KEY_OFFSET: usize = 8;
+/// Offset of `reservoir_key` in a ReservoirDailyStats account: right after the discriminator
+const DAILY_STATS_RESERVOIR_KEY_OFFSET: usize = 8;
+/// Offset of `reservoir_key` in a SpillRecord account: right after the discriminator
+const SPILL_RESERVOIR_KEY_OFFSET: usize = 8;
+/// Offset of `reservoir_key` in a HydroArchiveShard account: right after the discriminator
+const ARCHIVE_RESERVOIR_KEY_OFFSET: usize = 8;
+
+/// Reads and decodes AquaChain accounts over RPC
+pub struct AccountReader {
+    rpc: RpcClient,
+}
+
+impl AccountReader {
+    pub fn new(rpc_url: String) -> Self {
+        Self {
+            rpc: RpcClient::new(rpc_url),
+        }
+    }
+
+    /// The underlying RPC client, for queries beyond account reads
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    /// Lists every consumer account of the program, decoded
+    pub fn consumer_accounts(&self) -> Result<Vec<(Pubkey, Consumer)>, String> {
+        self.find::<Consumer>(vec![])
+    }
+
+    /// Fetches a consumer account by its public key
+    pub fn consumer(&self, pubkey: &Pubkey) -> Result<Option<Value>, String> {
+        let account = match self.rpc.get_account(pubkey) {
+            Ok(account) => account,
+            Err(_) => return Ok(None),
+        };
+        if account.owner != aquachain::ID {
+            return Ok(None);
+        }
+        let consumer = Consumer::try_deserialize(&mut account.data.as_slice())
+            .map_err(|err| err.to_string())?;
+        Ok(Some(consumer_json(pubkey, &consumer)))
+    }
+
+    /// Lists every tariff account of the program
+    pub fn tariffs(&self) -> Result<Value, String> {
+        let tariffs = self
+            .find::<Tariff>(vec![])?
+            .iter()
+            .map(|(pubkey, tariff)| tariff_json(pubkey, tariff))
+            .collect();
+        Ok(Value::Array(tariffs))
+    }
+
+    /// Finds a tariff account by its tariff key
+    pub fn tariff(&self, tariff_key: &Pubkey) -> Result<Option<Value>, String> {
+        Ok(self
+            .find::<Tariff>(vec![key_filter(TARIFF_KEY_OFFSET, tariff_key)])?
+            .first()
+            .map(|(pubkey, tariff)| tariff_json(pubkey, tariff)))
+    }
+
+    /// Finds the superseded versions of a tariff by its tariff key
+    pub fn tariff_history(&self, tariff_key: &Pubkey) -> Result<Option<Value>, String> {
+        Ok(self
+            .find::<TariffHistory>(vec![key_filter(HISTORY_TARIFF_KEY_OFFSET, tariff_key)])?
+            .first()
+            .map(|(_, history)| tariff_history_json(history)))
+    }
+
+    /// Finds a reservoir account by its reservoir key
+    pub fn reservoir(&self, reservoir_key: &Pubkey) -> Result<Option<Value>, String> {
+        Ok(self
+            .find::<Reservoir>(vec![key_filter(RESERVOIR_KEY_OFFSET, reservoir_key)])?
+            .first()
+            .map(|(pubkey, reservoir)| reservoir_json(pubkey, reservoir)))
+    }
+
+    /// Finds the daily withdrawals of a reservoir by its reservoir key
+    pub fn reservoir_daily_stats(&self, reservoir_key: &Pubkey) -> Result<Option<Value>, String> {
+        Ok(self
+            .find::<ReservoirDailyStats>(vec![key_filter(
+                DAILY_STATS_RESERVOIR_KEY_OFFSET,
+                reservoir_key,
+            )])?
+            .first()
+            .map(|(_, daily_stats)| reservoir_daily_stats_json(daily_stats)))
+    }
+
+    /// Finds the spills over capacity of a reservoir by its reservoir key
+    pub fn spill_record(&self, reservoir_key: &Pubkey) -> Result<Option<Value>, String> {
+        Ok(self
+            .find::<SpillRecord>(vec![key_filter(SPILL_RESERVOIR_KEY_OFFSET, reservoir_key)])?
+            .first()
+            .map(|(_, spill_record)| spill_record_json(spill_record)))
+    }
+
+    /// Lists the archived hydro-meteorological days of a reservoir by its reservoir key
+    pub fn hydro_archive(&self, reservoir_key: &Pubkey) -> Result<Option<Value>, String> {
+        let shards: Vec<HydroArchiveShard> = self
+            .find::<HydroArchiveShard>(vec![key_filter(
+                ARCHIVE_RESERVOIR_KEY_OFFSET,
+                reservoir_key,
+            )])?
+            .into_iter()
+            .map(|(_, shard)| shard)
+            .collect();
+        if shards.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(hydro_archive_json(reservoir_key, &shards)))
+    }
+
+    /// Fetches the aggregate statistics of an agency from its AgencyStats PDA
+    pub fn agency_stats(&self, agency: &Pubkey) -> Result<Option<Value>, String> {
+        let (pubkey, _) =
+            Pubkey::find_program_address(&[b"agency_stats", agency.as_ref()], &aquachain::ID);
+        let account = match self.rpc.get_account(&pubkey) {
+            Ok(account) => account,
+            Err(_) => return Ok(None),
+        };
+        if account.owner != aquachain::ID {
+            return Ok(None);
+        }
+        let agency_stats = AgencyStats::try_deserialize(&mut account.data.as_slice())
+            .map_err(|err| err.to_string())?;
+        Ok(Some(agency_stats_json(&pubkey, &agency_stats)))
+    }
+
+    /// Runs getProgramAccounts for one account type, with additional memcmp filters
+    fn find<T: AccountDeserialize + Discriminator>(
+        &self,
+        mut filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(Pubkey, T)>, String> {
+        filters.insert(
+            0,
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &T::DISCRIMINATOR)),
+        );
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        self.rpc
+            .get_program_accounts_with_config(&aquachain::ID, config)
+            .map_err(|err| err.to_string())?
+            .into_iter()
+            .map(|(pubkey, account)| {
+                T::try_deserialize(&mut account.data.as_slice())
+                    .map(|decoded| (pubkey, decoded))
+                    .map_err(|err| err.to_string())
+            })
+            .collect()
+    }
+}
+
+/// Matches a public key stored at the given offset
+fn key_filter(offset: usize, key: &Pubkey) -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, key.as_ref()))
+}
+
+fn optional_key(key: &Option<Pubkey>) -> Value {
+    key.map_or(Value::Null, |key| json!(key.to_string()))
+}
+
+fn padded_str(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn consumer_json(pubkey: &Pubkey, consumer: &Consumer) -> Value {
+    json!({
+        "pubkey": pubkey.to_string(),
+        "owner": consumer.owner.to_string(),
+        "block_rate": consumer.block_rate,
+        "contracted_capacity": consumer.contracted_capacity,
+        "assigned_tariff": consumer.assigned_tariff.to_string(),
+        "assigned_reservoir": consumer.assigned_reservoir.to_string(),
+        "guarantor": optional_key(&consumer.guarantor),
+        "master_consumer": optional_key(&consumer.master_consumer),
+        "tenant_share_bps": consumer.tenant_share_bps,
+        "sub_consumer_count": consumer.sub_consumer_count,
+        "autopay_enabled": consumer.autopay_enabled,
+        "outstanding_water_debt": consumer.outstanding_water_debt,
+        "debt_since_slot": consumer.debt_since_slot,
+        "average_usage": consumer.average_usage,
+        "period_usage": consumer.period_usage,
+        "period_waste": consumer.period_waste,
+        "period_watc_bought": consumer.period_watc_bought,
+        "conservation_streak": consumer.conservation_streak,
+        "certificates_awarded": consumer.certificates_awarded,
+        "household_size": consumer.household_size,
+        "contract_expiry_slot": consumer.contract_expiry_slot,
+        "meter_reading": consumer.meter_reading,
+        "meter_read_at": consumer.meter_read_at,
+        "geohash": padded_str(&consumer.geohash),
+        "identity_hash": hex(&consumer.identity_hash),
+        "identity_uri": consumer.identity_uri,
+        "co2e_grams": consumer.co2e_grams,
+        "last_bill": {
+            "fixed": consumer.last_bill.fixed,
+            "base": consumer.last_bill.base,
+            "excess": consumer.last_bill.excess,
+            "penalty": consumer.last_bill.penalty,
+            "discount": consumer.last_bill.discount,
+            "total": consumer.last_bill.total,
+        },
+        "last_pricing": {
+            "tariff_activated_slot": consumer.last_pricing.tariff_activated_slot,
+            "water_rate": consumer.last_pricing.water_rate,
+            "block_rate": consumer.last_pricing.block_rate,
+            "max_multiplier": consumer.last_pricing.max_multiplier,
+            "min_charge": consumer.last_pricing.min_charge,
+            "block_remaining": consumer.last_pricing.block_remaining,
+            "volume": consumer.last_pricing.volume,
+            "level": consumer.last_pricing.level,
+            "multiplier": consumer.last_pricing.multiplier,
+            "volume_scale": consumer.last_pricing.volume_scale,
+            "currency_decimals": consumer.last_pricing.currency_decimals,
+            "slot": consumer.last_pricing.slot,
+        },
+        "last_scarcity_inputs": {
+            "tariff_type": format!("{:?}", consumer.last_scarcity_inputs.tariff_type),
+            "capacity": consumer.last_scarcity_inputs.capacity,
+            "current_level": consumer.last_scarcity_inputs.current_level,
+            "smoothed_level": consumer.last_scarcity_inputs.smoothed_level,
+            "smoothing_bps": consumer.last_scarcity_inputs.smoothing_bps,
+            "forecast_level": consumer.last_scarcity_inputs.forecast_level,
+            "curve": consumer
+                .last_scarcity_inputs
+                .curve
+                .iter()
+                .take(consumer.last_scarcity_inputs.curve_len as usize)
+                .map(|band| json!({
+                    "max_level_bps": band.max_level_bps,
+                    "multiplier": band.multiplier,
+                }))
+                .collect::<Vec<_>>(),
+        },
+        "flagged_charge": consumer.flagged_charge.map(|flagged| json!({
+            "billed": flagged.billed,
+            "recomputed": flagged.recomputed,
+            "slot": flagged.slot,
+        })),
+        "estimated_usage": consumer.estimated_usage,
+        "estimated_charge": consumer.estimated_charge,
+        "budget_billing": consumer.budget_billing,
+        "budget_amount": consumer.budget_amount,
+        "budget_paid": consumer.budget_paid,
+    })
+}
+
+pub fn tariff_json(pubkey: &Pubkey, tariff: &Tariff) -> Value {
+    json!({
+        "pubkey": pubkey.to_string(),
+        "tariff_key": tariff.tariff_key.to_string(),
+        "water_rate": tariff.water_rate,
+        "waste_rate": tariff.waste_rate,
+        "tariff_type": format!("{:?}", tariff.tariff_type),
+        "currency_code": String::from_utf8_lossy(&tariff.currency_code).trim_matches('\0'),
+        "activated_slot": tariff.activated_slot,
+        "grace_period_slots": tariff.grace_period_slots,
+        "block_threshold": tariff.block_threshold,
+        "per_capita_allowance": tariff.per_capita_allowance,
+        "conservation_baseline_bps": tariff.conservation_baseline_bps,
+        "conservation_periods": tariff.conservation_periods,
+        "bulk_rate": tariff.bulk_rate,
+        "forecast_pricing": tariff.forecast_pricing,
+        "max_multiplier": tariff.max_multiplier,
+        "min_charge": tariff.min_charge,
+        "assigned_consumer_count": tariff.assigned_consumer_count,
+    })
+}
+
+pub fn tariff_history_json(history: &TariffHistory) -> Value {
+    let versions: Vec<Value> = history.versions[..history.len as usize]
+        .iter()
+        .map(|version| {
+            json!({
+                "water_rate": version.water_rate,
+                "waste_rate": version.waste_rate,
+                "tariff_type": format!("{:?}", version.tariff_type),
+                "activated_slot": version.activated_slot,
+                "superseded_slot": version.superseded_slot,
+            })
+        })
+        .collect();
+    json!({
+        "tariff_key": history.tariff_key.to_string(),
+        "versions": versions,
+    })
+}
+
+pub fn reservoir_daily_stats_json(daily_stats: &ReservoirDailyStats) -> Value {
+    let mut days: Vec<_> = daily_stats
+        .days
+        .iter()
+        .filter(|entry| entry.volume > 0)
+        .collect();
+    days.sort_by_key(|entry| entry.day);
+    let days: Vec<Value> = days
+        .iter()
+        .map(|entry| json!({ "day": entry.day, "volume": entry.volume }))
+        .collect();
+    json!({
+        "reservoir_key": daily_stats.reservoir_key.to_string(),
+        "days": days,
+    })
+}
+
+pub fn spill_record_json(spill_record: &SpillRecord) -> Value {
+    json!({
+        "reservoir_key": spill_record.reservoir_key.to_string(),
+        "spill_count": spill_record.spill_count,
+        "total_spilled": spill_record.total_spilled,
+        "last_spilled": spill_record.last_spilled,
+        "last_spill_slot": spill_record.last_spill_slot,
+    })
+}
+
+pub fn hydro_archive_json(reservoir_key: &Pubkey, shards: &[HydroArchiveShard]) -> Value {
+    let mut days: Vec<(u64, Value)> = shards
+        .iter()
+        .flat_map(|shard| {
+            let first_day = shard.shard * HYDRO_ARCHIVE_SHARD_DAYS as u64;
+            shard
+                .days
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| entry.level > 0)
+                .map(move |(offset, entry)| {
+                    let day = first_day + offset as u64;
+                    (
+                        day,
+                        json!({
+                            "day": day,
+                            "level": entry.level,
+                            "inflow": entry.inflow,
+                            "outflow": entry.outflow,
+                        }),
+                    )
+                })
+        })
+        .collect();
+    days.sort_by_key(|(day, _)| *day);
+    json!({
+        "reservoir_key": reservoir_key.to_string(),
+        "days": days.into_iter().map(|(_, entry)| entry).collect::<Vec<_>>(),
+    })
+}
+
+pub fn agency_stats_json(pubkey: &Pubkey, agency_stats: &AgencyStats) -> Value {
+    json!({
+        "pubkey": pubkey.to_string(),
+        "total_consumers": agency_stats.total_consumers,
+        "total_usage": agency_stats.total_usage,
+        "total_waste": agency_stats.total_waste,
+        "revenue_collected": agency_stats.revenue_collected,
+        "co2e_grams": agency_stats.co2e_grams,
+    })
+}
+
+pub fn reservoir_json(pubkey: &Pubkey, reservoir: &Reservoir) -> Value {
+    json!({
+        "pubkey": pubkey.to_string(),
+        "reservoir_key": reservoir.reservoir_key.to_string(),
+        "current_level": reservoir.current_level,
+        "capacity": reservoir.capacity,
+        "low": reservoir.is_low(),
+        "assigned_consumer_count": reservoir.assigned_consumer_count,
+        "zone_geohash": padded_str(&reservoir.zone_geohash),
+        "basin_id": reservoir.basin_id,
+        "pumping_intensity": reservoir.pumping_intensity,
+        "emissions_factor": reservoir.emissions_factor,
+        "smoothing_bps": reservoir.smoothing_bps,
+        "smoothed_level": reservoir.smoothed_level,
+        "pricing_level": reservoir.pricing_level(),
+        "telemetry_authority": optional_key(&reservoir.telemetry_authority),
+        "max_level_rate": reservoir.max_level_rate,
+        "level_updated_slot": reservoir.level_updated_slot,
+        "max_change_bps_per_hour": reservoir.max_change_bps_per_hour,
+        "level_updated_at": reservoir.level_updated_at,
+        "alert_level_high": reservoir.alert_level_high,
+        "alert_level_low": reservoir.alert_level_low,
+        "scarcity_curve": reservoir
+            .scarcity_curve
+            .iter()
+            .map(|band| json!({
+                "max_level_bps": band.max_level_bps,
+                "multiplier": band.multiplier,
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aquachain::state::{DailyUsage, HydroDay, TariffType, RESERVOIR_DAILY_STATS_LEN};
+
+    #[test]
+    fn test_tariff_key_offset() {
+        let tariff_key = Pubkey::new_unique();
+        let tariff = Tariff {
+            water_rate: 500,
+            waste_rate: 200,
+            tariff_type: TariffType::UniformIBT,
+            tariff_key,
+            currency_code: *b"TTD",
+            activated_slot: 0,
+            grace_period_slots: 0,
+            billing_period_slots: 0,
+            capacity_rollover_bps: 0,
+            block_threshold: 0,
+            per_capita_allowance: 0,
+            assigned_consumer_count: 0,
+            conservation_baseline_bps: 0,
+            conservation_periods: 0,
+            bulk_rate: 0,
+            forecast_pricing: false,
+            max_multiplier: 0,
+            min_charge: 0,
+        };
+        let mut data = Vec::new();
+        anchor_lang::AccountSerialize::try_serialize(&tariff, &mut data).unwrap();
+
+        assert_eq!(
+            &data[TARIFF_KEY_OFFSET..TARIFF_KEY_OFFSET + 32],
+            tariff_key.as_ref()
+        );
+        assert_eq!(
+            tariff_json(&Pubkey::default(), &tariff)["currency_code"],
+            "TTD"
+        );
+    }
+
+    #[test]
+    fn test_reservoir_key_offset() {
+        let reservoir_key = Pubkey::new_unique();
+        let reservoir = Reservoir {
+            current_level: 950000,
+            capacity: 1000000,
+            reservoir_key,
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 0,
+            scarcity_curve: vec![],
+            smoothing_bps: 0,
+            smoothed_level: 0,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 0,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 0,
+            alert_level_high: 0,
+            alert_level_low: 0,
+        };
+        let mut data = Vec::new();
+        anchor_lang::AccountSerialize::try_serialize(&reservoir, &mut data).unwrap();
+
+        assert_eq!(
+            &data[RESERVOIR_KEY_OFFSET..RESERVOIR_KEY_OFFSET + 32],
+            reservoir_key.as_ref()
+        );
+    }
+
+    #[test]
+    fn test_reservoir_daily_stats_json() {
+        let reservoir_key = Pubkey::new_unique();
+        let mut daily_stats = ReservoirDailyStats {
+            reservoir_key,
+            days: [DailyUsage { day: 0, volume: 0 }; RESERVOIR_DAILY_STATS_LEN],
+        };
+        daily_stats.record(20031, 30000);
+        daily_stats.record(20032, 5000);
+
+        let mut data = Vec::new();
+        anchor_lang::AccountSerialize::try_serialize(&daily_stats, &mut data).unwrap();
+        assert_eq!(
+            &data[DAILY_STATS_RESERVOIR_KEY_OFFSET..DAILY_STATS_RESERVOIR_KEY_OFFSET + 32],
+            reservoir_key.as_ref()
+        );
+
+        // Days are listed in order, whatever their position in the ring buffer
+        let value = reservoir_daily_stats_json(&daily_stats);
+        assert_eq!(value["days"][0]["day"], 20031);
+        assert_eq!(value["days"][1]["volume"], 5000);
+        assert_eq!(value["days"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_spill_record_json() {
+        let reservoir_key = Pubkey::new_unique();
+        let mut spill_record = SpillRecord {
+            reservoir_key,
+            spill_count: 0,
+            total_spilled: 0,
+            last_spilled: 0,
+            last_spill_slot: 0,
+        };
+        spill_record.record(60000, 120);
+
+        let mut data = Vec::new();
+        anchor_lang::AccountSerialize::try_serialize(&spill_record, &mut data).unwrap();
+        assert_eq!(
+            &data[SPILL_RESERVOIR_KEY_OFFSET..SPILL_RESERVOIR_KEY_OFFSET + 32],
+            reservoir_key.as_ref()
+        );
+
+        let value = spill_record_json(&spill_record);
+        assert_eq!(value["total_spilled"], 60000);
+        assert_eq!(value["last_spill_slot"], 120);
+    }
+
+    #[test]
+    fn test_hydro_archive_json() {
+        let reservoir_key = Pubkey::new_unique();
+        let shard = |day: u64| HydroArchiveShard {
+            reservoir_key,
+            shard: HydroArchiveShard::shard_of(day),
+            payer: Pubkey::default(),
+            days: [HydroDay::default(); HYDRO_ARCHIVE_SHARD_DAYS],
+        };
+        let mut later = shard(20035);
+        later.record(20035, 790000, 0, 3000);
+        let mut earlier = shard(20031);
+        earlier.record(20031, 800000, 1000, 4000);
+
+        let mut data = Vec::new();
+        anchor_lang::AccountSerialize::try_serialize(&earlier, &mut data).unwrap();
+        assert_eq!(
+            &data[ARCHIVE_RESERVOIR_KEY_OFFSET..ARCHIVE_RESERVOIR_KEY_OFFSET + 32],
+            reservoir_key.as_ref()
+        );
+
+        // Days are listed in order across shards, skipping days never recorded
+        let value = hydro_archive_json(&reservoir_key, &[later, earlier]);
+        assert_eq!(value["days"].as_array().unwrap().len(), 2);
+        assert_eq!(value["days"][0]["day"], 20031);
+        assert_eq!(value["days"][1]["outflow"], 3000);
+    }
+}
@@ -0,0 +1,299 @@
+use crate::accounts::AccountReader;
+use anchor_lang::{prelude::Pubkey, AnchorDeserialize, Discriminator};
+use aquachain::{
+    events::{PaymentReceived, WaterBilled},
+    state::Consumer,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_client::{
+    rpc_client::GetConfirmedSignaturesForAddress2Config, rpc_config::RpcTransactionConfig,
+};
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+use std::{fs, path::Path, str::FromStr};
+
+/// Prefix of the log lines Anchor writes emitted events to
+const PROGRAM_DATA: &str = "Program data: ";
+/// Maximum number of signatures requested per page of program history
+const SIGNATURE_PAGE: usize = 1000;
+
+/// Column schemas of the exported files. Columns are only ever appended, so
+/// downstream reconciliation jobs can rely on their positions.
+pub const CONSUMER_COLUMNS: &[&str] = &[
+    "pubkey",
+    "owner",
+    "assigned_tariff",
+    "assigned_reservoir",
+    "contracted_capacity",
+    "block_rate",
+    "outstanding_water_debt",
+    "debt_since_slot",
+    "average_usage",
+    "budget_billing",
+    "budget_amount",
+    "budget_paid",
+];
+pub const USAGE_COLUMNS: &[&str] = &[
+    "signature",
+    "slot",
+    "consumer",
+    "volume",
+    "charge",
+    "outstanding",
+];
+pub const PAYMENT_COLUMNS: &[&str] = &["signature", "slot", "consumer", "amount", "outstanding"];
+
+/// A billing period, as an inclusive range of slots
+#[derive(Debug, PartialEq)]
+pub struct Period {
+    pub from_slot: u64,
+    pub to_slot: u64,
+}
+
+impl Period {
+    /// Parses a `<from_slot>..<to_slot>` range
+    pub fn parse(range: &str) -> Option<Self> {
+        let (from, to) = range.split_once("..")?;
+        let period = Period {
+            from_slot: from.parse().ok()?,
+            to_slot: to.parse().ok()?,
+        };
+        (period.from_slot <= period.to_slot).then_some(period)
+    }
+
+    pub fn contains(&self, slot: u64) -> bool {
+        (self.from_slot..=self.to_slot).contains(&slot)
+    }
+}
+
+/// A table with a fixed column schema, written out as CSV
+pub struct Table {
+    columns: &'static [&'static str],
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(columns: &'static [&'static str]) -> Self {
+        Self {
+            columns,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Appends a row, which must match the column schema
+    pub fn push(&mut self, row: Vec<String>) {
+        assert_eq!(row.len(), self.columns.len(), "row does not match schema");
+        self.rows.push(row);
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = self.columns.join(",");
+        csv.push('\n');
+        for row in &self.rows {
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+pub fn consumer_row(pubkey: &Pubkey, consumer: &Consumer) -> Vec<String> {
+    vec![
+        pubkey.to_string(),
+        consumer.owner.to_string(),
+        consumer.assigned_tariff.to_string(),
+        consumer.assigned_reservoir.to_string(),
+        consumer.contracted_capacity.to_string(),
+        consumer.block_rate.to_string(),
+        consumer.outstanding_water_debt.to_string(),
+        consumer.debt_since_slot.to_string(),
+        consumer.average_usage.to_string(),
+        consumer.budget_billing.to_string(),
+        consumer.budget_amount.to_string(),
+        consumer.budget_paid.to_string(),
+    ]
+}
+
+/// Decodes an event of type `T` from a single log line, if it carries one
+fn decode_event<T: AnchorDeserialize + Discriminator>(line: &str) -> Option<T> {
+    let data = STANDARD.decode(line.strip_prefix(PROGRAM_DATA)?).ok()?;
+    if data.len() < 8 || data[..8] != T::DISCRIMINATOR[..] {
+        return None;
+    }
+    T::try_from_slice(&data[8..]).ok()
+}
+
+/// Adds the usage and payment events of one transaction's logs to their tables
+pub fn collect_events(
+    signature: &str,
+    logs: &[String],
+    period: &Period,
+    usage: &mut Table,
+    payments: &mut Table,
+) {
+    for line in logs {
+        if let Some(event) = decode_event::<WaterBilled>(line) {
+            if period.contains(event.slot) {
+                usage.push(vec![
+                    signature.to_string(),
+                    event.slot.to_string(),
+                    event.consumer.to_string(),
+                    event.volume.to_string(),
+                    event.charge.to_string(),
+                    event.outstanding.to_string(),
+                ]);
+            }
+        } else if let Some(event) = decode_event::<PaymentReceived>(line) {
+            if period.contains(event.slot) {
+                payments.push(vec![
+                    signature.to_string(),
+                    event.slot.to_string(),
+                    event.consumer.to_string(),
+                    event.amount.to_string(),
+                    event.outstanding.to_string(),
+                ]);
+            }
+        }
+    }
+}
+
+/// Exports consumers, usage and payments of a billing period to CSV files
+///
+/// Consumers are a snapshot of the current accounts; usage and payments are the
+/// `WaterBilled` and `PaymentReceived` events emitted within the period, read from
+/// the program's transaction history. Files are written to `out_dir` as
+/// `consumers.csv`, `usage.csv` and `payments.csv`.
+pub fn export(reader: &AccountReader, period: &Period, out_dir: &Path) -> Result<(), String> {
+    let mut consumers = Table::new(CONSUMER_COLUMNS);
+    for (pubkey, consumer) in reader.consumer_accounts()? {
+        consumers.push(consumer_row(&pubkey, &consumer));
+    }
+
+    let mut usage = Table::new(USAGE_COLUMNS);
+    let mut payments = Table::new(PAYMENT_COLUMNS);
+    let tx_config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Json),
+        commitment: None,
+        max_supported_transaction_version: Some(0),
+    };
+
+    // Walk the program's history backwards until the start of the period
+    let mut before = None;
+    'pages: loop {
+        let page = reader
+            .rpc()
+            .get_signatures_for_address_with_config(
+                &aquachain::ID,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    limit: Some(SIGNATURE_PAGE),
+                    ..Default::default()
+                },
+            )
+            .map_err(|err| err.to_string())?;
+
+        for status in &page {
+            if status.slot < period.from_slot {
+                break 'pages;
+            }
+            if status.slot > period.to_slot || status.err.is_some() {
+                continue;
+            }
+            let signature =
+                Signature::from_str(&status.signature).map_err(|err| err.to_string())?;
+            let transaction = reader
+                .rpc()
+                .get_transaction_with_config(&signature, tx_config)
+                .map_err(|err| err.to_string())?;
+            let logs: Option<Vec<String>> = transaction
+                .transaction
+                .meta
+                .and_then(|meta| meta.log_messages.into());
+            collect_events(
+                &status.signature,
+                &logs.unwrap_or_default(),
+                period,
+                &mut usage,
+                &mut payments,
+            );
+        }
+
+        match page.last() {
+            Some(last) if page.len() == SIGNATURE_PAGE => {
+                before = Some(Signature::from_str(&last.signature).map_err(|err| err.to_string())?)
+            }
+            _ => break,
+        }
+    }
+
+    fs::create_dir_all(out_dir).map_err(|err| err.to_string())?;
+    for (name, table) in [
+        ("consumers.csv", &consumers),
+        ("usage.csv", &usage),
+        ("payments.csv", &payments),
+    ] {
+        fs::write(out_dir.join(name), table.to_csv()).map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Event;
+    use aquachain::state::BillBreakdown;
+
+    #[test]
+    fn test_parse_period() {
+        assert_eq!(
+            Period::parse("100..200"),
+            Some(Period {
+                from_slot: 100,
+                to_slot: 200
+            })
+        );
+        assert_eq!(Period::parse("200..100"), None);
+        assert_eq!(Period::parse("100"), None);
+    }
+
+    #[test]
+    fn test_collect_events_in_period() {
+        let consumer = Pubkey::new_unique();
+        let billed = WaterBilled {
+            consumer,
+            volume: 10000,
+            charge: 5000,
+            outstanding: 5000,
+            breakdown: BillBreakdown::default(),
+            slot: 150,
+        };
+        let late_payment = PaymentReceived {
+            consumer,
+            amount: 5000,
+            outstanding: 0,
+            slot: 250,
+        };
+        let logs = vec![
+            "Program log: Instruction: UseWater".to_string(),
+            format!("{}{}", PROGRAM_DATA, STANDARD.encode(billed.data())),
+            format!("{}{}", PROGRAM_DATA, STANDARD.encode(late_payment.data())),
+        ];
+        let period = Period::parse("100..200").unwrap();
+
+        let mut usage = Table::new(USAGE_COLUMNS);
+        let mut payments = Table::new(PAYMENT_COLUMNS);
+        collect_events("sig", &logs, &period, &mut usage, &mut payments);
+
+        assert_eq!(
+            usage.to_csv(),
+            format!(
+                "signature,slot,consumer,volume,charge,outstanding\nsig,150,{},10000,5000,5000\n",
+                consumer
+            )
+        );
+        assert_eq!(
+            payments.to_csv(),
+            "signature,slot,consumer,amount,outstanding\n"
+        );
+    }
+}
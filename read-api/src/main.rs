@@ -0,0 +1,136 @@
+//! Read-only JSON API over AquaChain program accounts.
+//!
+//! Serves typed views of consumers, tariffs and reservoirs decoded directly from
+//! getProgramAccounts, so frontends can read program state without anchor-ts.
+//!
+//! # Endpoints
+//! * `GET /consumers/{pubkey}`
+//! * `GET /tariffs`
+//! * `GET /tariffs/{tariff_key}`
+//! * `GET /tariffs/{tariff_key}/history`
+//! * `GET /reservoirs/{reservoir_key}`
+//! * `GET /reservoirs/{reservoir_key}/daily`
+//! * `GET /reservoirs/{reservoir_key}/spills`
+//! * `GET /reservoirs/{reservoir_key}/archive`
+//! * `GET /agencies/{agency}/stats`
+//! * `GET /metrics` - Request counters in the Prometheus text format
+//! * `GET /healthz` - Liveness check
+//!
+//! # Export
+//! `aquachain-api export <from_slot>..<to_slot> <out_dir>` writes the consumers, usage
+//! and payments of a billing period to CSV files instead of serving requests.
+//!
+//! # Environment
+//! * `AQUACHAIN_RPC_URL` - RPC endpoint (default `http://127.0.0.1:8899`)
+//! * `AQUACHAIN_API_ADDR` - Address to listen on (default `0.0.0.0:8080`)
+
+mod accounts;
+mod export;
+mod routes;
+
+use accounts::AccountReader;
+use aquachain_telemetry::{respond, Telemetry};
+use export::Period;
+use routes::Route;
+use serde_json::{json, Value};
+use std::{env, path::Path, process};
+use tiny_http::{Header, Method, Response, Server};
+
+const DEFAULT_RPC_URL: &str = "http://127.0.0.1:8899";
+const DEFAULT_ADDR: &str = "0.0.0.0:8080";
+
+fn main() {
+    let rpc_url = env::var("AQUACHAIN_RPC_URL").unwrap_or_else(|_| DEFAULT_RPC_URL.to_string());
+    let addr = env::var("AQUACHAIN_API_ADDR").unwrap_or_else(|_| DEFAULT_ADDR.to_string());
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("export") {
+        run_export(AccountReader::new(rpc_url), &args[1..]);
+        return;
+    }
+
+    let server = Server::http(&addr).unwrap_or_else(|err| {
+        eprintln!("Failed to listen on {}: {}", addr, err);
+        process::exit(1);
+    });
+    let reader = AccountReader::new(rpc_url);
+    let telemetry = Telemetry::new();
+    telemetry.describe(
+        "aquachain_api_requests_total",
+        "Requests served, by status code",
+    );
+    println!("Serving AquaChain accounts on {}", addr);
+
+    for request in server.incoming_requests() {
+        let (status, content_type, body) = if *request.method() != Method::Get {
+            (
+                405,
+                "application/json",
+                json!({ "error": "Method not allowed" }).to_string(),
+            )
+        } else if let Some(response) = respond(&telemetry, request.url()) {
+            response
+        } else {
+            let (status, body) = handle(&reader, Route::parse(request.url()));
+            telemetry.inc(
+                "aquachain_api_requests_total",
+                &[("status", &status.to_string())],
+            );
+            (status, "application/json", body.to_string())
+        };
+
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(
+                Header::from_bytes("Content-Type", content_type).expect("static header is valid"),
+            );
+        if let Err(err) = request.respond(response) {
+            eprintln!("Failed to send response: {}", err);
+        }
+    }
+}
+
+/// Runs the `export` subcommand, exiting with an error on bad arguments or failures
+fn run_export(reader: AccountReader, args: &[String]) {
+    let (period, out_dir) = match args {
+        [range, out_dir] => match Period::parse(range) {
+            Some(period) => (period, Path::new(out_dir)),
+            None => {
+                eprintln!("Invalid period {}, expected <from_slot>..<to_slot>", range);
+                process::exit(1);
+            }
+        },
+        _ => {
+            eprintln!("Usage: aquachain-api export <from_slot>..<to_slot> <out_dir>");
+            process::exit(1);
+        }
+    };
+
+    if let Err(err) = export::export(&reader, &period, out_dir) {
+        eprintln!("Export failed: {}", err);
+        process::exit(1);
+    }
+    println!("Exported billing data to {}", out_dir.display());
+}
+
+/// Resolves a route into a status code and JSON body
+fn handle(reader: &AccountReader, route: Route) -> (u16, Value) {
+    let result = match route {
+        Route::Consumer(pubkey) => reader.consumer(&pubkey),
+        Route::Tariffs => reader.tariffs().map(Some),
+        Route::Tariff(tariff_key) => reader.tariff(&tariff_key),
+        Route::TariffHistory(tariff_key) => reader.tariff_history(&tariff_key),
+        Route::Reservoir(reservoir_key) => reader.reservoir(&reservoir_key),
+        Route::ReservoirDailyStats(reservoir_key) => reader.reservoir_daily_stats(&reservoir_key),
+        Route::SpillRecord(reservoir_key) => reader.spill_record(&reservoir_key),
+        Route::HydroArchive(reservoir_key) => reader.hydro_archive(&reservoir_key),
+        Route::AgencyStats(agency) => reader.agency_stats(&agency),
+        Route::NotFound => Ok(None),
+    };
+
+    match result {
+        Ok(Some(body)) => (200, body),
+        Ok(None) => (404, json!({ "error": "Not found" })),
+        Err(err) => (502, json!({ "error": err })),
+    }
+}
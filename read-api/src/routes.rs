@@ -0,0 +1,107 @@
+use anchor_lang::prelude::Pubkey;
+use std::str::FromStr;
+
+/// A parsed request path
+#[derive(Debug, PartialEq)]
+pub enum Route {
+    /// `GET /consumers/{pubkey}`
+    Consumer(Pubkey),
+    /// `GET /tariffs`
+    Tariffs,
+    /// `GET /tariffs/{tariff_key}`
+    Tariff(Pubkey),
+    /// `GET /tariffs/{tariff_key}/history`
+    TariffHistory(Pubkey),
+    /// `GET /reservoirs/{reservoir_key}`
+    Reservoir(Pubkey),
+    /// `GET /reservoirs/{reservoir_key}/daily`
+    ReservoirDailyStats(Pubkey),
+    /// `GET /reservoirs/{reservoir_key}/spills`
+    SpillRecord(Pubkey),
+    /// `GET /reservoirs/{reservoir_key}/archive`
+    HydroArchive(Pubkey),
+    /// `GET /agencies/{agency}/stats`
+    AgencyStats(Pubkey),
+    NotFound,
+}
+
+impl Route {
+    /// Parses a request URL, ignoring any query string and trailing slash
+    pub fn parse(url: &str) -> Self {
+        let path = url.split('?').next().unwrap_or_default();
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+        let key = |segment: &str| Pubkey::from_str(segment).ok();
+        match segments.as_slice() {
+            ["consumers", pubkey] => key(pubkey).map_or(Route::NotFound, Route::Consumer),
+            ["tariffs"] => Route::Tariffs,
+            ["tariffs", tariff_key] => key(tariff_key).map_or(Route::NotFound, Route::Tariff),
+            ["tariffs", tariff_key, "history"] => {
+                key(tariff_key).map_or(Route::NotFound, Route::TariffHistory)
+            }
+            ["reservoirs", reservoir_key] => {
+                key(reservoir_key).map_or(Route::NotFound, Route::Reservoir)
+            }
+            ["reservoirs", reservoir_key, "daily"] => {
+                key(reservoir_key).map_or(Route::NotFound, Route::ReservoirDailyStats)
+            }
+            ["reservoirs", reservoir_key, "spills"] => {
+                key(reservoir_key).map_or(Route::NotFound, Route::SpillRecord)
+            }
+            ["reservoirs", reservoir_key, "archive"] => {
+                key(reservoir_key).map_or(Route::NotFound, Route::HydroArchive)
+            }
+            ["agencies", agency, "stats"] => {
+                key(agency).map_or(Route::NotFound, Route::AgencyStats)
+            }
+            _ => Route::NotFound,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_routes() {
+        let key = Pubkey::new_unique();
+
+        assert_eq!(
+            Route::parse(&format!("/consumers/{}", key)),
+            Route::Consumer(key)
+        );
+        assert_eq!(Route::parse("/tariffs/"), Route::Tariffs);
+        assert_eq!(Route::parse("/tariffs?limit=10"), Route::Tariffs);
+        assert_eq!(
+            Route::parse(&format!("/tariffs/{}/history", key)),
+            Route::TariffHistory(key)
+        );
+        assert_eq!(
+            Route::parse(&format!("/reservoirs/{}", key)),
+            Route::Reservoir(key)
+        );
+        assert_eq!(
+            Route::parse(&format!("/reservoirs/{}/daily", key)),
+            Route::ReservoirDailyStats(key)
+        );
+        assert_eq!(
+            Route::parse(&format!("/reservoirs/{}/spills", key)),
+            Route::SpillRecord(key)
+        );
+        assert_eq!(
+            Route::parse(&format!("/reservoirs/{}/archive", key)),
+            Route::HydroArchive(key)
+        );
+        assert_eq!(
+            Route::parse(&format!("/agencies/{}/stats", key)),
+            Route::AgencyStats(key)
+        );
+    }
+
+    #[test]
+    fn test_invalid_routes() {
+        assert_eq!(Route::parse("/consumers/not-a-key"), Route::NotFound);
+        assert_eq!(Route::parse("/meters"), Route::NotFound);
+    }
+}
@@ -0,0 +1,105 @@
+use aquachain::state::TariffType;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::path::Path;
+
+/// One metered reading of a consumer's historical usage
+///
+/// # Fields
+/// * `consumer` - Identifier of the consumer in the source data
+/// * `period` - Billing period the reading belongs to
+/// * `usage` - Raw volume used during the period
+/// * `contracted_capacity` - Raw volume covered by the consumer's WATC at the start of the period
+/// * `household_income` - Household income for the period in currency base units, if known
+#[derive(Debug, Clone, Deserialize)]
+pub struct UsageRecord {
+    pub consumer: String,
+    pub period: u32,
+    pub usage: u64,
+    pub contracted_capacity: u64,
+    pub household_income: Option<u64>,
+}
+
+/// Level of the reservoir during one billing period
+///
+/// # Fields
+/// * `period` - Billing period of the observation
+/// * `level` - Raw reservoir level, at most `capacity`
+/// * `capacity` - Raw reservoir capacity
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReservoirRecord {
+    pub period: u32,
+    pub level: u64,
+    pub capacity: u64,
+}
+
+/// A candidate tariff to backtest
+///
+/// # Fields
+/// * `name` - Name of the design in the report
+/// * `tariff_type` - One of `uniform_ibt`, `seasonal_ibt` or `seasonal_dbt`
+/// * `water_rate` - Water rate, with three implied decimals as on-chain
+/// * `block_rate` - Block rate applied to every consumer, with three implied decimals
+#[derive(Debug, Clone, Deserialize)]
+pub struct TariffDesign {
+    pub name: String,
+    pub tariff_type: String,
+    pub water_rate: u64,
+    pub block_rate: u64,
+}
+
+impl TariffDesign {
+    /// Returns the on-chain tariff type of the design
+    pub fn tariff_type(&self) -> Result<TariffType, String> {
+        match self.tariff_type.as_str() {
+            "uniform_ibt" => Ok(TariffType::UniformIBT),
+            "seasonal_ibt" => Ok(TariffType::SeasonalIBT),
+            "seasonal_dbt" => Ok(TariffType::SeasonalDBT),
+            other => Err(format!("{}: unknown tariff type {}", self.name, other)),
+        }
+    }
+}
+
+/// Loads every record of a CSV file with a header row
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>, String> {
+    let mut reader = csv::Reader::from_path(path)
+        .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+    reader
+        .deserialize()
+        .collect::<Result<Vec<T>, _>>()
+        .map_err(|err| format!("Invalid record in {}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tariff_type() {
+        let design = TariffDesign {
+            name: "dry season".to_string(),
+            tariff_type: "seasonal_ibt".to_string(),
+            water_rate: 500,
+            block_rate: 800,
+        };
+        assert_eq!(design.tariff_type(), Ok(TariffType::SeasonalIBT));
+
+        let invalid = TariffDesign {
+            tariff_type: "flat".to_string(),
+            ..design
+        };
+        assert!(invalid.tariff_type().is_err());
+    }
+
+    #[test]
+    fn test_deserialize_usage_without_income() {
+        let data =
+            "consumer,period,usage,contracted_capacity,household_income\nA,1,120000,100000,\n";
+        let records: Vec<UsageRecord> = csv::Reader::from_reader(data.as_bytes())
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(records[0].usage, 120000);
+        assert_eq!(records[0].household_income, None);
+    }
+}
@@ -0,0 +1,8 @@
+//! Backtesting of tariff designs against historical consumption.
+//!
+//! Historical readings and reservoir levels are billed through the program's own pricing
+//! function, so each design's revenue, affordability and conservation metrics are exactly
+//! what it would have produced on-chain.
+
+pub mod input;
+pub mod simulation;
@@ -0,0 +1,75 @@
+//! Command-line tariff backtester.
+//!
+//! ```text
+//! aquachain-sim <usage.csv> <reservoir.csv> <designs.csv> [volume_scale] [currency_decimals]
+//! ```
+//!
+//! # Inputs
+//! * `usage.csv` - `consumer,period,usage,contracted_capacity,household_income`
+//! * `reservoir.csv` - `period,level,capacity`
+//! * `designs.csv` - `name,tariff_type,water_rate,block_rate`
+//!
+//! Units default to a volume scale of `1000` and `3` currency decimals.
+
+use aquachain::state::UnitConfig;
+use aquachain_sim::{
+    input::{load, ReservoirRecord, TariffDesign, UsageRecord},
+    simulation::{simulate, AFFORDABILITY_THRESHOLD_BPS},
+};
+use std::{env, path::Path, process};
+
+const USAGE: &str = "Usage: aquachain-sim <usage.csv> <reservoir.csv> <designs.csv> [volume_scale] [currency_decimals]";
+
+fn run(args: &[String]) -> Result<(), String> {
+    let [usage_path, trace_path, designs_path, rest @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+    let units = UnitConfig {
+        volume_scale: rest
+            .first()
+            .map_or(Ok(1000), |v| v.parse())
+            .map_err(|_| USAGE)?,
+        currency_decimals: rest
+            .get(1)
+            .map_or(Ok(3), |v| v.parse())
+            .map_err(|_| USAGE)?,
+    };
+    if !units.is_valid() {
+        return Err("Invalid unit configuration".to_string());
+    }
+
+    let usage: Vec<UsageRecord> = load(Path::new(usage_path))?;
+    let trace: Vec<ReservoirRecord> = load(Path::new(trace_path))?;
+    let designs: Vec<TariffDesign> = load(Path::new(designs_path))?;
+
+    println!(
+        "{:<20} {:>14} {:>12} {:>12} {:>14} {:>14}",
+        "design",
+        "revenue",
+        "avg price",
+        "low price",
+        "excess usage",
+        format!("bills > {}%", AFFORDABILITY_THRESHOLD_BPS as f64 / 100.0)
+    );
+    for design in &designs {
+        let report = simulate(design, &usage, &trace, &units)?;
+        println!(
+            "{:<20} {:>14.3} {:>12.3} {:>12.3} {:>13.1}% {:>13.1}%",
+            report.design,
+            report.revenue as f64 / 10f64.powi(units.currency_decimals as i32),
+            report.average_price(&units),
+            report.low_reservoir_price(&units),
+            report.excess_share() * 100.0,
+            report.unaffordable_share() * 100.0,
+        );
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(err) = run(&args) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
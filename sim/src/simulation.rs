@@ -0,0 +1,248 @@
+use crate::input::{ReservoirRecord, TariffDesign, UsageRecord};
+use aquachain::{
+    pricing::{calculate_total_cost, ChargeBounds, FixedPoint},
+    state::{Reservoir, UnitConfig},
+};
+use std::collections::HashMap;
+
+/// Share of household income above which a bill is considered unaffordable, in basis points
+pub const AFFORDABILITY_THRESHOLD_BPS: u64 = 300;
+
+/// Outcome of running a tariff design over historical usage
+///
+/// # Fields
+/// * `design` - Name of the tariff design
+/// * `revenue` - Total billed, in currency base units
+/// * `usage` - Total raw volume billed
+/// * `excess_usage` - Raw volume billed above contracted capacity, at the block rate
+/// * `low_reservoir_revenue` - Revenue billed while the reservoir was low
+/// * `low_reservoir_usage` - Raw volume billed while the reservoir was low
+/// * `bills` - Number of consumer bills
+/// * `bills_with_income` - Number of bills whose household income is known
+/// * `unaffordable_bills` - Bills above `AFFORDABILITY_THRESHOLD_BPS` of household income
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Report {
+    pub design: String,
+    pub revenue: u64,
+    pub usage: u64,
+    pub excess_usage: u64,
+    pub low_reservoir_revenue: u64,
+    pub low_reservoir_usage: u64,
+    pub bills: u64,
+    pub bills_with_income: u64,
+    pub unaffordable_bills: u64,
+}
+
+impl Report {
+    /// Share of usage billed at the block rate, the volume the tariff is meant to deter
+    pub fn excess_share(&self) -> f64 {
+        ratio(self.excess_usage, self.usage)
+    }
+
+    /// Share of bills with known income that exceed the affordability threshold
+    pub fn unaffordable_share(&self) -> f64 {
+        ratio(self.unaffordable_bills, self.bills_with_income)
+    }
+
+    /// Average price per billed unit of volume, in currency units
+    pub fn average_price(&self, units: &UnitConfig) -> f64 {
+        price(self.revenue, self.usage, units)
+    }
+
+    /// Average price per billed unit of volume while the reservoir was low, in currency units
+    pub fn low_reservoir_price(&self, units: &UnitConfig) -> f64 {
+        price(self.low_reservoir_revenue, self.low_reservoir_usage, units)
+    }
+}
+
+fn ratio(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+fn price(revenue: u64, usage: u64, units: &UnitConfig) -> f64 {
+    let currency = revenue as f64 / 10f64.powi(units.currency_decimals as i32);
+    let volume = usage as f64 / units.volume_scale as f64;
+    if volume == 0.0 {
+        0.0
+    } else {
+        currency / volume
+    }
+}
+
+/// Bills every reading under a tariff design, exactly as `use_water` would on-chain
+///
+/// Each period is a billing cycle: the consumer starts it with WATC equal to its contracted
+/// capacity, and the reading is priced against the reservoir level of that period.
+///
+/// # Arguments
+/// * `design` - The tariff design to backtest
+/// * `usage` - Historical readings, one per consumer and period
+/// * `trace` - Reservoir level of each period
+/// * `units` - Unit configuration used to convert volumes and costs
+///
+/// # Errors
+/// * If the design's tariff type is unknown, a period has no reservoir level, a level
+///   exceeds its capacity or a conversion overflows
+pub fn simulate(
+    design: &TariffDesign,
+    usage: &[UsageRecord],
+    trace: &[ReservoirRecord],
+    units: &UnitConfig,
+) -> Result<Report, String> {
+    let tariff_type = design.tariff_type()?;
+    let reservoirs: HashMap<u32, &ReservoirRecord> =
+        trace.iter().map(|record| (record.period, record)).collect();
+    let convert = |err: anchor_lang::error::Error| err.to_string();
+
+    let mut report = Report {
+        design: design.name.clone(),
+        ..Default::default()
+    };
+    for record in usage {
+        let reservoir = reservoirs
+            .get(&record.period)
+            .ok_or_else(|| format!("No reservoir level for period {}", record.period))?;
+        if reservoir.level > reservoir.capacity {
+            return Err(format!(
+                "Reservoir level exceeds capacity in period {}",
+                record.period
+            ));
+        }
+
+        let bill = units
+            .to_currency(calculate_total_cost(
+                units
+                    .to_volume(record.contracted_capacity)
+                    .map_err(convert)?,
+                units.to_volume(record.usage).map_err(convert)?,
+                FixedPoint::from(design.water_rate),
+                tariff_type,
+                FixedPoint::from(design.block_rate),
+                units.to_volume(reservoir.capacity).map_err(convert)?,
+                units.to_volume(reservoir.level).map_err(convert)?,
+                &ChargeBounds::default(),
+            ))
+            .map_err(convert)?;
+
+        report.revenue += bill;
+        report.usage += record.usage;
+        report.excess_usage += record.usage.saturating_sub(record.contracted_capacity);
+        report.bills += 1;
+
+        let is_low = Reservoir {
+            current_level: reservoir.level,
+            capacity: reservoir.capacity,
+            reservoir_key: Default::default(),
+            assigned_consumer_count: 0,
+            zone_geohash: [0; 12],
+            basin_id: 0,
+            pumping_intensity: 0,
+            emissions_factor: 0,
+            scarcity_curve: vec![],
+            smoothing_bps: 0,
+            smoothed_level: reservoir.level,
+            telemetry_authority: None,
+            max_level_rate: 0,
+            level_updated_slot: 0,
+            max_change_bps_per_hour: 0,
+            level_updated_at: 0,
+            alert_level_high: 0,
+            alert_level_low: 0,
+        }
+        .is_low();
+        if is_low {
+            report.low_reservoir_revenue += bill;
+            report.low_reservoir_usage += record.usage;
+        }
+
+        if let Some(income) = record.household_income {
+            report.bills_with_income += 1;
+            if (bill as u128) * 10_000 > (income as u128) * (AFFORDABILITY_THRESHOLD_BPS as u128) {
+                report.unaffordable_bills += 1;
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNITS: UnitConfig = UnitConfig {
+        volume_scale: 1000,
+        currency_decimals: 3,
+    };
+
+    fn design(tariff_type: &str) -> TariffDesign {
+        TariffDesign {
+            name: tariff_type.to_string(),
+            tariff_type: tariff_type.to_string(),
+            water_rate: 500,
+            block_rate: 800,
+        }
+    }
+
+    fn reading(period: u32, usage: u64, household_income: Option<u64>) -> UsageRecord {
+        UsageRecord {
+            consumer: "A".to_string(),
+            period,
+            usage,
+            contracted_capacity: 100000,
+            household_income,
+        }
+    }
+
+    fn trace() -> Vec<ReservoirRecord> {
+        vec![
+            ReservoirRecord {
+                period: 1,
+                level: 950000,
+                capacity: 1000000,
+            },
+            ReservoirRecord {
+                period: 2,
+                level: 100000,
+                capacity: 1000000,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_matches_on_chain_pricing() {
+        let usage = vec![reading(1, 100000, None), reading(1, 120000, None)];
+        let report = simulate(&design("uniform_ibt"), &usage, &trace(), &UNITS).unwrap();
+
+        // Same charges as the use_water pricing tests: 50.000 and 66.000
+        assert_eq!(report.revenue, 50000 + 66000);
+        assert_eq!(report.excess_usage, 20000);
+        assert_eq!(report.bills, 2);
+        assert_eq!(report.low_reservoir_usage, 0);
+    }
+
+    #[test]
+    fn test_low_reservoir_and_affordability() {
+        let usage = vec![
+            reading(2, 80000, Some(2_000_000)),
+            reading(1, 80000, Some(1_000_000)),
+        ];
+        let report = simulate(&design("uniform_ibt"), &usage, &trace(), &UNITS).unwrap();
+
+        // Each bill is 40.000, above 3% of 1000.000 but not of 2000.000
+        assert_eq!(report.low_reservoir_revenue, 40000);
+        assert_eq!(report.low_reservoir_usage, 80000);
+        assert_eq!(report.unaffordable_bills, 1);
+        assert_eq!(report.unaffordable_share(), 0.5);
+        assert_eq!(report.average_price(&UNITS), 0.5);
+    }
+
+    #[test]
+    fn test_missing_reservoir_period() {
+        let usage = vec![reading(3, 1000, None)];
+        assert!(simulate(&design("uniform_ibt"), &usage, &trace(), &UNITS).is_err());
+    }
+}
@@ -0,0 +1,233 @@
+//! Prometheus metrics and health endpoints shared by the AquaChain services.
+//!
+//! Each service keeps a [`Telemetry`] registry of counters and serves it, either on a
+//! dedicated port with [`serve`] or from its own HTTP server with [`respond`]:
+//! * `GET /metrics` - Counters in the Prometheus text exposition format
+//! * `GET /healthz` - `200 ok`, or `503` once the service stops sending heartbeats
+
+use std::{
+    collections::BTreeMap,
+    io,
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+use tiny_http::{Header, Response, Server};
+
+/// A counter series, identified by its metric name and label values
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Series {
+    name: &'static str,
+    labels: Vec<(&'static str, String)>,
+}
+
+impl Series {
+    fn new(name: &'static str, labels: &[(&'static str, &str)]) -> Self {
+        Self {
+            name,
+            labels: labels
+                .iter()
+                .map(|(label, value)| (*label, value.to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// Registry of the counters and liveness of a service
+///
+/// # Fields
+/// * `help` - Description of each metric, rendered as `# HELP` lines
+/// * `counters` - Current value of each counter series
+/// * `last_heartbeat` - When the service last reported making progress
+/// * `max_heartbeat_age` - Longest time without a heartbeat before the service is unhealthy
+#[derive(Default)]
+pub struct Telemetry {
+    help: Mutex<BTreeMap<&'static str, &'static str>>,
+    counters: Mutex<BTreeMap<Series, u64>>,
+    last_heartbeat: Mutex<Option<Instant>>,
+    max_heartbeat_age: Option<Duration>,
+}
+
+impl Telemetry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks the service unhealthy when no heartbeat is sent for `max_age`
+    pub fn with_max_heartbeat_age(mut self, max_age: Duration) -> Self {
+        self.max_heartbeat_age = Some(max_age);
+        self
+    }
+
+    /// Registers the description of a counter
+    pub fn describe(&self, name: &'static str, help: &'static str) {
+        self.help.lock().unwrap().insert(name, help);
+    }
+
+    /// Increments a counter series by one
+    pub fn inc(&self, name: &'static str, labels: &[(&'static str, &str)]) {
+        *self
+            .counters
+            .lock()
+            .unwrap()
+            .entry(Series::new(name, labels))
+            .or_default() += 1;
+    }
+
+    /// Returns the current value of a counter series
+    pub fn get(&self, name: &'static str, labels: &[(&'static str, &str)]) -> u64 {
+        self.counters
+            .lock()
+            .unwrap()
+            .get(&Series::new(name, labels))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Records that the service is making progress
+    pub fn heartbeat(&self) {
+        *self.last_heartbeat.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Returns false once the service has gone longer than its maximum heartbeat age
+    /// without a heartbeat. Services without a maximum age are always healthy.
+    pub fn is_healthy(&self) -> bool {
+        match self.max_heartbeat_age {
+            Some(max_age) => self
+                .last_heartbeat
+                .lock()
+                .unwrap()
+                .is_some_and(|last| last.elapsed() <= max_age),
+            None => true,
+        }
+    }
+
+    /// Renders every counter in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let help = self.help.lock().unwrap();
+        let counters = self.counters.lock().unwrap();
+
+        let mut output = String::new();
+        let mut current = None;
+        for (series, value) in counters.iter() {
+            if current != Some(series.name) {
+                if let Some(help) = help.get(series.name) {
+                    output.push_str(&format!("# HELP {} {}\n", series.name, help));
+                }
+                output.push_str(&format!("# TYPE {} counter\n", series.name));
+                current = Some(series.name);
+            }
+
+            let labels: Vec<String> = series
+                .labels
+                .iter()
+                .map(|(label, value)| format!("{}=\"{}\"", label, escape(value)))
+                .collect();
+            if labels.is_empty() {
+                output.push_str(&format!("{} {}\n", series.name, value));
+            } else {
+                output.push_str(&format!(
+                    "{}{{{}}} {}\n",
+                    series.name,
+                    labels.join(","),
+                    value
+                ));
+            }
+        }
+        output
+    }
+}
+
+/// Escapes a label value for the Prometheus text format
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Answers a telemetry request, returning `None` for paths that are not telemetry endpoints
+///
+/// # Returns
+/// The status code, content type and body of the response
+pub fn respond(telemetry: &Telemetry, path: &str) -> Option<(u16, &'static str, String)> {
+    match path.split('?').next().unwrap_or_default() {
+        "/metrics" => Some((200, "text/plain; version=0.0.4", telemetry.render())),
+        "/healthz" if telemetry.is_healthy() => Some((200, "text/plain", "ok".to_string())),
+        "/healthz" => Some((503, "text/plain", "unhealthy".to_string())),
+        _ => None,
+    }
+}
+
+/// Serves the telemetry endpoints on a background thread
+///
+/// # Arguments
+/// * `telemetry` - The registry to serve
+/// * `addr` - Address to listen on, e.g. `0.0.0.0:9100`
+pub fn serve(telemetry: Arc<Telemetry>, addr: &str) -> io::Result<JoinHandle<()>> {
+    let server = Server::http(addr).map_err(io::Error::other)?;
+
+    Ok(thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let (status, content_type, body) = respond(&telemetry, request.url()).unwrap_or((
+                404,
+                "text/plain",
+                "not found".to_string(),
+            ));
+            let response = Response::from_string(body)
+                .with_status_code(status)
+                .with_header(
+                    Header::from_bytes("Content-Type", content_type)
+                        .expect("static header is valid"),
+                );
+            if let Err(err) = request.respond(response) {
+                eprintln!("Failed to send telemetry response: {}", err);
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters() {
+        let telemetry = Telemetry::new();
+        telemetry.describe("aquachain_cranks_fired_total", "Cranks sent successfully");
+        telemetry.inc("aquachain_cranks_fired_total", &[("job", "autopay")]);
+        telemetry.inc("aquachain_cranks_fired_total", &[("job", "autopay")]);
+        telemetry.inc("aquachain_restarts_total", &[]);
+
+        assert_eq!(
+            telemetry.get("aquachain_cranks_fired_total", &[("job", "autopay")]),
+            2
+        );
+        assert_eq!(
+            telemetry.render(),
+            "# HELP aquachain_cranks_fired_total Cranks sent successfully\n\
+             # TYPE aquachain_cranks_fired_total counter\n\
+             aquachain_cranks_fired_total{job=\"autopay\"} 2\n\
+             # TYPE aquachain_restarts_total counter\n\
+             aquachain_restarts_total 1\n"
+        );
+    }
+
+    #[test]
+    fn test_label_values_are_escaped() {
+        assert_eq!(escape("a\"b\\c"), "a\\\"b\\\\c");
+    }
+
+    #[test]
+    fn test_health_follows_heartbeats() {
+        assert!(Telemetry::new().is_healthy());
+
+        let telemetry = Telemetry::new().with_max_heartbeat_age(Duration::from_secs(60));
+        assert!(!telemetry.is_healthy());
+        telemetry.heartbeat();
+        assert!(telemetry.is_healthy());
+
+        assert_eq!(respond(&telemetry, "/healthz").unwrap().0, 200);
+        assert!(respond(&telemetry, "/consumers").is_none());
+    }
+}
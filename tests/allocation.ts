@@ -0,0 +1,273 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { createMint, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { createHash } from "crypto";
+import { assert } from "chai";
+
+// Leaf of an allocation tree: sha256(claimant || contracted_capacity as u64 LE)
+const leaf = (claimant: PublicKey, capacity: number) =>
+  createHash("sha256")
+    .update(claimant.toBuffer())
+    .update(new anchor.BN(capacity).toArrayLike(Buffer, "le", 8))
+    .digest();
+
+// Parent of two nodes, hashed in sorted order as on-chain
+const hashPair = (a: Buffer, b: Buffer) =>
+  createHash("sha256")
+    .update(
+      Buffer.compare(a, b) <= 0 ? Buffer.concat([a, b]) : Buffer.concat([b, a])
+    )
+    .digest();
+
+describe("allocation", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let treeKey: PublicKey;
+  let alice: Keypair;
+  let bob: Keypair;
+  let merkleRoot: Buffer;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const blockRate = 800; // 0.800
+  const aliceCapacity = 100000; // 100.000
+  const bobCapacity = 50000; // 50.000
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  before(async () => {
+    // Initialize accounts
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+    treeKey = Keypair.generate().publicKey;
+
+    alice = Keypair.generate();
+    bob = Keypair.generate();
+
+    // Claimants pay for their own accounts
+    for (const claimant of [alice, bob]) {
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(claimant.publicKey, LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+    }
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a tariff
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a reservoir
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    merkleRoot = hashPair(
+      leaf(alice.publicKey, aliceCapacity),
+      leaf(bob.publicKey, bobCapacity)
+    );
+  });
+
+  it("Agency escrows the allocations of a Merkle tree", async () => {
+    await program.methods
+      .createAllocationTree(
+        treeKey,
+        [...merkleRoot],
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(blockRate),
+        new anchor.BN(aliceCapacity + bobCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .rpc();
+
+    const [allocationTreePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("allocation_tree"),
+        wallet.publicKey.toBuffer(),
+        treeKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const vault = getAssociatedTokenAddressSync(
+      watcMint,
+      allocationTreePDA,
+      true
+    );
+    const vaultBalance = await connection.getTokenAccountBalance(vault);
+    assert.equal(
+      vaultBalance.value.amount,
+      String(aliceCapacity + bobCapacity)
+    );
+  });
+
+  it("Claimant registers a consumer with a valid proof", async () => {
+    const consumer = Keypair.generate();
+
+    await program.methods
+      .claimAllocation(treeKey, new anchor.BN(aliceCapacity), [
+        [...leaf(bob.publicKey, bobCapacity)],
+      ])
+      .accounts({
+        consumer: consumer.publicKey,
+        claimant: alice.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer, alice])
+      .rpc();
+
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(consumerAccount.owner.toBase58(), alice.publicKey.toBase58());
+    assert.equal(
+      consumerAccount.assignedTariff.toBase58(),
+      tariffKey.toBase58()
+    );
+    assert.equal(consumerAccount.contractedCapacity.toNumber(), aliceCapacity);
+    assert.equal(consumerAccount.blockRate.toNumber(), blockRate);
+
+    const consumerWatc = getAssociatedTokenAddressSync(
+      watcMint,
+      consumer.publicKey
+    );
+    const watcBalance = await connection.getTokenAccountBalance(consumerWatc);
+    assert.equal(watcBalance.value.amount, String(aliceCapacity));
+
+    // The claimant is counted on the tree's tariff and reservoir
+    const [tariffPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("tariff"),
+        wallet.publicKey.toBuffer(),
+        tariffKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [reservoirPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservoir"),
+        wallet.publicKey.toBuffer(),
+        reservoirKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const tariff = await program.account.tariff.fetch(tariffPDA);
+    const reservoir = await program.account.reservoir.fetch(reservoirPDA);
+    assert.equal(tariff.assignedConsumerCount.toNumber(), 1);
+    assert.equal(reservoir.assignedConsumerCount.toNumber(), 1);
+  });
+
+  it("An allocation cannot be claimed twice", async () => {
+    const consumer = Keypair.generate();
+    try {
+      await program.methods
+        .claimAllocation(treeKey, new anchor.BN(aliceCapacity), [
+          [...leaf(bob.publicKey, bobCapacity)],
+        ])
+        .accounts({
+          consumer: consumer.publicKey,
+          claimant: alice.publicKey,
+          agency: wallet.publicKey,
+          watcMint: watcMint,
+        })
+        .signers([consumer, alice])
+        .rpc();
+      assert.fail("Expected the claim to fail");
+    } catch (err) {
+      assert.include(err.toString(), "already in use");
+    }
+  });
+
+  it("Claims with an inflated capacity are rejected", async () => {
+    const consumer = Keypair.generate();
+    try {
+      await program.methods
+        .claimAllocation(treeKey, new anchor.BN(bobCapacity * 2), [
+          [...leaf(alice.publicKey, aliceCapacity)],
+        ])
+        .accounts({
+          consumer: consumer.publicKey,
+          claimant: bob.publicKey,
+          agency: wallet.publicKey,
+          watcMint: watcMint,
+        })
+        .signers([consumer, bob])
+        .rpc();
+      assert.fail("Expected the claim to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidMerkleProof");
+    }
+  });
+});
@@ -0,0 +1,66 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { createHash } from "crypto";
+import { assert } from "chai";
+
+describe("audit", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  const tariffKey = Keypair.generate().publicKey;
+  const filing = `Rate filing for tariff ${tariffKey.toBase58()}`;
+  const documentHash = [...createHash("sha256").update(filing).digest()];
+
+  const recordAudit = (hash: number[]) =>
+    program.methods
+      .recordAudit(hash, { rateFiling: {} }, tariffKey)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+  it("Anchors the hash of a rate filing", async () => {
+    await recordAudit(documentHash);
+
+    const [auditRecordPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("audit_record"),
+        wallet.publicKey.toBuffer(),
+        Buffer.from(documentHash),
+      ],
+      program.programId
+    );
+    const auditRecord = await program.account.auditRecord.fetch(
+      auditRecordPDA
+    );
+    assert.deepEqual(auditRecord.documentHash, documentHash);
+    assert.deepEqual(auditRecord.category, { rateFiling: {} });
+    assert.equal(auditRecord.subject.toBase58(), tariffKey.toBase58());
+    assert.isAbove(auditRecord.recordedAt.toNumber(), 0);
+  });
+
+  it("A document can only be recorded once", async () => {
+    try {
+      await recordAudit(documentHash);
+      assert.fail("Expected the record to fail");
+    } catch (err) {
+      assert.include(err.toString(), "already in use");
+    }
+  });
+
+  it("Rejects an empty hash", async () => {
+    try {
+      await recordAudit(new Array(32).fill(0));
+      assert.fail("Expected the record to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidDocumentHash");
+    }
+  });
+});
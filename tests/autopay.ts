@@ -0,0 +1,375 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("autopay", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let usdcMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let agencyUsdcAccount: PublicKey;
+  let fxOraclePDA: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+  let consumerUsdcAccount: PublicKey;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const initialContractedCapacity = 100000; // 100.000
+  const initialBlockRate = 800; // 0.800
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const currencyCode = [...Buffer.from("BBD")];
+  const USDC_DECIMALS = 6;
+  const fxRate = 500000; // 1 BBD = 0.500000 USDC
+  const initialUsdcBalance = 10_000_000; // 10.000000 USDC
+  const autopayCap = 5_000_000; // 5.000000 USDC
+
+  const receiptPDA = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("receipt"),
+        consumer.publicKey.toBuffer(),
+        new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+  before(async () => {
+    // Initialize accounts
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    [fxOraclePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("fx_oracle"),
+        wallet.publicKey.toBuffer(),
+        Buffer.from(currencyCode),
+      ],
+      program.programId
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    usdcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      USDC_DECIMALS
+    );
+
+    // Create token accounts
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      consumer.publicKey
+    );
+
+    agencyUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      wallet.publicKey
+    ).then((account) => account.address);
+
+    consumerUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    await mintTo(
+      connection,
+      wallet.payer,
+      usdcMint,
+      consumerUsdcAccount,
+      wallet.payer,
+      initialUsdcBalance
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a tariff denominated in BBD
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateTariffCurrency(tariffKey, currencyCode)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a reservoir
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(initialContractedCapacity),
+        new anchor.BN(initialBlockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    // Register the FX oracle, with the agency wallet posting rates
+    await program.methods
+      .initializeFxOracle(currencyCode, wallet.publicKey, new anchor.BN(1000))
+      .accounts({
+        settlementMint: usdcMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateFxRate(new anchor.BN(fxRate))
+      .accounts({
+        fxOracle: fxOraclePDA,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("Collection is rejected before auto-pay is enabled", async () => {
+    try {
+      await program.methods
+        .collectAutopay(tariffKey)
+        .accounts({
+          cranker: wallet.publicKey,
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          wtkMint: wtkMint,
+          settlementMint: usdcMint,
+        })
+        .rpc();
+      assert.fail("Expected the collection to fail");
+    } catch (err) {
+      assert.include(err.toString(), "AutopayDisabled");
+    }
+  });
+
+  it("Consumer enables auto-pay with a capped delegation", async () => {
+    await program.methods
+      .enableAutopay(new anchor.BN(autopayCap))
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+        settlementMint: usdcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.isTrue(consumerAccount.autopayEnabled);
+
+    const consumerUsdc = await getAccount(connection, consumerUsdcAccount);
+    assert.equal(Number(consumerUsdc.delegatedAmount), autopayCap);
+  });
+
+  it("Anyone can crank collection of outstanding debt", async () => {
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(10000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const debt = Number(
+      (await getAccount(connection, consumerWtkAccount)).amount
+    );
+    assert.isAbove(debt, 0);
+
+    // The cranker pays the rent of the receipt the collection issues
+    const cranker = Keypair.generate();
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(cranker.publicKey, LAMPORTS_PER_SOL),
+      "confirmed"
+    );
+    await program.methods
+      .collectAutopay(tariffKey)
+      .accounts({
+        cranker: cranker.publicKey,
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+        settlementMint: usdcMint,
+      })
+      .signers([cranker])
+      .rpc();
+
+    const expectedUsdc = (debt * fxRate) / 10 ** DECIMALS;
+
+    const consumerWtkBalance = await connection.getTokenAccountBalance(
+      consumerWtkAccount
+    );
+    const consumerUsdc = await getAccount(connection, consumerUsdcAccount);
+    const treasuryBalance = await connection.getTokenAccountBalance(
+      agencyUsdcAccount
+    );
+
+    assert.equal(consumerWtkBalance.value.amount, "0");
+    assert.equal(
+      Number(consumerUsdc.amount),
+      initialUsdcBalance - expectedUsdc
+    );
+    assert.equal(
+      Number(consumerUsdc.delegatedAmount),
+      autopayCap - expectedUsdc
+    );
+    assert.equal(treasuryBalance.value.amount, String(expectedUsdc));
+  });
+
+  it("Collection issues a payment receipt", async () => {
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(consumerAccount.receiptsIssued.toNumber(), 1);
+
+    const receipt = await program.account.paymentReceipt.fetch(receiptPDA(0));
+    assert.equal(receipt.receiptNumber.toNumber(), 0);
+    assert.isAbove(receipt.amount.toNumber(), 0);
+    assert.equal(receipt.outstanding.toNumber(), 0);
+    assert.isTrue(receipt.consumer.equals(consumer.publicKey));
+    assert.isTrue(receipt.payer.equals(consumer.publicKey));
+  });
+
+  it("Collection waits for the tariff's grace period to elapse", async () => {
+    await program.methods
+      .updateTariffGracePeriod(tariffKey, new anchor.BN(1_000_000))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(10000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const debt = (await getAccount(connection, consumerWtkAccount)).amount;
+    try {
+      await program.methods
+        .collectAutopay(tariffKey)
+        .accounts({
+          cranker: wallet.publicKey,
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          wtkMint: wtkMint,
+          settlementMint: usdcMint,
+        })
+        .rpc();
+      assert.fail("Expected the collection to fail");
+    } catch (err) {
+      assert.include(err.toString(), "GracePeriodActive");
+    }
+    assert.equal(
+      (await getAccount(connection, consumerWtkAccount)).amount,
+      debt
+    );
+  });
+});
@@ -0,0 +1,220 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("batch", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  const consumers = [Keypair.generate(), Keypair.generate()];
+  const consumerWtkAccounts: PublicKey[] = [];
+
+  const SCALE = 1000;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  // Each consumer is passed as its account followed by its WTK account
+  const remainingAccounts = () =>
+    consumers.flatMap((consumer, index) => [
+      { pubkey: consumer.publicKey, isWritable: true, isSigner: false },
+      { pubkey: consumerWtkAccounts[index], isWritable: true, isSigner: false },
+    ]);
+
+  const reportUsageBatch = (
+    readings: { consumerIndex: number; amount: anchor.BN }[]
+  ) =>
+    program.methods
+      .reportUsageBatch(tariffKey, reservoirKey, readings)
+      .accounts({
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+      })
+      .remainingAccounts(remainingAccounts())
+      .rpc();
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    [wtkMint, watcMint, wstMint] = await Promise.all(
+      [0, 1, 2].map(() =>
+        createMint(connection, wallet.payer, wallet.publicKey, null, DECIMALS)
+      )
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    for (const consumer of consumers) {
+      for (const mint of [wtkMint, watcMint]) {
+        const account = await getOrCreateAssociatedTokenAccount(
+          connection,
+          wallet.payer,
+          mint,
+          consumer.publicKey
+        );
+        if (mint === wtkMint) {
+          consumerWtkAccounts.push(account.address);
+        }
+      }
+      await program.methods
+        .registerConsumer(
+          tariffKey,
+          reservoirKey,
+          new anchor.BN(contractedCapacity),
+          new anchor.BN(blockRate)
+        )
+        .accounts({
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          watcMint: watcMint,
+        })
+        .signers([consumer])
+        .rpc();
+    }
+  });
+
+  it("Rejects readings for account pairs that were not passed", async () => {
+    try {
+      await reportUsageBatch([{ consumerIndex: 2, amount: new anchor.BN(1) }]);
+      assert.fail("Expected the batch to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidBatchIndex");
+    }
+  });
+
+  it("Bills every reading of a meter file in one call", async () => {
+    await reportUsageBatch([
+      { consumerIndex: 0, amount: new anchor.BN(60000) },
+      { consumerIndex: 1, amount: new anchor.BN(20000) },
+      // A second reading for the first consumer crosses its threshold
+      { consumerIndex: 0, amount: new anchor.BN(60000) },
+    ]);
+
+    const balances = await Promise.all(
+      consumerWtkAccounts.map(async (account) =>
+        Number((await connection.getTokenAccountBalance(account)).value.amount)
+      )
+    );
+    assert.equal(
+      balances[0],
+      (contractedCapacity * initialWaterRate +
+        (120000 - contractedCapacity) * blockRate) /
+        SCALE
+    );
+    assert.equal(balances[1], (20000 * initialWaterRate) / SCALE);
+
+    const consumerAccount = await program.account.consumer.fetch(
+      consumers[0].publicKey
+    );
+    assert.equal(consumerAccount.periodUsage.toNumber(), 120000);
+    assert.equal(consumerAccount.outstandingWaterDebt.toNumber(), balances[0]);
+  });
+
+  it("Rejects readings from consumers outside the zone", async () => {
+    const geohash = (value: string) => [
+      ...Buffer.concat([Buffer.from(value), Buffer.alloc(12 - value.length)]),
+    ];
+    await program.methods
+      .updateReservoirZone(reservoirKey, geohash("d5tt"))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+    for (const [consumer, location] of [
+      [consumers[0], "d5ttkz9x"],
+      [consumers[1], "d5tv9x"],
+    ] as const) {
+      await program.methods
+        .updateConsumerLocation(tariffKey, geohash(location))
+        .accounts({
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+    }
+
+    try {
+      await reportUsageBatch([{ consumerIndex: 1, amount: new anchor.BN(10) }]);
+      assert.fail("Expected the batch to fail");
+    } catch (err) {
+      assert.include(err.toString(), "OutsideZone");
+    }
+
+    await reportUsageBatch([{ consumerIndex: 0, amount: new anchor.BN(10) }]);
+
+    // Geohashes outside the base32 alphabet are rejected
+    try {
+      await program.methods
+        .updateReservoirZone(reservoirKey, geohash("d5ta"))
+        .accounts({
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the update to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidGeohash");
+    }
+  });
+});
@@ -0,0 +1,264 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { grantRegulatorRole } from "./fixtures/regulator";
+
+describe("bond", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  const regulator = Keypair.generate();
+
+  let usdcMint: PublicKey;
+  let agencyUsdcAccount: PublicKey;
+  let consumerUsdcAccount: PublicKey;
+  let bondPDA: PublicKey;
+  let escrowAccount: PublicKey;
+
+  const USDC_DECIMALS = 6;
+  const initialUsdcBalance = 100_000_000; // 100.000000 USDC
+  const bond = 50_000_000; // 50.000000 USDC
+  const slashBps = 1000; // 10% of the bond per confirmed mischarge
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const rolePDA = (member: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("role"), wallet.publicKey.toBuffer(), member.toBuffer()],
+      program.programId
+    )[0];
+
+  const resolveFlaggedCharge = (authority: Keypair | null) => {
+    const builder = program.methods
+      .resolveFlaggedCharge(true)
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        authority: authority ? authority.publicKey : wallet.publicKey,
+        role: authority ? rolePDA(authority.publicKey) : null,
+        agencyBond: bondPDA,
+        escrow: escrowAccount,
+        consumerSettlement: consumerUsdcAccount,
+      });
+    return authority ? builder.signers([authority]).rpc() : builder.rpc();
+  };
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    // The consumer's WTK account receives the water charges
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { seasonalIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(regulator.publicKey, LAMPORTS_PER_SOL),
+      "confirmed"
+    );
+
+    await grantRegulatorRole(program, regulator.publicKey);
+  
+    [bondPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("agency_bond"), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+
+    usdcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      USDC_DECIMALS
+    );
+
+    agencyUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      wallet.publicKey
+    ).then((account) => account.address);
+
+    consumerUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    escrowAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      bondPDA,
+      true
+    ).then((account) => account.address);
+
+    await mintTo(
+      connection,
+      wallet.payer,
+      usdcMint,
+      agencyUsdcAccount,
+      wallet.payer,
+      initialUsdcBalance
+    );
+  });
+
+  it("should escrow the agency's bond", async () => {
+    await program.methods
+      .postAgencyBond(new anchor.BN(bond), slashBps)
+      .accounts({
+        agency: wallet.publicKey,
+        settlementMint: usdcMint,
+      })
+      .rpc();
+
+    const agencyBond = await program.account.agencyBond.fetch(bondPDA);
+    assert.equal(agencyBond.totalPosted.toNumber(), bond);
+    assert.equal(agencyBond.slashBps, slashBps);
+
+    const escrow = await getAccount(connection, escrowAccount);
+    assert.equal(Number(escrow.amount), bond);
+  });
+
+  it("should not let the agency resolve a flagged charge", async () => {
+    try {
+      await resolveFlaggedCharge(null);
+      assert.fail("Expected the resolution to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "MissingRegulatorApproval");
+    }
+  });
+
+  it("should refuse to resolve a charge that was never flagged", async () => {
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(10000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    try {
+      await resolveFlaggedCharge(regulator);
+      assert.fail("Expected the resolution to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "NoFlaggedCharge");
+    }
+
+    // The bond stays in escrow
+    const escrow = await getAccount(connection, escrowAccount);
+    assert.equal(Number(escrow.amount), bond);
+  });
+});
@@ -0,0 +1,250 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  grantRegulatorRole,
+  regulatorAuthorityPDA,
+} from "./fixtures/regulator";
+
+describe("bounds", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  const regulator = Keypair.generate();
+
+  const maxMultiplier = 2000; // 2.000
+  const minCharge = 5000; // 5.000
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const rolePDA = (member: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("role"), wallet.publicKey.toBuffer(), member.toBuffer()],
+      program.programId
+    )[0];
+
+  const updateTariffBounds = (authority: Keypair | null) => {
+    const builder = program.methods
+      .updateTariffBounds(
+        tariffKey,
+        new anchor.BN(maxMultiplier),
+        new anchor.BN(minCharge)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+        authority: authority ? authority.publicKey : wallet.publicKey,
+        role: authority ? rolePDA(authority.publicKey) : null,
+        regulatorAuthority: regulatorAuthorityPDA(program),
+      });
+    return authority ? builder.signers([authority]).rpc() : builder.rpc();
+  };
+
+  const useWater = (amount: number) =>
+    program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(amount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+  const wtkBalance = async () =>
+    Number((await getAccount(connection, consumerWtkAccount)).amount);
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { seasonalIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(regulator.publicKey, LAMPORTS_PER_SOL),
+      "confirmed"
+    );
+
+    await grantRegulatorRole(program, regulator.publicKey);
+  });
+
+  it("should not let the agency set its own bounds", async () => {
+    try {
+      await updateTariffBounds(null);
+      assert.fail("Expected the bounds to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "MissingRegulatorApproval");
+    }
+  });
+
+  it("should cap the multiplier set by the reservoir level", async () => {
+    await updateTariffBounds(regulator);
+
+    // 100.000 units at 0.500, plus 20.000 units at 0.800 x 2 instead of x 50
+    await useWater(120000);
+    assert.equal(await wtkBalance(), 82000);
+  });
+
+  it("should bill small readings the minimum charge", async () => {
+    // 1.000 unit at 0.800 x 2 is raised to the 5.000 floor
+    await useWater(1000);
+    assert.equal(await wtkBalance(), 82000 + minCharge);
+
+    // The stored breakdown shows how the floor was reached
+    const { lastBill } = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(lastBill.excess.toNumber(), 1600);
+    assert.equal(lastBill.fixed.toNumber(), minCharge - 1600);
+    assert.equal(lastBill.total.toNumber(), minCharge);
+  });
+
+  it("should record the pricing inputs of the last reading", async () => {
+    const { lastPricing, lastScarcityInputs } =
+      await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(lastPricing.volume.toNumber(), 1000);
+    // The reservoir's multiplier is recorded before the tariff's cap
+    assert.equal(lastPricing.multiplier.toNumber(), 50000);
+    assert.equal(lastPricing.maxMultiplier.toNumber(), maxMultiplier);
+    assert.equal(lastPricing.minCharge.toNumber(), minCharge);
+    assert.equal(lastPricing.level.toNumber(), initialReservoirLevel);
+
+    // The multiplier is derived again from the reservoir's recorded state
+    assert.equal(
+      lastScarcityInputs.capacity.toNumber(),
+      initialReservoirCapacity
+    );
+    assert.equal(
+      lastScarcityInputs.currentLevel.toNumber(),
+      initialReservoirLevel
+    );
+  });
+
+  it("should let anyone verify the last charge", async () => {
+    const verifier = Keypair.generate();
+    await program.methods
+      .verifyCharge()
+      .accounts({
+        verifier: verifier.publicKey,
+        consumer: consumer.publicKey,
+      })
+      .signers([verifier])
+      .rpc();
+
+    // The charge matches its recomputation, so nothing is flagged
+    const { flaggedCharge } = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.isNull(flaggedCharge);
+  });
+});
@@ -0,0 +1,234 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("bulk", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const driver = Keypair.generate();
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  const bulkRate = 1500; // 1.500
+  const deliveredVolume = 10000; // 10.000
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const rolePDA = (member: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("role"), wallet.publicKey.toBuffer(), member.toBuffer()],
+      program.programId
+    )[0];
+
+  const reservoirPDA = () =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservoir"),
+        wallet.publicKey.toBuffer(),
+        reservoirKey.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const dispenseBulk = (volume: number) =>
+    program.methods
+      .dispenseBulk(tariffKey, reservoirKey, new anchor.BN(volume))
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        driver: driver.publicKey,
+        driverRole: rolePDA(driver.publicKey),
+        wtkMint: wtkMint,
+      })
+      .signers([driver])
+      .rpc();
+
+  const wtkBalance = async () =>
+    Number((await getAccount(connection, consumerWtkAccount)).amount);
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await program.methods
+      .grantRole(driver.publicKey, { driver: {} })
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("should only let drivers dispense bulk deliveries", async () => {
+    const stranger = Keypair.generate();
+    try {
+      await program.methods
+        .dispenseBulk(tariffKey, reservoirKey, new anchor.BN(deliveredVolume))
+        .accounts({
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          driver: stranger.publicKey,
+          driverRole: null,
+          wtkMint: wtkMint,
+        })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Expected the delivery to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "MissingRole");
+    }
+  });
+
+  it("should refuse bulk deliveries without a bulk rate", async () => {
+    try {
+      await dispenseBulk(deliveredVolume);
+      assert.fail("Expected the delivery to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "BulkDeliveryUnavailable");
+    }
+  });
+
+  it("should bill bulk deliveries and debit the reservoir", async () => {
+    await program.methods
+      .updateTariffBulkRate(tariffKey, new anchor.BN(bulkRate))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await dispenseBulk(deliveredVolume);
+
+    // 10.000 units at 1.500 per unit
+    assert.equal(await wtkBalance(), 15000);
+    const billed = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(billed.outstandingWaterDebt.toNumber(), 15000);
+    assert.equal(billed.periodUsage.toNumber(), 0);
+
+    const reservoir = await program.account.reservoir.fetch(reservoirPDA());
+    assert.equal(
+      reservoir.currentLevel.toNumber(),
+      initialReservoirLevel - deliveredVolume
+    );
+  });
+
+  it("should not dispense more than the reservoir holds", async () => {
+    try {
+      await dispenseBulk(initialReservoirCapacity);
+      assert.fail("Expected the delivery to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InsufficientReservoirLevel");
+    }
+  });
+});
@@ -0,0 +1,235 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe("capacity", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let consumerWatcAccount: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const watcBalance = async () =>
+    Number(
+      (await connection.getTokenAccountBalance(consumerWatcAccount)).value
+        .amount
+    );
+
+  const refreshCapacity = () =>
+    program.methods
+      .refreshCapacity(tariffKey)
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .rpc();
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+    consumer = Keypair.generate();
+
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    consumerWatcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+  });
+
+  it("should not refresh capacity without a billing period", async () => {
+    try {
+      await refreshCapacity();
+      assert.fail("Expected the refresh to fail");
+    } catch (err) {
+      assert.include(err.toString(), "CapacityRefreshNotDue");
+    }
+  });
+
+  it("should refresh capacity once the billing period has ended", async () => {
+    await program.methods
+      .updateTariffBillingPeriod(tariffKey, new anchor.BN(2))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(40000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+    assert.equal(await watcBalance(), contractedCapacity - 40000);
+
+    // Let the two-slot billing period elapse
+    await sleep(2000);
+    await refreshCapacity();
+
+    // Unused capacity is kept, but never raises the balance above the contract
+    assert.equal(await watcBalance(), contractedCapacity);
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.isAbove(consumerAccount.capacityPeriodStart.toNumber(), 0);
+    // Usage counters start over with the new period
+    assert.equal(consumerAccount.periodUsage.toNumber(), 0);
+    assert.equal(consumerAccount.periodWaste.toNumber(), 0);
+  });
+
+  it("should not accept a rollover above 10,000 basis points", async () => {
+    try {
+      await program.methods
+        .updateTariffCapacityRollover(tariffKey, 10001)
+        .accounts({
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the update to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidShare");
+    }
+  });
+
+  it("should carry over the configured share of unused capacity", async () => {
+    await program.methods
+      .updateTariffCapacityRollover(tariffKey, 5000)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(40000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await sleep(2000);
+    await refreshCapacity();
+
+    // Half of the 60.000 left unused is carried on top of the contract
+    assert.equal(await watcBalance(), contractedCapacity + 30000);
+  });
+});
@@ -0,0 +1,244 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("carbon", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let creditMint: PublicKey;
+  let agencyCreditAccount: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  const emissionsFactor = 350; // 350 g CO2e per unit
+  const gramsPerCredit = new anchor.BN(1000000); // one tonne per credit
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const agencyStatsPDA = PublicKey.findProgramAddressSync(
+    [Buffer.from("agency_stats"), wallet.publicKey.toBuffer()],
+    program.programId
+  )[0];
+
+  const useWater = (amount: number) =>
+    program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(amount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+  const retireCarbonOffsets = (amount: number) =>
+    program.methods
+      .retireCarbonOffsets(new anchor.BN(amount))
+      .accounts({
+        creditMint: creditMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+  const wtkBalance = async () =>
+    Number((await getAccount(connection, consumerWtkAccount)).amount);
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await program.methods
+      .updateReservoirEmissions(reservoirKey, new anchor.BN(emissionsFactor))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Carbon credits held by the agency
+    creditMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    agencyCreditAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      creditMint,
+      wallet.publicKey
+    ).then((account) => account.address);
+    await mintTo(
+      connection,
+      wallet.payer,
+      creditMint,
+      agencyCreditAccount,
+      wallet.payer,
+      1000
+    );
+
+    await program.methods
+      .configureCarbonOffsets(gramsPerCredit)
+      .accounts({
+        creditMint: creditMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("should attribute emissions without changing the bill", async () => {
+    const before = await program.account.agencyStats.fetch(agencyStatsPDA);
+
+    // 40.000 units at 0.500, emitting 350 g each
+    await useWater(40000);
+    assert.equal(await wtkBalance(), 20000);
+
+    const billed = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(billed.co2eGrams.toNumber(), 14000);
+
+    const after = await program.account.agencyStats.fetch(agencyStatsPDA);
+    assert.equal(
+      after.co2eGrams.toNumber() - before.co2eGrams.toNumber(),
+      14000
+    );
+  });
+
+  it("should retire credits against recorded emissions", async () => {
+    // 0.010 credits offset 10,000 g of the 14,000 g emitted
+    await retireCarbonOffsets(10);
+
+    const credits = await getAccount(connection, agencyCreditAccount);
+    assert.equal(Number(credits.amount), 990);
+
+    // A whole credit offsets more than the agency has emitted
+    try {
+      await retireCarbonOffsets(1000);
+      assert.fail("Expected the retirement to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "OffsetExceedsEmissions");
+    }
+
+    const [carbonOffsetsPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("carbon_offsets"), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+    const offsets = await program.account.carbonOffsets.fetch(
+      carbonOffsetsPDA
+    );
+    assert.equal(offsets.co2eRetired.toNumber(), 10000);
+    assert.equal(offsets.retirements, 1);
+  });
+});
@@ -0,0 +1,323 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("communal", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let usdcMint: PublicKey;
+  let standpipeWtkAccount: PublicKey;
+  let agencyUsdcAccount: PublicKey;
+  let communalPointPDA: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let standpipe: Keypair;
+  let alice: Keypair;
+  let bob: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const currencyCode = [...Buffer.from("XCD")];
+  const USDC_DECIMALS = 6;
+  const fxRate = 370000; // 1 XCD = 0.370000 USDC
+  const initialUsdcBalance = 20_000_000; // 20.000000 USDC
+
+  const topUp = (member: Keypair, amount: number) =>
+    program.methods
+      .topUpCommunalPoint(new anchor.BN(amount))
+      .accounts({
+        consumer: standpipe.publicKey,
+        agency: wallet.publicKey,
+        member: member.publicKey,
+        settlementMint: usdcMint,
+      })
+      .signers([member])
+      .rpc();
+
+  const useWater = (amount: number) =>
+    program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(amount))
+      .accounts({
+        consumer: standpipe.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+        communalPoint: communalPointPDA,
+      })
+      .signers([standpipe])
+      .rpc();
+
+  const usdcBalance = async (account: PublicKey) =>
+    Number((await connection.getTokenAccountBalance(account)).value.amount);
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    standpipe = Keypair.generate();
+    alice = Keypair.generate();
+    bob = Keypair.generate();
+
+    [communalPointPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("communal_point"),
+        wallet.publicKey.toBuffer(),
+        standpipe.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    usdcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      USDC_DECIMALS
+    );
+
+    // Create token accounts
+    standpipeWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      standpipe.publicKey
+    ).then((account) => account.address);
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      standpipe.publicKey
+    );
+
+    agencyUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      wallet.publicKey
+    ).then((account) => account.address);
+
+    for (const member of [alice, bob]) {
+      const memberUsdcAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        wallet.payer,
+        usdcMint,
+        member.publicKey
+      ).then((account) => account.address);
+
+      await mintTo(
+        connection,
+        wallet.payer,
+        usdcMint,
+        memberUsdcAccount,
+        wallet.payer,
+        initialUsdcBalance
+      );
+    }
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a tariff denominated in XCD
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateTariffCurrency(tariffKey, currencyCode)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: standpipe.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([standpipe])
+      .rpc();
+
+    // Register the FX oracle, with the agency wallet posting rates
+    await program.methods
+      .initializeFxOracle(currencyCode, wallet.publicKey, new anchor.BN(1000))
+      .accounts({
+        settlementMint: usdcMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    const [fxOraclePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("fx_oracle"),
+        wallet.publicKey.toBuffer(),
+        Buffer.from(currencyCode),
+      ],
+      program.programId
+    );
+    await program.methods
+      .updateFxRate(new anchor.BN(fxRate))
+      .accounts({
+        fxOracle: fxOraclePDA,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerCommunalPoint()
+      .accounts({
+        consumer: standpipe.publicKey,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    for (const member of [alice, bob]) {
+      await program.methods
+        .addCommunalMember(member.publicKey)
+        .accounts({
+          communalPoint: communalPointPDA,
+          agency: wallet.publicKey,
+        })
+        .rpc();
+    }
+  });
+
+  it("should reject top-ups from wallets that are not members", async () => {
+    const stranger = Keypair.generate();
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      stranger.publicKey
+    );
+
+    try {
+      await topUp(stranger, 1000);
+      assert.fail("Expected the top-up to fail");
+    } catch (err) {
+      assert.include(err.toString(), "NotCommunalMember");
+    }
+  });
+
+  it("should record each member's contributions", async () => {
+    const treasuryBefore = await usdcBalance(agencyUsdcAccount);
+
+    await topUp(alice, 15000); // 15.000 XCD
+    await topUp(alice, 5000); // 5.000 XCD
+    await topUp(bob, 10000); // 10.000 XCD
+
+    // 30.000 XCD at 0.370000 USDC each
+    const treasuryAfter = await usdcBalance(agencyUsdcAccount);
+    assert.equal(treasuryAfter - treasuryBefore, 11_100_000);
+
+    const point = await program.account.communalPoint.fetch(communalPointPDA);
+    assert.equal(point.prepaidBalance.toNumber(), 30000);
+    assert.equal(point.members[0].contributed.toNumber(), 20000);
+    assert.equal(point.members[0].topUps, 2);
+    assert.equal(point.members[1].contributed.toNumber(), 10000);
+    assert.equal(point.members[1].topUps, 1);
+  });
+
+  it("should draw usage from the shared balance", async () => {
+    // 40.000 units at 0.500 is 20.000 WTK, fully prepaid
+    await useWater(40000);
+
+    let standpipeWtk = await getAccount(connection, standpipeWtkAccount);
+    assert.equal(Number(standpipeWtk.amount), 0);
+
+    // 30.000 units is 15.000 WTK, of which 10.000 is still prepaid
+    await useWater(30000);
+
+    standpipeWtk = await getAccount(connection, standpipeWtkAccount);
+    assert.equal(Number(standpipeWtk.amount), 5000);
+
+    const point = await program.account.communalPoint.fetch(communalPointPDA);
+    assert.equal(point.prepaidBalance.toNumber(), 0);
+    assert.equal(point.totalDrawn.toNumber(), 30000);
+  });
+});
@@ -0,0 +1,226 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe("complaints", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let aqcMint: PublicKey;
+  let consumerAqcAccount: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  const goodwillAmount = 10000; // 10.000 AQC
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const complaintPDA = (complaintKey: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("complaint"),
+        wallet.publicKey.toBuffer(),
+        complaintKey.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const configurePolicy = (slaSlots: number) =>
+    program.methods
+      .configureComplaintPolicy(
+        new anchor.BN(slaSlots),
+        new anchor.BN(goodwillAmount)
+      )
+      .accounts({
+        aqcMint: aqcMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+  const fileComplaint = async (category: any) => {
+    const complaintKey = Keypair.generate().publicKey;
+    await program.methods
+      .fileComplaint(complaintKey, category)
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+    return complaintPDA(complaintKey);
+  };
+
+  const resolveComplaint = (complaint: PublicKey) =>
+    program.methods
+      .resolveComplaint()
+      .accounts({
+        complaint: complaint,
+        consumer: consumer.publicKey,
+        aqcMint: aqcMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+  const aqcBalance = async () =>
+    Number((await getAccount(connection, consumerAqcAccount)).amount);
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    aqcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    consumerAqcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      aqcMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+  });
+
+  it("should credit no goodwill for complaints resolved in time", async () => {
+    await configurePolicy(1000);
+
+    const complaint = await fileComplaint({ billing: {} });
+    await resolveComplaint(complaint);
+    assert.equal(await aqcBalance(), 0);
+
+    const resolved = await program.account.complaint.fetch(complaint);
+    assert.deepEqual(resolved.category, { billing: {} });
+    assert.isAbove(resolved.resolvedSlot.toNumber(), 0);
+    assert.equal(resolved.goodwillCredited.toNumber(), 0);
+
+    try {
+      await resolveComplaint(complaint);
+      assert.fail("Expected the resolution to fail");
+    } catch (err) {
+      assert.include(err.toString(), "ComplaintAlreadyResolved");
+    }
+  });
+
+  it("should credit goodwill once the SLA is breached", async () => {
+    const slaSlots = 2;
+    await configurePolicy(slaSlots);
+
+    const complaint = await fileComplaint({ supply: {} });
+    const filed = await program.account.complaint.fetch(complaint);
+    const breachedAt = filed.openedSlot.toNumber() + slaSlots + 1;
+    while ((await connection.getSlot()) < breachedAt) {
+      await sleep(500);
+    }
+
+    await resolveComplaint(complaint);
+    assert.equal(await aqcBalance(), goodwillAmount);
+
+    const resolved = await program.account.complaint.fetch(complaint);
+    assert.equal(resolved.goodwillCredited.toNumber(), goodwillAmount);
+  });
+});
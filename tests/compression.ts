@@ -0,0 +1,239 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+} from "@solana/spl-token";
+import { createHash } from "crypto";
+import { assert } from "chai";
+
+const TREE_DEPTH = 20;
+
+const sha256 = (...parts: Buffer[]) => {
+  const hash = createHash("sha256");
+  parts.forEach((part) => hash.update(part));
+  return hash.digest();
+};
+
+// Roots of empty subtrees, one per level
+const zeroHashes = () => {
+  const zeros = [Buffer.alloc(32)];
+  for (let level = 0; level < TREE_DEPTH; level++) {
+    zeros.push(sha256(zeros[level], zeros[level]));
+  }
+  return zeros;
+};
+
+describe("compression", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let treeKey: PublicKey;
+  let consumer: Keypair;
+  let consumerData: Buffer;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const initialContractedCapacity = 100000; // 100.000
+  const initialBlockRate = 800; // 0.800
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  before(async () => {
+    // Initialize accounts
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+    treeKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      consumer.publicKey
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a tariff
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a reservoir
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(initialContractedCapacity),
+        new anchor.BN(initialBlockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await program.methods
+      .initializeConsumerTree(treeKey)
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("Inactive consumer is compressed into the tree", async () => {
+    // The leaf holds the account data without its discriminator
+    const account = await connection.getAccountInfo(consumer.publicKey);
+    consumerData = account.data.subarray(8);
+
+    await program.methods
+      .compressConsumer(treeKey)
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    assert.isNull(await connection.getAccountInfo(consumer.publicKey));
+  });
+
+  it("Compressed consumer is restored from its leaf", async () => {
+    // The consumer is the first leaf, so every sibling is an empty subtree
+    const zeros = zeroHashes();
+    const proof = zeros.slice(0, TREE_DEPTH);
+    const root = proof.reduce(
+      (node, sibling) => sha256(node, sibling),
+      sha256(consumer.publicKey.toBuffer(), consumerData)
+    );
+
+    await program.methods
+      .decompressConsumer(
+        treeKey,
+        new anchor.BN(0),
+        consumerData,
+        [...root],
+        proof.map((node) => [...node])
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(
+      consumerAccount.contractedCapacity.toNumber(),
+      initialContractedCapacity
+    );
+    assert.equal(
+      consumerAccount.assignedTariff.toBase58(),
+      tariffKey.toBase58()
+    );
+  });
+
+  it("A leaf can only be restored once", async () => {
+    // Restoring into another account still hits the leaf's hydration marker
+    const copy = Keypair.generate();
+    const proof = zeroHashes().slice(0, TREE_DEPTH);
+    const root = proof.reduce(
+      (node, sibling) => sha256(node, sibling),
+      sha256(consumer.publicKey.toBuffer(), consumerData)
+    );
+
+    try {
+      await program.methods
+        .decompressConsumer(
+          treeKey,
+          new anchor.BN(0),
+          consumerData,
+          [...root],
+          proof.map((node) => [...node])
+        )
+        .accounts({
+          consumer: copy.publicKey,
+          agency: wallet.publicKey,
+        })
+        .signers([copy])
+        .rpc();
+      assert.fail("Expected decompression to fail");
+    } catch (err) {
+      assert.include(err.toString(), "already in use");
+    }
+  });
+});
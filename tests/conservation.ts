@@ -0,0 +1,255 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe("conservation", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+  const billingPeriodSlots = 2;
+
+  const conservationBaselineBps = 8_000; // 80% of the block threshold
+  const conservationPeriods = 2;
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const refreshCapacity = async () => {
+    const account = await program.account.consumer.fetch(consumer.publicKey);
+    const due = account.capacityPeriodStart.toNumber() + billingPeriodSlots;
+    while ((await connection.getSlot()) < due) {
+      await sleep(500);
+    }
+
+    await program.methods
+      .refreshCapacity(tariffKey)
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .rpc();
+  };
+
+  const awardCertificate = async (certificateMint: Keypair) => {
+    const consumerCertificateToken = getAssociatedTokenAddressSync(
+      certificateMint.publicKey,
+      consumer.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .awardConservationCertificate(tariffKey)
+      .accounts({
+        consumer: consumer.publicKey,
+        certificateMint: certificateMint.publicKey,
+        consumerCertificateToken: consumerCertificateToken,
+        agency: wallet.publicKey,
+      })
+      .signers([certificateMint])
+      .rpc();
+
+    return consumerCertificateToken;
+  };
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      consumer.publicKey
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateTariffBillingPeriod(tariffKey, new anchor.BN(billingPeriodSlots))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateTariffConservation(
+        tariffKey,
+        conservationBaselineBps,
+        conservationPeriods
+      )
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+  });
+
+  it("should reject an award before the streak is complete", async () => {
+    await refreshCapacity();
+
+    const account = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(account.conservationStreak, 1);
+
+    try {
+      await awardCertificate(Keypair.generate());
+      assert.fail("Expected the award to fail");
+    } catch (err) {
+      assert.include(err.toString(), "ConservationStreakIncomplete");
+    }
+  });
+
+  it("should award a non-transferable certificate", async () => {
+    await refreshCapacity();
+
+    const certificateMint = Keypair.generate();
+    const certificateToken = await awardCertificate(certificateMint);
+
+    const token = await getAccount(
+      connection,
+      certificateToken,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(Number(token.amount), 1);
+
+    const account = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(account.conservationStreak, 0);
+    assert.equal(account.certificatesAwarded, 1);
+
+    const [certificatePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("conservation_certificate"),
+        consumer.publicKey.toBuffer(),
+        Buffer.from([0, 0]),
+      ],
+      program.programId
+    );
+    const certificate = await program.account.conservationCertificate.fetch(
+      certificatePDA
+    );
+    assert.equal(certificate.periods, conservationPeriods);
+    assert.isTrue(certificate.mint.equals(certificateMint.publicKey));
+  });
+
+  it("should reject updates with an invalid baseline", async () => {
+    try {
+      await program.methods
+        .updateTariffConservation(tariffKey, 10_001, conservationPeriods)
+        .accounts({
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the update to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidShare");
+    }
+  });
+});
@@ -1,7 +1,12 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Aquachain } from "../target/types/aquachain";
-import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  PublicKey,
+  Keypair,
+  Ed25519Program,
+  Secp256k1Program,
+} from "@solana/web3.js";
 import {
   createMint,
   getOrCreateAssociatedTokenAccount,
@@ -17,7 +22,9 @@ describe("consumer", () => {
   const connection = provider.connection;
   const wallet = provider.wallet as anchor.Wallet;
 
+  let wtkMint: PublicKey;
   let watcMint: PublicKey;
+  let wstMint: PublicKey;
   let consumerWatcAccount: PublicKey;
   let tariffPDA: PublicKey;
   let tariffKey: PublicKey;
@@ -34,6 +41,13 @@ describe("consumer", () => {
   const initialContractedCapacity = 100000; // 100.000
   const initialBlockRate = 800; // 0.800
 
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
   before(async () => {
     // Initialize accounts
     tariffKey = Keypair.generate().publicKey;
@@ -60,12 +74,26 @@ describe("consumer", () => {
     consumer = Keypair.generate();
 
     // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
     watcMint = await createMint(
       connection,
       wallet.payer,
       wallet.publicKey,
       null,
-      9
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
     );
 
     // Create token accounts
@@ -76,6 +104,14 @@ describe("consumer", () => {
       consumer.publicKey
     ).then((account) => account.address);
 
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
     // Initialize a tariff
     await program.methods
       .initializeTariff(
@@ -177,6 +213,69 @@ describe("consumer", () => {
     );
   });
 
+  it("should let the agency make pre-approved capacity changes", async () => {
+    const updateUnsigned = (contractedCapacity: number, blockRate: number) =>
+      program.methods
+        .updateConsumer(
+          tariffKey,
+          reservoirKey,
+          new anchor.BN(contractedCapacity),
+          new anchor.BN(blockRate)
+        )
+        .accounts({
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          watcMint: watcMint,
+        })
+        .rpc();
+
+    // Without an approval, every change needs the consumer's signature
+    try {
+      await updateUnsigned(210000, 1000);
+      assert.fail("Expected the unsigned update to fail");
+    } catch (err) {
+      assert.include(err.toString(), "CapacityChangeNotApproved");
+    }
+
+    // The consumer pre-approves changes of up to 10% a period
+    await program.methods
+      .setCapacityApproval(1000)
+      .accounts({
+        consumer: consumer.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await updateUnsigned(210000, 1000);
+
+    let consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(consumerAccount.contractedCapacity.toNumber(), 210000);
+
+    // The increase is minted on top of the balance
+    const consumerWatcBalance =
+      await provider.connection.getTokenAccountBalance(consumerWatcAccount);
+    assert.equal(consumerWatcBalance.value.amount, "210000");
+
+    // Changes add up within the period, and the block rate is not covered
+    for (const [contractedCapacity, blockRate] of [
+      [230000, 1000],
+      [210000, 1100],
+    ]) {
+      try {
+        await updateUnsigned(contractedCapacity, blockRate);
+        assert.fail("Expected the unsigned update to fail");
+      } catch (err) {
+        assert.include(err.toString(), "CapacityChangeNotApproved");
+      }
+    }
+
+    consumerAccount = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(consumerAccount.contractedCapacity.toNumber(), 210000);
+    assert.equal(consumerAccount.blockRate.toNumber(), 1000);
+  });
+
   it("should update consumer's assigned tariff", async () => {
     // Test values for new tariff
     const newWaterRate = 20; // 0.020
@@ -209,6 +308,7 @@ describe("consumer", () => {
       .accounts({
         consumer: consumer.publicKey,
         agency: wallet.publicKey,
+        authority: wallet.publicKey,
       })
       .rpc();
 
@@ -275,6 +375,7 @@ describe("consumer", () => {
       .accounts({
         consumer: consumer.publicKey,
         agency: wallet.publicKey,
+        authority: wallet.publicKey,
       })
       .rpc();
 
@@ -311,4 +412,448 @@ describe("consumer", () => {
       consumerReservoir.capacity.toNumber()
     );
   });
+
+  it("should decommission a reservoir with no consumers", async () => {
+    const { assignedReservoir } = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    const [assignedReservoirPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservoir"),
+        wallet.publicKey.toBuffer(),
+        assignedReservoir.toBuffer(),
+      ],
+      program.programId
+    );
+
+    // The consumer was moved off the original reservoir onto the new one
+    const original = await program.account.reservoir.fetch(reservoirPDA);
+    const assigned = await program.account.reservoir.fetch(
+      assignedReservoirPDA
+    );
+    assert.equal(original.assignedConsumerCount.toNumber(), 0);
+    assert.equal(assigned.assignedConsumerCount.toNumber(), 1);
+
+    try {
+      await program.methods
+        .decommissionReservoir(assignedReservoir)
+        .accounts({ agency: wallet.publicKey })
+        .rpc();
+      assert.fail("Expected the decommissioning to fail");
+    } catch (err) {
+      assert.include(err.toString(), "ReservoirInUse");
+    }
+
+    await program.methods
+      .decommissionReservoir(reservoirKey)
+      .accounts({ agency: wallet.publicKey })
+      .rpc();
+
+    const closed = await connection.getAccountInfo(reservoirPDA);
+    assert.isNull(closed);
+  });
+
+  it("should close a tariff with no consumers", async () => {
+    const { assignedTariff } = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    const [assignedTariffPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("tariff"),
+        wallet.publicKey.toBuffer(),
+        assignedTariff.toBuffer(),
+      ],
+      program.programId
+    );
+
+    // The consumer was moved off the original tariff onto the new one
+    const original = await program.account.tariff.fetch(tariffPDA);
+    const assigned = await program.account.tariff.fetch(assignedTariffPDA);
+    assert.equal(original.assignedConsumerCount.toNumber(), 0);
+    assert.equal(assigned.assignedConsumerCount.toNumber(), 1);
+
+    try {
+      await program.methods
+        .closeTariff(assignedTariff)
+        .accounts({ agency: wallet.publicKey })
+        .rpc();
+      assert.fail("Expected the closure to fail");
+    } catch (err) {
+      assert.include(err.toString(), "TariffInUse");
+    }
+
+    await program.methods
+      .closeTariff(tariffKey)
+      .accounts({ agency: wallet.publicKey })
+      .rpc();
+
+    const closed = await connection.getAccountInfo(tariffPDA);
+    assert.isNull(closed);
+  });
+
+  it("should reassign the account holder once debt is settled", async () => {
+    const newOwner = Keypair.generate();
+
+    // The consumer has never used water, so both debt balances are zero
+    const consumerWtk = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    );
+    const consumerWst = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wstMint,
+      consumer.publicKey
+    );
+
+    // Consumers start out held by their own keypair
+    let consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.ok(consumerAccount.owner.equals(consumer.publicKey));
+
+    await program.methods
+      .reassignAccountHolder(newOwner.publicKey)
+      .accounts({
+        consumer: consumer.publicKey,
+        owner: consumer.publicKey,
+        agency: wallet.publicKey,
+        consumerWtk: consumerWtk.address,
+        consumerWst: consumerWst.address,
+        wtkMint,
+        wstMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    consumerAccount = await program.account.consumer.fetch(consumer.publicKey);
+    assert.ok(consumerAccount.owner.equals(newOwner.publicKey));
+
+    // The previous holder can no longer reassign the account
+    try {
+      await program.methods
+        .reassignAccountHolder(consumer.publicKey)
+        .accounts({
+          consumer: consumer.publicKey,
+          owner: consumer.publicKey,
+          agency: wallet.publicKey,
+          consumerWtk: consumerWtk.address,
+          consumerWst: consumerWst.address,
+          wtkMint,
+          wstMint,
+        })
+        .signers([consumer])
+        .rpc();
+      assert.fail("Previous holder should not reassign the account");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+
+  it("should adjust consumer capacity by a delta", async () => {
+    const balanceBefore = Number(
+      (await provider.connection.getTokenAccountBalance(consumerWatcAccount))
+        .value.amount
+    );
+
+    // Top up capacity after an outage, then claw back part of it
+    await program.methods
+      .adjustCapacity(new anchor.BN(5000), { serviceInterruption: {} })
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await program.methods
+      .adjustCapacity(new anchor.BN(-2000), { mintingError: {} })
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const balanceAfter = Number(
+      (await provider.connection.getTokenAccountBalance(consumerWatcAccount))
+        .value.amount
+    );
+    assert.equal(balanceAfter, balanceBefore + 3000);
+
+    // Burning more than the remaining balance is rejected
+    try {
+      await program.methods
+        .adjustCapacity(new anchor.BN(-(balanceAfter + 1)), { chargeback: {} })
+        .accounts({
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          watcMint: watcMint,
+        })
+        .signers([consumer])
+        .rpc();
+      assert.fail("Expected the adjustment to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InsufficientCapacity");
+    }
+  });
+
+  it("should bind and rotate the consumer's identity", async () => {
+    const { assignedTariff } = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    const identityUri = "ipfs://bafy-kyc-record";
+
+    await program.methods
+      .updateConsumerIdentity(assignedTariff, Array(32).fill(1), identityUri)
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    let consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.deepEqual(consumerAccount.identityHash, Array(32).fill(1));
+    assert.equal(consumerAccount.identityUri, identityUri);
+
+    // The customer record changed, so the binding is rotated
+    await program.methods
+      .updateConsumerIdentity(assignedTariff, Array(32).fill(2), null)
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.deepEqual(consumerAccount.identityHash, Array(32).fill(2));
+    assert.isNull(consumerAccount.identityUri);
+
+    try {
+      await program.methods
+        .updateConsumerIdentity(assignedTariff, Array(32).fill(3), "")
+        .accounts({
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the identity update to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidIdentityUri");
+    }
+  });
+
+  it("should accept readings signed by a device session key", async () => {
+    const device = Keypair.generate();
+    const now = Math.floor(Date.now() / 1000);
+
+    // The agency provisions the meter with a key valid for an hour
+    await program.methods
+      .registerSessionKey(device.publicKey, new anchor.BN(now + 3600))
+      .accounts({
+        consumer: consumer.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    const submitReading = (reading: number) =>
+      program.methods
+        .submitReading(new anchor.BN(reading))
+        .accounts({
+          consumer: consumer.publicKey,
+          device: device.publicKey,
+        })
+        .signers([device])
+        .rpc();
+
+    await submitReading(1200);
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(consumerAccount.meterReading.toNumber(), 1200);
+    assert.isAbove(consumerAccount.meterReadAt.toNumber(), 0);
+
+    try {
+      await submitReading(1100);
+      assert.fail("Expected the reading to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidMeterReading");
+    }
+
+    // Sessions must expire in the future
+    try {
+      await program.methods
+        .registerSessionKey(device.publicKey, new anchor.BN(now - 60))
+        .accounts({
+          consumer: consumer.publicKey,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the registration to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidSessionExpiry");
+    }
+
+    // Revoked keys can no longer sign readings
+    await program.methods
+      .revokeSessionKey(device.publicKey)
+      .accounts({
+        consumer: consumer.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+    try {
+      await submitReading(1300);
+      assert.fail("Expected the reading to fail");
+    } catch (err) {
+      assert.include(err.toString(), "AccountNotInitialized");
+    }
+  });
+
+  it("should accept a signed meter payload from any relayer", async () => {
+    const device = Keypair.generate();
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .registerSessionKey(device.publicKey, new anchor.BN(now + 3600))
+      .accounts({
+        consumer: consumer.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    const { meterReading, meterReadAt } = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    // Let the cluster clock move past the previous reading
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    const relayReading = (
+      reading: number,
+      timestamp: number,
+      signedReading = reading
+    ) => {
+      const payload = Buffer.alloc(48);
+      consumer.publicKey.toBuffer().copy(payload, 0);
+      payload.writeBigUInt64LE(BigInt(signedReading), 32);
+      payload.writeBigInt64LE(BigInt(timestamp), 40);
+      return program.methods
+        .relayReading(
+          device.publicKey,
+          new anchor.BN(reading),
+          new anchor.BN(timestamp)
+        )
+        .accounts({
+          consumer: consumer.publicKey,
+          relayer: wallet.publicKey,
+        })
+        .preInstructions([
+          Ed25519Program.createInstructionWithPrivateKey({
+            privateKey: device.secretKey,
+            message: payload,
+          }),
+        ])
+        .rpc();
+    };
+
+    const reading = meterReading.toNumber() + 100;
+    const timestamp = meterReadAt.toNumber() + 1;
+    await relayReading(reading, timestamp);
+
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(consumerAccount.meterReading.toNumber(), reading);
+    assert.equal(consumerAccount.meterReadAt.toNumber(), timestamp);
+
+    // The relayed reading must match the one the device signed
+    try {
+      await relayReading(reading + 1, timestamp + 1, reading);
+      assert.fail("Expected the relay to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidMeterSignature");
+    }
+
+    // Payloads older than the latest reading cannot be replayed
+    try {
+      await relayReading(reading, timestamp - 1);
+      assert.fail("Expected the relay to fail");
+    } catch (err) {
+      assert.include(err.toString(), "StaleMeterReading");
+    }
+  });
+
+  it("should accept a meter payload signed with secp256k1", async () => {
+    // An existing AMI head-end key, identified on-chain by its Ethereum address
+    const privateKey = Keypair.generate().secretKey.slice(0, 32);
+    const signPayload = (reading: number, timestamp: number) => {
+      const payload = Buffer.alloc(48);
+      consumer.publicKey.toBuffer().copy(payload, 0);
+      payload.writeBigUInt64LE(BigInt(reading), 32);
+      payload.writeBigInt64LE(BigInt(timestamp), 40);
+      return Secp256k1Program.createInstructionWithPrivateKey({
+        privateKey,
+        message: payload,
+        instructionIndex: 0,
+      });
+    };
+    const ethAddress = [...signPayload(0, 0).data.subarray(12, 32)];
+
+    const deviceKey = new PublicKey(
+      Buffer.concat([Buffer.alloc(12), Buffer.from(ethAddress)])
+    );
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .registerSessionKey(deviceKey, new anchor.BN(now + 3600))
+      .accounts({
+        consumer: consumer.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+    const [sessionPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("session_key"),
+        consumer.publicKey.toBuffer(),
+        deviceKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    const { meterReading, meterReadAt } = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    const reading = meterReading.toNumber() + 100;
+    const timestamp = meterReadAt.toNumber() + 1;
+    await program.methods
+      .relayReadingSecp256k1(
+        ethAddress,
+        new anchor.BN(reading),
+        new anchor.BN(timestamp)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        session: sessionPDA,
+        relayer: wallet.publicKey,
+      })
+      .preInstructions([signPayload(reading, timestamp)])
+      .rpc();
+
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(consumerAccount.meterReading.toNumber(), reading);
+    assert.equal(consumerAccount.meterReadAt.toNumber(), timestamp);
+  });
 });
@@ -0,0 +1,176 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { createHash } from "crypto";
+import { assert } from "chai";
+import {
+  grantRegulatorRole,
+  regulatorAuthority,
+  regulatorAuthorityPDA,
+} from "./fixtures/regulator";
+
+describe("conveyance", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  const sourceKey = Keypair.generate().publicKey;
+  const destinationKey = Keypair.generate().publicKey;
+  const regulator = Keypair.generate();
+
+  const initialReservoirLevel = 500000; // 500.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const conveyedVolume = 100000; // 100.000
+  const destinationBasin = 2;
+
+  const permitHash = [
+    ...createHash("sha256").update("inter-basin transfer permit").digest(),
+  ];
+
+  const rolePDA = (member: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("role"), wallet.publicKey.toBuffer(), member.toBuffer()],
+      program.programId
+    )[0];
+
+  const reservoirPDA = (reservoirKey: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservoir"),
+        wallet.publicKey.toBuffer(),
+        reservoirKey.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const conveyWater = (hash: number[], approver: Keypair | null) => {
+    const builder = program.methods
+      .conveyWater(
+        sourceKey,
+        destinationKey,
+        new anchor.BN(conveyedVolume),
+        hash
+      )
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+        regulatorAuthority: regulatorAuthorityPDA(program),
+        regulator: approver ? approver.publicKey : null,
+        regulatorRole: approver ? rolePDA(approver.publicKey) : null,
+      });
+    return approver ? builder.signers([approver]).rpc() : builder.rpc();
+  };
+
+  const level = async (reservoirKey: PublicKey) =>
+    (
+      await program.account.reservoir.fetch(reservoirPDA(reservoirKey))
+    ).currentLevel.toNumber();
+
+  before(async () => {
+    for (const reservoirKey of [sourceKey, destinationKey]) {
+      await program.methods
+        .initializeReservoir(
+          reservoirKey,
+          new anchor.BN(initialReservoirLevel),
+          new anchor.BN(initialReservoirCapacity)
+        )
+        .accounts({
+          agency: wallet.publicKey,
+        })
+        .rpc();
+    }
+
+    await grantRegulatorRole(program, regulator.publicKey);
+  });
+
+  it("should convey water within a basin without approval", async () => {
+    await conveyWater(new Array(32).fill(0), null);
+
+    assert.equal(
+      await level(sourceKey),
+      initialReservoirLevel - conveyedVolume
+    );
+    assert.equal(
+      await level(destinationKey),
+      initialReservoirLevel + conveyedVolume
+    );
+  });
+
+  it("should require a regulator across basins", async () => {
+    await program.methods
+      .updateReservoirBasin(destinationKey, destinationBasin)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    try {
+      await conveyWater(permitHash, null);
+      assert.fail("Expected the transfer to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "MissingRegulatorApproval");
+    }
+
+    try {
+      await conveyWater(new Array(32).fill(0), regulator);
+      assert.fail("Expected the transfer to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidDocumentHash");
+    }
+  });
+
+  it("should convey water across basins with a permit", async () => {
+    await conveyWater(permitHash, regulator);
+
+    assert.equal(
+      await level(sourceKey),
+      initialReservoirLevel - 2 * conveyedVolume
+    );
+    assert.equal(
+      await level(destinationKey),
+      initialReservoirLevel + 2 * conveyedVolume
+    );
+  });
+
+  it("should accept the regulator authority's own approval", async () => {
+    await conveyWater(permitHash, regulatorAuthority);
+
+    assert.equal(
+      await level(sourceKey),
+      initialReservoirLevel - 3 * conveyedVolume
+    );
+  });
+
+  it("should cap the level and record the spill at capacity", async () => {
+    const capacity = initialReservoirLevel + 3 * conveyedVolume + 50000;
+    await program.methods
+      .updateReservoirConfig(destinationKey, new anchor.BN(capacity))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await conveyWater(permitHash, regulator);
+
+    assert.equal(await level(destinationKey), capacity);
+    const spillRecord = await program.account.spillRecord.fetch(
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("spill_record"),
+          wallet.publicKey.toBuffer(),
+          destinationKey.toBuffer(),
+        ],
+        program.programId
+      )[0]
+    );
+    assert.equal(spillRecord.spillCount.toNumber(), 1);
+    assert.equal(spillRecord.totalSpilled.toNumber(), conveyedVolume - 50000);
+  });
+});
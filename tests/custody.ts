@@ -0,0 +1,387 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  mintTo,
+  revoke,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("custody", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let usdcMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let consumerWatcAccount: PublicKey;
+  let consumerUsdcAccount: PublicKey;
+  let fxOraclePDA: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const SCALE = 1000;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const initialContractedCapacity = 100000; // 100.000
+  const initialBlockRate = 800; // 0.800
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const currencyCode = [...Buffer.from("BZD")];
+  const USDC_DECIMALS = 6;
+  const fxRate = 500000; // 1 BZD = 0.500000 USDC
+  const initialUsdcBalance = 10_000_000; // 10.000000 USDC
+  const autopayCap = 5_000_000; // 5.000000 USDC
+
+  const useWater = (amount: number) =>
+    program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(amount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+  const collectAutopay = () =>
+    program.methods
+      .collectAutopay(tariffKey)
+      .accounts({
+        cranker: wallet.publicKey,
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+        settlementMint: usdcMint,
+      })
+      .rpc();
+
+  const wtkBalance = async () =>
+    Number((await getAccount(connection, consumerWtkAccount)).amount);
+
+  before(async () => {
+    // Initialize accounts
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    [fxOraclePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("fx_oracle"),
+        wallet.publicKey.toBuffer(),
+        Buffer.from(currencyCode),
+      ],
+      program.programId
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    usdcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      USDC_DECIMALS
+    );
+
+    // Create token accounts
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    consumerWatcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wstMint,
+      consumer.publicKey
+    );
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      wallet.publicKey
+    );
+
+    consumerUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    await mintTo(
+      connection,
+      wallet.payer,
+      usdcMint,
+      consumerUsdcAccount,
+      wallet.payer,
+      initialUsdcBalance
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a tariff denominated in BZD
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateTariffCurrency(tariffKey, currencyCode)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a reservoir
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(initialContractedCapacity),
+        new anchor.BN(initialBlockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    // Register the FX oracle, with the agency wallet posting rates
+    await program.methods
+      .initializeFxOracle(currencyCode, wallet.publicKey, new anchor.BN(1000))
+      .accounts({
+        settlementMint: usdcMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateFxRate(new anchor.BN(fxRate))
+      .accounts({
+        fxOracle: fxOraclePDA,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("WTK cannot be burned without the consumer's signature", async () => {
+    const waterAmount = 20000; // 20.000
+    await useWater(waterAmount);
+    const debt = await wtkBalance();
+    assert.isAbove(debt, 0);
+
+    try {
+      await program.methods
+        .payForWater(tariffKey, reservoirKey, new anchor.BN(debt))
+        .accounts({
+          consumer: consumer.publicKey,
+          wtkMint: wtkMint,
+          agency: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the payment to fail");
+    } catch (err) {
+      assert.include(err.toString(), "Missing signature");
+    }
+    assert.equal(await wtkBalance(), debt);
+  });
+
+  it("Consumer burns its own WTK when it signs the payment", async () => {
+    const debt = await wtkBalance();
+
+    await program.methods
+      .payForWater(tariffKey, reservoirKey, new anchor.BN(debt))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    assert.equal(await wtkBalance(), 0);
+  });
+
+  it("Consumer burns its own WST when paying for waste", async () => {
+    const wasteAmount = 10000; // 10.000
+    await program.methods
+      .disposeWaste(tariffKey, new anchor.BN(wasteAmount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wstMint: wstMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .payForWaste(
+        tariffKey,
+        new anchor.BN((wasteAmount * initialWasteRate) / SCALE)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        wstMint: wstMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const consumerWst = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wstMint,
+      consumer.publicKey
+    );
+    assert.equal(Number(consumerWst.amount), 0);
+  });
+
+  it("Billing authority cannot burn undelegated WTK", async () => {
+    await program.methods
+      .enableAutopay(new anchor.BN(autopayCap))
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+        settlementMint: usdcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    // The consumer revokes its WTK delegation, keeping the settlement one
+    await revoke(connection, wallet.payer, consumerWtkAccount, consumer);
+
+    await useWater(10000);
+    const debt = await wtkBalance();
+
+    try {
+      await collectAutopay();
+      assert.fail("Expected the collection to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InsufficientDelegation");
+    }
+    assert.equal(await wtkBalance(), debt);
+  });
+
+  it("Billing authority burns delegated WTK unsigned", async () => {
+    await program.methods
+      .enableAutopay(new anchor.BN(autopayCap))
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+        settlementMint: usdcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await collectAutopay();
+
+    assert.equal(await wtkBalance(), 0);
+  });
+
+  it("Consumer signs the clawback of its own WATC", async () => {
+    const before = Number(
+      (await getAccount(connection, consumerWatcAccount)).amount
+    );
+    const clawback = 10000; // 10.000
+
+    await program.methods
+      .adjustCapacity(new anchor.BN(-clawback), { mintingError: {} })
+      .accounts({
+        consumer: consumer.publicKey,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const after = Number(
+      (await getAccount(connection, consumerWatcAccount)).amount
+    );
+    assert.equal(after, before - clawback);
+  });
+});
@@ -0,0 +1,287 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  revoke,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("delegation", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let consumerWstAccount: PublicKey;
+  let billingAuthority: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const SCALE = 1000;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const initialContractedCapacity = 100000; // 100.000
+  const initialBlockRate = 800; // 0.800
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const receiptPDA = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("receipt"),
+        consumer.publicKey.toBuffer(),
+        new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+  const useWater = (amount: number) =>
+    program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(amount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+  const payForWaterDelegated = (amount: number) =>
+    program.methods
+      .payForWaterDelegated(new anchor.BN(amount))
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+      })
+      .rpc();
+
+  before(async () => {
+    // Initialize accounts
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    [billingAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("billing_authority"), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    // Create token accounts
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      consumer.publicKey
+    );
+
+    consumerWstAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wstMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a tariff
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a reservoir
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("Consumer delegates its debt tokens at registration", async () => {
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(initialContractedCapacity),
+        new anchor.BN(initialBlockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+        billingAuthority,
+        consumerWtk: consumerWtkAccount,
+        consumerWst: consumerWstAccount,
+      })
+      .signers([consumer])
+      .rpc();
+
+    for (const address of [consumerWtkAccount, consumerWstAccount]) {
+      const account = await getAccount(connection, address);
+      assert.isTrue(account.delegate.equals(billingAuthority));
+    }
+  });
+
+  it("Agency settles water debt without the consumer's signature", async () => {
+    const waterAmount = 20000; // 20.000
+    await useWater(waterAmount);
+
+    const debt = Number(
+      (await getAccount(connection, consumerWtkAccount)).amount
+    );
+    await payForWaterDelegated(debt);
+
+    const consumerWtk = await getAccount(connection, consumerWtkAccount);
+    assert.equal(Number(consumerWtk.amount), 0);
+
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(consumerAccount.outstandingWaterDebt.toNumber(), 0);
+
+    const receipt = await program.account.paymentReceipt.fetch(receiptPDA(0));
+    assert.equal(receipt.amount.toNumber(), debt);
+    assert.isTrue(receipt.payer.equals(consumer.publicKey));
+  });
+
+  it("Agency settles waste debt without the consumer's signature", async () => {
+    const wasteAmount = 10000; // 10.000
+    await program.methods
+      .disposeWaste(tariffKey, new anchor.BN(wasteAmount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wstMint: wstMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .payForWasteDelegated(
+        new anchor.BN((wasteAmount * initialWasteRate) / SCALE)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wstMint: wstMint,
+      })
+      .rpc();
+
+    const consumerWst = await getAccount(connection, consumerWstAccount);
+    assert.equal(Number(consumerWst.amount), 0);
+  });
+
+  it("Zero delegated payments are rejected", async () => {
+    try {
+      await payForWaterDelegated(0);
+      assert.fail("Expected the water payment to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidAmount");
+    }
+
+    try {
+      await program.methods
+        .payForWasteDelegated(new anchor.BN(0))
+        .accounts({
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          wstMint: wstMint,
+        })
+        .rpc();
+      assert.fail("Expected the waste payment to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidAmount");
+    }
+  });
+
+  it("Consumer can revoke the delegation", async () => {
+    await revoke(connection, wallet.payer, consumerWtkAccount, consumer);
+
+    await useWater(10000);
+    const debt = Number(
+      (await getAccount(connection, consumerWtkAccount)).amount
+    );
+
+    try {
+      await payForWaterDelegated(debt);
+      assert.fail("Expected the payment to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InsufficientDelegation");
+    }
+
+    const consumerWtk = await getAccount(connection, consumerWtkAccount);
+    assert.equal(Number(consumerWtk.amount), debt);
+  });
+});
@@ -0,0 +1,322 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe("demand response", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let usdcMint: PublicKey;
+  let tariffKey: PublicKey;
+  let tariffPDA: PublicKey;
+  let reservoirKey: PublicKey;
+  let eventKey: PublicKey;
+  let drEventPDA: PublicKey;
+  let consumerUsdcAccount: PublicKey;
+  let consumer: Keypair;
+  let endSlot: number;
+
+  const targetVolume = 1000;
+  const paymentRate = 2; // 2 USDC base units per raw volume unit saved
+  const bidVolume = 500;
+  const weatherAdjustmentBps = 11500; // a heatwave raises demand by 15%
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const bid = (volume: number) =>
+    program.methods
+      .bidCurtailment(new anchor.BN(volume))
+      .accounts({
+        drEvent: drEventPDA,
+        consumer: consumer.publicKey,
+        tariff: tariffPDA,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+  const settle = () =>
+    program.methods
+      .settleDrEvent()
+      .accounts({
+        drEvent: drEventPDA,
+        consumer: consumer.publicKey,
+        settlementMint: usdcMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+    eventKey = Keypair.generate().publicKey;
+    consumer = Keypair.generate();
+
+    [tariffPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("tariff"),
+        wallet.publicKey.toBuffer(),
+        tariffKey.toBuffer(),
+      ],
+      program.programId
+    );
+    [drEventPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("dr_event"),
+        wallet.publicKey.toBuffer(),
+        eventKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    usdcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      6
+    );
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    );
+
+    consumerUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    // Fund the agency's treasury
+    const agencyUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      wallet.publicKey
+    ).then((account) => account.address);
+    await mintTo(
+      connection,
+      wallet.payer,
+      usdcMint,
+      agencyUsdcAccount,
+      wallet.payer,
+      targetVolume * paymentRate
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(500),
+        new anchor.BN(200),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(950000),
+        new anchor.BN(1000000)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(100000),
+        new anchor.BN(800)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await program.methods
+      .updateTariffBillingPeriod(tariffKey, new anchor.BN(2))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Usage in the capacity period sets the consumer's baseline once it closes
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(50000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+  });
+
+  it("should refuse events whose window is not in the future", async () => {
+    const slot = await connection.getSlot();
+    try {
+      await program.methods
+        .announceDrEvent(
+          eventKey,
+          reservoirKey,
+          new anchor.BN(slot),
+          new anchor.BN(slot + 20),
+          new anchor.BN(targetVolume),
+          new anchor.BN(paymentRate),
+          weatherAdjustmentBps
+        )
+        .accounts({
+          settlementMint: usdcMint,
+          agency: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the event to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidDrEvent");
+    }
+  });
+
+  it("should accept bids against closed periods", async () => {
+    const startSlot = (await connection.getSlot()) + 20;
+    endSlot = startSlot + 10;
+    await program.methods
+      .announceDrEvent(
+        eventKey,
+        reservoirKey,
+        new anchor.BN(startSlot),
+        new anchor.BN(endSlot),
+        new anchor.BN(targetVolume),
+        new anchor.BN(paymentRate),
+        weatherAdjustmentBps
+      )
+      .accounts({
+        settlementMint: usdcMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    // No capacity period has closed yet, so the consumer has no baseline
+    try {
+      await bid(bidVolume);
+      assert.fail("Expected the bid without a baseline to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidCurtailmentBid");
+    }
+
+    // Let the two-slot billing period elapse and close it
+    await sleep(2000);
+    await program.methods
+      .refreshCapacity(tariffKey)
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .rpc();
+
+    try {
+      await bid(targetVolume + 1);
+      assert.fail("Expected the bid to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidCurtailmentBid");
+    }
+
+    await bid(bidVolume);
+    const drEvent = await program.account.drEvent.fetch(drEventPDA);
+    assert.equal(drEvent.bidVolume.toNumber(), bidVolume);
+  });
+
+  it("should pay verified reductions once the window closes", async () => {
+    try {
+      await settle();
+      assert.fail("Expected settlement to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "DrEventClosed");
+    }
+
+    while ((await connection.getSlot()) < endSlot) {
+      await sleep(500);
+    }
+
+    // Nothing was used during the window, so the whole bid is paid
+    await settle();
+    assert.equal(
+      Number((await getAccount(connection, consumerUsdcAccount)).amount),
+      bidVolume * paymentRate
+    );
+
+    try {
+      await settle();
+      assert.fail("Expected the second settlement to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "CurtailmentAlreadySettled");
+    }
+  });
+});
@@ -0,0 +1,270 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("drought", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let droughtInsurancePDA: PublicKey;
+  let droughtCoverPDA: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  // Reservoir at 10% of its capacity, below the pool's trigger level
+  const initialReservoirLevel = 100000; // 100.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const initialContractedCapacity = 100000; // 100.000
+  const initialBlockRate = 800; // 0.800
+
+  const premium = 10000; // 10.000 WTK per period
+  const payoutBps = 5000; // half of the surcharge
+  const triggerLevelBps = 2000; // below 20% of capacity
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  before(async () => {
+    // Initialize accounts
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    [droughtInsurancePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("drought_insurance"),
+        wallet.publicKey.toBuffer(),
+        reservoirKey.toBuffer(),
+      ],
+      program.programId
+    );
+    [droughtCoverPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("drought_cover"), consumer.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    // Create token accounts
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      consumer.publicKey
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(initialContractedCapacity),
+        new anchor.BN(initialBlockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+  });
+
+  it("Consumer joins the reservoir's drought insurance pool", async () => {
+    await program.methods
+      .configureDroughtInsurance(
+        reservoirKey,
+        new anchor.BN(premium),
+        payoutBps,
+        triggerLevelBps
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .joinDroughtInsurance(reservoirKey)
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const pool = await program.account.droughtInsurance.fetch(
+      droughtInsurancePDA
+    );
+    assert.equal(pool.memberCount.toNumber(), 1);
+    assert.equal(pool.balance.toNumber(), 0);
+
+    const cover = await program.account.droughtCover.fetch(droughtCoverPDA);
+    assert.isNull(cover.paidPeriodStart);
+  });
+
+  it("Premium is billed once per capacity period", async () => {
+    await program.methods
+      .collectDroughtPremium()
+      .accounts({
+        consumer: consumer.publicKey,
+        droughtInsurance: droughtInsurancePDA,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+      })
+      .rpc();
+
+    const pool = await program.account.droughtInsurance.fetch(
+      droughtInsurancePDA
+    );
+    assert.equal(pool.balance.toNumber(), premium);
+
+    const consumerWtk = await getAccount(connection, consumerWtkAccount);
+    assert.equal(Number(consumerWtk.amount), premium);
+
+    try {
+      await program.methods
+        .collectDroughtPremium()
+        .accounts({
+          consumer: consumer.publicKey,
+          droughtInsurance: droughtInsurancePDA,
+          agency: wallet.publicKey,
+          wtkMint: wtkMint,
+        })
+        .rpc();
+      assert.fail("Premium was collected twice in one period");
+    } catch (err) {
+      assert.include(err.toString(), "PremiumAlreadyPaid");
+    }
+  });
+
+  it("Pool offsets the surcharge while the reservoir is low", async () => {
+    // 150 units: 100 at 0.500 and 50 at 0.800 is 90.000, against a flat
+    // 75.000, so half of the 15.000 surcharge is paid out of the pool
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(150000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+        droughtCover: droughtCoverPDA,
+        droughtInsurance: droughtInsurancePDA,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const consumerWtk = await getAccount(connection, consumerWtkAccount);
+    assert.equal(Number(consumerWtk.amount), premium + 82500);
+
+    const pool = await program.account.droughtInsurance.fetch(
+      droughtInsurancePDA
+    );
+    assert.equal(pool.balance.toNumber(), premium - 7500);
+
+    const cover = await program.account.droughtCover.fetch(droughtCoverPDA);
+    assert.equal(cover.totalPayouts.toNumber(), 7500);
+  });
+
+  it("Consumer leaves the drought insurance pool", async () => {
+    await program.methods
+      .leaveDroughtInsurance()
+      .accounts({
+        consumer: consumer.publicKey,
+        droughtInsurance: droughtInsurancePDA,
+        droughtCover: droughtCoverPDA,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const pool = await program.account.droughtInsurance.fetch(
+      droughtInsurancePDA
+    );
+    assert.equal(pool.memberCount.toNumber(), 0);
+    assert.isNull(await connection.getAccountInfo(droughtCoverPDA));
+  });
+});
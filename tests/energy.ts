@@ -0,0 +1,244 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("energy", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const oracleAuthority = Keypair.generate();
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  const pumpingIntensity = 400; // 0.400 kWh per unit
+  const energyPrice = 250; // 0.250 per kWh
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const energyOraclePDA = PublicKey.findProgramAddressSync(
+    [Buffer.from("energy_oracle"), wallet.publicKey.toBuffer()],
+    program.programId
+  )[0];
+
+  const updateEnergyPrice = (signer: Keypair) =>
+    program.methods
+      .updateEnergyPrice(new anchor.BN(energyPrice))
+      .accounts({
+        energyOracle: energyOraclePDA,
+        authority: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const sourceMixPDA = PublicKey.findProgramAddressSync(
+    [Buffer.from("source_mix"), wallet.publicKey.toBuffer()],
+    program.programId
+  )[0];
+
+  const useWater = (amount: number, sourceMix: PublicKey | null = null) =>
+    program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(amount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+        energyOracle: energyOraclePDA,
+        sourceMix,
+      })
+      .signers([consumer])
+      .rpc();
+
+  const wtkBalance = async () =>
+    Number((await getAccount(connection, consumerWtkAccount)).amount);
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await program.methods
+      .initializeEnergyOracle(oracleAuthority.publicKey, new anchor.BN(1000))
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateReservoirPumping(reservoirKey, new anchor.BN(pumpingIntensity))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("should refuse to bill energy before a price is posted", async () => {
+    try {
+      await useWater(40000);
+      assert.fail("Expected the usage to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "StaleOracle");
+    }
+  });
+
+  it("should only accept prices from the oracle authority", async () => {
+    try {
+      await updateEnergyPrice(Keypair.generate());
+      assert.fail("Expected the price to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+
+  it("should pass pumping costs through to the consumer", async () => {
+    await updateEnergyPrice(oracleAuthority);
+
+    // 40.000 units at 0.500, plus 16.000 kWh at 0.250
+    await useWater(40000);
+    assert.equal(await wtkBalance(), 24000);
+  });
+
+  it("should bill the blended cost of the agency's supply sources", async () => {
+    try {
+      await program.methods
+        .publishSourceMix([
+          { kind: { reservoir: {} }, unitCost: new anchor.BN(0), shareBps: 8000 },
+        ])
+        .accounts({
+          agency: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the source mix to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidSourceMix");
+    }
+
+    await program.methods
+      .publishSourceMix([
+        { kind: { reservoir: {} }, unitCost: new anchor.BN(0), shareBps: 8000 },
+        {
+          kind: { desalination: {} },
+          unitCost: new anchor.BN(1500), // 1.500 per unit
+          shareBps: 2000,
+        },
+      ])
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    // 40.000 units at 0.500, 16.000 kWh at 0.250, plus 40.000 units at a blended 0.300
+    await useWater(40000, sourceMixPDA);
+    assert.equal(await wtkBalance(), 24000 + 36000);
+  });
+});
@@ -0,0 +1,61 @@
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../../target/types/aquachain";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+
+// Every suite runs as the same agency, whose regulator can only be appointed
+// once, so the suites share a regulator derived from a fixed seed
+export const regulatorAuthority = Keypair.fromSeed(
+  new Uint8Array(32).fill(7)
+);
+
+export const regulatorAuthorityPDA = (program: Program<Aquachain>) =>
+  PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("regulator_authority"),
+      program.provider.publicKey.toBuffer(),
+    ],
+    program.programId
+  )[0];
+
+// Appoints the shared regulator for the provider's agency, unless a suite
+// run before already did
+export const appointRegulator = async (program: Program<Aquachain>) => {
+  const address = regulatorAuthorityPDA(program);
+  const connection = program.provider.connection;
+  if (await connection.getAccountInfo(address)) {
+    return address;
+  }
+
+  await connection.confirmTransaction(
+    await connection.requestAirdrop(
+      regulatorAuthority.publicKey,
+      LAMPORTS_PER_SOL
+    ),
+    "confirmed"
+  );
+  await program.methods
+    .appointRegulator()
+    .accounts({
+      agency: program.provider.publicKey,
+      authority: regulatorAuthority.publicKey,
+    })
+    .signers([regulatorAuthority])
+    .rpc();
+  return address;
+};
+
+// Grants a key the Regulator role on behalf of the shared regulator
+export const grantRegulatorRole = async (
+  program: Program<Aquachain>,
+  member: PublicKey
+) => {
+  await appointRegulator(program);
+  await program.methods
+    .grantRegulatorRole(member)
+    .accounts({
+      agency: program.provider.publicKey,
+      authority: regulatorAuthority.publicKey,
+    })
+    .signers([regulatorAuthority])
+    .rpc();
+};
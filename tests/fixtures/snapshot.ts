@@ -0,0 +1,34 @@
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { AddedAccount } from "solana-bankrun";
+import * as fs from "fs";
+import * as path from "path";
+
+// Written by `cargo run -p aquachain-fixtures -- snapshot`
+export const SNAPSHOT_DIR = "target/dev-fixtures";
+
+// Reads every account of the snapshot, stored in `solana account` JSON format
+export const loadSnapshot = (dir = SNAPSHOT_DIR): AddedAccount[] =>
+  fs.readdirSync(path.join(dir, "accounts")).map((file) => {
+    const { pubkey, account } = JSON.parse(
+      fs.readFileSync(path.join(dir, "accounts", file), "utf8")
+    );
+    return {
+      address: new PublicKey(pubkey),
+      info: {
+        lamports: account.lamports,
+        data: Buffer.from(account.data[0], "base64"),
+        owner: new PublicKey(account.owner),
+        executable: account.executable,
+      },
+    };
+  });
+
+// Reads the manifest listing the agency, mints, tariffs, reservoirs and
+// consumers
+export const loadManifest = (dir = SNAPSHOT_DIR) =>
+  JSON.parse(fs.readFileSync(path.join(dir, "manifest.json"), "utf8"));
+
+export const loadKeypair = (file: string) =>
+  Keypair.fromSecretKey(
+    Uint8Array.from(JSON.parse(fs.readFileSync(file, "utf8")))
+  );
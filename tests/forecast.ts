@@ -0,0 +1,220 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("forecast", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  // Rationing curve: triple the block rate below half full, 1.5x above it
+  const scarcityCurve = [
+    { maxLevelBps: 5000, multiplier: new anchor.BN(3000) },
+    { maxLevelBps: 10000, multiplier: new anchor.BN(1500) },
+  ];
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const levelForecastPDA = () =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("level_forecast"),
+        wallet.publicKey.toBuffer(),
+        reservoirKey.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const postLevelForecast = (levels: number[]) =>
+    program.methods
+      .postLevelForecast(
+        reservoirKey,
+        new anchor.BN(1000000),
+        levels.map((level) => new anchor.BN(level))
+      )
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+  const useWater = (amount: number, levelForecast: PublicKey | null) =>
+    program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(amount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+        levelForecast,
+      })
+      .signers([consumer])
+      .rpc();
+
+  const wtkBalance = async () =>
+    Number((await getAccount(connection, consumerWtkAccount)).amount);
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { seasonalIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await program.methods
+      .updateReservoirScarcityCurve(reservoirKey, scarcityCurve)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateTariffForecastPricing(tariffKey, true)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("should refuse to bill without a forecast", async () => {
+    try {
+      await useWater(10000, null);
+      assert.fail("Expected the usage to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "ForecastUnavailable");
+    }
+  });
+
+  it("should reject levels above the reservoir capacity", async () => {
+    try {
+      await postLevelForecast([initialReservoirCapacity + 1]);
+      assert.fail("Expected the forecast to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidLevelForecast");
+    }
+  });
+
+  it("should price usage on the projected level", async () => {
+    await postLevelForecast([400000, 300000]);
+
+    const forecast = await program.account.levelForecast.fetch(
+      levelForecastPDA()
+    );
+    assert.equal(forecast.levels.length, 2);
+
+    // The reservoir is 95% full, but projected at 40%: the block rate triples
+    // 100.000 units at 0.500, plus 20.000 units at 0.800 x 3
+    await useWater(120000, levelForecastPDA());
+    assert.equal(await wtkBalance(), 98000);
+  });
+});
@@ -0,0 +1,235 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe("forward", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let consumerWatcAccount: PublicKey;
+  let forwardContractPDA: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+  const raisedWaterRate = 900; // 0.900
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+  const billingPeriodSlots = 20;
+
+  const forwardCapacity = 40000; // 40.000
+  const premium = 1000; // 1.000 WTK
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const settleForwardContract = () =>
+    program.methods
+      .settleForwardContract()
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+      })
+      .rpc();
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    [forwardContractPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("forward_contract"), consumer.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    // Create token accounts
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    consumerWatcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateTariffBillingPeriod(tariffKey, new anchor.BN(billingPeriodSlots))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+  });
+
+  it("Consumer locks today's rate for next period's capacity", async () => {
+    await program.methods
+      .openForwardContract(
+        tariffKey,
+        new anchor.BN(forwardCapacity),
+        new anchor.BN(premium)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const forward = await program.account.forwardContract.fetch(
+      forwardContractPDA
+    );
+    assert.equal(forward.lockedRate.toNumber(), initialWaterRate);
+    assert.equal(forward.capacity.toNumber(), forwardCapacity);
+
+    const consumerWtk = await getAccount(connection, consumerWtkAccount);
+    assert.equal(Number(consumerWtk.amount), premium);
+
+    try {
+      await settleForwardContract();
+      assert.fail("Capacity was delivered before its period started");
+    } catch (err) {
+      assert.include(err.toString(), "ForwardNotDue");
+    }
+  });
+
+  it("Capacity is delivered at the locked rate", async () => {
+    await program.methods
+      .updateTariffRates(
+        tariffKey,
+        new anchor.BN(raisedWaterRate),
+        new anchor.BN(initialWasteRate)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    const forward = await program.account.forwardContract.fetch(
+      forwardContractPDA
+    );
+    while ((await connection.getSlot()) < forward.deliverySlot.toNumber()) {
+      await sleep(500);
+    }
+
+    await settleForwardContract();
+
+    // 40.000 units at the locked 0.500 is 20.000 WTK on top of the premium
+    const consumerWtk = await getAccount(connection, consumerWtkAccount);
+    assert.equal(Number(consumerWtk.amount), premium + 20000);
+
+    const consumerWatc = await getAccount(connection, consumerWatcAccount);
+    assert.equal(
+      Number(consumerWatc.amount),
+      contractedCapacity + forwardCapacity
+    );
+
+    assert.isNull(await connection.getAccountInfo(forwardContractPDA));
+  });
+});
@@ -0,0 +1,208 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { createMint } from "@solana/spl-token";
+import { assert } from "chai";
+import { createHash } from "crypto";
+
+describe("payment gateway", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let gatewayKey: PublicKey;
+  let gatewayPDA: PublicKey;
+  let consumer: Keypair;
+  let attestor: Keypair;
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  // Gateways identify payments by a hash of the mobile-money operator's transaction ID
+  const paymentId = (transactionId: string) => [
+    ...createHash("sha256").update(transactionId).digest(),
+  ];
+
+  const record = (transactionId: string, amount: number, signer = attestor) =>
+    program.methods
+      .recordOffchainPayment(paymentId(transactionId), new anchor.BN(amount))
+      .accounts({
+        paymentGateway: gatewayPDA,
+        consumer: consumer.publicKey,
+        attestor: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const updateGateway = (active: boolean) =>
+    program.methods
+      .updatePaymentGateway(attestor.publicKey, active)
+      .accounts({
+        paymentGateway: gatewayPDA,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+    gatewayKey = Keypair.generate().publicKey;
+    consumer = Keypair.generate();
+    attestor = Keypair.generate();
+
+    [gatewayPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("payment_gateway"),
+        wallet.publicKey.toBuffer(),
+        gatewayKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    // The attestor pays for the payment records
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(attestor.publicKey, LAMPORTS_PER_SOL)
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(500),
+        new anchor.BN(200),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(950000),
+        new anchor.BN(1000000)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(100000),
+        new anchor.BN(800)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await program.methods
+      .registerPaymentGateway(gatewayKey, attestor.publicKey)
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("should credit attested payments to the prepaid balance", async () => {
+    await record("MPESA-QK81XZ4T2B", 15000);
+    await record("MPESA-QK81XZ9R7C", 5000);
+
+    const account = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(account.prepaidBalance.toNumber(), 20000);
+
+    const gateway = await program.account.paymentGateway.fetch(gatewayPDA);
+    assert.equal(gateway.paymentsRecorded.toNumber(), 2);
+    assert.equal(gateway.totalCredited.toNumber(), 20000);
+  });
+
+  it("should not credit a payment twice", async () => {
+    try {
+      await record("MPESA-QK81XZ4T2B", 15000);
+      assert.fail("Expected the replayed payment to fail");
+    } catch (err) {
+      assert.include(err.toString(), "already in use");
+    }
+  });
+
+  it("should only accept payments attested by the gateway", async () => {
+    const impostor = Keypair.generate();
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(impostor.publicKey, LAMPORTS_PER_SOL)
+    );
+
+    try {
+      await record("MPESA-FAKE0001", 1000000, impostor);
+      assert.fail("Expected the payment to fail");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+
+  it("should refuse payments while the gateway is suspended", async () => {
+    await updateGateway(false);
+    try {
+      await record("MPESA-QK81Y02M5D", 1000);
+      assert.fail("Expected the payment to fail");
+    } catch (err) {
+      assert.include(err.toString(), "GatewaySuspended");
+    }
+
+    await updateGateway(true);
+    await record("MPESA-QK81Y02M5D", 1000);
+
+    const account = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(account.prepaidBalance.toNumber(), 21000);
+  });
+});
@@ -0,0 +1,367 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("guarantor", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let usdcMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let agencyUsdcAccount: PublicKey;
+  let fxOraclePDA: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+  let guarantor: Keypair;
+  let guarantorUsdcAccount: PublicKey;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const initialContractedCapacity = 100000; // 100.000
+  const initialBlockRate = 800; // 0.800
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const currencyCode = [...Buffer.from("JMD")];
+  const USDC_DECIMALS = 6;
+  const fxRate = 6400; // 1 JMD = 0.006400 USDC
+  const initialUsdcBalance = 10_000_000; // 10.000000 USDC
+  const guaranteeLimit = 5_000_000; // 5.000000 USDC
+
+  const receiptPDA = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("receipt"),
+        consumer.publicKey.toBuffer(),
+        new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+  before(async () => {
+    // Initialize accounts
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+    guarantor = Keypair.generate();
+
+    [fxOraclePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("fx_oracle"),
+        wallet.publicKey.toBuffer(),
+        Buffer.from(currencyCode),
+      ],
+      program.programId
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    usdcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      USDC_DECIMALS
+    );
+
+    // Create token accounts
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      consumer.publicKey
+    );
+
+    agencyUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      wallet.publicKey
+    ).then((account) => account.address);
+
+    guarantorUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      guarantor.publicKey
+    ).then((account) => account.address);
+
+    await mintTo(
+      connection,
+      wallet.payer,
+      usdcMint,
+      guarantorUsdcAccount,
+      wallet.payer,
+      initialUsdcBalance
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a tariff denominated in JMD
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateTariffCurrency(tariffKey, currencyCode)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a reservoir
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(initialContractedCapacity),
+        new anchor.BN(initialBlockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    // Register the FX oracle, with the agency wallet posting rates
+    await program.methods
+      .initializeFxOracle(currencyCode, wallet.publicKey, new anchor.BN(1000))
+      .accounts({
+        settlementMint: usdcMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateFxRate(new anchor.BN(fxRate))
+      .accounts({
+        fxOracle: fxOraclePDA,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("Guarantor is registered with a pre-approved delegation", async () => {
+    await program.methods
+      .setGuarantor(new anchor.BN(guaranteeLimit))
+      .accounts({
+        consumer: consumer.publicKey,
+        guarantor: guarantor.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+        settlementMint: usdcMint,
+      })
+      .signers([consumer, guarantor])
+      .rpc();
+
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(
+      consumerAccount.guarantor.toBase58(),
+      guarantor.publicKey.toBase58()
+    );
+
+    const guarantorUsdc = await getAccount(connection, guarantorUsdcAccount);
+    assert.equal(Number(guarantorUsdc.delegatedAmount), guaranteeLimit);
+  });
+
+  it("Agency collects defaulted debt from the guarantor", async () => {
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(100000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const debt = 50000; // 50.000 JMD
+
+    await program.methods
+      .claimFromGuarantor(tariffKey, new anchor.BN(debt))
+      .accounts({
+        consumer: consumer.publicKey,
+        guarantor: guarantor.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+        settlementMint: usdcMint,
+      })
+      .rpc();
+
+    const expectedUsdc = (debt * fxRate) / 10 ** DECIMALS;
+
+    const consumerWtkBalance = await connection.getTokenAccountBalance(
+      consumerWtkAccount
+    );
+    const guarantorUsdcBalance = await connection.getTokenAccountBalance(
+      guarantorUsdcAccount
+    );
+    const treasuryBalance = await connection.getTokenAccountBalance(
+      agencyUsdcAccount
+    );
+
+    assert.equal(consumerWtkBalance.value.amount, "0");
+    assert.equal(
+      guarantorUsdcBalance.value.amount,
+      String(initialUsdcBalance - expectedUsdc)
+    );
+    assert.equal(treasuryBalance.value.amount, String(expectedUsdc));
+  });
+
+  it("Guarantor claims issue a payment receipt", async () => {
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(consumerAccount.receiptsIssued.toNumber(), 1);
+
+    const receipt = await program.account.paymentReceipt.fetch(receiptPDA(0));
+    assert.equal(receipt.receiptNumber.toNumber(), 0);
+    assert.equal(receipt.amount.toNumber(), 50000);
+    assert.equal(
+      receipt.outstanding.toNumber(),
+      consumerAccount.outstandingWaterDebt.toNumber()
+    );
+    assert.isTrue(receipt.consumer.equals(consumer.publicKey));
+  });
+
+  it("Claims are rejected for a key that is not the guarantor", async () => {
+    const impostor = Keypair.generate();
+    try {
+      await program.methods
+        .claimFromGuarantor(tariffKey, new anchor.BN(1))
+        .accounts({
+          consumer: consumer.publicKey,
+          guarantor: impostor.publicKey,
+          agency: wallet.publicKey,
+          wtkMint: wtkMint,
+          settlementMint: usdcMint,
+        })
+        .rpc();
+      assert.fail("Expected the claim to fail");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+
+  it("Claims wait for the tariff's grace period to elapse", async () => {
+    await program.methods
+      .updateTariffGracePeriod(tariffKey, new anchor.BN(1_000_000))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(10000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    try {
+      await program.methods
+        .claimFromGuarantor(tariffKey, new anchor.BN(1))
+        .accounts({
+          consumer: consumer.publicKey,
+          guarantor: guarantor.publicKey,
+          agency: wallet.publicKey,
+          wtkMint: wtkMint,
+          settlementMint: usdcMint,
+        })
+        .rpc();
+      assert.fail("Expected the claim to fail");
+    } catch (err) {
+      assert.include(err.toString(), "GracePeriodActive");
+    }
+  });
+});
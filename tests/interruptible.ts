@@ -0,0 +1,246 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("interruptible supply", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let aqcMint: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let interruptiblePolicyPDA: PublicKey;
+  let consumer: Keypair;
+
+  const discountBps = 2000; // 20% off every charge
+  const compensationPerPeriod = 5000; // 5.000 AQC
+  const periodSlots = 1000000; // long enough to outlast the suite
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const setInterruptible = (interruptible: boolean) =>
+    program.methods
+      .setInterruptible(interruptible)
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+  const useWater = (amount: number, interruptiblePolicy = null) =>
+    program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(amount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+        interruptiblePolicy: interruptiblePolicy,
+      })
+      .signers([consumer])
+      .rpc();
+
+  const curtail = (periods: number) =>
+    program.methods
+      .curtailInterruptibleSupply(new anchor.BN(periods))
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+    consumer = Keypair.generate();
+
+    [interruptiblePolicyPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("interruptible_policy"), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    aqcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(500),
+        new anchor.BN(200),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(950000),
+        new anchor.BN(1000000)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(100000),
+        new anchor.BN(800)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await program.methods
+      .configureInterruptibleSupply(
+        discountBps,
+        new anchor.BN(compensationPerPeriod),
+        new anchor.BN(periodSlots)
+      )
+      .accounts({
+        aqcMint: aqcMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("should bill interruptible consumers at a discount", async () => {
+    await setInterruptible(true);
+
+    try {
+      await useWater(10000);
+      assert.fail("Expected the reading without the policy to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidInterruptiblePolicy");
+    }
+
+    await useWater(10000, interruptiblePolicyPDA);
+    const { lastBill } = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    // 20% of the charge is deducted
+    assert.equal(lastBill.discount.toNumber() * 4, lastBill.total.toNumber());
+  });
+
+  it("should refuse readings while supply is curtailed", async () => {
+    await curtail(2);
+
+    try {
+      await useWater(10000, interruptiblePolicyPDA);
+      assert.fail("Expected the reading to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "SupplyCurtailed");
+    }
+
+    try {
+      await curtail(1);
+      assert.fail("Expected the second curtailment to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "SupplyCurtailed");
+    }
+  });
+
+  it("should compensate each curtailed period once", async () => {
+    try {
+      await setInterruptible(false);
+      assert.fail("Expected the opt-out to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "CurtailmentUncompensated");
+    }
+
+    const compensate = () =>
+      program.methods
+        .compensateCurtailment()
+        .accounts({
+          consumer: consumer.publicKey,
+          aqcMint: aqcMint,
+          agency: wallet.publicKey,
+        })
+        .rpc();
+    await compensate();
+    await compensate();
+
+    const consumerAqc = getAssociatedTokenAddressSync(
+      aqcMint,
+      consumer.publicKey
+    );
+    assert.equal(
+      Number((await getAccount(connection, consumerAqc)).amount),
+      2 * compensationPerPeriod
+    );
+
+    await setInterruptible(false);
+  });
+});
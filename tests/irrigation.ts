@@ -0,0 +1,215 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("irrigation", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  const weeklyVolume = new anchor.BN(50000); // 50.000
+  const surchargeBps = 5000; // 50% on top of the water rate
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const irrigationSchedulePDA = () =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("irrigation_schedule"),
+        wallet.publicKey.toBuffer(),
+        consumer.publicKey.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const setSchedule = (seasons: any[]) =>
+    program.methods
+      .setIrrigationSchedule(surchargeBps, seasons)
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+  const useWater = (amount: number) =>
+    program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(amount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+        irrigationSchedule: irrigationSchedulePDA(),
+      })
+      .signers([consumer])
+      .rpc();
+
+  const season = (start: number, end: number) => ({
+    startTimestamp: new anchor.BN(start),
+    endTimestamp: new anchor.BN(end),
+    weeklyVolume: weeklyVolume,
+  });
+
+  const chainTime = async () =>
+    await connection.getBlockTime(await connection.getSlot());
+
+  const wtkBalance = async () =>
+    Number((await getAccount(connection, consumerWtkAccount)).amount);
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+  });
+
+  it("should refuse overlapping crop seasons", async () => {
+    const now = await chainTime();
+    try {
+      await setSchedule([
+        season(now - 3600, now + 86400),
+        season(now, now + 2 * 86400),
+      ]);
+      assert.fail("Expected the schedule to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidIrrigationSchedule");
+    }
+  });
+
+  it("should surcharge draws beyond the weekly allotment", async () => {
+    const now = await chainTime();
+    await setSchedule([season(now - 3600, now + 30 * 86400)]);
+
+    // 40.000 units in turn at 0.500
+    await useWater(40000);
+    assert.equal(await wtkBalance(), 20000);
+
+    // 10.000 of 20.000 units out of turn, surcharged 2.500
+    await useWater(20000);
+    assert.equal(await wtkBalance(), 32500);
+
+    const schedule = await program.account.irrigationSchedule.fetch(
+      irrigationSchedulePDA()
+    );
+    assert.equal(schedule.weekUsage.toNumber(), weeklyVolume.toNumber());
+  });
+
+  it("should surcharge every draw outside the crop seasons", async () => {
+    const now = await chainTime();
+    await setSchedule([season(now + 86400, now + 30 * 86400)]);
+
+    // 10.000 units out of turn: 5.000 plus a 2.500 surcharge
+    await useWater(10000);
+    assert.equal(await wtkBalance(), 40000);
+  });
+});
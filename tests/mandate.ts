@@ -0,0 +1,200 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import {
+  grantRegulatorRole,
+  regulatorAuthorityPDA,
+} from "./fixtures/regulator";
+
+describe("mandated capacity", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let tariffKey: PublicKey;
+  let fullReservoirKey: PublicKey;
+  let lowReservoirKey: PublicKey;
+  let consumer: Keypair;
+  let droughtConsumer: Keypair;
+
+  const regulator = Keypair.generate();
+
+  const contractedCapacity = 100000; // 100.000
+  const reservoirCapacity = 1000000; // 1000.000
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const rolePDA = (member: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("role"), wallet.publicKey.toBuffer(), member.toBuffer()],
+      program.programId
+    )[0];
+
+  const mandate = (
+    target: Keypair,
+    capacity: number,
+    approver: Keypair | null = null
+  ) => {
+    const builder = program.methods
+      .mandateConsumerCapacity(new anchor.BN(capacity))
+      .accounts({
+        consumer: target.publicKey,
+        agency: wallet.publicKey,
+        regulatorAuthority: regulatorAuthorityPDA(program),
+        regulator: approver ? approver.publicKey : null,
+        regulatorRole: approver ? rolePDA(approver.publicKey) : null,
+      });
+    return approver ? builder.signers([approver]).rpc() : builder.rpc();
+  };
+
+  const registerConsumer = async (target: Keypair, reservoirKey: PublicKey) => {
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      target.publicKey
+    );
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(800)
+      )
+      .accounts({
+        consumer: target.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([target])
+      .rpc();
+  };
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    fullReservoirKey = Keypair.generate().publicKey;
+    lowReservoirKey = Keypair.generate().publicKey;
+    consumer = Keypair.generate();
+    droughtConsumer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(500),
+        new anchor.BN(200),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    // One reservoir is full, the other below the low level of 20% of capacity
+    for (const [reservoirKey, level] of [
+      [fullReservoirKey, 950000],
+      [lowReservoirKey, 100000],
+    ] as const) {
+      await program.methods
+        .initializeReservoir(
+          reservoirKey,
+          new anchor.BN(level),
+          new anchor.BN(reservoirCapacity)
+        )
+        .accounts({
+          agency: wallet.publicKey,
+        })
+        .rpc();
+    }
+
+    await registerConsumer(consumer, fullReservoirKey);
+    await registerConsumer(droughtConsumer, lowReservoirKey);
+
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(regulator.publicKey, LAMPORTS_PER_SOL),
+      "confirmed"
+    );
+
+    await grantRegulatorRole(program, regulator.publicKey);
+  });
+
+  it("should need a regulator outside an emergency", async () => {
+    try {
+      await mandate(consumer, 80000);
+      assert.fail("Expected the reduction to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "MissingRegulatorApproval");
+    }
+
+    await mandate(consumer, 80000, regulator);
+
+    const account = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(account.contractedCapacity.toNumber(), 80000);
+  });
+
+  it("should let the agency reduce capacity while the reservoir is low", async () => {
+    await mandate(droughtConsumer, 70000);
+
+    const account = await program.account.consumer.fetch(
+      droughtConsumer.publicKey
+    );
+    assert.equal(account.contractedCapacity.toNumber(), 70000);
+  });
+
+  it("should only mandate reductions", async () => {
+    try {
+      await mandate(droughtConsumer, 90000);
+      assert.fail("Expected the increase to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidCapacity");
+    }
+  });
+});
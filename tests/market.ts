@@ -0,0 +1,306 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("market", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let usdcMint: PublicKey;
+  let watcMarketPDA: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let seller: Keypair;
+  let buyer: Keypair;
+  let askOrderPDA: PublicKey;
+  let bidOrderPDA: PublicKey;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  const USDC_DECIMALS = 6;
+  const initialUsdcBalance = 1_000_000; // 1.000000 USDC
+  const feeBps = 100; // 1% of proceeds
+  const tradeCap = 30000; // 30.000 units per period
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const usdcBalance = async (owner: PublicKey) => {
+    const address = getAssociatedTokenAddressSync(usdcMint, owner);
+    return Number((await getAccount(connection, address)).amount);
+  };
+
+  const nextOrderPDA = async () => {
+    const market = await program.account.watcMarket.fetch(watcMarketPDA);
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("watc_order"),
+        wallet.publicKey.toBuffer(),
+        market.orderCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+  };
+
+  const placeBid = async (amount: number, price: number) => {
+    const order = await nextOrderPDA();
+    await program.methods
+      .placeBid(new anchor.BN(amount), new anchor.BN(price))
+      .accounts({
+        consumer: buyer.publicKey,
+        watcOrder: order,
+        agency: wallet.publicKey,
+        settlementMint: usdcMint,
+      })
+      .signers([buyer])
+      .rpc();
+    return order;
+  };
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    seller = Keypair.generate();
+    buyer = Keypair.generate();
+
+    [watcMarketPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("watc_market"), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    usdcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      USDC_DECIMALS
+    );
+
+    // Create token accounts
+    for (const owner of [seller.publicKey, buyer.publicKey, wallet.publicKey]) {
+      const usdcAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        wallet.payer,
+        usdcMint,
+        owner
+      ).then((account) => account.address);
+      await mintTo(
+        connection,
+        wallet.payer,
+        usdcMint,
+        usdcAccount,
+        wallet.payer,
+        initialUsdcBalance
+      );
+    }
+
+    for (const consumer of [seller, buyer]) {
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        wallet.payer,
+        watcMint,
+        consumer.publicKey
+      );
+    }
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    for (const consumer of [seller, buyer]) {
+      await program.methods
+        .registerConsumer(
+          tariffKey,
+          reservoirKey,
+          new anchor.BN(contractedCapacity),
+          new anchor.BN(blockRate)
+        )
+        .accounts({
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          watcMint: watcMint,
+        })
+        .signers([consumer])
+        .rpc();
+    }
+
+    await program.methods
+      .configureWatcMarket(feeBps, new anchor.BN(tradeCap))
+      .accounts({
+        settlementMint: usdcMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("Consumers place an ask and a crossing bid", async () => {
+    askOrderPDA = await nextOrderPDA();
+    await program.methods
+      .placeAsk(new anchor.BN(20000), new anchor.BN(2))
+      .accounts({
+        consumer: seller.publicKey,
+        watcOrder: askOrderPDA,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([seller])
+      .rpc();
+
+    bidOrderPDA = await placeBid(25000, 3);
+
+    const ask = await program.account.watcOrder.fetch(askOrderPDA);
+    assert.deepEqual(ask.side, { ask: {} });
+    assert.equal(ask.remaining.toNumber(), 20000);
+
+    // The bid escrows 25.000 units at 0.003000 USDC each
+    assert.equal(
+      await usdcBalance(buyer.publicKey),
+      initialUsdcBalance - 75000
+    );
+  });
+
+  it("Matched orders trade at the ask price less the fee", async () => {
+    await program.methods
+      .matchOrders()
+      .accounts({
+        askOrder: askOrderPDA,
+        bidOrder: bidOrderPDA,
+        seller: seller.publicKey,
+        buyer: buyer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+        settlementMint: usdcMint,
+      })
+      .rpc();
+
+    // 20.000 units at 0.002000 USDC is 0.040000 USDC, with a 1% fee
+    assert.equal(
+      await usdcBalance(seller.publicKey),
+      initialUsdcBalance + 39600
+    );
+    assert.equal(await usdcBalance(wallet.publicKey), initialUsdcBalance + 400);
+    // The buyer is refunded the 0.001000 USDC per unit it bid above the ask
+    assert.equal(
+      await usdcBalance(buyer.publicKey),
+      initialUsdcBalance - 75000 + 20000
+    );
+
+    const buyerWatc = await getAccount(
+      connection,
+      getAssociatedTokenAddressSync(watcMint, buyer.publicKey)
+    );
+    assert.equal(Number(buyerWatc.amount), contractedCapacity + 20000);
+
+    const buyerAccount = await program.account.consumer.fetch(buyer.publicKey);
+    assert.equal(buyerAccount.periodWatcBought.toNumber(), 20000);
+
+    const bid = await program.account.watcOrder.fetch(bidOrderPDA);
+    assert.equal(bid.remaining.toNumber(), 5000);
+  });
+
+  it("Buyer cannot bid past the per-period trade cap", async () => {
+    try {
+      await placeBid(20000, 3);
+      assert.fail("Bid exceeding the trade cap was placed");
+    } catch (err) {
+      assert.include(err.toString(), "TradeCapExceeded");
+    }
+  });
+
+  it("Cancelled bid refunds its remaining escrow", async () => {
+    await program.methods
+      .cancelOrder()
+      .accounts({
+        consumer: buyer.publicKey,
+        watcOrder: bidOrderPDA,
+        agency: wallet.publicKey,
+        mint: usdcMint,
+      })
+      .signers([buyer])
+      .rpc();
+
+    assert.equal(
+      await usdcBalance(buyer.publicKey),
+      initialUsdcBalance - 40000
+    );
+    assert.isNull(await connection.getAccountInfo(bidOrderPDA));
+  });
+});
@@ -0,0 +1,194 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("migration", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let tariffKey: PublicKey;
+  let newTariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  const consumers = [Keypair.generate(), Keypair.generate()];
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const initializeTariff = (key: PublicKey) =>
+    program.methods
+      .initializeTariff(
+        key,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+  const asRemainingAccounts = (keypairs: Keypair[]) =>
+    keypairs.map((keypair) => ({
+      pubkey: keypair.publicKey,
+      isWritable: true,
+      isSigner: false,
+    }));
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    newTariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    [wtkMint, watcMint, wstMint] = await Promise.all(
+      [0, 1, 2].map(() =>
+        createMint(connection, wallet.payer, wallet.publicKey, null, DECIMALS)
+      )
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await initializeTariff(tariffKey);
+    await initializeTariff(newTariffKey);
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    for (const consumer of consumers) {
+      await getOrCreateAssociatedTokenAccount(
+        connection,
+        wallet.payer,
+        watcMint,
+        consumer.publicKey
+      );
+      await program.methods
+        .registerConsumer(
+          tariffKey,
+          reservoirKey,
+          new anchor.BN(contractedCapacity),
+          new anchor.BN(blockRate)
+        )
+        .accounts({
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          watcMint: watcMint,
+        })
+        .signers([consumer])
+        .rpc();
+    }
+  });
+
+  it("Rejects an empty batch", async () => {
+    try {
+      await program.methods
+        .migrateTariffConsumers(tariffKey, newTariffKey)
+        .accounts({
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the migration to fail");
+    } catch (err) {
+      assert.include(err.toString(), "EmptyBatch");
+    }
+  });
+
+  it("Reassigns a batch of consumers in one call", async () => {
+    await program.methods
+      .migrateTariffConsumers(tariffKey, newTariffKey)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .remainingAccounts(asRemainingAccounts(consumers))
+      .rpc();
+
+    for (const consumer of consumers) {
+      const consumerAccount = await program.account.consumer.fetch(
+        consumer.publicKey
+      );
+      assert.equal(
+        consumerAccount.assignedTariff.toBase58(),
+        newTariffKey.toBase58()
+      );
+    }
+
+    // The consumer counts follow the batch to the new tariff
+    const [tariffPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("tariff"),
+        wallet.publicKey.toBuffer(),
+        tariffKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [newTariffPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("tariff"),
+        wallet.publicKey.toBuffer(),
+        newTariffKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const tariff = await program.account.tariff.fetch(tariffPDA);
+    const newTariff = await program.account.tariff.fetch(newTariffPDA);
+    assert.equal(tariff.assignedConsumerCount.toNumber(), 0);
+    assert.equal(newTariff.assignedConsumerCount.toNumber(), consumers.length);
+  });
+
+  it("Rejects consumers not assigned to the current tariff", async () => {
+    try {
+      await program.methods
+        .migrateTariffConsumers(tariffKey, newTariffKey)
+        .accounts({
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        })
+        .remainingAccounts(asRemainingAccounts(consumers))
+        .rpc();
+      assert.fail("Expected the migration to fail");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+});
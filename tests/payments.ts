@@ -38,6 +38,13 @@ describe("payments", () => {
   const initialContractedCapacity = 100000; // 100.000
   const initialBlockRate = 800; // 0.800
 
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
   before(async () => {
     // Initialize accounts
     tariffKey = Keypair.generate().publicKey;
@@ -51,21 +58,21 @@ describe("payments", () => {
       wallet.payer,
       wallet.publicKey,
       null,
-      9
+      DECIMALS
     );
     watcMint = await createMint(
       connection,
       wallet.payer,
       wallet.publicKey,
       null,
-      9
+      DECIMALS
     );
     wstMint = await createMint(
       connection,
       wallet.payer,
       wallet.publicKey,
       null,
-      9
+      DECIMALS
     );
 
     // Create token accounts
@@ -90,6 +97,14 @@ describe("payments", () => {
       consumer.publicKey
     ).then((account) => account.address);
 
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
     // Initialize a tariff
     await program.methods
       .initializeTariff(
@@ -164,6 +179,15 @@ describe("payments", () => {
 
   it("Consumer can pay for water usage", async () => {
     const waterAmount = 100000; // 100.000
+    const payment = (waterAmount * initialWaterRate) / SCALE;
+
+    const [agencyStatsPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("agency_stats"), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+    const statsBefore = await program.account.agencyStats.fetch(
+      agencyStatsPDA
+    );
 
     await program.methods
       .useWater(tariffKey, reservoirKey, new anchor.BN(waterAmount))
@@ -177,22 +201,385 @@ describe("payments", () => {
       .rpc();
 
     await program.methods
-      .payForWater(
-        tariffKey,
-        reservoirKey,
-        new anchor.BN((waterAmount * initialWaterRate) / SCALE)
+      .payForWater(tariffKey, reservoirKey, new anchor.BN(payment))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const consumerWtkBalance = await connection.getTokenAccountBalance(
+      consumerWtkAccount
+    );
+    assert.equal(consumerWtkBalance.value.amount, "0");
+
+    // The agency's totals include the reading and the payment
+    const statsAfter = await program.account.agencyStats.fetch(agencyStatsPDA);
+    assert.equal(
+      statsAfter.totalUsage.sub(statsBefore.totalUsage).toNumber(),
+      waterAmount
+    );
+    assert.equal(
+      statsAfter.revenueCollected.sub(statsBefore.revenueCollected).toNumber(),
+      payment
+    );
+  });
+
+  it("Consumer can pay water debt in installments", async () => {
+    const waterAmount = 40000; // 40.000
+
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(waterAmount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    // The debt matches the WTK billed for the usage
+    let consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    const debt = consumerAccount.outstandingWaterDebt.toNumber();
+    const consumerWtkBalance = await connection.getTokenAccountBalance(
+      consumerWtkAccount
+    );
+    assert.isAbove(debt, 0);
+    assert.equal(consumerWtkBalance.value.amount, String(debt));
+
+    const installment = Math.floor(debt / 4);
+
+    await program.methods
+      .payForWater(tariffKey, reservoirKey, new anchor.BN(installment))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    consumerAccount = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(
+      consumerAccount.outstandingWaterDebt.toNumber(),
+      debt - installment
+    );
+
+    // Paying more than what is still owed is rejected
+    try {
+      await program.methods
+        .payForWater(tariffKey, reservoirKey, new anchor.BN(debt))
+        .accounts({
+          consumer: consumer.publicKey,
+          wtkMint: wtkMint,
+          agency: wallet.publicKey,
+        })
+        .signers([consumer])
+        .rpc();
+      assert.fail("Expected the overpayment to fail");
+    } catch (err) {
+      assert.include(err.toString(), "OverPayment");
+    }
+
+    await program.methods
+      .payForWater(tariffKey, reservoirKey, new anchor.BN(debt - installment))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    consumerAccount = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(consumerAccount.outstandingWaterDebt.toNumber(), 0);
+
+    // Each payment issued a receipt, the last one settling the invoice
+    assert.equal(consumerAccount.receiptsIssued.toNumber(), 3);
+    const [receiptPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("receipt"),
+        consumer.publicKey.toBuffer(),
+        new anchor.BN(2).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const receipt = await program.account.paymentReceipt.fetch(receiptPDA);
+    assert.equal(receipt.receiptNumber.toNumber(), 2);
+    assert.equal(receipt.amount.toNumber(), debt - installment);
+    assert.equal(receipt.outstanding.toNumber(), 0);
+    assert.isTrue(receipt.payer.equals(consumer.publicKey));
+  });
+
+  it("Zero water payments are rejected without a receipt", async () => {
+    try {
+      await program.methods
+        .payForWater(tariffKey, reservoirKey, new anchor.BN(0))
+        .accounts({
+          consumer: consumer.publicKey,
+          wtkMint: wtkMint,
+          agency: wallet.publicKey,
+        })
+        .signers([consumer])
+        .rpc();
+      assert.fail("Expected the zero payment to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidAmount");
+    }
+
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(consumerAccount.receiptsIssued.toNumber(), 3);
+  });
+
+  it("Agency can credit an overcharged consumer", async () => {
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(10000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const debt = (
+      await program.account.consumer.fetch(consumer.publicKey)
+    ).outstandingWaterDebt.toNumber();
+    const credit = Math.floor(debt / 2);
+
+    const noteKey = Keypair.generate().publicKey;
+    const [creditNotePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("credit_note"),
+        wallet.publicKey.toBuffer(),
+        noteKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .issueCredit(
+        noteKey,
+        { water: {} },
+        new anchor.BN(credit),
+        { meterMisread: {} }
       )
       .accounts({
         consumer: consumer.publicKey,
+        agency: wallet.publicKey,
         wtkMint: wtkMint,
+        wstMint: wstMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const creditNote = await program.account.creditNote.fetch(creditNotePDA);
+    assert.ok(creditNote.consumer.equals(consumer.publicKey));
+    assert.deepEqual(creditNote.kind, { water: {} });
+    assert.deepEqual(creditNote.reason, { meterMisread: {} });
+    assert.equal(creditNote.amount.toNumber(), credit);
+
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    const consumerWtkBalance = await connection.getTokenAccountBalance(
+      consumerWtkAccount
+    );
+    assert.equal(
+      consumerAccount.outstandingWaterDebt.toNumber(),
+      debt - credit
+    );
+    assert.equal(consumerWtkBalance.value.amount, String(debt - credit));
+  });
+
+  it("Missing readings are billed on estimate and trued up", async () => {
+    let consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    const debtBefore = consumerAccount.outstandingWaterDebt.toNumber();
+    const averageUsage = consumerAccount.averageUsage.toNumber();
+
+    await program.methods
+      .billEstimatedUsage(tariffKey, reservoirKey)
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    consumerAccount = await program.account.consumer.fetch(consumer.publicKey);
+    const estimatedCharge = consumerAccount.estimatedCharge.toNumber();
+    assert.equal(consumerAccount.estimatedUsage.toNumber(), averageUsage);
+    assert.isAbove(estimatedCharge, 0);
+    assert.equal(
+      consumerAccount.outstandingWaterDebt.toNumber(),
+      debtBefore + estimatedCharge
+    );
+
+    // The actual reading comes in lower than the estimate
+    const actualVolume = 50000; // 50.000, all beyond contracted capacity
+    await program.methods
+      .trueUp(tariffKey, reservoirKey, new anchor.BN(actualVolume))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    consumerAccount = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(consumerAccount.estimatedUsage.toNumber(), 0);
+    assert.equal(consumerAccount.estimatedCharge.toNumber(), 0);
+    assert.equal(
+      consumerAccount.outstandingWaterDebt.toNumber(),
+      debtBefore + (actualVolume * initialBlockRate) / SCALE
+    );
+  });
+
+  it("Budget billing collects levelized invoices and reconciles", async () => {
+    const budgetAmount = 5000; // 5.000 WTK per invoice
+
+    await program.methods
+      .setBudgetBilling(true, new anchor.BN(budgetAmount))
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(20000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
         agency: wallet.publicKey,
       })
       .signers([consumer])
       .rpc();
 
+    let consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    const debt = consumerAccount.outstandingWaterDebt.toNumber();
+    assert.isAbove(debt, budgetAmount);
+
+    // Each invoice collects the levelized amount, not the true cost
+    await program.methods
+      .payInvoice()
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    consumerAccount = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(
+      consumerAccount.outstandingWaterDebt.toNumber(),
+      debt - budgetAmount
+    );
+    assert.equal(consumerAccount.budgetPaid.toNumber(), budgetAmount);
+
+    // Year end settles the difference and levels next year's invoices
+    await program.methods
+      .reconcileBudgetBilling()
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    consumerAccount = await program.account.consumer.fetch(consumer.publicKey);
     const consumerWtkBalance = await connection.getTokenAccountBalance(
       consumerWtkAccount
     );
+    assert.equal(consumerAccount.outstandingWaterDebt.toNumber(), 0);
+    assert.equal(consumerAccount.budgetPaid.toNumber(), 0);
+    assert.equal(
+      consumerAccount.budgetAmount.toNumber(),
+      Math.floor(debt / 12)
+    );
     assert.equal(consumerWtkBalance.value.amount, "0");
   });
+
+  it("Budget invoices and reconciliation issue payment receipts", async () => {
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(20000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    let consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    const debt = consumerAccount.outstandingWaterDebt.toNumber();
+    const budgetAmount = consumerAccount.budgetAmount.toNumber();
+    const receiptsIssued = consumerAccount.receiptsIssued.toNumber();
+    assert.isAbove(debt, budgetAmount);
+
+    await program.methods
+      .payInvoice()
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+      })
+      .signers([consumer])
+      .rpc();
+    await program.methods
+      .reconcileBudgetBilling()
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    consumerAccount = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(consumerAccount.receiptsIssued.toNumber(), receiptsIssued + 2);
+
+    const receiptPDA = (index: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("receipt"),
+          consumer.publicKey.toBuffer(),
+          new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+    const invoice = await program.account.paymentReceipt.fetch(
+      receiptPDA(receiptsIssued)
+    );
+    assert.equal(invoice.amount.toNumber(), budgetAmount);
+    assert.equal(invoice.outstanding.toNumber(), debt - budgetAmount);
+
+    const reconciliation = await program.account.paymentReceipt.fetch(
+      receiptPDA(receiptsIssued + 1)
+    );
+    assert.equal(reconciliation.amount.toNumber(), debt - budgetAmount);
+    assert.equal(reconciliation.outstanding.toNumber(), 0);
+  });
 });
@@ -0,0 +1,268 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("prepaid", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let usdcMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let agencyUsdcAccount: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+  let customer: Keypair;
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const currencyCode = [...Buffer.from("GYD")];
+  const USDC_DECIMALS = 6;
+  const fxRate = 4800; // 1 GYD = 0.004800 USDC
+  const initialUsdcBalance = 20_000_000; // 20.000000 USDC
+
+  const topUp = (amount: number) =>
+    program.methods
+      .topUpPrepaid(new anchor.BN(amount))
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        payer: customer.publicKey,
+        settlementMint: usdcMint,
+      })
+      .signers([customer])
+      .rpc();
+
+  const useWater = (amount: number) =>
+    program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(amount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+  const usdcBalance = async (account: PublicKey) =>
+    Number((await connection.getTokenAccountBalance(account)).value.amount);
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+    customer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    usdcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      USDC_DECIMALS
+    );
+
+    // Create token accounts
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      consumer.publicKey
+    );
+
+    agencyUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      wallet.publicKey
+    ).then((account) => account.address);
+
+    // The customer paying at the kiosk need not be the consumer
+    const customerUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      customer.publicKey
+    ).then((account) => account.address);
+
+    await mintTo(
+      connection,
+      wallet.payer,
+      usdcMint,
+      customerUsdcAccount,
+      wallet.payer,
+      initialUsdcBalance
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a tariff denominated in GYD
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(500),
+        new anchor.BN(200),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateTariffCurrency(tariffKey, currencyCode)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(950000),
+        new anchor.BN(1000000)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(100000),
+        new anchor.BN(800)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    // Register the FX oracle, with the agency wallet posting rates
+    await program.methods
+      .initializeFxOracle(currencyCode, wallet.publicKey, new anchor.BN(1000))
+      .accounts({
+        settlementMint: usdcMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    const [fxOraclePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("fx_oracle"),
+        wallet.publicKey.toBuffer(),
+        Buffer.from(currencyCode),
+      ],
+      program.programId
+    );
+    await program.methods
+      .updateFxRate(new anchor.BN(fxRate))
+      .accounts({
+        fxOracle: fxOraclePDA,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("should reject empty top-ups", async () => {
+    try {
+      await topUp(0);
+      assert.fail("Expected the top-up to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidAmount");
+    }
+  });
+
+  it("should credit the prepaid balance and pay the agency", async () => {
+    const treasuryBefore = await usdcBalance(agencyUsdcAccount);
+
+    await topUp(15000); // 15.000 GYD
+    await topUp(5000); // 5.000 GYD
+
+    // 20.000 GYD at 0.004800 USDC each
+    const treasuryAfter = await usdcBalance(agencyUsdcAccount);
+    assert.equal(treasuryAfter - treasuryBefore, 96_000);
+
+    const account = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(account.prepaidBalance.toNumber(), 20000);
+  });
+
+  it("should draw usage from the prepaid balance before billing WTK", async () => {
+    // 20.000 units at 0.500 is 10.000 WTK, fully prepaid
+    await useWater(20000);
+
+    let consumerWtk = await getAccount(connection, consumerWtkAccount);
+    assert.equal(Number(consumerWtk.amount), 0);
+
+    // 40.000 units is 20.000 WTK, of which 10.000 is still prepaid
+    await useWater(40000);
+
+    consumerWtk = await getAccount(connection, consumerWtkAccount);
+    assert.equal(Number(consumerWtk.amount), 10000);
+
+    const account = await program.account.consumer.fetch(consumer.publicKey);
+    assert.equal(account.prepaidBalance.toNumber(), 0);
+  });
+});
@@ -0,0 +1,211 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("realloc", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let tariffPDA: PublicKey;
+  let reservoirPDA: PublicKey;
+  let tokensPDA: PublicKey;
+  const consumer = Keypair.generate();
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const accountSize = async (address: PublicKey) =>
+    (await connection.getAccountInfo(address)).data.length;
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    [tariffPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("tariff"),
+        wallet.publicKey.toBuffer(),
+        tariffKey.toBuffer(),
+      ],
+      program.programId
+    );
+    [reservoirPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservoir"),
+        wallet.publicKey.toBuffer(),
+        reservoirKey.toBuffer(),
+      ],
+      program.programId
+    );
+    [tokensPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("tokens"), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+
+    [wtkMint, watcMint, wstMint] = await Promise.all(
+      [0, 1, 2].map(() =>
+        createMint(connection, wallet.payer, wallet.publicKey, null, DECIMALS)
+      )
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      consumer.publicKey
+    );
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+  });
+
+  it("Leaves accounts of the current layout unchanged", async () => {
+    const accounts = [consumer.publicKey, tariffPDA, reservoirPDA, tokensPDA];
+    const sizes = await Promise.all(accounts.map(accountSize));
+
+    await program.methods
+      .growConsumer()
+      .accounts({
+        consumer: consumer.publicKey,
+        payer: wallet.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .growTariff(tariffKey)
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .growReservoir(reservoirKey)
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+    // Units already set are kept
+    await program.methods
+      .growTokens({
+        volumeScale: new anchor.BN(1),
+        currencyDecimals: 6,
+      })
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    assert.deepEqual(await Promise.all(accounts.map(accountSize)), sizes);
+    const tokens = await program.account.tokens.fetch(tokensPDA);
+    assert.equal(tokens.units.volumeScale.toNumber(), 1000);
+    assert.equal(tokens.units.currencyDecimals, DECIMALS);
+
+    // The grown consumer still deserializes
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(
+      consumerAccount.contractedCapacity.toNumber(),
+      contractedCapacity
+    );
+  });
+
+  it("Rejects growing an account of another type", async () => {
+    try {
+      await program.methods
+        .growConsumer()
+        .accounts({
+          consumer: tariffPDA,
+          payer: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the growth to fail");
+    } catch (err) {
+      assert.include(err.toString(), "AccountDiscriminatorMismatch");
+    }
+  });
+
+  it("Rejects growing an account the program does not own", async () => {
+    try {
+      await program.methods
+        .growConsumer()
+        .accounts({
+          consumer: wtkMint,
+          payer: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the growth to fail");
+    } catch (err) {
+      assert.include(err.toString(), "AccountOwnedByWrongProgram");
+    }
+  });
+});
@@ -0,0 +1,236 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("redemption", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let aqcMint: PublicKey;
+  let consumerAqcAccount: PublicKey;
+  let redemptionOfferPDA: PublicKey;
+  let offerKey: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  const initialAqcBalance = 12000; // 12.000 AQC
+  const price = 5000; // 5.000 AQC per redemption
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const voucherPDA = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("redemption_voucher"),
+        redemptionOfferPDA.toBuffer(),
+        new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+  const redeemOffer = (index: number) =>
+    program.methods
+      .redeemOffer()
+      .accounts({
+        consumer: consumer.publicKey,
+        redemptionOffer: redemptionOfferPDA,
+        redemptionVoucher: voucherPDA(index),
+        aqcMint: aqcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    offerKey = Keypair.generate().publicKey;
+    consumer = Keypair.generate();
+
+    [redemptionOfferPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("redemption_offer"),
+        wallet.publicKey.toBuffer(),
+        offerKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    aqcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    // Create token accounts
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      consumer.publicKey
+    );
+
+    consumerAqcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      aqcMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    await mintTo(
+      connection,
+      wallet.payer,
+      aqcMint,
+      consumerAqcAccount,
+      wallet.payer,
+      initialAqcBalance
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+  });
+
+  it("Agency lists a perk priced in AquaCoin", async () => {
+    await program.methods
+      .createRedemptionOffer(
+        offerKey,
+        "Leak inspection",
+        new anchor.BN(price),
+        new anchor.BN(2)
+      )
+      .accounts({
+        aqcMint: aqcMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    const offer = await program.account.redemptionOffer.fetch(
+      redemptionOfferPDA
+    );
+    assert.equal(offer.name, "Leak inspection");
+    assert.equal(offer.redeemed.toNumber(), 0);
+  });
+
+  it("Consumer burns AquaCoin for a claim voucher", async () => {
+    await redeemOffer(0);
+
+    const voucher = await program.account.redemptionVoucher.fetch(
+      voucherPDA(0)
+    );
+    assert.equal(voucher.consumer.toBase58(), consumer.publicKey.toBase58());
+    assert.equal(voucher.price.toNumber(), price);
+
+    const consumerAqc = await getAccount(connection, consumerAqcAccount);
+    assert.equal(Number(consumerAqc.amount), initialAqcBalance - price);
+  });
+
+  it("Offer cannot be redeemed past its quantity", async () => {
+    await redeemOffer(1);
+
+    try {
+      await redeemOffer(2);
+      assert.fail("Sold out offer was redeemed");
+    } catch (err) {
+      assert.include(err.toString(), "OfferSoldOut");
+    }
+  });
+});
@@ -1,7 +1,12 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Aquachain } from "../target/types/aquachain";
-import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  PublicKey,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
 import { assert } from "chai";
 
 describe("reservoir", () => {
@@ -59,13 +64,17 @@ describe("reservoir", () => {
     const newReservoirCapacity = 950; // 0.95
 
     await program.methods
-      .updateReservoir(
-        reservoirKey,
-        new anchor.BN(newReservoirLevel),
-        new anchor.BN(newReservoirCapacity)
-      )
+      .updateReservoirLevel(reservoirKey, new anchor.BN(newReservoirLevel))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .updateReservoirConfig(reservoirKey, new anchor.BN(newReservoirCapacity))
       .accounts({
         agency: wallet.publicKey,
+        authority: wallet.publicKey,
       })
       .rpc();
 
@@ -77,6 +86,222 @@ describe("reservoir", () => {
     assert.equal(updatedReservoir.capacity.toNumber(), newReservoirCapacity);
   });
 
+  it("should not shrink the capacity below the current level", async () => {
+    try {
+      await program.methods
+        .updateReservoirConfig(reservoirKey, new anchor.BN(600))
+        .accounts({
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the capacity to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidReservoirCapacity");
+    }
+  });
+
+  it("should only let the agency change the configuration", async () => {
+    const operator = Keypair.generate();
+    try {
+      await program.methods
+        .updateReservoirConfig(reservoirKey, new anchor.BN(2000))
+        .accounts({
+          agency: wallet.publicKey,
+          authority: operator.publicKey,
+          role: null,
+        })
+        .signers([operator])
+        .rpc();
+      assert.fail("Expected the configuration change to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+
+  it("should accept plausible levels from the telemetry key", async () => {
+    const telemetry = Keypair.generate();
+    await program.methods
+      .updateReservoirTelemetry(
+        reservoirKey,
+        telemetry.publicKey,
+        new anchor.BN(1) // 0.001 per slot
+      )
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    const updateLevel = (level: number) =>
+      program.methods
+        .updateReservoirLevel(reservoirKey, new anchor.BN(level))
+        .accounts({
+          agency: wallet.publicKey,
+          authority: telemetry.publicKey,
+          role: null,
+        })
+        .signers([telemetry])
+        .rpc();
+
+    await updateLevel(649);
+    const reservoir = await program.account.reservoir.fetch(reservoirPDA);
+    assert.equal(reservoir.currentLevel.toNumber(), 649);
+
+    // Draining a third of the capacity within a few slots is implausible
+    try {
+      await updateLevel(300);
+      assert.fail("Expected the level to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "ImplausibleLevelChange");
+    }
+
+    // The telemetry key can only report levels
+    try {
+      await program.methods
+        .updateReservoirConfig(reservoirKey, new anchor.BN(2000))
+        .accounts({
+          agency: wallet.publicKey,
+          authority: telemetry.publicKey,
+          role: null,
+        })
+        .signers([telemetry])
+        .rpc();
+      assert.fail("Expected the configuration change to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+
+  it("should require the agency to co-sign sudden level changes", async () => {
+    const telemetry = Keypair.generate();
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(telemetry.publicKey, LAMPORTS_PER_SOL),
+      "confirmed"
+    );
+    await program.methods
+      .updateReservoirTelemetry(
+        reservoirKey,
+        telemetry.publicKey,
+        new anchor.BN(0)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .updateReservoirChangeLimit(reservoirKey, 100) // 1% of capacity per hour
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    const updateLevel = (level: number) =>
+      program.methods
+        .updateReservoirLevel(reservoirKey, new anchor.BN(level))
+        .accounts({
+          agency: wallet.publicKey,
+          authority: telemetry.publicKey,
+          role: null,
+        });
+
+    // Paid and signed by the telemetry key alone, a 5% drop is refused
+    try {
+      const tx = await updateLevel(600).transaction();
+      tx.feePayer = telemetry.publicKey;
+      await sendAndConfirmTransaction(connection, tx, [telemetry]);
+      assert.fail("Expected the level to be refused");
+    } catch (err) {
+      assert.include(`${err} ${err.logs}`, "ImplausibleLevelChange");
+    }
+
+    // Co-signed by the agency, the same drop is recorded
+    await updateLevel(600).signers([telemetry]).rpc();
+    const reservoir = await program.account.reservoir.fetch(reservoirPDA);
+    assert.equal(reservoir.currentLevel.toNumber(), 600);
+  });
+
+  it("should set dam safety alert levels within capacity", async () => {
+    const updateAlerts = (high: number, low: number) =>
+      program.methods
+        .updateReservoirAlerts(
+          reservoirKey,
+          new anchor.BN(high),
+          new anchor.BN(low)
+        )
+        .accounts({
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    try {
+      await updateAlerts(900, 900);
+      assert.fail("Expected the alert levels to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidAlertLevels");
+    }
+
+    await updateAlerts(900, 200);
+    const reservoir = await program.account.reservoir.fetch(reservoirPDA);
+    assert.equal(reservoir.alertLevelHigh.toNumber(), 900);
+    assert.equal(reservoir.alertLevelLow.toNumber(), 200);
+  });
+
+  it("should archive today's flows and retain recent shards", async () => {
+    const day = Math.floor(Date.now() / 1000 / 86400);
+    const shard = Math.floor(day / 32);
+    const [archivePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("hydro_archive"),
+        wallet.publicKey.toBuffer(),
+        reservoirKey.toBuffer(),
+        new anchor.BN(shard).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    for (const [inflow, outflow] of [
+      [1000, 4000],
+      [500, 2000],
+    ]) {
+      await program.methods
+        .recordHydroDay(
+          reservoirKey,
+          new anchor.BN(shard),
+          new anchor.BN(inflow),
+          new anchor.BN(outflow)
+        )
+        .accounts({
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+          role: null,
+        })
+        .rpc();
+    }
+
+    const archive = await program.account.hydroArchiveShard.fetch(archivePDA);
+    const entry = archive.days[day % 32];
+    assert.equal(entry.level.toNumber(), 600);
+    assert.equal(entry.inflow.toNumber(), 1500);
+    assert.equal(entry.outflow.toNumber(), 6000);
+
+    try {
+      await program.methods
+        .pruneHydroArchive(reservoirKey, new anchor.BN(shard))
+        .accounts({
+          agency: wallet.publicKey,
+          payer: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the shard to be retained");
+    } catch (err) {
+      assert.include(err.toString(), "ArchiveShardRetained");
+    }
+  });
+
   it("should initialize a reservoir with a different ID", async () => {
     let newReservoirKey = Keypair.generate().publicKey;
     const [newReservoirPDA] = PublicKey.findProgramAddressSync(
@@ -0,0 +1,217 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  appointRegulator,
+  grantRegulatorRole,
+  regulatorAuthority,
+} from "./fixtures/regulator";
+
+describe("roles", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  const auditor = Keypair.generate();
+  const meterOperator = Keypair.generate();
+  const regulator = Keypair.generate();
+
+  const rolePDA = (member: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("role"), wallet.publicKey.toBuffer(), member.toBuffer()],
+      program.programId
+    )[0];
+
+  const recordAudit = (staff: Keypair, seed: number) =>
+    program.methods
+      .recordAudit(
+        new Array(32).fill(seed),
+        { labReport: {} },
+        PublicKey.default
+      )
+      .accounts({
+        agency: wallet.publicKey,
+        authority: staff.publicKey,
+        role: rolePDA(staff.publicKey),
+      })
+      .signers([staff])
+      .rpc();
+
+  before(async () => {
+    for (const staff of [auditor, meterOperator]) {
+      await connection.confirmTransaction(
+        await connection.requestAirdrop(staff.publicKey, LAMPORTS_PER_SOL),
+        "confirmed"
+      );
+    }
+  });
+
+  it("Grants staff keys their roles", async () => {
+    await program.methods
+      .grantRole(auditor.publicKey, { auditor: {} })
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .grantRole(meterOperator.publicKey, { meterOperator: {} })
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    const role = await program.account.role.fetch(rolePDA(auditor.publicKey));
+    assert.equal(role.agency.toBase58(), wallet.publicKey.toBase58());
+    assert.equal(role.member.toBase58(), auditor.publicKey.toBase58());
+    assert.deepEqual(role.kind, { auditor: {} });
+  });
+
+  it("Staff can only perform the duties of their role", async () => {
+    await recordAudit(auditor, 1);
+
+    try {
+      await recordAudit(meterOperator, 2);
+      assert.fail("Expected the record to fail");
+    } catch (err) {
+      assert.include(err.toString(), "MissingRole");
+    }
+  });
+
+  it("Only admins can grant roles", async () => {
+    try {
+      await program.methods
+        .grantRole(meterOperator.publicKey, { admin: {} })
+        .accounts({
+          agency: wallet.publicKey,
+          authority: meterOperator.publicKey,
+          authorityRole: rolePDA(meterOperator.publicKey),
+        })
+        .signers([meterOperator])
+        .rpc();
+      assert.fail("Expected the grant to fail");
+    } catch (err) {
+      assert.include(err.toString(), "MissingRole");
+    }
+  });
+
+  it("Revoked staff lose their access", async () => {
+    await program.methods
+      .revokeRole(auditor.publicKey)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    const role = await connection.getAccountInfo(rolePDA(auditor.publicKey));
+    assert.isNull(role);
+
+    try {
+      await recordAudit(auditor, 3);
+      assert.fail("Expected the record to fail");
+    } catch (err) {
+      assert.include(err.toString(), "AccountNotInitialized");
+    }
+  });
+
+  it("Only the regulator authority grants the Regulator role", async () => {
+    try {
+      await program.methods
+        .grantRole(regulator.publicKey, { regulator: {} })
+        .accounts({
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the grant to fail");
+    } catch (err) {
+      assert.include(err.toString(), "ReservedRole");
+    }
+
+    await grantRegulatorRole(program, regulator.publicKey);
+    const role = await program.account.role.fetch(
+      rolePDA(regulator.publicKey)
+    );
+    assert.deepEqual(role.kind, { regulator: {} });
+
+    // The agency can neither replace nor revoke the regulator's role
+    try {
+      await program.methods
+        .grantRole(regulator.publicKey, { billing: {} })
+        .accounts({
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the grant to fail");
+    } catch (err) {
+      assert.include(err.toString(), "ReservedRole");
+    }
+    try {
+      await program.methods
+        .revokeRole(regulator.publicKey)
+        .accounts({
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the revocation to fail");
+    } catch (err) {
+      assert.include(err.toString(), "ReservedRole");
+    }
+  });
+
+  it("The regulator must accept its appointment", async () => {
+    try {
+      await program.methods
+        .appointRegulator()
+        .accounts({
+          agency: wallet.publicKey,
+          authority: Keypair.generate().publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the appointment to fail");
+    } catch (err) {
+      assert.include(err.toString(), "Missing signature");
+    }
+  });
+
+  it("The regulator cannot be replaced once appointed", async () => {
+    await appointRegulator(program);
+
+    const replacement = Keypair.generate();
+    try {
+      await program.methods
+        .appointRegulator()
+        .accounts({
+          agency: wallet.publicKey,
+          authority: replacement.publicKey,
+        })
+        .signers([replacement])
+        .rpc();
+      assert.fail("Expected the appointment to fail");
+    } catch (err) {
+      assert.include(err.toString(), "already in use");
+    }
+
+    await program.methods
+      .revokeRegulatorRole(regulator.publicKey)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: regulatorAuthority.publicKey,
+      })
+      .signers([regulatorAuthority])
+      .rpc();
+    assert.isNull(
+      await connection.getAccountInfo(rolePDA(regulator.publicKey))
+    );
+  });
+});
@@ -0,0 +1,221 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("scarcity", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  // Rationing curve: triple the block rate below half full, 1.5x above it
+  const scarcityCurve = [
+    { maxLevelBps: 5000, multiplier: new anchor.BN(3000) },
+    { maxLevelBps: 10000, multiplier: new anchor.BN(1500) },
+  ];
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const reservoirPDA = () =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservoir"),
+        wallet.publicKey.toBuffer(),
+        reservoirKey.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const updateScarcityCurve = (curve: typeof scarcityCurve) =>
+    program.methods
+      .updateReservoirScarcityCurve(reservoirKey, curve)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+  const useWater = (amount: number) =>
+    program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(amount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+  const wtkBalance = async () =>
+    Number((await getAccount(connection, consumerWtkAccount)).amount);
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { seasonalIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+  });
+
+  it("should reject a curve not covering a full reservoir", async () => {
+    try {
+      await updateScarcityCurve(scarcityCurve.slice(0, 1));
+      assert.fail("Expected the curve to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidScarcityCurve");
+    }
+  });
+
+  it("should price usage above the block by the curve", async () => {
+    await updateScarcityCurve(scarcityCurve);
+
+    const reservoir = await program.account.reservoir.fetch(reservoirPDA());
+    assert.equal(reservoir.scarcityCurve.length, 2);
+
+    // 100.000 units at 0.500, plus 20.000 units at 0.800 x 1.5 at 95% full
+    await useWater(120000);
+    assert.equal(await wtkBalance(), 74000);
+  });
+
+  it("should price on the smoothed level once enabled", async () => {
+    await program.methods
+      .updateReservoirSmoothing(reservoirKey, 5000)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // A sudden drop to 40% only moves the smoothed level halfway, to 67.5%
+    await program.methods
+      .updateReservoirLevel(reservoirKey, new anchor.BN(400000))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    const reservoir = await program.account.reservoir.fetch(reservoirPDA());
+    assert.equal(reservoir.smoothedLevel.toNumber(), 675000);
+
+    // The block is used up: 10.000 units at 0.800 x 1.5 rather than x 3
+    const before = await wtkBalance();
+    await useWater(10000);
+    assert.equal((await wtkBalance()) - before, 12000);
+  });
+
+  it("should restore the linear adjustment with an empty curve", async () => {
+    await updateScarcityCurve([]);
+
+    const reservoir = await program.account.reservoir.fetch(reservoirPDA());
+    assert.isEmpty(reservoir.scarcityCurve);
+  });
+});
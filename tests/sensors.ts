@@ -0,0 +1,128 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { assert } from "chai";
+
+describe("sensors", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  const reservoirKey = Keypair.generate().publicKey;
+  const gauges = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const sensorKeys = gauges.map(() => Keypair.generate().publicKey);
+
+  const reservoirPDA = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("reservoir"),
+      wallet.publicKey.toBuffer(),
+      reservoirKey.toBuffer(),
+    ],
+    program.programId
+  )[0];
+
+  const sensorPDAs = sensorKeys.map(
+    (sensorKey) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("sensor"),
+          wallet.publicKey.toBuffer(),
+          reservoirKey.toBuffer(),
+          sensorKey.toBuffer(),
+        ],
+        program.programId
+      )[0]
+  );
+
+  const asRemainingAccounts = (sensors: PublicKey[]) =>
+    sensors.map((pubkey) => ({
+      pubkey,
+      isWritable: false,
+      isSigner: false,
+    }));
+
+  const submitReading = (index: number, level: number) =>
+    program.methods
+      .submitSensorReading(new anchor.BN(level))
+      .accounts({
+        sensor: sensorPDAs[index],
+        authority: gauges[index].publicKey,
+      })
+      .signers([gauges[index]])
+      .rpc();
+
+  const aggregate = (sensors: PublicKey[]) =>
+    program.methods
+      .aggregateSensorLevels(reservoirKey)
+      .accounts({ agency: wallet.publicKey })
+      .remainingAccounts(asRemainingAccounts(sensors))
+      .rpc();
+
+  before(async () => {
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(900),
+        new anchor.BN(1000)
+      )
+      .accounts({ agency: wallet.publicKey })
+      .rpc();
+
+    for (let i = 0; i < gauges.length; i++) {
+      await program.methods
+        .registerSensor(reservoirKey, sensorKeys[i], gauges[i].publicKey)
+        .accounts({ agency: wallet.publicKey })
+        .rpc();
+    }
+  });
+
+  it("should refuse to aggregate before any sensor reported", async () => {
+    try {
+      await aggregate(sensorPDAs);
+      assert.fail("Expected the aggregation to fail");
+    } catch (err) {
+      assert.include(err.toString(), "NoRecentSensorReadings");
+    }
+  });
+
+  it("should only accept readings from the sensor's key", async () => {
+    try {
+      await program.methods
+        .submitSensorReading(new anchor.BN(800))
+        .accounts({
+          sensor: sensorPDAs[0],
+          authority: gauges[1].publicKey,
+        })
+        .signers([gauges[1]])
+        .rpc();
+      assert.fail("Expected the reading to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+
+  it("should set the level to the median of the readings", async () => {
+    await submitReading(0, 800);
+    await submitReading(1, 820);
+    // A faulty gauge reports far above capacity
+    await submitReading(2, 5000);
+
+    await aggregate(sensorPDAs);
+
+    const reservoir = await program.account.reservoir.fetch(reservoirPDA);
+    assert.equal(reservoir.currentLevel.toNumber(), 820);
+  });
+
+  it("should reject a sensor passed twice", async () => {
+    try {
+      await aggregate([sensorPDAs[2], sensorPDAs[2], sensorPDAs[0]]);
+      assert.fail("Expected the aggregation to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidSensor");
+    }
+  });
+});
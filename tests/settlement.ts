@@ -0,0 +1,297 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("settlement", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let usdcMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let consumerUsdcAccount: PublicKey;
+  let agencyUsdcAccount: PublicKey;
+  let fxOraclePDA: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const initialContractedCapacity = 100000; // 100.000
+  const initialBlockRate = 800; // 0.800
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const currencyCode = [...Buffer.from("TTD")];
+  const USDC_DECIMALS = 6;
+  const fxRate = 147300; // 1 TTD = 0.147300 USDC
+  const initialUsdcBalance = 10_000_000; // 10.000000 USDC
+
+  before(async () => {
+    // Initialize accounts
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    [fxOraclePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("fx_oracle"),
+        wallet.publicKey.toBuffer(),
+        Buffer.from(currencyCode),
+      ],
+      program.programId
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    usdcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      USDC_DECIMALS
+    );
+
+    // Create token accounts
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      watcMint,
+      consumer.publicKey
+    );
+
+    consumerUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    agencyUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      wallet.publicKey
+    ).then((account) => account.address);
+
+    await mintTo(
+      connection,
+      wallet.payer,
+      usdcMint,
+      consumerUsdcAccount,
+      wallet.payer,
+      initialUsdcBalance
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a tariff denominated in TTD
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .updateTariffCurrency(tariffKey, currencyCode)
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Initialize a reservoir
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(initialContractedCapacity),
+        new anchor.BN(initialBlockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    // Register the FX oracle, with the agency wallet posting rates
+    await program.methods
+      .initializeFxOracle(currencyCode, wallet.publicKey, new anchor.BN(1000))
+      .accounts({
+        settlementMint: usdcMint,
+        agency: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("Settlement is refused while the rate is unset", async () => {
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(1000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    try {
+      await program.methods
+        .settleWaterDebt(tariffKey, new anchor.BN(500))
+        .accounts({
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          wtkMint: wtkMint,
+          settlementMint: usdcMint,
+        })
+        .signers([consumer])
+        .rpc();
+      assert.fail("Expected settlement to fail");
+    } catch (err) {
+      assert.include(err.toString(), "StaleOracle");
+    }
+  });
+
+  it("Consumer settles water debt in USDC at the oracle rate", async () => {
+    await program.methods
+      .updateFxRate(new anchor.BN(fxRate))
+      .accounts({
+        fxOracle: fxOraclePDA,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    const usdcBefore = Number(
+      (await connection.getTokenAccountBalance(consumerUsdcAccount)).value
+        .amount
+    );
+    const treasuryBefore = Number(
+      (await connection.getTokenAccountBalance(agencyUsdcAccount)).value.amount
+    );
+
+    const settledAmount = 50000; // 50.000 TTD
+
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(100000))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const wtkBefore = Number(
+      (await connection.getTokenAccountBalance(consumerWtkAccount)).value
+        .amount
+    );
+
+    await program.methods
+      .settleWaterDebt(tariffKey, new anchor.BN(settledAmount))
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+        settlementMint: usdcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const expectedUsdc = (settledAmount * fxRate) / 10 ** DECIMALS;
+
+    const wtkAfter = Number(
+      (await connection.getTokenAccountBalance(consumerWtkAccount)).value
+        .amount
+    );
+    const usdcAfter = Number(
+      (await connection.getTokenAccountBalance(consumerUsdcAccount)).value
+        .amount
+    );
+    const treasuryAfter = Number(
+      (await connection.getTokenAccountBalance(agencyUsdcAccount)).value.amount
+    );
+
+    assert.equal(wtkAfter, wtkBefore - settledAmount);
+    assert.equal(usdcAfter, usdcBefore - expectedUsdc);
+    assert.equal(treasuryAfter, treasuryBefore + expectedUsdc);
+  });
+});
@@ -0,0 +1,210 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("shared reservoir", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let sharedReservoirPDA: PublicKey;
+  let consumer: Keypair;
+
+  // A neighbouring agency holding 40% of the reservoir
+  const partnerAgency = Keypair.generate().publicKey;
+  const periodVolume = 100000; // 100.000 per period, shared 60/40
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const configureSharedReservoir = (
+    shares: { agency: PublicKey; shareBps: number }[]
+  ) =>
+    program.methods
+      .configureSharedReservoir(
+        reservoirKey,
+        new anchor.BN(periodVolume),
+        new anchor.BN(1000000),
+        shares
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+  const useWater = (amount: number) =>
+    program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(amount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+        sharedReservoir: sharedReservoirPDA,
+      })
+      .signers([consumer])
+      .rpc();
+
+  const owedBy = async (agency: PublicKey) =>
+    (
+      await program.account.sharedReservoir.fetch(sharedReservoirPDA)
+    ).drawRights
+      .find((right) => right.agency.equals(agency))
+      .owed.toNumber();
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+    consumer = Keypair.generate();
+
+    [sharedReservoirPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("shared_reservoir"),
+        wallet.publicKey.toBuffer(),
+        reservoirKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    );
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(500),
+        new anchor.BN(200),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(950000),
+        new anchor.BN(1000000)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(100000),
+        new anchor.BN(800)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+  });
+
+  it("should refuse shares not splitting the whole reservoir", async () => {
+    try {
+      await configureSharedReservoir([
+        { agency: wallet.publicKey, shareBps: 6000 },
+        { agency: partnerAgency, shareBps: 3000 },
+      ]);
+      assert.fail("Expected the draw rights to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidDrawRights");
+    }
+  });
+
+  it("should record draws beyond the agency's share as owed", async () => {
+    await configureSharedReservoir([
+      { agency: wallet.publicKey, shareBps: 6000 },
+      { agency: partnerAgency, shareBps: 4000 },
+    ]);
+
+    await useWater(50000);
+    assert.equal(await owedBy(wallet.publicKey), 0);
+
+    // The agency may draw 60.000 per period
+    await useWater(25000);
+    assert.equal(await owedBy(wallet.publicKey), 15000);
+    assert.equal(await owedBy(partnerAgency), 0);
+  });
+
+  it("should keep obligations until the operator settles them", async () => {
+    try {
+      await configureSharedReservoir([
+        { agency: partnerAgency, shareBps: 10000 },
+      ]);
+      assert.fail("Expected the agency's removal to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "OutstandingSettlement");
+    }
+
+    await program.methods
+      .settleSharedDraw(reservoirKey, wallet.publicKey, new anchor.BN(10000))
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+    assert.equal(await owedBy(wallet.publicKey), 5000);
+  });
+});
@@ -0,0 +1,93 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey } from "@solana/web3.js";
+import {
+  AccountLayout,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { startAnchor, ProgramTestContext } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { loadSnapshot, loadManifest, loadKeypair } from "./fixtures/snapshot";
+import * as fs from "fs";
+import { assert } from "chai";
+
+describe("snapshot", () => {
+  const manifest = loadManifest();
+  const agency = loadKeypair(manifest.agency_keypair);
+  const wtkMint = new PublicKey(manifest.mints.wtk);
+  const watcMint = new PublicKey(manifest.mints.watc);
+
+  let context: ProgramTestContext;
+  let program: Program<Aquachain>;
+
+  before(async () => {
+    // Load the initialized agency instead of rebuilding it transaction by
+    // transaction, with the program deployed from target/deploy
+    context = await startAnchor("", [], loadSnapshot());
+    const provider = new BankrunProvider(context);
+    const idl = JSON.parse(
+      fs.readFileSync("target/idl/aquachain.json", "utf8")
+    );
+    program = new Program<Aquachain>(idl as Aquachain, provider);
+  });
+
+  it("Loads the agency's tariffs, reservoirs and consumers", async () => {
+    assert.equal(manifest.tariffs.length, 3);
+    assert.equal(manifest.reservoirs.length, 2);
+    assert.equal(manifest.consumers.length, 50);
+
+    for (const tariff of manifest.tariffs) {
+      const account = await program.account.tariff.fetch(tariff.address);
+      assert.equal(account.tariffKey.toBase58(), tariff.tariff_key);
+    }
+    const consumer = await program.account.consumer.fetch(
+      manifest.consumers[0].address
+    );
+    assert.equal(
+      consumer.contractedCapacity.toNumber(),
+      manifest.consumers[0].contracted_capacity
+    );
+    assert.isAbove(consumer.outstandingWaterDebt.toNumber(), 0);
+  });
+
+  it("Bills a snapshot consumer without rebuilding the agency", async () => {
+    const fixture = manifest.consumers[0];
+    const consumer = loadKeypair(fixture.keypair);
+    const tariff = manifest.tariffs.find((t) => t.name === fixture.tariff);
+    const reservoir = manifest.reservoirs.find(
+      (r) => r.name === fixture.reservoir
+    );
+    const before = await program.account.consumer.fetch(consumer.publicKey);
+
+    await program.methods
+      .useWater(
+        new PublicKey(tariff.tariff_key),
+        new PublicKey(reservoir.reservoir_key),
+        new anchor.BN(1000)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: agency.publicKey,
+      })
+      .signers([consumer, agency])
+      .rpc();
+
+    const after = await program.account.consumer.fetch(consumer.publicKey);
+    assert.isAbove(
+      after.outstandingWaterDebt.toNumber(),
+      before.outstandingWaterDebt.toNumber()
+    );
+
+    const wtkAddress = getAssociatedTokenAddressSync(
+      wtkMint,
+      consumer.publicKey
+    );
+    const wtk = AccountLayout.decode(
+      Buffer.from((await context.banksClient.getAccount(wtkAddress)).data)
+    );
+    assert.equal(Number(wtk.amount), after.outstandingWaterDebt.toNumber());
+  });
+});
@@ -106,6 +106,7 @@ describe("tariff", () => {
       )
       .accounts({
         agency: wallet.publicKey,
+        authority: wallet.publicKey,
       })
       .rpc();
 
@@ -120,6 +121,7 @@ describe("tariff", () => {
       .updateTariffType(tariffKey, { seasonalDbt: {} })
       .accounts({
         agency: wallet.publicKey,
+        authority: wallet.publicKey,
       })
       .rpc();
 
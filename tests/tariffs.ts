@@ -38,6 +38,13 @@ describe("tariffs", () => {
   const initialContractedCapacity = 100000; // 100.000
   const initialBlockRate = 800; // 0.800
 
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
   beforeEach(async () => {
     // Initialize accounts
     tariffKey = Keypair.generate().publicKey;
@@ -51,21 +58,21 @@ describe("tariffs", () => {
       wallet.payer,
       wallet.publicKey,
       null,
-      9
+      DECIMALS
     );
     watcMint = await createMint(
       connection,
       wallet.payer,
       wallet.publicKey,
       null,
-      9
+      DECIMALS
     );
     wstMint = await createMint(
       connection,
       wallet.payer,
       wallet.publicKey,
       null,
-      9
+      DECIMALS
     );
 
     // Create token accounts
@@ -90,6 +97,14 @@ describe("tariffs", () => {
       consumer.publicKey
     ).then((account) => account.address);
 
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
     // Initialize a tariff
     await program.methods
       .initializeTariff(
@@ -150,6 +165,11 @@ describe("tariffs", () => {
       consumerWstBalance.value.amount,
       String((wasteAmount * initialWasteRate) / SCALE)
     );
+
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+    assert.equal(consumerAccount.periodWaste.toNumber(), wasteAmount);
   });
 
   it("Consumer can use water within contracted capacity", async () => {
@@ -158,6 +178,7 @@ describe("tariffs", () => {
       .updateTariffType(tariffKey, { uniformIbt: {} })
       .accounts({
         agency: wallet.publicKey,
+        authority: wallet.publicKey,
       })
       .rpc();
 
@@ -211,6 +232,7 @@ describe("tariffs", () => {
           .updateTariffType(tariffKey, type)
           .accounts({
             agency: wallet.publicKey,
+            authority: wallet.publicKey,
           })
           .rpc();
 
@@ -271,4 +293,342 @@ describe("tariffs", () => {
       });
     });
   });
+
+  it("Superseded tariff versions are kept in the history", async () => {
+    const newWaterRate = 650; // 0.650
+    const newWasteRate = 250; // 0.250
+
+    await program.methods
+      .updateTariffRates(
+        tariffKey,
+        new anchor.BN(newWaterRate),
+        new anchor.BN(newWasteRate)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    const [tariffPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("tariff"),
+        wallet.publicKey.toBuffer(),
+        tariffKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [tariffHistoryPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("tariff_history"),
+        wallet.publicKey.toBuffer(),
+        tariffKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    const tariff = await program.account.tariff.fetch(tariffPDA);
+    const history = await program.account.tariffHistory.fetch(
+      tariffHistoryPDA
+    );
+
+    // Three tariff type changes followed by one rate change
+    assert.equal(history.len, 4);
+    const latest = history.versions[history.head - 1];
+    assert.equal(latest.waterRate.toNumber(), initialWaterRate);
+    assert.equal(latest.wasteRate.toNumber(), initialWasteRate);
+    assert.deepEqual(latest.tariffType, { seasonalDbt: {} });
+    assert.equal(
+      latest.supersededSlot.toNumber(),
+      tariff.activatedSlot.toNumber()
+    );
+    assert.equal(tariff.waterRate.toNumber(), newWaterRate);
+  });
+
+  it("Past periods are rebilled under the tariff in force at the time", async () => {
+    const [tariffHistoryPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("tariff_history"),
+        wallet.publicKey.toBuffer(),
+        tariffKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const history = await program.account.tariffHistory.fetch(
+      tariffHistoryPDA
+    );
+    const periodSlot = history.versions[history.head - 1].activatedSlot;
+
+    // A late meter read shows 50.000 used within capacity, billed as 30.000 WTK
+    const actualVolume = 50000;
+    const billedAmount = 30000;
+    const correctedAmount = (actualVolume * initialWaterRate) / SCALE;
+
+    const wtkBefore = Number(
+      (await connection.getTokenAccountBalance(consumerWtkAccount)).value
+        .amount
+    );
+
+    await program.methods
+      .rebillPeriod(
+        tariffKey,
+        reservoirKey,
+        periodSlot,
+        new anchor.BN(actualVolume),
+        new anchor.BN(billedAmount)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const wtkAfter = Number(
+      (await connection.getTokenAccountBalance(consumerWtkAccount)).value
+        .amount
+    );
+    assert.equal(wtkAfter, wtkBefore - (billedAmount - correctedAmount));
+
+    // Periods older than any stored version cannot be rebilled
+    try {
+      await program.methods
+        .rebillPeriod(
+          tariffKey,
+          reservoirKey,
+          new anchor.BN(0),
+          new anchor.BN(actualVolume),
+          new anchor.BN(billedAmount)
+        )
+        .accounts({
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          wtkMint: wtkMint,
+        })
+        .signers([consumer])
+        .rpc();
+      assert.fail("Expected rebilling to fail");
+    } catch (err) {
+      assert.include(err.toString(), "TariffVersionUnavailable");
+    }
+  });
+
+  it("Usage is tiered against the tariff's block threshold", async () => {
+    const blockThreshold = 50000; // 50.000
+    await program.methods
+      .updateTariffType(tariffKey, { uniformIbt: {} })
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .updateTariffBlockThreshold(tariffKey, new anchor.BN(blockThreshold))
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Two readings that add up to 60.000, within the contracted capacity
+    for (const waterAmount of [40000, 20000]) {
+      await program.methods
+        .useWater(tariffKey, reservoirKey, new anchor.BN(waterAmount))
+        .accounts({
+          consumer: consumer.publicKey,
+          wtkMint: wtkMint,
+          watcMint: watcMint,
+          agency: wallet.publicKey,
+        })
+        .signers([consumer])
+        .rpc();
+    }
+
+    const consumerWtkBalance = await connection.getTokenAccountBalance(
+      consumerWtkAccount
+    );
+    const consumerWatcBalance = await connection.getTokenAccountBalance(
+      consumerWatcAccount
+    );
+    const consumerAccount = await program.account.consumer.fetch(
+      consumer.publicKey
+    );
+
+    // The second reading crosses the threshold although WATC is left over
+    const expectedWaterTokenCost =
+      (blockThreshold * initialWaterRate +
+        (60000 - blockThreshold) * initialBlockRate) /
+      SCALE;
+    assert.equal(
+      consumerWtkBalance.value.amount,
+      String(expectedWaterTokenCost)
+    );
+    assert.equal(
+      consumerWatcBalance.value.amount,
+      String(initialContractedCapacity - 60000)
+    );
+    assert.equal(consumerAccount.periodUsage.toNumber(), 60000);
+  });
+
+  it("Lifeline allocation scales with the household size", async () => {
+    const perCapitaAllowance = 15000; // 15.000
+    const householdSize = 2;
+    await program.methods
+      .updateTariffType(tariffKey, { uniformIbt: {} })
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .updateTariffPerCapitaAllowance(
+        tariffKey,
+        new anchor.BN(perCapitaAllowance)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .updateConsumerHousehold(tariffKey, householdSize)
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    const waterAmount = 40000; // 40.000
+    await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(waterAmount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc();
+
+    const consumerWtkBalance = await connection.getTokenAccountBalance(
+      consumerWtkAccount
+    );
+    const lifeline = perCapitaAllowance * householdSize;
+    const expectedWaterTokenCost =
+      (lifeline * initialWaterRate +
+        (waterAmount - lifeline) * initialBlockRate) /
+      SCALE;
+    assert.equal(
+      consumerWtkBalance.value.amount,
+      String(expectedWaterTokenCost)
+    );
+  });
+
+  it("Expired contracts are charged at the block rate", async () => {
+    await program.methods
+      .updateTariffType(tariffKey, { uniformIbt: {} })
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    // Renewals must end in the future
+    try {
+      await program.methods
+        .renewContract(tariffKey, new anchor.BN(1))
+        .accounts({
+          consumer: consumer.publicKey,
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Expected the renewal to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidContractExpiry");
+    }
+
+    const slot = await connection.getSlot();
+    await program.methods
+      .renewContract(tariffKey, new anchor.BN(slot + 2))
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    const waterAmount = 10000; // 10.000, well within contracted capacity
+    const signature = await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(waterAmount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc({ commitment: "confirmed" });
+
+    const consumerWtkBalance = await connection.getTokenAccountBalance(
+      consumerWtkAccount
+    );
+    assert.equal(
+      consumerWtkBalance.value.amount,
+      String((waterAmount * initialBlockRate) / SCALE)
+    );
+
+    const tx = await connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(
+      program.programId,
+      new anchor.BorshCoder(program.idl)
+    );
+    const events = [...parser.parseLogs(tx.meta.logMessages)];
+    assert.include(
+      events.map((event) => event.name),
+      "ContractExpired"
+    );
+  });
+
+  it("Withdrawals are aggregated per reservoir and day", async () => {
+    const waterAmount = 10000; // 10.000
+    const signature = await program.methods
+      .useWater(tariffKey, reservoirKey, new anchor.BN(waterAmount))
+      .accounts({
+        consumer: consumer.publicKey,
+        wtkMint: wtkMint,
+        watcMint: watcMint,
+        agency: wallet.publicKey,
+      })
+      .signers([consumer])
+      .rpc({ commitment: "confirmed" });
+    const tx = await connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const day = Math.floor(tx.blockTime / 86400);
+
+    const [dailyStatsPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservoir_daily_stats"),
+        wallet.publicKey.toBuffer(),
+        reservoirKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const dailyStats = await program.account.reservoirDailyStats.fetch(
+      dailyStatsPDA
+    );
+    assert.equal(dailyStats.reservoirKey.toBase58(), reservoirKey.toBase58());
+
+    // Earlier readings of the suite fall on the same day
+    const today = dailyStats.days.find((entry) => entry.day.toNumber() === day);
+    assert.isAtLeast(today.volume.toNumber(), waterAmount);
+  });
 });
@@ -1,7 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Aquachain } from "../target/types/aquachain";
-import { PublicKey } from "@solana/web3.js";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
 import { createMint } from "@solana/spl-token";
 import { assert } from "chai";
 
@@ -18,7 +18,19 @@ describe("tokens", () => {
   let watcMint: PublicKey;
   let wstMint: PublicKey;
 
+  // A dedicated authority so the recorded mints are not shared with other suites
+  const authority = Keypair.generate();
+  const units = {
+    volumeScale: new anchor.BN(1000),
+    currencyDecimals: 9,
+  };
+
   before(async () => {
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(authority.publicKey, LAMPORTS_PER_SOL),
+      "confirmed"
+    );
+
     // Initialize token mints
     wtkMint = await createMint(
       connection,
@@ -44,16 +56,17 @@ describe("tokens", () => {
 
     // Register tokens
     await program.methods
-      .initializeTokens(wtkMint, watcMint, wstMint)
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
       .accounts({
-        authority: wallet.publicKey,
+        authority: authority.publicKey,
       })
+      .signers([authority])
       .rpc();
   });
 
   it("Tokens are recorded", async () => {
     const [tokensPDA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("tokens"), wallet.publicKey.toBuffer()],
+      [Buffer.from("tokens"), authority.publicKey.toBuffer()],
       program.programId
     );
 
@@ -62,5 +75,34 @@ describe("tokens", () => {
     assert.equal(tokens.wtk.toString(), wtkMint.toString());
     assert.equal(tokens.watc.toString(), watcMint.toString());
     assert.equal(tokens.wst.toString(), wstMint.toString());
+    assert.equal(tokens.units.volumeScale.toNumber(), 1000);
+    assert.equal(tokens.units.currencyDecimals, 9);
+  });
+
+  it("Rejects a zero volume scale", async () => {
+    const otherAuthority = Keypair.generate();
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(
+        otherAuthority.publicKey,
+        LAMPORTS_PER_SOL
+      ),
+      "confirmed"
+    );
+
+    try {
+      await program.methods
+        .initializeTokens(wtkMint, watcMint, wstMint, {
+          volumeScale: new anchor.BN(0),
+          currencyDecimals: 9,
+        })
+        .accounts({
+          authority: otherAuthority.publicKey,
+        })
+        .signers([otherAuthority])
+        .rpc();
+      assert.fail("Expected initialization to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidUnitConfig");
+    }
   });
 });
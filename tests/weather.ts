@@ -0,0 +1,225 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe("weather", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let usdcMint: PublicKey;
+  let agencyUsdcAccount: PublicKey;
+  let counterpartyUsdcAccount: PublicKey;
+  let weatherDerivativePDA: PublicKey;
+  let derivativeKey: PublicKey;
+  let counterparty: Keypair;
+  let oracle: Keypair;
+  let windowStart: number;
+  let windowEnd: number;
+
+  const USDC_DECIMALS = 6;
+  const initialUsdcBalance = 100_000_000; // 100.000000 USDC
+  const payout = 50_000_000; // 50.000000 USDC
+  const premium = 2_500_000; // 2.500000 USDC
+  const strike = 3000; // 300 mm of rainfall over the window
+
+  const chainTime = async () =>
+    connection.getBlockTime(await connection.getSlot());
+
+  const waitUntil = async (timestamp: number) => {
+    while ((await chainTime()) < timestamp) {
+      await sleep(500);
+    }
+  };
+
+  before(async () => {
+    derivativeKey = Keypair.generate().publicKey;
+    counterparty = Keypair.generate();
+    oracle = Keypair.generate();
+
+    [weatherDerivativePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("weather_derivative"),
+        wallet.publicKey.toBuffer(),
+        derivativeKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(counterparty.publicKey, LAMPORTS_PER_SOL),
+      "confirmed"
+    );
+
+    usdcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      USDC_DECIMALS
+    );
+
+    agencyUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      wallet.publicKey
+    ).then((account) => account.address);
+
+    counterpartyUsdcAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      usdcMint,
+      counterparty.publicKey
+    ).then((account) => account.address);
+
+    for (const account of [agencyUsdcAccount, counterpartyUsdcAccount]) {
+      await mintTo(
+        connection,
+        wallet.payer,
+        usdcMint,
+        account,
+        wallet.payer,
+        initialUsdcBalance
+      );
+    }
+
+    windowStart = (await chainTime()) + 4;
+    windowEnd = windowStart + 4;
+  });
+
+  it("Counterparty escrows the payout of rainfall cover", async () => {
+    await program.methods
+      .offerWeatherDerivative(
+        derivativeKey,
+        oracle.publicKey,
+        new anchor.BN(strike),
+        new anchor.BN(windowStart),
+        new anchor.BN(windowEnd),
+        new anchor.BN(payout),
+        new anchor.BN(premium)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+        counterparty: counterparty.publicKey,
+        settlementMint: usdcMint,
+      })
+      .signers([counterparty])
+      .rpc();
+
+    const derivative = await program.account.weatherDerivative.fetch(
+      weatherDerivativePDA
+    );
+    assert.deepEqual(derivative.status, { offered: {} });
+
+    const counterpartyUsdc = await getAccount(
+      connection,
+      counterpartyUsdcAccount
+    );
+    assert.equal(Number(counterpartyUsdc.amount), initialUsdcBalance - payout);
+  });
+
+  it("Agency treasury buys the cover", async () => {
+    await program.methods
+      .buyWeatherDerivative()
+      .accounts({
+        weatherDerivative: weatherDerivativePDA,
+        agency: wallet.publicKey,
+        counterparty: counterparty.publicKey,
+        settlementMint: usdcMint,
+      })
+      .rpc();
+
+    const derivative = await program.account.weatherDerivative.fetch(
+      weatherDerivativePDA
+    );
+    assert.deepEqual(derivative.status, { active: {} });
+
+    const agencyUsdc = await getAccount(connection, agencyUsdcAccount);
+    assert.equal(Number(agencyUsdc.amount), initialUsdcBalance - premium);
+  });
+
+  it("Oracle reports rainfall within the window", async () => {
+    await waitUntil(windowStart + 1);
+
+    await program.methods
+      .reportRainfall(new anchor.BN(1200), new anchor.BN(windowStart + 1))
+      .accounts({
+        weatherDerivative: weatherDerivativePDA,
+        oracleAuthority: oracle.publicKey,
+      })
+      .signers([oracle])
+      .rpc();
+
+    try {
+      await program.methods
+        .reportRainfall(new anchor.BN(1200), new anchor.BN(windowStart + 1))
+        .accounts({
+          weatherDerivative: weatherDerivativePDA,
+          oracleAuthority: oracle.publicKey,
+        })
+        .signers([oracle])
+        .rpc();
+      assert.fail("The same observation was counted twice");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidRainfallObservation");
+    }
+
+    try {
+      await program.methods
+        .settleWeatherDerivative()
+        .accounts({
+          weatherDerivative: weatherDerivativePDA,
+          agency: wallet.publicKey,
+          counterparty: counterparty.publicKey,
+          settlementMint: usdcMint,
+          payer: wallet.publicKey,
+        })
+        .rpc();
+      assert.fail("Derivative settled before its window ended");
+    } catch (err) {
+      assert.include(err.toString(), "WeatherWindowOpen");
+    }
+  });
+
+  it("Dry window pays the escrow to the agency treasury", async () => {
+    await waitUntil(windowEnd);
+
+    await program.methods
+      .settleWeatherDerivative()
+      .accounts({
+        weatherDerivative: weatherDerivativePDA,
+        agency: wallet.publicKey,
+        counterparty: counterparty.publicKey,
+        settlementMint: usdcMint,
+        payer: wallet.publicKey,
+      })
+      .rpc();
+
+    const derivative = await program.account.weatherDerivative.fetch(
+      weatherDerivativePDA
+    );
+    assert.deepEqual(derivative.status, { settled: {} });
+    assert.equal(derivative.cumulativeRainfall.toNumber(), 1200);
+
+    const agencyUsdc = await getAccount(connection, agencyUsdcAccount);
+    assert.equal(
+      Number(agencyUsdc.amount),
+      initialUsdcBalance - premium + payout
+    );
+  });
+});
@@ -0,0 +1,285 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Aquachain } from "../target/types/aquachain";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("workorders", () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Aquachain as Program<Aquachain>;
+  const connection = provider.connection;
+  const wallet = provider.wallet as anchor.Wallet;
+
+  let wtkMint: PublicKey;
+  let watcMint: PublicKey;
+  let wstMint: PublicKey;
+  let consumerWtkAccount: PublicKey;
+  let tariffKey: PublicKey;
+  let reservoirKey: PublicKey;
+  let consumer: Keypair;
+
+  const worker = Keypair.generate();
+  const repairKey = Keypair.generate().publicKey;
+  const reconnectionKey = Keypair.generate().publicKey;
+
+  const initialWaterRate = 500; // 0.500
+  const initialWasteRate = 200; // 0.200
+
+  const initialReservoirLevel = 950000; // 950.000
+  const initialReservoirCapacity = 1000000; // 1000.000
+
+  const contractedCapacity = 100000; // 100.000
+  const blockRate = 800; // 0.800
+
+  const repairFee = 10000; // 10.000 WTK
+  const reconnectionFee = 25000; // 25.000 WTK
+
+  // Billing units shared by every test suite of this agency
+  const DECIMALS = 3; // 1 WTK = 1000 base units, matching the fixed-point scale
+  const units = {
+    volumeScale: new anchor.BN(1000), // 1000 raw units per billed unit
+    currencyDecimals: DECIMALS,
+  };
+
+  const rolePDA = (member: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("role"), wallet.publicKey.toBuffer(), member.toBuffer()],
+      program.programId
+    )[0];
+
+  const workOrderPDA = (orderKey: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("work_order"),
+        wallet.publicKey.toBuffer(),
+        orderKey.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const createWorkOrder = (
+    orderKey: PublicKey,
+    kind: any,
+    assignee: PublicKey,
+    fee: number,
+    prerequisite: PublicKey | null
+  ) =>
+    program.methods
+      .createWorkOrder(orderKey, kind, assignee, new anchor.BN(fee))
+      .accounts({
+        consumer: consumer.publicKey,
+        prerequisite: prerequisite,
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+        workerRole: rolePDA(assignee),
+      })
+      .rpc();
+
+  const completeWorkOrder = (orderKey: PublicKey, signer: Keypair) =>
+    program.methods
+      .completeWorkOrder()
+      .accounts({
+        workOrder: workOrderPDA(orderKey),
+        worker: signer.publicKey,
+      })
+      .signers([signer])
+      .rpc();
+
+  const invoiceWorkOrder = (
+    orderKey: PublicKey,
+    prerequisite: PublicKey | null
+  ) =>
+    program.methods
+      .invoiceWorkOrder()
+      .accounts({
+        workOrder: workOrderPDA(orderKey),
+        prerequisite: prerequisite,
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        wtkMint: wtkMint,
+      })
+      .rpc();
+
+  const wtkBalance = async () =>
+    Number((await getAccount(connection, consumerWtkAccount)).amount);
+
+  before(async () => {
+    tariffKey = Keypair.generate().publicKey;
+    reservoirKey = Keypair.generate().publicKey;
+
+    consumer = Keypair.generate();
+
+    // Initialize token mints
+    wtkMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    watcMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+    wstMint = await createMint(
+      connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      DECIMALS
+    );
+
+    consumerWtkAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      wallet.payer,
+      wtkMint,
+      consumer.publicKey
+    ).then((account) => account.address);
+
+    // Register token mints and billing units (no-op if already registered)
+    await program.methods
+      .initializeTokens(wtkMint, watcMint, wstMint, units)
+      .accounts({
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeTariff(
+        tariffKey,
+        new anchor.BN(initialWaterRate),
+        new anchor.BN(initialWasteRate),
+        { uniformIbt: {} }
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .initializeReservoir(
+        reservoirKey,
+        new anchor.BN(initialReservoirLevel),
+        new anchor.BN(initialReservoirCapacity)
+      )
+      .accounts({
+        agency: wallet.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerConsumer(
+        tariffKey,
+        reservoirKey,
+        new anchor.BN(contractedCapacity),
+        new anchor.BN(blockRate)
+      )
+      .accounts({
+        consumer: consumer.publicKey,
+        agency: wallet.publicKey,
+        watcMint: watcMint,
+      })
+      .signers([consumer])
+      .rpc();
+
+    await program.methods
+      .grantRole(worker.publicKey, { fieldOperator: {} })
+      .accounts({
+        agency: wallet.publicKey,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+  });
+
+  it("should only dispatch work orders to field operators", async () => {
+    try {
+      await program.methods
+        .createWorkOrder(
+          Keypair.generate().publicKey,
+          { meterSwap: {} },
+          Keypair.generate().publicKey,
+          new anchor.BN(0)
+        )
+        .accounts({
+          consumer: consumer.publicKey,
+          prerequisite: null,
+          agency: wallet.publicKey,
+          authority: wallet.publicKey,
+          workerRole: null,
+        })
+        .rpc();
+      assert.fail("Expected the work order to be refused");
+    } catch (err) {
+      assert.include(err.toString(), "MissingRole");
+    }
+
+    await createWorkOrder(
+      repairKey,
+      { repair: {} },
+      worker.publicKey,
+      repairFee,
+      null
+    );
+    await createWorkOrder(
+      reconnectionKey,
+      { reconnection: {} },
+      worker.publicKey,
+      reconnectionFee,
+      workOrderPDA(repairKey)
+    );
+
+    const reconnection = await program.account.workOrder.fetch(
+      workOrderPDA(reconnectionKey)
+    );
+    assert.deepEqual(reconnection.status, { open: {} });
+    assert.isTrue(reconnection.dependsOn.equals(workOrderPDA(repairKey)));
+  });
+
+  it("should only let the assigned worker complete a work order", async () => {
+    try {
+      await invoiceWorkOrder(repairKey, null);
+      assert.fail("Expected the invoice to fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidWorkOrderStatus");
+    }
+
+    try {
+      await completeWorkOrder(repairKey, Keypair.generate());
+      assert.fail("Expected the completion to fail");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+
+    await completeWorkOrder(repairKey, worker);
+    await completeWorkOrder(reconnectionKey, worker);
+
+    const repair = await program.account.workOrder.fetch(
+      workOrderPDA(repairKey)
+    );
+    assert.deepEqual(repair.status, { completed: {} });
+  });
+
+  it("should waive the reconnection fee once the repair is done", async () => {
+    await invoiceWorkOrder(repairKey, null);
+    assert.equal(await wtkBalance(), repairFee);
+
+    await invoiceWorkOrder(reconnectionKey, workOrderPDA(repairKey));
+    assert.equal(await wtkBalance(), repairFee);
+
+    const reconnection = await program.account.workOrder.fetch(
+      workOrderPDA(reconnectionKey)
+    );
+    assert.deepEqual(reconnection.status, { invoiced: {} });
+  });
+});
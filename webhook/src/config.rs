@@ -0,0 +1,68 @@
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+/// Webhook configuration loaded from a JSON file.
+///
+/// # Example
+/// ```json
+/// {
+///   "webhooks": [
+///     { "url": "https://billing.example.com/aquachain", "events": ["WaterBilled", "PaymentReceived"] },
+///     { "url": "https://ops.example.com/alerts", "events": ["ReservoirLow"] }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    pub webhooks: Vec<Webhook>,
+}
+
+/// A single endpoint that receives event payloads
+///
+/// # Fields
+/// * `url` - Endpoint the JSON payloads are POSTed to
+/// * `events` - Names of the events to forward, all events if empty
+#[derive(Debug, Deserialize)]
+pub struct Webhook {
+    pub url: String,
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+impl Webhook {
+    /// Returns true if this webhook subscribes to the named event
+    pub fn wants(&self, event: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|name| name == event)
+    }
+}
+
+impl Config {
+    /// Reads the configuration from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+        serde_json::from_str(&contents)
+            .map_err(|err| format!("invalid config {}: {}", path.display(), err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_filter() {
+        let config: Config = serde_json::from_str(
+            r#"{ "webhooks": [
+                { "url": "https://a.example.com" },
+                { "url": "https://b.example.com", "events": ["ReservoirLow"] }
+            ] }"#,
+        )
+        .unwrap();
+
+        assert!(config.webhooks[0].wants("PaymentReceived"));
+        assert!(config.webhooks[1].wants("ReservoirLow"));
+        assert!(!config.webhooks[1].wants("PaymentReceived"));
+    }
+}
@@ -0,0 +1,212 @@
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use aquachain::events::*;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+
+/// Prefix of the log lines Anchor writes emitted events to
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// An event decoded from a transaction's logs
+///
+/// # Fields
+/// * `name` - Name of the event struct, e.g. `PaymentReceived`
+/// * `data` - The event fields as JSON, with public keys in base58
+#[derive(Debug, PartialEq)]
+pub struct DecodedEvent {
+    pub name: &'static str,
+    pub data: Value,
+}
+
+/// Converts an event into the JSON payload sent to webhooks
+trait ToJson {
+    fn to_json(&self) -> Value;
+}
+
+impl ToJson for AccountHolderReassigned {
+    fn to_json(&self) -> Value {
+        json!({
+            "consumer": self.consumer.to_string(),
+            "previous_owner": self.previous_owner.to_string(),
+            "new_owner": self.new_owner.to_string(),
+            "slot": self.slot,
+        })
+    }
+}
+
+impl ToJson for CapacityAdjusted {
+    fn to_json(&self) -> Value {
+        json!({
+            "consumer": self.consumer.to_string(),
+            "delta": self.delta,
+            "reason": format!("{:?}", self.reason),
+            "balance": self.balance,
+            "slot": self.slot,
+        })
+    }
+}
+
+impl ToJson for PeriodRebilled {
+    fn to_json(&self) -> Value {
+        json!({
+            "consumer": self.consumer.to_string(),
+            "period_slot": self.period_slot,
+            "billed_amount": self.billed_amount,
+            "corrected_amount": self.corrected_amount,
+            "slot": self.slot,
+        })
+    }
+}
+
+impl ToJson for BudgetBillingReconciled {
+    fn to_json(&self) -> Value {
+        json!({
+            "consumer": self.consumer.to_string(),
+            "levelized_paid": self.levelized_paid,
+            "difference": self.difference,
+            "budget_amount": self.budget_amount,
+            "slot": self.slot,
+        })
+    }
+}
+
+impl ToJson for WaterBilled {
+    fn to_json(&self) -> Value {
+        json!({
+            "consumer": self.consumer.to_string(),
+            "volume": self.volume,
+            "charge": self.charge,
+            "outstanding": self.outstanding,
+            "slot": self.slot,
+        })
+    }
+}
+
+impl ToJson for PaymentReceived {
+    fn to_json(&self) -> Value {
+        json!({
+            "consumer": self.consumer.to_string(),
+            "amount": self.amount,
+            "outstanding": self.outstanding,
+            "slot": self.slot,
+        })
+    }
+}
+
+impl ToJson for ReservoirLow {
+    fn to_json(&self) -> Value {
+        json!({
+            "reservoir_key": self.reservoir_key.to_string(),
+            "current_level": self.current_level,
+            "capacity": self.capacity,
+            "slot": self.slot,
+        })
+    }
+}
+
+impl ToJson for ConsumerCompressed {
+    fn to_json(&self) -> Value {
+        json!({
+            "consumer": self.consumer.to_string(),
+            "tree_key": self.tree_key.to_string(),
+            "leaf_index": self.leaf_index,
+            "data": STANDARD.encode(&self.data),
+            "slot": self.slot,
+        })
+    }
+}
+
+impl ToJson for ContractExpired {
+    fn to_json(&self) -> Value {
+        json!({
+            "consumer": self.consumer.to_string(),
+            "expiry_slot": self.expiry_slot,
+            "slot": self.slot,
+        })
+    }
+}
+
+/// Tries each listed event type against the discriminator of an encoded event
+macro_rules! decode_as {
+    ($discriminator:expr, $payload:expr, $($event:ident),+ $(,)?) => {
+        $(
+            if $discriminator == &$event::DISCRIMINATOR[..] {
+                let event = $event::try_from_slice($payload).ok()?;
+                return Some(DecodedEvent {
+                    name: stringify!($event),
+                    data: event.to_json(),
+                });
+            }
+        )+
+    };
+}
+
+/// Decodes a single Anchor-encoded event (discriminator followed by Borsh data)
+fn decode(data: &[u8]) -> Option<DecodedEvent> {
+    if data.len() < 8 {
+        return None;
+    }
+    let (discriminator, payload) = data.split_at(8);
+
+    decode_as!(
+        discriminator,
+        payload,
+        AccountHolderReassigned,
+        CapacityAdjusted,
+        PeriodRebilled,
+        BudgetBillingReconciled,
+        WaterBilled,
+        PaymentReceived,
+        ReservoirLow,
+        ConsumerCompressed,
+        ContractExpired,
+    );
+    None
+}
+
+/// Extracts all known AquaChain events from a transaction's log messages
+///
+/// Lines that are not event data, or that belong to unknown events, are skipped.
+pub fn decode_logs(logs: &[String]) -> Vec<DecodedEvent> {
+    logs.iter()
+        .filter_map(|line| line.strip_prefix(PROGRAM_DATA))
+        .filter_map(|encoded| STANDARD.decode(encoded).ok())
+        .filter_map(|data| decode(&data))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{prelude::Pubkey, Event};
+
+    #[test]
+    fn test_decode_payment_received() {
+        let consumer = Pubkey::new_unique();
+        let event = PaymentReceived {
+            consumer,
+            amount: 25000,
+            outstanding: 5000,
+            slot: 42,
+        };
+        let logs = vec![
+            "Program log: Instruction: PayForWater".to_string(),
+            format!("{}{}", PROGRAM_DATA, STANDARD.encode(event.data())),
+        ];
+
+        let decoded = decode_logs(&logs);
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].name, "PaymentReceived");
+        assert_eq!(decoded[0].data["consumer"], consumer.to_string());
+        assert_eq!(decoded[0].data["amount"], 25000);
+        assert_eq!(decoded[0].data["outstanding"], 5000);
+    }
+
+    #[test]
+    fn test_unknown_data_is_skipped() {
+        let logs = vec![
+            format!("{}{}", PROGRAM_DATA, STANDARD.encode([0u8; 16])),
+            format!("{}not-base64", PROGRAM_DATA),
+        ];
+        assert!(decode_logs(&logs).is_empty());
+    }
+}
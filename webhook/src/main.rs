@@ -0,0 +1,128 @@
+//! Forwards AquaChain program events to agency-configured webhooks.
+//!
+//! The service subscribes to the logs of every transaction that mentions the AquaChain
+//! program, decodes the events they emit and POSTs each one as JSON to the webhooks
+//! subscribed to it, so existing billing systems can react to usage, payments and
+//! reservoir alerts without running an indexer.
+//!
+//! # Environment
+//! * `AQUACHAIN_WS_URL` - RPC websocket endpoint (default `ws://127.0.0.1:8900`)
+//! * `AQUACHAIN_WEBHOOK_CONFIG` - Path to the webhook configuration (default `webhooks.json`)
+//! * `AQUACHAIN_METRICS_ADDR` - Address serving `/metrics` and `/healthz` (default `0.0.0.0:9101`)
+
+mod config;
+mod events;
+
+use aquachain_telemetry::{serve, Telemetry};
+use config::Config;
+use events::{decode_logs, DecodedEvent};
+use reqwest::blocking::Client;
+use serde_json::json;
+use solana_client::{
+    pubsub_client::PubsubClient,
+    rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+};
+use std::{env, process, sync::Arc, thread, time::Duration};
+
+const DEFAULT_WS_URL: &str = "ws://127.0.0.1:8900";
+const DEFAULT_CONFIG_PATH: &str = "webhooks.json";
+const DEFAULT_METRICS_ADDR: &str = "0.0.0.0:9101";
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+fn main() {
+    let ws_url = env::var("AQUACHAIN_WS_URL").unwrap_or_else(|_| DEFAULT_WS_URL.to_string());
+    let config_path =
+        env::var("AQUACHAIN_WEBHOOK_CONFIG").unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string());
+
+    let config = Config::load(&config_path).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    let client = Client::new();
+
+    let telemetry = Arc::new(Telemetry::new());
+    telemetry.describe(
+        "aquachain_events_processed_total",
+        "Program events decoded from transaction logs",
+    );
+    telemetry.describe(
+        "aquachain_webhook_failures_total",
+        "Event deliveries that failed",
+    );
+    telemetry.describe(
+        "aquachain_subscription_failures_total",
+        "Log subscriptions that failed or closed",
+    );
+    let metrics_addr =
+        env::var("AQUACHAIN_METRICS_ADDR").unwrap_or_else(|_| DEFAULT_METRICS_ADDR.to_string());
+    if let Err(err) = serve(telemetry.clone(), &metrics_addr) {
+        eprintln!("Failed to serve metrics on {}: {}", metrics_addr, err);
+        process::exit(1);
+    }
+
+    loop {
+        if let Err(err) = forward_events(&ws_url, &config, &client, &telemetry) {
+            eprintln!("Subscription to {} failed: {}", ws_url, err);
+            telemetry.inc("aquachain_subscription_failures_total", &[]);
+        }
+        thread::sleep(RECONNECT_DELAY);
+    }
+}
+
+/// Subscribes to the program's logs and forwards events until the subscription ends
+fn forward_events(
+    ws_url: &str,
+    config: &Config,
+    client: &Client,
+    telemetry: &Telemetry,
+) -> Result<(), String> {
+    let (_subscription, receiver) = PubsubClient::logs_subscribe(
+        ws_url,
+        RpcTransactionLogsFilter::Mentions(vec![aquachain::ID.to_string()]),
+        RpcTransactionLogsConfig { commitment: None },
+    )
+    .map_err(|err| err.to_string())?;
+    println!("Forwarding AquaChain events from {}", ws_url);
+
+    for response in receiver {
+        let logs = response.value;
+        if logs.err.is_some() {
+            continue; // failed transactions emit nothing
+        }
+        for event in decode_logs(&logs.logs) {
+            telemetry.inc("aquachain_events_processed_total", &[("event", event.name)]);
+            dispatch(config, client, telemetry, &logs.signature, &event);
+        }
+    }
+    Err("subscription closed".to_string())
+}
+
+/// POSTs an event to every webhook subscribed to it
+fn dispatch(
+    config: &Config,
+    client: &Client,
+    telemetry: &Telemetry,
+    signature: &str,
+    event: &DecodedEvent,
+) {
+    let payload = json!({
+        "event": event.name,
+        "signature": signature,
+        "data": event.data,
+    });
+
+    for webhook in config.webhooks.iter().filter(|w| w.wants(event.name)) {
+        let result = client
+            .post(&webhook.url)
+            .json(&payload)
+            .send()
+            .and_then(|response| response.error_for_status());
+        if let Err(err) = result {
+            eprintln!(
+                "Failed to deliver {} to {}: {}",
+                event.name, webhook.url, err
+            );
+            telemetry.inc("aquachain_webhook_failures_total", &[("event", event.name)]);
+        }
+    }
+}